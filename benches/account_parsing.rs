@@ -0,0 +1,92 @@
+//! 账户解析基准：刷新 vault 余额和加载池子时解析的账户
+//!
+//! 运行 `cargo bench --bench account_parsing`。`slice_copy` 是改用零拷贝布局之前逐字段
+//! 复制的写法，用来对比。
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use solana_onchain_arbitrage_bot::dex::meteora::vault_info::MeteoraVaultInfo;
+use solana_onchain_arbitrage_bot::dex::raydium::RaydiumCpAmmInfo;
+use solana_onchain_arbitrage_bot::quote::{token_amount, QuoteAccount};
+use solana_sdk::pubkey::Pubkey;
+
+/// 读取 `tests/fixtures/<name>.json` 中的账户数据
+fn fixture(name: &str) -> Vec<u8> {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    BASE64_STANDARD
+        .decode(json["account"]["data"][0].as_str().unwrap())
+        .unwrap()
+}
+
+/// 165 字节的代币账户，余额为 `amount`
+fn token_account(amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(Pubkey::new_unique().as_ref());
+    data[32..64].copy_from_slice(Pubkey::new_unique().as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// 只填写了余额字段的 Meteora vault 账户
+fn meteora_vault() -> Vec<u8> {
+    let mut data = vec![0u8; 1232];
+    data[..8].copy_from_slice(&anchor_discriminator("Vault"));
+    data[11..19].copy_from_slice(&1_000_000u64.to_le_bytes());
+    data
+}
+
+fn token_accounts(c: &mut Criterion) {
+    let data = token_account(42);
+    let mut group = c.benchmark_group("token_amount");
+    group.bench_function("layout", |b| b.iter(|| token_amount(black_box(&data))));
+    group.bench_function("slice_copy", |b| {
+        b.iter(|| {
+            black_box(&data)
+                .get(64..72)
+                .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        })
+    });
+    group.finish();
+
+    c.bench_function("mint_supply", |b| {
+        b.iter(|| QuoteAccount::MintSupply.amount(black_box(&data), 0))
+    });
+}
+
+fn pool_states(c: &mut Criterion) {
+    let vault = meteora_vault();
+    c.bench_function("meteora_vault", |b| {
+        b.iter(|| MeteoraVaultInfo::load_checked(black_box(&vault)).unwrap())
+    });
+
+    let cpmm = fixture("raydium_cpmm");
+    let mut group = c.benchmark_group("raydium_cp_pool");
+    group.bench_function("layout", |b| {
+        b.iter(|| RaydiumCpAmmInfo::load_checked(black_box(&cpmm)).unwrap())
+    });
+    group.bench_function("slice_copy", |b| {
+        b.iter(|| {
+            let data = black_box(&cpmm);
+            [8, 72, 104, 168, 200, 296]
+                .map(|offset| Pubkey::try_from(&data[offset..offset + 32]).unwrap())
+        })
+    });
+    group.finish();
+
+    let damm_v2 = fixture("meteora_damm_v2");
+    c.bench_function("meteora_damm_v2_pool", |b| {
+        b.iter(|| MeteoraDAmmV2Info::load_checked(black_box(&damm_v2)).unwrap())
+    });
+}
+
+criterion_group!(benches, token_accounts, pool_states);
+criterion_main!(benches);
@@ -0,0 +1,107 @@
+//! 报价基准：DLMM bin array、CLMM 和 Whirlpool tick array 的推导，以及按储备选择路线
+//!
+//! 运行 `cargo bench --bench quoting`。
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::dex::meteora::dlmm_info::DlmmInfo;
+use solana_onchain_arbitrage_bot::dex::raydium::{
+    get_tick_array_pubkeys, raydium_clmm_program_id, PoolState,
+};
+use solana_onchain_arbitrage_bot::dex::whirlpool::constants::whirlpool_program_id;
+use solana_onchain_arbitrage_bot::dex::whirlpool::state::Whirlpool;
+use solana_onchain_arbitrage_bot::dex::whirlpool::update_tick_array_accounts_for_onchain;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::{best_route, PoolReserves, TradeSizing};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// 读取 `tests/fixtures/<name>.json` 中的账户地址和数据
+fn fixture(name: &str) -> (Pubkey, Vec<u8>) {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    let pubkey = Pubkey::from_str(json["pubkey"].as_str().unwrap()).unwrap();
+    let data = BASE64_STANDARD
+        .decode(json["account"]["data"][0].as_str().unwrap())
+        .unwrap();
+    (pubkey, data)
+}
+
+/// `count` 个 Raydium 池子，储备逐个错开，相邻池子之间有价差
+fn quoted_pools(count: usize) -> (MintPoolData, HashMap<Pubkey, PoolReserves>) {
+    let mut pool_data = MintPoolData::new(*usdc_mint(), Pubkey::new_unique(), spl_token::ID);
+    let mut reserves = HashMap::new();
+    for i in 0..count as u64 {
+        let pool = Pubkey::new_unique();
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+        reserves.insert(
+            pool,
+            PoolReserves {
+                token: 5_000_000_000_000 + i * 40_000_000_000,
+                sol: 1_000_000_000_000,
+                fee_bps: 25,
+            },
+        );
+    }
+    (pool_data, reserves)
+}
+
+fn tick_arrays(c: &mut Criterion) {
+    let (pair, data) = fixture("meteora_dlmm");
+    let dlmm = DlmmInfo::load_checked(&data).unwrap();
+    c.bench_function("dlmm_bin_arrays", |b| {
+        b.iter(|| dlmm.calculate_bin_arrays(black_box(&pair)).unwrap())
+    });
+
+    let (pool, data) = fixture("raydium_clmm");
+    let clmm = PoolState::load_checked(&data).unwrap();
+    let program_id = *raydium_clmm_program_id();
+    c.bench_function("raydium_clmm_tick_arrays", |b| {
+        b.iter(|| {
+            get_tick_array_pubkeys(
+                black_box(&pool),
+                clmm.tick_current,
+                clmm.tick_spacing,
+                &[-1, 0, 1],
+                &program_id,
+            )
+            .unwrap()
+        })
+    });
+
+    let (pool, data) = fixture("whirlpool");
+    let whirlpool = Whirlpool::try_deserialize(&data).unwrap();
+    let program_id = *whirlpool_program_id();
+    c.bench_function("whirlpool_tick_arrays", |b| {
+        b.iter(|| update_tick_array_accounts_for_onchain(&whirlpool, black_box(&pool), &program_id))
+    });
+}
+
+fn routes(c: &mut Criterion) {
+    let fixed = TradeSizing::Fixed((1..=10).map(|i| i * 100_000_000).collect());
+    let optimal = TradeSizing::Optimal {
+        max_input: 10_000_000_000,
+    };
+    let mut group = c.benchmark_group("best_route");
+    for count in [2, 8, 16] {
+        let (pool_data, reserves) = quoted_pools(count);
+        group.bench_with_input(BenchmarkId::new("fixed_10_sizes", count), &count, |b, _| {
+            b.iter(|| best_route(&pool_data, black_box(&reserves), &fixed))
+        });
+        group.bench_with_input(BenchmarkId::new("optimal", count), &count, |b, _| {
+            b.iter(|| best_route(&pool_data, black_box(&reserves), &optimal))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, tick_arrays, routes);
+criterion_main!(benches);
@@ -0,0 +1,119 @@
+//! 交易构建基准：完整构建、使用交易模板，以及不使用查找表的 legacy 交易
+//!
+//! 运行 `cargo bench --bench transaction_building`。
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::transaction::{
+    build_legacy_transaction, build_transaction, SwapPrograms,
+};
+use solana_onchain_arbitrage_bot::tx_template::TransactionTemplates;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn config(wallet: &Keypair) -> Config {
+    toml::from_str(&format!(
+        r#"
+[bot]
+compute_unit_limit = 600000
+
+[routing]
+[[routing.mint_config_list]]
+mint = "{USDC_MINT}"
+process_delay = 1000
+
+[rpc]
+url = "http://127.0.0.1:8899"
+
+[wallet]
+private_key = "{}"
+"#,
+        wallet.to_base58_string()
+    ))
+    .unwrap()
+}
+
+/// `count` 个 Raydium 池子和一张包含它们全部账户的查找表
+fn route(wallet: &Keypair, count: usize) -> (MintPoolData, AddressLookupTableAccount) {
+    let mut pool_data = MintPoolData::new(*usdc_mint(), wallet.pubkey(), spl_token::ID);
+    for _ in 0..count {
+        pool_data.add_raydium_pool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+    }
+    let lookup_table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: pool_data
+            .pool_accounts()
+            .into_iter()
+            .flat_map(|pool| pool.accounts.into_iter().map(|meta| meta.pubkey))
+            .collect(),
+    };
+    (pool_data, lookup_table)
+}
+
+fn transactions(c: &mut Criterion) {
+    let wallet = Keypair::new();
+    let config = config(&wallet);
+    let programs = SwapPrograms::from_config(&config).unwrap();
+    let mut group = c.benchmark_group("build_transaction");
+    for count in [2, 4, 8] {
+        let (pool_data, lookup_table) = route(&wallet, count);
+        let tables = [lookup_table];
+        group.bench_with_input(BenchmarkId::new("full", count), &count, |b, _| {
+            b.iter(|| {
+                build_transaction(
+                    &wallet,
+                    &config,
+                    &programs,
+                    black_box(&pool_data),
+                    Hash::default(),
+                    &tables,
+                )
+                .unwrap()
+            })
+        });
+
+        let mut templates = TransactionTemplates::new();
+        group.bench_with_input(BenchmarkId::new("template", count), &count, |b, _| {
+            b.iter(|| {
+                templates
+                    .build(
+                        &wallet,
+                        &config,
+                        &programs,
+                        black_box(&pool_data),
+                        Hash::default(),
+                        &tables,
+                    )
+                    .unwrap()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("legacy", count), &count, |b, _| {
+            b.iter(|| {
+                build_legacy_transaction(
+                    &wallet,
+                    &config,
+                    &programs,
+                    black_box(&pool_data),
+                    Hash::default(),
+                )
+                .unwrap()
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, transactions);
+criterion_main!(benches);
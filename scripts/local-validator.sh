@@ -0,0 +1,44 @@
+#!/usr/bin/env bash
+# 启动一个克隆了主网账户的本地验证节点，供 tests/local_validator.rs 使用。
+#
+# 用法:
+#   MAINNET_URL=https://api.mainnet-beta.solana.com ./scripts/local-validator.sh
+#   cargo test --test local_validator -- --ignored
+#
+# 需要额外克隆的账户（例如 Pump 池的 vault、创建者 vault 等）可以通过
+# EXTRA_CLONE_ACCOUNTS 传入，用空格分隔。
+#
+# 要克隆自己配置中的所有程序和账户，用 `cargo run -- -c config.toml local-sim --print`
+# 生成验证器命令。
+set -euo pipefail
+
+MAINNET_URL="${MAINNET_URL:-https://api.mainnet-beta.solana.com}"
+LEDGER_DIR="${LEDGER_DIR:-test-ledger}"
+
+# 需要克隆的程序
+PROGRAMS=(
+  MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz # 链上套利程序
+  675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8 # Raydium AMM v4
+  pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA # Pump AMM
+)
+
+# 需要克隆的账户
+ACCOUNTS=(
+  EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v # USDC mint
+  58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2 # Raydium SOL/USDC 池
+  DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz # Raydium coin vault
+  HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz # Raydium pc vault
+  Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v # Pump 池
+  ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw # Pump 全局配置
+  JCRGumoE9Qi5BBgULTgdgTLjSgkCMSbF62ZZfGs84JeU # Pump 手续费钱包
+)
+
+ARGS=(--reset --ledger "$LEDGER_DIR" --url "$MAINNET_URL")
+for program in "${PROGRAMS[@]}"; do
+  ARGS+=(--clone-upgradeable-program "$program")
+done
+for account in "${ACCOUNTS[@]}" ${EXTRA_CLONE_ACCOUNTS:-}; do
+  ARGS+=(--clone "$account")
+done
+
+exec solana-test-validator "${ARGS[@]}"
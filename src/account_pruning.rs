@@ -0,0 +1,304 @@
+use crate::app_state::AppState;
+use crate::config::Config;
+use crate::pools::MintPoolData;
+use crate::transaction::{build_transaction, SwapPrograms};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+use solana_sdk::account::Account;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// 每个池子账户列表末尾数量可变的 tick array 和 bin array，按池子分组
+///
+/// 其他账户在链上程序里按位置读取，不能去掉。
+pub fn prunable_accounts(pool_data: &MintPoolData) -> Vec<(Pubkey, Vec<Pubkey>)> {
+    let dlmm = pool_data
+        .dlmm_pairs
+        .iter()
+        .map(|pair| (pair.pair, pair.bin_arrays.clone()));
+    let whirlpool = pool_data
+        .whirlpool_pools
+        .iter()
+        .map(|pool| (pool.pool, pool.tick_arrays.clone()));
+    let raydium_clmm = pool_data
+        .raydium_clmm_pools
+        .iter()
+        .map(|pool| (pool.pool, pool.tick_arrays.clone()));
+    dlmm.chain(whirlpool)
+        .chain(raydium_clmm)
+        .filter(|(_, arrays)| arrays.len() > 1)
+        .collect()
+}
+
+/// 按模拟中被写入的账户选出可以去掉的账户
+///
+/// 每个池子保留被写入的 tick array 和 bin array；一个都没有被写入时（兑换没有跨过已初始化的
+/// tick 或 bin）保留第一个，链上程序至少需要一个。
+pub fn select_pruned(
+    groups: &[(Pubkey, Vec<Pubkey>)],
+    written: &HashSet<Pubkey>,
+) -> HashSet<Pubkey> {
+    let mut pruned = HashSet::new();
+    for (_, arrays) in groups {
+        let keep_first = !arrays.iter().any(|array| written.contains(array));
+        for (i, array) in arrays.iter().enumerate() {
+            let keep = written.contains(array) || (keep_first && i == 0);
+            if !keep {
+                pruned.insert(*array);
+            }
+        }
+    }
+    pruned
+}
+
+/// 去掉指定 tick array 和 bin array 后的池子数据
+pub fn without_accounts(pool_data: &MintPoolData, pruned: &HashSet<Pubkey>) -> MintPoolData {
+    let mut pool_data = pool_data.clone();
+    for pair in &mut pool_data.dlmm_pairs {
+        pair.bin_arrays.retain(|array| !pruned.contains(array));
+    }
+    for pool in &mut pool_data.whirlpool_pools {
+        pool.tick_arrays.retain(|array| !pruned.contains(array));
+    }
+    for pool in &mut pool_data.raydium_clmm_pools {
+        pool.tick_arrays.retain(|array| !pruned.contains(array));
+    }
+    pool_data
+}
+
+/// 模拟交易，返回 `addresses` 中各账户模拟后的状态；模拟失败时返回错误
+fn simulate_accounts(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+    addresses: &[Pubkey],
+) -> anyhow::Result<Vec<Option<Account>>> {
+    let result = client
+        .simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::processed()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: addresses
+                        .iter()
+                        .map(|address| address.to_string())
+                        .collect(),
+                }),
+                ..Default::default()
+            },
+        )?
+        .value;
+    if let Some(err) = result.err {
+        return Err(anyhow::anyhow!("Simulation failed: {}", err));
+    }
+    let accounts = result.accounts.unwrap_or_default();
+    Ok((0..addresses.len())
+        .map(|i| {
+            accounts
+                .get(i)
+                .cloned()
+                .flatten()
+                .and_then(|ui_account| ui_account.decode::<Account>())
+        })
+        .collect())
+}
+
+/// 模拟前后账户状态不同时认为账户被写入
+fn was_written(pre: &Option<Account>, post: &Option<Account>) -> bool {
+    match (pre, post) {
+        (Some(pre), Some(post)) => pre.data != post.data || pre.lamports != post.lamports,
+        (None, None) => false,
+        _ => true,
+    }
+}
+
+/// 找出路线中模拟时没有用到的 tick array 和 bin array
+///
+/// 先模拟完整的交易，去掉没有被写入的账户后再模拟一次；第二次模拟失败或钱包报价货币账户的
+/// 余额和第一次不同时不去掉任何账户。模拟只能看到被写入的账户，只读的账户按第一个保留的规则处理。
+///
+/// # 参数
+/// * `client` - 用于模拟的 RPC 客户端
+/// * `wallet_kp` - 签名钱包
+/// * `config` - 构建交易使用的配置
+/// * `programs` - 构建交易使用的执行程序和外部闪电贷
+/// * `pool_data` - 路线的池子数据
+/// * `blockhash` - 构建交易的 blockhash，模拟时会被替换
+/// * `address_lookup_table_accounts` - 地址查找表
+///
+/// # 返回值
+/// 可以去掉的账户，没有可以去掉的账户时为空
+///
+/// # 错误
+/// 获取账户、构建交易或完整交易的模拟失败时返回错误
+pub fn find_unused_accounts(
+    client: &RpcClient,
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    pool_data: &MintPoolData,
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<HashSet<Pubkey>> {
+    let groups = prunable_accounts(pool_data);
+    if groups.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let wallet_quote_account = pool_data.wallet_quote_account();
+    let mut addresses = vec![wallet_quote_account];
+    addresses.extend(groups.iter().flat_map(|(_, arrays)| arrays.iter().copied()));
+
+    let pre = client.get_multiple_accounts(&addresses)?;
+    let tx = build_transaction(
+        wallet_kp,
+        config,
+        programs,
+        pool_data,
+        blockhash,
+        address_lookup_table_accounts,
+    )?;
+    let post = simulate_accounts(client, &tx, &addresses)?;
+    let written: HashSet<Pubkey> = addresses
+        .iter()
+        .zip(pre.iter().zip(post.iter()))
+        .filter(|(_, (pre, post))| was_written(pre, post))
+        .map(|(address, _)| *address)
+        .collect();
+
+    let pruned = select_pruned(&groups, &written);
+    if pruned.is_empty() {
+        return Ok(pruned);
+    }
+
+    // 去掉账户后的交易要能成功，且钱包余额变化和完整交易相同
+    let pruned_tx = build_transaction(
+        wallet_kp,
+        config,
+        programs,
+        &without_accounts(pool_data, &pruned),
+        blockhash,
+        address_lookup_table_accounts,
+    )?;
+    match simulate_accounts(client, &pruned_tx, &[wallet_quote_account]) {
+        Ok(pruned_post) if pruned_post.first() == post.first() => Ok(pruned),
+        Ok(_) => {
+            debug!("Pruned route changes the wallet balance, keeping all accounts");
+            Ok(HashSet::new())
+        }
+        Err(e) => {
+            debug!(
+                "Pruned route failed to simulate, keeping all accounts: {}",
+                e
+            );
+            Ok(HashSet::new())
+        }
+    }
+}
+
+/// 按路线缓存的可以去掉的账户
+///
+/// tick array 和 bin array 随价格变化，缓存超过 `ttl` 后重新模拟。模拟失败的路线也缓存空结果，
+/// 避免每轮都请求模拟。
+pub struct PrunedRoutes {
+    ttl: Duration,
+    routes: HashMap<Vec<Pubkey>, (HashSet<Pubkey>, Instant)>,
+}
+
+impl PrunedRoutes {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// 路线的缓存键：排序后的池子地址
+    pub fn route_key(pool_data: &MintPoolData) -> Vec<Pubkey> {
+        let mut pools: Vec<Pubkey> = pool_data
+            .pool_addresses()
+            .into_iter()
+            .map(|(_, pool)| pool)
+            .collect();
+        pools.sort();
+        pools
+    }
+
+    /// 缓存中没有过期的结果
+    pub fn get(&self, route: &[Pubkey], now: Instant) -> Option<&HashSet<Pubkey>> {
+        self.routes
+            .get(route)
+            .filter(|(_, at)| now.saturating_duration_since(*at) < self.ttl)
+            .map(|(pruned, _)| pruned)
+    }
+
+    pub fn insert(&mut self, route: Vec<Pubkey>, pruned: HashSet<Pubkey>, now: Instant) {
+        self.routes
+            .retain(|_, (_, at)| now.saturating_duration_since(*at) < self.ttl);
+        self.routes.insert(route, (pruned, now));
+    }
+
+    /// 返回去掉没有用到的账户后的池子数据，路线没有缓存时先模拟
+    ///
+    /// 模拟失败时记录日志并使用完整的池子数据。`state` 提供模拟使用的 RPC 客户端、钱包和执行程序，
+    /// `config` 是本轮发送使用的配置。
+    pub fn prune(
+        &mut self,
+        state: &AppState,
+        config: &Config,
+        pool_data: &MintPoolData,
+        blockhash: Hash,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> MintPoolData {
+        if prunable_accounts(pool_data).is_empty() {
+            return pool_data.clone();
+        }
+        let route = Self::route_key(pool_data);
+        let now = Instant::now();
+        if let Some(pruned) = self.get(&route, now) {
+            return without_accounts(pool_data, pruned);
+        }
+
+        let pruned = match find_unused_accounts(
+            &state.rpc_client,
+            &state.wallet,
+            config,
+            &state.programs,
+            pool_data,
+            blockhash,
+            address_lookup_table_accounts,
+        ) {
+            Ok(pruned) => {
+                if !pruned.is_empty() {
+                    info!(
+                        "Pruned {} unused tick/bin array(s) from route for mint {}",
+                        pruned.len(),
+                        pool_data.mint
+                    );
+                }
+                pruned
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to simulate route for mint {}, keeping all accounts: {}",
+                    pool_data.mint, e
+                );
+                HashSet::new()
+            }
+        };
+        let pool_data = without_accounts(pool_data, &pruned);
+        self.insert(route, pruned, now);
+        pool_data
+    }
+}
@@ -0,0 +1,264 @@
+use crate::batch::Batcher;
+use crate::config::{AccountPruningConfig, Config, InlineSwapConfig};
+use crate::copy_route::CopyRouteBook;
+use crate::export::Exporter;
+use crate::fee_budget::FeeGovernor;
+use crate::inventory::Inventory;
+use crate::jito::JitoSender;
+use crate::landing_model::LandingModel;
+use crate::leader_schedule::LeaderFilter;
+use crate::notifications::Notifier;
+use crate::pools::MintPoolData;
+use crate::research::OpportunityLog;
+use crate::route_fees::RouteFees;
+use crate::rpc::RpcMetrics;
+use crate::status::{unix_now_ms, BotStatus, TransactionOutcome};
+use crate::storage::TradeStore;
+use crate::submit::Submitters;
+use crate::transaction::SwapPrograms;
+use crate::transaction_watch::TransactionWatch;
+use crate::vaults::VaultTracker;
+use crate::watchdog::Watchdog;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::error;
+
+/// 所有后台任务共享的运行状态
+///
+/// 启动时创建一次，以 `Arc<AppState>` 交给各个任务，不再为每个任务分别克隆配置、
+/// RPC 客户端、告警推送器和各个可选子系统。仪表盘或控制接口等新的子系统从这里读取同一份配置、
+/// blockhash、计算单元价格和各代币的池子数据。
+pub struct AppState {
+    pub config: Config,
+    pub wallet: Keypair,
+    /// 启动时从配置解析的执行程序和外部闪电贷，构建每笔交易时使用
+    pub programs: SwapPrograms,
+    /// 报价、刷新和确认交易使用的主 RPC 客户端
+    pub rpc_client: Arc<RpcClient>,
+    /// 按配置选择的交易提交器：RPC 发送端点、TPU、Jito 或 dry-run 模拟记录器
+    pub submitters: Arc<Submitters>,
+    /// 启用 `[jito]` 时的区块引擎发送器，用于估计小费和记录各区域的上链结果
+    pub jito_sender: Option<Arc<JitoSender>>,
+    /// 最新的 blockhash 及获取时的 slot，由 blockhash 刷新任务更新
+    pub blockhash: Arc<Mutex<(Hash, Slot)>>,
+    /// 上次更新 blockhash 的时间（Unix 毫秒）
+    blockhash_updated_ms: AtomicU64,
+    pub status: Arc<BotStatus>,
+    pub notifier: Notifier,
+    pub trade_sinks: TradeSinks,
+    /// 按配置启用的可选子系统
+    pub services: Services,
+    /// 当前发送使用的计算单元价格（micro-lamports）
+    priority_fee: AtomicU64,
+    /// 正在运行的代币和发送任务共用的池子数据
+    mints: RwLock<HashMap<Pubkey, Arc<Mutex<MintPoolData>>>>,
+}
+
+impl AppState {
+    /// 计算单元价格取配置的值，使用默认执行程序，没有提交器，还没有注册任何代币
+    pub fn new(
+        config: Config,
+        wallet: Keypair,
+        rpc_client: Arc<RpcClient>,
+        blockhash: (Hash, Slot),
+        status: Arc<BotStatus>,
+        notifier: Notifier,
+    ) -> Self {
+        Self {
+            priority_fee: AtomicU64::new(config.compute_unit_price()),
+            config,
+            wallet,
+            programs: SwapPrograms::default(),
+            rpc_client,
+            submitters: Arc::new(Submitters::default()),
+            jito_sender: None,
+            blockhash: Arc::new(Mutex::new(blockhash)),
+            blockhash_updated_ms: AtomicU64::new(unix_now_ms()),
+            status,
+            notifier,
+            trade_sinks: TradeSinks::default(),
+            services: Services::default(),
+            mints: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn with_programs(mut self, programs: SwapPrograms) -> Self {
+        self.programs = programs;
+        self
+    }
+
+    pub fn with_submitters(mut self, submitters: Submitters) -> Self {
+        self.submitters = Arc::new(submitters);
+        self
+    }
+
+    pub fn with_jito_sender(mut self, jito_sender: Option<Arc<JitoSender>>) -> Self {
+        self.jito_sender = jito_sender;
+        self
+    }
+
+    pub fn with_trade_sinks(mut self, trade_sinks: TradeSinks) -> Self {
+        self.trade_sinks = trade_sinks;
+        self
+    }
+
+    pub fn with_services(mut self, services: Services) -> Self {
+        self.services = services;
+        self
+    }
+
+    /// 缓存的 blockhash 及获取时的 slot
+    pub async fn latest_blockhash(&self) -> (Hash, Slot) {
+        *self.blockhash.lock().await
+    }
+
+    /// 更新缓存的 blockhash，并记录更新时间
+    pub async fn set_blockhash(&self, blockhash: Hash, slot: Slot) {
+        *self.blockhash.lock().await = (blockhash, slot);
+        self.blockhash_updated_ms
+            .store(unix_now_ms(), Ordering::Relaxed);
+    }
+
+    /// 缓存的 blockhash 距离上次更新经过的时间
+    pub fn blockhash_age(&self) -> Duration {
+        let updated_ms = self.blockhash_updated_ms.load(Ordering::Relaxed);
+        Duration::from_millis(unix_now_ms().saturating_sub(updated_ms))
+    }
+
+    /// 当前发送使用的计算单元价格
+    pub fn priority_fee(&self) -> u64 {
+        self.priority_fee.load(Ordering::Relaxed)
+    }
+
+    /// 更新计算单元价格，同时更新仪表盘显示的价格
+    pub fn set_priority_fee(&self, micro_lamports: u64) {
+        self.priority_fee.store(micro_lamports, Ordering::Relaxed);
+        self.status.set_priority_fee(micro_lamports);
+    }
+
+    /// 交易发送到的端点，按发送顺序：启用 Jito 时为区块引擎地址，否则为 `tpu` 和 RPC 发送端点，
+    /// dry-run 模式下为 `paper`
+    pub fn send_endpoints(&self) -> Vec<String> {
+        self.submitters.endpoints()
+    }
+
+    /// 注册一个代币的池子数据，发送任务和运行时加入池子的任务共用同一份
+    pub fn insert_mint(&self, mint: Pubkey, pool_data: Arc<Mutex<MintPoolData>>) {
+        self.mints.write().unwrap().insert(mint, pool_data);
+    }
+
+    /// 一个代币的池子数据，代币没有运行时为 `None`
+    pub fn mint(&self, mint: &Pubkey) -> Option<Arc<Mutex<MintPoolData>>> {
+        self.mints.read().unwrap().get(mint).cloned()
+    }
+
+    /// 所有正在运行的代币
+    pub fn mints(&self) -> Vec<Pubkey> {
+        self.mints.read().unwrap().keys().copied().collect()
+    }
+}
+
+/// 各代币的发送任务、上链跟踪和池子监视任务共用的可选子系统，没有启用的为 `None`
+#[derive(Default)]
+pub struct Services {
+    /// 启用 `[rpc.metrics]` 时所有 RPC 客户端共用的请求统计
+    pub rpc_metrics: Option<Arc<RpcMetrics>>,
+    /// 研究模式的机会记录，启用时只报价不发送
+    pub opportunity_log: Option<Arc<OpportunityLog>>,
+    /// 多个代币合并到同一笔交易发送
+    pub batcher: Option<Arc<Batcher>>,
+    /// 不使用执行程序，直接调用两个池子的 swap 指令
+    pub inline_swap: Option<InlineSwapConfig>,
+    /// 模拟裁剪 tick array 和 bin array，直接兑换时不启用
+    pub account_pruning: Option<AccountPruningConfig>,
+    /// 按 leader 计划过滤发送时机
+    pub leader_filter: Option<Arc<LeaderFilter>>,
+    /// 闲置 WSOL 存入借贷市场，余额不够时取回
+    pub inventory: Option<Arc<Inventory>>,
+    /// 优先费预算
+    pub fee_governor: Option<Arc<FeeGovernor>>,
+    /// 按路线的上链率调整的优先费
+    pub route_fees: Option<Arc<RouteFees>>,
+    /// 批量跟踪的池子 vault 余额，报价和仪表盘共用
+    pub vault_tracker: Option<Arc<VaultTracker>>,
+    /// 其他机器人最近走过的路线
+    pub copy_routes: Option<Arc<CopyRouteBook>>,
+    /// 数据过期时暂停发送
+    pub watchdog: Option<Arc<Watchdog>>,
+    /// 期望收益门槛的上链率模型
+    pub landing_model: Option<Arc<LandingModel>>,
+    /// Yellowstone gRPC 推送的钱包交易结果
+    pub transaction_watch: Option<Arc<TransactionWatch>>,
+}
+
+/// 交易记录的持久化目标：SQLite 交易历史和 CSV 导出，写入失败只记录日志
+#[derive(Clone, Default)]
+pub struct TradeSinks {
+    pub trade_store: Option<Arc<TradeStore>>,
+    pub exporter: Option<Arc<Exporter>>,
+}
+
+impl TradeSinks {
+    pub fn is_enabled(&self) -> bool {
+        self.trade_store.is_some() || self.exporter.is_some()
+    }
+
+    pub fn record_attempt(
+        &self,
+        mint: &str,
+        route: &str,
+        signature: Option<&Signature>,
+        simulated_profit: Option<i64>,
+        outcome: &TransactionOutcome,
+    ) {
+        if let Some(trade_store) = &self.trade_store {
+            if let Err(e) =
+                trade_store.record_attempt(mint, route, signature, simulated_profit, outcome)
+            {
+                error!("Failed to record trade history for mint {}: {}", mint, e);
+            }
+        }
+        if let Some(exporter) = &self.exporter {
+            if let Err(e) = exporter.record_send(mint, route, signature, simulated_profit, outcome)
+            {
+                error!("Failed to export send for mint {}: {}", mint, e);
+            }
+        }
+    }
+
+    /// 记录发送交易时支付的小费，只写入交易历史
+    pub fn record_tip(&self, signature: &Signature, tip: u64) {
+        if let Some(trade_store) = &self.trade_store {
+            if let Err(e) = trade_store.record_tip(signature, tip) {
+                error!("Failed to record tip for {}: {}", signature, e);
+            }
+        }
+    }
+
+    pub fn update_outcome(
+        &self,
+        mint: &str,
+        signature: &Signature,
+        outcome: &TransactionOutcome,
+        fee: Option<u64>,
+    ) {
+        if let Some(trade_store) = &self.trade_store {
+            if let Err(e) = trade_store.update_outcome(signature, outcome, fee) {
+                error!("Failed to update trade history for {}: {}", signature, e);
+            }
+        }
+        if let Some(exporter) = &self.exporter {
+            if let Err(e) = exporter.record_outcome(mint, signature, outcome, fee) {
+                error!("Failed to export outcome for {}: {}", signature, e);
+            }
+        }
+    }
+}
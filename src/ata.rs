@@ -0,0 +1,186 @@
+use crate::config::CreateAtaPolicy;
+use anyhow::Context;
+use solana_client::rpc_client::RpcClient;
+use solana_program::hash::Hash;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Token 2022 程序地址
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// 创建 ATA 交易的计算单元上限
+const CREATE_ATA_COMPUTE_UNIT_LIMIT: u32 = 60_000;
+
+/// 两次创建尝试之间的等待时间
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// 根据 mint 账户的所有者确定代币程序
+///
+/// ATA 地址和创建指令都取决于代币程序，Token 2022 的 mint 按 SPL Token 推导会得到不存在的账户。
+///
+/// # 参数
+/// * `mint` - 代币 mint，用于错误信息
+/// * `owner` - mint 账户的所有者
+///
+/// # 错误
+/// 所有者既不是 SPL Token 也不是 Token 2022 时返回错误
+pub fn mint_token_program(mint: &Pubkey, owner: &Pubkey) -> anyhow::Result<Pubkey> {
+    let token_2022_program_id = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+    if *owner == spl_token::ID || *owner == token_2022_program_id {
+        Ok(*owner)
+    } else {
+        Err(anyhow::anyhow!(
+            "Unknown token program for mint {}: {}",
+            mint,
+            owner
+        ))
+    }
+}
+
+/// 构造为钱包幂等创建 ATA 的交易
+///
+/// # 参数
+/// * `wallet` - 付款并持有 ATA 的钱包
+/// * `mint` - 代币 mint
+/// * `token_program` - mint 所属的代币程序（Token 或 Token 2022）
+/// * `compute_unit_price` - 计算单元价格（micro-lamports）
+/// * `blockhash` - 最新的 blockhash
+pub fn create_ata_transaction(
+    wallet: &Keypair,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    compute_unit_price: u64,
+    blockhash: Hash,
+) -> Transaction {
+    let payer = wallet.pubkey();
+    Transaction::new_signed_with_payer(
+        &[
+            ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+            ComputeBudgetInstruction::set_compute_unit_limit(CREATE_ATA_COMPUTE_UNIT_LIMIT),
+            create_associated_token_account_idempotent(&payer, &payer, mint, token_program),
+        ],
+        Some(&payer),
+        &[wallet],
+        blockhash,
+    )
+}
+
+/// 检查钱包在 `mint` 下的 ATA，缺少时按 `policy` 处理
+///
+/// ATA 地址按 mint 账户的所有者（Token 或 Token 2022）推导。创建最多尝试 `attempts` 次，
+/// 每次发送后确认 ATA 已经存在才算成功。
+///
+/// # 参数
+/// * `policy` - 缺少 ATA 时创建、跳过还是失败
+/// * `compute_unit_price` - 创建交易的计算单元价格（micro-lamports）
+/// * `attempts` - 创建最多尝试的次数
+/// * `dry_run` - dry-run 模式下不广播交易，缺少的 ATA 只记录
+///
+/// # 返回值
+/// ATA 地址
+///
+/// # 错误
+/// 读取 mint 失败、`fail_if_missing` 下缺少 ATA，或创建 `attempts` 次仍未成功时返回错误
+pub fn ensure_token_account(
+    rpc_client: &RpcClient,
+    wallet: &Keypair,
+    mint: &Pubkey,
+    policy: CreateAtaPolicy,
+    compute_unit_price: u64,
+    attempts: u32,
+    dry_run: bool,
+) -> anyhow::Result<Pubkey> {
+    let mint_owner = rpc_client
+        .get_account(mint)
+        .with_context(|| format!("Failed to fetch mint {}", mint))?
+        .owner;
+    let token_program = mint_token_program(mint, &mint_owner)?;
+    let ata = get_associated_token_address_with_program_id(&wallet.pubkey(), mint, &token_program);
+    info!("Token mint: {}, wallet token ATA: {}", mint, ata);
+    if rpc_client.get_account(&ata).is_ok() {
+        return Ok(ata);
+    }
+
+    match policy {
+        CreateAtaPolicy::Never => {
+            warn!(
+                "Token account {} for {} does not exist, not creating it",
+                ata, mint
+            );
+            return Ok(ata);
+        }
+        CreateAtaPolicy::FailIfMissing => {
+            return Err(anyhow::anyhow!(
+                "Token account {} for {} does not exist (bot.create_ata = \"fail_if_missing\")",
+                ata,
+                mint
+            ));
+        }
+        CreateAtaPolicy::Auto if dry_run => {
+            warn!(
+                "Token account {} for {} does not exist, skipping creation in dry-run mode",
+                ata, mint
+            );
+            return Ok(ata);
+        }
+        CreateAtaPolicy::Auto => {}
+    }
+
+    let mut last_error = None;
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            std::thread::sleep(RETRY_DELAY);
+        }
+        let blockhash = match rpc_client.get_latest_blockhash() {
+            Ok(blockhash) => blockhash,
+            Err(e) => {
+                warn!(
+                    "Failed to get a blockhash (attempt {}/{}): {}",
+                    attempt, attempts, e
+                );
+                last_error = Some(e.into());
+                continue;
+            }
+        };
+        let tx =
+            create_ata_transaction(wallet, mint, &token_program, compute_unit_price, blockhash);
+        match rpc_client.send_and_confirm_transaction(&tx) {
+            Ok(signature) => {
+                if rpc_client.get_account(&ata).is_ok() {
+                    info!("Created token account {} for {}: {}", ata, mint, signature);
+                    return Ok(ata);
+                }
+                warn!(
+                    "Token account {} is still missing after {} (attempt {}/{})",
+                    ata, signature, attempt, attempts
+                );
+                last_error = Some(anyhow::anyhow!(
+                    "transaction {} confirmed but the account is missing",
+                    signature
+                ));
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to create token account {} (attempt {}/{}): {}",
+                    ata, attempt, attempts, e
+                );
+                last_error = Some(e.into());
+            }
+        }
+    }
+    Err(anyhow::anyhow!(
+        "Failed to create token account {} for {} after {} attempts: {}",
+        ata,
+        mint,
+        attempts,
+        last_error.map(|e| e.to_string()).unwrap_or_default()
+    ))
+}
@@ -0,0 +1,72 @@
+use crate::pools::MintPoolData;
+use crate::quote::SizedQuote;
+use serde::Serialize;
+use solana_program::pubkey::Pubkey;
+use tracing::info;
+
+/// 发送记录日志的 target，按 target 过滤即可取出所有记录
+pub const ATTEMPT_LOG_TARGET: &str = "unit_economics";
+
+/// 路线中的一个池子
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RoutePool {
+    pub dex: &'static str,
+    pub pool: String,
+}
+
+/// 一次发送的单位经济数据
+///
+/// 每次发送输出一条单行 JSON 日志，分析时不需要再拼接同一轮的多条日志。
+/// 金额以代币的报价货币最小单位计（SOL 报价时为 lamports）。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AttemptRecord {
+    pub mint: String,
+    /// 报价选出的买入池和卖出池；没有报价时为交给执行程序的所有池子
+    pub route: Vec<RoutePool>,
+    /// 报价选出的投入，没有报价时为 `None`
+    pub input_size: Option<u64>,
+    /// 报价的预期收益，不含交易手续费
+    pub expected_profit: Option<i64>,
+    /// 计算单元价格（micro-lamports）
+    pub compute_unit_price: u64,
+    pub compute_unit_limit: u32,
+    /// 使用的 blockhash 距离获取时经过的时间（毫秒）
+    pub blockhash_age_ms: u64,
+    /// 发送端点：RPC 地址、`tpu` 或 Jito 区块引擎地址
+    pub endpoints: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl AttemptRecord {
+    /// 发送的路线：有报价时为买入池和卖出池，否则为代币的所有池子
+    pub fn route(pool_data: &MintPoolData, quote: Option<&SizedQuote>) -> Vec<RoutePool> {
+        let pools = pool_data.pool_addresses();
+        let route_pool = |(dex, pool): (&'static str, Pubkey)| RoutePool {
+            dex,
+            pool: pool.to_string(),
+        };
+        match quote {
+            Some(quote) => [quote.buy_pool, quote.sell_pool]
+                .into_iter()
+                .map(|pool| {
+                    let dex = pools
+                        .iter()
+                        .find(|(_, address)| *address == pool)
+                        .map_or("unknown", |(dex, _)| *dex);
+                    route_pool((dex, pool))
+                })
+                .collect(),
+            None => pools.into_iter().map(route_pool).collect(),
+        }
+    }
+
+    /// 单行 JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// 以 `ATTEMPT_LOG_TARGET` 输出一条日志
+    pub fn log(&self) {
+        info!(target: ATTEMPT_LOG_TARGET, "{}", self.to_json());
+    }
+}
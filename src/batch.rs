@@ -0,0 +1,262 @@
+use crate::config::{BatchConfig, Config};
+use crate::pools::MintPoolData;
+use crate::submit::{Submission, Submitters};
+use crate::transaction::{
+    build_and_send_transaction, build_batch_transaction, SendOutcome, SwapPrograms,
+};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::clock::Slot;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Keypair;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex};
+use tracing::{debug, info};
+
+/// 一个代币提交的发送请求
+struct BatchRequest {
+    pool_data: MintPoolData,
+    /// 该代币本轮决定的计算单元价格
+    compute_unit_price: u64,
+    lookup_tables: Arc<Vec<AddressLookupTableAccount>>,
+    reply: oneshot::Sender<anyhow::Result<SendOutcome>>,
+}
+
+/// 按顺序把请求分组，每组尽量多放代币，直到交易放不下或达到 `max_mints`
+///
+/// # 参数
+/// * `count` - 请求数量
+/// * `max_mints` - 每组最多的代币数
+/// * `fits` - 给定的一组请求能否放进一笔交易，只对两个及以上的组调用；
+///   单个代币的交易放不下时由 `build_transaction` 裁剪池子
+///
+/// # 返回值
+/// 请求下标的分组，保持原有顺序
+pub fn pack(
+    count: usize,
+    max_mints: usize,
+    mut fits: impl FnMut(&[usize]) -> bool,
+) -> Vec<Vec<usize>> {
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut current: Vec<usize> = Vec::new();
+    for i in 0..count {
+        current.push(i);
+        if current.len() > 1 && (current.len() > max_mints || !fits(&current)) {
+            current.pop();
+            groups.push(std::mem::take(&mut current));
+            current.push(i);
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+/// 把同一时间窗口内准备发送的多个代币合并成尽量少的交易
+///
+/// 每个代币的发送任务照常报价和做发送前的检查，然后通过 `submit` 提交池子并等待结果；
+/// 后台任务收到第一个请求后再等待 `window_ms` 收集其他代币，按 `pack` 分组后发送，
+/// 同一笔交易的结果返回给组内每个代币。
+pub struct Batcher {
+    requests: mpsc::UnboundedSender<BatchRequest>,
+}
+
+impl Batcher {
+    /// 启动合并发送的后台任务
+    ///
+    /// # 参数
+    /// * `batch_config` - 合并交易配置
+    /// * `config` - 机器人配置，用于构建交易
+    /// * `programs` - 启动时解析的执行程序和外部闪电贷
+    /// * `wallet_kp` - 签名用的钱包
+    /// * `submitters` - 发送交易的提交器
+    /// * `cached_blockhash` - 后台刷新的 blockhash 及其 slot
+    pub fn spawn(
+        batch_config: &BatchConfig,
+        config: Config,
+        programs: SwapPrograms,
+        wallet_kp: Keypair,
+        submitters: Arc<Submitters>,
+        cached_blockhash: Arc<Mutex<(Hash, Slot)>>,
+    ) -> Self {
+        let (requests, receiver) = mpsc::unbounded_channel();
+        let worker = BatchWorker {
+            config,
+            programs,
+            wallet_kp,
+            submitters,
+            cached_blockhash,
+            max_mints: batch_config.max_mints(),
+            window: batch_config.window(),
+        };
+        tokio::spawn(worker.run(receiver));
+        Self { requests }
+    }
+
+    /// 提交一个代币本轮要发送的池子，等待所在交易的发送结果
+    ///
+    /// # 参数
+    /// * `pool_data` - 本轮参与套利的池子
+    /// * `compute_unit_price` - 该代币本轮的计算单元价格，同一笔交易取最高的价格
+    /// * `lookup_tables` - 该代币使用的地址查找表，同一笔交易使用所有代币查找表的并集
+    ///
+    /// # 错误
+    /// 构建或发送失败，或后台任务已经停止时返回错误
+    pub async fn submit(
+        &self,
+        pool_data: MintPoolData,
+        compute_unit_price: u64,
+        lookup_tables: Arc<Vec<AddressLookupTableAccount>>,
+    ) -> anyhow::Result<SendOutcome> {
+        let (reply, result) = oneshot::channel();
+        self.requests
+            .send(BatchRequest {
+                pool_data,
+                compute_unit_price,
+                lookup_tables,
+                reply,
+            })
+            .map_err(|_| anyhow::anyhow!("Batch sender has stopped"))?;
+        result
+            .await
+            .map_err(|_| anyhow::anyhow!("Batch sender dropped the request"))?
+    }
+}
+
+struct BatchWorker {
+    config: Config,
+    programs: SwapPrograms,
+    wallet_kp: Keypair,
+    submitters: Arc<Submitters>,
+    cached_blockhash: Arc<Mutex<(Hash, Slot)>>,
+    max_mints: usize,
+    window: Duration,
+}
+
+impl BatchWorker {
+    async fn run(self, mut receiver: mpsc::UnboundedReceiver<BatchRequest>) {
+        while let Some(first) = receiver.recv().await {
+            let mut requests = vec![first];
+            let deadline = tokio::time::Instant::now() + self.window;
+            while let Ok(Some(request)) = tokio::time::timeout_at(deadline, receiver.recv()).await {
+                requests.push(request);
+            }
+            self.send(requests).await;
+        }
+    }
+
+    /// 分组发送一批请求，并把结果返回给每个代币
+    async fn send(&self, requests: Vec<BatchRequest>) {
+        let (blockhash, context_slot) = *self.cached_blockhash.lock().await;
+        let groups = pack(requests.len(), self.max_mints, |group| {
+            let (config, lookup_tables) = self.group_config(&requests, group);
+            let pool_datas: Vec<&MintPoolData> =
+                group.iter().map(|&i| &requests[i].pool_data).collect();
+            // 收益按同一个报价货币账户统计，报价货币不同的代币分开发送
+            if pool_datas
+                .iter()
+                .any(|pool_data| pool_data.quote_mint != pool_datas[0].quote_mint)
+            {
+                return false;
+            }
+            build_batch_transaction(
+                &self.wallet_kp,
+                &config,
+                &self.programs,
+                &pool_datas,
+                blockhash,
+                &lookup_tables,
+            )
+            .is_ok()
+        });
+        debug!(
+            "Sending {} mints in {} transaction(s)",
+            requests.len(),
+            groups.len()
+        );
+
+        let mut results: Vec<Option<anyhow::Result<SendOutcome>>> =
+            requests.iter().map(|_| None).collect();
+        for group in &groups {
+            let (config, lookup_tables) = self.group_config(&requests, group);
+            if let [i] = group[..] {
+                results[i] = Some(
+                    build_and_send_transaction(
+                        &self.wallet_kp,
+                        &config,
+                        &self.programs,
+                        &requests[i].pool_data,
+                        &self.submitters,
+                        (blockhash, context_slot),
+                        &lookup_tables,
+                    )
+                    .await,
+                );
+                continue;
+            }
+
+            let pool_datas: Vec<&MintPoolData> =
+                group.iter().map(|&i| &requests[i].pool_data).collect();
+            let mints: Vec<_> = pool_datas.iter().map(|p| p.mint).collect();
+            let outcome = match build_batch_transaction(
+                &self.wallet_kp,
+                &config,
+                &self.programs,
+                &pool_datas,
+                blockhash,
+                &lookup_tables,
+            ) {
+                Ok(tx) => {
+                    info!("Sending one transaction for {} mints", group.len());
+                    // 合并的交易依赖查找表，不构建 legacy 版本
+                    self.submitters
+                        .send(&Submission::new(&tx, pool_datas[0], context_slot))
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            for &i in group {
+                results[i] = Some(match &outcome {
+                    Ok(outcome) => Ok(SendOutcome {
+                        batch_mints: mints.clone(),
+                        ..outcome.clone()
+                    }),
+                    Err(e) => Err(anyhow::anyhow!("{}", e)),
+                });
+            }
+        }
+
+        for (request, result) in requests.into_iter().zip(results) {
+            let result = result.unwrap_or_else(|| {
+                Err(anyhow::anyhow!("Request was not assigned to a transaction"))
+            });
+            // 代币任务已经退出时忽略
+            let _ = request.reply.send(result);
+        }
+    }
+
+    /// 一组请求使用的配置（取组内最高的计算单元价格）和查找表并集
+    fn group_config(
+        &self,
+        requests: &[BatchRequest],
+        group: &[usize],
+    ) -> (Config, Vec<AddressLookupTableAccount>) {
+        let compute_unit_price = group
+            .iter()
+            .map(|&i| requests[i].compute_unit_price)
+            .max()
+            .unwrap_or_else(|| self.config.compute_unit_price());
+        let config = self.config.with_compute_unit_price(compute_unit_price);
+
+        let mut seen = HashSet::new();
+        let lookup_tables = group
+            .iter()
+            .flat_map(|&i| requests[i].lookup_tables.iter())
+            .filter(|table| seen.insert(table.key))
+            .cloned()
+            .collect();
+        (config, lookup_tables)
+    }
+}
@@ -0,0 +1,306 @@
+use crate::app_state::AppState;
+use crate::config::BlacklistConfig;
+use crate::dex::pump::constants::pump_program_id;
+use crate::notifications::Alert;
+use crate::pools::MintPoolData;
+use crate::quote::MAX_MULTIPLE_ACCOUNTS;
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, warn};
+
+/// 下载远程黑名单的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 黑名单条目，配置文件的 `[blacklist]` 和远程黑名单使用相同的字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct BlacklistEntries {
+    /// 池子地址
+    #[serde(default)]
+    pub pools: Vec<String>,
+    /// 代币的 mint authority，命中时不交易该代币
+    #[serde(default)]
+    pub mint_authorities: Vec<String>,
+    /// Pump AMM 池子的代币创建者
+    #[serde(default)]
+    pub creators: Vec<String>,
+}
+
+/// 池子或代币被屏蔽的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blocked {
+    Pool,
+    MintAuthority(Pubkey),
+    Creator(Pubkey),
+}
+
+impl fmt::Display for Blocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Blocked::Pool => write!(f, "pool is blacklisted"),
+            Blocked::MintAuthority(authority) => {
+                write!(f, "mint authority {} is blacklisted", authority)
+            }
+            Blocked::Creator(creator) => write!(f, "coin creator {} is blacklisted", creator),
+        }
+    }
+}
+
+/// 解析后的黑名单
+///
+/// 代币创建者不直接保存在池子数据中，按 Pump 程序的 `creator_vault` PDA 和池子的
+/// `coin_creator_vault_authority` 匹配，缓存中加载的池子也能检查。
+#[derive(Debug, Clone, Default)]
+pub struct Blacklist {
+    pools: HashSet<Pubkey>,
+    mint_authorities: HashSet<Pubkey>,
+    /// 创建者 vault 权限地址到创建者的映射
+    creator_vaults: HashMap<Pubkey, Pubkey>,
+}
+
+impl Blacklist {
+    /// 合并多份黑名单条目，无法解析的地址记录警告后跳过
+    pub fn new(entries: &[&BlacklistEntries]) -> Self {
+        let parse = |kind: &str, addresses: &[String]| -> Vec<Pubkey> {
+            addresses
+                .iter()
+                .filter_map(|address| match Pubkey::from_str(address) {
+                    Ok(pubkey) => Some(pubkey),
+                    Err(e) => {
+                        warn!("Ignoring blacklisted {} `{}`: {}", kind, address, e);
+                        None
+                    }
+                })
+                .collect()
+        };
+        let pump_program = *pump_program_id();
+        let mut blacklist = Self::default();
+        for entries in entries {
+            blacklist.pools.extend(parse("pool", &entries.pools));
+            blacklist
+                .mint_authorities
+                .extend(parse("mint authority", &entries.mint_authorities));
+            for creator in parse("creator", &entries.creators) {
+                let (vault_authority, _) = Pubkey::find_program_address(
+                    &[b"creator_vault", creator.as_ref()],
+                    &pump_program,
+                );
+                blacklist.creator_vaults.insert(vault_authority, creator);
+            }
+        }
+        blacklist
+    }
+
+    /// 合并配置中的条目、本地黑名单文件和远程黑名单
+    ///
+    /// 文件读取或远程下载失败时记录警告，只使用其余来源的条目。
+    pub async fn load(config: &BlacklistConfig) -> Self {
+        let file = match &config.path {
+            Some(path) => match read_file(path) {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    warn!("Failed to read blacklist file {}: {}", path, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let remote = match &config.url {
+            Some(url) => match fetch_remote(url).await {
+                Ok(entries) => Some(entries),
+                Err(e) => {
+                    warn!("Failed to download blacklist {}: {}", url, e);
+                    None
+                }
+            },
+            None => None,
+        };
+        let mut entries = vec![&config.entries];
+        entries.extend(file.as_ref());
+        entries.extend(remote.as_ref());
+        Self::new(&entries)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.len() + self.mint_authorities.len() + self.creator_vaults.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 是否需要读取 mint 账户检查 mint authority
+    pub fn has_mint_authorities(&self) -> bool {
+        !self.mint_authorities.is_empty()
+    }
+
+    /// 检查 mint 账户的 mint authority
+    ///
+    /// # 参数
+    /// * `mint_data` - SPL Token 或 Token 2022 mint 账户的数据
+    pub fn check_mint(&self, mint_data: &[u8]) -> Option<Blocked> {
+        mint_authority(mint_data)
+            .filter(|authority| self.mint_authorities.contains(authority))
+            .map(Blocked::MintAuthority)
+    }
+
+    /// 命中黑名单的池子：池子地址或 Pump 池子的代币创建者
+    pub fn blocked_pools(&self, pool_data: &MintPoolData) -> Vec<(&'static str, Pubkey, Blocked)> {
+        let mut blocked: Vec<_> = pool_data
+            .pool_addresses()
+            .into_iter()
+            .filter(|(_, pool)| self.pools.contains(pool))
+            .map(|(dex, pool)| (dex, pool, Blocked::Pool))
+            .collect();
+        for pump_pool in &pool_data.pump_pools {
+            if let Some(creator) = self
+                .creator_vaults
+                .get(&pump_pool.coin_creator_vault_authority)
+            {
+                if !self.pools.contains(&pump_pool.pool) {
+                    blocked.push(("pump", pump_pool.pool, Blocked::Creator(*creator)));
+                }
+            }
+        }
+        blocked
+    }
+}
+
+/// SPL Token mint 账户的 mint authority，Token 2022 mint 的基础部分布局相同
+///
+/// # 返回值
+/// 没有 mint authority（供应量已固定）或数据长度不足时返回 `None`
+pub fn mint_authority(mint_data: &[u8]) -> Option<Pubkey> {
+    // COption<Pubkey>：4 字节标记，1 表示存在，后面是 32 字节地址
+    let tag = u32::from_le_bytes(mint_data.get(0..4)?.try_into().ok()?);
+    (tag == 1).then(|| Pubkey::try_from(mint_data.get(4..36)?).ok())?
+}
+
+/// 读取本地黑名单文件，内容为与 `[blacklist]` 相同字段的 JSON
+pub fn read_file(path: &str) -> anyhow::Result<BlacklistEntries> {
+    Ok(serde_json::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+/// 下载远程黑名单，响应为与 `[blacklist]` 相同字段的 JSON
+pub async fn fetch_remote(url: &str) -> anyhow::Result<BlacklistEntries> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    Ok(client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?)
+}
+
+/// 读取 mint 账户并检查 mint authority，账户不存在的 mint 不屏蔽
+pub fn fetch_blocked_mints(
+    rpc_client: &RpcClient,
+    blacklist: &Blacklist,
+    mints: &[Pubkey],
+) -> anyhow::Result<Vec<(Pubkey, Blocked)>> {
+    let mut blocked = Vec::new();
+    for chunk in mints.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let accounts = rpc_client.get_multiple_accounts(chunk)?;
+        for (mint, account) in chunk.iter().zip(accounts) {
+            if let Some(reason) = account.and_then(|account| blacklist.check_mint(&account.data)) {
+                blocked.push((*mint, reason));
+            }
+        }
+    }
+    Ok(blocked)
+}
+
+/// 定期重新读取黑名单并检查运行中的代币
+///
+/// 远程黑名单可能新增条目，代币的 mint authority 也可能变化，命中时把池子从共享的池子数据中移除，
+/// 记录错误日志并推送告警；mint authority 命中时移除该代币的所有池子。
+pub struct BlacklistWatcher {
+    pub config: BlacklistConfig,
+    pub state: Arc<AppState>,
+}
+
+impl BlacklistWatcher {
+    pub async fn run(self, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            let blacklist = Blacklist::load(&self.config).await;
+            if blacklist.is_empty() {
+                continue;
+            }
+
+            let mints = self.state.mints();
+            let blocked_mints = if blacklist.has_mint_authorities() {
+                let rpc_client = self.state.rpc_client.clone();
+                let blacklist = blacklist.clone();
+                let mints = mints.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    fetch_blocked_mints(&rpc_client, &blacklist, &mints)
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+                match result {
+                    Ok(blocked) => blocked,
+                    Err(e) => {
+                        error!("Failed to check mint authorities: {}", e);
+                        Vec::new()
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+
+            for mint in mints {
+                let Some(pool_data) = self.state.mint(&mint) else {
+                    continue;
+                };
+                let mut guard = pool_data.lock().await;
+                let blocked: Vec<_> =
+                    match blocked_mints.iter().find(|(blocked, _)| *blocked == mint) {
+                        Some((_, reason)) => guard
+                            .pool_addresses()
+                            .into_iter()
+                            .map(|(dex, pool)| (dex, pool, *reason))
+                            .collect(),
+                        None => blacklist.blocked_pools(&guard),
+                    };
+                if blocked.is_empty() {
+                    continue;
+                }
+                guard.retain_pools(|pool| !blocked.iter().any(|(_, blocked, _)| blocked == pool));
+                for (dex, pool, reason) in blocked {
+                    error!(
+                        "Removing {} pool {} from mint {}: {}",
+                        dex, pool, mint, reason
+                    );
+                    self.state.notifier.notify(Alert::PoolEvicted {
+                        mint: mint.to_string(),
+                        dex,
+                        pool,
+                        reason: reason.to_string(),
+                    });
+                }
+                if !guard.has_valid_route() {
+                    warn!(
+                        "Mint {} has no buy/sell pool pair left after removing pools",
+                        mint
+                    );
+                }
+                self.state.status.update_pools(&guard);
+            }
+            debug!(
+                "Checked {} mint(s) against {} blacklist entries",
+                self.state.mints().len(),
+                blacklist.len()
+            );
+        }
+    }
+}
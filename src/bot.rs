@@ -1,24 +1,78 @@
-use crate::config::Config;
-use crate::refresh::initialize_pool_data;
-use crate::transaction::build_and_send_transaction;
+use crate::account_pruning::PrunedRoutes;
+use crate::app_state::{AppState, Services, TradeSinks};
+use crate::ata;
+use crate::attempt_log::AttemptRecord;
+use crate::batch::Batcher;
+use crate::blacklist::{fetch_blocked_mints, Blacklist, BlacklistWatcher};
+use crate::broadcast::BroadcastLog;
+use crate::config::{
+    Config, ConfigFormat, GeyserConfig, InlineSwapConfig, MintConfig, SendingEndpoint,
+};
+use crate::constants::CashMint;
+use crate::cooldown::FailureCooldown;
+use crate::copy_route::{self, CopyRouteBook};
+use crate::export::Exporter;
+use crate::fee_budget::{FeeDecision, FeeGovernor};
+use crate::graduation::GraduationWatcher;
+use crate::in_flight::InFlight;
+use crate::inline_swap::{build_inline_transaction, fetch_quote_balance, InlineRoute};
+use crate::inventory::Inventory;
+use crate::jito::{tip_ratio, JitoSender};
+use crate::landing_model::{expected_value, transaction_fee, LandingBucket, LandingModel};
+use crate::leader_schedule::{LeaderFilter, LeaderTracker};
+use crate::lookup_table::{extend_own_lookup_table, fetch_lookup_table};
+use crate::mint_safety::mint_risks;
+use crate::notifications::{Alert, AlertKind, Notifier};
+use crate::pool_cache::PoolCache;
+use crate::pool_owners;
+use crate::pool_watch::PoolWatcher;
+use crate::pools::MintPoolData;
+use crate::price_feed::SolPriceFeed;
+use crate::quote::{self, PoolReserves, SizedQuote, TradeSizing};
+use crate::rebalance::Rebalancer;
+use crate::refresh::{initialize_pool_data, PoolLists, PoolLoadContext};
+use crate::research::OpportunityLog;
+use crate::route_fees::RouteFees;
+use crate::rpc::{
+    build_cached_rpc_client, build_nonblocking_rpc_client, build_rpc_client,
+    get_latest_blockhash_with_slot, AccountCache, RpcMetrics,
+};
+use crate::schedule::ProcessSchedule;
+use crate::state_dump::StateDump;
+use crate::status::{unix_now_ms, BotStatus, TransactionOutcome};
+use crate::storage::TradeStore;
+use crate::submit::{Submission, Submitters};
+use crate::tpu::TpuSender;
+use crate::transaction::{
+    audit_profit, fetch_landed_profit, legacy_transaction_for, uncovered_accounts,
+    LandedTransaction, ProfitAudit, SendingClient, SwapPrograms,
+};
+use crate::transaction_watch::TransactionWatch;
+use crate::tx_template::TransactionTemplates;
+use crate::vaults::VaultTracker;
+use crate::watchdog::Watchdog;
+use crate::wsol::WsolTopUp;
 use anyhow::Context;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
-use solana_sdk::hash::Hash;
+use solana_sdk::native_token::sol_to_lamports;
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::Keypair;
+use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
-use solana_sdk::{
-    address_lookup_table::state::AddressLookupTable, compute_budget::ComputeBudgetInstruction,
-};
-use spl_associated_token_account::{
-    get_associated_token_address, get_associated_token_address_with_program_id,
-};
-use std::str::FromStr;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+
+/// blockhash 连续刷新失败多少次后认为 RPC 故障
+const RPC_OUTAGE_THRESHOLD: u32 = 3;
+
+/// 上链率模型有新结果时多久保存一次
+const LANDING_MODEL_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 /// 启动并运行交易机器人。
 ///
@@ -26,43 +80,383 @@ use tracing::{error, info, warn};
 /// 检查或创建代币账户，并为每个代币配置启动独立的交易发送任务。
 ///
 /// # 参数
-/// * `config_path` - 配置文件路径，用于加载机器人运行所需的各项配置。
+/// * `config_path` - 配置文件路径，用于加载机器人运行所需的各项配置，`-` 表示从标准输入读取。
+/// * `config_format` - 显式指定的配置格式，为 `None` 时按扩展名推断。
+/// * `use_pool_cache` - 是否读取池子元数据缓存；为 `false` 时重新加载所有池子并覆盖缓存。
+/// * `tui` - 是否在启动完成后显示终端监控界面，界面退出时机器人随之退出。
 ///
 /// # 返回值
 /// 返回 `anyhow::Result<()>`，表示运行过程中是否发生错误。
-pub async fn run_bot(config_path: &str) -> anyhow::Result<()> {
-    let config = Config::load(config_path)?;
+pub async fn run_bot(
+    config_path: &str,
+    config_format: Option<ConfigFormat>,
+    use_pool_cache: bool,
+    tui: bool,
+    dump_state: Option<&Path>,
+) -> anyhow::Result<()> {
+    let config = Config::load_with_format(config_path, config_format)?;
     info!("Configuration loaded successfully");
+    run_with_config(config, use_pool_cache, tui, dump_state).await
+}
+
+/// 使用已加载的配置运行机器人，`local-sim` 用它在改写后的配置上运行
+///
+/// # 参数
+/// * `config` - 已解析并校验的配置
+/// * `use_pool_cache` - 是否读取池子元数据缓存
+/// * `tui` - 是否显示终端监控界面
+/// * `dump_state` - 设置时只加载池子并把快照写入该目录后返回，不修改链上或本地状态
+pub async fn run_with_config(
+    config: Config,
+    use_pool_cache: bool,
+    tui: bool,
+    dump_state: Option<&Path>,
+) -> anyhow::Result<()> {
+    // 应用集群配置中的程序 ID 覆盖（devnet/localnet）
+    if let Some(cluster) = &config.cluster {
+        let overrides = cluster.program_id_overrides()?;
+        info!(
+            "Cluster: {}, {} program id override(s)",
+            cluster.name.as_deref().unwrap_or("mainnet"),
+            overrides.len()
+        );
+        for (key, pubkey) in &overrides {
+            info!("    {}: {}", key, pubkey);
+        }
+        crate::constants::init_program_id_overrides(overrides)?;
+    }
+
+    // --dump-state 只读取链上状态：加载池子、写入快照后返回，不创建代币账户、不包装 SOL，也不启动后台任务
+    if let Some(dir) = dump_state {
+        return dump_pool_state(&config, use_pool_cache, dir).await;
+    }
+
+    let (state, startup) = setup(config).await?;
+    let config = &state.config;
+
+    // 池子元数据缓存，重启时跳过已解析过的静态池子
+    let pool_cache_path = config.bot.pool_cache_path();
+    let mut pool_cache = if use_pool_cache {
+        PoolCache::load(&pool_cache_path)
+    } else {
+        info!("Pool cache disabled, fetching all pools from RPC");
+        PoolCache::empty()
+    };
+
+    // 黑名单：跳过命中的池子，以及 mint authority 命中的代币
+    let blacklist = load_blacklist(config).await;
+    if let Some(blacklist_config) = &config.blacklist {
+        if let Some(interval) = blacklist_config.refresh_interval() {
+            let watcher = BlacklistWatcher {
+                config: blacklist_config.clone(),
+                state: state.clone(),
+            };
+            tokio::spawn(watcher.run(interval));
+        }
+    }
+
+    // 为每个代币配置初始化池数据并启动交易发送任务->这个只运行一次
+    let loaded_mints = load_mint_pools(
+        config,
+        &state.wallet.pubkey(),
+        &startup.rpc_client,
+        &startup.async_rpc_client,
+        &mut pool_cache,
+        &blacklist,
+    )
+    .await?;
+    for loaded in loaded_mints {
+        spawn_mint(&state, &startup.rpc_client, loaded, tui)?;
+    }
+
+    // 监视池子目录，新池子不需要重启就加入对应代币的下一轮交易
+    if let Some(pool_watch) = config.pool_watch.as_ref().filter(|p| p.enabled) {
+        let dir = PathBuf::from(pool_watch.dir());
+        info!("Watching {} for new pools", dir.display());
+        let watcher = PoolWatcher {
+            state: state.clone(),
+        };
+        tokio::spawn(watcher.run(dir, pool_watch.interval()));
+    }
+
+    match pool_cache.save(&pool_cache_path) {
+        Ok(()) => info!(
+            "Saved {} pools to cache {}",
+            pool_cache.len(),
+            pool_cache_path
+        ),
+        Err(e) => warn!("Failed to save pool cache {}: {}", pool_cache_path, e),
+    }
+    if config.rpc.cache_slots() > 0 {
+        info!(
+            "Startup account reads: {} served from cache, {} fetched from RPC",
+            startup.account_cache.hits(),
+            startup.account_cache.misses()
+        );
+    }
+
+    state.notifier.notify(Alert::Started {
+        wallet: state.wallet.pubkey(),
+        mint_count: config.routing.mint_config_list.len(),
+    });
+
+    wait_for_shutdown(&state, tui).await
+}
+
+/// 启动阶段：创建 RPC 客户端、发送端点和各个可选子系统，组装共享的运行状态，
+/// 启动 blockhash 刷新、健康检查和余额检查等后台任务，并准备好钱包的代币账户
+///
+/// # 参数
+/// * `config` - 已解析并校验的配置
+///
+/// # 返回值
+/// 共享的运行状态，以及加载池子时使用的客户端
+///
+/// # 错误
+/// 执行程序检查失败、客户端创建失败、读取钱包或 blockhash 失败，或创建代币账户失败时返回错误
+async fn setup(config: Config) -> anyhow::Result<(Arc<AppState>, StartupClients)> {
+    // 执行程序和外部闪电贷只在启动时解析一次，之后构建每笔交易时使用解析结果
+    let programs = SwapPrograms::from_config(&config)?;
+    let executors = &programs.executors;
+    if config.executor.is_some() {
+        let executor = executors.default;
+        info!(
+            "Executor program: {} (discriminator {}, layout v{}), fee collector: {}",
+            executor.program_id,
+            executor.discriminator,
+            executor.layout_version,
+            executor.fee_collector
+        );
+        for router in &executors.routers {
+            info!(
+                "    Router {} (discriminator {}, layout v{}) for {} mint(s), {}..={} pools",
+                router.executor.program_id,
+                router.executor.discriminator,
+                router.executor.layout_version,
+                if router.mints.is_empty() {
+                    "all".to_string()
+                } else {
+                    router.mints.len().to_string()
+                },
+                router.min_pools,
+                if router.max_pools == usize::MAX {
+                    "any".to_string()
+                } else {
+                    router.max_pools.to_string()
+                }
+            );
+        }
+    }
 
     // 创建一个新的RPC客户端实例
     //
     // 该代码行执行以下操作：
     // 1. 从配置中克隆RPC服务器的URL地址
-    // 2. 使用该URL创建一个新的RpcClient实例
+    // 2. 使用该URL创建一个新的RpcClient实例，配置了 rate_limit 时按令牌桶限流
     // 3. 将RpcClient包装在Arc智能指针中以支持多线程共享
     //
     // 返回值：Arc<RpcClient> - 线程安全的RPC客户端引用计数智能指针
-    let rpc_client = Arc::new(RpcClient::new(config.rpc.url.clone()));
+    // 启用 [rpc.metrics] 时所有 RPC 客户端按端点和方法记录请求延迟和错误
+    let rpc_metrics = config.rpc.metrics().map(|metrics| {
+        let rpc_metrics = Arc::new(RpcMetrics::new());
+        tokio::spawn(rpc_metrics.clone().log_summaries(metrics.log_interval()));
+        rpc_metrics
+    });
+    let rpc_client = Arc::new(build_rpc_client(
+        &config.rpc.url,
+        config.rpc.rate_limit.as_ref(),
+        rpc_metrics.as_ref(),
+        config.rpc.proxy.as_ref(),
+    )?);
+    // 代理只作用于 HTTP 请求，websocket 订阅、TPU 的 QUIC 连接和 Geyser gRPC 仍然直接连接
+    if config.rpc.proxy.is_some() {
+        let direct: Vec<&str> = [
+            (
+                "TPU sending",
+                config
+                    .sending
+                    .as_ref()
+                    .is_some_and(|sending| sending.mode.uses_tpu()),
+            ),
+            (
+                "copy_route",
+                config
+                    .copy_route
+                    .as_ref()
+                    .is_some_and(|copy_route| copy_route.enabled),
+            ),
+            (
+                "geyser",
+                config.geyser.as_ref().is_some_and(|geyser| geyser.enabled),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, enabled)| enabled.then_some(name))
+        .collect();
+        if !direct.is_empty() {
+            warn!(
+                "rpc.proxy only applies to HTTP requests; {} connect directly",
+                direct.join(", ")
+            );
+        }
+    }
+
+    // 检查部署的执行程序，编码不一致时在发送任何交易前退出；直接兑换和研究模式不调用执行程序
+    let uses_executor = !config.is_research()
+        && !config
+            .inline_swap
+            .as_ref()
+            .is_some_and(|inline_swap| inline_swap.enabled);
+    if uses_executor
+        && config
+            .executor
+            .as_ref()
+            .is_none_or(|executor| executor.probe_on_startup())
+    {
+        for executor in executors.programs() {
+            executor.probe(|pubkey| {
+                Ok(rpc_client
+                    .get_account_with_commitment(pubkey, rpc_client.commitment())?
+                    .value)
+            })?;
+            info!(
+                "Executor program {} is deployed{}",
+                executor.program_id,
+                if executor.version_check.is_some() {
+                    format!(" and reports layout v{}", executor.layout_version)
+                } else {
+                    String::new()
+                }
+            );
+        }
+    }
+
+    let startup = startup_clients(&config, &rpc_client, rpc_metrics.as_ref())?;
+
+    // SOL/USD 价格，配置了 [price_feed] 时收益、余额告警、仪表盘和终端界面同时显示美元金额
+    let price_feed = match &config.price_feed {
+        Some(price_feed) if price_feed.enabled => Some(Arc::new(SolPriceFeed::new(price_feed)?)),
+        _ => None,
+    };
+
+    // 告警推送器，未配置 [notifications] 时不会发送任何消息
+    let notifier = Notifier::new(config.notifications.as_ref()).with_price_feed(price_feed.clone());
+
+    // 运行状态，供仪表盘和终端界面展示
+    let status = Arc::new(BotStatus::new(
+        config.rpc.url.clone(),
+        config.is_dry_run(),
+        config.compute_unit_price(),
+    ));
+    if let Some(price_feed) = &price_feed {
+        info!("Fetching SOL/USD price from {:?}", price_feed.source());
+        tokio::spawn(price_feed.clone().run(rpc_client.clone(), status.clone()));
+    }
+
+    // 交易历史存储，配置了 [storage] 时启用
+    let trade_store = match &config.storage {
+        Some(storage) => {
+            let trade_store = TradeStore::open(storage.database_path())
+                .with_context(|| format!("Failed to open {}", storage.database_path()))?;
+            info!("Recording trade history to {}", storage.database_path());
+            Some(Arc::new(trade_store))
+        }
+        None => None,
+    };
+    // 报价、发送和交易结果的 CSV 导出，配置了 [export] 时启用
+    let exporter = match &config.export {
+        Some(export) if export.enabled => {
+            info!(
+                "Exporting quotes, sends and outcomes to {}",
+                export.directory()
+            );
+            Some(Arc::new(Exporter::new(export)))
+        }
+        _ => None,
+    };
+    let trade_sinks = TradeSinks {
+        trade_store,
+        exporter,
+    };
+    if let Some(dashboard) = config.dashboard.as_ref().filter(|d| d.enabled) {
+        let bind_address = dashboard.bind_address();
+        let dashboard_status = status.clone();
+        let dashboard_metrics = rpc_metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::dashboard::serve(&bind_address, dashboard_status, dashboard_metrics).await
+            {
+                error!("Dashboard server stopped: {}", e);
+            }
+        });
+    }
 
     // 根据配置决定RPC客户端列表的构建方式
-    // 如果启用了spam配置，则使用配置中的多个RPC URL创建客户端列表
+    // 如果启用了spam配置，则使用配置中的多个发送端点创建客户端列表
     // 否则只使用默认的rpc_client克隆版本
-    let sending_rpc_clients = if let Some(spam_config) = &config.spam {
+    // dry-run 模式下只使用主 RPC 客户端进行交易模拟
+    let default_sending_client = SendingClient::new(
+        rpc_client.clone(),
+        config.spam.as_ref().map_or_else(
+            || SendingEndpoint::new(&config.rpc.url),
+            |spam_config| spam_config.endpoint_for(&config.rpc.url),
+        ),
+    );
+    let sending_rpc_clients = if config.is_dry_run() {
+        info!("Dry-run mode enabled: transactions will be simulated but never broadcast");
+        vec![default_sending_client]
+    } else if let Some(spam_config) = &config.spam {
         // 检查是否启用spam功能
         if spam_config.enabled {
-            // 当spam启用时，为每个配置的RPC URL创建新的RpcClient实例
+            // 当spam启用时，为每个发送端点创建新的RpcClient实例，各自独立限流
             spam_config
-                .sending_rpc_urls
-                .iter()
-                .map(|url| Arc::new(RpcClient::new(url.clone())))
-                .collect::<Vec<_>>()
+                .sending_endpoints()
+                .into_iter()
+                .map(|endpoint| {
+                    let client = build_rpc_client(
+                        &endpoint.url,
+                        spam_config.rate_limit.as_ref(),
+                        rpc_metrics.as_ref(),
+                        endpoint.proxy.as_ref(),
+                    )?;
+                    Ok(SendingClient::new(Arc::new(client), endpoint))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
         } else {
             // spam配置存在但未启用时，使用默认RPC客户端
-            vec![rpc_client.clone()]
+            vec![default_sending_client]
         }
     } else {
         // 无spam配置时，使用默认RPC客户端
-        vec![rpc_client.clone()]
+        vec![default_sending_client]
+    };
+
+    // 发送方式：rpc | tpu | both，dry-run 模式下不创建 TPU 客户端
+    let sending_mode = config
+        .sending
+        .as_ref()
+        .map(|sending| sending.mode)
+        .unwrap_or_default();
+    let tpu_sender = match &config.sending {
+        Some(sending) if sending_mode.uses_tpu() && !config.is_dry_run() => {
+            let websocket_url = sending.websocket_url(&config.rpc.url);
+            info!(
+                "Sending through TPU (mode {:?}, fanout {} slots, websocket {})",
+                sending_mode,
+                sending.fanout_slots(),
+                websocket_url
+            );
+            let tpu_sender =
+                TpuSender::new(rpc_client.clone(), &websocket_url, sending.fanout_slots())
+                    .context("Failed to create TPU client")?;
+            Some(Arc::new(tpu_sender))
+        }
+        _ => None,
+    };
+    let sending_rpc_clients = if sending_mode.uses_rpc() || config.is_dry_run() {
+        sending_rpc_clients
+    } else {
+        Vec::new()
     };
     // 加载钱包密钥对
     let wallet_kp =
@@ -70,276 +464,1572 @@ pub async fn run_bot(config_path: &str) -> anyhow::Result<()> {
     info!("Wallet loaded: {}", wallet_kp.pubkey());
 
     // 获取最新的区块哈希值，用于后续的交易签名和验证
-    // 该操作通过RPC客户端与区块链网络交互，获取当前最新的区块哈希
-    let initial_blockhash = rpc_client.get_latest_blockhash()?;
+    // 该操作通过RPC客户端与区块链网络交互，获取当前最新的区块哈希及其所在的 slot
+    let initial_blockhash = get_latest_blockhash_with_slot(&rpc_client)?;
+
+    // 启用 Jito 时套利交易和小费交易作为 bundle 发送到区块引擎，小费按模拟收益分成
+    let jito_sender = match &config.jito {
+        Some(jito) if jito.enabled => {
+            info!(
+                "Sending bundles through Jito block engine(s) {} to {} at a time (tip {}% of simulated profit, min {} / max {:?} lamports)",
+                jito.block_engine_urls().join(", "),
+                jito.race_regions(),
+                jito.tip_percent(),
+                jito.min_tip_lamports(),
+                jito.max_tip_lamports
+            );
+            Some(Arc::new(JitoSender::new(
+                jito,
+                rpc_client.clone(),
+                Keypair::from_bytes(&wallet_kp.to_bytes())?,
+            )?))
+        }
+        _ => None,
+    };
 
-    // 将获取到的初始区块哈希值包装为线程安全的共享引用
-    // 使用Arc<Mutex<T>>结构实现多线程环境下的安全访问和修改
-    // 这样可以在多个作用域或线程中共享和更新区块哈希值
-    let cached_blockhash = Arc::new(Mutex::new(initial_blockhash));
-    let refresh_interval = Duration::from_secs(10);
-    let blockhash_client = rpc_client.clone();
-    let blockhash_cache = cached_blockhash.clone();
+    // 按配置选择交易提交器：dry-run 模拟记录器、Jito bundle，或 TPU 加 RPC 发送端点
+    let submitters = Submitters::from_config(
+        &config,
+        &rpc_client,
+        sending_rpc_clients,
+        tpu_sender,
+        jito_sender.clone(),
+    );
+
+    // 各个任务共享的运行状态：配置、钱包、RPC 客户端、发送端点、blockhash 缓存、
+    // 计算单元价格、各代币的池子数据和可选子系统，以 Arc 交给每个任务
+    let state = AppState::new(
+        config,
+        wallet_kp,
+        rpc_client,
+        initial_blockhash,
+        status,
+        notifier,
+    )
+    .with_programs(programs)
+    .with_submitters(submitters)
+    .with_jito_sender(jito_sender)
+    .with_trade_sinks(trade_sinks);
+    let services = start_services(&state, rpc_metrics)?;
+    let state = Arc::new(state.with_services(services));
+    let config = &state.config;
+    let wallet_kp = &state.wallet;
 
     // 启动后台任务定期刷新 blockhash 缓存
-    tokio::spawn(async move {
-        blockhash_refresher(blockhash_client, blockhash_cache, refresh_interval).await;
-    });
+    tokio::spawn(blockhash_refresher(state.clone(), Duration::from_secs(10)));
+
+    // 健康检查服务在加载池子之前启动，加载期间 /readyz 返回未就绪
+    if let Some(health) = config.health.as_ref().filter(|h| h.enabled) {
+        let bind_address = health.bind_address();
+        let max_blockhash_age = health.max_blockhash_age();
+        let health_state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) =
+                crate::health::serve(&bind_address, health_state, max_blockhash_age).await
+            {
+                error!("Health check server stopped: {}", e);
+            }
+        });
+    }
+
+    // 启动后台任务检查钱包余额
+    if let Some(min_balance_sol) = config
+        .notifications
+        .as_ref()
+        .and_then(|notifications| notifications.min_balance_sol)
+    {
+        if state.notifier.is_enabled(AlertKind::LowBalance) {
+            let check_interval = config
+                .notifications
+                .as_ref()
+                .map(|notifications| notifications.balance_check_interval())
+                .unwrap_or(Duration::from_secs(60));
+            tokio::spawn(balance_monitor(
+                state.clone(),
+                sol_to_lamports(min_balance_sol),
+                check_interval,
+            ));
+        }
+    }
 
-    // 遍历所有代币配置，检查并创建对应的关联代币账户（ATA）
+    // 数据过期时暂停发送，恢复后继续
+    if let Some(watchdog) = &state.services.watchdog {
+        tokio::spawn(watchdog.clone().run(state.clone()));
+    }
+
+    // 遍历所有代币和非 SOL 的报价货币，按 `bot.create_ata` 检查并创建对应的关联代币账户（ATA）；WSOL 账户由 wsol_top_up 处理
+    let mut ata_mints: Vec<Pubkey> = Vec::new();
     for mint_config in &config.routing.mint_config_list {
-        // 获取代币的 owner program ID（如 Token Program 或 Token-2022）
-        // 通过RPC客户端获取mint账户的所有者信息
-        // 该代码块完成了以下操作：
-        // 1. 将mint_config中的mint字符串转换为Pubkey类型
-        // 2. 使用RPC客户端查询该账户的详细信息
-        // 3. 提取账户的所有者(owner)字段
-        // 它获取的是代币铸造账户（mint account）的所有者程序ID。
-        let mint_owner = rpc_client
-            .get_account(&Pubkey::from_str(&mint_config.mint).unwrap())
-            .unwrap()
-            .owner;
-
-        // 获取钱包关联的代币账户地址
-        //
-        // 该函数通过钱包公钥、铸币地址和铸币所有者程序ID来计算关联的代币账户地址。
-        // 主要用于SPL代币标准中，为特定钱包和代币组合生成确定性的关联账户地址。
-        //
-        // # 参数
-        // * `wallet_kp.pubkey()` - 钱包的公钥，用于生成关联账户
-        // * `Pubkey::from_str(&mint_config.mint).unwrap()` - 铸币地址，表示特定代币的mint地址
-        // * `&mint_owner` - 铸币所有者程序ID，通常是代币程序的地址
-        //
-        // # 返回值
-        // 返回计算得到的关联代币账户地址
-        let wallet_token_account = get_associated_token_address_with_program_id(
-            &wallet_kp.pubkey(),
-            &Pubkey::from_str(&mint_config.mint).unwrap(),
-            &mint_owner,
+        let quote_mint = mint_config.quote_mint()?;
+        let quote_mint = (!quote_mint.is_sol()).then_some(quote_mint.mint);
+        for mint in std::iter::once(mint_config.mint_pubkey()?).chain(quote_mint) {
+            if !ata_mints.contains(&mint) {
+                ata_mints.push(mint);
+            }
+        }
+    }
+    for mint in &ata_mints {
+        ata::ensure_token_account(
+            &state.rpc_client,
+            wallet_kp,
+            mint,
+            config.bot.create_ata,
+            config.compute_unit_price(),
+            config.bot.create_ata_attempts(),
+            config.is_dry_run(),
+        )?;
+    }
+
+    // WSOL 不足时自动包装原生 SOL，dry-run 模式下只记录
+    if let Some(wsol_top_up) = config.wsol_top_up.as_ref().filter(|w| w.enabled) {
+        let top_up = Arc::new(WsolTopUp::new(
+            state.rpc_client.clone(),
+            wallet_kp,
+            wsol_top_up,
+            config.is_dry_run(),
+            state.notifier.clone(),
+        )?);
+        info!(
+            "WSOL top-up: wrapping SOL when WSOL drops below {} SOL, target {} SOL, keeping {} SOL for fees",
+            wsol_top_up.min_wsol_sol,
+            wsol_top_up.target_wsol_sol(),
+            wsol_top_up.gas_reserve_sol()
         );
+        tokio::spawn(top_up.run());
+    }
 
-        println!("   Token mint: {}", mint_config.mint);
-        println!("   Wallet token ATA: {}", wallet_token_account);
-        // 检查钱包的关联代币账户是否存在，若不存在则创建
-        println!("\n   Checking if token account exists...");
-        loop {
-            match rpc_client.get_account(&wallet_token_account) {
-                Ok(_) => {
-                    println!("   token account exists!");
-                    break;
-                }
-                Err(_) => {
-                    println!("   token account does not exist. Creating it...");
-
-                    // 构造创建 ATA 的指令（幂等创建）
-                    let create_ata_ix =
-                            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
-                                &wallet_kp.pubkey(), // Funding account
-                                &wallet_kp.pubkey(), // Wallet account
-                                &Pubkey::from_str(&mint_config.mint).unwrap(),   // Token mint
-                                &spl_token::ID,      // Token program
-                            );
-
-                    // 获取最新的 blockhash 用于交易签名
-                    let blockhash = rpc_client.get_latest_blockhash()?;
-
-                    // 创建设置计算单元价格的指令，参数为每计算单元的价格（微 lamports）
-                    let compute_unit_price_ix =
-                        ComputeBudgetInstruction::set_compute_unit_price(1_000_000);
-
-                    // 创建设置计算单元限制的指令，参数为交易允许使用的最大计算单元数量
-                    let compute_unit_limit_ix =
-                        ComputeBudgetInstruction::set_compute_unit_limit(60_000);
-
-                    // 构造并签名交易
-                    let create_ata_tx = solana_sdk::transaction::Transaction::new_signed_with_payer(
-                        &[compute_unit_price_ix, compute_unit_limit_ix, create_ata_ix],
-                        Some(&wallet_kp.pubkey()),
-                        &[&wallet_kp],
-                        blockhash,
-                    );
+    Ok((state, startup))
+}
 
-                    // 发送并确认交易
-                    match rpc_client.send_and_confirm_transaction(&create_ata_tx) {
-                        Ok(sig) => {
-                            println!("   token account created successfully! Signature: {}", sig);
-                        }
-                        Err(e) => {
-                            println!("   Failed to create token account: {:?}", e);
-                            return Err(anyhow::anyhow!("Failed to create token account"));
-                        }
+/// 按配置创建各代币发送任务共用的可选子系统，需要后台运行的子系统在这里启动
+///
+/// 看门狗需要共享的运行状态，由 `setup` 在组装好 `AppState` 后启动。
+///
+/// # 参数
+/// * `state` - 还没有加入子系统的运行状态，提供配置、钱包、RPC 客户端、提交器和 blockhash 缓存
+/// * `rpc_metrics` - 启用 `[rpc.metrics]` 时的请求统计
+///
+/// # 错误
+/// 读取库存配置、打开机会记录文件、创建 Geyser 订阅或复制钱包密钥失败时返回错误
+fn start_services(
+    state: &AppState,
+    rpc_metrics: Option<Arc<RpcMetrics>>,
+) -> anyhow::Result<Services> {
+    let config = &state.config;
+    let rpc_client = &state.rpc_client;
+    let wallet_kp = &state.wallet;
+
+    // 按 leader 计划过滤发送时机
+    let leader_filter = match &config.leader_schedule {
+        Some(leader_schedule) => {
+            let tracker = Arc::new(LeaderTracker::default());
+            tokio::spawn(tracker.clone().run(rpc_client.clone()));
+            Some(Arc::new(LeaderFilter::new(leader_schedule, tracker)?))
+        }
+        None => None,
+    };
+
+    // 库存管理：闲置的 WSOL 存入借贷市场，dry-run 模式下不存取
+    let inventory = match &config.inventory {
+        Some(inventory_config) if inventory_config.enabled && !config.is_dry_run() => {
+            let inventory = Arc::new(Inventory::new(
+                rpc_client.clone(),
+                wallet_kp,
+                inventory_config,
+            )?);
+            tokio::spawn(inventory.clone().run());
+            Some(inventory)
+        }
+        _ => None,
+    };
+
+    // 优先费预算，所有代币共用，dry-run 模式下不发送交易也就没有支出
+    let fee_governor = match &config.fee_budget {
+        Some(fee_budget) if fee_budget.enabled && !config.is_dry_run() => {
+            Some(Arc::new(FeeGovernor::new(fee_budget)))
+        }
+        _ => None,
+    };
+
+    // 按路线的上链率调整优先费，所有代币共用，dry-run 模式下没有上链结果
+    let route_fees = match &config.route_fees {
+        Some(route_fees) if route_fees.enabled && !config.is_dry_run() => Some(Arc::new(
+            RouteFees::new(route_fees, config.compute_unit_price()),
+        )),
+        _ => None,
+    };
+
+    // 期望收益门槛的上链率模型，跨运行保存
+    let landing_model = match &config.ev_gate {
+        Some(ev_gate) if ev_gate.enabled => {
+            let path = PathBuf::from(ev_gate.model_path());
+            let model = Arc::new(LandingModel::load(&path, ev_gate.prior_landing_rate()));
+            tokio::spawn(model.clone().run_saver(path, LANDING_MODEL_SAVE_INTERVAL));
+            Some(model)
+        }
+        _ => None,
+    };
+
+    // Yellowstone gRPC 推送钱包的交易结果时，确认上链不再轮询交易状态
+    let transaction_watch = match &config.geyser {
+        Some(geyser) if geyser.enabled => {
+            Some(spawn_transaction_watch(geyser, wallet_kp.pubkey())?)
+        }
+        _ => None,
+    };
+
+    // 研究模式：只报价并记录观察到的机会，不发送交易
+    let opportunity_log = match &config.research {
+        Some(research) if research.enabled => {
+            let path = research.output_path();
+            let log = OpportunityLog::create(&path)
+                .with_context(|| format!("Failed to open opportunity log {}", path))?;
+            info!("Research mode: recording opportunities to {}", path);
+            Some(Arc::new(log))
+        }
+        _ => None,
+    };
+
+    // 批量跟踪所有池子的 vault 余额，报价和仪表盘共用
+    let vault_tracker = config.bot.vault_refresh_interval().map(|interval| {
+        let tracker = Arc::new(VaultTracker::new());
+        tokio::spawn(
+            tracker
+                .clone()
+                .run(rpc_client.clone(), interval, state.status.clone()),
+        );
+        tracker
+    });
+
+    // 数据过期时暂停发送，恢复后继续
+    let watchdog = config
+        .watchdog
+        .as_ref()
+        .filter(|watchdog| watchdog.enabled)
+        .map(|watchdog_config| Arc::new(Watchdog::new(watchdog_config, vault_tracker.clone())));
+
+    // 多个代币合并到同一笔交易发送，研究模式下不发送交易
+    let batcher = match &config.batch {
+        Some(batch) if batch.enabled && opportunity_log.is_none() => {
+            info!(
+                "Batching up to {} mints per transaction ({:?} window)",
+                batch.max_mints(),
+                batch.window()
+            );
+            Some(Arc::new(Batcher::spawn(
+                batch,
+                config.clone(),
+                state.programs.clone(),
+                Keypair::from_bytes(&wallet_kp.to_bytes())?,
+                state.submitters.clone(),
+                state.blockhash.clone(),
+            )))
+        }
+        _ => None,
+    };
+
+    // 不使用链上执行程序，直接调用两个池子的 swap 指令
+    let inline_swap = config
+        .inline_swap
+        .clone()
+        .filter(|inline_swap| inline_swap.enabled);
+    if let Some(inline_swap) = &inline_swap {
+        info!(
+            "Inline swaps enabled: minimum profit {}, balance check {}",
+            inline_swap.min_profit(),
+            if inline_swap.balance_check() {
+                "on"
+            } else {
+                "off"
+            }
+        );
+    }
+
+    // 模拟裁剪账户列表：直接兑换不使用 tick array 和 bin array 列表，不需要裁剪
+    let account_pruning = config
+        .account_pruning
+        .clone()
+        .filter(|account_pruning| account_pruning.enabled && inline_swap.is_none());
+    if let Some(account_pruning) = &account_pruning {
+        info!(
+            "Account pruning enabled: results cached for {}s",
+            account_pruning.ttl().as_secs()
+        );
+    }
+
+    // 跟单路线：订阅区块，记录其他机器人经过已配置池子的套利路线
+    let copy_routes = match &config.copy_route {
+        Some(copy_route) if copy_route.enabled => {
+            let book = Arc::new(CopyRouteBook::new(
+                copy_route.ttl(),
+                copy_route.max_routes(),
+            ));
+            tokio::spawn(copy_route::run(
+                book.clone(),
+                copy_route.websocket_url(&config.rpc.url),
+                wallet_kp.pubkey(),
+            ));
+            Some(book)
+        }
+        _ => None,
+    };
+
+    Ok(Services {
+        rpc_metrics,
+        opportunity_log,
+        batcher,
+        inline_swap,
+        account_pruning,
+        leader_filter,
+        inventory,
+        fee_governor,
+        route_fees,
+        vault_tracker,
+        copy_routes,
+        watchdog,
+        landing_model,
+        transaction_watch,
+    })
+}
+
+/// 注册一个代币的池子数据，启动它的池子维护任务和交易发送任务
+///
+/// # 参数
+/// * `state` - 共享的运行状态
+/// * `startup_rpc_client` - 启动阶段带缓存的客户端，用于加载查找表
+/// * `loaded` - 加载完成的池子数据
+/// * `tui` - 是否显示终端监控界面，显示时每轮重新报价
+///
+/// # 错误
+/// 查找表地址无法解析或创建再平衡任务失败时返回错误
+fn spawn_mint(
+    state: &Arc<AppState>,
+    startup_rpc_client: &RpcClient,
+    loaded: LoadedMint,
+    tui: bool,
+) -> anyhow::Result<()> {
+    let config = &state.config;
+    let services = &state.services;
+    let LoadedMint {
+        index: mint_index,
+        quote_mint,
+        pool_data,
+    } = loaded;
+    let mint_config = &config.routing.mint_config_list[mint_index];
+    state.status.update_pools(&pool_data);
+    if let Some(vault_tracker) = &services.vault_tracker {
+        vault_tracker.register(&pool_data);
+    }
+    if let Some(copy_routes) = &services.copy_routes {
+        copy_routes.register(&pool_data);
+    }
+    // 交易历史中记录的路由：参与套利的 DEX 列表
+    let mut route_dexes: Vec<&str> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .map(|(dex, _)| dex)
+        .collect();
+    route_dexes.dedup();
+    let route = route_dexes.join(",");
+    let lookup_tables = load_lookup_tables(state, mint_config, startup_rpc_client, &pool_data)?;
+    let mint = pool_data.mint;
+    let mint_pool_data = Arc::new(Mutex::new(pool_data));
+    state.insert_mint(mint, mint_pool_data.clone());
+
+    // TODO: Add logic to periodically refresh pool data
+
+    // 池子账户关闭后地址可能被其他程序复用，定期校验所有者，不一致的池子直接移除
+    if let Some(interval) = config.bot.pool_owner_check_interval() {
+        tokio::spawn(pool_owners::run(
+            mint_config.mint.to_string(),
+            mint_pool_data.clone(),
+            state.rpc_client.clone(),
+            interval,
+            state.notifier.clone(),
+            state.status.clone(),
+        ));
+    }
+
+    // 套利失败或部分成交后留在钱包里的代币定期卖回报价货币，dry-run 模式下不卖出
+    if let Some(rebalance) = config
+        .rebalance
+        .as_ref()
+        .filter(|rebalance| rebalance.enabled && !config.is_dry_run())
+    {
+        if mint != quote_mint.mint {
+            let rebalancer = Arc::new(Rebalancer::new(
+                state.rpc_client.clone(),
+                &state.wallet,
+                mint_pool_data.clone(),
+                rebalance,
+                &quote_mint,
+                config.compute_unit_price(),
+            )?);
+            tokio::spawn(rebalancer.run());
+        }
+    }
+
+    // 联合曲线毕业后流动性迁移到 AMM，自动加入新池子并移除曲线
+    if let Some(interval) = config.bot.graduation_check_interval() {
+        let watcher = GraduationWatcher {
+            mint: mint_config.mint.to_string(),
+            pool_data: mint_pool_data.clone(),
+            state: state.clone(),
+        };
+        tokio::spawn(watcher.run(interval));
+    }
+
+    // 各代币的轮询错开，避免同一时刻集中请求 RPC 和发送
+    let schedule = ProcessSchedule::for_mint(
+        mint_config.process_delay,
+        mint_config.process_delay_jitter(),
+        mint_config.phase_offset,
+        mint_index,
+        config.routing.mint_config_list.len(),
+        config.bot.stagger_mints(),
+    );
+    info!("Mint {} schedule: {}", mint_config.mint, schedule);
+
+    let trade_sizing = mint_config.trade_sizing();
+    let research_sizing = config
+        .research
+        .as_ref()
+        .filter(|research| research.enabled)
+        .map(|research| research.trade_sizing(mint_config));
+    // 期望收益按以 SOL 计的报价收益计算，没有配置交易规模或以其他货币报价的代币不经过门槛
+    let ev_gate = services
+        .landing_model
+        .clone()
+        .filter(|_| trade_sizing.is_some() && quote_mint.is_sol());
+    if services.landing_model.is_some() && ev_gate.is_none() {
+        warn!(
+            "   EV gate does not apply to mint {}: it needs trade_sizes or max_trade_size and SOL quotes",
+            mint_config.mint
+        );
+    }
+    // 配置了 top_pools 或交易规模、研究模式、跟单路线或终端界面时每轮重新报价
+    let quotes_each_round = mint_config.top_pools.is_some()
+        || trade_sizing.is_some()
+        || research_sizing.is_some()
+        || services.copy_routes.is_some()
+        || tui;
+    // 只有每轮报价的代币保存会过期的池子数据
+    if let Some(watchdog) = services.watchdog.as_ref().filter(|_| quotes_each_round) {
+        watchdog.watch_mint(mint, Instant::now());
+    }
+    // 连续失败退避，超时未上链也算失败
+    let cooldown = config.bot.cooldown_after_failures.map(|threshold| {
+        Arc::new(FailureCooldown::new(
+            threshold,
+            schedule.delay(),
+            config.bot.max_cooldown(),
+        ))
+    });
+    // 未确认交易上限，同一代币的交易共用输入资金，同时在途的多笔交易最多只有一笔能成功
+    let in_flight = match config.bot.in_flight_limit() {
+        Some(limit) if !config.is_dry_run() => Some(Arc::new(InFlight::new(limit))),
+        _ => None,
+    };
+    // 仪表盘、终端界面、交易历史或导出、上链或亏损告警、优先费预算、失败退避、在途交易上限或 Jito 区域权重
+    // 需要时才查询交易结果，避免额外的 RPC 请求
+    let track_landing = config.dashboard.as_ref().is_some_and(|d| d.enabled)
+        || tui
+        || state.trade_sinks.is_enabled()
+        || state.notifier.is_enabled(AlertKind::Landed)
+        || state.notifier.is_enabled(AlertKind::ProfitAudit)
+        || services.fee_governor.is_some()
+        || services.route_fees.is_some()
+        || cooldown.is_some()
+        || in_flight.is_some()
+        || ev_gate.is_some()
+        || state.jito_sender.is_some();
+
+    let landing_tracker = LandingTracker {
+        state: state.clone(),
+        mint: mint_config.mint.to_string(),
+        quote_mint,
+        cooldown: cooldown.clone(),
+        in_flight: in_flight.clone(),
+        landing_model: ev_gate.clone(),
+    };
+    let sender = MintSender {
+        state: state.clone(),
+        // 记录和告警使用的代币地址字符串，只在任务开始时编码一次
+        mint_label: mint_config.mint.to_string(),
+        top_pools: mint_config.top_pools,
+        quote_mint,
+        pool_data: mint_pool_data,
+        route,
+        lookup_tables: Arc::new(lookup_tables),
+        schedule,
+        trade_sizing,
+        research_sizing,
+        quotes_each_round,
+        ev_gate,
+        cooldown,
+        in_flight,
+        track_landing,
+        landing_tracker,
+        failure_threshold: config
+            .notifications
+            .as_ref()
+            .map_or(5, |notifications| notifications.failure_threshold()),
+        send_endpoints: state.send_endpoints(),
+        // 每个代币的发送任务各自缓存裁剪结果
+        pruned_routes: services
+            .account_pruning
+            .as_ref()
+            .map(|account_pruning| PrunedRoutes::new(account_pruning.ttl())),
+        templates: TransactionTemplates::new(),
+        broadcasts: BroadcastLog::new(),
+        consecutive_failures: 0,
+        spread_since: None,
+    };
+
+    // 启动交易发送任务
+    tokio::spawn(sender.run());
+    Ok(())
+}
+
+/// 加载代币使用的地址查找表，并检查它们是否覆盖交易中的全部账户
+///
+/// 代币自己的查找表之后加入所有代币共用的查找表，钱包自己的查找表没有列出时也加载。
+/// 没有被覆盖的账户在非 dry-run 模式下加入钱包自己的查找表。
+///
+/// # 参数
+/// * `state` - 共享的运行状态，提供钱包、执行程序和扩展查找表使用的 RPC 客户端
+/// * `mint_config` - 代币配置
+/// * `startup_rpc_client` - 启动阶段带缓存的客户端
+/// * `pool_data` - 代币的池子数据，用于检查覆盖情况
+///
+/// # 返回值
+/// 成功加载的查找表，加载失败的查找表记录错误后跳过
+///
+/// # 错误
+/// 查找表地址不是合法的 Pubkey 时返回错误，`Config::validate` 已经检查过
+fn load_lookup_tables(
+    state: &AppState,
+    mint_config: &MintConfig,
+    startup_rpc_client: &RpcClient,
+    pool_data: &MintPoolData,
+) -> anyhow::Result<Vec<AddressLookupTableAccount>> {
+    // 获取查找表账户列表，如果不存在则使用默认空列表
+    let mut lookup_table_accounts = mint_config.lookup_tables()?;
+    // 加入所有代币共用的查找表，跳过已经列出的
+    for table in state.config.routing.global_lookup_tables()? {
+        if !lookup_table_accounts.contains(&table) {
+            lookup_table_accounts.push(table);
+        }
+    }
+
+    let mut lookup_table_accounts_list = vec![];
+
+    // 加载地址查找表（Address Lookup Tables）用于交易优化
+    // 处理查找表账户列表，加载并验证每个查找表账户
+    //
+    // 该函数遍历启动时解析的查找表地址列表，对每个地址进行以下操作：
+    // 1. 从RPC客户端获取对应的账户数据
+    // 2. 反序列化账户数据为地址查找表
+    // 3. 将有效的查找表添加到结果列表中
+    //
+    // 错误处理:
+    // - 获取账户失败：记录错误日志并跳过该查找表
+    // - 反序列化失败：记录错误日志并跳过该查找表
+    // - 所有错误都不会中断整个处理流程，而是继续处理下一个查找表
+    for pubkey in lookup_table_accounts {
+        // 使用公钥从RPC客户端获取账户数据
+        match startup_rpc_client.get_account(&pubkey) {
+            Ok(account) => {
+                // 尝试将账户数据反序列化为地址查找表
+                match AddressLookupTable::deserialize(&account.data) {
+                    Ok(lookup_table) => {
+                        // 成功反序列化后，创建查找表账户对象并添加到结果列表
+                        let lookup_table_account = AddressLookupTableAccount {
+                            key: pubkey,
+                            addresses: lookup_table.addresses.into_owned(),
+                        };
+                        lookup_table_accounts_list.push(lookup_table_account);
+                        info!("   Successfully loaded lookup table: {}", pubkey);
+                    }
+                    Err(e) => {
+                        error!("   Failed to deserialize lookup table {}: {}", pubkey, e);
+                        continue; // Skip this lookup table but continue processing others
                     }
                 }
             }
+            Err(e) => {
+                error!("   Failed to fetch lookup table account {}: {}", pubkey, e);
+                continue; // Skip this lookup table but continue processing others
+            }
         }
     }
 
-    // 为每个代币配置初始化池数据并启动交易发送任务->这个只运行一次
-    for mint_config in &config.routing.mint_config_list {
-        info!("Processing mint: {}", mint_config.mint);
+    // 钱包自己的查找表没有列在 lookup_table_accounts 中时也加载，交易可以使用其中的地址
+    let own_lookup_table = mint_config.own_lookup_table_pubkey()?;
+    if let Some(own_lookup_table) = own_lookup_table {
+        if !lookup_table_accounts_list
+            .iter()
+            .any(|table| table.key == own_lookup_table)
+        {
+            match fetch_lookup_table(startup_rpc_client, &own_lookup_table) {
+                Ok(table) => lookup_table_accounts_list.push(table),
+                Err(e) => error!(
+                    "   Failed to load own lookup table {}: {}",
+                    own_lookup_table, e
+                ),
+            }
+        }
+    }
 
-        let pool_data = initialize_pool_data(
-            &mint_config.mint,
-            &wallet_kp.pubkey().to_string(),
-            mint_config.raydium_pool_list.as_ref(),
-            mint_config.raydium_cp_pool_list.as_ref(),
-            mint_config.pump_pool_list.as_ref(),
-            mint_config.meteora_dlmm_pool_list.as_ref(),
-            mint_config.whirlpool_pool_list.as_ref(),
-            mint_config.raydium_clmm_pool_list.as_ref(),
-            mint_config.meteora_damm_pool_list.as_ref(),
-            mint_config.solfi_pool_list.as_ref(),
-            mint_config.meteora_damm_v2_pool_list.as_ref(),
-            mint_config.vertigo_pool_list.as_ref(),
-            rpc_client.clone(),
-        )
-        .await?;
+    if lookup_table_accounts_list.is_empty() {
+        warn!("   Warning: No valid lookup tables were loaded");
+    } else {
+        info!(
+            "   Loaded {} lookup tables successfully",
+            lookup_table_accounts_list.len()
+        );
+    }
+
+    // 检查查找表覆盖情况：没有被覆盖的账户每个让交易多 32 字节，池子多时交易会超出大小上限
+    match uncovered_accounts(
+        &state.wallet,
+        &state.config,
+        &state.programs,
+        pool_data,
+        &lookup_table_accounts_list,
+    ) {
+        Ok(uncovered) if uncovered.is_empty() => {
+            info!("   Lookup tables cover every account in the transaction")
+        }
+        Ok(uncovered) => {
+            warn!(
+                "   {} accounts are not covered by the lookup tables ({} extra bytes per transaction):",
+                uncovered.len(),
+                uncovered.len() * 32
+            );
+            for pubkey in &uncovered {
+                warn!("      {}", pubkey);
+            }
 
-        let mint_pool_data = Arc::new(Mutex::new(pool_data));
-
-        // TODO: Add logic to periodically refresh pool data
-
-        // 克隆配置以在线程中使用
-        let config_clone = config.clone();
-        // 克隆当前代币配置以在线程中使用
-        let mint_config_clone = mint_config.clone();
-        // 克隆RPC客户端列表以在线程中使用
-        let sending_rpc_clients_clone = sending_rpc_clients.clone();
-        // 克隆区块哈希缓存以在线程中使用
-        let cached_blockhash_clone = cached_blockhash.clone();
-        // 获取钱包密钥对的字节表示，以便后续克隆
-        let wallet_bytes = wallet_kp.to_bytes();
-        // 从字节数据重新创建钱包密钥对以在线程中使用
-        let wallet_kp_clone = Keypair::from_bytes(&wallet_bytes).unwrap();
-        // 获取查找表账户列表，如果不存在则使用默认空列表
-        let mut lookup_table_accounts = mint_config_clone.lookup_table_accounts.unwrap_or_default();
-        // 添加默认的查找表账户地址到列表中
-        lookup_table_accounts.push("4sKLJ1Qoudh8PJyqBeuKocYdsZvxTcRShUt9aKqwhgvC".to_string());
-
-        let mut lookup_table_accounts_list = vec![];
-
-        // 加载地址查找表（Address Lookup Tables）用于交易优化
-        // 处理查找表账户列表，加载并验证每个查找表账户
-        //
-        // 该函数遍历提供的查找表账户地址字符串列表，对每个地址进行以下操作：
-        // 1. 将字符串解析为公钥(Pubkey)
-        // 2. 从RPC客户端获取对应的账户数据
-        // 3. 反序列化账户数据为地址查找表
-        // 4. 将有效的查找表添加到结果列表中
-        //
-        // 参数:
-        // * `lookup_table_accounts`: 包含查找表账户地址字符串的迭代器
-        // * `rpc_client`: 用于获取账户数据的RPC客户端引用
-        // * `lookup_table_accounts_list`: 用于存储成功加载的查找表账户的可变引用向量
-        //
-        // 返回值:
-        // 无返回值，通过修改传入的lookup_table_accounts_list参数返回结果
-        //
-        // 错误处理:
-        // - 无效的公钥字符串：记录错误日志并跳过该查找表
-        // - 获取账户失败：记录错误日志并跳过该查找表
-        // - 反序列化失败：记录错误日志并跳过该查找表
-        // - 所有错误都不会中断整个处理流程，而是继续处理下一个查找表
-        for lookup_table_account in lookup_table_accounts {
-            // 尝试将查找表账户字符串解析为公钥
-            match Pubkey::from_str(&lookup_table_account) {
-                Ok(pubkey) => {
-                    // 使用公钥从RPC客户端获取账户数据
-                    match rpc_client.get_account(&pubkey) {
-                        Ok(account) => {
-                            // 尝试将账户数据反序列化为地址查找表
-                            match AddressLookupTable::deserialize(&account.data) {
-                                Ok(lookup_table) => {
-                                    // 成功反序列化后，创建查找表账户对象并添加到结果列表
-                                    let lookup_table_account = AddressLookupTableAccount {
-                                        key: pubkey,
-                                        addresses: lookup_table.addresses.into_owned(),
-                                    };
-                                    lookup_table_accounts_list.push(lookup_table_account);
-                                    info!("   Successfully loaded lookup table: {}", pubkey);
-                                }
-                                Err(e) => {
-                                    error!(
-                                        "   Failed to deserialize lookup table {}: {}",
-                                        pubkey, e
-                                    );
-                                    continue; // Skip this lookup table but continue processing others
-                                }
+            let own_table_index = own_lookup_table.and_then(|own_lookup_table| {
+                lookup_table_accounts_list
+                    .iter()
+                    .position(|table| table.key == own_lookup_table)
+            });
+            if let Some(index) = own_table_index {
+                let own_lookup_table = lookup_table_accounts_list[index].key;
+                if state.config.is_dry_run() {
+                    warn!(
+                        "   Not extending lookup table {} in dry-run mode",
+                        own_lookup_table
+                    );
+                } else {
+                    match extend_own_lookup_table(
+                        &state.rpc_client,
+                        &state.wallet,
+                        &lookup_table_accounts_list[index],
+                        &uncovered,
+                    ) {
+                        Ok(added) => {
+                            if added < uncovered.len() {
+                                warn!(
+                                    "   Lookup table {} is full, {} accounts are still not covered",
+                                    own_lookup_table,
+                                    uncovered.len() - added
+                                );
+                            }
+                            match fetch_lookup_table(&state.rpc_client, &own_lookup_table) {
+                                Ok(table) => lookup_table_accounts_list[index] = table,
+                                Err(e) => error!(
+                                    "   Failed to reload lookup table {}: {}",
+                                    own_lookup_table, e
+                                ),
                             }
                         }
-                        Err(e) => {
-                            error!("   Failed to fetch lookup table account {}: {}", pubkey, e);
-                            continue; // Skip this lookup table but continue processing others
+                        Err(e) => error!(
+                            "   Failed to extend lookup table {}: {}",
+                            own_lookup_table, e
+                        ),
+                    }
+                }
+            }
+        }
+        Err(e) => warn!("   Failed to check lookup table coverage: {}", e),
+    }
+
+    Ok(lookup_table_accounts_list)
+}
+
+/// 一个代币的交易发送任务：每轮报价、做发送前的检查，然后构建并发送交易
+struct MintSender {
+    state: Arc<AppState>,
+    /// 记录和告警使用的代币地址字符串
+    mint_label: String,
+    /// 每轮只把价差最大的前 N 个池子传给链上程序
+    top_pools: Option<usize>,
+    quote_mint: CashMint,
+    /// 和池子维护任务共用的池子数据
+    pool_data: Arc<Mutex<MintPoolData>>,
+    /// 交易历史中记录的路由：参与套利的 DEX 列表
+    route: String,
+    lookup_tables: Arc<Vec<AddressLookupTableAccount>>,
+    schedule: ProcessSchedule,
+    trade_sizing: Option<TradeSizing>,
+    /// 研究模式下记录机会使用的交易规模
+    research_sizing: Option<TradeSizing>,
+    /// 是否每轮重新报价
+    quotes_each_round: bool,
+    /// 期望收益门槛的上链率模型，门槛不适用于该代币时为 `None`
+    ev_gate: Option<Arc<LandingModel>>,
+    cooldown: Option<Arc<FailureCooldown>>,
+    in_flight: Option<Arc<InFlight>>,
+    /// 是否查询已发送交易的结果
+    track_landing: bool,
+    landing_tracker: LandingTracker,
+    /// 连续失败多少次后推送告警
+    failure_threshold: u32,
+    /// 交易发送到的端点，记录在单位经济日志中
+    send_endpoints: Vec<String>,
+    pruned_routes: Option<PrunedRoutes>,
+    /// 按路线缓存的交易模板，每轮只替换 blockhash 和计算单元价格
+    templates: TransactionTemplates,
+    /// 当前 blockhash 下签名和广播过的交易，相同的消息复用签名，重发不重复记录结果
+    broadcasts: BroadcastLog,
+    consecutive_failures: u32,
+    /// 当前这条有收益的价差第一次被报价发现的时间
+    spread_since: Option<Instant>,
+}
+
+impl MintSender {
+    /// 按代币的相位偏移错开启动，然后一直按轮询间隔报价和发送
+    async fn run(mut self) {
+        tokio::time::sleep(self.schedule.phase_offset()).await;
+        loop {
+            let delay = self.round().await;
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// 执行一轮报价和发送
+    ///
+    /// # 返回值
+    /// 到下一轮之前等待的时间
+    async fn round(&mut self) -> Duration {
+        let state = &self.state;
+        let services = &state.services;
+        let quote_mint = self.quote_mint;
+
+        // 即将到来的 leader 都不在可用列表中时跳过本轮，避免白白支付优先费
+        if let Some(leader_filter) = &services.leader_filter {
+            if !leader_filter.should_send() {
+                debug!(
+                    "Skipping mint {}: no good leader in the upcoming slots",
+                    self.mint_label
+                );
+                return self.schedule.next_delay();
+            }
+        }
+
+        // 之前发送的交易还没有结果时跳过本轮，避免和它争抢同一笔输入资金
+        if let Some(in_flight) = &self.in_flight {
+            if in_flight.is_full() {
+                debug!(
+                    "Skipping mint {}: {} transaction(s) still unconfirmed",
+                    self.mint_label,
+                    in_flight.count()
+                );
+                return self.schedule.next_delay();
+            }
+        }
+
+        let (latest_blockhash, context_slot) = state.latest_blockhash().await;
+
+        let mint_pool_data = self.pool_data.clone();
+        let mut guard = mint_pool_data.lock().await;
+
+        // 自适应手续费随波动变化，报价前换成最新读取到的费率
+        if self.quotes_each_round {
+            let live_fees = match &services.vault_tracker {
+                Some(vault_tracker) => vault_tracker.pool_fees(&guard),
+                None => quote::fetch_live_fees(&state.rpc_client, &guard).unwrap_or_else(|e| {
+                    warn!(
+                        "Failed to read live pool fees for mint {}: {}",
+                        self.mint_label, e
+                    );
+                    HashMap::new()
+                }),
+            };
+            guard.pool_fees.extend(live_fees);
+        }
+
+        let reserves = if self.quotes_each_round {
+            if let Some(vault_tracker) = &services.vault_tracker {
+                // 跟踪 vault 余额时直接使用缓存，不再单独请求
+                Some(vault_tracker.reserves(&guard))
+            } else {
+                match quote::fetch_reserves(&state.rpc_client, &guard) {
+                    Ok(reserves) => {
+                        if let Some(watchdog) = &services.watchdog {
+                            watchdog.record_pool_update(&guard.mint, Instant::now());
                         }
+                        Some(reserves)
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to quote pools for mint {}, using all pools: {}",
+                            self.mint_label, e
+                        );
+                        None
                     }
                 }
-                Err(e) => {
+            }
+        } else {
+            None
+        };
+
+        if let Some(reserves) = &reserves {
+            state
+                .status
+                .update_prices(&guard, &quote::spot_prices(reserves));
+        }
+
+        // 池子数据、blockhash 或 slot 过期时暂停发送，报价照常进行以便恢复
+        if let Some(reason) = services
+            .watchdog
+            .as_ref()
+            .and_then(|watchdog| watchdog.pause_reason(&guard.mint))
+        {
+            debug!("Skipping mint {}: {}", self.mint_label, reason);
+            return self.schedule.next_delay();
+        }
+
+        if let (Some(exporter), Some(reserves)) = (&state.trade_sinks.exporter, &reserves) {
+            if let Err(e) =
+                exporter.record_quotes(&self.mint_label, &guard.pool_addresses(), reserves)
+            {
+                error!(
+                    "Failed to export quotes for mint {}: {}",
+                    self.mint_label, e
+                );
+            }
+        }
+
+        // 研究模式：记录本轮有收益的路线后进入下一轮，不构建交易
+        if let (Some(opportunity_log), Some(sizing)) =
+            (&services.opportunity_log, &self.research_sizing)
+        {
+            if let Some(reserves) = &reserves {
+                let routes = quote::profitable_routes(&guard, reserves, sizing);
+                if let Err(e) = opportunity_log.observe(
+                    &guard,
+                    &routes,
+                    &quote::spot_prices(reserves),
+                    unix_now_ms(),
+                ) {
                     error!(
-                        "   Invalid lookup table pubkey string {}: {}",
-                        lookup_table_account, e
+                        "Failed to record opportunities for mint {}: {}",
+                        self.mint_label, e
                     );
-                    continue; // Skip this lookup table but continue processing others
                 }
             }
+            return self.schedule.next_delay();
         }
 
-        if lookup_table_accounts_list.is_empty() {
-            warn!("   Warning: No valid lookup tables were loaded");
-        } else {
-            info!(
-                "   Loaded {} lookup tables successfully",
-                lookup_table_accounts_list.len()
-            );
+        // 其他机器人最近走过的路线，和自己的报价一起比较
+        let copied = services
+            .copy_routes
+            .as_ref()
+            .map(|copy_routes| copy_routes.routes(&guard.mint))
+            .unwrap_or_default();
+
+        // 只把价差最大的几个池子传给链上程序，减少 CU 消耗；跟单路线经过的池子也保留
+        let ranked;
+        let pool_data = match (self.top_pools, &reserves) {
+            (Some(top_n), Some(reserves)) => {
+                let prices = quote::spot_prices(reserves);
+                let selected = quote::select_top_pools(&guard, &prices, top_n);
+                ranked = if copied.is_empty() {
+                    selected
+                } else {
+                    copy_route::include_routes(&guard, &selected, &copied)
+                };
+                debug!(
+                    "Selected {} pools for mint {}, best spread {:?}",
+                    ranked.pool_addresses().len(),
+                    self.mint_label,
+                    quote::best_spread(&guard, &prices)
+                );
+                &ranked
+            }
+            _ => &*guard,
+        };
+
+        // 按报价（包括跟单路线）没有赚钱的交易规模时跳过本轮，省下交易手续费
+        let mut expected_profit = None;
+        let mut best_quote = None;
+        if let (Some(sizing), Some(reserves)) = (&self.trade_sizing, &reserves) {
+            // 直接兑换时只能在支持的池子之间选择路线
+            let inline_reserves;
+            let reserves = if services.inline_swap.is_some() {
+                inline_reserves = crate::inline_swap::supported_reserves(pool_data, reserves);
+                &inline_reserves
+            } else {
+                reserves
+            };
+            let best = quote::best_route(pool_data, reserves, sizing)
+                .into_iter()
+                .chain(copy_route::quote_routes(pool_data, reserves, &copied))
+                .max_by_key(|quote| quote.profit);
+            match best {
+                Some(best) if best.profit > 0 => {
+                    expected_profit = Some(best.profit);
+                    best_quote = Some(best);
+                    self.spread_since.get_or_insert_with(Instant::now);
+                    debug!(
+                        "Best size for mint {}: {} {}, expected profit {} {}",
+                        self.mint_label,
+                        quote_mint.from_base_units(best.size as i64),
+                        quote_mint.symbol,
+                        quote_mint.from_base_units(best.profit),
+                        quote_mint.symbol
+                    );
+                    // 钱包余额不够这个规模时，从借贷市场取回差额（借贷市场只存放 WSOL）
+                    if let Some(inventory) =
+                        services.inventory.as_ref().filter(|_| quote_mint.is_sol())
+                    {
+                        if let Err(e) = inventory.ensure_available(best.size) {
+                            warn!("Failed to withdraw inventory: {}", e);
+                        }
+                    }
+                }
+                best => {
+                    self.spread_since = None;
+                    debug!(
+                        "Skipping mint {}: no profitable trade size ({:?})",
+                        self.mint_label, best
+                    );
+                    return self.schedule.next_delay();
+                }
+            }
         }
 
-        // 启动交易发送任务
-        tokio::spawn(async move {
-            let process_delay = Duration::from_millis(mint_config_clone.process_delay);
+        // 按路线的上链率调整计算单元价格，没有启用时使用配置的价格
+        let route_key = services
+            .route_fees
+            .as_ref()
+            .map(|_| RouteFees::route_key(&guard.mint, best_quote.as_ref()));
+        let route_price = match (&services.route_fees, &route_key) {
+            (Some(route_fees), Some(route_key)) => route_fees.price(route_key),
+            _ => state.config.compute_unit_price(),
+        };
 
-            loop {
-                let latest_blockhash = {
-                    let guard = cached_blockhash_clone.lock().await;
-                    *guard
-                };
+        // 优先费预算快用完时降低计算单元价格，用完时跳过本轮
+        let compute_unit_price = match &services.fee_governor {
+            Some(fee_governor) => match fee_governor.decide(route_price) {
+                FeeDecision::Throttle => {
+                    debug!(
+                        "Skipping mint {}: hourly priority fee budget is exhausted",
+                        self.mint_label
+                    );
+                    return self.schedule.next_delay();
+                }
+                FeeDecision::Send { compute_unit_price } => {
+                    state.set_priority_fee(compute_unit_price);
+                    compute_unit_price
+                }
+            },
+            None => route_price,
+        };
+        let priced_config;
+        let send_config = if compute_unit_price == state.config.compute_unit_price() {
+            &state.config
+        } else {
+            priced_config = state.config.with_compute_unit_price(compute_unit_price);
+            &priced_config
+        };
+
+        // 期望收益不为正时跳过本轮，上链概率按发送价格和价差持续时间从历史结果估计
+        let landing_bucket = match (&self.ev_gate, expected_profit, self.spread_since) {
+            (Some(landing_model), Some(profit), Some(since)) => {
+                let compute_unit_price = send_config.compute_unit_price();
+                let bucket = LandingBucket::new(compute_unit_price, since.elapsed());
+                let probability = landing_model.landing_probability(&bucket);
+                let fee = transaction_fee(compute_unit_price, send_config.bot.compute_unit_limit);
+                // 通过 Jito 发送时按报价收益估计要付的小费
+                let tip = state
+                    .jito_sender
+                    .as_ref()
+                    .map_or(0, |jito| jito.tip_for_profit(Some(profit)));
+                let ev = expected_value(profit, probability, fee, tip);
+                if ev <= 0.0 {
+                    debug!(
+                        "Skipping mint {}: expected value {:.0} lamports (profit {}, P(land) {:.3}, fee {}, tip {})",
+                        self.mint_label, ev, profit, probability, fee, tip
+                    );
+                    return self.schedule.next_delay();
+                }
+                Some(bucket)
+            }
+            _ => None,
+        };
 
-                let guard = mint_pool_data.lock().await;
+        // 每次发送输出一条单位经济记录，包含路线、规模、预期收益、计算单元和发送端点
+        AttemptRecord {
+            mint: self.mint_label.clone(),
+            route: AttemptRecord::route(pool_data, best_quote.as_ref()),
+            input_size: best_quote.map(|quote| quote.size),
+            expected_profit,
+            compute_unit_price: send_config.compute_unit_price(),
+            compute_unit_limit: send_config.bot.compute_unit_limit,
+            blockhash_age_ms: state.blockhash_age().as_millis() as u64,
+            endpoints: self.send_endpoints.clone(),
+            dry_run: send_config.is_dry_run(),
+        }
+        .log();
 
-                match build_and_send_transaction(
-                    &wallet_kp_clone,
-                    &config_clone,
-                    &*guard, // Dereference the guard here
-                    &sending_rpc_clients_clone,
+        // 去掉模拟时没有用到的 tick array 和 bin array，路线第一次发送前模拟，之后使用缓存
+        let pruned;
+        let pool_data = match &mut self.pruned_routes {
+            Some(pruned_routes) => {
+                pruned = pruned_routes.prune(
+                    state,
+                    send_config,
+                    pool_data,
                     latest_blockhash,
-                    &lookup_table_accounts_list,
+                    &self.lookup_tables,
+                );
+                &pruned
+            }
+            None => pool_data,
+        };
+
+        let result = match (&services.batcher, &services.inline_swap) {
+            (_, Some(inline_swap)) => {
+                let tx = inline_route(
+                    &state.rpc_client,
+                    inline_swap,
+                    pool_data,
+                    best_quote,
+                    reserves.as_ref(),
                 )
-                .await
-                {
-                    Ok(signatures) => {
+                .and_then(|route| {
+                    build_inline_transaction(
+                        &state.wallet,
+                        send_config,
+                        pool_data,
+                        &route,
+                        latest_blockhash,
+                        &self.lookup_tables,
+                    )
+                });
+                match tx {
+                    // 直接兑换的交易依赖查找表，不构建 legacy 版本
+                    Ok(tx) => {
+                        state
+                            .submitters
+                            .send(&Submission::new(&tx, pool_data, context_slot))
+                            .await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            (Some(batcher), None) => {
+                batcher
+                    .submit(
+                        pool_data.clone(),
+                        send_config.compute_unit_price(),
+                        self.lookup_tables.clone(),
+                    )
+                    .await
+            }
+            (None, None) => {
+                let tx = self
+                    .templates
+                    .build_message(
+                        &state.wallet,
+                        send_config,
+                        &state.programs,
+                        pool_data,
+                        latest_blockhash,
+                        &self.lookup_tables,
+                    )
+                    .and_then(|message| self.broadcasts.sign(&state.wallet, message));
+                match tx {
+                    Ok(tx) => {
+                        let legacy_tx = legacy_transaction_for(
+                            &state.wallet,
+                            send_config,
+                            &state.programs,
+                            pool_data,
+                            &state.submitters,
+                            latest_blockhash,
+                        );
+                        state
+                            .submitters
+                            .send(
+                                &Submission::new(&tx, pool_data, context_slot)
+                                    .with_legacy_tx(legacy_tx.as_ref()),
+                            )
+                            .await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+        match result {
+            Ok(outcome) if state.config.is_dry_run() => {
+                self.consecutive_failures = 0;
+                if let Some(cooldown) = &self.cooldown {
+                    cooldown.record_success();
+                }
+                record_attempt(
+                    state,
+                    &self.mint_label,
+                    &self.route,
+                    None,
+                    outcome.simulated_profit,
+                    TransactionOutcome::Simulated,
+                );
+                info!("Dry-run iteration finished for mint {}", self.mint_label);
+            }
+            Ok(outcome) => {
+                // 合并发送时整笔交易的收益和手续费只记在第一个代币上，避免重复统计
+                let owns_profit = outcome
+                    .batch_mints
+                    .first()
+                    .is_none_or(|first| *first == guard.mint);
+                // 合并发送时余额变化包含其他代币的收益，只核对是否亏损
+                let audited_profit = expected_profit.filter(|_| outcome.batch_mints.len() <= 1);
+                let signatures = outcome.signatures;
+                self.consecutive_failures = 0;
+                info!(
+                    "Transactions sent successfully for mint {}",
+                    self.mint_label
+                );
+                if outcome.batch_mints.len() > 1 {
+                    info!(
+                        "  Batched with {} other mint(s)",
+                        outcome.batch_mints.len() - 1
+                    );
+                }
+                for signature in &signatures {
+                    info!("  Signature: {}", signature);
+                }
+
+                // 同一笔交易经多个 RPC 发送时签名相同，只跟踪一次
+                match signatures.first().copied() {
+                    // 当前 blockhash 下已经广播过的同一笔交易，结果已经在跟踪
+                    Some(signature) if !self.broadcasts.record(latest_blockhash, signature) => {
                         info!(
-                            "Transactions sent successfully for mint {}",
-                            mint_config_clone.mint
+                            "Resent transaction {} for mint {}, its outcome is already tracked",
+                            signature, self.mint_label
                         );
-                        for signature in signatures {
-                            info!("  Signature: {}", signature);
+                    }
+                    Some(signature) => {
+                        record_attempt(
+                            state,
+                            &self.mint_label,
+                            &self.route,
+                            Some(signature),
+                            None,
+                            TransactionOutcome::Sent,
+                        );
+                        if let Some(tip) = outcome.tip {
+                            state.trade_sinks.record_tip(&signature, tip);
+                        }
+                        if let Some(in_flight) = &self.in_flight {
+                            in_flight.insert(signature);
+                        }
+                        if self.track_landing {
+                            tokio::spawn(self.landing_tracker.clone().track(
+                                signature,
+                                owns_profit,
+                                landing_bucket,
+                                route_key,
+                                outcome.tip,
+                                audited_profit,
+                            ));
                         }
                     }
-                    Err(e) => {
-                        error!(
-                            "Error sending transaction for mint {}: {}",
-                            mint_config_clone.mint, e
+                    None => {
+                        record_attempt(
+                            state,
+                            &self.mint_label,
+                            &self.route,
+                            None,
+                            None,
+                            TransactionOutcome::SendFailed {
+                                error: "all senders failed".to_string(),
+                            },
                         );
+                        if let Some(cooldown) = &self.cooldown {
+                            record_cooldown_failure(cooldown, &state.notifier, &self.mint_label);
+                        }
                     }
                 }
+            }
+            Err(e) => {
+                self.consecutive_failures += 1;
+                error!(
+                    "Error sending transaction for mint {}: {}",
+                    self.mint_label, e
+                );
+                record_attempt(
+                    state,
+                    &self.mint_label,
+                    &self.route,
+                    None,
+                    None,
+                    TransactionOutcome::SendFailed {
+                        error: e.to_string(),
+                    },
+                );
+                if self.consecutive_failures == self.failure_threshold {
+                    state.notifier.notify(Alert::RepeatedFailures {
+                        mint: self.mint_label.clone(),
+                        count: self.consecutive_failures,
+                        last_error: e.to_string(),
+                    });
+                }
+                if let Some(cooldown) = &self.cooldown {
+                    record_cooldown_failure(cooldown, &state.notifier, &self.mint_label);
+                }
+            }
+        }
 
-                tokio::time::sleep(process_delay).await;
+        // 连续失败时按退避时间等待，避免以 process_delay 的频率反复发送同一条失败的路线
+        match self
+            .cooldown
+            .as_ref()
+            .map(|cooldown| cooldown.delay())
+            .filter(|delay| *delay > self.schedule.delay())
+        {
+            Some(delay) => {
+                debug!(
+                    "Backing off mint {} for {:?} after {} consecutive failures",
+                    self.mint_label,
+                    delay,
+                    self.cooldown
+                        .as_ref()
+                        .map_or(0, |cooldown| cooldown.failures())
+                );
+                delay
             }
-        });
+            None => self.schedule.next_delay(),
+        }
     }
+}
 
-    // 主线程保持运行，防止程序退出
-    loop {
-        tokio::time::sleep(Duration::from_secs(1)).await;
+/// 运行到收到 Ctrl-C 或关闭终端界面为止，然后保存上链率模型和研究记录并推送停止消息
+///
+/// # 错误
+/// 监听 Ctrl-C 失败或终端界面出错时返回错误
+async fn wait_for_shutdown(state: &AppState, tui: bool) -> anyhow::Result<()> {
+    // 主线程保持运行，直到收到 Ctrl-C 或关闭终端界面
+    let reason = if tui {
+        info!("Starting terminal monitor");
+        let status = state.status.clone();
+        let monitor = tokio::task::spawn_blocking(move || crate::tui::run(status));
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => {
+                result?;
+                "received Ctrl-C"
+            }
+            result = monitor => {
+                result??;
+                "terminal monitor closed"
+            }
+        }
+    } else {
+        tokio::signal::ctrl_c().await?;
+        "received Ctrl-C"
+    };
+    info!("Shutting down: {}", reason);
+    if let (Some(landing_model), Some(ev_gate)) =
+        (&state.services.landing_model, &state.config.ev_gate)
+    {
+        if let Err(e) = landing_model.save(ev_gate.model_path()) {
+            error!("Failed to save landing model: {}", e);
+        }
+    }
+    if let Some(opportunity_log) = &state.services.opportunity_log {
+        match opportunity_log.flush_open() {
+            Ok(count) => info!("Recorded {} opportunities still visible at shutdown", count),
+            Err(e) => error!("Failed to record open opportunities: {}", e),
+        }
+    }
+    state
+        .notifier
+        .notify_and_wait(Alert::Stopped {
+            reason: reason.to_string(),
+        })
+        .await;
+
+    Ok(())
+}
+
+/// `--dump-state`：只读取链上状态，加载所有池子并把快照写入 `dir`
+///
+/// 与正常启动使用相同的池子加载流程，但不检查执行程序、不创建代币账户、不发送任何交易，
+/// 也不启动后台任务，池子缓存只读不写。
+///
+/// # 参数
+/// * `config` - 已解析并校验的配置
+/// * `use_pool_cache` - 是否读取池子元数据缓存
+/// * `dir` - 快照目录
+///
+/// # 错误
+/// 读取钱包、池子或账户失败，或写入快照失败时返回错误
+async fn dump_pool_state(config: &Config, use_pool_cache: bool, dir: &Path) -> anyhow::Result<()> {
+    let rpc_client = Arc::new(build_rpc_client(
+        &config.rpc.url,
+        config.rpc.rate_limit.as_ref(),
+        None,
+        config.rpc.proxy.as_ref(),
+    )?);
+    let startup = startup_clients(config, &rpc_client, None)?;
+    let wallet_kp =
+        load_keypair(&config.wallet.private_key).context("Failed to load wallet keypair")?;
+    let mut pool_cache = if use_pool_cache {
+        PoolCache::load(config.bot.pool_cache_path())
+    } else {
+        PoolCache::empty()
+    };
+    let blacklist = load_blacklist(config).await;
+
+    let mints = load_mint_pools(
+        config,
+        &wallet_kp.pubkey(),
+        &startup.rpc_client,
+        &startup.async_rpc_client,
+        &mut pool_cache,
+        &blacklist,
+    )
+    .await?
+    .into_iter()
+    .map(|loaded| loaded.pool_data)
+    .collect();
+    let dump = StateDump::capture(&rpc_client, mints)?;
+    dump.save(dir)?;
+    info!(
+        "Dumped {} mint(s) and {} account(s) at slot {} to {}",
+        dump.mints.len(),
+        dump.accounts.len(),
+        dump.slot,
+        dir.display()
+    );
+    for pubkey in dump.missing_accounts() {
+        warn!("   Account {} does not exist", pubkey);
+    }
+    Ok(())
+}
+
+/// 启动阶段加载池子使用的客户端
+struct StartupClients {
+    account_cache: Arc<AccountCache>,
+    /// 启动阶段的同步客户端
+    rpc_client: Arc<RpcClient>,
+    /// 并发预取池子账户的 async 客户端，与同步客户端共享账户缓存
+    async_rpc_client: NonblockingRpcClient,
+}
+
+/// 创建启动阶段加载池子使用的客户端
+///
+/// 跨 mint 重复读取的账户（mint、共享的 AMM 配置、查找表等）走带缓存的客户端，
+/// 运行中的报价、刷新和发送仍使用 `rpc_client`，始终读取最新状态。
+fn startup_clients(
+    config: &Config,
+    rpc_client: &Arc<RpcClient>,
+    rpc_metrics: Option<&Arc<RpcMetrics>>,
+) -> anyhow::Result<StartupClients> {
+    let account_cache = Arc::new(AccountCache::new(config.rpc.cache_slots()));
+    let startup_rpc_client = if config.rpc.cache_slots() > 0 {
+        Arc::new(build_cached_rpc_client(
+            &config.rpc.url,
+            config.rpc.rate_limit.as_ref(),
+            account_cache.clone(),
+            rpc_metrics,
+            config.rpc.proxy.as_ref(),
+        )?)
+    } else {
+        rpc_client.clone()
+    };
+    let startup_async_rpc_client = build_nonblocking_rpc_client(
+        &config.rpc.url,
+        config.rpc.rate_limit.as_ref(),
+        (config.rpc.cache_slots() > 0).then(|| account_cache.clone()),
+        rpc_metrics,
+        config.rpc.proxy.as_ref(),
+    )?;
+    Ok(StartupClients {
+        account_cache,
+        rpc_client: startup_rpc_client,
+        async_rpc_client: startup_async_rpc_client,
+    })
+}
+
+/// 读取配置的黑名单，未配置时为空
+async fn load_blacklist(config: &Config) -> Blacklist {
+    match &config.blacklist {
+        Some(blacklist_config) => {
+            let blacklist = Blacklist::load(blacklist_config).await;
+            info!("Loaded {} blacklist entries", blacklist.len());
+            blacklist
+        }
+        None => Blacklist::default(),
+    }
+}
+
+/// 一个代币加载完成的池子数据
+struct LoadedMint {
+    /// 代币在 `mint_config_list` 中的序号
+    index: usize,
+    quote_mint: CashMint,
+    pool_data: MintPoolData,
+}
+
+/// 加载所有代币的池子
+///
+/// 跳过黑名单和有风险的代币，去掉黑名单中的池子，并记录每个池子的报价偏离，
+/// 池子太多放不进一笔交易时据此决定保留哪些池子。
+///
+/// # 参数
+/// * `config` - 配置
+/// * `wallet` - 钱包地址，用于推导代币账户
+/// * `rpc_client` - 启动阶段的同步客户端
+/// * `async_rpc_client` - 并发预取账户的 async 客户端
+/// * `pool_cache` - 池子元数据缓存，新加载的池子写入其中
+/// * `blacklist` - 黑名单
+///
+/// # 错误
+/// 读取 mint 账户失败，或 `on_pool_error = "fail"` 时池子加载失败
+async fn load_mint_pools(
+    config: &Config,
+    wallet: &Pubkey,
+    rpc_client: &Arc<RpcClient>,
+    async_rpc_client: &NonblockingRpcClient,
+    pool_cache: &mut PoolCache,
+    blacklist: &Blacklist,
+) -> anyhow::Result<Vec<LoadedMint>> {
+    let blocked_mints = if blacklist.has_mint_authorities() {
+        let mints: Vec<Pubkey> = config
+            .routing
+            .mint_config_list
+            .iter()
+            .map(MintConfig::mint_pubkey)
+            .collect::<anyhow::Result<_>>()?;
+        fetch_blocked_mints(rpc_client, blacklist, &mints)
+            .context("Failed to check mint authorities against the blacklist")?
+    } else {
+        Vec::new()
+    };
+    let inline_swap = config
+        .inline_swap
+        .as_ref()
+        .is_some_and(|inline_swap| inline_swap.enabled);
+
+    let mut loaded = Vec::new();
+    for (index, mint_config) in config.routing.mint_config_list.iter().enumerate() {
+        info!("Processing mint: {}", mint_config.mint);
+        let mint = mint_config.mint_pubkey()?;
+        if let Some((_, reason)) = blocked_mints.iter().find(|(blocked, _)| *blocked == mint) {
+            warn!("Skipping mint {}: {}", mint_config.mint, reason);
+            continue;
+        }
+        // 冻结权限和部分 Token 2022 扩展可能让库存在套利途中被冻结或转走，默认不交易这类代币
+        let mint_account = rpc_client
+            .get_account(&mint)
+            .with_context(|| format!("Failed to read mint {}", mint_config.mint))?;
+        let risks = mint_risks(&mint_account.data);
+        if !risks.is_empty() {
+            let risks = risks
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            if !mint_config.allow_risky() {
+                error!(
+                    "Refusing to trade mint {} ({}), set allow_risky = true to trade it anyway",
+                    mint_config.mint, risks
+                );
+                continue;
+            }
+            warn!("Trading risky mint {} ({})", mint_config.mint, risks);
+        }
+        let quote_mint = mint_config.quote_mint()?;
+        if !quote_mint.is_sol() {
+            info!("Quoting mint {} in {}", mint_config.mint, quote_mint.symbol);
+        }
+
+        let mut pool_data = initialize_pool_data(
+            &mint,
+            wallet,
+            &quote_mint.mint,
+            // 禁用的池子不参与初始化
+            &PoolLists::enabled(mint_config)?,
+            config
+                .bot
+                .min_pool_liquidity_sol
+                .map(|min| quote_mint.to_base_units(min)),
+            &PoolLoadContext {
+                rpc_client: rpc_client.clone(),
+                async_rpc_client,
+                pool_cache: Some(pool_cache),
+                on_pool_error: config.bot.on_pool_error,
+                non_quote_pools: config.routing.non_quote_pools,
+            },
+        )
+        .await?;
+        pool_cache.insert_mint_pools(&pool_data);
+        let blocked_pools = blacklist.blocked_pools(&pool_data);
+        if !blocked_pools.is_empty() {
+            pool_data
+                .retain_pools(|pool| !blocked_pools.iter().any(|(_, blocked, _)| blocked == pool));
+            for (dex, pool, reason) in blocked_pools {
+                warn!(
+                    "Skipping {} pool {} for mint {}: {}",
+                    dex, pool, mint_config.mint, reason
+                );
+            }
+        }
+        pool_data.pool_directions = mint_config.pool_directions()?;
+        match quote::fetch_spot_prices(rpc_client, &pool_data) {
+            Ok(prices) => pool_data.pool_edges = quote::pool_edges(&prices),
+            Err(e) => warn!("Failed to quote pools for mint {}: {}", mint_config.mint, e),
+        }
+        if !pool_data.has_valid_route() {
+            warn!(
+                "Mint {} has no buy/sell pool pair allowed by the configured directions",
+                mint_config.mint
+            );
+        }
+        if inline_swap {
+            let inline_pools = pool_data
+                .pool_addresses()
+                .iter()
+                .filter(|(_, pool)| crate::inline_swap::supports(&pool_data, pool))
+                .count();
+            if inline_pools < 2 {
+                warn!(
+                    "Mint {} has {} pool(s) that support inline swaps, at least 2 are needed",
+                    mint_config.mint, inline_pools
+                );
+            }
+        }
+
+        info!(
+            "Mint {}: {} pool(s)\n{}",
+            mint_config.mint,
+            pool_data.pool_addresses().len(),
+            pool_data.summary_table()
+        );
+        loaded.push(LoadedMint {
+            index,
+            quote_mint,
+            pool_data,
+        });
     }
+    Ok(loaded)
 }
 
 /// 异步函数，用于定期刷新并缓存最新的区块哈希值
@@ -348,27 +2038,42 @@ pub async fn run_bot(config_path: &str) -> anyhow::Result<()> {
 /// 并将其存储在共享的缓存中供其他组件使用。
 ///
 /// # 参数
-/// * `rpc_client` - RPC客户端的Arc引用，用于与区块链节点通信获取最新区块哈希
-/// * `cached_blockhash` - 通过Arc<Mutex<Hash>>包装的共享区块哈希缓存
+/// * `state` - 共享的运行状态：用主 RPC 客户端获取区块哈希，写入 `state.blockhash`（同时记录获取时的 slot），
+///   连续刷新失败时推送 RPC 故障告警、恢复后推送恢复消息，并在运行状态中记录 RPC 健康情况
 /// * `refresh_interval` - 刷新间隔时间，控制获取新区块哈希的频率
-async fn blockhash_refresher(
-    rpc_client: Arc<RpcClient>,
-    cached_blockhash: Arc<Mutex<Hash>>,
-    refresh_interval: Duration,
-) {
+async fn blockhash_refresher(state: Arc<AppState>, refresh_interval: Duration) {
+    let rpc_client = &state.rpc_client;
+    let mut consecutive_failures = 0u32;
+
     // 持续循环刷新区块哈希
     loop {
         // 尝试获取最新的区块哈希
-        match rpc_client.get_latest_blockhash() {
-            Ok(blockhash) => {
+        match get_latest_blockhash_with_slot(rpc_client) {
+            Ok((blockhash, slot)) => {
                 // 成功获取区块哈希，更新缓存
-                let mut guard = cached_blockhash.lock().await;
-                *guard = blockhash;
+                state.set_blockhash(blockhash, slot).await;
                 info!("Blockhash refreshed: {}", blockhash);
+                state.status.record_rpc_success();
+
+                if consecutive_failures >= RPC_OUTAGE_THRESHOLD {
+                    state.notifier.notify(Alert::RpcRecovered {
+                        url: rpc_client.url(),
+                    });
+                }
+                consecutive_failures = 0;
             }
             Err(e) => {
                 // 获取区块哈希失败，记录错误日志
                 error!("Failed to refresh blockhash: {:?}", e);
+                state.status.record_rpc_failure(e.to_string());
+
+                consecutive_failures += 1;
+                if consecutive_failures == RPC_OUTAGE_THRESHOLD {
+                    state.notifier.notify(Alert::RpcOutage {
+                        url: rpc_client.url(),
+                        error: e.to_string(),
+                    });
+                }
             }
         }
         // 等待指定的刷新间隔时间
@@ -376,6 +2081,335 @@ async fn blockhash_refresher(
     }
 }
 
+/// 跟踪已发送交易上链情况需要的共享状态，每个代币一份
+#[derive(Clone)]
+struct LandingTracker {
+    /// 共享的运行状态：RPC 客户端、钱包、告警推送器、仪表盘状态、交易历史存储，
+    /// 以及优先费预算、按路线调整的优先费和 Geyser 推送的交易结果
+    state: Arc<AppState>,
+    /// 交易对应的代币
+    mint: String,
+    /// 代币的报价货币，不是 SOL 时另外记录该货币的收益
+    quote_mint: CashMint,
+    /// 代币的失败退避，上链成功时清零，上链失败或超时计为一次失败
+    cooldown: Option<Arc<FailureCooldown>>,
+    /// 代币的在途交易，有结果后释放名额
+    in_flight: Option<Arc<InFlight>>,
+    /// 期望收益门槛的上链率模型，记录交易是否上链
+    landing_model: Option<Arc<LandingModel>>,
+}
+
+impl LandingTracker {
+    /// 等待交易上链，更新仪表盘中的交易结果，成功上链时推送收益告警
+    ///
+    /// # 参数
+    /// * `signature` - 交易签名
+    /// * `owns_profit` - 交易的收益和手续费是否记在这个代币上；合并发送的交易只记在第一个代币上，
+    ///   其他代币上链时记为收益 0
+    /// * `landing_bucket` - 发送时上链率模型的分桶，没有经过期望收益门槛时为 `None`
+    /// * `route_key` - 交易的路线，见 `RouteFees::route_key`，没有启用 `[route_fees]` 时为 `None`
+    /// * `tip` - 通过 Jito 发送时 bundle 中支付的小费，上链时从收益中扣除
+    /// * `expected_profit` - 发送时报价的收益，上链后和余额变化核对，没有报价时只核对是否亏损
+    async fn track(
+        self,
+        signature: Signature,
+        owns_profit: bool,
+        landing_bucket: Option<LandingBucket>,
+        route_key: Option<String>,
+        tip: Option<u64>,
+        expected_profit: Option<i64>,
+    ) {
+        let landed = match &self.state.services.transaction_watch {
+            Some(watch) if watch.is_connected() => {
+                match watch.wait(&signature, Duration::from_secs(60)).await {
+                    Some(update) => Ok(update.landed(&self.quote_mint.mint)),
+                    // 订阅期间断线可能漏掉推送，最后再查一次交易状态
+                    None => {
+                        fetch_landed_profit(
+                            &self.state.rpc_client,
+                            &signature,
+                            &self.state.wallet.pubkey(),
+                            &self.quote_mint.mint,
+                            Duration::ZERO,
+                        )
+                        .await
+                    }
+                }
+            }
+            _ => {
+                fetch_landed_profit(
+                    &self.state.rpc_client,
+                    &signature,
+                    &self.state.wallet.pubkey(),
+                    &self.quote_mint.mint,
+                    Duration::from_secs(60),
+                )
+                .await
+            }
+        };
+        // 上链、失败、超时或查询出错都释放名额，和失败退避一样把超时未上链的交易视为失败
+        if let Some(in_flight) = &self.in_flight {
+            in_flight.remove(&signature);
+        }
+        if let (Some(landing_model), Some(bucket), Ok(landed)) =
+            (&self.landing_model, landing_bucket, &landed)
+        {
+            landing_model.record(bucket, landed.is_some());
+        }
+        // 没有上链的路线提高出价，上链的路线降低出价
+        if let (Some(route_fees), Some(route_key), Ok(landed)) =
+            (&self.state.services.route_fees, &route_key, &landed)
+        {
+            let status = route_fees.record(route_key, landed.is_some());
+            self.state.status.update_route_fee(route_key, status);
+        }
+        // 按上链结果调整各区域区块引擎的权重
+        if let Some(jito_sender) = &self.state.jito_sender {
+            jito_sender.record_result(
+                &signature,
+                landed.as_ref().ok().map(|landed| landed.is_some()),
+            );
+        }
+        let (outcome, fee) = match landed {
+            Ok(Some(_)) if !owns_profit => {
+                if let Some(cooldown) = &self.cooldown {
+                    cooldown.record_success();
+                }
+                (TransactionOutcome::Landed { profit_lamports: 0 }, None)
+            }
+            Ok(Some(mut landed)) => {
+                self.audit(&signature, &landed, expected_profit);
+                // 小费在 bundle 中的另一笔交易里支付，记录小费占收益的比例用于调整分成比例
+                if let Some(tip) = tip {
+                    info!(
+                        "Bundle {} for mint {} landed: tip {} lamports is {} of profit {} lamports",
+                        signature,
+                        self.mint,
+                        tip,
+                        tip_ratio(tip, landed.profit_lamports)
+                            .map_or("n/a".to_string(), |ratio| format!("{:.1}%", ratio * 100.0)),
+                        landed.profit_lamports
+                    );
+                    landed.profit_lamports -= tip as i64;
+                }
+                // 仪表盘、告警和优先费预算的收益以 SOL 计，其他报价货币的收益只记录在日志中
+                if let Some(quote_profit) = landed.quote_profit {
+                    info!(
+                        "Transaction {} for mint {} changed the wallet's {} balance by {}",
+                        signature,
+                        self.mint,
+                        self.quote_mint.symbol,
+                        self.quote_mint.from_base_units(quote_profit)
+                    );
+                }
+                if let Some(fee_governor) = &self.state.services.fee_governor {
+                    fee_governor.record(landed.fee_lamports, landed.profit_lamports);
+                }
+                if let Some(cooldown) = &self.cooldown {
+                    cooldown.record_success();
+                }
+                self.state.notifier.notify(Alert::Landed {
+                    mint: self.mint.clone(),
+                    signature,
+                    profit_lamports: landed.profit_lamports,
+                });
+                (
+                    TransactionOutcome::Landed {
+                        profit_lamports: landed.profit_lamports,
+                    },
+                    Some(landed.fee_lamports),
+                )
+            }
+            Ok(None) => {
+                if let Some(cooldown) = &self.cooldown {
+                    record_cooldown_failure(cooldown, &self.state.notifier, &self.mint);
+                }
+                (TransactionOutcome::NotLanded, None)
+            }
+            Err(e) => {
+                warn!("Failed to check transaction {}: {}", signature, e);
+                return;
+            }
+        };
+
+        self.state
+            .trade_sinks
+            .update_outcome(&self.mint, &signature, &outcome, fee);
+        self.state
+            .status
+            .update_transaction_outcome(&self.mint, &signature, outcome);
+    }
+
+    /// 核对上链交易的报价货币余额变化：低于报价收益较多时记录警告，
+    /// 余额减少时说明报价有误，记录错误并推送告警
+    fn audit(
+        &self,
+        signature: &Signature,
+        landed: &LandedTransaction,
+        expected_profit: Option<i64>,
+    ) {
+        let tolerance = self.state.config.bot.profit_audit_tolerance_percent();
+        let symbol = self.quote_mint.symbol;
+        match audit_profit(expected_profit, landed.quote_delta(), tolerance) {
+            ProfitAudit::Ok => {}
+            ProfitAudit::Shortfall { expected, actual } => warn!(
+                "Transaction {} for mint {} changed the wallet's {} balance by {}, more than {}% below the quoted profit {}",
+                signature,
+                self.mint,
+                symbol,
+                self.quote_mint.from_base_units(actual),
+                tolerance,
+                self.quote_mint.from_base_units(expected)
+            ),
+            ProfitAudit::Loss { expected, actual } => {
+                let expected = expected.map(|expected| self.quote_mint.from_base_units(expected));
+                let actual = self.quote_mint.from_base_units(actual);
+                error!(
+                    "CRITICAL: transaction {} for mint {} landed but lost {} {} (quoted profit {:?}), the quote is likely wrong",
+                    signature, self.mint, -actual, symbol, expected
+                );
+                self.state.notifier.notify(Alert::RouteLoss {
+                    mint: self.mint.clone(),
+                    signature: *signature,
+                    symbol,
+                    expected,
+                    actual,
+                });
+            }
+        }
+    }
+}
+
+/// 启动 Yellowstone gRPC 交易订阅
+#[cfg(feature = "yellowstone")]
+fn spawn_transaction_watch(
+    geyser: &GeyserConfig,
+    wallet: Pubkey,
+) -> anyhow::Result<Arc<TransactionWatch>> {
+    let watch = Arc::new(TransactionWatch::new());
+    tokio::spawn(crate::geyser::run(
+        watch.clone(),
+        geyser.endpoint.clone(),
+        geyser.x_token.clone(),
+        wallet,
+    ));
+    Ok(watch)
+}
+
+#[cfg(not(feature = "yellowstone"))]
+fn spawn_transaction_watch(
+    _geyser: &GeyserConfig,
+    _wallet: Pubkey,
+) -> anyhow::Result<Arc<TransactionWatch>> {
+    Err(anyhow::anyhow!(
+        "geyser.enabled requires building with `--features yellowstone`"
+    ))
+}
+
+/// 记录代币的一次失败，刚进入退避时告警
+fn record_cooldown_failure(cooldown: &FailureCooldown, notifier: &Notifier, mint: &str) {
+    if cooldown.record_failure() {
+        let delay = cooldown.delay();
+        warn!(
+            "Mint {} failed {} times in a row, backing off (next wait {:?})",
+            mint,
+            cooldown.failures(),
+            delay
+        );
+        notifier.notify(Alert::Cooldown {
+            mint: mint.to_string(),
+            failures: cooldown.failures(),
+            delay,
+        });
+    }
+}
+
+/// 按本轮报价构造直接兑换的路线
+///
+/// 开启余额检查时先读取钱包报价货币账户的余额作为基准。
+///
+/// # 错误
+/// 没有报价、报价的路线在整数复核后不再满足最低收益，或读取余额失败时返回错误
+fn inline_route(
+    rpc_client: &RpcClient,
+    inline_swap: &InlineSwapConfig,
+    pool_data: &MintPoolData,
+    quote: Option<SizedQuote>,
+    reserves: Option<&HashMap<Pubkey, PoolReserves>>,
+) -> anyhow::Result<InlineRoute> {
+    let (Some(quote), Some(reserves)) = (quote, reserves) else {
+        anyhow::bail!("No quoted route for inline swaps");
+    };
+    let quote_balance = if inline_swap.balance_check() {
+        Some(fetch_quote_balance(
+            rpc_client,
+            &pool_data.wallet_quote_account(),
+        )?)
+    } else {
+        None
+    };
+    InlineRoute::from_quote(&quote, reserves, inline_swap.min_profit(), quote_balance).ok_or_else(
+        || {
+            anyhow::anyhow!(
+                "Route {} -> {} does not cover the minimum profit of {}",
+                quote.buy_pool,
+                quote.sell_pool,
+                inline_swap.min_profit()
+            )
+        },
+    )
+}
+
+/// 将一次交易尝试记录到仪表盘状态和交易历史中
+fn record_attempt(
+    state: &AppState,
+    mint: &str,
+    route: &str,
+    signature: Option<Signature>,
+    simulated_profit: Option<i64>,
+    outcome: TransactionOutcome,
+) {
+    state
+        .trade_sinks
+        .record_attempt(mint, route, signature.as_ref(), simulated_profit, &outcome);
+    state.status.record_transaction(mint, signature, outcome);
+}
+
+/// 定期检查钱包 SOL 余额，低于阈值时推送告警
+///
+/// 余额跌破阈值时只告警一次，回到阈值以上后重新开始检查。
+///
+/// # 参数
+/// * `state` - 共享的运行状态，用主 RPC 客户端查询钱包余额，通过其中的告警推送器告警
+/// * `threshold_lamports` - 余额阈值（lamports）
+/// * `check_interval` - 检查间隔
+async fn balance_monitor(state: Arc<AppState>, threshold_lamports: u64, check_interval: Duration) {
+    let wallet = state.wallet.pubkey();
+    let mut alerted = false;
+
+    loop {
+        match state.rpc_client.get_balance(&wallet) {
+            Ok(balance_lamports) if balance_lamports < threshold_lamports => {
+                if !alerted {
+                    warn!(
+                        "Wallet balance {} lamports is below {} lamports",
+                        balance_lamports, threshold_lamports
+                    );
+                    state.notifier.notify(Alert::LowBalance {
+                        wallet,
+                        balance_lamports,
+                        threshold_lamports,
+                    });
+                    alerted = true;
+                }
+            }
+            Ok(_) => alerted = false,
+            Err(e) => error!("Failed to fetch wallet balance: {:?}", e),
+        }
+        tokio::time::sleep(check_interval).await;
+    }
+}
+
 /// 从字符串加载密钥对
 ///
 /// 该函数尝试从给定的字符串加载Solana密钥对。它首先尝试将字符串解析为base58编码的
@@ -387,7 +2421,7 @@ async fn blockhash_refresher(
 /// # 返回值
 /// * `Ok(Keypair)` - 成功加载的密钥对
 /// * `Err(anyhow::Error)` - 加载失败时返回错误信息
-fn load_keypair(private_key: &str) -> anyhow::Result<Keypair> {
+pub fn load_keypair(private_key: &str) -> anyhow::Result<Keypair> {
     // 尝试将输入字符串解析为base58编码的密钥对
     if let Ok(keypair) = bs58::decode(private_key)
         .into_vec()
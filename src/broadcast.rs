@@ -0,0 +1,77 @@
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::{HashMap, HashSet};
+
+/// 一个代币在当前 blockhash 窗口内签名和广播过的交易
+///
+/// 同一条路线在同一个 blockhash 下可能编译出完全相同的消息（计算单元限制的随机扰动只有 1000 种取值），
+/// 相同的消息签名后就是同一笔交易，各端点会把再次广播报告为已处理。发送任务用它复用之前的签名，
+/// 只为第一次广播的签名记录发送结果和跟踪上链，之后的发送只是幂等的重发。blockhash 变化后清空。
+#[derive(Debug, Default)]
+pub struct BroadcastLog {
+    blockhash: Hash,
+    /// 序列化的消息 -> 签名
+    signed: HashMap<Vec<u8>, Signature>,
+    /// 至少通过一个端点广播成功的签名
+    broadcast: HashSet<Signature>,
+}
+
+impl BroadcastLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 签名消息，当前窗口内签过相同的消息时直接复用之前的签名
+    ///
+    /// # 错误
+    /// 签名失败时返回错误
+    pub fn sign(
+        &mut self,
+        wallet_kp: &Keypair,
+        message: VersionedMessage,
+    ) -> anyhow::Result<VersionedTransaction> {
+        self.roll(*message.recent_blockhash());
+        let bytes = message.serialize();
+        if let Some(signature) = self.signed.get(&bytes) {
+            return Ok(VersionedTransaction {
+                signatures: vec![*signature],
+                message,
+            });
+        }
+        let tx = VersionedTransaction::try_new(message, &[wallet_kp])?;
+        self.signed.insert(bytes, tx.signatures[0]);
+        Ok(tx)
+    }
+
+    /// 记录一笔广播成功的交易
+    ///
+    /// # 参数
+    /// * `blockhash` - 交易使用的 blockhash
+    ///
+    /// # 返回值
+    /// 签名在当前窗口内第一次广播时为 `true`，重发时为 `false`
+    pub fn record(&mut self, blockhash: Hash, signature: Signature) -> bool {
+        self.roll(blockhash);
+        self.broadcast.insert(signature)
+    }
+
+    /// 当前窗口内广播过的交易数
+    pub fn len(&self) -> usize {
+        self.broadcast.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.broadcast.is_empty()
+    }
+
+    /// 换了 blockhash 的消息不可能与之前的相同，旧窗口的记录不再需要
+    fn roll(&mut self, blockhash: Hash) {
+        if self.blockhash != blockhash {
+            self.blockhash = blockhash;
+            self.signed.clear();
+            self.broadcast.clear();
+        }
+    }
+}
@@ -0,0 +1,607 @@
+//! 集中流动性池子（Raydium CLMM、Whirlpool）的 sqrt price 数学和按 tick 遍历的兑换模拟
+//!
+//! 价格以 Q64.64 定点数的 `sqrt_price_x64` 表示，取整方式与链上程序一致，
+//! 中间结果最多需要 320 位，用 512 位整数计算。两个程序的公式相同，
+//! 只有 tick 对应的 sqrt price 和 token 0 数量的取整细节不同，见 [`TickMath`]。
+
+use std::cmp::Ordering;
+
+/// 最小 tick
+pub const MIN_TICK: i32 = -443_636;
+
+/// 最大 tick
+pub const MAX_TICK: i32 = 443_636;
+
+/// `MIN_TICK` 对应的 sqrt price
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+
+/// `MAX_TICK` 对应的 sqrt price
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+/// Whirlpool 中 `MAX_TICK` 对应的 sqrt price，与 Raydium CLMM 的计算精度不同
+pub const WHIRLPOOL_MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// 手续费率的分母（百万分之一）
+pub const FEE_RATE_DENOMINATOR: u32 = 1_000_000;
+
+/// 兑换中间结果使用的 512 位无符号整数，低位在前
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct U512([u64; 8]);
+
+impl From<u128> for U512 {
+    fn from(value: u128) -> Self {
+        let mut limbs = [0; 8];
+        limbs[0] = value as u64;
+        limbs[1] = (value >> 64) as u64;
+        Self(limbs)
+    }
+}
+
+impl PartialOrd for U512 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U512 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+impl U512 {
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+
+    /// 乘积超过 512 位时高位被截断，调用方保证不会发生
+    fn mul(&self, other: &Self) -> Self {
+        let mut result = [0u64; 8];
+        for (i, &a) in self.0.iter().enumerate() {
+            let mut carry = 0u128;
+            for (j, &b) in other.0.iter().enumerate().take(8 - i) {
+                let product = a as u128 * b as u128 + result[i + j] as u128 + carry;
+                result[i + j] = product as u64;
+                carry = product >> 64;
+            }
+        }
+        Self(result)
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut result = [0u64; 8];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, overflow_a) = self.0[i].overflowing_add(other.0[i]);
+            let (sum, overflow_b) = sum.overflowing_add(carry as u64);
+            *limb = sum;
+            carry = overflow_a || overflow_b;
+        }
+        Self(result)
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let mut result = [0u64; 8];
+        let mut borrow = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (diff, underflow_a) = self.0[i].overflowing_sub(other.0[i]);
+            let (diff, underflow_b) = diff.overflowing_sub(borrow as u64);
+            *limb = diff;
+            borrow = underflow_a || underflow_b;
+        }
+        Self(result)
+    }
+
+    /// 左移 64 位
+    fn shl_64(&self) -> Self {
+        let mut result = [0u64; 8];
+        result[1..].copy_from_slice(&self.0[..7]);
+        Self(result)
+    }
+
+    /// 右移 `bits` 位
+    fn shr(&self, bits: usize) -> Self {
+        let (limbs, bits) = (bits / 64, bits % 64);
+        let mut result = [0u64; 8];
+        for (i, limb) in result.iter_mut().enumerate().take(8 - limbs) {
+            let low = self.0[i + limbs] >> bits;
+            let high = match self.0.get(i + limbs + 1) {
+                Some(next) if bits > 0 => next << (64 - bits),
+                _ => 0,
+            };
+            *limb = low | high;
+        }
+        Self(result)
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.0[index / 64] >> (index % 64) & 1 == 1
+    }
+
+    /// 逐位长除法，返回 (商, 余数)；`divisor` 为 0 时返回 `None`
+    fn div_rem(&self, divisor: &Self) -> Option<(Self, Self)> {
+        if divisor.is_zero() {
+            return None;
+        }
+        let mut quotient = [0u64; 8];
+        let mut remainder = Self::default();
+        for index in (0..512).rev() {
+            remainder = remainder.add(&remainder);
+            remainder.0[0] |= self.bit(index) as u64;
+            if remainder >= *divisor {
+                remainder = remainder.sub(divisor);
+                quotient[index / 64] |= 1 << (index % 64);
+            }
+        }
+        Some((Self(quotient), remainder))
+    }
+
+    /// `self / divisor`，按 `round_up` 向上或向下取整
+    fn div(&self, divisor: &Self, round_up: bool) -> Option<Self> {
+        let (quotient, remainder) = self.div_rem(divisor)?;
+        if round_up && !remainder.is_zero() {
+            Some(quotient.add(&Self::from(1)))
+        } else {
+            Some(quotient)
+        }
+    }
+
+    fn to_u128(self) -> Option<u128> {
+        self.0[2..]
+            .iter()
+            .all(|limb| *limb == 0)
+            .then(|| self.0[0] as u128 | (self.0[1] as u128) << 64)
+    }
+
+    fn to_u64(self) -> Option<u64> {
+        self.to_u128().and_then(|value| u64::try_from(value).ok())
+    }
+}
+
+/// tick 对应的 sqrt price：`sqrt(1.0001^tick) × 2^64`
+///
+/// 与 Raydium CLMM 的 `get_sqrt_price_at_tick` 逐位一致，tick 超出范围时返回 `None`。
+pub fn sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    const RATIOS: [u128; 18] = [
+        0xfff97272373d4000,
+        0xfff2e50f5f657000,
+        0xffe5caca7e10f000,
+        0xffcb9843d60f7000,
+        0xff973b41fa98e800,
+        0xff2ea16466c9b000,
+        0xfe5dee046a9a3800,
+        0xfcbe86c7900bb000,
+        0xf987a7253ac65800,
+        0xf3392b0822bb6000,
+        0xe7159475a2caf000,
+        0xd097f3bdfd2f2000,
+        0xa9f746462d9f8000,
+        0x70d869a156f31c00,
+        0x31be135f97ed3200,
+        0x9aa508b5b85a500,
+        0x5d6af8dedc582c,
+        0x2216e584f5fa,
+    ];
+
+    let abs_tick = tick.unsigned_abs();
+    if abs_tick > MAX_TICK as u32 {
+        return None;
+    }
+    let mut ratio: u128 = if abs_tick & 1 != 0 {
+        0xfffcb933bd6fb800
+    } else {
+        1 << 64
+    };
+    for (i, factor) in RATIOS.iter().enumerate() {
+        if abs_tick & (2 << i) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+    if tick > 0 {
+        ratio = u128::MAX / ratio;
+    }
+    Some(ratio)
+}
+
+/// Whirlpool 中 tick 对应的 sqrt price，与链上 `sqrt_price_from_tick_index` 逐位一致
+///
+/// 正 tick 以 Q32.96 累乘后右移 32 位，负 tick 以 Q64.64 累乘；tick 超出范围时返回 `None`。
+pub fn whirlpool_sqrt_price_at_tick(tick: i32) -> Option<u128> {
+    const POSITIVE_RATIOS: [u128; 18] = [
+        79236085330515764027303304731,
+        79244008939048815603706035061,
+        79259858533276714757314932305,
+        79291567232598584799939703904,
+        79355022692464371645785046466,
+        79482085999252804386437311141,
+        79736823300114093921829183326,
+        80248749790819932309965073892,
+        81282483887344747381513967011,
+        83390072131320151908154831281,
+        87770609709833776024991924138,
+        97234110755111693312479820773,
+        119332217159966728226237229890,
+        179736315981702064433883588727,
+        407748233172238350107850275304,
+        2098478828474011932436660412517,
+        55581415166113811149459800483533,
+        38992368544603139932233054999993551,
+    ];
+    const NEGATIVE_RATIOS: [u128; 18] = [
+        18444899583751176498,
+        18443055278223354162,
+        18439367220385604838,
+        18431993317065449817,
+        18417254355718160513,
+        18387811781193591352,
+        18329067761203520168,
+        18212142134806087854,
+        17980523815641551639,
+        17526086738831147013,
+        16651378430235024244,
+        15030750278693429944,
+        12247334978882834399,
+        8131365268884726200,
+        3584323654723342297,
+        696457651847595233,
+        26294789957452057,
+        37481735321082,
+    ];
+
+    let abs_tick = tick.unsigned_abs();
+    if abs_tick > MAX_TICK as u32 {
+        return None;
+    }
+    if tick >= 0 {
+        let mut ratio = U512::from(if abs_tick & 1 != 0 {
+            79232123823359799118286999567
+        } else {
+            79228162514264337593543950336
+        });
+        for (i, factor) in POSITIVE_RATIOS.iter().enumerate() {
+            if abs_tick & (2 << i) != 0 {
+                ratio = ratio.mul(&U512::from(*factor)).shr(96);
+            }
+        }
+        ratio.shr(32).to_u128()
+    } else {
+        let mut ratio: u128 = if abs_tick & 1 != 0 {
+            18445821805675392311
+        } else {
+            1 << 64
+        };
+        for (i, factor) in NEGATIVE_RATIOS.iter().enumerate() {
+            if abs_tick & (2 << i) != 0 {
+                ratio = (ratio * factor) >> 64;
+            }
+        }
+        Some(ratio)
+    }
+}
+
+/// 链上程序的 sqrt price 实现
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickMath {
+    RaydiumClmm,
+    Whirlpool,
+}
+
+impl TickMath {
+    /// tick 对应的 sqrt price，见 [`sqrt_price_at_tick`] 和 [`whirlpool_sqrt_price_at_tick`]
+    pub fn sqrt_price_at_tick(self, tick: i32) -> Option<u128> {
+        match self {
+            TickMath::RaydiumClmm => sqrt_price_at_tick(tick),
+            TickMath::Whirlpool => whirlpool_sqrt_price_at_tick(tick),
+        }
+    }
+
+    /// 价格区间对应的 token 0 数量，见 [`delta_amount_0`] 和 [`whirlpool_delta_amount_0`]
+    pub fn delta_amount_0(
+        self,
+        sqrt_price_a: u128,
+        sqrt_price_b: u128,
+        liquidity: u128,
+        round_up: bool,
+    ) -> Option<u64> {
+        match self {
+            TickMath::RaydiumClmm => {
+                delta_amount_0(sqrt_price_a, sqrt_price_b, liquidity, round_up)
+            }
+            TickMath::Whirlpool => {
+                whirlpool_delta_amount_0(sqrt_price_a, sqrt_price_b, liquidity, round_up)
+            }
+        }
+    }
+}
+
+/// 价格在两个 sqrt price 之间移动需要的 token 0 数量：`L × (√B − √A) × 2^64 / (√A × √B)`
+///
+/// 与 Raydium CLMM 相同，先除以 √B 再除以 √A，每次都取整；超过 u64 时返回 `None`。
+pub fn delta_amount_0(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = (
+        sqrt_price_a.min(sqrt_price_b),
+        sqrt_price_a.max(sqrt_price_b),
+    );
+    if lower == 0 {
+        return None;
+    }
+    let numerator = U512::from(liquidity)
+        .shl_64()
+        .mul(&U512::from(upper - lower));
+    numerator
+        .div(&U512::from(upper), round_up)?
+        .div(&U512::from(lower), round_up)?
+        .to_u64()
+}
+
+/// Whirlpool 的 token 0（token A）数量：与 [`delta_amount_0`] 公式相同，但一次除以 `√A × √B`
+///
+/// 超过 u64 时返回 `None`。
+pub fn whirlpool_delta_amount_0(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = (
+        sqrt_price_a.min(sqrt_price_b),
+        sqrt_price_a.max(sqrt_price_b),
+    );
+    let numerator = U512::from(liquidity)
+        .mul(&U512::from(upper - lower))
+        .shl_64();
+    let denominator = U512::from(upper).mul(&U512::from(lower));
+    numerator.div(&denominator, round_up)?.to_u64()
+}
+
+/// 价格在两个 sqrt price 之间移动需要的 token 1 数量：`L × (√B − √A) / 2^64`
+///
+/// 超过 u64 时返回 `None`。
+pub fn delta_amount_1(
+    sqrt_price_a: u128,
+    sqrt_price_b: u128,
+    liquidity: u128,
+    round_up: bool,
+) -> Option<u64> {
+    let (lower, upper) = (
+        sqrt_price_a.min(sqrt_price_b),
+        sqrt_price_a.max(sqrt_price_b),
+    );
+    U512::from(liquidity)
+        .mul(&U512::from(upper - lower))
+        .div(&U512::from(1u128 << 64), round_up)?
+        .to_u64()
+}
+
+/// 输入 `amount_in` 后的 sqrt price
+///
+/// 输入 token 0 时价格下降：`L × √P × 2^64 / (L × 2^64 + amount × √P)`，向上取整；
+/// 输入 token 1 时价格上升：`√P + amount × 2^64 / L`，向下取整。
+pub fn next_sqrt_price_from_input(
+    sqrt_price: u128,
+    liquidity: u128,
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Option<u128> {
+    if amount_in == 0 {
+        return Some(sqrt_price);
+    }
+    if liquidity == 0 {
+        return None;
+    }
+    if zero_for_one {
+        let numerator = U512::from(liquidity).shl_64();
+        let denominator =
+            numerator.add(&U512::from(amount_in as u128).mul(&U512::from(sqrt_price)));
+        numerator
+            .mul(&U512::from(sqrt_price))
+            .div(&denominator, true)?
+            .to_u128()
+    } else {
+        sqrt_price.checked_add(((amount_in as u128) << 64) / liquidity)
+    }
+}
+
+/// 单个价格区间内的兑换结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapStep {
+    /// 兑换后的 sqrt price
+    pub sqrt_price_next: u128,
+    /// 消耗的输入，不含手续费
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+/// 在流动性不变的区间内从 `sqrt_price_current` 向 `sqrt_price_target` 兑换（输入数量固定）
+///
+/// 与链上 `compute_swap_step` 相同：先按费率扣除手续费，能到达目标价格时手续费按实际输入反推，
+/// 否则剩余的输入全部算作手续费。
+///
+/// # 参数
+/// * `math` - 池子所属程序的 tick 数学
+/// * `amount_remaining` - 剩余输入（含手续费）
+/// * `fee_rate` - 手续费率（百万分之一）
+pub fn compute_swap_step(
+    math: TickMath,
+    sqrt_price_current: u128,
+    sqrt_price_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fee_rate: u32,
+) -> Option<SwapStep> {
+    let zero_for_one = sqrt_price_current >= sqrt_price_target;
+    let fee_rate = fee_rate.min(FEE_RATE_DENOMINATOR - 1) as u128;
+    let amount_remaining_less_fee = (amount_remaining as u128
+        * (FEE_RATE_DENOMINATOR as u128 - fee_rate)
+        / FEE_RATE_DENOMINATOR as u128) as u64;
+
+    // 到达目标价格需要的输入，超过 u64 时一定到不了
+    let amount_to_target = if zero_for_one {
+        math.delta_amount_0(sqrt_price_target, sqrt_price_current, liquidity, true)
+    } else {
+        delta_amount_1(sqrt_price_current, sqrt_price_target, liquidity, true)
+    };
+    let sqrt_price_next = match amount_to_target {
+        Some(amount) if amount_remaining_less_fee >= amount => sqrt_price_target,
+        _ => next_sqrt_price_from_input(
+            sqrt_price_current,
+            liquidity,
+            amount_remaining_less_fee,
+            zero_for_one,
+        )?,
+    };
+    let reached_target = sqrt_price_next == sqrt_price_target;
+
+    let (amount_in, amount_out) = if zero_for_one {
+        let amount_in = match amount_to_target {
+            Some(amount) if reached_target => amount,
+            _ => math.delta_amount_0(sqrt_price_next, sqrt_price_current, liquidity, true)?,
+        };
+        let amount_out = delta_amount_1(sqrt_price_next, sqrt_price_current, liquidity, false)?;
+        (amount_in, amount_out)
+    } else {
+        let amount_in = match amount_to_target {
+            Some(amount) if reached_target => amount,
+            _ => delta_amount_1(sqrt_price_current, sqrt_price_next, liquidity, true)?,
+        };
+        let amount_out =
+            math.delta_amount_0(sqrt_price_current, sqrt_price_next, liquidity, false)?;
+        (amount_in, amount_out)
+    };
+
+    let fee = if reached_target {
+        (amount_in as u128 * fee_rate).div_ceil(FEE_RATE_DENOMINATOR as u128 - fee_rate) as u64
+    } else {
+        amount_remaining.checked_sub(amount_in)?
+    };
+    Some(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee,
+    })
+}
+
+/// 已初始化的 tick：穿过时流动性按 `liquidity_net` 变化
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InitializedTick {
+    pub tick: i32,
+    pub liquidity_net: i128,
+}
+
+/// 兑换前的池子状态
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolPrice {
+    pub sqrt_price_x64: u128,
+    pub tick_current: i32,
+    pub liquidity: u128,
+}
+
+/// 按 tick 遍历的兑换结果
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SwapResult {
+    pub amount_out: u64,
+    /// 从输入中扣除的手续费
+    pub fee: u64,
+    /// 穿过的已初始化 tick 数量
+    pub ticks_crossed: u32,
+    /// 最后一步兑换的目标 tick，决定交易需要传入哪些 tick array
+    pub last_tick: i32,
+}
+
+/// 输入固定数量时按 tick 遍历模拟兑换
+///
+/// 每一步从当前价格兑换到兑换方向上的下一个已初始化 tick，穿过 tick 时按 `liquidity_net`
+/// 更新流动性（价格下降时取反），直到输入耗尽。
+///
+/// # 参数
+/// * `math` - 池子所属程序的 tick 数学
+/// * `pool` - 兑换前的价格、tick 和流动性
+/// * `ticks` - 已加载的已初始化 tick，顺序不限
+/// * `boundary` - 已加载的 tick 能保证完整的范围边界：兑换方向上超出这个 tick 的部分没有加载
+/// * `amount_in` - 输入数量（含手续费）
+/// * `zero_for_one` - `true` 表示输入 token 0 换出 token 1（价格下降）
+/// * `fee_rate` - 手续费率（百万分之一）
+///
+/// # 返回值
+/// 换出数量和手续费；输入到达 `boundary` 仍未耗尽，或流动性数据不一致时返回 `None`
+pub fn swap_exact_in(
+    math: TickMath,
+    pool: PoolPrice,
+    ticks: &[InitializedTick],
+    boundary: i32,
+    amount_in: u64,
+    zero_for_one: bool,
+    fee_rate: u32,
+) -> Option<SwapResult> {
+    let boundary = boundary.clamp(MIN_TICK, MAX_TICK);
+    if (zero_for_one && boundary > pool.tick_current)
+        || (!zero_for_one && boundary <= pool.tick_current)
+    {
+        return None;
+    }
+
+    let mut ticks = ticks.to_vec();
+    ticks.sort_by_key(|tick| tick.tick);
+
+    let mut result = SwapResult {
+        last_tick: pool.tick_current,
+        ..Default::default()
+    };
+    let mut sqrt_price = pool.sqrt_price_x64;
+    let mut tick_current = pool.tick_current;
+    let mut liquidity = pool.liquidity;
+    let mut amount_remaining = amount_in;
+    while amount_remaining > 0 {
+        let next = if zero_for_one {
+            ticks
+                .iter()
+                .rev()
+                .find(|tick| tick.tick <= tick_current && tick.tick >= boundary)
+        } else {
+            ticks
+                .iter()
+                .find(|tick| tick.tick > tick_current && tick.tick <= boundary)
+        };
+        let tick_next = next.map_or(boundary, |tick| tick.tick);
+        let sqrt_price_target = math.sqrt_price_at_tick(tick_next)?;
+
+        let step = compute_swap_step(
+            math,
+            sqrt_price,
+            sqrt_price_target,
+            liquidity,
+            amount_remaining,
+            fee_rate,
+        )?;
+        amount_remaining = amount_remaining.checked_sub(step.amount_in.checked_add(step.fee)?)?;
+        result.amount_out = result.amount_out.checked_add(step.amount_out)?;
+        result.fee += step.fee;
+        result.last_tick = tick_next;
+        sqrt_price = step.sqrt_price_next;
+
+        if sqrt_price != sqrt_price_target || amount_remaining == 0 {
+            break;
+        }
+        // 到达边界后仍有剩余输入：后面的 tick 没有加载
+        let tick = next?;
+        liquidity = if zero_for_one {
+            liquidity.checked_add_signed(tick.liquidity_net.checked_neg()?)?
+        } else {
+            liquidity.checked_add_signed(tick.liquidity_net)?
+        };
+        tick_current = if zero_for_one {
+            tick.tick - 1
+        } else {
+            tick.tick
+        };
+        result.ticks_crossed += 1;
+    }
+    Some(result)
+}
@@ -1,8 +1,278 @@
+use crate::dex::moonshot::constants::{
+    MOONSHOT_CONFIG, MOONSHOT_DEX_FEE, MOONSHOT_HELIO_FEE, MOONSHOT_PROGRAM_ID,
+};
+use crate::dex::perena::constants::PERENA_PROGRAM_ID;
+use crate::dex::pump::constants::{
+    PUMP_EVENT_AUTHORITY, PUMP_FEE_WALLET, PUMP_GLOBAL_CONFIG, PUMP_PROGRAM_ID,
+};
+use crate::dex::whirlpool::constants::WHIRLPOOL_PROGRAM_ID;
+use crate::flashloan::SOLEND_PROGRAM_ID;
+use crate::inline_swap::LIGHTHOUSE_PROGRAM_ID;
 use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::{LazyLock, OnceLock};
 
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
 
-pub fn sol_mint() -> Pubkey {
-    Pubkey::from_str(SOL_MINT).unwrap()
+/// 注册表中的地址：程序 ID、手续费账户、PDA 和报价货币 mint
+///
+/// 取值是 `PROGRAM_ID_TABLE` 中的下标，两者顺序必须一致。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgramId {
+    SolMint,
+    UsdcMint,
+    UsdtMint,
+    Pump,
+    PumpFeeWallet,
+    PumpGlobalConfig,
+    PumpEventAuthority,
+    Raydium,
+    RaydiumAuthority,
+    RaydiumCp,
+    RaydiumCpAuthority,
+    RaydiumClmm,
+    MeteoraDlmm,
+    MeteoraDlmmEventAuthority,
+    MeteoraDamm,
+    MeteoraVault,
+    MeteoraDammV2,
+    MeteoraDammV2EventAuthority,
+    MeteoraDammV2PoolAuthority,
+    Whirlpool,
+    Solfi,
+    Vertigo,
+    Moonshot,
+    MoonshotDexFee,
+    MoonshotHelioFee,
+    MoonshotConfig,
+    Perena,
+    Zerofi,
+    Solend,
+    Lighthouse,
+}
+
+const PROGRAM_ID_COUNT: usize = ProgramId::Lighthouse as usize + 1;
+
+/// 每个地址的名称（`[cluster.program_ids]` 和覆盖文件中使用）与主网默认值，按 `ProgramId` 排列
+#[rustfmt::skip]
+const PROGRAM_ID_TABLE: [(&str, &str); PROGRAM_ID_COUNT] = [
+    ("sol_mint", SOL_MINT),
+    ("usdc_mint", USDC_MINT),
+    ("usdt_mint", USDT_MINT),
+    ("pump", PUMP_PROGRAM_ID),
+    ("pump_fee_wallet", PUMP_FEE_WALLET),
+    ("pump_global_config", PUMP_GLOBAL_CONFIG),
+    ("pump_event_authority", PUMP_EVENT_AUTHORITY),
+    ("raydium", "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"),
+    ("raydium_authority", "5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+    ("raydium_cp", "CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C"),
+    ("raydium_cp_authority", "GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL"),
+    ("raydium_clmm", "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK"),
+    ("meteora_dlmm", "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo"),
+    ("meteora_dlmm_event_authority", "D1ZN9Wj1fRSUQfCjhvnu1hqDMT7hzjzBBpi12nVniYD6"),
+    ("meteora_damm", "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB"),
+    ("meteora_vault", "24Uqj9JCLxUeoC3hGfh5W3s9FM9uCHDS2SG3LYwBpyTi"),
+    ("meteora_damm_v2", "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG"),
+    ("meteora_damm_v2_event_authority", "3rmHSu74h1ZcmAisVcWerTCiRDQbUrBKmcwptYGjHfet"),
+    ("meteora_damm_v2_pool_authority", "HLnpSz9h2S4hiLQ43rnSD9XkcUThA7B8hQMKmDaiTLcC"),
+    ("whirlpool", WHIRLPOOL_PROGRAM_ID),
+    ("solfi", "SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe"),
+    ("vertigo", "vrTGoBuy5rYSxAfV3jaRJWHH6nN9WK4NRExGxsk1bCJ"),
+    ("moonshot", MOONSHOT_PROGRAM_ID),
+    ("moonshot_dex_fee", MOONSHOT_DEX_FEE),
+    ("moonshot_helio_fee", MOONSHOT_HELIO_FEE),
+    ("moonshot_config", MOONSHOT_CONFIG),
+    ("perena", PERENA_PROGRAM_ID),
+    ("zerofi", "ZERor4xhbUycZ6gb9ntrhqscUcZmAbQDjEAtCf4hbZY"),
+    ("solend", SOLEND_PROGRAM_ID),
+    ("lighthouse", LIGHTHOUSE_PROGRAM_ID),
+];
+
+impl ProgramId {
+    /// `[cluster.program_ids]` 和覆盖文件中使用的名称
+    pub fn name(self) -> &'static str {
+        PROGRAM_ID_TABLE[self as usize].0
+    }
+
+    /// 主网默认地址
+    pub fn mainnet_address(self) -> &'static str {
+        PROGRAM_ID_TABLE[self as usize].1
+    }
+}
+
+/// 可以覆盖的地址名称
+pub fn program_id_names() -> impl Iterator<Item = &'static str> {
+    PROGRAM_ID_TABLE.iter().map(|(name, _)| *name)
+}
+
+/// 按 `ProgramId` 索引的地址表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramIds([Pubkey; PROGRAM_ID_COUNT]);
+
+impl ProgramIds {
+    /// 全部使用主网默认地址
+    pub fn mainnet() -> Self {
+        Self(PROGRAM_ID_TABLE.map(|(_, address)| Pubkey::from_str(address).unwrap()))
+    }
+
+    /// 在主网默认地址上应用覆盖项
+    ///
+    /// # 错误
+    /// 覆盖项的名称不在注册表中时返回错误
+    pub fn with_overrides(overrides: &HashMap<String, Pubkey>) -> anyhow::Result<Self> {
+        let mut ids = Self::mainnet();
+        for (name, pubkey) in overrides {
+            let index = program_id_names()
+                .position(|known| known == name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Unknown program id override `{}`, expected one of: {}",
+                        name,
+                        program_id_names().collect::<Vec<_>>().join(", ")
+                    )
+                })?;
+            ids.0[index] = *pubkey;
+        }
+        Ok(ids)
+    }
+
+    pub fn get(&self, id: ProgramId) -> &Pubkey {
+        &self.0[id as usize]
+    }
+}
+
+/// 主网默认地址，第一次访问时解析
+static MAINNET_PROGRAM_IDS: LazyLock<ProgramIds> = LazyLock::new(ProgramIds::mainnet);
+
+/// 启动时应用覆盖项后构造的注册表，未设置时使用 `MAINNET_PROGRAM_IDS`
+static PROGRAM_IDS: OnceLock<ProgramIds> = OnceLock::new();
+
+/// 返回各集群预设的地址覆盖项
+///
+/// - `mainnet`: 不覆盖任何地址
+/// - `localnet`: solana-test-validator 通过 `--clone` 克隆的程序保留主网地址，因此同样不覆盖
+/// - `devnet`: Raydium 在 devnet 上使用独立部署的程序，USDC 使用 devnet 的 mint
+///
+/// # 参数
+/// * `cluster` - 集群名称
+///
+/// # 返回值
+/// 返回 (名称, 地址) 列表，未知的集群名称返回错误
+pub fn cluster_preset(cluster: &str) -> anyhow::Result<Vec<(&'static str, &'static str)>> {
+    match cluster {
+        "mainnet" | "mainnet-beta" | "localnet" => Ok(Vec::new()),
+        "devnet" => Ok(vec![
+            ("raydium", "HWy1jotHpo6UqeQxx49dpYYdQB8wj9Qk9MdxwjLvDHB8"),
+            ("raydium_cp", "CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW"),
+            (
+                "raydium_clmm",
+                "devi51mZmdwUJGU9hjN27vEz64Gps7uUefqxg27EAtH",
+            ),
+            ("usdc_mint", "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"),
+        ]),
+        _ => Err(anyhow::anyhow!(
+            "Unknown cluster `{}`, expected one of: mainnet, devnet, localnet",
+            cluster
+        )),
+    }
+}
+
+/// 应用覆盖项构造全局地址注册表，只能在启动时调用一次
+///
+/// # 错误
+/// 覆盖项的名称未知或注册表已经构造过时返回错误
+pub fn init_program_id_overrides(overrides: HashMap<String, Pubkey>) -> anyhow::Result<()> {
+    PROGRAM_IDS
+        .set(ProgramIds::with_overrides(&overrides)?)
+        .map_err(|_| anyhow::anyhow!("Program ID overrides have already been initialized"))
+}
+
+/// 获取注册表中的地址：启动时配置了覆盖则返回覆盖值，否则返回主网默认值
+///
+/// 地址只在构造注册表时解析一次，热路径上的访问只是一次数组下标。
+pub fn program_id(id: ProgramId) -> &'static Pubkey {
+    PROGRAM_IDS
+        .get()
+        .unwrap_or_else(|| &MAINNET_PROGRAM_IDS)
+        .get(id)
+}
+
+pub fn sol_mint() -> &'static Pubkey {
+    program_id(ProgramId::SolMint)
+}
+
+pub fn usdc_mint() -> &'static Pubkey {
+    program_id(ProgramId::UsdcMint)
+}
+
+pub fn usdt_mint() -> &'static Pubkey {
+    program_id(ProgramId::UsdtMint)
+}
+
+/// 可以作为报价货币（计价单位）的 mint
+///
+/// 代币配置的 `quote_mint` 选择其中之一，池子必须是代币与该货币的交易对，
+/// 交易规模、报价和链上程序的收益都以该货币的最小单位计。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CashMint {
+    /// 名称，例如 `SOL`、`USDC`
+    pub symbol: &'static str,
+    pub mint: Pubkey,
+    pub decimals: u8,
+}
+
+impl CashMint {
+    pub fn sol() -> Self {
+        Self {
+            symbol: "SOL",
+            mint: *sol_mint(),
+            decimals: 9,
+        }
+    }
+
+    /// 是否为 SOL；只有 SOL 需要包装成 WSOL，也只有 SOL 能用 Solend 闪电贷借入
+    pub fn is_sol(&self) -> bool {
+        self.mint == *sol_mint()
+    }
+
+    /// 把以货币为单位的数量换算成最小单位，例如 1.5 USDC 为 1_500_000
+    pub fn to_base_units(&self, amount: f64) -> u64 {
+        (amount * 10f64.powi(self.decimals as i32)).round() as u64
+    }
+
+    /// 把最小单位换算成以货币为单位的数量，收益可能为负
+    pub fn from_base_units(&self, amount: i64) -> f64 {
+        amount as f64 / 10f64.powi(self.decimals as i32)
+    }
+}
+
+/// 支持的报价货币：SOL、USDC 和 USDT
+pub fn cash_mints() -> [CashMint; 3] {
+    [
+        CashMint::sol(),
+        CashMint {
+            symbol: "USDC",
+            mint: *usdc_mint(),
+            decimals: 6,
+        },
+        CashMint {
+            symbol: "USDT",
+            mint: *usdt_mint(),
+            decimals: 6,
+        },
+    ]
+}
+
+/// 按名称（不区分大小写）或 mint 地址查找报价货币
+///
+/// # 返回值
+/// 不是支持的报价货币时返回 `None`
+pub fn cash_mint(name_or_address: &str) -> Option<CashMint> {
+    cash_mints().into_iter().find(|cash| {
+        cash.symbol.eq_ignore_ascii_case(name_or_address)
+            || cash.mint.to_string() == name_or_address
+    })
 }
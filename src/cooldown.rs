@@ -0,0 +1,59 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+/// 单个代币的连续失败退避
+///
+/// 发送失败、交易上链失败或超时未上链都算一次失败，成功上链后清零。连续失败达到阈值后，
+/// 每轮的等待时间从 `base_delay`（代币的 `process_delay`）开始逐次翻倍，直到 `max_delay`。
+#[derive(Debug)]
+pub struct FailureCooldown {
+    threshold: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    failures: AtomicU32,
+}
+
+impl FailureCooldown {
+    /// # 参数
+    /// * `threshold` - 连续失败多少次后开始退避，至少为 1
+    /// * `base_delay` - 没有退避时每轮的等待时间
+    /// * `max_delay` - 退避等待时间的上限
+    pub fn new(threshold: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            threshold: threshold.max(1),
+            base_delay,
+            max_delay,
+            failures: AtomicU32::new(0),
+        }
+    }
+
+    /// 当前的连续失败次数
+    pub fn failures(&self) -> u32 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// 记录一次失败
+    ///
+    /// # 返回值
+    /// 这次失败让代币进入退避时返回 `true`，用于只告警一次
+    pub fn record_failure(&self) -> bool {
+        self.failures.fetch_add(1, Ordering::Relaxed) + 1 == self.threshold
+    }
+
+    /// 记录一次成功，结束退避
+    pub fn record_success(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+    }
+
+    /// 本轮结束后的等待时间
+    pub fn delay(&self) -> Duration {
+        let failures = self.failures();
+        if failures < self.threshold {
+            return self.base_delay;
+        }
+        let doublings = (failures - self.threshold + 1).min(31);
+        self.base_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay.max(self.base_delay))
+    }
+}
@@ -0,0 +1,371 @@
+use crate::dex::pool_program_id;
+use crate::pools::MintPoolData;
+use crate::quote::{round_trip_out, PoolReserves, SizedQuote};
+use futures::StreamExt;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcBlockSubscribeConfig, RpcBlockSubscribeFilter};
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    TransactionDetails, UiInstruction, UiTransactionEncoding, UiTransactionStatusMeta,
+};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info};
+
+/// 区块订阅断开后重新连接前的等待时间
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// 从其他机器人的套利交易中还原出的路线
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopiedRoute {
+    pub mint: Pubkey,
+    /// 交易依次兑换的池子，至少两个
+    pub pools: Vec<Pubkey>,
+    /// 投入第一个池子的报价货币（默认 SOL，单位为最小单位），由池子报价货币 vault 的余额变化得出；
+    /// 第一个池子不是用报价货币买入，或者没有登记 vault（Meteora DAMM v1）时为 `None`
+    pub size: Option<u64>,
+    /// 交易的付款人
+    pub signer: Pubkey,
+    pub signature: Signature,
+    pub slot: Slot,
+}
+
+/// 登记的池子
+struct RegisteredPool {
+    mint: Pubkey,
+    /// 池子所属 DEX 的程序，只有调用这个程序的指令才算经过了池子
+    program_id: Pubkey,
+    /// 报价货币一侧的 vault，买入时余额的增加量就是投入规模
+    vaults: Vec<Pubkey>,
+}
+
+struct CopiedEntry {
+    route: CopiedRoute,
+    seen_at: Instant,
+}
+
+/// 跟单路线簿
+///
+/// 登记所有代币的池子后，区块订阅任务把每笔成功的外部交易交给 `detect`，
+/// 同一个代币经过两个及以上已配置池子的交易记为一条候选路线。
+/// 发送任务每轮用 `routes` 取出还在有效期内的路线，和自己的报价一起比较。
+pub struct CopyRouteBook {
+    pools: RwLock<HashMap<Pubkey, RegisteredPool>>,
+    /// 代币 -> 候选路线，最新的在前
+    routes: RwLock<HashMap<Pubkey, Vec<CopiedEntry>>>,
+    ttl: Duration,
+    max_routes: usize,
+}
+
+impl CopyRouteBook {
+    /// # 参数
+    /// * `ttl` - 候选路线的有效期
+    /// * `max_routes` - 每个代币最多保留的候选路线数
+    pub fn new(ttl: Duration, max_routes: usize) -> Self {
+        Self {
+            pools: RwLock::new(HashMap::new()),
+            routes: RwLock::new(HashMap::new()),
+            ttl,
+            max_routes,
+        }
+    }
+
+    /// 登记一个代币的所有池子及其 vault
+    pub fn register(&self, pool_data: &MintPoolData) {
+        let mut vaults: HashMap<Pubkey, Vec<Pubkey>> = HashMap::new();
+        for (pool, _, sol_vault) in pool_data.pool_vaults() {
+            vaults.insert(pool, vec![sol_vault]);
+        }
+
+        let mut pools = self.pools.write().unwrap();
+        for (dex, pool) in pool_data.pool_addresses() {
+            let Some(program_id) = pool_program_id(dex) else {
+                continue;
+            };
+            pools.insert(
+                pool,
+                RegisteredPool {
+                    mint: pool_data.mint,
+                    program_id,
+                    vaults: vaults.remove(&pool).unwrap_or_default(),
+                },
+            );
+        }
+    }
+
+    /// 从一笔交易中找出经过已登记池子的套利路线
+    ///
+    /// 按指令顺序（每条外层指令之后是它的内层指令）记录调用池子所属 DEX 程序的指令中第一次出现的池子，
+    /// 只传入池子地址而不调用 DEX 的指令（例如把所有池子交给链上程序自行选择）不算。
+    ///
+    /// # 参数
+    /// * `tx` - 已解码的交易
+    /// * `meta` - 交易的执行结果，需要 base64 编码下的内层指令和代币余额
+    /// * `slot` - 交易所在的 slot
+    ///
+    /// # 返回值
+    /// 每个经过两个及以上池子的代币一条路线，失败的交易没有路线
+    pub fn detect(
+        &self,
+        tx: &VersionedTransaction,
+        meta: &UiTransactionStatusMeta,
+        slot: Slot,
+    ) -> Vec<CopiedRoute> {
+        if meta.err.is_some() {
+            return Vec::new();
+        }
+        let Some(keys) = account_keys(tx, meta) else {
+            return Vec::new();
+        };
+
+        let pools = self.pools.read().unwrap();
+        let mut touched: Vec<Pubkey> = Vec::new();
+        let mut visit = |program_id_index: u8, accounts: &[u8]| {
+            let Some(program_id) = keys.get(program_id_index as usize) else {
+                return;
+            };
+            for key in accounts.iter().filter_map(|&i| keys.get(i as usize)) {
+                let calls_pool = pools
+                    .get(key)
+                    .is_some_and(|pool| pool.program_id == *program_id);
+                if calls_pool && !touched.contains(key) {
+                    touched.push(*key);
+                }
+            }
+        };
+        let inner_instructions = match &meta.inner_instructions {
+            OptionSerializer::Some(inner_instructions) => inner_instructions.as_slice(),
+            _ => &[],
+        };
+        for (i, instruction) in tx.message.instructions().iter().enumerate() {
+            visit(instruction.program_id_index, &instruction.accounts);
+            for inner in inner_instructions
+                .iter()
+                .filter(|inner| inner.index as usize == i)
+            {
+                for instruction in &inner.instructions {
+                    if let UiInstruction::Compiled(instruction) = instruction {
+                        visit(instruction.program_id_index, &instruction.accounts);
+                    }
+                }
+            }
+        }
+
+        let mut routes: Vec<CopiedRoute> = Vec::new();
+        for pool in touched {
+            let mint = pools[&pool].mint;
+            match routes.iter_mut().find(|route| route.mint == mint) {
+                Some(route) => route.pools.push(pool),
+                None => routes.push(CopiedRoute {
+                    mint,
+                    pools: vec![pool],
+                    size: None,
+                    signer: keys[0],
+                    signature: tx.signatures.first().copied().unwrap_or_default(),
+                    slot,
+                }),
+            }
+        }
+        routes.retain(|route| route.pools.len() >= 2);
+
+        let deltas = token_deltas(&keys, meta);
+        for route in &mut routes {
+            route.size = pools[&route.pools[0]]
+                .vaults
+                .iter()
+                .filter_map(|vault| deltas.get(vault))
+                .filter(|delta| **delta > 0)
+                .max()
+                .map(|delta| *delta as u64);
+        }
+        routes
+    }
+
+    /// 记录一条候选路线，替换同一组池子之前的记录
+    pub fn record(&self, route: CopiedRoute) {
+        let mut routes = self.routes.write().unwrap();
+        let entries = routes.entry(route.mint).or_default();
+        entries
+            .retain(|entry| entry.route.pools != route.pools && entry.seen_at.elapsed() < self.ttl);
+        entries.insert(
+            0,
+            CopiedEntry {
+                route,
+                seen_at: Instant::now(),
+            },
+        );
+        entries.truncate(self.max_routes);
+    }
+
+    /// 一个代币还在有效期内的候选路线，最新的在前
+    pub fn routes(&self, mint: &Pubkey) -> Vec<CopiedRoute> {
+        self.routes
+            .read()
+            .unwrap()
+            .get(mint)
+            .into_iter()
+            .flatten()
+            .filter(|entry| entry.seen_at.elapsed() < self.ttl)
+            .map(|entry| entry.route.clone())
+            .collect()
+    }
+}
+
+/// 交易的完整账户列表：静态账户之后依次是查找表加载的可写、只读账户
+fn account_keys(tx: &VersionedTransaction, meta: &UiTransactionStatusMeta) -> Option<Vec<Pubkey>> {
+    let mut keys = tx.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(Pubkey::from_str(address).ok()?);
+        }
+    }
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// 交易前后每个代币账户的余额变化
+fn token_deltas(keys: &[Pubkey], meta: &UiTransactionStatusMeta) -> HashMap<Pubkey, i128> {
+    let mut deltas: HashMap<Pubkey, i128> = HashMap::new();
+    for (balances, sign) in [
+        (&meta.pre_token_balances, -1),
+        (&meta.post_token_balances, 1),
+    ] {
+        let OptionSerializer::Some(balances) = balances else {
+            continue;
+        };
+        for balance in balances.iter() {
+            let (Some(key), Ok(amount)) = (
+                keys.get(balance.account_index as usize),
+                balance.ui_token_amount.amount.parse::<i128>(),
+            ) else {
+                continue;
+            };
+            *deltas.entry(*key).or_default() += sign * amount;
+        }
+    }
+    deltas
+}
+
+/// 按当前储备给候选路线报价，返回收益最高的一条
+///
+/// 路线在第一个池子买入、最后一个池子卖出，投入与被跟单的交易相同；
+/// 没有投入规模、池子已被移除或不符合方向限制的路线跳过。
+pub fn quote_routes(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    routes: &[CopiedRoute],
+) -> Option<SizedQuote> {
+    routes
+        .iter()
+        .filter_map(|route| {
+            let size = route.size?;
+            let (buy_pool, sell_pool) = (*route.pools.first()?, *route.pools.last()?);
+            if buy_pool == sell_pool
+                || !pool_data.direction(&buy_pool).can_buy()
+                || !pool_data.direction(&sell_pool).can_sell()
+            {
+                return None;
+            }
+            let (buy, sell) = (reserves.get(&buy_pool)?, reserves.get(&sell_pool)?);
+            Some(SizedQuote {
+                size,
+                buy_pool,
+                sell_pool,
+                profit: round_trip_out(pool_data, buy, sell, size) as i64 - size as i64,
+            })
+        })
+        .max_by_key(|quote| quote.profit)
+}
+
+/// 在选出的池子之外保留候选路线经过的池子
+///
+/// # 参数
+/// * `pool_data` - 代币的全部池子
+/// * `selected` - `quote::select_top_pools` 选出的池子
+pub fn include_routes(
+    pool_data: &MintPoolData,
+    selected: &MintPoolData,
+    routes: &[CopiedRoute],
+) -> MintPoolData {
+    let keep: HashSet<Pubkey> = selected
+        .pool_addresses()
+        .into_iter()
+        .map(|(_, pool)| pool)
+        .chain(routes.iter().flat_map(|route| route.pools.iter().copied()))
+        .collect();
+    let mut pools = pool_data.clone();
+    pools.retain_pools(|pool| keep.contains(pool));
+    pools.pool_edges = selected.pool_edges.clone();
+    pools
+}
+
+/// 订阅已确认的区块，把其他机器人经过已配置池子的套利交易记入路线簿
+///
+/// 订阅断开后等待几秒重新连接。
+///
+/// # 参数
+/// * `websocket_url` - 节点的 websocket 地址，节点需要开启 `blockSubscribe`
+/// * `wallet` - 机器人自己的钱包，自己发送的交易不记录
+pub async fn run(book: Arc<CopyRouteBook>, websocket_url: String, wallet: Pubkey) {
+    loop {
+        if let Err(e) = watch_blocks(&book, &websocket_url, &wallet).await {
+            error!("Copy-route block subscription failed: {}", e);
+        }
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn watch_blocks(
+    book: &CopyRouteBook,
+    websocket_url: &str,
+    wallet: &Pubkey,
+) -> anyhow::Result<()> {
+    let client = PubsubClient::new(websocket_url).await?;
+    let (mut blocks, unsubscribe) = client
+        .block_subscribe(
+            RpcBlockSubscribeFilter::All,
+            Some(RpcBlockSubscribeConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::Full),
+                show_rewards: Some(false),
+                max_supported_transaction_version: Some(0),
+            }),
+        )
+        .await?;
+    info!("Watching blocks for copyable routes on {}", websocket_url);
+
+    while let Some(update) = blocks.next().await {
+        let slot = update.value.slot;
+        let Some(block) = update.value.block else {
+            continue;
+        };
+        for tx in block.transactions.into_iter().flatten() {
+            let (Some(meta), Some(decoded)) = (tx.meta, tx.transaction.decode()) else {
+                continue;
+            };
+            if decoded.message.static_account_keys().first() == Some(wallet) {
+                continue;
+            }
+            for route in book.detect(&decoded, &meta, slot) {
+                debug!(
+                    "Copied route for mint {} from {} ({} pools, size {:?})",
+                    route.mint,
+                    route.signature,
+                    route.pools.len(),
+                    route.size
+                );
+                book.record(route);
+            }
+        }
+    }
+
+    unsubscribe().await;
+    Err(anyhow::anyhow!("Block subscription closed"))
+}
@@ -0,0 +1,138 @@
+use crate::rpc::RpcMetrics;
+use crate::status::{BotStatus, StatusSnapshot};
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use std::sync::Arc;
+use tracing::info;
+
+/// 启动仪表盘 HTTP 服务
+///
+/// - `GET /`: HTML 仪表盘，每 2 秒轮询一次状态 API
+/// - `GET /api/status`: JSON 状态
+/// - `GET /metrics`: Prometheus 格式的 RPC 请求统计，未启用 `[rpc.metrics]` 时返回 404
+///
+/// # 参数
+/// * `bind_address` - 监听地址，例如 `127.0.0.1:8080`
+/// * `status` - 共享的机器人运行状态
+/// * `rpc_metrics` - RPC 请求统计
+pub async fn serve(
+    bind_address: &str,
+    status: Arc<BotStatus>,
+    rpc_metrics: Option<Arc<RpcMetrics>>,
+) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/", get(index))
+        .route("/api/status", get(api_status))
+        .route("/metrics", get(metrics))
+        .with_state(DashboardState {
+            status,
+            rpc_metrics,
+        });
+
+    let listener = tokio::net::TcpListener::bind(bind_address).await?;
+    info!("Dashboard listening on http://{}", listener.local_addr()?);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[derive(Clone)]
+struct DashboardState {
+    status: Arc<BotStatus>,
+    rpc_metrics: Option<Arc<RpcMetrics>>,
+}
+
+async fn api_status(State(dashboard): State<DashboardState>) -> Json<StatusSnapshot> {
+    Json(dashboard.status.snapshot())
+}
+
+async fn metrics(State(dashboard): State<DashboardState>) -> Response {
+    match &dashboard.rpc_metrics {
+        Some(rpc_metrics) => (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            rpc_metrics.render_prometheus(),
+        )
+            .into_response(),
+        None => (StatusCode::NOT_FOUND, "RPC metrics are disabled").into_response(),
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+const DASHBOARD_HTML: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Arbitrage Bot</title>
+<style>
+  body { font-family: monospace; margin: 2em; background: #111; color: #ddd; }
+  h1, h2 { color: #fff; }
+  table { border-collapse: collapse; margin-bottom: 1.5em; }
+  td, th { border: 1px solid #444; padding: 4px 8px; text-align: left; }
+  .ok { color: #4c4; } .bad { color: #e44; } .pending { color: #cc4; }
+  a { color: #6af; }
+</style>
+</head>
+<body>
+<h1>Arbitrage Bot</h1>
+<div id="summary"></div>
+<h2>Transactions</h2>
+<table id="transactions"></table>
+<h2>Route Fees</h2>
+<table id="route_fees"></table>
+<h2>Mints</h2>
+<div id="mints"></div>
+<script>
+const sol = (lamports) => (lamports / 1e9).toFixed(9);
+let solUsd = null;
+const usd = (lamports) => solUsd != null ? ` ($${(lamports / 1e9 * solUsd).toFixed(2)})` : "";
+const time = (secs) => secs ? new Date(secs * 1000).toLocaleTimeString() : "-";
+const outcome = (o) => {
+  switch (o.status) {
+    case "landed": return `<span class="ok">landed ${sol(o.profit_lamports)} SOL${usd(o.profit_lamports)}</span>`;
+    case "sent": return `<span class="pending">sent</span>`;
+    case "simulated": return `<span class="pending">simulated</span>`;
+    case "not_landed": return `<span class="bad">not landed</span>`;
+    default: return `<span class="bad">send failed: ${o.error}</span>`;
+  }
+};
+async function refresh() {
+  const s = await (await fetch("/api/status")).json();
+  solUsd = s.sol_usd_price;
+  const rpc = s.rpc.healthy
+    ? `<span class="ok">healthy</span>`
+    : `<span class="bad">${s.rpc.consecutive_failures} failures: ${s.rpc.last_error}</span>`;
+  document.getElementById("summary").innerHTML = `
+    <table>
+      <tr><th>Started</th><td>${new Date(s.started_at * 1000).toLocaleString()}${s.dry_run ? " (dry-run)" : ""}</td></tr>
+      <tr><th>Realized PnL</th><td>${sol(s.realized_pnl_lamports)} SOL${usd(s.realized_pnl_lamports)} (${s.landed_count} landed)</td></tr>
+      <tr><th>SOL price</th><td>${solUsd != null ? "$" + solUsd.toFixed(2) : "-"}</td></tr>
+      <tr><th>Priority fee</th><td>${s.priority_fee_micro_lamports} micro-lamports/CU</td></tr>
+      <tr><th>RPC</th><td>${s.rpc.url} ${rpc} (last ok ${time(s.rpc.last_success)})</td></tr>
+    </table>`;
+  document.getElementById("transactions").innerHTML =
+    "<tr><th>Time</th><th>Mint</th><th>Signature</th><th>Outcome</th></tr>" +
+    s.recent_transactions.map((t) => `<tr><td>${time(t.timestamp)}</td><td>${t.mint}</td>
+      <td>${t.signature ? `<a href="https://solscan.io/tx/${t.signature}">${t.signature.slice(0, 16)}…</a>` : "-"}</td>
+      <td>${outcome(t.outcome)}</td></tr>`).join("");
+  const routes = Object.entries(s.route_fees);
+  document.getElementById("route_fees").innerHTML = routes.length === 0 ? "" :
+    "<tr><th>Route</th><th>Price (micro-lamports/CU)</th><th>Landed</th></tr>" +
+    routes.map(([route, r]) => `<tr><td>${route}</td><td>${r.compute_unit_price}</td>
+      <td>${r.landed}/${r.sent} (${(r.landed / r.sent * 100).toFixed(0)}%)</td></tr>`).join("");
+  document.getElementById("mints").innerHTML = Object.entries(s.mints).map(([mint, m]) =>
+    `<h3>${mint} <small>refreshed ${time(m.last_refresh)}</small></h3><table>` +
+    m.pools.map((p) => `<tr><td>${p.dex}</td><td>${p.pool}</td>
+      <td>${p.token_balance ?? "-"}</td><td>${p.sol_balance != null ? sol(p.sol_balance) + " SOL" : "-"}</td></tr>`).join("") +
+    "</table>").join("");
+}
+refresh();
+setInterval(refresh, 2000);
+</script>
+</body>
+</html>
+"#;
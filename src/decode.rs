@@ -0,0 +1,182 @@
+use crate::dex::discriminator::check_anchor_discriminator;
+use crate::dex::meteora::constants::{
+    damm_program_id, damm_v2_program_id, dlmm_program_id, vault_program_id,
+};
+use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::meteora::vault_info::MeteoraVaultInfo;
+use crate::dex::moonshot::{moonshot_program_id, MoonshotCurve};
+use crate::dex::perena::{perena_program_id, PerenaPoolInfo};
+use crate::dex::pump::{pump_program_id, PumpAmmInfo};
+use crate::dex::raydium::{
+    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id, OpenBookMarket, PoolState,
+    RaydiumAmmInfo, RaydiumCpAmmInfo,
+};
+use crate::dex::solfi::constants::solfi_program_id;
+use crate::dex::solfi::info::SolfiInfo;
+use crate::dex::vertigo::{vertigo_program_id, VertigoInfo};
+use crate::dex::whirlpool::constants::whirlpool_program_id;
+use crate::dex::whirlpool::state::Whirlpool;
+use crate::dex::zerofi::{zerofi_program_id, ZeroFiInfo};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+
+/// 按支持的布局解析出的账户
+pub enum DecodedAccount {
+    RaydiumAmm(RaydiumAmmInfo),
+    RaydiumCp(RaydiumCpAmmInfo),
+    RaydiumClmm(Box<PoolState>),
+    OpenBookMarket(OpenBookMarket),
+    Pump(PumpAmmInfo),
+    MeteoraDlmm(Box<DlmmInfo>),
+    MeteoraDamm(Box<meteora_damm_cpi::Pool>),
+    MeteoraDammV2(MeteoraDAmmV2Info),
+    MeteoraVault(MeteoraVaultInfo),
+    Whirlpool(Box<Whirlpool>),
+    Solfi(SolfiInfo),
+    Vertigo(VertigoInfo),
+    Moonshot(MoonshotCurve),
+    Perena(PerenaPoolInfo),
+    ZeroFi(ZeroFiInfo),
+}
+
+impl DecodedAccount {
+    /// 布局名称，DEX 名称与配置中的池子列表一致
+    pub fn layout(&self) -> &'static str {
+        match self {
+            DecodedAccount::RaydiumAmm(_) => "raydium (AMM v4 pool)",
+            DecodedAccount::RaydiumCp(_) => "raydium_cp (pool)",
+            DecodedAccount::RaydiumClmm(_) => "raydium_clmm (pool)",
+            DecodedAccount::OpenBookMarket(_) => "OpenBook market",
+            DecodedAccount::Pump(_) => "pump (pool)",
+            DecodedAccount::MeteoraDlmm(_) => "meteora_dlmm (lb pair)",
+            DecodedAccount::MeteoraDamm(_) => "meteora_damm (pool)",
+            DecodedAccount::MeteoraDammV2(_) => "meteora_damm_v2 (pool)",
+            DecodedAccount::MeteoraVault(_) => "Meteora vault",
+            DecodedAccount::Whirlpool(_) => "whirlpool (pool)",
+            DecodedAccount::Solfi(_) => "solfi (pool)",
+            DecodedAccount::Vertigo(_) => "vertigo (pool)",
+            DecodedAccount::Moonshot(_) => "moonshot (curve)",
+            DecodedAccount::Perena(_) => "perena (pool)",
+            DecodedAccount::ZeroFi(_) => "zerofi (pair)",
+        }
+    }
+}
+
+impl fmt::Display for DecodedAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodedAccount::RaydiumAmm(info) => write!(f, "{:#?}", info),
+            DecodedAccount::RaydiumCp(info) => write!(f, "{:#?}", info),
+            DecodedAccount::RaydiumClmm(info) => write!(f, "{:#?}", info),
+            DecodedAccount::OpenBookMarket(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Pump(info) => write!(f, "{:#?}", info),
+            DecodedAccount::MeteoraDlmm(info) => write!(f, "{:#?}", info),
+            // IDL 生成的结构体只打印加载池子时用到的字段
+            DecodedAccount::MeteoraDamm(pool) => {
+                writeln!(f, "Pool {{")?;
+                for (name, value) in [
+                    ("lp_mint", pool.lp_mint),
+                    ("token_a_mint", pool.token_a_mint),
+                    ("token_b_mint", pool.token_b_mint),
+                    ("a_vault", pool.a_vault),
+                    ("b_vault", pool.b_vault),
+                    ("a_vault_lp", pool.a_vault_lp),
+                    ("b_vault_lp", pool.b_vault_lp),
+                    ("admin_token_a_fee", pool.admin_token_a_fee),
+                    ("admin_token_b_fee", pool.admin_token_b_fee),
+                ] {
+                    writeln!(f, "    {}: {},", name, value)?;
+                }
+                writeln!(f, "    enabled: {},", pool.enabled)?;
+                writeln!(
+                    f,
+                    "    trade_fee: {} / {},",
+                    pool.fees.trade_fee_numerator, pool.fees.trade_fee_denominator
+                )?;
+                write!(f, "}}")
+            }
+            DecodedAccount::MeteoraDammV2(info) => write!(f, "{:#?}", info),
+            DecodedAccount::MeteoraVault(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Whirlpool(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Solfi(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Vertigo(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Moonshot(info) => write!(f, "{:#?}", info),
+            DecodedAccount::Perena(info) => write!(f, "{:#?}", info),
+            DecodedAccount::ZeroFi(info) => write!(f, "{:#?}", info),
+        }
+    }
+}
+
+/// 按所有者判断账户属于哪种支持的布局并解析，各解析器会核对类型标识或账户长度
+///
+/// OpenBook 市场的所有者随市场程序版本变化，所以不看所有者，只看数据开头的 "serum" 填充。
+///
+/// # 参数
+/// * `pubkey` - 账户地址，Vertigo 池子的 vault 由池子地址推导
+/// * `account` - 账户内容
+///
+/// # 错误
+/// 所有者不是支持的 DEX 程序、类型标识不匹配或数据无法解析时返回错误
+pub fn decode_account(pubkey: &Pubkey, account: &Account) -> anyhow::Result<DecodedAccount> {
+    let data = account.data.as_slice();
+    let owner = account.owner;
+
+    let decoded = if owner == *raydium_program_id() {
+        DecodedAccount::RaydiumAmm(RaydiumAmmInfo::load_checked(data)?)
+    } else if owner == *raydium_cp_program_id() {
+        DecodedAccount::RaydiumCp(RaydiumCpAmmInfo::load_checked(data)?)
+    } else if owner == *raydium_clmm_program_id() {
+        DecodedAccount::RaydiumClmm(Box::new(PoolState::load_checked(data)?))
+    } else if owner == *pump_program_id() {
+        DecodedAccount::Pump(PumpAmmInfo::load_checked(data)?)
+    } else if owner == *dlmm_program_id() {
+        DecodedAccount::MeteoraDlmm(Box::new(DlmmInfo::load_checked(data)?))
+    } else if owner == *damm_program_id() {
+        check_anchor_discriminator(data, "Pool")?;
+        let pool = meteora_damm_cpi::Pool::deserialize_unchecked(data)
+            .map_err(|e| anyhow::anyhow!("Failed to parse Meteora DAMM pool: {:?}", e))?;
+        DecodedAccount::MeteoraDamm(Box::new(pool))
+    } else if owner == *damm_v2_program_id() {
+        DecodedAccount::MeteoraDammV2(MeteoraDAmmV2Info::load_checked(data)?)
+    } else if owner == *vault_program_id() {
+        DecodedAccount::MeteoraVault(MeteoraVaultInfo::load_checked(data)?)
+    } else if owner == *whirlpool_program_id() {
+        DecodedAccount::Whirlpool(Box::new(Whirlpool::try_deserialize(data)?))
+    } else if owner == *solfi_program_id() {
+        DecodedAccount::Solfi(SolfiInfo::load_checked(data)?)
+    } else if owner == *vertigo_program_id() {
+        DecodedAccount::Vertigo(VertigoInfo::load_checked(data, pubkey)?)
+    } else if owner == *moonshot_program_id() {
+        DecodedAccount::Moonshot(MoonshotCurve::load_checked(data)?)
+    } else if owner == *perena_program_id() {
+        DecodedAccount::Perena(PerenaPoolInfo::load_checked(data)?)
+    } else if owner == *zerofi_program_id() {
+        DecodedAccount::ZeroFi(ZeroFiInfo::load_checked(data)?)
+    } else if data.starts_with(b"serum") {
+        DecodedAccount::OpenBookMarket(OpenBookMarket::load_checked(data)?)
+    } else {
+        return Err(anyhow::anyhow!(
+            "Account {} is owned by {}, which is not a supported DEX program",
+            pubkey,
+            owner
+        ));
+    };
+    Ok(decoded)
+}
+
+/// 读取账户并打印解析结果，供 `decode` 子命令使用
+pub fn print_account(rpc_client: &RpcClient, pubkey: &Pubkey) -> anyhow::Result<()> {
+    let account = rpc_client.get_account(pubkey)?;
+    println!("Account:  {}", pubkey);
+    println!("Owner:    {}", account.owner);
+    println!("Lamports: {}", account.lamports);
+    println!("Size:     {} bytes", account.data.len());
+
+    let decoded = decode_account(pubkey, &account)?;
+    println!("Layout:   {}", decoded.layout());
+    println!("{}", decoded);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use solana_sdk::hash::hashv;
+
+/// Anchor 账户的 8 字节类型标识：`sha256("account:<name>")` 的前 8 字节
+pub fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"account:", name.as_bytes()]);
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// Anchor 指令的 8 字节标识：`sha256("global:<name>")` 的前 8 字节
+pub fn anchor_instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"global:", name.as_bytes()]);
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// 按固定偏移解析 Anchor 账户之前核对类型标识
+///
+/// 程序升级账户布局后类型标识通常不变，但传入同一程序的其他账户（例如配置账户）时不同；
+/// 不核对的话会从错误的偏移读出 vault 地址。
+///
+/// # 参数
+/// * `data` - 账户的原始字节数据
+/// * `name` - Anchor 账户类型名称，例如 `PoolState`
+///
+/// # 错误
+/// 数据不足 8 字节或类型标识不匹配时返回 "Unsupported account version" 错误
+pub fn check_anchor_discriminator(data: &[u8], name: &str) -> Result<()> {
+    let expected = anchor_discriminator(name);
+    match data.get(..8) {
+        Some(actual) if actual == expected => Ok(()),
+        Some(actual) => Err(anyhow::anyhow!(
+            "Unsupported account version: discriminator {} is not a {} account ({})",
+            hex(actual),
+            name,
+            hex(&expected)
+        )),
+        None => Err(anyhow::anyhow!(
+            "Unsupported account version: {} bytes is too short for a {} account",
+            data.len(),
+            name
+        )),
+    }
+}
+
+/// 没有类型标识的账户按长度核对版本，布局变化时账户长度随之变化
+///
+/// # 参数
+/// * `data` - 账户的原始字节数据
+/// * `name` - 账户类型名称，用于错误信息
+/// * `len` - 当前支持的布局的账户长度
+///
+/// # 错误
+/// 长度不等于 `len` 时返回 "Unsupported account version" 错误
+pub fn check_account_len(data: &[u8], name: &str, len: usize) -> Result<()> {
+    if data.len() != len {
+        return Err(anyhow::anyhow!(
+            "Unsupported account version: {} account is {} bytes, expected {}",
+            name,
+            data.len(),
+            len
+        ));
+    }
+    Ok(())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
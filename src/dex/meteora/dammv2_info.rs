@@ -1,24 +1,65 @@
+use crate::ata::TOKEN_2022_PROGRAM_ID;
+use crate::dex::discriminator::check_anchor_discriminator;
+use crate::layout;
 use anyhow::Result;
+use bytemuck::{Pod, Zeroable};
 use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
 
+/// `token_a_mint` 的偏移，之后依次是 `token_b_mint`、`token_a_vault` 和 `token_b_vault`
+const TOKEN_A_MINT_OFFSET: usize = 168;
+/// `token_a_flag` 的偏移，`token_b_flag` 紧随其后
+const TOKEN_A_FLAG_OFFSET: usize = 482;
+
+/// cp-amm `Pool` 中两种代币的 mint 和 vault
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct PoolTokensLayout {
+    token_a_mint: Pubkey,
+    token_b_mint: Pubkey,
+    token_a_vault: Pubkey,
+    token_b_vault: Pubkey,
+}
+
+#[derive(Debug)]
 pub struct MeteoraDAmmV2Info {
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
     pub base_vault: Pubkey,
     pub quote_vault: Pubkey,
+    /// base 代币的代币程序（SPL Token 或 Token 2022）
+    pub base_token_program: Pubkey,
+    /// quote 代币的代币程序
+    pub quote_token_program: Pubkey,
+}
+
+/// cp-amm 的 `TokenProgramFlags`：0 为 SPL Token，1 为 Token 2022
+fn token_program(flag: u8) -> Result<Pubkey> {
+    match flag {
+        0 => Ok(spl_token::ID),
+        1 => Ok(Pubkey::from_str(TOKEN_2022_PROGRAM_ID)?),
+        _ => Err(anyhow::anyhow!(
+            "Unknown Meteora DAMM V2 token flag {}",
+            flag
+        )),
+    }
 }
 
 impl MeteoraDAmmV2Info {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        let base_mint = Pubkey::new(&data[168..200]);
-        let quote_mint = Pubkey::new(&data[200..232]);
-        let base_vault = Pubkey::new(&data[232..264]);
-        let quote_vault = Pubkey::new(&data[264..296]);
+        check_anchor_discriminator(data, "Pool")?;
+        if data.len() < TOKEN_A_FLAG_OFFSET + 2 {
+            return Err(anyhow::anyhow!("Invalid data length for MeteoraDAmmV2Info"));
+        }
+        let tokens = layout::view::<PoolTokensLayout>(data, TOKEN_A_MINT_OFFSET)
+            .ok_or_else(|| anyhow::anyhow!("Invalid data length for MeteoraDAmmV2Info"))?;
         Ok(Self {
-            base_mint,
-            quote_mint,
-            base_vault,
-            quote_vault,
+            base_mint: tokens.token_a_mint,
+            quote_mint: tokens.token_b_mint,
+            base_vault: tokens.token_a_vault,
+            quote_vault: tokens.token_b_vault,
+            base_token_program: token_program(data[TOKEN_A_FLAG_OFFSET])?,
+            quote_token_program: token_program(data[TOKEN_A_FLAG_OFFSET + 1])?,
         })
     }
 }
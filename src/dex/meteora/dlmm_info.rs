@@ -1,3 +1,4 @@
+use crate::dex::discriminator::check_anchor_discriminator;
 use crate::dex::meteora::constants::{dlmm_program_id, BIN_ARRAY};
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
@@ -35,7 +36,9 @@ pub struct StaticParameters {
     pub min_bin_id: i32,
     pub max_bin_id: i32,
     pub protocol_share: u16,
-    pub _padding: [u8; 6],
+    /// 基础手续费的 10 次幂倍数
+    pub base_fee_power_factor: u8,
+    pub _padding: [u8; 5],
 }
 
 #[repr(C)]
@@ -85,6 +88,34 @@ pub struct LbPair {
     pub _reserved: [u8; 24],
 }
 
+/// 每个 bin array 账户包含的 bin 数量
+pub const MAX_BIN_PER_ARRAY: usize = 70;
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Bin {
+    pub amount_x: u64,
+    pub amount_y: u64,
+    /// Q64.64 定点数表示的价格（1 个 X 值多少 Y），首次存入流动性时写入
+    pub price: u128,
+    pub liquidity_supply: u128,
+    pub reward_per_token_stored: [u128; 2],
+    pub fee_amount_x_per_token_stored: u128,
+    pub fee_amount_y_per_token_stored: u128,
+    pub amount_x_in: u128,
+    pub amount_y_in: u128,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct BinArray {
+    pub index: i64,
+    pub version: u8,
+    pub _padding: [u8; 7],
+    pub lb_pair: Pubkey,
+    pub bins: [Bin; MAX_BIN_PER_ARRAY],
+}
+
 #[derive(Debug)]
 pub struct DlmmInfo {
     pub token_x_mint: Pubkey,
@@ -98,6 +129,7 @@ pub struct DlmmInfo {
 
 impl DlmmInfo {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "LbPair")?;
         if data.len() < 8 + size_of::<LbPair>() {
             return Err(anyhow::anyhow!("Invalid data length for DlmmInfo"));
         }
@@ -167,7 +199,7 @@ impl DlmmInfo {
     fn derive_bin_array_pda(&self, lb_pair: &Pubkey, index: i64) -> Result<Pubkey> {
         let seeds = [BIN_ARRAY, lb_pair.as_ref(), &index.to_le_bytes()[0..8]];
 
-        let (pda, _) = Pubkey::find_program_address(&seeds, &dlmm_program_id());
+        let (pda, _) = Pubkey::find_program_address(&seeds, dlmm_program_id());
 
         Ok(pda)
     }
@@ -184,3 +216,27 @@ impl LbPair {
         Ok(lb_pair)
     }
 }
+
+impl BinArray {
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "BinArray")?;
+        if data.len() < 8 + size_of::<BinArray>() {
+            return Err(anyhow::anyhow!("Invalid data length for BinArray"));
+        }
+
+        let bin_array = unsafe { std::ptr::read_unaligned(data[8..].as_ptr() as *const BinArray) };
+
+        Ok(bin_array)
+    }
+
+    /// 该 bin array 覆盖的第一个 bin id
+    pub fn lower_bin_id(&self) -> i64 {
+        self.index * MAX_BIN_PER_ARRAY as i64
+    }
+
+    /// 查找 `bin_id` 对应的 bin，不在该 bin array 范围内时返回 `None`
+    pub fn bin(&self, bin_id: i32) -> Option<&Bin> {
+        let offset = usize::try_from(bin_id as i64 - self.lower_bin_id()).ok()?;
+        self.bins.get(offset)
+    }
+}
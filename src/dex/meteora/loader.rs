@@ -0,0 +1,354 @@
+use crate::dex::discriminator::check_anchor_discriminator;
+use crate::dex::meteora::constants::{damm_program_id, damm_v2_program_id, dlmm_program_id};
+use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use crate::dex::meteora::dlmm_info::DlmmInfo;
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::fees::{dlmm_fee, meteora_damm_fee, meteora_damm_v2_fee};
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, error, warn};
+
+/// 加载一个 Meteora DLMM 池，并根据当前 active id 计算需要的 Bin Array 地址
+pub fn load_dlmm_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let dlmm_pool_pubkey = *pool_address;
+
+    // 获取 DLMM 池账户信息并验证所有者
+    match get_account(&dlmm_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *dlmm_program_id() {
+                error!(
+                    "Error: DLMM pool account is not owned by the DLMM program. Expected: {}, Actual: {}",
+                    *dlmm_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "DLMM pool account is not owned by the DLMM program"
+                ));
+            }
+
+            // 解析 DLMM 池账户数据
+            match DlmmInfo::load_checked(&account.data) {
+                Ok(amm_info) => {
+                    let quote_mint = match quote_side(
+                        pool_data,
+                        "DLMM",
+                        &dlmm_pool_pubkey,
+                        &amm_info.token_x_mint,
+                        &amm_info.token_y_mint,
+                    )? {
+                        QuoteSide::A => amm_info.token_x_mint,
+                        QuoteSide::B => amm_info.token_y_mint,
+                    };
+                    let (token_vault, sol_vault) =
+                        amm_info.get_token_and_sol_vaults(&pool_data.mint, &quote_mint);
+
+                    // 计算 Bin Array 地址
+                    let bin_arrays = match amm_info.calculate_bin_arrays(&dlmm_pool_pubkey) {
+                        Ok(arrays) => arrays,
+                        Err(e) => {
+                            error!(
+                                "Error calculating bin arrays for DLMM pool {}: {:?}",
+                                dlmm_pool_pubkey, e
+                            );
+                            return Err(e);
+                        }
+                    };
+
+                    // 将池信息添加到 pool_data
+                    pool_data.add_dlmm_pool(
+                        *pool_address,
+                        token_vault,
+                        sol_vault,
+                        amm_info.oracle,
+                        bin_arrays.clone(),
+                        None, // memo_program
+                    );
+
+                    // 打印池信息
+                    debug!("DLMM pool added: {}", pool_address);
+                    debug!("    Token X Mint: {}", amm_info.token_x_mint.to_string());
+                    debug!("    Token Y Mint: {}", amm_info.token_y_mint.to_string());
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    debug!("    Oracle: {}", amm_info.oracle.to_string());
+                    debug!("    Active ID: {}", amm_info.active_id);
+                    let fee = dlmm_fee(&amm_info.lb_pair);
+                    debug!(
+                        "    Fee: {} bps (dynamic {} bps)",
+                        fee.total_bps(),
+                        fee.dynamic_fee_bps
+                    );
+                    pool_data.pool_fees.insert(dlmm_pool_pubkey, fee);
+
+                    for (i, array) in bin_arrays.iter().enumerate() {
+                        debug!("    Bin Array {}: {}", i, array);
+                    }
+                    debug!("");
+                }
+                Err(e) => {
+                    error!(
+                        "Error parsing AmmInfo from DLMM pool {}: {:?}",
+                        dlmm_pool_pubkey, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching DLMM pool account {}: {:?}",
+                dlmm_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching DLMM pool account"));
+        }
+    }
+    Ok(())
+}
+
+/// 加载一个 Meteora DAMM 池
+///
+/// DAMM 池的资金存放在 Meteora 动态 vault 中，需要额外读取两个 vault 账户得到 token vault 和 LP mint。
+pub fn load_damm_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let meteora_damm_pool_pubkey = *pool_address;
+
+    match get_account(&meteora_damm_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *damm_program_id() {
+                error!(
+                    "Error: Meteora DAMM pool account is not owned by the Meteora DAMM program. Expected: {}, Actual: {}",
+                    *damm_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Meteora DAMM pool account is not owned by the Meteora DAMM program"
+                ));
+            }
+
+            check_anchor_discriminator(&account.data, "Pool")?;
+            match meteora_damm_cpi::Pool::deserialize_unchecked(&account.data) {
+                Ok(pool) => {
+                    if pool.token_a_mint != pool_data.mint && pool.token_b_mint != pool_data.mint {
+                        error!(
+                            "Mint {} is not present in Meteora DAMM pool {}, skipping",
+                            pool_data.mint, meteora_damm_pool_pubkey
+                        );
+                        return Err(anyhow::anyhow!(
+                            "Invalid Meteora DAMM pool: {}",
+                            meteora_damm_pool_pubkey
+                        ));
+                    }
+
+                    let quote_mint = match quote_side(
+                        pool_data,
+                        "Meteora DAMM",
+                        &meteora_damm_pool_pubkey,
+                        &pool.token_a_mint,
+                        &pool.token_b_mint,
+                    )? {
+                        QuoteSide::A => pool.token_a_mint,
+                        QuoteSide::B => pool.token_b_mint,
+                    };
+
+                    let (x_vault, sol_vault) = if quote_mint == pool.token_a_mint {
+                        (pool.b_vault, pool.a_vault)
+                    } else {
+                        (pool.a_vault, pool.b_vault)
+                    };
+
+                    // Fetch vault accounts
+                    let x_vault_data = get_account(&x_vault)?;
+                    let sol_vault_data = get_account(&sol_vault)?;
+
+                    check_anchor_discriminator(&x_vault_data.data, "Vault")?;
+                    check_anchor_discriminator(&sol_vault_data.data, "Vault")?;
+                    let x_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
+                        &mut x_vault_data.data.as_slice(),
+                    )?;
+                    let sol_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
+                        &mut sol_vault_data.data.as_slice(),
+                    )?;
+
+                    let x_token_vault = x_vault_obj.token_vault;
+                    let sol_token_vault = sol_vault_obj.token_vault;
+                    let x_lp_mint = x_vault_obj.lp_mint;
+                    let sol_lp_mint = sol_vault_obj.lp_mint;
+
+                    let (x_pool_lp, sol_pool_lp) = if quote_mint == pool.token_a_mint {
+                        (pool.b_vault_lp, pool.a_vault_lp)
+                    } else {
+                        (pool.a_vault_lp, pool.b_vault_lp)
+                    };
+
+                    let (x_admin_fee, sol_admin_fee) = if quote_mint == pool.token_a_mint {
+                        (pool.admin_token_b_fee, pool.admin_token_a_fee)
+                    } else {
+                        (pool.admin_token_a_fee, pool.admin_token_b_fee)
+                    };
+
+                    pool_data.add_meteora_damm_pool(
+                        *pool_address,
+                        x_vault,
+                        sol_vault,
+                        x_token_vault,
+                        sol_token_vault,
+                        x_lp_mint,
+                        sol_lp_mint,
+                        x_pool_lp,
+                        sol_pool_lp,
+                        x_admin_fee,
+                        sol_admin_fee,
+                    );
+
+                    debug!("Meteora DAMM pool added: {}", pool_address);
+                    debug!("    Token X vault: {}", x_token_vault.to_string());
+                    debug!("    SOL vault: {}", sol_token_vault.to_string());
+                    debug!("    Token X LP mint: {}", x_lp_mint.to_string());
+                    debug!("    SOL LP mint: {}", sol_lp_mint.to_string());
+                    debug!("    Token X pool LP: {}", x_pool_lp.to_string());
+                    debug!("    SOL pool LP: {}", sol_pool_lp.to_string());
+                    debug!("    Token X admin fee: {}", x_admin_fee.to_string());
+                    debug!("    SOL admin fee: {}", sol_admin_fee.to_string());
+                    match meteora_damm_fee(
+                        pool.fees.trade_fee_numerator,
+                        pool.fees.trade_fee_denominator,
+                    ) {
+                        Some(fee) => {
+                            debug!("    Fee: {} bps", fee.total_bps());
+                            pool_data.pool_fees.insert(meteora_damm_pool_pubkey, fee);
+                        }
+                        None => {
+                            warn!("    Meteora DAMM pool has no valid trade fee, using default")
+                        }
+                    }
+                    debug!("");
+                }
+                Err(e) => {
+                    error!(
+                        "Error parsing Meteora DAMM pool data from pool {}: {:?}",
+                        meteora_damm_pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error parsing Meteora DAMM pool data"));
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching Meteora DAMM pool account {}: {:?}",
+                meteora_damm_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching Meteora DAMM pool account"));
+        }
+    }
+    Ok(())
+}
+
+/// 加载一个 Meteora DAMM V2 池
+pub fn load_damm_v2_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let meteora_damm_v2_pool_pubkey = *pool_address;
+
+    match get_account(&meteora_damm_v2_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *damm_v2_program_id() {
+                return Err(anyhow::anyhow!(
+                    "Meteora DAMM V2 pool {} is not owned by the Meteora DAMM V2 program",
+                    pool_address
+                ));
+            }
+
+            match MeteoraDAmmV2Info::load_checked(&account.data) {
+                Ok(meteora_damm_v2_info) => {
+                    debug!("Meteora DAMM V2 pool added: {}", pool_address);
+                    debug!(
+                        "    Base mint: {}",
+                        meteora_damm_v2_info.base_mint.to_string()
+                    );
+                    debug!(
+                        "    Quote mint: {}",
+                        meteora_damm_v2_info.quote_mint.to_string()
+                    );
+                    debug!(
+                        "    Base vault: {}",
+                        meteora_damm_v2_info.base_vault.to_string()
+                    );
+                    debug!(
+                        "    Quote vault: {}",
+                        meteora_damm_v2_info.quote_vault.to_string()
+                    );
+                    debug!(
+                        "    Token programs: {} / {}",
+                        meteora_damm_v2_info.base_token_program,
+                        meteora_damm_v2_info.quote_token_program
+                    );
+                    match meteora_damm_v2_fee(&account.data) {
+                        Some(fee) => {
+                            debug!(
+                                "    Fee: {} bps (dynamic {} bps)",
+                                fee.total_bps(),
+                                fee.dynamic_fee_bps
+                            );
+                            pool_data.pool_fees.insert(meteora_damm_v2_pool_pubkey, fee);
+                        }
+                        None => {
+                            warn!("    Failed to read Meteora DAMM V2 pool fees, using default")
+                        }
+                    }
+                    debug!("");
+                    let side = quote_side(
+                        pool_data,
+                        "Meteora DAMM V2",
+                        &meteora_damm_v2_pool_pubkey,
+                        &meteora_damm_v2_info.base_mint,
+                        &meteora_damm_v2_info.quote_mint,
+                    )?;
+                    let (token_x_vault, token_sol_vault, token_x_program, token_sol_program) =
+                        match side {
+                            QuoteSide::A => (
+                                meteora_damm_v2_info.quote_vault,
+                                meteora_damm_v2_info.base_vault,
+                                meteora_damm_v2_info.quote_token_program,
+                                meteora_damm_v2_info.base_token_program,
+                            ),
+                            QuoteSide::B => (
+                                meteora_damm_v2_info.base_vault,
+                                meteora_damm_v2_info.quote_vault,
+                                meteora_damm_v2_info.base_token_program,
+                                meteora_damm_v2_info.quote_token_program,
+                            ),
+                        };
+                    pool_data.add_meteora_damm_v2_pool(
+                        *pool_address,
+                        token_x_vault,
+                        token_sol_vault,
+                        token_x_program,
+                        token_sol_program,
+                    );
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Error parsing Meteora DAMM V2 pool data from pool {}: {:?}",
+                        meteora_damm_v2_pool_pubkey,
+                        e
+                    ));
+                }
+            }
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Error fetching Meteora DAMM V2 pool account {}: {:?}",
+                meteora_damm_v2_pool_pubkey,
+                e
+            ));
+        }
+    }
+    Ok(())
+}
@@ -1,3 +1,5 @@
 pub mod constants;
 pub mod dammv2_info;
 pub mod dlmm_info;
+pub mod loader;
+pub mod vault_info;
@@ -1,6 +1,141 @@
+pub mod discriminator;
 pub mod meteora;
+pub mod moonshot;
+pub mod perena;
 pub mod pump;
 pub mod raydium;
 pub mod solfi;
 pub mod vertigo;
 pub mod whirlpool;
+pub mod zerofi;
+
+use crate::pools::{MintPoolData, NonQuotePoolPolicy};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::fmt;
+
+/// 池子加载器读取账户的函数，启动时通常优先返回已经并发预取的账户
+pub type GetAccount<'a> = dyn FnMut(&Pubkey) -> anyhow::Result<Account> + 'a;
+
+/// 所有 DEX 池子加载器的统一签名
+///
+/// 读取并校验池子账户，解析出 swap 需要的账户后加入 `pool_data`。
+/// 池子不属于当前代币或账户无法解析时返回错误，由调用方按 `on_pool_error` 策略处理。
+pub type PoolLoader = fn(&Pubkey, &mut GetAccount<'_>, &mut MintPoolData) -> anyhow::Result<()>;
+
+/// 池子的两个代币中报价货币所在的一侧
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteSide {
+    A,
+    B,
+}
+
+/// 池子两侧都不是报价货币，加载器返回的错误，调用方按 `routing.non_quote_pools` 处理
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonQuotePool {
+    pub dex: &'static str,
+    pub pool: Pubkey,
+    pub quote_mint: Pubkey,
+}
+
+impl fmt::Display for NonQuotePool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Quote mint {} is not present in {} pool {}",
+            self.quote_mint, self.dex, self.pool
+        )
+    }
+}
+
+impl std::error::Error for NonQuotePool {}
+
+/// 选出池子中报价货币所在的一侧
+///
+/// 两侧都不是报价货币时，`NonQuotePoolPolicy::Allow` 下目标代币之外的一侧当作报价货币一侧，
+/// 池子记入 `MintPoolData::non_quote_pools`，报价时跳过；其他策略返回 `NonQuotePool` 错误。
+///
+/// # 参数
+/// * `dex` - 错误信息中的 DEX 名称
+/// * `mint_a`, `mint_b` - 池子两侧的 mint，顺序与 `QuoteSide` 对应
+pub fn quote_side(
+    pool_data: &mut MintPoolData,
+    dex: &'static str,
+    pool: &Pubkey,
+    mint_a: &Pubkey,
+    mint_b: &Pubkey,
+) -> anyhow::Result<QuoteSide> {
+    if *mint_a == pool_data.quote_mint {
+        return Ok(QuoteSide::A);
+    }
+    if *mint_b == pool_data.quote_mint {
+        return Ok(QuoteSide::B);
+    }
+    if pool_data.non_quote_pool_policy != NonQuotePoolPolicy::Allow {
+        return Err(NonQuotePool {
+            dex,
+            pool: *pool,
+            quote_mint: pool_data.quote_mint,
+        }
+        .into());
+    }
+    let (side, other_mint) = if *mint_a == pool_data.mint {
+        (QuoteSide::B, *mint_b)
+    } else {
+        (QuoteSide::A, *mint_a)
+    };
+    pool_data.non_quote_pools.insert(*pool, other_mint);
+    Ok(side)
+}
+
+/// 池子账户应当归属的 DEX 程序
+///
+/// # 参数
+/// * `dex` - `MintPoolData::pool_addresses` 返回的 DEX 名称
+///
+/// # 返回值
+/// 未知的 DEX 名称返回 `None`
+pub fn pool_program_id(dex: &str) -> Option<Pubkey> {
+    Some(match dex {
+        "raydium" => *raydium::raydium_program_id(),
+        "raydium_cp" => *raydium::raydium_cp_program_id(),
+        "raydium_clmm" => *raydium::raydium_clmm_program_id(),
+        "pump" => *pump::pump_program_id(),
+        "meteora_dlmm" => *meteora::constants::dlmm_program_id(),
+        "meteora_damm" => *meteora::constants::damm_program_id(),
+        "meteora_damm_v2" => *meteora::constants::damm_v2_program_id(),
+        "whirlpool" => *whirlpool::constants::whirlpool_program_id(),
+        "solfi" => *solfi::constants::solfi_program_id(),
+        "vertigo" => *vertigo::vertigo_program_id(),
+        "moonshot" => *moonshot::moonshot_program_id(),
+        "perena" => *perena::perena_program_id(),
+        "zerofi" => *zerofi::zerofi_program_id(),
+        _ => return None,
+    })
+}
+
+/// DEX 名称对应的池子加载器，运行时加入池子时按名称选择
+///
+/// # 参数
+/// * `dex` - `MintPoolData::pool_addresses` 返回的 DEX 名称
+///
+/// # 返回值
+/// 未知的 DEX 名称返回 `None`
+pub fn pool_loader(dex: &str) -> Option<PoolLoader> {
+    Some(match dex {
+        "raydium" => raydium::loader::load_amm_pool,
+        "raydium_cp" => raydium::loader::load_cp_pool,
+        "raydium_clmm" => raydium::loader::load_clmm_pool,
+        "pump" => pump::loader::load_pool,
+        "meteora_dlmm" => meteora::loader::load_dlmm_pool,
+        "meteora_damm" => meteora::loader::load_damm_pool,
+        "meteora_damm_v2" => meteora::loader::load_damm_v2_pool,
+        "whirlpool" => whirlpool::loader::load_pool,
+        "solfi" => solfi::loader::load_pool,
+        "vertigo" => vertigo::loader::load_pool,
+        "moonshot" => moonshot::loader::load_pool,
+        "perena" => perena::loader::load_pool,
+        "zerofi" => zerofi::loader::load_pool,
+        _ => return None,
+    })
+}
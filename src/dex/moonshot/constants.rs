@@ -0,0 +1,30 @@
+use crate::constants::{program_id, ProgramId};
+use solana_program::pubkey::Pubkey;
+
+pub const MOONSHOT_PROGRAM_ID: &str = "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG";
+pub const MOONSHOT_DEX_FEE: &str = "3udvfL24waJcLhskRAsStNMoNUvtyXdxrWQz4hgi953N";
+pub const MOONSHOT_HELIO_FEE: &str = "5K5RtTWzzLp4P8Npi84ocf7F1vBsAu29N1irG4iiUnzt";
+pub const MOONSHOT_CONFIG: &str = "36Eru7v11oU5Pfrojyn5oY3nETA1a1iqsw2WUu6afkM9";
+
+/// 每笔交易收取的手续费（基点），由 DEX 和 Helio 两个手续费账户分成
+pub const MOONSHOT_FEE_BPS: u64 = 100;
+
+/// 获取 Moonshot（Dexlab）程序的公钥
+pub fn moonshot_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Moonshot)
+}
+
+/// 获取 Moonshot DEX 手续费账户的公钥
+pub fn moonshot_dex_fee() -> &'static Pubkey {
+    program_id(ProgramId::MoonshotDexFee)
+}
+
+/// 获取 Moonshot Helio 手续费账户的公钥
+pub fn moonshot_helio_fee() -> &'static Pubkey {
+    program_id(ProgramId::MoonshotHelioFee)
+}
+
+/// 获取 Moonshot 全局配置账户的公钥
+pub fn moonshot_config() -> &'static Pubkey {
+    program_id(ProgramId::MoonshotConfig)
+}
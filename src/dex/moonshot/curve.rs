@@ -0,0 +1,77 @@
+use crate::dex::discriminator::check_anchor_discriminator;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+
+/// `CurveAccount` 的最小长度：8 字节类型标识加上所有字段
+const CURVE_ACCOUNT_LEN: usize = 82;
+
+/// `collateral_currency` 为 SOL 时的取值
+const COLLATERAL_SOL: u8 = 0;
+
+/// Moonshot 联合曲线账户
+///
+/// 曲线账户本身以 lamports 持有 SOL，代币存放在曲线账户的 ATA 中。
+#[derive(Debug)]
+pub struct MoonshotCurve {
+    /// 代币总供应量
+    pub total_supply: u64,
+    /// 曲线上剩余可以买入的代币数量
+    pub curve_amount: u64,
+    /// 代币的 mint
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// 抵押货币，0 为 SOL
+    pub collateral_currency: u8,
+    pub curve_type: u8,
+    /// 市值达到该值（以抵押货币计）后迁移到 AMM
+    pub marketcap_threshold: u64,
+    pub marketcap_currency: u8,
+    pub migration_fee: u64,
+    pub coef_b: u32,
+    pub bump: u8,
+    /// 迁移目标 AMM
+    pub migration_target: u8,
+}
+
+impl MoonshotCurve {
+    /// 从曲线账户数据中解析 `MoonshotCurve`
+    ///
+    /// # 参数
+    /// * `data` - `CurveAccount` 账户的原始字节数据
+    ///
+    /// # 错误
+    /// 类型标识不匹配或数据长度不足时返回错误
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "CurveAccount")?;
+        if data.len() < CURVE_ACCOUNT_LEN {
+            return Err(anyhow::anyhow!("Invalid data length for MoonshotCurve"));
+        }
+
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        Ok(Self {
+            total_supply: u64_at(8),
+            curve_amount: u64_at(16),
+            mint: Pubkey::from(<[u8; 32]>::try_from(&data[24..56]).unwrap()),
+            decimals: data[56],
+            collateral_currency: data[57],
+            curve_type: data[58],
+            marketcap_threshold: u64_at(59),
+            marketcap_currency: data[67],
+            migration_fee: u64_at(68),
+            coef_b: u32::from_le_bytes(data[76..80].try_into().unwrap()),
+            bump: data[80],
+            migration_target: data[81],
+        })
+    }
+
+    /// 抵押货币是否为 SOL
+    pub fn is_sol_collateral(&self) -> bool {
+        self.collateral_currency == COLLATERAL_SOL
+    }
+
+    /// 曲线上的代币已经全部卖出，代币即将（或已经）迁移到 AMM，曲线不再能交易
+    pub fn is_complete(&self) -> bool {
+        self.curve_amount == 0
+    }
+}
@@ -0,0 +1,69 @@
+use crate::constants::sol_mint;
+use crate::dex::moonshot::{moonshot_program_id, MoonshotCurve, MOONSHOT_FEE_BPS};
+use crate::dex::GetAccount;
+use crate::fees::PoolFee;
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::debug;
+
+/// 加载一条 Moonshot 联合曲线
+///
+/// 曲线账户以 lamports 持有 SOL，所以只支持以 SOL 报价的代币；代币 vault 是曲线账户的 ATA。
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let curve_pubkey = *pool_address;
+    let account = get_account(&curve_pubkey).map_err(|e| {
+        anyhow::anyhow!(
+            "Error fetching Moonshot curve account {}: {:?}",
+            curve_pubkey,
+            e
+        )
+    })?;
+    if account.owner != *moonshot_program_id() {
+        return Err(anyhow::anyhow!(
+            "Moonshot curve account is not owned by the Moonshot program. Expected: {}, Actual: {}",
+            *moonshot_program_id(),
+            account.owner
+        ));
+    }
+
+    let curve = MoonshotCurve::load_checked(&account.data)
+        .map_err(|e| anyhow::anyhow!("Error parsing Moonshot curve {}: {:?}", curve_pubkey, e))?;
+    if curve.mint != pool_data.mint {
+        return Err(anyhow::anyhow!(
+            "Moonshot curve {} trades {}, not {}",
+            curve_pubkey,
+            curve.mint,
+            pool_data.mint
+        ));
+    }
+    if !curve.is_sol_collateral() || pool_data.quote_mint != *sol_mint() {
+        return Err(anyhow::anyhow!(
+            "Moonshot curve {} is only supported for mints quoted in SOL",
+            curve_pubkey
+        ));
+    }
+
+    let token_vault = spl_associated_token_account::get_associated_token_address_with_program_id(
+        &curve_pubkey,
+        &curve.mint,
+        &pool_data.token_program,
+    );
+    pool_data.add_moonshot_pool(*pool_address, token_vault);
+    pool_data.pool_fees.insert(
+        curve_pubkey,
+        PoolFee {
+            protocol_fee_bps: MOONSHOT_FEE_BPS,
+            ..Default::default()
+        },
+    );
+
+    debug!("Moonshot curve added: {}", pool_address);
+    debug!("    Mint: {}", curve.mint);
+    debug!("    Token vault: {}", token_vault);
+    debug!("    Remaining curve amount: {}", curve.curve_amount);
+    Ok(())
+}
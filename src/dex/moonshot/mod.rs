@@ -0,0 +1,6 @@
+pub mod constants;
+pub mod curve;
+pub mod loader;
+
+pub use constants::*;
+pub use curve::MoonshotCurve;
@@ -0,0 +1,9 @@
+use crate::constants::{program_id, ProgramId};
+use solana_program::pubkey::Pubkey;
+
+pub const PERENA_PROGRAM_ID: &str = "NUMERUNsFCP3kuNmWZuXtm1AaQCPj9uw6Guv2Ekoi5P";
+
+/// 获取 Perena Numeraire 程序的公钥
+pub fn perena_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Perena)
+}
@@ -0,0 +1,56 @@
+use crate::dex::perena::{perena_program_id, PerenaPoolInfo};
+use crate::dex::GetAccount;
+use crate::fees::perena_fee;
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::debug;
+
+/// 加载一个 Perena Numeraire 稳定币池
+///
+/// 池子中有多个代币，只使用当前代币和报价货币的两个 vault，两者都必须在池子中。
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let pool_pubkey = *pool_address;
+    let account = get_account(&pool_pubkey).map_err(|e| {
+        anyhow::anyhow!(
+            "Error fetching Perena pool account {}: {:?}",
+            pool_pubkey,
+            e
+        )
+    })?;
+    if account.owner != *perena_program_id() {
+        return Err(anyhow::anyhow!(
+            "Perena pool account is not owned by the Perena program. Expected: {}, Actual: {}",
+            *perena_program_id(),
+            account.owner
+        ));
+    }
+
+    let pool = PerenaPoolInfo::load_checked(&account.data)
+        .map_err(|e| anyhow::anyhow!("Error parsing Perena pool {}: {:?}", pool_pubkey, e))?;
+    let (Some(token_vault), Some(quote_vault)) = (
+        pool.vault(&pool_data.mint),
+        pool.vault(&pool_data.quote_mint),
+    ) else {
+        return Err(anyhow::anyhow!(
+            "Perena pool {} doesn't contain both {} and {}",
+            pool_pubkey,
+            pool_data.mint,
+            pool_data.quote_mint
+        ));
+    };
+
+    pool_data.add_perena_pool(*pool_address, token_vault, quote_vault);
+    let fee = perena_fee(pool.fee_rate);
+    pool_data.pool_fees.insert(pool_pubkey, fee);
+
+    debug!("Perena pool added: {}", pool_address);
+    debug!("    Tokens: {}", pool.mints.len());
+    debug!("    Token vault: {}", token_vault);
+    debug!("    Quote vault: {}", quote_vault);
+    debug!("    Fee: {} bps", fee.total_bps());
+    Ok(())
+}
@@ -0,0 +1,6 @@
+pub mod constants;
+pub mod loader;
+pub mod pool;
+
+pub use constants::*;
+pub use pool::PerenaPoolInfo;
@@ -0,0 +1,80 @@
+use crate::dex::discriminator::check_anchor_discriminator;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+
+/// 一个池子最多包含的代币数
+pub const MAX_POOL_TOKENS: usize = 8;
+
+/// `num_tokens` 的偏移：8 字节类型标识 + authority
+const NUM_TOKENS_OFFSET: usize = 8 + 32;
+/// `mints` 的偏移，`num_tokens` 后有 7 字节对齐填充
+const MINTS_OFFSET: usize = NUM_TOKENS_OFFSET + 8;
+const VAULTS_OFFSET: usize = MINTS_OFFSET + 32 * MAX_POOL_TOKENS;
+/// `fee_rate`（百万分之一）的偏移
+const FEE_RATE_OFFSET: usize = VAULTS_OFFSET + 32 * MAX_POOL_TOKENS;
+/// 池子账户的最小长度
+const PERENA_POOL_LEN: usize = FEE_RATE_OFFSET + 8;
+
+/// Perena Numeraire 稳定币池
+///
+/// 一个池子包含多个锚定同一货币的代币（USD* 池中有 USDC、USDT、PYUSD 等），
+/// 任意两个代币之间都可以兑换，每个代币有各自的 vault。
+#[derive(Debug)]
+pub struct PerenaPoolInfo {
+    pub authority: Pubkey,
+    /// 池子中的代币，按池子中的顺序
+    pub mints: Vec<Pubkey>,
+    /// 与 `mints` 一一对应的 vault
+    pub vaults: Vec<Pubkey>,
+    /// 交易手续费（百万分之一）
+    pub fee_rate: u64,
+}
+
+impl PerenaPoolInfo {
+    /// 从池子账户数据中解析 `PerenaPoolInfo`
+    ///
+    /// # 参数
+    /// * `data` - `Pool` 账户的原始字节数据
+    ///
+    /// # 错误
+    /// 类型标识不匹配、数据长度不足或代币数超过 `MAX_POOL_TOKENS` 时返回错误
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "Pool")?;
+        if data.len() < PERENA_POOL_LEN {
+            return Err(anyhow::anyhow!("Invalid data length for PerenaPoolInfo"));
+        }
+        let num_tokens = data[NUM_TOKENS_OFFSET] as usize;
+        if num_tokens > MAX_POOL_TOKENS {
+            return Err(anyhow::anyhow!(
+                "Perena pool has {} tokens, at most {} are supported",
+                num_tokens,
+                MAX_POOL_TOKENS
+            ));
+        }
+
+        let pubkey_at =
+            |offset: usize| Pubkey::from(<[u8; 32]>::try_from(&data[offset..offset + 32]).unwrap());
+        Ok(Self {
+            authority: pubkey_at(8),
+            mints: (0..num_tokens)
+                .map(|i| pubkey_at(MINTS_OFFSET + 32 * i))
+                .collect(),
+            vaults: (0..num_tokens)
+                .map(|i| pubkey_at(VAULTS_OFFSET + 32 * i))
+                .collect(),
+            fee_rate: u64::from_le_bytes(
+                data[FEE_RATE_OFFSET..FEE_RATE_OFFSET + 8]
+                    .try_into()
+                    .unwrap(),
+            ),
+        })
+    }
+
+    /// 代币在池子中的 vault
+    pub fn vault(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.mints
+            .iter()
+            .position(|pool_mint| pool_mint == mint)
+            .map(|index| self.vaults[index])
+    }
+}
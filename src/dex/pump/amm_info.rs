@@ -1,5 +1,5 @@
-use std::str::FromStr;
-
+use crate::dex::discriminator::check_anchor_discriminator;
+use crate::dex::pump::constants::pump_program_id;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
@@ -44,8 +44,12 @@ impl PumpAmmInfo {
     // 所以这些地址的存在是为了让AMM知道在哪里找到交易所需的代币和如何管理这些资金。
     /// - `Err(...)`: 数据不合法时返回错误信息
     pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "Pool")?;
+
         // 跳过前缀数据（8字节signature + 1字节bump + 2字节version + 32字节padding）
-        let data = &data[8 + 1 + 2 + 32..];
+        let data = data
+            .get(8 + 1 + 2 + 32..)
+            .ok_or_else(|| anyhow::anyhow!("Invalid data length for PumpAmmInfo"))?;
 
         // 检查剩余数据是否足够包含4个Pubkey（各32字节）和lp_supply（8字节）
         if data.len() < 4 * 32 + 8 {
@@ -69,9 +73,8 @@ impl PumpAmmInfo {
         //  AMM池中的报价代币账户地址
         let pool_quote_token_account = Pubkey::from(<[u8; 32]>::try_from(&data[128..160]).unwrap());
 
-        // 固定的 Pump Program ID
-        let pump_program_id =
-            Pubkey::from_str("pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA").unwrap();
+        // Pump Program ID（可通过集群配置覆盖）
+        let pump_program_id = *pump_program_id();
         println!("data: {:?}", data.len());
 
         // 解析代币创建者地址（如果存在）
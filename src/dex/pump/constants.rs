@@ -1,21 +1,19 @@
+use crate::constants::{program_id, ProgramId};
 use solana_program::pubkey::Pubkey;
-use std::str::FromStr;
 
 pub const PUMP_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
 pub const PUMP_FEE_WALLET: &str = "JCRGumoE9Qi5BBgULTgdgTLjSgkCMSbF62ZZfGs84JeU";
+pub const PUMP_GLOBAL_CONFIG: &str = "ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw";
+pub const PUMP_EVENT_AUTHORITY: &str = "GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR";
 
 /// 获取 Pump 程序的公钥标识符
 ///
 /// 该函数用于返回与 Pump 程序关联的唯一公钥标识符。
 ///
 /// # 返回值
-/// * `Pubkey` - Pump 程序的公钥
-///
-/// # Panics
-/// 当 `PUMP_PROGRAM_ID` 字符串无法解析为有效公钥时会触发 panic
-pub fn pump_program_id() -> Pubkey {
-    // 将预定义的程序 ID 字符串转换为 Pubkey 类型
-    Pubkey::from_str(PUMP_PROGRAM_ID).unwrap()
+/// * `&'static Pubkey` - Pump 程序的公钥，来自启动时构造的地址注册表
+pub fn pump_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Pump)
 }
 
 /// 获取Pump费用钱包的公钥
@@ -23,10 +21,17 @@ pub fn pump_program_id() -> Pubkey {
 /// 该函数用于获取Pump协议的费用钱包地址，该地址用于接收协议收取的费用
 ///
 /// # 返回值
-/// * `Pubkey` - Pump费用钱包的公钥地址
-///
-/// # Panics
-/// 当`PUMP_FEE_WALLET`字符串无法解析为有效公钥时会panic
-pub fn pump_fee_wallet() -> Pubkey {
-    Pubkey::from_str(PUMP_FEE_WALLET).unwrap()
+/// * `&'static Pubkey` - Pump费用钱包的公钥地址
+pub fn pump_fee_wallet() -> &'static Pubkey {
+    program_id(ProgramId::PumpFeeWallet)
+}
+
+/// 获取 Pump 全局配置账户的公钥
+pub fn pump_global_config() -> &'static Pubkey {
+    program_id(ProgramId::PumpGlobalConfig)
+}
+
+/// 获取 Pump 事件授权账户的公钥
+pub fn pump_event_authority() -> &'static Pubkey {
+    program_id(ProgramId::PumpEventAuthority)
 }
@@ -0,0 +1,61 @@
+use crate::dex::discriminator::check_anchor_discriminator;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+
+/// `GlobalConfig` 中 `protocol_fee_recipients` 的偏移：
+/// 8 字节类型标识 + admin + lp_fee_basis_points + protocol_fee_basis_points + disable_flags
+const PROTOCOL_FEE_RECIPIENTS_OFFSET: usize = 8 + 32 + 8 + 8 + 1;
+
+/// 协议手续费接收地址的槽位数
+const PROTOCOL_FEE_RECIPIENT_SLOTS: usize = 8;
+
+/// Pump AMM 全局配置账户中的协议手续费接收地址
+///
+/// 交易时传入其中任意一个地址及其报价货币 ATA 都可以通过程序检查，
+/// 协议更换接收地址后沿用硬编码的 `PUMP_FEE_WALLET` 会导致交易失败。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PumpFeeRecipients {
+    /// 已设置的接收地址，不包括空槽位
+    pub recipients: Vec<Pubkey>,
+}
+
+impl PumpFeeRecipients {
+    /// 从全局配置账户数据中解析协议手续费接收地址
+    ///
+    /// # 参数
+    /// * `data` - `GlobalConfig` 账户的原始字节数据
+    ///
+    /// # 错误
+    /// 类型标识不匹配、数据长度不足或没有设置任何接收地址时返回错误
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "GlobalConfig")?;
+        let slots = data
+            .get(
+                PROTOCOL_FEE_RECIPIENTS_OFFSET
+                    ..PROTOCOL_FEE_RECIPIENTS_OFFSET + 32 * PROTOCOL_FEE_RECIPIENT_SLOTS,
+            )
+            .ok_or_else(|| anyhow::anyhow!("Invalid data length for Pump GlobalConfig"))?;
+        let recipients: Vec<Pubkey> = slots
+            .chunks_exact(32)
+            .map(|slot| Pubkey::from(<[u8; 32]>::try_from(slot).unwrap()))
+            .filter(|recipient| *recipient != Pubkey::default())
+            .collect();
+        if recipients.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Pump GlobalConfig has no protocol fee recipients"
+            ));
+        }
+        Ok(Self { recipients })
+    }
+
+    /// 为池子选择一个接收地址
+    ///
+    /// 按池子地址固定选择，同一个池子每次启动使用同一个地址（地址查找表可以复用），
+    /// 不同池子分散到不同的接收地址上，减少交易之间的写锁冲突。
+    pub fn for_pool(&self, pool: &Pubkey) -> Pubkey {
+        let bytes = pool.to_bytes();
+        let index =
+            u64::from_le_bytes(bytes[..8].try_into().unwrap()) as usize % self.recipients.len();
+        self.recipients[index]
+    }
+}
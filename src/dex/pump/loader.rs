@@ -0,0 +1,143 @@
+use crate::dex::pump::{
+    pump_fee_wallet, pump_global_config, pump_program_id, PumpAmmInfo, PumpFeeRecipients,
+};
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::fees::pump_fee;
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, error, warn};
+
+/// 加载一个 pump.fun AMM 池
+///
+/// 解析 AMM 信息，确定报价货币和代币的 vault，计算手续费账户和创建者 vault 的 ATA 后加入 `pool_data`。
+/// 手续费和协议手续费接收地址从全局配置账户读取，读取失败时使用默认手续费和 `PUMP_FEE_WALLET`。
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let pump_pool_pubkey = *pool_address;
+    // 获取帐号信息
+    match get_account(&pump_pool_pubkey) {
+        Ok(account) => {
+            // 如果拿到的帐号信息比对 pump.fun 池的账户，则返回错误
+            if account.owner != *pump_program_id() {
+                error!(
+                    "Error: Pump pool account is not owned by the Pump program. Expected: {}, Actual: {}",
+                    *pump_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Pump pool account is not owned by the Pump program"
+                ));
+            }
+
+            match PumpAmmInfo::load_checked(&account.data) {
+                Ok(amm_info) => {
+                    // 根据 base_mint 或 quote_mint 是否为报价货币（默认 SOL）来决定 token_vault 和 sol_vault 的对应关系
+                    let side = quote_side(
+                        pool_data,
+                        "Pump",
+                        &pump_pool_pubkey,
+                        &amm_info.base_mint,
+                        &amm_info.quote_mint,
+                    )?;
+                    let (sol_vault, token_vault) = match side {
+                        QuoteSide::A => (
+                            amm_info.pool_base_token_account,
+                            amm_info.pool_quote_token_account,
+                        ),
+                        QuoteSide::B => (
+                            amm_info.pool_quote_token_account,
+                            amm_info.pool_base_token_account,
+                        ),
+                    };
+
+                    // 手续费和协议手续费接收地址在全局配置账户中，所有池子相同
+                    let global_config = get_account(pump_global_config());
+                    let fee_recipient = match global_config
+                        .as_ref()
+                        .map_err(|e| anyhow::anyhow!("{}", e))
+                        .and_then(|config| PumpFeeRecipients::load_checked(&config.data))
+                    {
+                        Ok(recipients) => recipients.for_pool(&pump_pool_pubkey),
+                        Err(e) => {
+                            warn!(
+                                "    Failed to read fee recipients from Pump global config, using {}: {}",
+                                *pump_fee_wallet(),
+                                e
+                            );
+                            *pump_fee_wallet()
+                        }
+                    };
+
+                    // 计算手续费接收地址的关联代币账户地址
+                    let fee_token_wallet =
+                        spl_associated_token_account::get_associated_token_address(
+                            &fee_recipient,
+                            &amm_info.quote_mint,
+                        );
+
+                    // 计算代币创建者 vault 的 ATA 地址
+                    let coin_creator_vault_ata =
+                        spl_associated_token_account::get_associated_token_address(
+                            &amm_info.coin_creator_vault_authority,
+                            &amm_info.quote_mint,
+                        );
+
+                    // 将解析出的池信息添加到 pool_data 中
+                    pool_data.add_pump_pool(
+                        *pool_address,
+                        token_vault,
+                        sol_vault,
+                        fee_recipient,
+                        fee_token_wallet,
+                        coin_creator_vault_ata,
+                        amm_info.coin_creator_vault_authority,
+                    );
+
+                    // 打印调试日志，记录添加的池信息
+                    debug!("Pump pool added: {}", pool_address);
+                    debug!("    Base mint: {}", amm_info.base_mint.to_string());
+                    debug!("    Quote mint: {}", amm_info.quote_mint.to_string());
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    debug!("    Fee recipient: {}", fee_recipient);
+                    debug!("    Fee token wallet: {}", fee_token_wallet.to_string());
+                    debug!(
+                        "    Coin creator vault ata: {}",
+                        coin_creator_vault_ata.to_string()
+                    );
+                    debug!(
+                        "    Coin creator vault authority: {}",
+                        amm_info.coin_creator_vault_authority.to_string()
+                    );
+
+                    match global_config.map(|config| pump_fee(&config.data)) {
+                        Ok(Some(fee)) => {
+                            debug!("    Fee: {} bps", fee.total_bps());
+                            pool_data.pool_fees.insert(pump_pool_pubkey, fee);
+                        }
+                        _ => warn!("    Failed to read fee from Pump global config, using default"),
+                    }
+                    debug!("    Initialized Pump pool: {}\n", pump_pool_pubkey);
+                }
+                Err(e) => {
+                    // 如果无法解析 AMM 信息，则记录错误并返回
+                    error!(
+                        "Error parsing AmmInfo from Pump pool {}: {:?}",
+                        pump_pool_pubkey, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching Pump pool account {}: {:?}",
+                pump_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching Pump pool account"));
+        }
+    }
+    Ok(())
+}
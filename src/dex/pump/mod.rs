@@ -1,5 +1,8 @@
 pub mod amm_info;
 pub mod constants;
+pub mod global_config;
+pub mod loader;
 
 pub use amm_info::PumpAmmInfo;
 pub use constants::*;
+pub use global_config::PumpFeeRecipients;
@@ -1,3 +1,4 @@
+use crate::dex::discriminator::check_account_len;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
@@ -5,12 +6,19 @@ const COIN_VAULT_OFFSET: usize = 336; // coinVault/tokenVaultA
 const PC_VAULT_OFFSET: usize = 368; // pcVault/tokenVaultB
 const COIN_MINT_OFFSET: usize = 400; // coinMint/tokenMintA
 const PC_MINT_OFFSET: usize = 432; // pcMint/tokenMintB
+const OPEN_ORDERS_OFFSET: usize = 496; // openOrders
+const MARKET_OFFSET: usize = 528; // marketId
+const MARKET_PROGRAM_OFFSET: usize = 560; // marketProgramId
+const TARGET_ORDERS_OFFSET: usize = 592; // targetOrders
+
+/// AMM v4 池子账户的长度
+const AMM_INFO_LEN: usize = 752;
 
 #[derive(Debug)]
 /// Raydium AMM 信息结构体
 ///
 /// 该结构体存储了 Raydium 自动做市商(AMM)的核心账户信息，
-/// 包括代币的铸造地址和对应的金库地址
+/// 包括代币的铸造地址和对应的金库地址，以及池子关联的 OpenBook 市场
 pub struct RaydiumAmmInfo {
     /// 代币A的铸造地址(Pubkey)
     pub coin_mint: Pubkey,
@@ -20,16 +28,25 @@ pub struct RaydiumAmmInfo {
     pub coin_vault: Pubkey,
     /// 代币B的金库地址(Pubkey)
     pub pc_vault: Pubkey,
+    /// 池子在 OpenBook 市场上的 open orders 账户
+    pub open_orders: Pubkey,
+    /// 池子的 target orders 账户
+    pub target_orders: Pubkey,
+    /// 关联的 OpenBook（Serum）市场
+    pub market: Pubkey,
+    /// 市场所属的程序
+    pub market_program: Pubkey,
 }
 
 impl RaydiumAmmInfo {
     /// 从字节数据中加载并验证RaydiumAmmInfo结构体
     ///
-    /// 该函数会检查数据长度是否足够，并从指定偏移位置提取四个公钥信息：
+    /// 该函数会检查数据长度是否足够，并从指定偏移位置提取以下公钥信息：
     /// - coin_mint: 代币A的铸币地址
     /// - pc_mint: 代币B的铸币地址  
     /// - coin_vault: 代币A的资金池地址
     /// - pc_vault: 代币B的资金池地址
+    /// - open_orders、target_orders、market、market_program: OpenBook 市场相关账户
     ///
     /// # 参数
     /// * `data` - 包含AMM信息的原始字节数据切片
@@ -38,24 +55,32 @@ impl RaydiumAmmInfo {
     /// * `Result<Self>` - 成功时返回解析出的RaydiumAmmInfo实例，失败时返回错误信息
     ///
     /// # 错误
-    /// 当数据长度小于PC_MINT_OFFSET+32时会返回错误
+    /// 当数据长度小于TARGET_ORDERS_OFFSET+32时会返回错误
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        // 验证数据长度是否满足最小要求
-        if data.len() < PC_MINT_OFFSET + 32 {
-            return Err(anyhow::anyhow!("Invalid data length for RaydiumAmmInfo"));
-        }
+        // AMM v4 没有类型标识，按账户长度核对布局版本
+        check_account_len(data, "Raydium AMM v4 pool", AMM_INFO_LEN)?;
 
-        // 从数据中提取四个公钥信息
+        // 从数据中提取公钥信息
         let coin_vault = Pubkey::try_from(&data[COIN_VAULT_OFFSET..COIN_VAULT_OFFSET + 32])?;
         let pc_vault = Pubkey::try_from(&data[PC_VAULT_OFFSET..PC_VAULT_OFFSET + 32])?;
         let coin_mint = Pubkey::try_from(&data[COIN_MINT_OFFSET..COIN_MINT_OFFSET + 32])?;
         let pc_mint = Pubkey::try_from(&data[PC_MINT_OFFSET..PC_MINT_OFFSET + 32])?;
+        let open_orders = Pubkey::try_from(&data[OPEN_ORDERS_OFFSET..OPEN_ORDERS_OFFSET + 32])?;
+        let market = Pubkey::try_from(&data[MARKET_OFFSET..MARKET_OFFSET + 32])?;
+        let market_program =
+            Pubkey::try_from(&data[MARKET_PROGRAM_OFFSET..MARKET_PROGRAM_OFFSET + 32])?;
+        let target_orders =
+            Pubkey::try_from(&data[TARGET_ORDERS_OFFSET..TARGET_ORDERS_OFFSET + 32])?;
 
         Ok(Self {
             coin_mint,
             pc_mint,
             coin_vault,
             pc_vault,
+            open_orders,
+            target_orders,
+            market,
+            market_program,
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::dex::discriminator::check_anchor_discriminator;
 use anyhow::Result;
 use solana_program::pubkey::Pubkey;
 
@@ -8,6 +9,15 @@ pub const REWARD_NUM: usize = 3;
 
 pub const POOL_TICK_ARRAY_BITMAP_SEED: &str = "pool_tick_array_bitmap_extension";
 
+/// 池子账户（不含类型标识）中 `tick_array_bitmap` 的偏移
+const TICK_ARRAY_BITMAP_OFFSET: usize = 896;
+
+/// 池子账户中的 bitmap 覆盖的 tick array 数量，超出范围的记录在 bitmap 扩展账户中
+pub const TICK_ARRAY_BITMAP_SIZE: i32 = 1024;
+
+/// tick array 账户中每个 tick 占用的字节数
+const TICK_STATE_LEN: usize = 168;
+
 pub enum RewardState {
     Uninitialized,
     Initialized,
@@ -103,7 +113,8 @@ pub struct PoolState {
 
 impl PoolState {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
-        if data.len() < 8 + 32 + 32 + 32 + 32 + 32 + 32 + 4 + 2 {
+        check_anchor_discriminator(data, "PoolState")?;
+        if data.len() < 8 + TICK_ARRAY_BITMAP_OFFSET + 128 {
             return Err(anyhow::anyhow!(
                 "Invalid data length for RaydiumClmmPoolState"
             ));
@@ -153,15 +164,25 @@ impl PoolState {
         let tick_spacing = u16::from_le_bytes(tick_spacing_bytes);
         offset += 2;
 
+        let mut liquidity_bytes = [0u8; 16];
+        liquidity_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let liquidity = u128::from_le_bytes(liquidity_bytes);
         offset += 16;
 
-        // Skip sqrt_price_x64
+        let mut sqrt_price_bytes = [0u8; 16];
+        sqrt_price_bytes.copy_from_slice(&data[offset..offset + 16]);
+        let sqrt_price_x64 = u128::from_le_bytes(sqrt_price_bytes);
         offset += 16;
 
         let mut tick_current_bytes = [0u8; 4];
         tick_current_bytes.copy_from_slice(&data[offset..offset + 4]);
         let tick_current = i32::from_le_bytes(tick_current_bytes);
-        offset += 4;
+
+        let mut tick_array_bitmap = [0u64; 16];
+        for (i, word) in tick_array_bitmap.iter_mut().enumerate() {
+            let start = TICK_ARRAY_BITMAP_OFFSET + i * 8;
+            *word = u64::from_le_bytes(data[start..start + 8].try_into().unwrap());
+        }
 
         Ok(Self {
             amm_config,
@@ -171,10 +192,74 @@ impl PoolState {
             token_vault_1,
             observation_key,
             tick_spacing,
+            liquidity,
+            sqrt_price_x64,
             tick_current,
+            tick_array_bitmap,
             ..Default::default()
         })
     }
+
+    /// 池子账户的 bitmap 中 `start_tick_index` 开始的 tick array 是否已初始化
+    ///
+    /// 超出 bitmap 范围（记录在扩展账户中）时返回 `None`。
+    pub fn is_tick_array_initialized(&self, start_tick_index: i32) -> Option<bool> {
+        let ticks_in_array = TICK_ARRAY_SIZE * self.tick_spacing as i32;
+        let bit = start_tick_index.div_euclid(ticks_in_array) + TICK_ARRAY_BITMAP_SIZE / 2;
+        if !(0..TICK_ARRAY_BITMAP_SIZE).contains(&bit) {
+            return None;
+        }
+        let bit = bit as usize;
+        Some(self.tick_array_bitmap[bit / 64] >> (bit % 64) & 1 == 1)
+    }
+}
+
+/// tick array 中的一个 tick，只解析报价需要的字段
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickState {
+    pub tick: i32,
+    pub liquidity_net: i128,
+    pub liquidity_gross: u128,
+}
+
+impl TickState {
+    pub fn is_initialized(&self) -> bool {
+        self.liquidity_gross != 0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TickArrayState {
+    pub pool_id: Pubkey,
+    pub start_tick_index: i32,
+    pub ticks: [TickState; TICK_ARRAY_SIZE_USIZE],
+}
+
+impl TickArrayState {
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        check_anchor_discriminator(data, "TickArrayState")?;
+        if data.len() < 8 + 36 + TICK_ARRAY_SIZE_USIZE * TICK_STATE_LEN {
+            return Err(anyhow::anyhow!("Invalid data length for TickArrayState"));
+        }
+
+        let data = &data[8..];
+        let pool_id = Pubkey::new_from_array(data[..32].try_into().unwrap());
+        let start_tick_index = i32::from_le_bytes(data[32..36].try_into().unwrap());
+        let ticks = std::array::from_fn(|i| {
+            let tick = &data[36 + i * TICK_STATE_LEN..];
+            TickState {
+                tick: i32::from_le_bytes(tick[..4].try_into().unwrap()),
+                liquidity_net: i128::from_le_bytes(tick[4..20].try_into().unwrap()),
+                liquidity_gross: u128::from_le_bytes(tick[20..36].try_into().unwrap()),
+            }
+        });
+
+        Ok(Self {
+            pool_id,
+            start_tick_index,
+            ticks,
+        })
+    }
 }
 
 pub fn compute_tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
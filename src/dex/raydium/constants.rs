@@ -1,24 +1,22 @@
+use crate::constants::{program_id, ProgramId};
 use solana_program::pubkey::Pubkey;
-use std::str::FromStr;
 
-pub fn raydium_program_id() -> Pubkey {
-    Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8").unwrap()
+pub fn raydium_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Raydium)
 }
 
-pub fn raydium_authority() -> Pubkey {
-    Pubkey::from_str("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1").unwrap()
+pub fn raydium_authority() -> &'static Pubkey {
+    program_id(ProgramId::RaydiumAuthority)
 }
 
-
-
-pub fn raydium_cp_program_id() -> Pubkey {
-    Pubkey::from_str("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C").unwrap()
+pub fn raydium_cp_program_id() -> &'static Pubkey {
+    program_id(ProgramId::RaydiumCp)
 }
 
-pub fn raydium_cp_authority() -> Pubkey {
-    Pubkey::from_str("GpMZbSM2GgvTKHJirzeGfMFoaZ8UR2X7F4v8vHTvxFbL").unwrap()
+pub fn raydium_cp_authority() -> &'static Pubkey {
+    program_id(ProgramId::RaydiumCpAuthority)
 }
 
-pub fn raydium_clmm_program_id() -> Pubkey {
-    Pubkey::from_str("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK").unwrap()
+pub fn raydium_clmm_program_id() -> &'static Pubkey {
+    program_id(ProgramId::RaydiumClmm)
 }
@@ -0,0 +1,359 @@
+use crate::dex::raydium::{
+    get_tick_array_pubkeys, raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id,
+    OpenBookMarket, PoolState, RaydiumAmmInfo, RaydiumCpAmmInfo,
+};
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::fees::{raydium_amm_fee, raydium_clmm_fee, raydium_cp_fee};
+use crate::pools::{MintPoolData, RaydiumMarket};
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, error, warn};
+
+/// 读取 Raydium 池子关联的 OpenBook 市场，推导 swap 指令需要的其余账户
+fn load_market(
+    amm_info: &RaydiumAmmInfo,
+    quote_mint: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+) -> anyhow::Result<RaydiumMarket> {
+    let account = get_account(&amm_info.market)?;
+    if account.owner != amm_info.market_program {
+        return Err(anyhow::anyhow!(
+            "Market account is owned by {}, expected {}",
+            account.owner,
+            amm_info.market_program
+        ));
+    }
+    let market = OpenBookMarket::load_checked(&account.data)?;
+    Ok(RaydiumMarket {
+        open_orders: amm_info.open_orders,
+        target_orders: amm_info.target_orders,
+        sol_is_coin: amm_info.coin_mint == *quote_mint,
+        market_program: amm_info.market_program,
+        market: amm_info.market,
+        bids: market.bids,
+        asks: market.asks,
+        event_queue: market.event_queue,
+        coin_vault: market.coin_vault,
+        pc_vault: market.pc_vault,
+        vault_signer: market.vault_signer(&amm_info.market, &amm_info.market_program)?,
+    })
+}
+
+/// 加载一个 Raydium AMM 池
+///
+/// 验证池账户的所有者，确认池中同时包含目标代币和报价货币（默认 SOL），然后把 vault 地址加入 `pool_data`。
+pub fn load_amm_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let raydium_pool_pubkey = *pool_address;
+
+    // 获取池账户信息并验证其所有者是否为 Raydium 程序
+    match get_account(&raydium_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *raydium_program_id() {
+                error!(
+                    "Error: Raydium pool account is not owned by the Raydium program. Expected: {}, Actual: {}",
+                    *raydium_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Raydium pool account is not owned by the Raydium program"
+                ));
+            }
+
+            // 解析账户数据为 AmmInfo 并进行有效性检查
+            match RaydiumAmmInfo::load_checked(&account.data) {
+                Ok(amm_info) => {
+                    // 确保目标 mint 在池中存在
+                    if amm_info.coin_mint != pool_data.mint && amm_info.pc_mint != pool_data.mint {
+                        error!(
+                            "Mint {} is not present in Raydium pool {}, skipping",
+                            pool_data.mint, raydium_pool_pubkey
+                        );
+                        return Err(anyhow::anyhow!(
+                            "Invalid Raydium pool: {}",
+                            raydium_pool_pubkey
+                        ));
+                    }
+
+                    // 根据报价货币是 coin 还是 pc 来确定 vault 的对应关系
+                    let side = quote_side(
+                        pool_data,
+                        "Raydium",
+                        &raydium_pool_pubkey,
+                        &amm_info.coin_mint,
+                        &amm_info.pc_mint,
+                    )?;
+                    let (quote_mint, sol_vault, token_vault) = match side {
+                        QuoteSide::A => {
+                            (amm_info.coin_mint, amm_info.coin_vault, amm_info.pc_vault)
+                        }
+                        QuoteSide::B => (amm_info.pc_mint, amm_info.pc_vault, amm_info.coin_vault),
+                    };
+
+                    // 将解析出的池信息加入 pool_data
+                    pool_data.add_raydium_pool(*pool_address, token_vault, sol_vault);
+                    debug!("Raydium pool added: {}", pool_address);
+                    debug!("    Coin mint: {}", amm_info.coin_mint.to_string());
+                    debug!("    PC mint: {}", amm_info.pc_mint.to_string());
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    match raydium_amm_fee(&account.data) {
+                        Some(fee) => {
+                            debug!("    Fee: {} bps", fee.total_bps());
+                            pool_data.pool_fees.insert(raydium_pool_pubkey, fee);
+                        }
+                        None => warn!("    Raydium pool has no valid swap fee, using default"),
+                    }
+
+                    // swap 指令需要的 OpenBook 市场账户，加载失败不影响通过执行程序交易
+                    match load_market(&amm_info, &quote_mint, get_account) {
+                        Ok(market) => {
+                            debug!("    Market: {}", market.market);
+                            pool_data.set_raydium_market(&raydium_pool_pubkey, market);
+                        }
+                        Err(e) => warn!(
+                            "    Failed to load OpenBook market {} for Raydium pool {}: {}",
+                            amm_info.market, raydium_pool_pubkey, e
+                        ),
+                    }
+                    debug!("    Initialized Raydium pool: {}\n", raydium_pool_pubkey);
+                }
+                Err(e) => {
+                    error!(
+                        "Error parsing AmmInfo from Raydium pool {}: {:?}",
+                        raydium_pool_pubkey, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching Raydium pool account {}: {:?}",
+                raydium_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching Raydium pool account"));
+        }
+    }
+    Ok(())
+}
+
+/// 加载一个 Raydium CP 池
+///
+/// 除 vault 外还需要 AMM 配置和观测账户。
+pub fn load_cp_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let raydium_cp_pool_pubkey = *pool_address;
+
+    // 获取池账户信息
+    match get_account(&raydium_cp_pool_pubkey) {
+        Ok(account) => {
+            // 验证账户是否由正确的程序拥有
+            if account.owner != *raydium_cp_program_id() {
+                error!(
+                    "Error: Raydium CP pool account is not owned by the Raydium CP program. Expected: {}, Actual: {}",
+                    *raydium_cp_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Raydium CP pool account is not owned by the Raydium CP program"
+                ));
+            }
+
+            // 尝试解析账户中的 AMM 信息
+            match RaydiumCpAmmInfo::load_checked(&account.data) {
+                Ok(amm_info) => {
+                    // 确保目标代币存在于该池中
+                    if amm_info.token_0_mint != pool_data.mint
+                        && amm_info.token_1_mint != pool_data.mint
+                    {
+                        error!(
+                            "Mint {} is not present in Raydium CP pool {}, skipping",
+                            pool_data.mint, raydium_cp_pool_pubkey
+                        );
+                        return Err(anyhow::anyhow!(
+                            "Invalid Raydium CP pool: {}",
+                            raydium_cp_pool_pubkey
+                        ));
+                    }
+
+                    // 根据报价货币是 token0 还是 token1 来决定 vault 的顺序
+                    let side = quote_side(
+                        pool_data,
+                        "Raydium CP",
+                        &raydium_cp_pool_pubkey,
+                        &amm_info.token_0_mint,
+                        &amm_info.token_1_mint,
+                    )?;
+                    let (sol_vault, token_vault) = match side {
+                        QuoteSide::A => (amm_info.token_0_vault, amm_info.token_1_vault),
+                        QuoteSide::B => (amm_info.token_1_vault, amm_info.token_0_vault),
+                    };
+
+                    // 将解析出的信息添加到 pool_data 中
+                    pool_data.add_raydium_cp_pool(
+                        *pool_address,
+                        token_vault,
+                        sol_vault,
+                        amm_info.amm_config,
+                        amm_info.observation_key,
+                    );
+                    debug!("Raydium CP pool added: {}", pool_address);
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    debug!("    AMM Config: {}", amm_info.amm_config.to_string());
+                    debug!(
+                        "    Observation Key: {}",
+                        amm_info.observation_key.to_string()
+                    );
+
+                    // 交易手续费在 AMM 配置账户中，读取失败时报价使用默认费率
+                    match get_account(&amm_info.amm_config)
+                        .map(|config| raydium_cp_fee(&config.data))
+                    {
+                        Ok(Some(fee)) => {
+                            debug!("    Fee: {} bps\n", fee.total_bps());
+                            pool_data.pool_fees.insert(raydium_cp_pool_pubkey, fee);
+                        }
+                        _ => warn!(
+                            "    Failed to read fee from AMM config {}, using default\n",
+                            amm_info.amm_config
+                        ),
+                    }
+                }
+                Err(e) => {
+                    error!(
+                        "Error parsing AmmInfo from Raydium CP pool {}: {:?}",
+                        raydium_cp_pool_pubkey, e
+                    );
+                    return Err(e);
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching Raydium CP pool account {}: {:?}",
+                raydium_cp_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching Raydium CP pool account"));
+        }
+    }
+    Ok(())
+}
+
+/// 加载一个 Raydium CLMM 池，并根据当前 tick 计算前后三个 Tick Array 地址
+pub fn load_clmm_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let raydium_clmm_program_id = *raydium_clmm_program_id();
+
+    match get_account(pool_address) {
+        Ok(account) => {
+            if account.owner != raydium_clmm_program_id {
+                return Err(anyhow::anyhow!(
+                    "Raydium CLMM pool {} is not owned by the Raydium CLMM program",
+                    pool_address
+                ));
+            }
+
+            match PoolState::load_checked(&account.data) {
+                Ok(raydium_clmm) => {
+                    if raydium_clmm.token_mint_0 != pool_data.mint
+                        && raydium_clmm.token_mint_1 != pool_data.mint
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Mint {} is not present in Raydium CLMM pool {}",
+                            pool_data.mint,
+                            pool_address
+                        ));
+                    }
+
+                    let side = quote_side(
+                        pool_data,
+                        "Raydium CLMM",
+                        pool_address,
+                        &raydium_clmm.token_mint_0,
+                        &raydium_clmm.token_mint_1,
+                    )?;
+                    let (token_vault, sol_vault) = match side {
+                        QuoteSide::A => (raydium_clmm.token_vault_1, raydium_clmm.token_vault_0),
+                        QuoteSide::B => (raydium_clmm.token_vault_0, raydium_clmm.token_vault_1),
+                    };
+
+                    let tick_array_pubkeys = get_tick_array_pubkeys(
+                        pool_address,
+                        raydium_clmm.tick_current,
+                        raydium_clmm.tick_spacing,
+                        &[-1, 0, 1],
+                        &raydium_clmm_program_id,
+                    )?;
+
+                    pool_data.add_raydium_clmm_pool(
+                        *pool_address,
+                        raydium_clmm.amm_config,
+                        raydium_clmm.observation_key,
+                        token_vault,
+                        sol_vault,
+                        tick_array_pubkeys.clone(),
+                        None, // memo_program
+                    );
+
+                    debug!("Raydium CLMM pool added: {}", pool_address);
+                    debug!(
+                        "    Token mint 0: {}",
+                        raydium_clmm.token_mint_0.to_string()
+                    );
+                    debug!(
+                        "    Token mint 1: {}",
+                        raydium_clmm.token_mint_1.to_string()
+                    );
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    debug!("    AMM config: {}", raydium_clmm.amm_config.to_string());
+                    debug!(
+                        "    Observation key: {}",
+                        raydium_clmm.observation_key.to_string()
+                    );
+
+                    for (i, array) in tick_array_pubkeys.iter().enumerate() {
+                        debug!("    Tick Array {}: {}", i, array);
+                    }
+
+                    match get_account(&raydium_clmm.amm_config)
+                        .map(|config| raydium_clmm_fee(&config.data))
+                    {
+                        Ok(Some(fee)) => {
+                            debug!("    Fee: {} bps", fee.total_bps());
+                            pool_data.pool_fees.insert(*pool_address, fee);
+                        }
+                        _ => warn!(
+                            "    Failed to read fee from AMM config {}, using default",
+                            raydium_clmm.amm_config
+                        ),
+                    }
+                    debug!("");
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Error parsing Raydium CLMM data from pool {}: {:?}",
+                        pool_address,
+                        e
+                    ));
+                }
+            }
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Error fetching Raydium CLMM pool account {}: {:?}",
+                pool_address,
+                e
+            ));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use crate::dex::discriminator::check_account_len;
+use anyhow::Result;
+use solana_program::pubkey::Pubkey;
+
+// Serum/OpenBook MarketState 布局，账户数据以 5 字节的 "serum" 填充开头
+const VAULT_SIGNER_NONCE_OFFSET: usize = 45;
+const COIN_VAULT_OFFSET: usize = 117;
+const PC_VAULT_OFFSET: usize = 165;
+const EVENT_QUEUE_OFFSET: usize = 253;
+const BIDS_OFFSET: usize = 285;
+const ASKS_OFFSET: usize = 317;
+/// 市场账户的长度，包括开头 5 字节和结尾 7 字节的填充
+const MARKET_LEN: usize = 388;
+/// "serum" 填充之后的账户标志：已初始化 | 市场
+const ACCOUNT_FLAGS_OFFSET: usize = 5;
+const MARKET_ACCOUNT_FLAGS: u64 = 1 | 2;
+
+#[derive(Debug)]
+/// OpenBook（Serum）市场信息
+///
+/// Raydium AMM v4 的 swap 指令除了池子 vault 之外，还需要池子关联市场的订单簿、
+/// 事件队列、vault 和 vault signer 账户。
+pub struct OpenBookMarket {
+    /// 市场的 vault signer PDA 使用的 nonce
+    pub vault_signer_nonce: u64,
+    /// 市场的 coin vault
+    pub coin_vault: Pubkey,
+    /// 市场的 pc vault
+    pub pc_vault: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+}
+
+impl OpenBookMarket {
+    /// 从市场账户数据中解析订单簿相关账户
+    ///
+    /// # 参数
+    /// * `data` - 市场账户的原始字节数据
+    ///
+    /// # 错误
+    /// 不以 "serum" 填充开头、长度不是当前布局或账户标志不是已初始化的市场时返回错误
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        if !data.starts_with(b"serum") {
+            return Err(anyhow::anyhow!("Invalid data for OpenBook market"));
+        }
+        check_account_len(data, "OpenBook market", MARKET_LEN)?;
+        let account_flags =
+            u64::from_le_bytes(data[ACCOUNT_FLAGS_OFFSET..ACCOUNT_FLAGS_OFFSET + 8].try_into()?);
+        if account_flags & MARKET_ACCOUNT_FLAGS != MARKET_ACCOUNT_FLAGS {
+            return Err(anyhow::anyhow!(
+                "Unsupported account version: OpenBook account flags {:#x} are not an initialized market",
+                account_flags
+            ));
+        }
+
+        let vault_signer_nonce = u64::from_le_bytes(
+            data[VAULT_SIGNER_NONCE_OFFSET..VAULT_SIGNER_NONCE_OFFSET + 8].try_into()?,
+        );
+        let coin_vault = Pubkey::try_from(&data[COIN_VAULT_OFFSET..COIN_VAULT_OFFSET + 32])?;
+        let pc_vault = Pubkey::try_from(&data[PC_VAULT_OFFSET..PC_VAULT_OFFSET + 32])?;
+        let event_queue = Pubkey::try_from(&data[EVENT_QUEUE_OFFSET..EVENT_QUEUE_OFFSET + 32])?;
+        let bids = Pubkey::try_from(&data[BIDS_OFFSET..BIDS_OFFSET + 32])?;
+        let asks = Pubkey::try_from(&data[ASKS_OFFSET..ASKS_OFFSET + 32])?;
+
+        Ok(Self {
+            vault_signer_nonce,
+            coin_vault,
+            pc_vault,
+            event_queue,
+            bids,
+            asks,
+        })
+    }
+
+    /// 市场的 vault signer，由市场地址和 nonce 推导
+    ///
+    /// # 参数
+    /// * `market` - 市场地址
+    /// * `market_program` - 市场所属的程序
+    pub fn vault_signer(&self, market: &Pubkey, market_program: &Pubkey) -> Result<Pubkey> {
+        Ok(Pubkey::create_program_address(
+            &[market.as_ref(), &self.vault_signer_nonce.to_le_bytes()],
+            market_program,
+        )?)
+    }
+}
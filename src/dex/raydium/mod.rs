@@ -1,9 +1,12 @@
 pub mod amm_info;
+pub mod clmm_info;
 pub mod constants;
 pub mod cp_amm_info;
-pub mod clmm_info;
+pub mod loader;
+pub mod market;
 
 pub use amm_info::RaydiumAmmInfo;
+pub use clmm_info::{get_tick_array_pubkeys, PoolState};
 pub use constants::*;
 pub use cp_amm_info::RaydiumCpAmmInfo;
-pub use clmm_info::{PoolState, get_tick_array_pubkeys};
+pub use market::OpenBookMarket;
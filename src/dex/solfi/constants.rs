@@ -1,6 +1,6 @@
+use crate::constants::{program_id, ProgramId};
 use solana_program::pubkey::Pubkey;
-use std::str::FromStr;
 
-pub fn solfi_program_id() -> Pubkey {
-    Pubkey::from_str("SoLFiHG9TfgtdUXUjWAxi3LtvYuFyDLVhBWxdMZxyCe").unwrap()
+pub fn solfi_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Solfi)
 }
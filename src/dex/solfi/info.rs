@@ -1,6 +1,11 @@
+use crate::dex::discriminator::check_account_len;
 use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
 
+/// Solfi 池子账户的长度
+const SOLFI_POOL_LEN: usize = 2816;
+
+#[derive(Debug)]
 pub struct SolfiInfo {
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
@@ -10,6 +15,8 @@ pub struct SolfiInfo {
 
 impl SolfiInfo {
     pub fn load_checked(data: &[u8]) -> Result<Self> {
+        // Solfi 池子没有类型标识，按账户长度核对布局版本
+        check_account_len(data, "Solfi pool", SOLFI_POOL_LEN)?;
         let base_mint = Pubkey::new(&data[2664..2696]);
         let quote_mint = Pubkey::new(&data[2696..2728]);
         let base_vault = Pubkey::new(&data[2736..2768]);
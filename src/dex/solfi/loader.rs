@@ -0,0 +1,81 @@
+use crate::dex::solfi::constants::solfi_program_id;
+use crate::dex::solfi::info::SolfiInfo;
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::debug;
+
+/// 加载一个 Solfi 池
+///
+/// Solfi 的 swap 除池子和两个 vault 外只需要 instructions sysvar（程序用它校验调用方），
+/// 价格由做市方直接写入池子账户，没有单独的预言机账户，所以只从池子账户读取 mint 和 vault。
+/// 池子必须包含目标代币，另一侧不是报价货币时按 `routing.non_quote_pools` 处理。
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let solfi_pool_pubkey = *pool_address;
+
+    match get_account(&solfi_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *solfi_program_id() {
+                return Err(anyhow::anyhow!(
+                    "Solfi pool {} is not owned by the Solfi program",
+                    pool_address
+                ));
+            }
+
+            match SolfiInfo::load_checked(&account.data) {
+                Ok(solfi_info) => {
+                    debug!("Solfi pool added: {}", pool_address);
+                    debug!("    Base mint: {}", solfi_info.base_mint.to_string());
+                    debug!("    Quote mint: {}", solfi_info.quote_mint.to_string());
+                    debug!("    Base vault: {}", solfi_info.base_vault.to_string());
+                    debug!("    Quote vault: {}", solfi_info.quote_vault.to_string());
+
+                    if solfi_info.base_mint != pool_data.mint
+                        && solfi_info.quote_mint != pool_data.mint
+                    {
+                        return Err(anyhow::anyhow!(
+                            "Solfi pool {} trades {}/{}, not {}/{}",
+                            pool_address,
+                            solfi_info.base_mint,
+                            solfi_info.quote_mint,
+                            pool_data.mint,
+                            pool_data.quote_mint
+                        ));
+                    }
+                    let side = quote_side(
+                        pool_data,
+                        "Solfi",
+                        &solfi_pool_pubkey,
+                        &solfi_info.base_mint,
+                        &solfi_info.quote_mint,
+                    )?;
+                    let (token_x_vault, token_sol_vault) = match side {
+                        QuoteSide::A => (solfi_info.quote_vault, solfi_info.base_vault),
+                        QuoteSide::B => (solfi_info.base_vault, solfi_info.quote_vault),
+                    };
+
+                    pool_data.add_solfi_pool(*pool_address, token_x_vault, token_sol_vault);
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Error parsing Solfi pool data from pool {}: {:?}",
+                        pool_address,
+                        e
+                    ));
+                }
+            }
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Error fetching Solfi pool account {}: {:?}",
+                solfi_pool_pubkey,
+                e
+            ));
+        }
+    }
+    Ok(())
+}
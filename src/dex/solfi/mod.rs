@@ -1,2 +1,3 @@
 pub mod constants;
 pub mod info;
+pub mod loader;
@@ -1,6 +1,6 @@
+use crate::constants::{program_id, ProgramId};
 use solana_program::pubkey::Pubkey;
-use std::str::FromStr;
 
-pub fn vertigo_program_id() -> Pubkey {
-    Pubkey::from_str("vrTGoBuy5rYSxAfV3jaRJWHH6nN9WK4NRExGxsk1bCJ").unwrap()
+pub fn vertigo_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Vertigo)
 }
@@ -1,3 +1,4 @@
+use crate::dex::discriminator::check_account_len;
 use anyhow::Result;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
@@ -10,6 +11,9 @@ pub struct VertigoPool {
     // Add other fields as needed based on actual Vertigo pool structure
 }
 
+/// 池子账户的长度：mint_a、mint_b、owner 三个公钥
+const VERTIGO_POOL_LEN: usize = 3 * 32;
+
 impl VertigoPool {
     pub fn try_deserialize(data: &mut &[u8]) -> Result<Self> {
         Self::try_from_slice(data)
@@ -26,6 +30,7 @@ pub struct VertigoInfo {
 
 impl VertigoInfo {
     pub fn load_checked(data: &[u8], pool: &Pubkey) -> Result<Self> {
+        check_account_len(data, "Vertigo pool", VERTIGO_POOL_LEN)?;
         let mut data_slice = &data[..];
         let vertigo_pool = VertigoPool::try_deserialize(&mut data_slice)?;
 
@@ -57,5 +62,5 @@ impl VertigoInfo {
 pub fn derive_vault_address(pool: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
     use crate::dex::vertigo::constants::vertigo_program_id;
 
-    Pubkey::find_program_address(&[pool.as_ref(), mint.as_ref()], &vertigo_program_id())
+    Pubkey::find_program_address(&[pool.as_ref(), mint.as_ref()], vertigo_program_id())
 }
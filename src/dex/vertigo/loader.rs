@@ -0,0 +1,90 @@
+use crate::dex::vertigo::{derive_vault_address, vertigo_program_id, VertigoInfo};
+use crate::dex::{quote_side, GetAccount};
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, error};
+
+/// 加载一个 Vertigo 池，两个 vault 由池子地址和 mint 推导
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let vertigo_pool_pubkey = *pool_address;
+
+    match get_account(&vertigo_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *vertigo_program_id() {
+                error!(
+                    "Error: Vertigo pool account is not owned by the Vertigo program. Expected: {}, Actual: {}",
+                    *vertigo_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Vertigo pool account is not owned by the Vertigo program"
+                ));
+            }
+
+            match VertigoInfo::load_checked(&account.data, &vertigo_pool_pubkey) {
+                Ok(vertigo_info) => {
+                    debug!("Vertigo pool added: {}", pool_address);
+                    debug!("    Mint A: {}", vertigo_info.mint_a.to_string());
+                    debug!("    Mint B: {}", vertigo_info.mint_b.to_string());
+
+                    // vault 由目标代币推导，这里只检查另一侧是报价货币
+                    quote_side(
+                        pool_data,
+                        "Vertigo",
+                        &vertigo_pool_pubkey,
+                        &vertigo_info.mint_a,
+                        &vertigo_info.mint_b,
+                    )?;
+
+                    let base_mint = pool_data.mint.to_string();
+
+                    // Following the original loading pattern from user's code:
+                    let non_base_vault = if base_mint == vertigo_info.mint_a.to_string() {
+                        derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_b).0
+                    } else {
+                        derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_a).0
+                    };
+                    let base_vault = if base_mint == vertigo_info.mint_a.to_string() {
+                        derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_a).0
+                    } else {
+                        derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_b).0
+                    };
+
+                    // Map to transaction expected fields:
+                    // base_mint is our trading token, non-base should be SOL
+                    let token_x_vault = base_vault; // vault for our trading token
+                    let token_sol_vault = non_base_vault; // vault for SOL
+
+                    debug!("    Token X Vault: {}", token_x_vault.to_string());
+                    debug!("    Token SOL Vault: {}", token_sol_vault.to_string());
+                    debug!("");
+
+                    pool_data.add_vertigo_pool(
+                        *pool_address,
+                        vertigo_info.pool,
+                        token_x_vault,
+                        token_sol_vault,
+                    );
+                }
+                Err(e) => {
+                    return Err(anyhow::anyhow!(
+                        "Error parsing Vertigo pool data from pool {}: {:?}",
+                        vertigo_pool_pubkey,
+                        e
+                    ));
+                }
+            }
+        }
+        Err(e) => {
+            return Err(anyhow::anyhow!(
+                "Error fetching Vertigo pool account {}: {:?}",
+                vertigo_pool_pubkey,
+                e
+            ));
+        }
+    }
+    Ok(())
+}
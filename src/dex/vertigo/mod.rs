@@ -1,5 +1,6 @@
 pub mod constants;
 pub mod info;
+pub mod loader;
 
 pub use constants::*;
 pub use info::*;
@@ -0,0 +1,135 @@
+use crate::dex::whirlpool::{
+    constants::whirlpool_program_id,
+    state::{Whirlpool, WhirlpoolOracle},
+    update_tick_array_accounts_for_onchain,
+};
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::fees::{whirlpool_adaptive_fee, whirlpool_fee};
+use crate::pools::MintPoolData;
+use crate::status::unix_now;
+use solana_program::pubkey::Pubkey;
+use tracing::{debug, error, warn};
+
+/// 加载一个 Whirlpool 池，并根据当前 tick 计算 oracle 和 Tick Array 地址
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let whirlpool_pool_pubkey = *pool_address;
+
+    match get_account(&whirlpool_pool_pubkey) {
+        Ok(account) => {
+            if account.owner != *whirlpool_program_id() {
+                error!(
+                    "Error: Whirlpool pool account is not owned by the Whirlpool program. Expected: {}, Actual: {}",
+                    *whirlpool_program_id(), account.owner
+                );
+                return Err(anyhow::anyhow!(
+                    "Whirlpool pool account is not owned by the Whirlpool program"
+                ));
+            }
+
+            match Whirlpool::try_deserialize(&account.data) {
+                Ok(whirlpool) => {
+                    if whirlpool.token_mint_a != pool_data.mint
+                        && whirlpool.token_mint_b != pool_data.mint
+                    {
+                        error!(
+                            "Mint {} is not present in Whirlpool pool {}, skipping",
+                            pool_data.mint, whirlpool_pool_pubkey
+                        );
+                        return Err(anyhow::anyhow!(
+                            "Invalid Whirlpool pool: {}",
+                            whirlpool_pool_pubkey
+                        ));
+                    }
+
+                    let side = quote_side(
+                        pool_data,
+                        "Whirlpool",
+                        &whirlpool_pool_pubkey,
+                        &whirlpool.token_mint_a,
+                        &whirlpool.token_mint_b,
+                    )?;
+                    let (sol_vault, token_vault) = match side {
+                        QuoteSide::A => (whirlpool.token_vault_a, whirlpool.token_vault_b),
+                        QuoteSide::B => (whirlpool.token_vault_b, whirlpool.token_vault_a),
+                    };
+
+                    let whirlpool_oracle = Pubkey::find_program_address(
+                        &[b"oracle", whirlpool_pool_pubkey.as_ref()],
+                        whirlpool_program_id(),
+                    )
+                    .0;
+
+                    let whirlpool_tick_arrays = update_tick_array_accounts_for_onchain(
+                        &whirlpool,
+                        &whirlpool_pool_pubkey,
+                        whirlpool_program_id(),
+                    );
+
+                    let tick_arrays: Vec<Pubkey> = whirlpool_tick_arrays
+                        .iter()
+                        .map(|meta| meta.pubkey)
+                        .collect();
+
+                    pool_data.add_whirlpool_pool(
+                        *pool_address,
+                        whirlpool_oracle,
+                        token_vault,
+                        sol_vault,
+                        tick_arrays.clone(),
+                        None, // memo_program
+                    );
+
+                    debug!("Whirlpool pool added: {}", pool_address);
+                    debug!("    Token mint A: {}", whirlpool.token_mint_a.to_string());
+                    debug!("    Token mint B: {}", whirlpool.token_mint_b.to_string());
+                    debug!("    Token vault: {}", token_vault.to_string());
+                    debug!("    Sol vault: {}", sol_vault.to_string());
+                    debug!("    Oracle: {}", whirlpool_oracle.to_string());
+                    // 自适应手续费池子的费率还取决于 oracle 中的波动状态，之后随 vault 一起刷新
+                    let fee = if whirlpool.is_initialized_with_adaptive_fee() {
+                        match get_account(&whirlpool_oracle)
+                            .and_then(|oracle| Ok(WhirlpoolOracle::try_deserialize(&oracle.data)?))
+                        {
+                            Ok(oracle) => whirlpool_adaptive_fee(&whirlpool, &oracle, unix_now()),
+                            Err(e) => {
+                                warn!(
+                                    "Failed to read the adaptive fee oracle {} of Whirlpool pool {}, using the static fee: {}",
+                                    whirlpool_oracle, whirlpool_pool_pubkey, e
+                                );
+                                whirlpool_fee(whirlpool.fee_rate)
+                            }
+                        }
+                    } else {
+                        whirlpool_fee(whirlpool.fee_rate)
+                    };
+                    debug!("    Fee: {} bps", fee.total_bps());
+                    pool_data.pool_fees.insert(whirlpool_pool_pubkey, fee);
+
+                    for (i, array) in tick_arrays.iter().enumerate() {
+                        debug!("    Tick Array {}: {}", i, array);
+                    }
+                    debug!("");
+                }
+                Err(e) => {
+                    error!(
+                        "Error parsing Whirlpool data from pool {}: {:?}",
+                        whirlpool_pool_pubkey, e
+                    );
+                    return Err(anyhow::anyhow!("Error parsing Whirlpool data"));
+                }
+            }
+        }
+        Err(e) => {
+            error!(
+                "Error fetching Whirlpool pool account {}: {:?}",
+                whirlpool_pool_pubkey, e
+            );
+            return Err(anyhow::anyhow!("Error fetching Whirlpool pool account"));
+        }
+    }
+    Ok(())
+}
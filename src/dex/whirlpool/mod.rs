@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod loader;
 pub mod state;
 
 use crate::dex::whirlpool::state::{Whirlpool, TICK_ARRAY_SIZE};
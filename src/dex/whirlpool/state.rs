@@ -39,6 +39,19 @@ pub struct Whirlpool {
 
 impl Whirlpool {
     pub const LEN: usize = 8 + 261 + 384;
+
+    /// 创建池子时使用的手续费档位，早期池子中是 `tick_spacing_seed`
+    pub fn fee_tier_index(&self) -> u16 {
+        u16::from_le_bytes(self.tick_spacing_seed)
+    }
+
+    /// 是否通过 `AdaptiveFeeTier` 创建、启用了自适应手续费
+    ///
+    /// 普通池子的手续费档位就是 tick spacing；自适应手续费档位的编号与 tick spacing 不同，
+    /// 这类池子另有 oracle 账户记录波动状态，实际费率为 `fee_rate` 加上自适应费率。
+    pub fn is_initialized_with_adaptive_fee(&self) -> bool {
+        self.fee_tier_index() != self.tick_spacing
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -75,6 +88,7 @@ impl Tick {
 
 pub const TICK_ARRAY_SIZE: usize = 88;
 
+use crate::dex::discriminator::check_anchor_discriminator;
 use std::io::{Error, ErrorKind, Result};
 
 impl Whirlpool {
@@ -82,6 +96,8 @@ impl Whirlpool {
         if data.len() < Self::LEN {
             return Err(Error::new(ErrorKind::InvalidData, "data too short for Whirlpool"));
         }
+        check_anchor_discriminator(data, "Whirlpool")
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
 
         let data = &data[8..];
         
@@ -227,3 +243,182 @@ impl Whirlpool {
         })
     }
 }
+
+/// 自适应手续费的固定参数，创建池子时从 `AdaptiveFeeTier` 复制到 oracle
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveFeeConstants {
+    /// 距离上一次更新不足这么多秒时不衰减参考波动
+    pub filter_period: u16,
+    /// 距离上一次更新超过这么多秒时参考波动清零
+    pub decay_period: u16,
+    /// 衰减时参考波动保留的比例（万分之一）
+    pub reduction_factor: u16,
+    pub adaptive_fee_control_factor: u32,
+    pub max_volatility_accumulator: u32,
+    /// 波动按多少个 tick 为一组计算
+    pub tick_group_size: u16,
+    pub major_swap_threshold_ticks: u16,
+}
+
+/// 自适应手续费的波动状态，每次 swap 后更新
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AdaptiveFeeVariables {
+    pub last_reference_update_timestamp: u64,
+    pub last_major_swap_timestamp: u64,
+    pub volatility_reference: u32,
+    pub tick_group_index_reference: i32,
+    pub volatility_accumulator: u32,
+}
+
+/// 启用自适应手续费的池子的 oracle 账户，地址为 PDA `["oracle", whirlpool]`
+#[derive(Clone, Copy, Debug)]
+pub struct WhirlpoolOracle {
+    pub whirlpool: Pubkey,
+    /// 在此之前池子不允许交易
+    pub trade_enable_timestamp: u64,
+    pub adaptive_fee_constants: AdaptiveFeeConstants,
+    pub adaptive_fee_variables: AdaptiveFeeVariables,
+}
+
+impl WhirlpoolOracle {
+    /// 类型标识、池子地址、开放交易时间、固定参数（34）、波动状态（44）和保留字节
+    pub const LEN: usize = 8 + 32 + 8 + 34 + 44 + 128;
+
+    /// 解析 oracle 账户，账户按 `repr(C, packed)` 存储，字段之间没有填充
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        if data.len() < Self::LEN {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "data too short for Oracle",
+            ));
+        }
+        check_anchor_discriminator(data, "Oracle")
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+
+        let data = &data[8..];
+        let u16_at = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        Ok(WhirlpoolOracle {
+            whirlpool: Pubkey::new_from_array(data[0..32].try_into().unwrap()),
+            trade_enable_timestamp: u64_at(32),
+            adaptive_fee_constants: AdaptiveFeeConstants {
+                filter_period: u16_at(40),
+                decay_period: u16_at(42),
+                reduction_factor: u16_at(44),
+                adaptive_fee_control_factor: u32_at(46),
+                max_volatility_accumulator: u32_at(50),
+                tick_group_size: u16_at(54),
+                major_swap_threshold_ticks: u16_at(56),
+            },
+            adaptive_fee_variables: AdaptiveFeeVariables {
+                last_reference_update_timestamp: u64_at(74),
+                last_major_swap_timestamp: u64_at(82),
+                volatility_reference: u32_at(90),
+                tick_group_index_reference: u32_at(94) as i32,
+                volatility_accumulator: u32_at(98),
+            },
+        })
+    }
+}
+
+impl Tick {
+    /// 固定布局中每个 tick 的长度：是否初始化（1）、净流动性和总流动性（32）、手续费和奖励增长（80）
+    pub const LEN: usize = 113;
+
+    /// 解析已初始化 tick 的数据（不含 `initialized` 标志）
+    fn from_data(data: &[u8]) -> Self {
+        let u128_at =
+            |offset: usize| u128::from_le_bytes(data[offset..offset + 16].try_into().unwrap());
+        Tick {
+            initialized: true,
+            liquidity_net: u128_at(0) as i128,
+            liquidity_gross: u128_at(16),
+            fee_growth_outside_a: u128_at(32),
+            fee_growth_outside_b: u128_at(48),
+            reward_growths_outside: [u128_at(64), u128_at(80), u128_at(96)],
+        }
+    }
+}
+
+impl TickArray {
+    /// 固定布局的长度：类型标识、起始 tick、88 个 tick 和池子地址
+    pub const LEN: usize = 8 + 4 + Tick::LEN * TICK_ARRAY_SIZE + 32;
+
+    /// 动态布局的头部长度：类型标识、起始 tick、池子地址和初始化位图
+    const DYNAMIC_HEADER_LEN: usize = 8 + 4 + 32 + 16;
+
+    /// 解析 tick array 账户，支持固定布局的 `TickArray` 和只为已初始化 tick 分配空间的 `DynamicTickArray`
+    pub fn try_deserialize(data: &[u8]) -> Result<Self> {
+        let too_short = || Error::new(ErrorKind::InvalidData, "data too short for TickArray");
+        if check_anchor_discriminator(data, "DynamicTickArray").is_ok() {
+            return Self::try_deserialize_dynamic(data);
+        }
+        if data.len() < Self::LEN {
+            return Err(too_short());
+        }
+        check_anchor_discriminator(data, "TickArray")
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e.to_string()))?;
+
+        let mut ticks = [Tick::default(); TICK_ARRAY_SIZE];
+        for (i, tick) in ticks.iter_mut().enumerate() {
+            let offset = 12 + i * Tick::LEN;
+            if data[offset] != 0 {
+                *tick = Tick::from_data(&data[offset + 1..offset + Tick::LEN]);
+            }
+        }
+        let whirlpool_offset = 12 + Tick::LEN * TICK_ARRAY_SIZE;
+        Ok(TickArray {
+            start_tick_index: i32::from_le_bytes(data[8..12].try_into().unwrap()),
+            ticks,
+            whirlpool: Pubkey::new_from_array(
+                data[whirlpool_offset..whirlpool_offset + 32]
+                    .try_into()
+                    .unwrap(),
+            ),
+        })
+    }
+
+    /// 动态布局中每个 tick 以 1 字节标签开头，未初始化的 tick 只有标签
+    fn try_deserialize_dynamic(data: &[u8]) -> Result<Self> {
+        let too_short = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                "data too short for DynamicTickArray",
+            )
+        };
+        if data.len() < Self::DYNAMIC_HEADER_LEN {
+            return Err(too_short());
+        }
+
+        let mut ticks = [Tick::default(); TICK_ARRAY_SIZE];
+        let mut offset = Self::DYNAMIC_HEADER_LEN;
+        for tick in ticks.iter_mut() {
+            match data.get(offset) {
+                Some(0) => offset += 1,
+                Some(1) => {
+                    let tick_data = data
+                        .get(offset + 1..offset + Tick::LEN)
+                        .ok_or_else(too_short)?;
+                    *tick = Tick::from_data(tick_data);
+                    offset += Tick::LEN;
+                }
+                Some(tag) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid DynamicTick tag {}", tag),
+                    ))
+                }
+                None => return Err(too_short()),
+            }
+        }
+        Ok(TickArray {
+            start_tick_index: i32::from_le_bytes(data[8..12].try_into().unwrap()),
+            ticks,
+            whirlpool: Pubkey::new_from_array(data[12..44].try_into().unwrap()),
+        })
+    }
+}
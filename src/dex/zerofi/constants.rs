@@ -0,0 +1,6 @@
+use crate::constants::{program_id, ProgramId};
+use solana_program::pubkey::Pubkey;
+
+pub fn zerofi_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Zerofi)
+}
@@ -0,0 +1,36 @@
+use crate::dex::discriminator::check_account_len;
+use anyhow::Result;
+use solana_sdk::pubkey::Pubkey;
+
+/// ZeroFi 交易对账户的长度
+const ZEROFI_PAIR_LEN: usize = 520;
+
+/// ZeroFi 交易对
+///
+/// 每一边除了代币 vault 之外还有一个 vault info 账户，记录做市商给出的价格和库存，swap 时两者都要写入。
+#[derive(Debug)]
+pub struct ZeroFiInfo {
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault_info: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault_info: Pubkey,
+    pub quote_vault: Pubkey,
+}
+
+impl ZeroFiInfo {
+    pub fn load_checked(data: &[u8]) -> Result<Self> {
+        // ZeroFi 交易对没有类型标识，按账户长度核对布局版本
+        check_account_len(data, "ZeroFi pair", ZEROFI_PAIR_LEN)?;
+        let pubkey_at = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+
+        Ok(Self {
+            base_mint: pubkey_at(8),
+            quote_mint: pubkey_at(40),
+            base_vault_info: pubkey_at(72),
+            base_vault: pubkey_at(104),
+            quote_vault_info: pubkey_at(136),
+            quote_vault: pubkey_at(168),
+        })
+    }
+}
@@ -0,0 +1,81 @@
+use crate::dex::zerofi::{zerofi_program_id, ZeroFiInfo};
+use crate::dex::{quote_side, GetAccount, QuoteSide};
+use crate::pools::MintPoolData;
+use solana_program::pubkey::Pubkey;
+use tracing::debug;
+
+/// 加载一个 ZeroFi 交易对
+pub fn load_pool(
+    pool_address: &Pubkey,
+    get_account: &mut GetAccount<'_>,
+    pool_data: &mut MintPoolData,
+) -> anyhow::Result<()> {
+    let pair_pubkey = *pool_address;
+    let account = get_account(&pair_pubkey).map_err(|e| {
+        anyhow::anyhow!(
+            "Error fetching ZeroFi pair account {}: {:?}",
+            pair_pubkey,
+            e
+        )
+    })?;
+    if account.owner != *zerofi_program_id() {
+        return Err(anyhow::anyhow!(
+            "ZeroFi pair {} is not owned by the ZeroFi program",
+            pool_address
+        ));
+    }
+
+    let info = ZeroFiInfo::load_checked(&account.data).map_err(|e| {
+        anyhow::anyhow!(
+            "Error parsing ZeroFi pair data from pair {}: {:?}",
+            pool_address,
+            e
+        )
+    })?;
+    if info.base_mint != pool_data.mint && info.quote_mint != pool_data.mint {
+        return Err(anyhow::anyhow!(
+            "ZeroFi pair {} trades {}/{}, not {}/{}",
+            pool_address,
+            info.base_mint,
+            info.quote_mint,
+            pool_data.mint,
+            pool_data.quote_mint
+        ));
+    }
+    let side = quote_side(
+        pool_data,
+        "ZeroFi",
+        &pair_pubkey,
+        &info.base_mint,
+        &info.quote_mint,
+    )?;
+    let (token_x_vault_info, token_x_vault, token_sol_vault_info, token_sol_vault) = match side {
+        QuoteSide::A => (
+            info.quote_vault_info,
+            info.quote_vault,
+            info.base_vault_info,
+            info.base_vault,
+        ),
+        QuoteSide::B => (
+            info.base_vault_info,
+            info.base_vault,
+            info.quote_vault_info,
+            info.quote_vault,
+        ),
+    };
+
+    pool_data.add_zerofi_pool(
+        *pool_address,
+        token_x_vault_info,
+        token_x_vault,
+        token_sol_vault_info,
+        token_sol_vault,
+    );
+
+    debug!("ZeroFi pair added: {}", pool_address);
+    debug!("    Base mint: {}", info.base_mint);
+    debug!("    Quote mint: {}", info.quote_mint);
+    debug!("    Base vault: {}", info.base_vault);
+    debug!("    Quote vault: {}", info.quote_vault);
+    Ok(())
+}
@@ -0,0 +1,352 @@
+use crate::config::{ExecutorConfig, RouterConfig};
+use anyhow::Context;
+use solana_program::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::collections::HashSet;
+use std::str::FromStr;
+
+/// 默认的链上执行程序
+pub const DEFAULT_EXECUTOR_PROGRAM_ID: &str = "MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz";
+
+/// 默认执行程序的手续费接收账户
+pub const DEFAULT_FEE_COLLECTOR: &str = "6AGB9kqgSp2mQXwYpdrV4QVV8urvCaDS35U1wsLssy6H";
+
+/// 默认执行程序 Swap 指令数据的第一个字节
+pub const DEFAULT_SWAP_DISCRIMINATOR: u8 = 26;
+
+/// 当前支持的指令数据布局版本
+///
+/// - `1`: 标识字节 + `minimum_profit` (u64) + `compute_unit_limit` (u64) + `no_failure_mode` (u8)
+///   + 保留字段 (u16，固定为 0) + `use_flashloan` (u8)
+pub const SUPPORTED_LAYOUT_VERSIONS: &[u8] = &[1];
+
+/// 默认执行程序不接受的池子列表
+///
+/// 这些 DEX 的账户组是按各自的 swap 指令排列的，闭源的默认执行程序是否能解析无法确认，
+/// 只能在 `[executor]` 指向支持它们的自部署程序时使用。
+pub const FORK_ONLY_POOL_LISTS: &[&str] =
+    &["moonshot_pool_list", "perena_pool_list", "zerofi_pool_list"];
+
+/// 启动时检查的版本字节所在位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionCheck {
+    /// 记录版本的程序派生账户，为 `None` 时读取程序的可执行数据
+    pub account: Option<Pubkey>,
+    pub offset: usize,
+}
+
+/// 套利交易调用的链上执行程序及其 Swap 指令格式
+///
+/// 默认是公开部署的执行程序；运行自己部署（fork）的程序时通过 `[executor]` 覆盖，
+/// 账户顺序不变，只有程序地址、手续费账户和指令数据可以不同。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorProgram {
+    pub program_id: Pubkey,
+    pub fee_collector: Pubkey,
+    pub discriminator: u8,
+    pub layout_version: u8,
+    /// 配置了 `version_offset` 或 `version_seed` 时启动检查还要比对版本字节
+    pub version_check: Option<VersionCheck>,
+}
+
+impl Default for ExecutorProgram {
+    fn default() -> Self {
+        Self {
+            program_id: Pubkey::from_str(DEFAULT_EXECUTOR_PROGRAM_ID).unwrap(),
+            fee_collector: Pubkey::from_str(DEFAULT_FEE_COLLECTOR).unwrap(),
+            discriminator: DEFAULT_SWAP_DISCRIMINATOR,
+            layout_version: SUPPORTED_LAYOUT_VERSIONS[SUPPORTED_LAYOUT_VERSIONS.len() - 1],
+            version_check: None,
+        }
+    }
+}
+
+impl ExecutorProgram {
+    /// 按配置覆盖默认值，没有 `[executor]` 时返回默认执行程序
+    ///
+    /// # 错误
+    /// 地址不是合法的 pubkey 或布局版本不受支持时返回错误
+    pub fn from_config(config: Option<&ExecutorConfig>) -> anyhow::Result<Self> {
+        let mut executor = Self::default();
+        let Some(config) = config else {
+            return Ok(executor);
+        };
+        executor.apply(
+            config.program_id.as_deref(),
+            config.fee_collector.as_deref(),
+            config.discriminator,
+            config.layout_version,
+        )?;
+        executor.set_version_check(config);
+        Ok(executor)
+    }
+
+    /// 覆盖配置了的字段，其余字段保持不变
+    fn apply(
+        &mut self,
+        program_id: Option<&str>,
+        fee_collector: Option<&str>,
+        discriminator: Option<u8>,
+        layout_version: Option<u8>,
+    ) -> anyhow::Result<()> {
+        if let Some(program_id) = program_id {
+            self.program_id = Pubkey::from_str(program_id)
+                .with_context(|| format!("Invalid executor program ID `{}`", program_id))?;
+        }
+        if let Some(fee_collector) = fee_collector {
+            self.fee_collector = Pubkey::from_str(fee_collector)
+                .with_context(|| format!("Invalid executor fee collector `{}`", fee_collector))?;
+        }
+        if let Some(discriminator) = discriminator {
+            self.discriminator = discriminator;
+        }
+        if let Some(layout_version) = layout_version {
+            if !SUPPORTED_LAYOUT_VERSIONS.contains(&layout_version) {
+                anyhow::bail!(
+                    "Unsupported executor layout version {}, expected one of {:?}",
+                    layout_version,
+                    SUPPORTED_LAYOUT_VERSIONS
+                );
+            }
+            self.layout_version = layout_version;
+        }
+        Ok(())
+    }
+
+    /// 按 `version_offset` 和 `version_seed` 设置版本检查，版本账户由当前程序地址派生
+    fn set_version_check(&mut self, config: &ExecutorConfig) {
+        self.version_check = (config.version_offset.is_some() || config.version_seed.is_some())
+            .then(|| VersionCheck {
+                account: config.version_seed.as_ref().map(|seed| {
+                    Pubkey::find_program_address(&[seed.as_bytes()], &self.program_id).0
+                }),
+                offset: config.version_offset.unwrap_or(0),
+            });
+    }
+
+    /// 启动时检查部署的执行程序与构建交易使用的编码是否一致
+    ///
+    /// 程序账户必须存在且可执行；可升级程序的 ProgramData 账户也必须存在且没有被关闭。
+    /// 配置了 `version_check` 时，版本字节必须等于 `layout_version`。
+    ///
+    /// # 参数
+    /// * `fetch` - 读取账户，账户不存在时返回 `Ok(None)`
+    ///
+    /// # 错误
+    /// 任何一项不满足或读取账户失败时返回说明原因的错误
+    pub fn probe(
+        &self,
+        fetch: impl Fn(&Pubkey) -> anyhow::Result<Option<Account>>,
+    ) -> anyhow::Result<()> {
+        let program = fetch(&self.program_id)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "Executor program {} does not exist on this cluster, check [executor] program_id",
+                self.program_id
+            )
+        })?;
+        if !program.executable {
+            anyhow::bail!(
+                "Executor program account {} is not executable (owner {}), check [executor] program_id",
+                self.program_id,
+                program.owner
+            );
+        }
+
+        // 可升级程序的代码在 ProgramData 账户中，跳过账户头
+        let program_data;
+        let executable_data = if program.owner == bpf_loader_upgradeable::id() {
+            let Ok(UpgradeableLoaderState::Program {
+                programdata_address,
+            }) = program.deserialize_data()
+            else {
+                anyhow::bail!(
+                    "Executor program {} is not a deployed upgradeable program",
+                    self.program_id
+                );
+            };
+            program_data = fetch(&programdata_address)?.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Program data account {} of executor program {} does not exist",
+                    programdata_address,
+                    self.program_id
+                )
+            })?;
+            let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+            if program_data.data.len() <= metadata_len {
+                anyhow::bail!(
+                    "Executor program {} has been closed, its program data holds no code",
+                    self.program_id
+                );
+            }
+            &program_data.data[metadata_len..]
+        } else {
+            &program.data[..]
+        };
+
+        let Some(check) = self.version_check else {
+            return Ok(());
+        };
+        let version_account;
+        let (data, source) = match check.account {
+            Some(account) => {
+                version_account = fetch(&account)?.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Executor version account {} does not exist, check [executor] version_seed",
+                        account
+                    )
+                })?;
+                (
+                    &version_account.data[..],
+                    format!("version account {}", account),
+                )
+            }
+            None => (executable_data, "program data".to_string()),
+        };
+        match data.get(check.offset) {
+            Some(&version) if version == self.layout_version => Ok(()),
+            Some(&version) => Err(anyhow::anyhow!(
+                "Executor program {} reports layout version {} in its {} at offset {}, \
+                 but the bot encodes layout v{}; set [executor] layout_version to match the deployed program",
+                self.program_id,
+                version,
+                source,
+                check.offset,
+                self.layout_version
+            )),
+            None => Err(anyhow::anyhow!(
+                "Executor {} is only {} bytes long, version offset {} is out of range",
+                source,
+                data.len(),
+                check.offset
+            )),
+        }
+    }
+
+    /// 按布局版本编码 Swap 指令数据
+    ///
+    /// # 参数
+    /// * `minimum_profit` - 链上要求的最低收益，不足时交易失败（或在 `no_failure_mode` 下什么也不做）
+    /// * `compute_unit_limit` - 交易的计算单元限制
+    /// * `no_failure_mode` - 找不到有利可图的套利时不让交易失败
+    /// * `use_flashloan` - 使用执行程序内置的 Kamino 闪电贷
+    ///
+    /// # 错误
+    /// 布局版本不受支持时返回错误
+    pub fn swap_data(
+        &self,
+        minimum_profit: u64,
+        compute_unit_limit: u64,
+        no_failure_mode: bool,
+        use_flashloan: bool,
+    ) -> anyhow::Result<Vec<u8>> {
+        match self.layout_version {
+            1 => {
+                let mut data = vec![self.discriminator];
+                data.extend_from_slice(&minimum_profit.to_le_bytes());
+                data.extend_from_slice(&compute_unit_limit.to_le_bytes());
+                data.push(no_failure_mode as u8);
+                data.extend_from_slice(&0u16.to_le_bytes()); // Keep this 0.
+                data.push(use_flashloan as u8);
+                Ok(data)
+            }
+            version => Err(anyhow::anyhow!(
+                "Unsupported executor layout version {}",
+                version
+            )),
+        }
+    }
+}
+
+/// 额外部署的一个执行程序（路由程序）及其适用的代币和池子数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Router {
+    pub executor: ExecutorProgram,
+    /// 为空时适用于所有代币
+    pub mints: HashSet<Pubkey>,
+    pub min_pools: usize,
+    pub max_pools: usize,
+}
+
+impl Router {
+    /// 路由程序是否适用于代币的一笔交易
+    ///
+    /// # 参数
+    /// * `pool_count` - 交易 Swap 指令中的池子数（按大小限制裁剪之后）
+    pub fn matches(&self, mint: &Pubkey, pool_count: usize) -> bool {
+        (self.mints.is_empty() || self.mints.contains(mint))
+            && (self.min_pools..=self.max_pools).contains(&pool_count)
+    }
+}
+
+/// `[executor]` 中的默认执行程序和 `routers` 中按代币、池子数选择的其他执行程序
+///
+/// 有的运营者为不同的账户数上限各部署一个程序，构建交易时按代币和池子数选择第一个匹配的路由程序，
+/// 没有匹配时使用默认执行程序。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Executors {
+    pub default: ExecutorProgram,
+    pub routers: Vec<Router>,
+}
+
+impl Executors {
+    /// 路由程序没有配置的字段沿用 `[executor]` 的设置，版本检查按各自的程序地址派生版本账户
+    ///
+    /// # 错误
+    /// 地址不是合法的 pubkey 或布局版本不受支持时返回错误
+    pub fn from_config(config: Option<&ExecutorConfig>) -> anyhow::Result<Self> {
+        let default = ExecutorProgram::from_config(config)?;
+        let mut routers = Vec::new();
+        if let Some(config) = config {
+            for router in config.routers.iter().flatten() {
+                routers.push(Self::router(default, config, router)?);
+            }
+        }
+        Ok(Self { default, routers })
+    }
+
+    fn router(
+        default: ExecutorProgram,
+        config: &ExecutorConfig,
+        router: &RouterConfig,
+    ) -> anyhow::Result<Router> {
+        let mut executor = default;
+        executor.apply(
+            Some(&router.program_id),
+            router.fee_collector.as_deref(),
+            router.discriminator,
+            router.layout_version,
+        )?;
+        executor.set_version_check(config);
+        let mints = router
+            .mints
+            .iter()
+            .flatten()
+            .map(|mint| {
+                Pubkey::from_str(mint).with_context(|| format!("Invalid router mint `{}`", mint))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Router {
+            executor,
+            mints,
+            min_pools: router.min_pools.unwrap_or(0),
+            max_pools: router.max_pools.unwrap_or(usize::MAX),
+        })
+    }
+
+    /// 代币的一笔交易使用的执行程序
+    ///
+    /// # 参数
+    /// * `pool_count` - 交易 Swap 指令中的池子数
+    pub fn select(&self, mint: &Pubkey, pool_count: usize) -> ExecutorProgram {
+        self.routers
+            .iter()
+            .find(|router| router.matches(mint, pool_count))
+            .map_or(self.default, |router| router.executor)
+    }
+
+    /// 所有执行程序，默认执行程序在前，启动检查时使用
+    pub fn programs(&self) -> impl Iterator<Item = &ExecutorProgram> {
+        std::iter::once(&self.default).chain(self.routers.iter().map(|router| &router.executor))
+    }
+}
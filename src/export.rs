@@ -0,0 +1,222 @@
+use crate::config::ExportConfig;
+use crate::quote::PoolReserves;
+use crate::status::{unix_now_ms, TransactionOutcome};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const QUOTES_HEADER: &str =
+    "timestamp_ms,mint,dex,pool,token_reserve,sol_reserve,fee_bps,price";
+pub const SENDS_HEADER: &str =
+    "timestamp_ms,mint,route,signature,simulated_profit_lamports,outcome,error";
+pub const OUTCOMES_HEADER: &str =
+    "timestamp_ms,mint,signature,outcome,realized_profit_lamports,fee_lamports";
+
+/// 按 CSV 规则转义字段：包含逗号、引号或换行时用引号包起来
+pub fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+/// 按大小和时间轮转的 CSV 文件
+///
+/// 文件名为 `<name>-<UTC 时间>.csv`，每个文件以表头开始。当前文件达到 `max_bytes`
+/// 或打开超过 `max_age` 后，下一行写入新文件。
+pub struct RotatingCsv {
+    directory: PathBuf,
+    name: &'static str,
+    header: &'static str,
+    max_bytes: u64,
+    max_age: Duration,
+    current: Option<CsvFile>,
+}
+
+struct CsvFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    opened_at: Instant,
+    bytes: u64,
+}
+
+impl RotatingCsv {
+    pub fn new(
+        directory: impl AsRef<Path>,
+        name: &'static str,
+        header: &'static str,
+        max_bytes: u64,
+        max_age: Duration,
+    ) -> Self {
+        Self {
+            directory: directory.as_ref().to_path_buf(),
+            name,
+            header,
+            max_bytes,
+            max_age,
+            current: None,
+        }
+    }
+
+    /// 当前写入的文件，还没有写入任何行时为 `None`
+    pub fn current_path(&self) -> Option<&Path> {
+        self.current.as_ref().map(|file| file.path.as_path())
+    }
+
+    /// 写入若干行并刷新到磁盘，需要时先轮转到新文件
+    pub fn write_rows(&mut self, rows: &[String]) -> anyhow::Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let rotate = self.current.as_ref().is_none_or(|file| {
+            file.bytes >= self.max_bytes || file.opened_at.elapsed() >= self.max_age
+        });
+        if rotate {
+            self.current = Some(self.open_next()?);
+        }
+
+        let file = self.current.as_mut().unwrap();
+        for row in rows {
+            writeln!(file.writer, "{}", row)?;
+            file.bytes += row.len() as u64 + 1;
+        }
+        file.writer.flush()?;
+        Ok(())
+    }
+
+    fn open_next(&self) -> anyhow::Result<CsvFile> {
+        fs::create_dir_all(&self.directory)?;
+        let stamp = chrono::Utc::now().format("%Y%m%d-%H%M%S");
+        // 同一秒内多次轮转时加序号
+        let path = (0..)
+            .map(|index| {
+                let file_name = match index {
+                    0 => format!("{}-{}.csv", self.name, stamp),
+                    _ => format!("{}-{}-{}.csv", self.name, stamp, index),
+                };
+                self.directory.join(file_name)
+            })
+            .find(|path| !path.exists())
+            .unwrap();
+
+        let mut writer = BufWriter::new(File::create(&path)?);
+        writeln!(writer, "{}", self.header)?;
+        Ok(CsvFile {
+            path,
+            writer,
+            opened_at: Instant::now(),
+            bytes: self.header.len() as u64 + 1,
+        })
+    }
+}
+
+/// 报价、发送和交易结果的 CSV 导出，供 pandas / ClickHouse 等离线分析
+///
+/// 三类数据各自写入 `quotes-*.csv`、`sends-*.csv`、`outcomes-*.csv`，按配置的大小和时间轮转。
+pub struct Exporter {
+    quotes: Mutex<RotatingCsv>,
+    sends: Mutex<RotatingCsv>,
+    outcomes: Mutex<RotatingCsv>,
+}
+
+impl Exporter {
+    pub fn new(config: &ExportConfig) -> Self {
+        let directory = config.directory();
+        let file = |name, header| {
+            Mutex::new(RotatingCsv::new(
+                &directory,
+                name,
+                header,
+                config.rotate_bytes(),
+                config.rotate_interval(),
+            ))
+        };
+        Self {
+            quotes: file("quotes", QUOTES_HEADER),
+            sends: file("sends", SENDS_HEADER),
+            outcomes: file("outcomes", OUTCOMES_HEADER),
+        }
+    }
+
+    /// 记录一轮报价中每个池子的储备和即时价格
+    ///
+    /// # 参数
+    /// * `pools` - 代币的所有池子 (DEX, 池子地址)，没有报价的池子不记录
+    pub fn record_quotes(
+        &self,
+        mint: &str,
+        pools: &[(&str, Pubkey)],
+        reserves: &HashMap<Pubkey, PoolReserves>,
+    ) -> anyhow::Result<()> {
+        let now = unix_now_ms();
+        let rows: Vec<String> = pools
+            .iter()
+            .filter_map(|(dex, pool)| {
+                let reserves = reserves.get(pool)?;
+                let price = if reserves.token > 0 {
+                    reserves.sol as f64 / reserves.token as f64
+                } else {
+                    0.0
+                };
+                Some(format!(
+                    "{},{},{},{},{},{},{},{}",
+                    now, mint, dex, pool, reserves.token, reserves.sol, reserves.fee_bps, price
+                ))
+            })
+            .collect();
+        self.quotes.lock().unwrap().write_rows(&rows)
+    }
+
+    /// 记录一次交易尝试
+    pub fn record_send(
+        &self,
+        mint: &str,
+        route: &str,
+        signature: Option<&Signature>,
+        simulated_profit: Option<i64>,
+        outcome: &TransactionOutcome,
+    ) -> anyhow::Result<()> {
+        let row = format!(
+            "{},{},{},{},{},{},{}",
+            unix_now_ms(),
+            mint,
+            csv_field(route),
+            signature.map(|s| s.to_string()).unwrap_or_default(),
+            simulated_profit.map(|p| p.to_string()).unwrap_or_default(),
+            outcome.as_str(),
+            csv_field(outcome.error().unwrap_or_default())
+        );
+        self.sends.lock().unwrap().write_rows(&[row])
+    }
+
+    /// 记录已发送交易的最终结果
+    pub fn record_outcome(
+        &self,
+        mint: &str,
+        signature: &Signature,
+        outcome: &TransactionOutcome,
+        fee: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let realized_profit = match outcome {
+            TransactionOutcome::Landed { profit_lamports } => profit_lamports.to_string(),
+            _ => String::new(),
+        };
+        let row = format!(
+            "{},{},{},{},{},{}",
+            unix_now_ms(),
+            mint,
+            signature,
+            outcome.as_str(),
+            realized_profit,
+            fee.map(|f| f.to_string()).unwrap_or_default()
+        );
+        self.outcomes.lock().unwrap().write_rows(&[row])
+    }
+}
@@ -0,0 +1,104 @@
+use crate::config::FeeBudgetConfig;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 预算统计的时间窗口
+const BUDGET_WINDOW: Duration = Duration::from_secs(3600);
+
+/// 本轮的发送决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeDecision {
+    /// 按该计算单元价格（micro-lamports）发送
+    Send { compute_unit_price: u64 },
+    /// 预算已经用完，跳过本轮
+    Throttle,
+}
+
+/// 优先费预算
+///
+/// 记录最近一小时内已上链交易的手续费和净收益。没有被收益抵消的手续费（净亏损）计入预算：
+/// 超过 `lower_price_at` 比例后按剩余预算线性降低计算单元价格，最低降到 `min_compute_unit_price`；
+/// 用完后暂停发送，直到较早的交易移出时间窗口或者新的收益补回预算。
+#[derive(Debug)]
+pub struct FeeGovernor {
+    max_lamports_per_hour: u64,
+    lower_price_at: f64,
+    min_compute_unit_price: u64,
+    /// (上链时间, 手续费, 扣除手续费后的净收益)
+    landed: Mutex<VecDeque<(Instant, u64, i64)>>,
+}
+
+impl FeeGovernor {
+    pub fn new(config: &FeeBudgetConfig) -> Self {
+        Self {
+            max_lamports_per_hour: config.max_lamports_per_hour,
+            lower_price_at: config.lower_price_at(),
+            min_compute_unit_price: config.min_compute_unit_price(),
+            landed: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// 记录一笔已上链的交易
+    ///
+    /// # 参数
+    /// * `fee_lamports` - 交易手续费，包括优先费
+    /// * `profit_lamports` - 扣除手续费后的净收益
+    pub fn record(&self, fee_lamports: u64, profit_lamports: i64) {
+        self.record_at(Instant::now(), fee_lamports, profit_lamports);
+    }
+
+    pub fn record_at(&self, at: Instant, fee_lamports: u64, profit_lamports: i64) {
+        self.landed
+            .lock()
+            .unwrap()
+            .push_back((at, fee_lamports, profit_lamports));
+    }
+
+    /// 时间窗口内支付的手续费总额
+    pub fn spent_at(&self, now: Instant) -> u64 {
+        self.window(now).iter().map(|(_, fee, _)| fee).sum()
+    }
+
+    /// 时间窗口内没有被收益抵消的手续费
+    pub fn net_loss_at(&self, now: Instant) -> u64 {
+        let profit: i64 = self.window(now).iter().map(|(_, _, profit)| profit).sum();
+        profit.min(0).unsigned_abs()
+    }
+
+    /// 根据预算使用情况决定本轮是否发送以及使用的计算单元价格
+    ///
+    /// # 参数
+    /// * `configured_price` - 配置的计算单元价格（micro-lamports）
+    pub fn decide(&self, configured_price: u64) -> FeeDecision {
+        self.decide_at(Instant::now(), configured_price)
+    }
+
+    pub fn decide_at(&self, now: Instant, configured_price: u64) -> FeeDecision {
+        let used = self.net_loss_at(now) as f64 / self.max_lamports_per_hour.max(1) as f64;
+        if used >= 1.0 {
+            return FeeDecision::Throttle;
+        }
+
+        let min_price = self.min_compute_unit_price.min(configured_price);
+        let compute_unit_price = if used <= self.lower_price_at {
+            configured_price
+        } else {
+            let remaining = (1.0 - used) / (1.0 - self.lower_price_at);
+            min_price + ((configured_price - min_price) as f64 * remaining) as u64
+        };
+        FeeDecision::Send { compute_unit_price }
+    }
+
+    /// 移除时间窗口以外的记录，返回剩余记录
+    fn window(&self, now: Instant) -> std::sync::MutexGuard<'_, VecDeque<(Instant, u64, i64)>> {
+        let mut landed = self.landed.lock().unwrap();
+        while landed
+            .front()
+            .is_some_and(|(at, _, _)| now.saturating_duration_since(*at) > BUDGET_WINDOW)
+        {
+            landed.pop_front();
+        }
+        landed
+    }
+}
@@ -0,0 +1,375 @@
+use crate::dex::meteora::dlmm_info::LbPair;
+use crate::dex::whirlpool::state::{Whirlpool, WhirlpoolOracle};
+use serde::{Deserialize, Serialize};
+
+/// Raydium AMM v4 池子账户中 `fees.swap_fee_numerator` / `swap_fee_denominator` 的偏移
+const RAYDIUM_SWAP_FEE_NUMERATOR_OFFSET: usize = 176;
+const RAYDIUM_SWAP_FEE_DENOMINATOR_OFFSET: usize = 184;
+
+/// Raydium CP `AmmConfig` 中 `trade_fee_rate`（百万分之一）的偏移
+const RAYDIUM_CP_TRADE_FEE_RATE_OFFSET: usize = 12;
+
+/// Raydium CLMM `AmmConfig` 中 `trade_fee_rate`（百万分之一，u32）的偏移
+const RAYDIUM_CLMM_TRADE_FEE_RATE_OFFSET: usize = 47;
+
+/// Pump AMM `GlobalConfig` 中各项手续费（基点）的偏移
+const PUMP_LP_FEE_OFFSET: usize = 40;
+const PUMP_PROTOCOL_FEE_OFFSET: usize = 48;
+const PUMP_COIN_CREATOR_FEE_OFFSET: usize = 313;
+
+/// Meteora DAMM v2 池子账户中 `pool_fees` 各字段的偏移
+const DAMM_V2_CLIFF_FEE_NUMERATOR_OFFSET: usize = 8;
+const DAMM_V2_FEE_SCHEDULER_MODE_OFFSET: usize = 16;
+const DAMM_V2_NUMBER_OF_PERIOD_OFFSET: usize = 22;
+const DAMM_V2_REDUCTION_FACTOR_OFFSET: usize = 32;
+const DAMM_V2_DYNAMIC_FEE_INITIALIZED_OFFSET: usize = 56;
+const DAMM_V2_VARIABLE_FEE_CONTROL_OFFSET: usize = 68;
+const DAMM_V2_BIN_STEP_OFFSET: usize = 72;
+const DAMM_V2_VOLATILITY_ACCUMULATOR_OFFSET: usize = 120;
+
+/// Raydium CP / CLMM 和 Whirlpool 费率的分母
+const FEE_RATE_DENOMINATOR: u128 = 1_000_000;
+
+/// Meteora DLMM / DAMM v2 费率的分母
+pub const METEORA_FEE_PRECISION: u128 = 1_000_000_000;
+
+/// Meteora DLMM 费率上限（精度 1e9），基础费加上动态费不超过 10%
+const DLMM_MAX_FEE_RATE: u128 = 100_000_000;
+
+/// 动态手续费公式中的缩放系数
+const VARIABLE_FEE_SCALE: u128 = 100_000_000_000;
+
+/// Whirlpool 费率上限（百万分之一），静态费率加上自适应费率不超过 10%
+const WHIRLPOOL_FEE_RATE_HARD_LIMIT: u128 = 100_000;
+
+/// Whirlpool 波动累积值的缩放系数：每跨过一个 tick 组累积 10000
+const WHIRLPOOL_VOLATILITY_SCALE: u128 = 10_000;
+
+/// Whirlpool `reduction_factor` 的分母
+const WHIRLPOOL_REDUCTION_FACTOR_DENOMINATOR: u128 = 10_000;
+
+/// Whirlpool `adaptive_fee_control_factor` 的分母
+const WHIRLPOOL_ADAPTIVE_FEE_CONTROL_DENOMINATOR: u128 = 100_000;
+
+/// 池子的交易手续费，加载池子时从链上账户读取
+///
+/// 换算成基点时向上取整，报价宁可略微低估收益。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct PoolFee {
+    /// 交易手续费（基点），其中分给协议的部分也包含在内
+    pub lp_fee_bps: u64,
+    /// 在交易手续费之外另外收取的协议手续费（基点），例如 Pump 的协议费和创建者费
+    pub protocol_fee_bps: u64,
+    /// 随价格波动变化的动态手续费（基点），只反映加载时的状态
+    pub dynamic_fee_bps: u64,
+}
+
+impl PoolFee {
+    /// 交易者实际支付的总手续费（基点）
+    pub fn total_bps(&self) -> u64 {
+        (self.lp_fee_bps + self.protocol_fee_bps + self.dynamic_fee_bps).min(10_000)
+    }
+}
+
+/// `numerator / denominator` 换算成基点，向上取整
+fn to_bps(numerator: u128, denominator: u128) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    Some(
+        numerator
+            .saturating_mul(10_000)
+            .div_ceil(denominator)
+            .min(10_000) as u64,
+    )
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn read_u128(data: &[u8], offset: usize) -> Option<u128> {
+    Some(u128::from_le_bytes(
+        data.get(offset..offset + 16)?.try_into().ok()?,
+    ))
+}
+
+/// Raydium AMM v4：池子账户中的 swap fee
+pub fn raydium_amm_fee(pool: &[u8]) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: to_bps(
+            read_u64(pool, RAYDIUM_SWAP_FEE_NUMERATOR_OFFSET)? as u128,
+            read_u64(pool, RAYDIUM_SWAP_FEE_DENOMINATOR_OFFSET)? as u128,
+        )?,
+        ..Default::default()
+    })
+}
+
+/// Raydium CP：池子 `amm_config` 账户中的 trade fee，协议费和基金费从中分出
+pub fn raydium_cp_fee(amm_config: &[u8]) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: to_bps(
+            read_u64(amm_config, RAYDIUM_CP_TRADE_FEE_RATE_OFFSET)? as u128,
+            FEE_RATE_DENOMINATOR,
+        )?,
+        ..Default::default()
+    })
+}
+
+/// Raydium CLMM：池子 `amm_config` 账户中的 trade fee
+pub fn raydium_clmm_fee(amm_config: &[u8]) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: to_bps(
+            raydium_clmm_fee_rate(amm_config)? as u128,
+            FEE_RATE_DENOMINATOR,
+        )?,
+        ..Default::default()
+    })
+}
+
+/// Raydium CLMM：`amm_config` 中的交易费率（百万分之一），报价逐个 tick 区间扣除手续费时使用
+pub fn raydium_clmm_fee_rate(amm_config: &[u8]) -> Option<u32> {
+    read_u32(amm_config, RAYDIUM_CLMM_TRADE_FEE_RATE_OFFSET)
+}
+
+/// Pump AMM：全局配置中的 LP 费，协议费和创建者费另外收取
+pub fn pump_fee(global_config: &[u8]) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: read_u64(global_config, PUMP_LP_FEE_OFFSET)?,
+        protocol_fee_bps: read_u64(global_config, PUMP_PROTOCOL_FEE_OFFSET)?
+            + read_u64(global_config, PUMP_COIN_CREATOR_FEE_OFFSET)?,
+        ..Default::default()
+    })
+}
+
+/// Whirlpool：池子的 `fee_rate`（百万分之一）
+pub fn whirlpool_fee(fee_rate: u16) -> PoolFee {
+    PoolFee {
+        lp_fee_bps: to_bps(fee_rate as u128, FEE_RATE_DENOMINATOR).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// 下一笔 swap 开始时 Whirlpool 的波动累积值
+///
+/// 与链上程序相同：先按距离上一次更新的时间衰减或清零参考波动，再加上当前 tick 组与参考 tick 组的距离。
+fn whirlpool_volatility_accumulator(
+    whirlpool: &Whirlpool,
+    oracle: &WhirlpoolOracle,
+    now: u64,
+) -> u128 {
+    let constants = oracle.adaptive_fee_constants;
+    let variables = oracle.adaptive_fee_variables;
+    let tick_group_index = whirlpool
+        .tick_current_index
+        .div_euclid(constants.tick_group_size.max(1) as i32);
+
+    let reference_age = now.saturating_sub(variables.last_reference_update_timestamp);
+    let elapsed = now.saturating_sub(
+        variables
+            .last_reference_update_timestamp
+            .max(variables.last_major_swap_timestamp),
+    );
+    let (reference_index, volatility_reference) = if reference_age > constants.decay_period as u64
+        || elapsed >= constants.decay_period as u64
+    {
+        (tick_group_index, 0)
+    } else if elapsed >= constants.filter_period as u64 {
+        (
+            tick_group_index,
+            variables.volatility_accumulator as u128 * constants.reduction_factor as u128
+                / WHIRLPOOL_REDUCTION_FACTOR_DENOMINATOR,
+        )
+    } else {
+        (
+            variables.tick_group_index_reference,
+            variables.volatility_reference as u128,
+        )
+    };
+
+    let index_delta = (reference_index as i64 - tick_group_index as i64).unsigned_abs() as u128;
+    (volatility_reference + index_delta * WHIRLPOOL_VOLATILITY_SCALE)
+        .min(constants.max_volatility_accumulator as u128)
+}
+
+/// Whirlpool 自适应手续费：静态 `fee_rate` 加上按 oracle 中的波动状态算出的自适应费率
+///
+/// 自适应费率为 `control_factor × (volatility_accumulator × tick_group_size)² / (1e5 × 1e4²)`，
+/// 两者之和不超过 10%；结果只反映下一笔 swap 开始时的费率，swap 途中跨过 tick 组后费率还会上升。
+///
+/// # 参数
+/// * `whirlpool` - 池子账户，提供静态费率和当前 tick
+/// * `oracle` - 池子的 oracle 账户
+/// * `now` - 当前 unix 时间（秒），用于衰减参考波动
+pub fn whirlpool_adaptive_fee(
+    whirlpool: &Whirlpool,
+    oracle: &WhirlpoolOracle,
+    now: u64,
+) -> PoolFee {
+    let (static_fee_rate, adaptive_fee_rate) = whirlpool_fee_rates(whirlpool, Some(oracle), now);
+    PoolFee {
+        lp_fee_bps: to_bps(static_fee_rate, FEE_RATE_DENOMINATOR).unwrap_or_default(),
+        protocol_fee_bps: 0,
+        dynamic_fee_bps: to_bps(adaptive_fee_rate, FEE_RATE_DENOMINATOR).unwrap_or_default(),
+    }
+}
+
+/// Whirlpool 下一笔 swap 开始时的总费率（百万分之一），报价时传给 `whirlpool_get_amount_out`
+///
+/// # 参数
+/// * `oracle` - 池子没有启用自适应手续费时传 `None`，只使用静态 `fee_rate`
+pub fn whirlpool_fee_rate(
+    whirlpool: &Whirlpool,
+    oracle: Option<&WhirlpoolOracle>,
+    now: u64,
+) -> u32 {
+    let (static_fee_rate, adaptive_fee_rate) = whirlpool_fee_rates(whirlpool, oracle, now);
+    (static_fee_rate + adaptive_fee_rate) as u32
+}
+
+/// 静态费率和自适应费率（百万分之一），两者之和不超过 10%
+fn whirlpool_fee_rates(
+    whirlpool: &Whirlpool,
+    oracle: Option<&WhirlpoolOracle>,
+    now: u64,
+) -> (u128, u128) {
+    let static_fee_rate = (whirlpool.fee_rate as u128).min(WHIRLPOOL_FEE_RATE_HARD_LIMIT);
+    let Some(oracle) = oracle else {
+        return (static_fee_rate, 0);
+    };
+    let volatility_accumulator = whirlpool_volatility_accumulator(whirlpool, oracle, now);
+    let crossed = volatility_accumulator * oracle.adaptive_fee_constants.tick_group_size as u128;
+    let adaptive_fee_rate = (oracle.adaptive_fee_constants.adaptive_fee_control_factor as u128)
+        .saturating_mul(crossed.saturating_pow(2))
+        .div_ceil(
+            WHIRLPOOL_ADAPTIVE_FEE_CONTROL_DENOMINATOR
+                * WHIRLPOOL_VOLATILITY_SCALE
+                * WHIRLPOOL_VOLATILITY_SCALE,
+        );
+    let adaptive_fee_rate = adaptive_fee_rate.min(WHIRLPOOL_FEE_RATE_HARD_LIMIT - static_fee_rate);
+    (static_fee_rate, adaptive_fee_rate)
+}
+
+/// 由 Whirlpool 池子和 oracle 的原始账户数据计算当前费率
+///
+/// # 返回值
+/// 池子没有启用自适应手续费或任一账户无法解析时返回 `None`，此时沿用加载时的静态费率
+pub fn whirlpool_live_fee(pool: &[u8], oracle: &[u8], now: u64) -> Option<PoolFee> {
+    let whirlpool = Whirlpool::try_deserialize(pool).ok()?;
+    if !whirlpool.is_initialized_with_adaptive_fee() {
+        return None;
+    }
+    let oracle = WhirlpoolOracle::try_deserialize(oracle).ok()?;
+    Some(whirlpool_adaptive_fee(&whirlpool, &oracle, now))
+}
+
+/// Perena Numeraire：池子的 `fee_rate`（百万分之一）
+pub fn perena_fee(fee_rate: u64) -> PoolFee {
+    PoolFee {
+        lp_fee_bps: to_bps(fee_rate as u128, FEE_RATE_DENOMINATOR).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Meteora DAMM v1：池子的 trade fee，协议费从中分出
+pub fn meteora_damm_fee(trade_fee_numerator: u64, trade_fee_denominator: u64) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: to_bps(trade_fee_numerator as u128, trade_fee_denominator as u128)?,
+        ..Default::default()
+    })
+}
+
+/// 动态手续费：`(volatility_accumulator × bin_step)² × variable_fee_control / 1e11`，精度 1e9
+fn variable_fee(volatility_accumulator: u128, bin_step: u16, variable_fee_control: u32) -> u128 {
+    let square_vfa_bin = volatility_accumulator
+        .saturating_mul(bin_step as u128)
+        .saturating_pow(2);
+    square_vfa_bin
+        .saturating_mul(variable_fee_control as u128)
+        .div_ceil(VARIABLE_FEE_SCALE)
+}
+
+/// Meteora DLMM 基础费：`base_factor × bin_step × 10 × 10^base_fee_power_factor`，精度 1e9
+fn dlmm_base_fee(lb_pair: &LbPair) -> u128 {
+    let parameters = lb_pair.parameters;
+    parameters.base_factor as u128
+        * lb_pair.bin_step as u128
+        * 10
+        * 10u128.pow(parameters.base_fee_power_factor as u32)
+}
+
+/// Meteora DLMM 在给定波动累积值下的总费率（精度 1e9），与链上一样不超过 10%
+///
+/// 兑换每跨过一个 bin 波动累积值都会增大，报价时逐个 bin 重新计算费率。
+pub fn dlmm_fee_rate(lb_pair: &LbPair, volatility_accumulator: u32) -> u128 {
+    let dynamic_fee = variable_fee(
+        volatility_accumulator as u128,
+        lb_pair.bin_step,
+        lb_pair.parameters.variable_fee_control,
+    );
+    (dlmm_base_fee(lb_pair) + dynamic_fee).min(DLMM_MAX_FEE_RATE)
+}
+
+/// Meteora DLMM：基础费 `base_factor × bin_step × 10 × 10^base_fee_power_factor` 加上当前的动态费
+pub fn dlmm_fee(lb_pair: &LbPair) -> PoolFee {
+    let parameters = lb_pair.parameters;
+    let base_fee = dlmm_base_fee(lb_pair);
+    let dynamic_fee = variable_fee(
+        lb_pair.v_parameters.volatility_accumulator as u128,
+        lb_pair.bin_step,
+        parameters.variable_fee_control,
+    );
+    PoolFee {
+        lp_fee_bps: to_bps(base_fee, METEORA_FEE_PRECISION).unwrap_or_default(),
+        protocol_fee_bps: 0,
+        dynamic_fee_bps: to_bps(dynamic_fee, METEORA_FEE_PRECISION).unwrap_or_default(),
+    }
+}
+
+/// Meteora DAMM v2：费率调度结束后的基础费加上当前的动态费
+///
+/// 新池子开盘时基础费从 `cliff_fee_numerator` 按周期递减（线性或指数），
+/// 这里取所有周期结束后的费率，开盘阶段会低估手续费。
+pub fn meteora_damm_v2_fee(pool: &[u8]) -> Option<PoolFee> {
+    let cliff_fee = read_u64(pool, DAMM_V2_CLIFF_FEE_NUMERATOR_OFFSET)? as u128;
+    let periods = read_u16(pool, DAMM_V2_NUMBER_OF_PERIOD_OFFSET)? as u32;
+    let reduction_factor = read_u64(pool, DAMM_V2_REDUCTION_FACTOR_OFFSET)? as u128;
+    let base_fee = match *pool.get(DAMM_V2_FEE_SCHEDULER_MODE_OFFSET)? {
+        // 线性：每个周期减少 reduction_factor
+        0 => cliff_fee.saturating_sub(reduction_factor * periods as u128),
+        // 指数：每个周期乘以 (1 - reduction_factor / 10000)
+        _ => (0..periods).fold(cliff_fee, |fee, _| {
+            fee * 10_000u128.saturating_sub(reduction_factor) / 10_000
+        }),
+    };
+
+    let dynamic_fee = if *pool.get(DAMM_V2_DYNAMIC_FEE_INITIALIZED_OFFSET)? != 0 {
+        variable_fee(
+            read_u128(pool, DAMM_V2_VOLATILITY_ACCUMULATOR_OFFSET)?,
+            read_u16(pool, DAMM_V2_BIN_STEP_OFFSET)?,
+            read_u32(pool, DAMM_V2_VARIABLE_FEE_CONTROL_OFFSET)?,
+        )
+    } else {
+        0
+    };
+
+    Some(PoolFee {
+        lp_fee_bps: to_bps(base_fee, METEORA_FEE_PRECISION)?,
+        protocol_fee_bps: 0,
+        dynamic_fee_bps: to_bps(dynamic_fee, METEORA_FEE_PRECISION)?,
+    })
+}
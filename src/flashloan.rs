@@ -0,0 +1,144 @@
+use crate::config::{FlashloanConfig, FlashloanProvider};
+use crate::constants::{program_id, ProgramId};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::native_token::sol_to_lamports;
+use solana_program::pubkey::Pubkey;
+use solana_program::sysvar;
+use std::str::FromStr;
+
+pub const SOLEND_PROGRAM_ID: &str = "So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo";
+
+/// Solend 主池的 SOL 储备
+pub const SOLEND_SOL_RESERVE: &str = "8PbodeaosQP19SjYFx855UMqWxH2HynZLdBXmsrbac36";
+pub const SOLEND_SOL_LIQUIDITY_SUPPLY: &str = "8UviNr47S8eL6J3WfDxMRa3hvLta1VDJwNWqsDgtN3Cv";
+pub const SOLEND_SOL_FEE_RECEIVER: &str = "5wo1tFpi4HaVKnemqaXeQnBEpezrJXcXvuztYaPhvgC7";
+pub const SOLEND_MAIN_LENDING_MARKET: &str = "4UpD2fh7xH3VP9QQaXtsS1YY3bxzWhtfpks7FatyKvdY";
+
+/// Solend 闪电贷默认手续费（基点）
+pub const DEFAULT_FLASHLOAN_FEE_BPS: u64 = 30;
+
+const FLASH_BORROW_RESERVE_LIQUIDITY: u8 = 19;
+const FLASH_REPAY_RESERVE_LIQUIDITY: u8 = 20;
+
+pub fn solend_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Solend)
+}
+
+/// 借款使用的 Solend 储备
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolendReserve {
+    pub reserve: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub fee_receiver: Pubkey,
+    pub lending_market: Pubkey,
+}
+
+impl SolendReserve {
+    /// 主池的 SOL 储备
+    pub fn main_pool_sol() -> Self {
+        Self {
+            reserve: Pubkey::from_str(SOLEND_SOL_RESERVE).unwrap(),
+            liquidity_supply: Pubkey::from_str(SOLEND_SOL_LIQUIDITY_SUPPLY).unwrap(),
+            fee_receiver: Pubkey::from_str(SOLEND_SOL_FEE_RECEIVER).unwrap(),
+            lending_market: Pubkey::from_str(SOLEND_MAIN_LENDING_MARKET).unwrap(),
+        }
+    }
+
+    /// 借贷市场的 PDA 权限账户
+    pub fn lending_market_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[self.lending_market.as_ref()], solend_program_id()).0
+    }
+}
+
+/// 外部闪电贷的借款和还款参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashLoan {
+    pub reserve: SolendReserve,
+    /// 借入的 lamports
+    pub amount: u64,
+    pub fee_bps: u64,
+}
+
+impl FlashLoan {
+    /// 根据配置创建，未启用或使用执行程序内置闪电贷时返回 `None`
+    pub fn from_config(config: &FlashloanConfig) -> anyhow::Result<Option<Self>> {
+        if !config.enabled || config.provider != FlashloanProvider::Solend {
+            return Ok(None);
+        }
+        let reserve = match &config.solend {
+            Some(solend) => SolendReserve {
+                reserve: Pubkey::from_str(&solend.reserve)?,
+                liquidity_supply: Pubkey::from_str(&solend.liquidity_supply)?,
+                fee_receiver: Pubkey::from_str(&solend.fee_receiver)?,
+                lending_market: Pubkey::from_str(&solend.lending_market)?,
+            },
+            None => SolendReserve::main_pool_sol(),
+        };
+        let max_borrow = config
+            .max_borrow
+            .ok_or_else(|| anyhow::anyhow!("flashloan.max_borrow is required for Solend"))?;
+        Ok(Some(Self {
+            reserve,
+            amount: sol_to_lamports(max_borrow),
+            fee_bps: config.fee_bps(),
+        }))
+    }
+
+    /// 还款时需要额外支付的手续费（lamports），向上取整
+    pub fn fee(&self) -> u64 {
+        (self.amount as u128 * self.fee_bps as u128).div_ceil(10_000) as u64
+    }
+
+    /// 从储备借出 SOL 到钱包的 WSOL 账户
+    pub fn borrow_instruction(&self, destination: &Pubkey) -> Instruction {
+        let mut data = vec![FLASH_BORROW_RESERVE_LIQUIDITY];
+        data.extend_from_slice(&self.amount.to_le_bytes());
+
+        Instruction {
+            program_id: *solend_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.reserve.liquidity_supply, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new(self.reserve.reserve, false),
+                AccountMeta::new_readonly(self.reserve.lending_market, false),
+                AccountMeta::new_readonly(self.reserve.lending_market_authority(), false),
+                AccountMeta::new_readonly(sysvar::instructions::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data,
+        }
+    }
+
+    /// 从钱包的 WSOL 账户归还本金和手续费
+    ///
+    /// # 参数
+    /// * `source` - 钱包的 WSOL 账户，同时作为 host 手续费接收账户，把 host 分成返还给自己
+    /// * `authority` - 钱包地址
+    /// * `borrow_instruction_index` - 借款指令在交易中的位置
+    pub fn repay_instruction(
+        &self,
+        source: &Pubkey,
+        authority: &Pubkey,
+        borrow_instruction_index: u8,
+    ) -> Instruction {
+        let mut data = vec![FLASH_REPAY_RESERVE_LIQUIDITY];
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        data.push(borrow_instruction_index);
+
+        Instruction {
+            program_id: *solend_program_id(),
+            accounts: vec![
+                AccountMeta::new(*source, false),
+                AccountMeta::new(self.reserve.liquidity_supply, false),
+                AccountMeta::new(self.reserve.fee_receiver, false),
+                AccountMeta::new(*source, false),
+                AccountMeta::new(self.reserve.reserve, false),
+                AccountMeta::new_readonly(self.reserve.lending_market, false),
+                AccountMeta::new_readonly(*authority, true),
+                AccountMeta::new_readonly(sysvar::instructions::ID, false),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data,
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::transaction_watch::{TransactionUpdate, TransactionWatch};
+use futures::StreamExt;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+use yellowstone_grpc_client::GeyserGrpcClient;
+use yellowstone_grpc_proto::prelude::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterTransactions, SubscribeUpdateTransaction, TokenBalance,
+};
+
+/// 订阅断开后等待多久重连
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// 连接 Yellowstone gRPC 的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 订阅钱包签名的所有交易（包括执行失败的），把结果推送给 `watch`
+///
+/// 以 processed 确认级别订阅，交易所在 slot 处理完就能拿到结果，不需要等 `getSignatureStatuses`
+/// 轮询到 confirmed。断开后自动重连，断开期间 `watch` 标记为未连接。
+///
+/// # 参数
+/// * `endpoint` - Yellowstone gRPC 地址
+/// * `x_token` - 访问令牌，不需要时为 `None`
+/// * `wallet` - 钱包地址，套利交易都由它签名
+pub async fn run(
+    watch: Arc<TransactionWatch>,
+    endpoint: String,
+    x_token: Option<String>,
+    wallet: Pubkey,
+) {
+    loop {
+        if let Err(e) = subscribe(&watch, &endpoint, x_token.clone(), &wallet).await {
+            warn!("Yellowstone subscription to {} failed: {:#}", endpoint, e);
+        }
+        watch.set_connected(false);
+        tokio::time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn subscribe(
+    watch: &TransactionWatch,
+    endpoint: &str,
+    x_token: Option<String>,
+    wallet: &Pubkey,
+) -> anyhow::Result<()> {
+    let mut client = GeyserGrpcClient::build_from_shared(endpoint.to_string())?
+        .x_token(x_token)?
+        .connect_timeout(CONNECT_TIMEOUT)
+        .connect()
+        .await?;
+    let request = SubscribeRequest {
+        transactions: HashMap::from([(
+            "wallet".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                account_required: vec![wallet.to_string()],
+                ..Default::default()
+            },
+        )]),
+        commitment: Some(CommitmentLevel::Processed as i32),
+        ..Default::default()
+    };
+    let (_sink, mut stream) = client.subscribe_with_request(Some(request)).await?;
+    watch.set_connected(true);
+    info!(
+        "Tracking wallet transactions through Yellowstone gRPC {}",
+        endpoint
+    );
+
+    while let Some(update) = stream.next().await {
+        if let Some(UpdateOneof::Transaction(transaction)) = update?.update_oneof {
+            if let Some(update) = transaction_update(wallet, transaction) {
+                watch.publish(update);
+            }
+        }
+    }
+    Err(anyhow::anyhow!("stream closed by the server"))
+}
+
+fn transaction_update(
+    wallet: &Pubkey,
+    transaction: SubscribeUpdateTransaction,
+) -> Option<TransactionUpdate> {
+    let info = transaction.transaction?;
+    let meta = info.meta?;
+    let signature = Signature::try_from(info.signature.as_slice()).ok()?;
+
+    let wallet = wallet.to_string();
+    let mut wallet_deltas: HashMap<Pubkey, i64> = HashMap::new();
+    let mut add = |balances: &[TokenBalance], sign: i64| {
+        for balance in balances.iter().filter(|balance| balance.owner == wallet) {
+            let amount = balance
+                .ui_token_amount
+                .as_ref()
+                .and_then(|amount| amount.amount.parse::<i64>().ok());
+            if let (Ok(mint), Some(amount)) = (Pubkey::from_str(&balance.mint), amount) {
+                *wallet_deltas.entry(mint).or_default() += sign * amount;
+            }
+        }
+    };
+    add(&meta.pre_token_balances, -1);
+    add(&meta.post_token_balances, 1);
+
+    Some(TransactionUpdate {
+        signature,
+        slot: transaction.slot,
+        failed: meta.err.is_some(),
+        fee: meta.fee,
+        wallet_deltas,
+    })
+}
@@ -0,0 +1,296 @@
+use crate::app_state::AppState;
+use crate::dex::moonshot::{moonshot_program_id, MoonshotCurve};
+use crate::dex::pool_program_id;
+use crate::notifications::Alert;
+use crate::pool_watch::add_pool_from_rpc;
+use crate::pools::MintPoolData;
+use crate::quote::MAX_MULTIPLE_ACCOUNTS;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+/// 联合曲线毕业后可能承接流动性的 AMM
+#[derive(Debug, Clone, Copy)]
+pub struct MigrationVenue {
+    /// DEX 名称，与 `MintPoolData::pool_addresses` 一致
+    pub dex: &'static str,
+    /// 池子账户中两个 mint 的偏移，代币在前、报价货币在后
+    pub mint_offsets: (usize, usize),
+    /// 两个 mint 的位置不固定（按池子创建时的顺序或地址排序），需要两种排列都查询
+    pub either_order: bool,
+}
+
+/// 按查询顺序排列的迁移目标：PumpSwap、Raydium AMM v4、Raydium CP、Meteora DAMM v2
+pub const MIGRATION_VENUES: [MigrationVenue; 4] = [
+    // 8 字节类型标识 + bump + version + creator 之后是 base_mint / quote_mint
+    MigrationVenue {
+        dex: "pump",
+        mint_offsets: (43, 75),
+        either_order: false,
+    },
+    MigrationVenue {
+        dex: "raydium",
+        mint_offsets: (400, 432),
+        either_order: true,
+    },
+    MigrationVenue {
+        dex: "raydium_cp",
+        mint_offsets: (168, 200),
+        either_order: true,
+    },
+    MigrationVenue {
+        dex: "meteora_damm_v2",
+        mint_offsets: (168, 200),
+        either_order: true,
+    },
+];
+
+impl MigrationVenue {
+    /// 池子账户数据中的两个 mint 是否为 `mint` 和 `quote_mint`
+    pub fn matches(&self, data: &[u8], mint: &Pubkey, quote_mint: &Pubkey) -> bool {
+        let at = |offset: usize| data.get(offset..offset + 32);
+        let (first, second) = self.mint_offsets;
+        let (Some(first), Some(second)) = (at(first), at(second)) else {
+            return false;
+        };
+        (first == mint.as_ref() && second == quote_mint.as_ref())
+            || (self.either_order && first == quote_mint.as_ref() && second == mint.as_ref())
+    }
+
+    /// 查询 `mint` / `quote_mint` 池子的 `getProgramAccounts` 过滤条件，每种排列一组
+    pub fn filters(&self, mint: &Pubkey, quote_mint: &Pubkey) -> Vec<Vec<RpcFilterType>> {
+        let (first, second) = self.mint_offsets;
+        let filter = |a: &Pubkey, b: &Pubkey| {
+            vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(first, a.to_bytes().to_vec())),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(second, b.to_bytes().to_vec())),
+            ]
+        };
+        let mut filters = vec![filter(mint, quote_mint)];
+        if self.either_order {
+            filters.push(filter(quote_mint, mint));
+        }
+        filters
+    }
+}
+
+/// Moonshot 曲线是否已经毕业
+///
+/// 曲线上的代币全部卖出、曲线账户已关闭或不再属于 Moonshot 程序时都视为毕业。
+///
+/// # 参数
+/// * `account` - 曲线账户，账户不存在时为 `None`
+pub fn curve_graduated(account: Option<&Account>) -> bool {
+    let Some(account) = account else {
+        return true;
+    };
+    if account.owner != *moonshot_program_id() {
+        return true;
+    }
+    MoonshotCurve::load_checked(&account.data).is_ok_and(|curve| curve.is_complete())
+}
+
+/// 在所有迁移目标中查找 `mint` / `quote_mint` 的池子
+///
+/// # 返回值
+/// (DEX 名称, 池子地址)，按 `MIGRATION_VENUES` 的顺序
+///
+/// # 错误
+/// 任何一次 `getProgramAccounts` 请求失败时返回错误
+pub fn find_migrated_pools(
+    rpc_client: &RpcClient,
+    mint: &Pubkey,
+    quote_mint: &Pubkey,
+) -> anyhow::Result<Vec<(&'static str, Pubkey)>> {
+    let mut pools = Vec::new();
+    for venue in MIGRATION_VENUES {
+        let Some(program_id) = pool_program_id(venue.dex) else {
+            continue;
+        };
+        for filters in venue.filters(mint, quote_mint) {
+            let config = RpcProgramAccountsConfig {
+                filters: Some(filters),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let accounts = rpc_client.get_program_accounts_with_config(&program_id, config)?;
+            pools.extend(
+                accounts
+                    .into_iter()
+                    .filter(|(_, account)| venue.matches(&account.data, mint, quote_mint))
+                    .map(|(pool, _)| (venue.dex, pool)),
+            );
+        }
+    }
+    Ok(pools)
+}
+
+/// 定期检查一个代币的联合曲线是否毕业，毕业后加载代币新的 AMM 池子并移除曲线
+///
+/// 曲线毕业后不再能交易，流动性转移到 AMM。不需要修改配置，新池子从下一轮交易开始使用，
+/// 与池子目录中加入的池子一样不写入池子缓存。
+pub struct GraduationWatcher {
+    /// 代币地址，用于日志和告警
+    pub mint: String,
+    /// 发送任务共用的池子数据
+    pub pool_data: Arc<Mutex<MintPoolData>>,
+    /// 共享的运行状态：RPC 客户端、告警推送器、仪表盘状态、vault 跟踪和跟单路线
+    pub state: Arc<AppState>,
+}
+
+impl GraduationWatcher {
+    /// 每隔 `interval` 检查一次
+    ///
+    /// 曲线账户关闭后所有者校验可能先把曲线移除，所以这里自己记住见过的曲线，
+    /// 直到找到迁移后的池子为止。毕业的曲线立即移除，新池子找到后再加入。
+    pub async fn run(self, interval: Duration) {
+        let mut curves: HashSet<Pubkey> = HashSet::new();
+        loop {
+            tokio::time::sleep(interval).await;
+
+            let (mint, quote_mint) = {
+                let guard = self.pool_data.lock().await;
+                curves.extend(guard.moonshot_pools.iter().map(|p| p.pool));
+                (guard.mint, guard.quote_mint)
+            };
+            if curves.is_empty() {
+                continue;
+            }
+
+            // 读取账户期间不持有锁，不阻塞发送任务
+            let graduated = {
+                let rpc_client = self.state.rpc_client.clone();
+                let curves: Vec<Pubkey> = curves.iter().copied().collect();
+                tokio::task::spawn_blocking(move || {
+                    let mut graduated = Vec::new();
+                    for chunk in curves.chunks(MAX_MULTIPLE_ACCOUNTS) {
+                        let accounts = rpc_client.get_multiple_accounts(chunk)?;
+                        graduated.extend(
+                            chunk
+                                .iter()
+                                .zip(&accounts)
+                                .filter(|(_, account)| curve_graduated(account.as_ref()))
+                                .map(|(curve, _)| *curve),
+                        );
+                    }
+                    let pools = if graduated.is_empty() {
+                        Vec::new()
+                    } else {
+                        find_migrated_pools(&rpc_client, &mint, &quote_mint)?
+                    };
+                    Ok::<_, anyhow::Error>((graduated, pools))
+                })
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+            };
+            let (graduated, pools) = match graduated {
+                Ok(graduated) => graduated,
+                Err(e) => {
+                    error!(
+                        "Failed to check curve graduation for mint {}: {}",
+                        self.mint, e
+                    );
+                    continue;
+                }
+            };
+            if graduated.is_empty() {
+                continue;
+            }
+
+            self.migrate(&graduated, &pools).await;
+            if pools.is_empty() {
+                // 迁移交易可能还没有执行，曲线已经移除，继续查找新池子
+                warn!(
+                    "Bonding curve of mint {} has graduated, but no migrated pool was found yet",
+                    self.mint
+                );
+            } else {
+                for curve in &graduated {
+                    curves.remove(curve);
+                }
+            }
+        }
+    }
+
+    /// 加入迁移后的池子，再移除已经毕业的曲线
+    async fn migrate(&self, graduated: &[Pubkey], pools: &[(&'static str, Pubkey)]) {
+        let mut added = Vec::new();
+        for (dex, pool) in pools {
+            let address = pool.to_string();
+            match add_pool_from_rpc(
+                &self.pool_data,
+                self.state.rpc_client.clone(),
+                dex,
+                &address,
+            )
+            .await
+            {
+                Ok(Some(_)) => {
+                    info!(
+                        "Added migrated {} pool {} to mint {}",
+                        dex, address, self.mint
+                    );
+                    added.push(format!("{} pool {}", dex, address));
+                }
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "Failed to load migrated {} pool {} for mint {}: {:#}",
+                    dex, address, self.mint, e
+                ),
+            }
+        }
+
+        let mut guard = self.pool_data.lock().await;
+        let reason = if added.is_empty() {
+            "bonding curve graduated".to_string()
+        } else {
+            format!("bonding curve graduated, migrated to {}", added.join(", "))
+        };
+        let removed: Vec<Pubkey> = graduated
+            .iter()
+            .filter(|curve| guard.moonshot_pools.iter().any(|p| p.pool == **curve))
+            .copied()
+            .collect();
+        if removed.is_empty() && added.is_empty() {
+            return;
+        }
+        for curve in &removed {
+            info!(
+                "Removing moonshot curve {} from mint {}: {}",
+                curve, self.mint, reason
+            );
+            self.state.notifier.notify(Alert::PoolEvicted {
+                mint: self.mint.clone(),
+                dex: "moonshot",
+                pool: *curve,
+                reason: reason.clone(),
+            });
+        }
+        guard.retain_pools(|pool| !removed.contains(pool));
+        if !guard.has_valid_route() {
+            warn!(
+                "Mint {} has no buy/sell pool pair left after the curve graduated",
+                self.mint
+            );
+        }
+        self.state.status.update_pools(&guard);
+        if let Some(vault_tracker) = &self.state.services.vault_tracker {
+            vault_tracker.register(&guard);
+        }
+        if let Some(copy_routes) = &self.state.services.copy_routes {
+            copy_routes.register(&guard);
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::app_state::AppState;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+/// 健康检查需要的运行状态
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthInputs {
+    /// 已初始化池子数据的代币数量
+    pub mint_count: usize,
+    /// 缓存的 blockhash 距离上次刷新经过的时间
+    pub blockhash_age: Duration,
+    /// 主 RPC 最近一次请求是否成功
+    pub rpc_healthy: bool,
+}
+
+impl HealthInputs {
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            mint_count: state.mints().len(),
+            blockhash_age: state.blockhash_age(),
+            rpc_healthy: state.status.rpc_health().healthy,
+        }
+    }
+}
+
+/// 单项检查结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// 健康检查响应，所有检查都通过时 `ok` 为 true
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    pub ok: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthReport {
+    fn new(checks: Vec<HealthCheck>) -> Self {
+        Self {
+            ok: checks.iter().all(|check| check.ok),
+            checks,
+        }
+    }
+
+    /// 存活检查：blockhash 仍在刷新且主 RPC 可用
+    ///
+    /// 不检查代币是否初始化完成，启动时加载池子较慢也不会被重启。
+    pub fn liveness(inputs: &HealthInputs, max_blockhash_age: Duration) -> Self {
+        Self::new(vec![
+            blockhash_check(inputs, max_blockhash_age),
+            rpc_check(inputs),
+        ])
+    }
+
+    /// 就绪检查：配置已加载、至少一个代币初始化完成，且满足存活检查
+    pub fn readiness(inputs: &HealthInputs, max_blockhash_age: Duration) -> Self {
+        Self::new(vec![
+            HealthCheck {
+                // 服务在配置加载并校验通过后才启动
+                name: "config",
+                ok: true,
+                detail: "loaded".to_string(),
+            },
+            HealthCheck {
+                name: "mints",
+                ok: inputs.mint_count > 0,
+                detail: format!("{} mint(s) initialized", inputs.mint_count),
+            },
+            blockhash_check(inputs, max_blockhash_age),
+            rpc_check(inputs),
+        ])
+    }
+}
+
+fn blockhash_check(inputs: &HealthInputs, max_blockhash_age: Duration) -> HealthCheck {
+    HealthCheck {
+        name: "blockhash",
+        ok: inputs.blockhash_age <= max_blockhash_age,
+        detail: format!(
+            "refreshed {}s ago (max {}s)",
+            inputs.blockhash_age.as_secs(),
+            max_blockhash_age.as_secs()
+        ),
+    }
+}
+
+fn rpc_check(inputs: &HealthInputs) -> HealthCheck {
+    HealthCheck {
+        name: "rpc",
+        ok: inputs.rpc_healthy,
+        detail: if inputs.rpc_healthy {
+            "healthy".to_string()
+        } else {
+            "last request failed".to_string()
+        },
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    state: Arc<AppState>,
+    max_blockhash_age: Duration,
+}
+
+/// 启动健康检查 HTTP 服务
+///
+/// - `GET /healthz`: 存活检查，失败时应重启进程
+/// - `GET /readyz`: 就绪检查，启动阶段加载池子时返回失败
+///
+/// 检查通过时返回 200，否则返回 503，响应体为 `HealthReport` 的 JSON。
+///
+/// # 参数
+/// * `bind_address` - 监听地址，例如 `0.0.0.0:8081`
+/// * `state` - 共享的运行状态
+/// * `max_blockhash_age` - blockhash 超过该时间没有刷新时认为不健康
+pub async fn serve(
+    bind_address: &str,
+    state: Arc<AppState>,
+    max_blockhash_age: Duration,
+) -> anyhow::Result<()> {
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(HealthState {
+            state,
+            max_blockhash_age,
+        });
+
+    let listener = tokio::net::TcpListener::bind(bind_address).await?;
+    info!(
+        "Health checks listening on http://{}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+fn respond(report: HealthReport) -> (StatusCode, Json<HealthReport>) {
+    let status = if report.ok {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+async fn healthz(State(health): State<HealthState>) -> (StatusCode, Json<HealthReport>) {
+    let inputs = HealthInputs::from_state(&health.state);
+    respond(HealthReport::liveness(&inputs, health.max_blockhash_age))
+}
+
+async fn readyz(State(health): State<HealthState>) -> (StatusCode, Json<HealthReport>) {
+    let inputs = HealthInputs::from_state(&health.state);
+    respond(HealthReport::readiness(&inputs, health.max_blockhash_age))
+}
@@ -0,0 +1,44 @@
+use solana_sdk::signature::Signature;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// 单个代币已发送、还没有确认结果的交易
+///
+/// 同一个代币的交易都从同一个 WSOL 账户借出输入资金，上一笔还没上链时再发一笔，两笔最多只有一笔能成功，
+/// 另一笔白白支付手续费。发送循环在达到上限时跳过本轮，交易上链、确认失败或超时后释放名额。
+#[derive(Debug)]
+pub struct InFlight {
+    limit: usize,
+    pending: Mutex<HashSet<Signature>>,
+}
+
+impl InFlight {
+    /// # 参数
+    /// * `limit` - 同时等待确认的交易上限，至少为 1
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit: limit.max(1),
+            pending: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// 当前等待确认的交易数量
+    pub fn count(&self) -> usize {
+        self.pending.lock().unwrap().len()
+    }
+
+    /// 是否已达到上限，达到时本轮不应再发送
+    pub fn is_full(&self) -> bool {
+        self.count() >= self.limit
+    }
+
+    /// 记录一笔已发送的交易
+    pub fn insert(&self, signature: Signature) {
+        self.pending.lock().unwrap().insert(signature);
+    }
+
+    /// 交易有了结果（上链、失败或超时）后释放名额
+    pub fn remove(&self, signature: &Signature) {
+        self.pending.lock().unwrap().remove(signature);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::config::Config;
+use crate::constants::{program_id, ProgramId};
+use crate::dex::discriminator::anchor_instruction_discriminator;
+use crate::dex::raydium::{raydium_cp_authority, raydium_cp_program_id, raydium_program_id};
+use crate::pools::MintPoolData;
+use crate::quote::{PoolReserves, SizedQuote};
+use crate::transaction::select_lookup_tables;
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::v0::Message;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+
+/// Lighthouse 断言程序，用于交易末尾的余额检查
+pub const LIGHTHOUSE_PROGRAM_ID: &str = "L2TExMFKdjpN9kozasaurPirfHy9P8sbXoAN1qA3S95";
+
+/// Raydium AMM v4 `swap_base_in` 指令编号
+const RAYDIUM_SWAP_BASE_IN: u8 = 9;
+
+/// Lighthouse `AssertTokenAccount` 指令编号
+const LIGHTHOUSE_ASSERT_TOKEN_ACCOUNT: u8 = 8;
+/// `TokenAccountAssertion::Amount`
+const TOKEN_ACCOUNT_ASSERTION_AMOUNT: u8 = 2;
+/// `IntegerOperator::GreaterThanOrEqual`
+const INTEGER_OPERATOR_GTE: u8 = 4;
+
+pub fn lighthouse_program_id() -> &'static Pubkey {
+    program_id(ProgramId::Lighthouse)
+}
+
+/// 兑换方向：买入用报价货币换代币，卖出用代币换报价货币
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapSide {
+    Buy,
+    Sell,
+}
+
+/// 不经过执行程序、直接调用 DEX 完成的一次套利
+///
+/// 买入指令要求至少换到 `token_amount` 个代币，卖出指令卖出同样数量的代币并要求至少换回
+/// `minimum_out`；两条指令都按报价设置，价格在上链前变化时交易直接失败。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InlineRoute {
+    pub buy_pool: Pubkey,
+    pub sell_pool: Pubkey,
+    /// 投入的报价货币（最小单位）
+    pub amount_in: u64,
+    /// 买入得到、再全部卖出的代币数量
+    pub token_amount: u64,
+    /// 卖出至少换回的报价货币：投入加上要求的最低收益
+    pub minimum_out: u64,
+    /// 交易结束时钱包报价货币账户至少要有的余额，为 `None` 时不检查
+    pub minimum_balance: Option<u64>,
+}
+
+impl InlineRoute {
+    /// 按报价构造路线
+    ///
+    /// # 参数
+    /// * `quote` - 选出的买入/卖出池子和投入
+    /// * `reserves` - 池子储备，用于计算买入得到的代币数量
+    /// * `min_profit` - 要求的最低收益（报价货币最小单位）
+    /// * `quote_balance` - 发送前钱包报价货币账户的余额，为 `None` 时不做余额检查
+    ///
+    /// # 返回值
+    /// 缺少池子储备、买入得不到代币或卖出不够要求时返回 `None`
+    pub fn from_quote(
+        quote: &SizedQuote,
+        reserves: &HashMap<Pubkey, PoolReserves>,
+        min_profit: u64,
+        quote_balance: Option<u64>,
+    ) -> Option<Self> {
+        let buy = reserves.get(&quote.buy_pool)?;
+        let sell = reserves.get(&quote.sell_pool)?;
+        let token_amount = buy.buy(quote.size);
+        let minimum_out = quote.size.checked_add(min_profit)?;
+        if token_amount == 0 || sell.sell(token_amount) < minimum_out {
+            return None;
+        }
+        Some(Self {
+            buy_pool: quote.buy_pool,
+            sell_pool: quote.sell_pool,
+            amount_in: quote.size,
+            token_amount,
+            minimum_out,
+            minimum_balance: quote_balance.and_then(|balance| balance.checked_add(min_profit)),
+        })
+    }
+}
+
+/// 池子能否直接构造 swap 指令
+///
+/// 目前支持已加载 OpenBook 市场的 Raydium AMM v4 池子（只支持 SPL Token 代币）和 Raydium CP 池子。
+pub fn supports(pool_data: &MintPoolData, pool: &Pubkey) -> bool {
+    let raydium = pool_data
+        .raydium_pools
+        .iter()
+        .any(|p| p.pool == *pool && p.market.is_some());
+    (raydium && pool_data.token_program == spl_token::ID)
+        || pool_data.raydium_cp_pools.iter().any(|p| p.pool == *pool)
+}
+
+/// 只保留支持直接兑换的池子的储备，报价时只在这些池子中选择路线
+pub fn supported_reserves(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+) -> HashMap<Pubkey, PoolReserves> {
+    reserves
+        .iter()
+        .filter(|(pool, _)| supports(pool_data, pool))
+        .map(|(pool, reserves)| (*pool, *reserves))
+        .collect()
+}
+
+/// 构造一个池子的 swap 指令，按精确输入兑换
+///
+/// # 参数
+/// * `wallet` - 钱包地址，付款和接收都使用钱包的关联代币账户
+/// * `pool` - 池子地址
+/// * `side` - 兑换方向
+/// * `amount_in` - 转出的数量
+/// * `minimum_out` - 至少换到的数量
+///
+/// # 错误
+/// 池子不在 `pool_data` 中或不支持直接兑换时返回错误
+pub fn swap_instruction(
+    pool_data: &MintPoolData,
+    wallet: &Pubkey,
+    pool: &Pubkey,
+    side: SwapSide,
+    amount_in: u64,
+    minimum_out: u64,
+) -> anyhow::Result<Instruction> {
+    let wallet_quote_account = pool_data.wallet_quote_account();
+    let wallet_token_account =
+        spl_associated_token_account::get_associated_token_address_with_program_id(
+            wallet,
+            &pool_data.mint,
+            &pool_data.token_program,
+        );
+    let (user_source, user_destination) = match side {
+        SwapSide::Buy => (wallet_quote_account, wallet_token_account),
+        SwapSide::Sell => (wallet_token_account, wallet_quote_account),
+    };
+
+    if let Some(raydium_pool) = pool_data.raydium_pools.iter().find(|p| p.pool == *pool) {
+        let accounts = raydium_pool
+            .swap_accounts(&user_source, &user_destination, wallet)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Raydium pool {} has no OpenBook market loaded", pool)
+            })?;
+        let mut data = vec![RAYDIUM_SWAP_BASE_IN];
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&minimum_out.to_le_bytes());
+        return Ok(Instruction {
+            program_id: *raydium_program_id(),
+            accounts,
+            data,
+        });
+    }
+
+    if let Some(cp_pool) = pool_data.raydium_cp_pools.iter().find(|p| p.pool == *pool) {
+        let token = (cp_pool.token_vault, pool_data.token_program, pool_data.mint);
+        let quote = (cp_pool.sol_vault, spl_token::ID, pool_data.quote_mint);
+        let (input, output) = match side {
+            SwapSide::Buy => (quote, token),
+            SwapSide::Sell => (token, quote),
+        };
+        let mut data = anchor_instruction_discriminator("swap_base_input").to_vec();
+        data.extend_from_slice(&amount_in.to_le_bytes());
+        data.extend_from_slice(&minimum_out.to_le_bytes());
+        return Ok(Instruction {
+            program_id: *raydium_cp_program_id(),
+            accounts: vec![
+                AccountMeta::new_readonly(*wallet, true),
+                AccountMeta::new_readonly(*raydium_cp_authority(), false),
+                AccountMeta::new_readonly(cp_pool.amm_config, false),
+                AccountMeta::new(cp_pool.pool, false),
+                AccountMeta::new(user_source, false),
+                AccountMeta::new(user_destination, false),
+                AccountMeta::new(input.0, false),
+                AccountMeta::new(output.0, false),
+                AccountMeta::new_readonly(input.1, false),
+                AccountMeta::new_readonly(output.1, false),
+                AccountMeta::new_readonly(input.2, false),
+                AccountMeta::new_readonly(output.2, false),
+                AccountMeta::new(cp_pool.observation, false),
+            ],
+            data,
+        });
+    }
+
+    Err(anyhow::anyhow!(
+        "Pool {} of mint {} does not support inline swaps",
+        pool,
+        pool_data.mint
+    ))
+}
+
+/// 余额检查指令：代币账户余额不低于 `minimum` 时成功，否则整笔交易失败
+///
+/// 使用 Lighthouse 的 `AssertTokenAccount` 断言，不输出日志。
+pub fn balance_check_instruction(token_account: &Pubkey, minimum: u64) -> Instruction {
+    let mut data = vec![
+        LIGHTHOUSE_ASSERT_TOKEN_ACCOUNT,
+        0, // LogLevel::Silent
+        TOKEN_ACCOUNT_ASSERTION_AMOUNT,
+    ];
+    data.extend_from_slice(&minimum.to_le_bytes());
+    data.push(INTEGER_OPERATOR_GTE);
+    Instruction {
+        program_id: *lighthouse_program_id(),
+        accounts: vec![AccountMeta::new_readonly(*token_account, false)],
+        data,
+    }
+}
+
+/// 读取钱包报价货币账户的余额，余额检查以此为基准
+pub fn fetch_quote_balance(rpc_client: &RpcClient, token_account: &Pubkey) -> anyhow::Result<u64> {
+    let balance = rpc_client.get_token_account_balance(token_account)?;
+    Ok(balance.amount.parse()?)
+}
+
+/// 构建并签名直接调用 DEX 的套利交易
+///
+/// 交易包含计算单元限制（带随机扰动）、计算单元价格、买入和卖出两条 swap 指令，
+/// `route.minimum_balance` 不为空时最后是余额检查指令。
+///
+/// # 参数说明
+/// - `route`: 买入/卖出池子和数量，见 `InlineRoute::from_quote`。
+/// - 其余参数同 `build_transaction`。
+///
+/// # 错误
+/// 代币有转账手续费、池子不支持直接兑换或交易编译失败时返回错误
+pub fn build_inline_transaction(
+    wallet_kp: &Keypair,
+    config: &Config,
+    mint_pool_data: &MintPoolData,
+    route: &InlineRoute,
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<VersionedTransaction> {
+    // 钱包收到的代币会少于买入指令的输出，卖出同样数量会失败
+    if mint_pool_data.transfer_fee.is_some() {
+        anyhow::bail!(
+            "Mint {} has a transfer fee, inline swaps are not supported",
+            mint_pool_data.mint
+        );
+    }
+    let wallet = wallet_kp.pubkey();
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(
+            config.bot.compute_unit_limit + rand::random::<u32>() % 1000,
+        ),
+        ComputeBudgetInstruction::set_compute_unit_price(config.compute_unit_price()),
+        swap_instruction(
+            mint_pool_data,
+            &wallet,
+            &route.buy_pool,
+            SwapSide::Buy,
+            route.amount_in,
+            route.token_amount,
+        )?,
+        swap_instruction(
+            mint_pool_data,
+            &wallet,
+            &route.sell_pool,
+            SwapSide::Sell,
+            route.token_amount,
+            route.minimum_out,
+        )?,
+    ];
+    if let Some(minimum_balance) = route.minimum_balance {
+        instructions.push(balance_check_instruction(
+            &mint_pool_data.wallet_quote_account(),
+            minimum_balance,
+        ));
+    }
+
+    let message = Message::try_compile(
+        &wallet,
+        &instructions,
+        &select_lookup_tables(&instructions, address_lookup_table_accounts),
+        blockhash,
+    )?;
+    Ok(VersionedTransaction::try_new(
+        VersionedMessage::V0(message),
+        &[wallet_kp],
+    )?)
+}
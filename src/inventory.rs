@@ -0,0 +1,400 @@
+use crate::config::InventoryConfig;
+use crate::constants::sol_mint;
+use crate::flashloan::{solend_program_id, SOLEND_SOL_RESERVE};
+use crate::quote::token_amount;
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::native_token::{lamports_to_sol, sol_to_lamports};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info};
+
+const REFRESH_RESERVE: u8 = 3;
+const DEPOSIT_RESERVE_LIQUIDITY: u8 = 4;
+const REDEEM_RESERVE_COLLATERAL: u8 = 5;
+
+/// 储备账户中各字段的偏移（SPL token-lending 布局）
+const RESERVE_LENDING_MARKET_OFFSET: usize = 10;
+const RESERVE_LIQUIDITY_MINT_OFFSET: usize = 42;
+const RESERVE_LIQUIDITY_SUPPLY_OFFSET: usize = 75;
+const RESERVE_PYTH_ORACLE_OFFSET: usize = 107;
+const RESERVE_SWITCHBOARD_ORACLE_OFFSET: usize = 139;
+const RESERVE_AVAILABLE_AMOUNT_OFFSET: usize = 171;
+const RESERVE_BORROWED_AMOUNT_WADS_OFFSET: usize = 179;
+const RESERVE_COLLATERAL_MINT_OFFSET: usize = 227;
+const RESERVE_COLLATERAL_SUPPLY_AMOUNT_OFFSET: usize = 259;
+const RESERVE_MIN_LEN: usize = 299;
+
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// 从链上读取的 Solend 储备状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReserveState {
+    pub address: Pubkey,
+    pub lending_market: Pubkey,
+    pub liquidity_mint: Pubkey,
+    pub liquidity_supply: Pubkey,
+    pub pyth_oracle: Pubkey,
+    pub switchboard_oracle: Pubkey,
+    pub collateral_mint: Pubkey,
+    /// 储备中可借出的流动性
+    pub available_amount: u64,
+    /// 已借出的流动性（WAD 精度）
+    pub borrowed_amount_wads: u128,
+    /// 存款凭证（cToken）的总供应量
+    pub collateral_supply: u64,
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(data[offset..offset + 32].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+impl ReserveState {
+    /// 解析储备账户数据
+    pub fn parse(address: Pubkey, data: &[u8]) -> anyhow::Result<Self> {
+        if data.len() < RESERVE_MIN_LEN {
+            anyhow::bail!(
+                "Reserve account {} is too short ({} bytes)",
+                address,
+                data.len()
+            );
+        }
+        Ok(Self {
+            address,
+            lending_market: read_pubkey(data, RESERVE_LENDING_MARKET_OFFSET),
+            liquidity_mint: read_pubkey(data, RESERVE_LIQUIDITY_MINT_OFFSET),
+            liquidity_supply: read_pubkey(data, RESERVE_LIQUIDITY_SUPPLY_OFFSET),
+            pyth_oracle: read_pubkey(data, RESERVE_PYTH_ORACLE_OFFSET),
+            switchboard_oracle: read_pubkey(data, RESERVE_SWITCHBOARD_ORACLE_OFFSET),
+            collateral_mint: read_pubkey(data, RESERVE_COLLATERAL_MINT_OFFSET),
+            available_amount: read_u64(data, RESERVE_AVAILABLE_AMOUNT_OFFSET),
+            borrowed_amount_wads: u128::from_le_bytes(
+                data[RESERVE_BORROWED_AMOUNT_WADS_OFFSET..RESERVE_BORROWED_AMOUNT_WADS_OFFSET + 16]
+                    .try_into()
+                    .unwrap(),
+            ),
+            collateral_supply: read_u64(data, RESERVE_COLLATERAL_SUPPLY_AMOUNT_OFFSET),
+        })
+    }
+
+    /// 储备的总流动性：可借出的加上已借出的
+    pub fn total_liquidity(&self) -> u128 {
+        self.available_amount as u128 + self.borrowed_amount_wads / WAD
+    }
+
+    /// cToken 数量换算成流动性（lamports）
+    pub fn collateral_to_liquidity(&self, collateral: u64) -> u64 {
+        if self.collateral_supply == 0 {
+            return collateral;
+        }
+        (collateral as u128 * self.total_liquidity() / self.collateral_supply as u128) as u64
+    }
+
+    /// 流动性换算成 cToken 数量，向上取整以保证赎回的流动性不少于 `liquidity`
+    pub fn liquidity_to_collateral(&self, liquidity: u64) -> u64 {
+        let total = self.total_liquidity();
+        if total == 0 {
+            return liquidity;
+        }
+        (liquidity as u128 * self.collateral_supply as u128).div_ceil(total) as u64
+    }
+
+    fn lending_market_authority(&self) -> Pubkey {
+        Pubkey::find_program_address(&[self.lending_market.as_ref()], solend_program_id()).0
+    }
+
+    /// 刷新储备的利率和价格，存取款前必须在同一笔交易中调用
+    pub fn refresh_instruction(&self) -> Instruction {
+        Instruction {
+            program_id: *solend_program_id(),
+            accounts: vec![
+                AccountMeta::new(self.address, false),
+                AccountMeta::new_readonly(self.pyth_oracle, false),
+                AccountMeta::new_readonly(self.switchboard_oracle, false),
+            ],
+            data: vec![REFRESH_RESERVE],
+        }
+    }
+
+    /// 把 `amount` lamports 的 WSOL 存入储备，换成 cToken
+    pub fn deposit_instruction(
+        &self,
+        source: &Pubkey,
+        collateral_account: &Pubkey,
+        owner: &Pubkey,
+        amount: u64,
+    ) -> Instruction {
+        let mut data = vec![DEPOSIT_RESERVE_LIQUIDITY];
+        data.extend_from_slice(&amount.to_le_bytes());
+        Instruction {
+            program_id: *solend_program_id(),
+            accounts: vec![
+                AccountMeta::new(*source, false),
+                AccountMeta::new(*collateral_account, false),
+                AccountMeta::new(self.address, false),
+                AccountMeta::new(self.liquidity_supply, false),
+                AccountMeta::new(self.collateral_mint, false),
+                AccountMeta::new_readonly(self.lending_market, false),
+                AccountMeta::new_readonly(self.lending_market_authority(), false),
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data,
+        }
+    }
+
+    /// 赎回 `collateral` 个 cToken，换回 WSOL
+    pub fn redeem_instruction(
+        &self,
+        collateral_account: &Pubkey,
+        destination: &Pubkey,
+        owner: &Pubkey,
+        collateral: u64,
+    ) -> Instruction {
+        let mut data = vec![REDEEM_RESERVE_COLLATERAL];
+        data.extend_from_slice(&collateral.to_le_bytes());
+        Instruction {
+            program_id: *solend_program_id(),
+            accounts: vec![
+                AccountMeta::new(*collateral_account, false),
+                AccountMeta::new(*destination, false),
+                AccountMeta::new(self.address, false),
+                AccountMeta::new(self.collateral_mint, false),
+                AccountMeta::new(self.liquidity_supply, false),
+                AccountMeta::new_readonly(self.lending_market, false),
+                AccountMeta::new_readonly(self.lending_market_authority(), false),
+                AccountMeta::new_readonly(*owner, true),
+                AccountMeta::new_readonly(spl_token::ID, false),
+            ],
+            data,
+        }
+    }
+}
+
+/// 钱包的 WSOL 余额和存在借贷市场中的部分
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InventoryBalance {
+    /// 钱包 WSOL 账户余额（lamports）
+    pub wallet_wsol: u64,
+    /// 持有的 cToken 数量
+    pub collateral: u64,
+    /// cToken 按当前兑换率折算的 lamports
+    pub deposited: u64,
+}
+
+impl InventoryBalance {
+    pub fn total(&self) -> u64 {
+        self.wallet_wsol + self.deposited
+    }
+}
+
+/// 库存管理：把闲置的 WSOL 存入 Solend 赚取利息，遇到大额机会时及时取回
+///
+/// 后台任务定期检查余额，钱包中的 WSOL 超过 `idle_sol + rebalance_threshold_sol` 时把超出 `idle_sol` 的部分存入；
+/// 报价得到的交易规模超过钱包余额时，由发送循环调用 `ensure_available` 取回差额。
+pub struct Inventory {
+    rpc_client: Arc<RpcClient>,
+    wallet: Keypair,
+    wallet_wsol_account: Pubkey,
+    collateral_account: Pubkey,
+    reserve_address: Pubkey,
+    idle: u64,
+    threshold: u64,
+    check_interval: Duration,
+    balance: Mutex<InventoryBalance>,
+}
+
+impl Inventory {
+    /// 创建库存管理器并读取一次余额
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        wallet: &Keypair,
+        config: &InventoryConfig,
+    ) -> anyhow::Result<Self> {
+        let reserve_address =
+            Pubkey::from_str(config.reserve.as_deref().unwrap_or(SOLEND_SOL_RESERVE))?;
+        let reserve = fetch_reserve(&rpc_client, &reserve_address)?;
+        if reserve.liquidity_mint != *sol_mint() {
+            anyhow::bail!(
+                "Reserve {} lends {}, expected WSOL",
+                reserve_address,
+                reserve.liquidity_mint
+            );
+        }
+
+        let inventory = Self {
+            rpc_client,
+            wallet: Keypair::from_bytes(&wallet.to_bytes())?,
+            wallet_wsol_account: get_associated_token_address(&wallet.pubkey(), sol_mint()),
+            collateral_account: get_associated_token_address(
+                &wallet.pubkey(),
+                &reserve.collateral_mint,
+            ),
+            reserve_address,
+            idle: sol_to_lamports(config.idle_sol),
+            threshold: sol_to_lamports(config.rebalance_threshold_sol()),
+            check_interval: config.check_interval(),
+            balance: Mutex::new(InventoryBalance::default()),
+        };
+        let balance = inventory.refresh()?;
+        info!(
+            "Inventory: {} SOL total, {} SOL in wallet, {} SOL deposited in reserve {}",
+            lamports_to_sol(balance.total()),
+            lamports_to_sol(balance.wallet_wsol),
+            lamports_to_sol(balance.deposited),
+            reserve_address
+        );
+        Ok(inventory)
+    }
+
+    /// 最近一次读取的余额
+    pub fn balance(&self) -> InventoryBalance {
+        *self.balance.lock().unwrap()
+    }
+
+    /// 重新读取钱包 WSOL、cToken 余额和储备兑换率
+    pub fn refresh(&self) -> anyhow::Result<InventoryBalance> {
+        let (balance, _) = self.fetch_balance()?;
+        *self.balance.lock().unwrap() = balance;
+        Ok(balance)
+    }
+
+    fn fetch_balance(&self) -> anyhow::Result<(InventoryBalance, ReserveState)> {
+        let accounts = self.rpc_client.get_multiple_accounts(&[
+            self.reserve_address,
+            self.wallet_wsol_account,
+            self.collateral_account,
+        ])?;
+        let reserve_account = accounts[0]
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Reserve {} not found", self.reserve_address))?;
+        let reserve = ReserveState::parse(self.reserve_address, &reserve_account.data)?;
+        let amount_at = |index: usize| {
+            accounts[index]
+                .as_ref()
+                .and_then(|account| token_amount(&account.data))
+                .unwrap_or(0)
+        };
+
+        let collateral = amount_at(2);
+        let balance = InventoryBalance {
+            wallet_wsol: amount_at(1),
+            collateral,
+            deposited: reserve.collateral_to_liquidity(collateral),
+        };
+        Ok((balance, reserve))
+    }
+
+    /// 把钱包中超出保留量的 WSOL 存入储备
+    ///
+    /// # 返回值
+    /// 发送了存款交易时返回签名
+    pub fn park_excess(&self) -> anyhow::Result<Option<Signature>> {
+        let (balance, reserve) = self.fetch_balance()?;
+        if balance.wallet_wsol <= self.idle + self.threshold {
+            return Ok(None);
+        }
+        let amount = balance.wallet_wsol - self.idle;
+        let owner = self.wallet.pubkey();
+        let signature = self.send(&[
+            create_associated_token_account_idempotent(
+                &owner,
+                &owner,
+                &reserve.collateral_mint,
+                &spl_token::ID,
+            ),
+            reserve.refresh_instruction(),
+            reserve.deposit_instruction(
+                &self.wallet_wsol_account,
+                &self.collateral_account,
+                &owner,
+                amount,
+            ),
+        ])?;
+        info!(
+            "Parked {} SOL in reserve {}: {}",
+            lamports_to_sol(amount),
+            self.reserve_address,
+            signature
+        );
+        self.refresh()?;
+        Ok(Some(signature))
+    }
+
+    /// 保证钱包中至少有 `amount` lamports 的 WSOL，不足时从储备赎回差额
+    ///
+    /// # 返回值
+    /// 发送了赎回交易时返回签名；存款不足以补齐时赎回全部存款
+    pub fn ensure_available(&self, amount: u64) -> anyhow::Result<Option<Signature>> {
+        // 先用缓存的余额判断，避免每轮都请求 RPC
+        if self.balance().wallet_wsol >= amount {
+            return Ok(None);
+        }
+        let (balance, reserve) = self.fetch_balance()?;
+        if balance.wallet_wsol >= amount || balance.collateral == 0 {
+            return Ok(None);
+        }
+        let shortfall = amount - balance.wallet_wsol;
+        let collateral = reserve
+            .liquidity_to_collateral(shortfall)
+            .min(balance.collateral);
+        let owner = self.wallet.pubkey();
+        let signature = self.send(&[
+            reserve.refresh_instruction(),
+            reserve.redeem_instruction(
+                &self.collateral_account,
+                &self.wallet_wsol_account,
+                &owner,
+                collateral,
+            ),
+        ])?;
+        info!(
+            "Withdrew {} SOL from reserve {}: {}",
+            lamports_to_sol(reserve.collateral_to_liquidity(collateral)),
+            self.reserve_address,
+            signature
+        );
+        self.refresh()?;
+        Ok(Some(signature))
+    }
+
+    fn send(&self, instructions: &[Instruction]) -> anyhow::Result<Signature> {
+        let mut all_instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(200_000)];
+        all_instructions.extend_from_slice(instructions);
+        let tx = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&self.wallet.pubkey()),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash()?,
+        );
+        Ok(self.rpc_client.send_and_confirm_transaction(&tx)?)
+    }
+
+    /// 定期把闲置的 WSOL 存入储备
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            tokio::time::sleep(self.check_interval).await;
+            if let Err(e) = self.park_excess() {
+                error!("Failed to park idle WSOL: {}", e);
+            }
+        }
+    }
+}
+
+/// 读取并解析储备账户
+pub fn fetch_reserve(rpc_client: &RpcClient, address: &Pubkey) -> anyhow::Result<ReserveState> {
+    let account = rpc_client.get_account(address)?;
+    ReserveState::parse(*address, &account.data)
+}
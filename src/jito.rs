@@ -0,0 +1,391 @@
+use crate::config::JitoConfig;
+use crate::constants::sol_mint;
+use crate::submit::{Submission, SubmissionResult, Submitter};
+use crate::transaction::simulate_profit;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use futures::future::join_all;
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// 默认的 Jito 区块引擎
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// Jito 小费账户，每次随机选择一个，减少写锁竞争
+pub const TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// 区块引擎请求的超时时间
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 先验上链率相当于多少个 bundle，样本少的区域权重主要由先验决定
+const REGION_PRIOR_WEIGHT: f64 = 4.0;
+
+/// 没有结果时假设的区域上链率
+const REGION_PRIOR_LANDING_RATE: f64 = 0.5;
+
+/// 按收益分成计算小费
+///
+/// 小费为收益的 `tip_percent`%，限制在 `min_tip_lamports` 和 `max_tip_lamports` 之间；
+/// 收益未知或不为正时使用下限。
+///
+/// # 参数
+/// * `profit` - 以 lamports 计的收益，以其他货币报价时传入 `None`
+pub fn tip_for_profit(config: &JitoConfig, profit: Option<i64>) -> u64 {
+    let min_tip = config.min_tip_lamports();
+    let share = match profit {
+        Some(profit) if profit > 0 => (profit as f64 * config.tip_percent() / 100.0) as u64,
+        _ => 0,
+    };
+    let tip = share.max(min_tip);
+    config
+        .max_tip_lamports
+        .map_or(tip, |max_tip| tip.min(max_tip))
+}
+
+/// 小费占收益的比例，收益不为正时为 `None`
+///
+/// # 参数
+/// * `profit` - 套利交易上链后的收益（lamports），不含另一笔交易中支付的小费
+pub fn tip_ratio(tip: u64, profit: i64) -> Option<f64> {
+    (profit > 0).then(|| tip as f64 / profit as f64)
+}
+
+/// 给随机一个小费账户转账的交易，和套利交易放在同一个 bundle 中
+pub fn tip_transaction(wallet_kp: &Keypair, tip: u64, blockhash: Hash) -> VersionedTransaction {
+    let tip_account = Pubkey::from_str(TIP_ACCOUNTS[rand::random::<usize>() % TIP_ACCOUNTS.len()])
+        .expect("tip accounts are valid pubkeys");
+    let instruction = system_instruction::transfer(&wallet_kp.pubkey(), &tip_account, tip);
+    Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&wallet_kp.pubkey()),
+        &[wallet_kp],
+        blockhash,
+    )
+    .into()
+}
+
+/// 一个区域的区块引擎发送统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegionStats {
+    /// 发送过的 bundle 数
+    pub sent: u64,
+    /// 区块引擎接受的 bundle 数
+    pub accepted: u64,
+    /// 接受后上链的 bundle 数
+    pub landed: u64,
+}
+
+impl RegionStats {
+    /// 按发送数计算的上链率，向先验收缩，作为选择区域的权重
+    pub fn weight(&self) -> f64 {
+        (self.landed as f64 + REGION_PRIOR_WEIGHT * REGION_PRIOR_LANDING_RATE)
+            / (self.sent as f64 + REGION_PRIOR_WEIGHT)
+    }
+}
+
+/// 多个区域的区块引擎及各区域的上链统计
+///
+/// 每个 bundle 发送到 `race` 个区域，按权重无放回抽样选择；上链率高的区域更常被选中，
+/// 先验让新区域和落后的区域仍有机会被选中。发送到所有区域时权重只用于日志。
+#[derive(Debug)]
+pub struct BlockEngineRegions {
+    urls: Vec<String>,
+    race: usize,
+    stats: Mutex<Vec<RegionStats>>,
+}
+
+impl BlockEngineRegions {
+    pub fn new(urls: Vec<String>, race: usize) -> Self {
+        Self {
+            stats: Mutex::new(vec![RegionStats::default(); urls.len()]),
+            race: race.clamp(1, urls.len().max(1)),
+            urls,
+        }
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    /// 本次发送的区域下标，按权重从高到低的抽样顺序
+    pub fn pick(&self) -> Vec<usize> {
+        if self.race >= self.urls.len() {
+            return (0..self.urls.len()).collect();
+        }
+        let stats = self.stats.lock().unwrap();
+        // Efraimidis-Spirakis 加权抽样：每个区域取 u^(1/w)，保留最大的 race 个
+        let mut keys: Vec<(f64, usize)> = stats
+            .iter()
+            .enumerate()
+            .map(|(region, stats)| (rand::random::<f64>().powf(1.0 / stats.weight()), region))
+            .collect();
+        keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+        keys.into_iter()
+            .take(self.race)
+            .map(|(_, region)| region)
+            .collect()
+    }
+
+    /// 记录一次发送及区块引擎是否接受了 bundle
+    pub fn record_send(&self, region: usize, accepted: bool) {
+        let mut stats = self.stats.lock().unwrap();
+        stats[region].sent += 1;
+        if accepted {
+            stats[region].accepted += 1;
+        }
+    }
+
+    /// 记录上链结果，接受了这个 bundle 的区域都记一次上链
+    pub fn record_landed(&self, regions: &[usize]) {
+        let mut stats = self.stats.lock().unwrap();
+        for region in regions {
+            stats[*region].landed += 1;
+        }
+    }
+
+    pub fn stats(&self) -> Vec<RegionStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// 各区域的上链数、发送数和权重，用于日志
+    pub fn summary(&self) -> String {
+        self.urls
+            .iter()
+            .zip(self.stats())
+            .map(|(url, stats)| {
+                format!(
+                    "{} {}/{} landed (weight {:.2})",
+                    url,
+                    stats.landed,
+                    stats.sent,
+                    stats.weight()
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// 至少一个区域接受的 bundle
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SentBundle {
+    pub bundle_id: String,
+    /// 接受了 bundle 的区域下标
+    pub regions: Vec<usize>,
+}
+
+/// 通过 Jito 区块引擎发送 bundle
+///
+/// 套利交易先模拟得到收益，按收益计算小费，和小费交易一起作为 bundle 发送。
+pub struct JitoSender {
+    client: reqwest::Client,
+    config: JitoConfig,
+    /// 发送前模拟交易使用的 RPC 客户端
+    rpc_client: Arc<RpcClient>,
+    /// 签名小费交易的钱包
+    wallet: Keypair,
+    regions: BlockEngineRegions,
+    /// 已发送、还没有上链结果的 bundle，按套利交易签名记录接受它的区域
+    pending: Mutex<HashMap<Signature, Vec<usize>>>,
+}
+
+impl JitoSender {
+    /// # 参数
+    /// * `config` - Jito 配置
+    /// * `rpc_client` - 发送前模拟交易使用的 RPC 客户端
+    /// * `wallet` - 支付小费的钱包，和套利交易的签名者相同
+    pub fn new(
+        config: &JitoConfig,
+        rpc_client: Arc<RpcClient>,
+        wallet: Keypair,
+    ) -> anyhow::Result<Self> {
+        let client = crate::proxy::configure(
+            reqwest::Client::builder().timeout(REQUEST_TIMEOUT),
+            config.proxy.as_ref(),
+        )?
+        .build()?;
+        Ok(Self {
+            client,
+            config: config.clone(),
+            rpc_client,
+            wallet,
+            regions: BlockEngineRegions::new(config.block_engine_urls(), config.race_regions()),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn regions(&self) -> &BlockEngineRegions {
+        &self.regions
+    }
+
+    /// 按收益计算的小费，见 `tip_for_profit`
+    pub fn tip_for_profit(&self, profit: Option<i64>) -> u64 {
+        tip_for_profit(&self.config, profit)
+    }
+
+    /// 把 bundle 同时发送到选中的区域，至少一个区域接受时成功
+    ///
+    /// # 错误
+    /// 所有区域都拒绝或请求失败时返回最后一个错误
+    pub async fn send_bundle(
+        &self,
+        transactions: &[&VersionedTransaction],
+    ) -> anyhow::Result<SentBundle> {
+        let encoded = transactions
+            .iter()
+            .map(|tx| Ok(BASE64_STANDARD.encode(bincode::serialize(tx)?)))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "sendBundle",
+            "params": [encoded, { "encoding": "base64" }],
+        });
+        let picked = self.regions.pick();
+        let results = join_all(
+            picked
+                .iter()
+                .map(|region| self.post_bundle(&self.regions.urls[*region], &request)),
+        )
+        .await;
+
+        let mut bundle = None;
+        let mut regions = Vec::new();
+        let mut last_error = None;
+        for (region, result) in picked.into_iter().zip(results) {
+            self.regions.record_send(region, result.is_ok());
+            match result {
+                Ok(bundle_id) => {
+                    regions.push(region);
+                    bundle.get_or_insert(bundle_id);
+                }
+                Err(e) => {
+                    debug!("Block engine {} failed: {}", self.regions.urls[region], e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match bundle {
+            Some(bundle_id) => Ok(SentBundle { bundle_id, regions }),
+            None => {
+                Err(last_error.unwrap_or_else(|| anyhow::anyhow!("No block engine configured")))
+            }
+        }
+    }
+
+    /// 调用一个区块引擎的 `sendBundle`，返回 bundle ID
+    async fn post_bundle(&self, block_engine_url: &str, request: &Value) -> anyhow::Result<String> {
+        let url = format!("{}/api/v1/bundles", block_engine_url.trim_end_matches('/'));
+        let response: Value = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await?
+            .json()
+            .await?;
+        bundle_id(&response)
+    }
+
+    /// 记录 bundle 的上链结果，更新接受它的区域的上链统计
+    ///
+    /// # 参数
+    /// * `signature` - bundle 中套利交易的签名
+    /// * `landed` - 是否上链；为 `None` 时结果未知，只清理记录
+    pub fn record_result(&self, signature: &Signature, landed: Option<bool>) {
+        let Some(regions) = self.pending.lock().unwrap().remove(signature) else {
+            return;
+        };
+        if landed == Some(true) {
+            self.regions.record_landed(&regions);
+            if self.regions.urls.len() > 1 {
+                info!("Block engine regions: {}", self.regions.summary());
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Submitter for JitoSender {
+    fn endpoints(&self) -> Vec<String> {
+        self.regions.urls().to_vec()
+    }
+
+    /// 模拟得到收益后按收益计算小费，和小费交易一起作为 bundle 发送
+    ///
+    /// # 错误
+    /// 模拟失败、模拟收益不足以支付小费，或区块引擎拒绝 bundle 时返回错误
+    async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SubmissionResult> {
+        let tx = submission.tx;
+        let profit = simulate_profit(&self.rpc_client, tx, &submission.wallet_quote_account)?;
+        // 小费以 lamports 支付，只有 SOL 报价的收益可以直接分成
+        let quote_is_sol = submission.quote_mint == *sol_mint();
+        let tip = self.tip_for_profit(profit.filter(|_| quote_is_sol));
+        match profit {
+            Some(profit) if profit > 0 && (!quote_is_sol || profit > tip as i64) => {}
+            profit => anyhow::bail!(
+                "Simulated profit {:?} does not cover the Jito tip of {} lamports",
+                profit,
+                tip
+            ),
+        }
+
+        let tip_tx = tip_transaction(&self.wallet, tip, *tx.message.recent_blockhash());
+        let sent = self.send_bundle(&[tx, &tip_tx]).await?;
+        info!(
+            "Bundle {} accepted by {} block engine(s): tip {} lamports, simulated profit {:?}",
+            sent.bundle_id,
+            sent.regions.len(),
+            tip,
+            profit
+        );
+        let mut result = SubmissionResult {
+            tip: Some(tip),
+            ..Default::default()
+        };
+        for region in &sent.regions {
+            result.record(tx.signatures[0], &self.regions.urls[*region]);
+        }
+        self.pending
+            .lock()
+            .unwrap()
+            .insert(tx.signatures[0], sent.regions);
+        Ok(result)
+    }
+}
+
+/// 解析 `sendBundle` 的响应
+///
+/// # 错误
+/// 响应中带有错误或没有 bundle ID 时返回错误
+pub fn bundle_id(response: &Value) -> anyhow::Result<String> {
+    if let Some(error) = response.get("error") {
+        anyhow::bail!("Block engine rejected the bundle: {}", error);
+    }
+    response
+        .get("result")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected sendBundle response: {}", response))
+}
@@ -0,0 +1,215 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// 模型文件格式版本，结构变化时递增，旧文件会被忽略
+const MODEL_VERSION: u32 = 1;
+
+/// 价差持续时间分桶的上界（毫秒），超过最后一个上界的归入最后一个桶
+const SPREAD_AGE_BOUNDS_MS: [u64; 4] = [100, 400, 1_600, 6_400];
+
+/// 先验上链率相当于多少笔交易，样本少的分桶主要由先验决定
+const PRIOR_WEIGHT: f64 = 4.0;
+
+/// 每个签名的基础手续费（lamports）
+const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// 上链率统计的分桶：计算单元价格的数量级和价差已经持续的时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LandingBucket {
+    /// `compute_unit_price` 的二进制位数，0 表示不付优先费
+    pub fee_bucket: u8,
+    /// `SPREAD_AGE_BOUNDS_MS` 中第一个大于价差持续时间的上界的下标
+    pub age_bucket: u8,
+}
+
+impl LandingBucket {
+    /// # 参数
+    /// * `compute_unit_price` - 发送时的计算单元价格（micro-lamports）
+    /// * `spread_age` - 这条价差从第一次报价有收益到发送经过的时间
+    pub fn new(compute_unit_price: u64, spread_age: Duration) -> Self {
+        let age_ms = spread_age.as_millis() as u64;
+        let age_bucket = SPREAD_AGE_BOUNDS_MS
+            .iter()
+            .position(|bound| age_ms < *bound)
+            .unwrap_or(SPREAD_AGE_BOUNDS_MS.len());
+        Self {
+            fee_bucket: (u64::BITS - compute_unit_price.leading_zeros()) as u8,
+            age_bucket: age_bucket as u8,
+        }
+    }
+}
+
+/// 一个分桶中已经有结果的交易数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LandingCounts {
+    pub sent: u64,
+    pub landed: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ModelFile {
+    version: u32,
+    buckets: Vec<(LandingBucket, LandingCounts)>,
+}
+
+/// 按计算单元价格和价差持续时间统计的上链率
+///
+/// 每个分桶的上链率向所有交易的整体上链率收缩，整体上链率再向配置的先验收缩，
+/// 各自相当于 `PRIOR_WEIGHT` 笔交易，所以新分桶和刚启动时的估计不会因为几笔结果大起大落。
+#[derive(Debug)]
+pub struct LandingModel {
+    prior_landing_rate: f64,
+    buckets: Mutex<HashMap<LandingBucket, LandingCounts>>,
+    /// 上次保存后是否记录过新的结果
+    dirty: AtomicBool,
+}
+
+impl LandingModel {
+    pub fn new(prior_landing_rate: f64) -> Self {
+        Self {
+            prior_landing_rate,
+            buckets: Mutex::new(HashMap::new()),
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// 读取上次运行保存的模型，文件不存在或无法解析时从空模型开始
+    pub fn load<P: AsRef<Path>>(path: P, prior_landing_rate: f64) -> Self {
+        let path = path.as_ref();
+        let model = Self::new(prior_landing_rate);
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return model,
+            Err(e) => {
+                warn!("Failed to read landing model {}: {}", path.display(), e);
+                return model;
+            }
+        };
+        match serde_json::from_str::<ModelFile>(&contents) {
+            Ok(file) if file.version == MODEL_VERSION => {
+                let mut buckets = model.buckets.lock().unwrap();
+                buckets.extend(file.buckets);
+                let total = buckets.values().map(|counts| counts.sent).sum::<u64>();
+                info!(
+                    "Loaded landing model {} ({} transactions)",
+                    path.display(),
+                    total
+                );
+                drop(buckets);
+                model
+            }
+            Ok(file) => {
+                warn!(
+                    "Ignoring landing model {}: version {} is not {}",
+                    path.display(),
+                    file.version,
+                    MODEL_VERSION
+                );
+                model
+            }
+            Err(e) => {
+                warn!("Ignoring landing model {}: {}", path.display(), e);
+                model
+            }
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let mut buckets: Vec<_> = self
+            .buckets
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(bucket, counts)| (*bucket, *counts))
+            .collect();
+        buckets.sort_by_key(|(bucket, _)| (bucket.fee_bucket, bucket.age_bucket));
+        let file = ModelFile {
+            version: MODEL_VERSION,
+            buckets,
+        };
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, serde_json::to_vec_pretty(&file)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        self.dirty.store(false, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// 记录一笔交易的结果
+    ///
+    /// # 参数
+    /// * `bucket` - 发送时的分桶
+    /// * `landed` - 是否上链，超时未上链记为 `false`
+    pub fn record(&self, bucket: LandingBucket, landed: bool) {
+        let mut buckets = self.buckets.lock().unwrap();
+        let counts = buckets.entry(bucket).or_default();
+        counts.sent += 1;
+        counts.landed += landed as u64;
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn counts(&self, bucket: &LandingBucket) -> LandingCounts {
+        self.buckets
+            .lock()
+            .unwrap()
+            .get(bucket)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 估计该分桶的交易上链的概率
+    pub fn landing_probability(&self, bucket: &LandingBucket) -> f64 {
+        let buckets = self.buckets.lock().unwrap();
+        let (sent, landed) = buckets.values().fold((0, 0), |(sent, landed), counts| {
+            (sent + counts.sent, landed + counts.landed)
+        });
+        let overall = smoothed(landed, sent, self.prior_landing_rate);
+        let counts = buckets.get(bucket).copied().unwrap_or_default();
+        smoothed(counts.landed, counts.sent, overall)
+    }
+
+    /// 定期把模型保存到 `path`，没有新结果时不写文件
+    pub async fn run_saver(self: Arc<Self>, path: PathBuf, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            if !self.dirty.load(Ordering::Relaxed) {
+                continue;
+            }
+            if let Err(e) = self.save(&path) {
+                warn!("Failed to save landing model {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// 以 `prior` 为先验、权重 `PRIOR_WEIGHT` 的上链率
+fn smoothed(landed: u64, sent: u64, prior: f64) -> f64 {
+    (landed as f64 + prior * PRIOR_WEIGHT) / (sent as f64 + PRIOR_WEIGHT)
+}
+
+/// 一笔交易的手续费：签名费加优先费（lamports）
+///
+/// # 参数
+/// * `compute_unit_price` - 计算单元价格（micro-lamports）
+/// * `compute_unit_limit` - 计算单元上限
+pub fn transaction_fee(compute_unit_price: u64, compute_unit_limit: u32) -> u64 {
+    let priority_fee =
+        (compute_unit_price as u128 * compute_unit_limit as u128).div_ceil(1_000_000) as u64;
+    LAMPORTS_PER_SIGNATURE + priority_fee
+}
+
+/// 期望收益：`profit × P(上链) − 手续费 − 小费`（lamports）
+///
+/// # 参数
+/// * `profit` - 报价得到的预期收益
+/// * `landing_probability` - 估计的上链概率
+/// * `fee` - 交易手续费，见 `transaction_fee`
+/// * `tip` - 给区块引擎的小费
+pub fn expected_value(profit: i64, landing_probability: f64, fee: u64, tip: u64) -> f64 {
+    profit as f64 * landing_probability - fee as f64 - tip as f64
+}
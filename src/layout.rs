@@ -0,0 +1,50 @@
+use bytemuck::{Pod, Zeroable};
+use solana_sdk::pubkey::Pubkey;
+
+/// 小端序 u64，对齐为 1，可以映射到账户数据中的任意偏移
+#[repr(transparent)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Pod, Zeroable)]
+pub struct PodU64([u8; 8]);
+
+impl PodU64 {
+    pub fn get(self) -> u64 {
+        u64::from_le_bytes(self.0)
+    }
+}
+
+impl From<u64> for PodU64 {
+    fn from(value: u64) -> Self {
+        Self(value.to_le_bytes())
+    }
+}
+
+/// 把账户数据 `offset` 处的字节直接当作 `T` 读取，不复制字段
+///
+/// RPC 返回的账户数据没有对齐保证，`T` 只能由 `Pubkey`、`PodU64` 和字节数组组成（对齐为 1），
+/// 否则总是返回 `None`。
+///
+/// # 返回值
+/// 数据长度不足时返回 `None`
+pub fn view<T: Pod>(data: &[u8], offset: usize) -> Option<&T> {
+    let end = offset.checked_add(std::mem::size_of::<T>())?;
+    bytemuck::try_from_bytes(data.get(offset..end)?).ok()
+}
+
+/// SPL Token 和 Token 2022 代币账户的开头部分
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct TokenAccountLayout {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: PodU64,
+}
+
+/// SPL Token 和 Token 2022 mint 账户的开头部分
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct MintLayout {
+    /// `COption` 的标记，1 表示设置了 mint authority
+    pub mint_authority_option: [u8; 4],
+    pub mint_authority: Pubkey,
+    pub supply: PodU64,
+}
@@ -0,0 +1,150 @@
+use crate::config::LeaderScheduleConfig;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// 当前 slot 的轮询间隔，约等于一个 slot 的时长
+const SLOT_POLL_INTERVAL: Duration = Duration::from_millis(400);
+
+/// 每次获取的 leader 数量
+const LEADER_WINDOW_SLOTS: u64 = 1_000;
+
+/// 剩余的 leader 不足这么多个 slot 时重新获取
+const LEADER_REFRESH_MARGIN: u64 = 100;
+
+#[derive(Debug, Default)]
+struct LeaderWindow {
+    current_slot: Slot,
+    /// `leaders[i]` 是 `start_slot + i` 的 leader
+    start_slot: Slot,
+    leaders: Vec<Pubkey>,
+}
+
+impl LeaderWindow {
+    fn end_slot(&self) -> Slot {
+        self.start_slot + self.leaders.len() as u64
+    }
+
+    fn leader_at(&self, slot: Slot) -> Option<Pubkey> {
+        slot.checked_sub(self.start_slot)
+            .and_then(|index| self.leaders.get(index as usize))
+            .copied()
+    }
+}
+
+/// 跟踪当前 slot 和即将到来的 leader
+///
+/// 后台任务定期轮询 `getSlot`，leader 列表快用完时通过 `getSlotLeaders` 再取一段。
+#[derive(Debug, Default)]
+pub struct LeaderTracker {
+    window: RwLock<LeaderWindow>,
+}
+
+impl LeaderTracker {
+    /// 从当前 slot 开始的 `count` 个 slot 的 leader，leader 未知的 slot 会被跳过
+    pub fn upcoming_leaders(&self, count: u64) -> Vec<Pubkey> {
+        let window = self.window.read().unwrap();
+        (window.current_slot..window.current_slot + count)
+            .filter_map(|slot| window.leader_at(slot))
+            .collect()
+    }
+
+    /// 持续刷新当前 slot 和 leader 列表
+    pub async fn run(self: Arc<Self>, rpc_client: Arc<RpcClient>) {
+        loop {
+            // 使用 processed 提交级别，finalized 的 slot 会落后三十多个 slot
+            match rpc_client.get_slot_with_commitment(CommitmentConfig::processed()) {
+                Ok(slot) => {
+                    let needs_leaders = {
+                        let mut window = self.window.write().unwrap();
+                        window.current_slot = slot;
+                        slot < window.start_slot
+                            || slot + LEADER_REFRESH_MARGIN >= window.end_slot()
+                    };
+                    if needs_leaders {
+                        self.refresh_leaders(&rpc_client, slot);
+                    }
+                }
+                Err(e) => error!("Failed to fetch current slot: {}", e),
+            }
+            tokio::time::sleep(SLOT_POLL_INTERVAL).await;
+        }
+    }
+
+    fn refresh_leaders(&self, rpc_client: &RpcClient, start_slot: Slot) {
+        match rpc_client.get_slot_leaders(start_slot, LEADER_WINDOW_SLOTS) {
+            Ok(leaders) => {
+                debug!(
+                    "Fetched {} slot leaders starting at {}",
+                    leaders.len(),
+                    start_slot
+                );
+                let mut window = self.window.write().unwrap();
+                window.start_slot = start_slot;
+                window.leaders = leaders;
+            }
+            Err(e) => error!("Failed to fetch slot leaders from {}: {}", start_slot, e),
+        }
+    }
+}
+
+/// 根据即将到来的 leader 决定是否发送交易
+///
+/// leader 满足以下条件时认为可用：
+/// - 配置了 `allowed_leaders` 时必须在其中（例如只发给运行 Jito 客户端的验证者）
+/// - 不在 `blocked_leaders` 中（例如已知会丢弃交易或夹子的验证者）
+///
+/// 未来 `lookahead_slots` 个 slot 中只要有一个可用的 leader 就发送。
+#[derive(Debug, Clone)]
+pub struct LeaderFilter {
+    tracker: Arc<LeaderTracker>,
+    allowed: HashSet<Pubkey>,
+    blocked: HashSet<Pubkey>,
+    lookahead_slots: u64,
+}
+
+impl LeaderFilter {
+    pub fn new(config: &LeaderScheduleConfig, tracker: Arc<LeaderTracker>) -> anyhow::Result<Self> {
+        let parse = |keys: Option<&Vec<String>>| -> anyhow::Result<HashSet<Pubkey>> {
+            keys.into_iter()
+                .flatten()
+                .map(|key| Ok(Pubkey::from_str(key)?))
+                .collect()
+        };
+
+        let filter = Self {
+            tracker,
+            allowed: parse(config.allowed_leaders.as_ref())?,
+            blocked: parse(config.blocked_leaders.as_ref())?,
+            lookahead_slots: config.lookahead_slots(),
+        };
+        info!(
+            "Leader filter: {} allowed, {} blocked, looking {} slots ahead",
+            filter.allowed.len(),
+            filter.blocked.len(),
+            filter.lookahead_slots
+        );
+        Ok(filter)
+    }
+
+    pub fn is_good_leader(&self, leader: &Pubkey) -> bool {
+        (self.allowed.is_empty() || self.allowed.contains(leader)) && !self.blocked.contains(leader)
+    }
+
+    /// 是否应该在当前 slot 发送
+    ///
+    /// leader 列表还没有加载时直接放行，避免启动阶段完全不发送。
+    pub fn should_send(&self) -> bool {
+        let leaders = self.tracker.upcoming_leaders(self.lookahead_slots);
+        if leaders.is_empty() {
+            return true;
+        }
+        leaders.iter().any(|leader| self.is_good_leader(leader))
+    }
+}
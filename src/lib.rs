@@ -1,7 +1,90 @@
+//! Solana 链上套利机器人的核心库
+//!
+//! 二进制程序只负责解析命令行参数并调用 [`bot::run_bot`]，其余逻辑都在这里，
+//! 仪表盘、研究脚本或自定义策略可以直接依赖本库而不需要启动整个机器人：
+//!
+//! - [`Config`] 加载和校验配置文件
+//! - [`initialize_pool_data`] 从链上读取并解析每个 mint 的池子，得到 [`MintPoolData`]
+//! - [`build_transaction`] 为一组池子构建套利交易，[`build_and_send_transaction`] 构建并通过 [`Submitters`] 发送
+//! - [`dex`] 下各个 DEX 的账户解析器，例如 [`PumpAmmInfo`] 和 [`RaydiumAmmInfo`]
+//!
+//! 根目录的重新导出是稳定的对外接口，各模块内部的其他公开项可能随版本调整。
+
+pub mod account_pruning;
+pub mod app_state;
+pub mod ata;
+pub mod attempt_log;
+pub mod batch;
+pub mod blacklist;
 pub mod bot;
+pub mod broadcast;
+pub mod concentrated_liquidity;
 pub mod config;
 pub mod constants;
+pub mod cooldown;
+pub mod copy_route;
+pub mod dashboard;
+pub mod decode;
 pub mod dex;
+pub mod executor;
+pub mod export;
+pub mod fee_budget;
+pub mod fees;
+pub mod flashloan;
+#[cfg(feature = "yellowstone")]
+pub mod geyser;
+pub mod graduation;
+pub mod health;
+pub mod in_flight;
+pub mod inline_swap;
+pub mod inventory;
+pub mod jito;
+pub mod landing_model;
+pub mod layout;
+pub mod leader_schedule;
+pub mod local_sim;
+pub mod lookup_table;
+pub mod mint_safety;
+pub mod notifications;
+pub mod pool_cache;
+pub mod pool_owners;
+pub mod pool_watch;
 pub mod pools;
+pub mod price_feed;
+pub mod proxy;
+pub mod quote;
+pub mod rebalance;
 pub mod refresh;
+pub mod research;
+pub mod route_fees;
+pub mod rpc;
+pub mod schedule;
+pub mod state_dump;
+pub mod status;
+pub mod storage;
+pub mod submit;
+pub mod tpu;
 pub mod transaction;
+pub mod transaction_watch;
+pub mod transfer_fee;
+pub mod tui;
+pub mod tx_template;
+pub mod vaults;
+pub mod watchdog;
+pub mod wsol;
+
+pub use config::{Config, ConfigFormat, MintConfig, PoolEntry};
+pub use dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+pub use dex::meteora::dlmm_info::{DlmmInfo, LbPair};
+pub use dex::moonshot::MoonshotCurve;
+pub use dex::perena::PerenaPoolInfo;
+pub use dex::pump::PumpAmmInfo;
+pub use dex::raydium::{PoolState as RaydiumClmmPoolState, RaydiumAmmInfo, RaydiumCpAmmInfo};
+pub use dex::solfi::info::SolfiInfo;
+pub use dex::vertigo::info::VertigoInfo;
+pub use dex::whirlpool::state::Whirlpool;
+pub use dex::zerofi::ZeroFiInfo;
+pub use pools::{MintPoolData, PoolDirection};
+pub use refresh::initialize_pool_data;
+pub use submit::{Submission, SubmissionResult, Submitter, Submitters};
+pub use transaction::{build_and_send_transaction, build_transaction, SendOutcome, SendingClient};
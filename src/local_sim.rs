@@ -0,0 +1,417 @@
+//! 本地验证器模式：在 `solana-test-validator` 上端到端运行机器人
+//!
+//! `local-sim` 子命令先按 `--dump-state` 的流程从主网加载配置中的池子，收集交易需要的所有程序和账户
+//! （池子 swap 指令的账户、执行程序、查找表和闪电贷储备），生成 `solana-test-validator` 的克隆参数。
+//! `--print` 只打印命令；否则启动验证器，给钱包创建代币账户并包装 SOL，再以 dry-run 模式把机器人
+//! 指向本地 RPC，所有交易都只在克隆出的状态上模拟。
+
+use crate::ata::{self, TOKEN_2022_PROGRAM_ID};
+use crate::bot;
+use crate::config::{Config, CreateAtaPolicy};
+use crate::flashloan::solend_program_id;
+use crate::pools::MintPoolData;
+use crate::state_dump::{fetch_accounts, referenced_accounts, StateDump};
+use crate::transaction::{build_transaction, SwapPrograms};
+use crate::wsol::wrap_sol_instructions;
+use anyhow::Context;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::hash::Hash;
+use solana_sdk::native_loader;
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// 验证器可执行文件
+pub const VALIDATOR_BIN: &str = "solana-test-validator";
+
+/// 默认的账本目录，与 `scripts/local-validator.sh` 一致
+pub const DEFAULT_LEDGER_DIR: &str = "test-ledger";
+
+/// 默认的本地 RPC 端口
+pub const DEFAULT_RPC_PORT: u16 = 8899;
+
+/// 默认包装进钱包 WSOL 账户的 SOL
+pub const DEFAULT_WRAP_SOL: f64 = 10.0;
+
+/// 等待验证器 RPC 可用的最长时间，克隆账户较多时启动较慢
+const VALIDATOR_STARTUP_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// `solana-test-validator` 自带的 SPL 程序，不需要也不能再克隆
+const PRELOADED_PROGRAMS: [&str; 4] = [
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+    TOKEN_2022_PROGRAM_ID,
+    "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr",
+];
+
+/// `local-sim` 子命令的参数
+#[derive(Debug, Clone)]
+pub struct LocalSimOptions {
+    /// 验证器的账本目录，每次启动都会重置
+    pub ledger: PathBuf,
+    pub rpc_port: u16,
+    /// 配置之外需要额外克隆的账户
+    pub extra_accounts: Vec<Pubkey>,
+    /// 启动后包装进钱包 WSOL 账户的 SOL
+    pub wrap_sol: f64,
+    /// 只打印验证器命令，不启动验证器和机器人
+    pub print_only: bool,
+}
+
+impl Default for LocalSimOptions {
+    fn default() -> Self {
+        Self {
+            ledger: PathBuf::from(DEFAULT_LEDGER_DIR),
+            rpc_port: DEFAULT_RPC_PORT,
+            extra_accounts: Vec::new(),
+            wrap_sol: DEFAULT_WRAP_SOL,
+            print_only: false,
+        }
+    }
+}
+
+impl LocalSimOptions {
+    /// 本地验证器的 RPC 地址
+    pub fn rpc_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.rpc_port)
+    }
+}
+
+/// 需要从主网克隆的程序和账户
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClonePlan {
+    /// 可升级程序，通过 `--clone-upgradeable-program` 连同可执行数据一起克隆
+    pub upgradeable_programs: BTreeSet<Pubkey>,
+    /// 普通账户和旧加载器部署的程序，通过 `--clone` 克隆
+    pub accounts: BTreeSet<Pubkey>,
+    /// 主网上不存在的账户（例如还没创建的钱包 ATA），克隆会让验证器启动失败，由本地运行时创建
+    pub missing: BTreeSet<Pubkey>,
+}
+
+impl ClonePlan {
+    /// 按主网上的账户内容给需要的地址分类
+    ///
+    /// 钱包本身不克隆，通过 `--mint` 在创世时获得 SOL；原生程序、sysvar 和验证器自带的 SPL 程序跳过；
+    /// 可升级程序的可执行数据账户随程序一起克隆，不单独列出。
+    ///
+    /// # 参数
+    /// * `wanted` - 交易需要的所有地址
+    /// * `accounts` - 从主网读取的账户，不存在的地址不在其中
+    /// * `wallet` - 钱包地址
+    pub fn new(
+        wanted: impl IntoIterator<Item = Pubkey>,
+        accounts: &HashMap<Pubkey, Account>,
+        wallet: &Pubkey,
+    ) -> Self {
+        let preloaded: Vec<Pubkey> = PRELOADED_PROGRAMS
+            .iter()
+            .map(|program| Pubkey::from_str(program).unwrap())
+            .collect();
+        let mut plan = Self::default();
+        for pubkey in wanted {
+            if pubkey == *wallet || preloaded.contains(&pubkey) {
+                continue;
+            }
+            let Some(account) = accounts.get(&pubkey) else {
+                plan.missing.insert(pubkey);
+                continue;
+            };
+            if account.owner == native_loader::id() || account.owner == sysvar::id() {
+                continue;
+            }
+            if account.owner == bpf_loader_upgradeable::id() {
+                if account.executable {
+                    plan.upgradeable_programs.insert(pubkey);
+                }
+                continue;
+            }
+            plan.accounts.insert(pubkey);
+        }
+        plan
+    }
+
+    /// `solana-test-validator` 的参数，不包含可执行文件本身
+    ///
+    /// # 参数
+    /// * `mainnet_url` - 克隆账户使用的主网 RPC
+    /// * `wallet` - 在创世时获得 SOL 的钱包
+    /// * `options` - 账本目录和 RPC 端口
+    pub fn validator_args(
+        &self,
+        mainnet_url: &str,
+        wallet: &Pubkey,
+        options: &LocalSimOptions,
+    ) -> Vec<String> {
+        let mut args = vec![
+            "--reset".to_string(),
+            "--ledger".to_string(),
+            options.ledger.display().to_string(),
+            "--rpc-port".to_string(),
+            options.rpc_port.to_string(),
+            "--url".to_string(),
+            mainnet_url.to_string(),
+            "--mint".to_string(),
+            wallet.to_string(),
+        ];
+        for program in &self.upgradeable_programs {
+            args.push("--clone-upgradeable-program".to_string());
+            args.push(program.to_string());
+        }
+        for account in &self.accounts {
+            args.push("--clone".to_string());
+            args.push(account.to_string());
+        }
+        args
+    }
+}
+
+/// 配置的交易需要的所有地址
+///
+/// 按配置为每个代币编译一笔不使用查找表的交易，取其中的全部账户（包括执行程序、闪电贷等固定账户），
+/// 再加上池子报价读取的账户、所有执行程序和路由程序、配置的查找表以及 Solend 闪电贷储备。
+///
+/// # 参数
+/// * `config` - 机器人配置
+/// * `wallet` - 钱包密钥对，只用于编译交易，不签名发送
+/// * `mints` - 从主网加载完成的池子数据
+///
+/// # 错误
+/// 执行程序、查找表或闪电贷配置无法解析时返回错误
+pub fn required_accounts(
+    config: &Config,
+    wallet: &Keypair,
+    mints: &[MintPoolData],
+) -> anyhow::Result<BTreeSet<Pubkey>> {
+    let programs = SwapPrograms::from_config(config)?;
+    let mut accounts: BTreeSet<Pubkey> = mints.iter().flat_map(referenced_accounts).collect();
+    for pool_data in mints {
+        match build_transaction(wallet, config, &programs, pool_data, Hash::default(), &[]) {
+            Ok(tx) => accounts.extend(tx.message.static_account_keys().iter().copied()),
+            Err(e) => warn!(
+                "Failed to build a transaction for mint {}, cloning pool accounts only: {}",
+                pool_data.mint, e
+            ),
+        }
+    }
+
+    for executor in programs.executors.programs() {
+        accounts.insert(executor.program_id);
+        accounts.insert(executor.fee_collector);
+        if let Some(account) = executor
+            .version_check
+            .as_ref()
+            .and_then(|check| check.account)
+        {
+            accounts.insert(account);
+        }
+    }
+
+    accounts.extend(config.routing.global_lookup_tables()?);
+    for mint_config in &config.routing.mint_config_list {
+        accounts.extend(mint_config.lookup_tables()?);
+        accounts.extend(mint_config.own_lookup_table_pubkey()?);
+    }
+
+    if let Some(flash_loan) = programs.flash_loan {
+        let reserve = flash_loan.reserve;
+        accounts.extend([
+            *solend_program_id(),
+            reserve.reserve,
+            reserve.liquidity_supply,
+            reserve.fee_receiver,
+            reserve.lending_market,
+        ]);
+    }
+
+    Ok(accounts)
+}
+
+/// 在本地验证器上运行的配置：RPC 指向本地并强制 dry-run
+///
+/// 去掉所有会把交易或请求发往主网的设置：发送端点、TPU、Jito、geyser 订阅、代理和通知，
+/// 钱包 ATA 和 WSOL 由 `local-sim` 在启动机器人前创建，不再由机器人自动创建或补充。
+pub fn local_config(mut config: Config, rpc_url: &str) -> Config {
+    config.rpc.url = rpc_url.to_string();
+    config.rpc.proxy = None;
+    config.rpc.rate_limit = None;
+    config.bot.dry_run = Some(true);
+    config.bot.create_ata = CreateAtaPolicy::Never;
+    config.spam = None;
+    config.sending = None;
+    config.leader_schedule = None;
+    config.jito = None;
+    config.geyser = None;
+    config.notifications = None;
+    config.wsol_top_up = None;
+    config.inventory = None;
+    config.rebalance = None;
+    config
+}
+
+/// 打印可以直接复制运行的验证器命令
+fn print_command(args: &[String]) {
+    let mut command = VALIDATOR_BIN.to_string();
+    for pair in args.chunks(2) {
+        command.push_str(" \\\n  ");
+        command.push_str(&pair.join(" "));
+    }
+    println!("{}", command);
+}
+
+/// 等待验证器的 RPC 可用，验证器提前退出或超时时返回错误
+async fn wait_for_validator(
+    rpc_client: &RpcClient,
+    validator: &mut tokio::process::Child,
+    ledger: &Path,
+) -> anyhow::Result<()> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = validator.try_wait()? {
+            anyhow::bail!(
+                "{} exited with {}, see {}",
+                VALIDATOR_BIN,
+                status,
+                ledger.join("validator.log").display()
+            );
+        }
+        if rpc_client.get_health().is_ok() {
+            return Ok(());
+        }
+        if started.elapsed() > VALIDATOR_STARTUP_TIMEOUT {
+            anyhow::bail!(
+                "{} did not become healthy within {:?}, see {}",
+                VALIDATOR_BIN,
+                VALIDATOR_STARTUP_TIMEOUT,
+                ledger.join("validator.log").display()
+            );
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// 在本地验证器上为钱包创建所有代币和报价货币的 ATA，并包装 `wrap_sol` 个 SOL
+fn prepare_wallet(
+    rpc_client: &RpcClient,
+    wallet: &Keypair,
+    config: &Config,
+    wrap_sol: f64,
+) -> anyhow::Result<()> {
+    let mut mints = Vec::new();
+    for mint_config in &config.routing.mint_config_list {
+        let quote_mint = mint_config.quote_mint()?;
+        let quote_mint = (!quote_mint.is_sol()).then_some(quote_mint.mint);
+        for mint in std::iter::once(mint_config.mint_pubkey()?).chain(quote_mint) {
+            if !mints.contains(&mint) {
+                mints.push(mint);
+            }
+        }
+    }
+    for mint in &mints {
+        ata::ensure_token_account(
+            rpc_client,
+            wallet,
+            mint,
+            CreateAtaPolicy::Auto,
+            0,
+            config.bot.create_ata_attempts(),
+            false,
+        )?;
+    }
+
+    let lamports = sol_to_lamports(wrap_sol);
+    if lamports > 0 {
+        let tx = Transaction::new_signed_with_payer(
+            &wrap_sol_instructions(&wallet.pubkey(), lamports)?,
+            Some(&wallet.pubkey()),
+            &[wallet],
+            rpc_client.get_latest_blockhash()?,
+        );
+        rpc_client
+            .send_and_confirm_transaction(&tx)
+            .context("Failed to wrap SOL on the local validator")?;
+        info!("Wrapped {} SOL into the wallet's WSOL account", wrap_sol);
+    }
+    Ok(())
+}
+
+/// 运行 `local-sim`：从主网收集需要克隆的账户，启动验证器并以 dry-run 模式运行机器人
+///
+/// # 参数
+/// * `config` - 机器人配置，`rpc.url` 作为克隆账户的主网 RPC
+/// * `options` - 子命令参数
+/// * `use_pool_cache` - 从主网加载池子时是否读取池子元数据缓存
+/// * `tui` - 机器人是否显示终端监控界面
+///
+/// # 错误
+/// 加载池子、读取账户、启动验证器或准备钱包失败时返回错误
+pub async fn run(
+    config: Config,
+    options: &LocalSimOptions,
+    use_pool_cache: bool,
+    tui: bool,
+) -> anyhow::Result<()> {
+    let wallet =
+        bot::load_keypair(&config.wallet.private_key).context("Failed to load wallet keypair")?;
+    let mainnet_url = config.rpc.url.clone();
+
+    // 复用 --dump-state 的流程从主网加载池子，这个流程只读取链上状态
+    let state_dir = std::env::temp_dir().join(format!("local-sim-{}", std::process::id()));
+    bot::run_with_config(config.clone(), use_pool_cache, false, Some(&state_dir)).await?;
+    let dump = StateDump::load(&state_dir)?;
+    let _ = std::fs::remove_dir_all(&state_dir);
+
+    let mut wanted = required_accounts(&config, &wallet, &dump.mints)?;
+    wanted.extend(options.extra_accounts.iter().copied());
+    let wanted: Vec<Pubkey> = wanted.into_iter().collect();
+    let mainnet_client = RpcClient::new(mainnet_url.clone());
+    let accounts =
+        fetch_accounts(&mainnet_client, &wanted).context("Failed to read accounts to clone")?;
+    let plan = ClonePlan::new(wanted, &accounts, &wallet.pubkey());
+    info!(
+        "Cloning {} program(s) and {} account(s) for {} mint(s)",
+        plan.upgradeable_programs.len(),
+        plan.accounts.len(),
+        dump.mints.len()
+    );
+    for pubkey in &plan.missing {
+        warn!(
+            "   Account {} does not exist on mainnet, not cloned",
+            pubkey
+        );
+    }
+
+    let args = plan.validator_args(&mainnet_url, &wallet.pubkey(), options);
+    if options.print_only {
+        print_command(&args);
+        return Ok(());
+    }
+
+    let mut validator = tokio::process::Command::new(VALIDATOR_BIN)
+        .args(&args)
+        .arg("--quiet")
+        .stdout(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .with_context(|| format!("Failed to start {}", VALIDATOR_BIN))?;
+    let rpc_url = options.rpc_url();
+    let local_client = RpcClient::new(rpc_url.clone());
+    info!("Waiting for {} at {}", VALIDATOR_BIN, rpc_url);
+    wait_for_validator(&local_client, &mut validator, &options.ledger).await?;
+    prepare_wallet(&local_client, &wallet, &config, options.wrap_sol)?;
+
+    info!("Running the bot in dry-run mode against {}", rpc_url);
+    let result =
+        bot::run_with_config(local_config(config, &rpc_url), use_pool_cache, tui, None).await;
+    let _ = validator.kill().await;
+    result
+}
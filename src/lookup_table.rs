@@ -0,0 +1,67 @@
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::instruction::extend_lookup_table;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use tracing::info;
+
+/// 每笔 extend 交易加入的地址数，保证交易不超过大小上限
+const EXTEND_BATCH_SIZE: usize = 20;
+
+/// 一个查找表最多容纳的地址数
+pub const LOOKUP_TABLE_MAX_ADDRESSES: usize = 256;
+
+/// 读取并解析查找表
+pub fn fetch_lookup_table(
+    rpc_client: &RpcClient,
+    address: &Pubkey,
+) -> anyhow::Result<AddressLookupTableAccount> {
+    let account = rpc_client.get_account(address)?;
+    let lookup_table = AddressLookupTable::deserialize(&account.data)?;
+    Ok(AddressLookupTableAccount {
+        key: *address,
+        addresses: lookup_table.addresses.into_owned(),
+    })
+}
+
+/// 把地址加入钱包自己的查找表
+///
+/// 每 `EXTEND_BATCH_SIZE` 个地址发送一笔交易并等待确认，钱包必须是查找表的 authority。
+/// 查找表放不下全部地址时只加入放得下的部分。新加入的地址要到下一个 slot 才能在交易中使用。
+///
+/// # 返回值
+/// 实际加入的地址数
+pub fn extend_own_lookup_table(
+    rpc_client: &RpcClient,
+    wallet_kp: &Keypair,
+    lookup_table: &AddressLookupTableAccount,
+    addresses: &[Pubkey],
+) -> anyhow::Result<usize> {
+    let room = LOOKUP_TABLE_MAX_ADDRESSES.saturating_sub(lookup_table.addresses.len());
+    let addresses = &addresses[..addresses.len().min(room)];
+    for batch in addresses.chunks(EXTEND_BATCH_SIZE) {
+        let extend_ix = extend_lookup_table(
+            lookup_table.key,
+            wallet_kp.pubkey(),
+            Some(wallet_kp.pubkey()),
+            batch.to_vec(),
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[extend_ix],
+            Some(&wallet_kp.pubkey()),
+            &[wallet_kp],
+            rpc_client.get_latest_blockhash()?,
+        );
+        let signature = rpc_client.send_and_confirm_transaction(&tx)?;
+        info!(
+            "Added {} addresses to lookup table {}: {}",
+            batch.len(),
+            lookup_table.key,
+            signature
+        );
+    }
+    Ok(addresses.len())
+}
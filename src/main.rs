@@ -1,17 +1,174 @@
-mod bot;
-mod config;
-mod constants;
-mod dex;
-mod pools;
-mod refresh;
-mod transaction;
-
 use clap::{App, Arg};
+use solana_client::rpc_client::RpcClient;
+use solana_onchain_arbitrage_bot::{bot, config, decode, local_sim, status, storage};
+use solana_sdk::pubkey::Pubkey;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use tracing::{info, Level};
-use tracing_subscriber::FmtSubscriber;
+use tracing_subscriber::{EnvFilter, FmtSubscriber};
+
+/// TUI 模式下的日志文件
+const TUI_LOG_FILE: &str = "bot.log";
+
+/// `--verbose` 的日志过滤：池子加载器的逐字段日志为 debug，其余模块仍为 info
+const VERBOSE_LOG_FILTER: &str = "info,solana_onchain_arbitrage_bot::dex=debug";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // 解析命令行参数，配置应用程序的基本信息和参数选项
+    // 该函数创建一个命令行应用实例，设置应用名称、版本、作者和描述信息
+    // 并定义一个可选的配置文件参数，支持短参数-c和长参数--config
+    // 参数说明：
+    //   config: 可选的配置文件路径，默认值为"config.toml"
+    // 返回值：解析后的命令行参数匹配结果
+    let matches = App::new("Solana Onchain Arbitrage Bot")
+        .version("0.1.0")
+        .author("Cetipo")
+        .about("A simplified Solana onchain arbitrage bot")
+        .arg(
+            Arg::with_name("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Sets a custom config file")
+                .takes_value(true)
+                .default_value("config.toml"),
+        )
+        .arg(
+            Arg::with_name("config-format")
+                .long("config-format")
+                .value_name("FORMAT")
+                .help("Sets the config format (toml, yaml, json), defaults to the file extension")
+                .takes_value(true)
+                .possible_values(["toml", "yaml", "json"]),
+        )
+        .arg(
+            Arg::with_name("tui")
+                .long("tui")
+                .help("Shows a live terminal monitor instead of log output, logs go to bot.log"),
+        )
+        .arg(
+            Arg::with_name("no-cache")
+                .long("no-cache")
+                .help("Ignores the pool metadata cache, refetches every pool and rewrites the cache"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .short('v')
+                .long("verbose")
+                .help("Logs every field of each loaded pool instead of one summary table per mint"),
+        )
+        .arg(
+            Arg::with_name("dump-state")
+                .long("dump-state")
+                .value_name("DIR")
+                .help("Loads every pool, writes the resolved pools and the raw bytes of every account they reference to DIR, then exits")
+                .takes_value(true),
+        )
+        .subcommand(
+            App::new("history")
+                .about("Queries the trade history database")
+                .arg(
+                    Arg::with_name("db")
+                        .long("db")
+                        .value_name("FILE")
+                        .help("Sets the database path, defaults to storage.database_path in the config")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("mint")
+                        .long("mint")
+                        .value_name("MINT")
+                        .help("Only shows trades for this mint")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("outcome")
+                        .long("outcome")
+                        .value_name("OUTCOME")
+                        .help("Only shows trades with this outcome")
+                        .takes_value(true)
+                        .possible_values(["sent", "landed", "not_landed", "send_failed", "simulated"]),
+                )
+                .arg(
+                    Arg::with_name("since-hours")
+                        .long("since-hours")
+                        .value_name("HOURS")
+                        .help("Only shows trades from the last N hours")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .short('n')
+                        .long("limit")
+                        .value_name("N")
+                        .help("Maximum number of trades to show")
+                        .takes_value(true)
+                        .default_value("20"),
+                ),
+        )
+        .subcommand(
+            App::new("decode")
+                .about("Fetches an account and prints it parsed with the matching DEX layout")
+                .arg(
+                    Arg::with_name("pubkey")
+                        .value_name("PUBKEY")
+                        .help("Account to decode")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::with_name("rpc-url")
+                        .long("rpc-url")
+                        .value_name("URL")
+                        .help("Sets the RPC URL, defaults to rpc.url in the config")
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            App::new("local-sim")
+                .about("Clones the programs and accounts the config needs into solana-test-validator and runs the bot against it in dry-run mode")
+                .arg(
+                    Arg::with_name("print")
+                        .long("print")
+                        .help("Only prints the solana-test-validator command"),
+                )
+                .arg(
+                    Arg::with_name("ledger")
+                        .long("ledger")
+                        .value_name("DIR")
+                        .help("Sets the validator ledger directory, reset on every start")
+                        .takes_value(true)
+                        .default_value(local_sim::DEFAULT_LEDGER_DIR),
+                )
+                .arg(
+                    Arg::with_name("rpc-port")
+                        .long("rpc-port")
+                        .value_name("PORT")
+                        .help("Sets the local validator RPC port")
+                        .takes_value(true)
+                        .default_value("8899"),
+                )
+                .arg(
+                    Arg::with_name("clone")
+                        .long("clone")
+                        .value_name("PUBKEY")
+                        .help("Clones an extra account the config does not reference")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("wrap-sol")
+                        .long("wrap-sol")
+                        .value_name("SOL")
+                        .help("Sets the SOL wrapped into the wallet's WSOL account before the bot starts")
+                        .takes_value(true)
+                        .default_value("10"),
+                ),
+        )
+        .get_matches();
+
     /// 构建一个格式化日志订阅器
     ///
     /// 该函数创建一个FmtSubscriber实例，用于格式化和输出日志信息。
@@ -26,12 +183,17 @@ async fn main() -> anyhow::Result<()> {
     /// # 示例
     /// ```
     /// let subscriber = FmtSubscriber::builder()
-    ///         .with_max_level(Level::INFO)
+    ///         .with_env_filter(EnvFilter::new("info"))
     ///         .finish();
     /// ```
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
+    ///
+    /// `--verbose` 时池子加载器的逐字段日志（debug 级别）也会输出
+    let filter = if matches.is_present("verbose") {
+        EnvFilter::new(VERBOSE_LOG_FILTER)
+    } else {
+        EnvFilter::new(Level::INFO.to_string())
+    };
+    let subscriber = FmtSubscriber::builder().with_env_filter(filter);
 
     // 设置全局默认的 tracing 订阅者
     //
@@ -43,39 +205,103 @@ async fn main() -> anyhow::Result<()> {
     //
     // # Panics
     // 当设置全局默认订阅者失败时，程序会 panic 并输出错误信息
-    tracing::subscriber::set_global_default(subscriber)
+    //
+    // TUI 模式下终端由界面占用，日志改为写入文件
+    let tui = matches.is_present("tui");
+    if tui {
+        let log_file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(TUI_LOG_FILE)?;
+        tracing::subscriber::set_global_default(
+            subscriber
+                .with_ansi(false)
+                .with_writer(std::sync::Mutex::new(log_file))
+                .finish(),
+        )
         .expect("Failed to set global default subscriber");
+    } else {
+        tracing::subscriber::set_global_default(subscriber.finish())
+            .expect("Failed to set global default subscriber");
+    }
 
     info!("Starting Solana Onchain Bot");
 
-    // 解析命令行参数，配置应用程序的基本信息和参数选项
-    // 该函数创建一个命令行应用实例，设置应用名称、版本、作者和描述信息
-    // 并定义一个可选的配置文件参数，支持短参数-c和长参数--config
-    // 参数说明：
-    //   config: 可选的配置文件路径，默认值为"config.toml"
-    // 返回值：解析后的命令行参数匹配结果
-    let matches = App::new("Solana Onchain Arbitrage Bot")
-        .version("0.1.0")
-        .author("Cetipo")
-        .about("A simplified Solana onchain arbitrage bot")
-        .arg(
-            Arg::with_name("config")
-                .short('c')
-                .long("config")
-                .value_name("FILE")
-                .help("Sets a custom config file")
-                .takes_value(true)
-                .default_value("config.toml"),
-        )
-        .get_matches();
-
     // 获取配置文件路径参数
     let config_path = matches.value_of("config").unwrap();
     // 记录使用的配置文件路径
     info!("Using config file: {}", config_path);
 
+    // 显式指定的配置格式，适用于通过标准输入（`-c -`）传入的配置
+    let config_format = matches
+        .value_of("config-format")
+        .map(|format| format.parse::<config::ConfigFormat>())
+        .transpose()?;
+
+    // history 子命令：查询交易历史后退出
+    if let Some(history) = matches.subcommand_matches("history") {
+        let database_path = match history.value_of("db") {
+            Some(path) => path.to_string(),
+            None => config::Config::load_with_format(config_path, config_format)?
+                .storage
+                .map(|storage| storage.database_path())
+                .ok_or_else(|| anyhow::anyhow!("No [storage] section in config, pass --db"))?,
+        };
+        let query = storage::HistoryQuery {
+            mint: history.value_of("mint").map(str::to_string),
+            outcome: history.value_of("outcome").map(str::to_string),
+            since: history
+                .value_of("since-hours")
+                .map(|hours| hours.parse::<u64>())
+                .transpose()?
+                .map(|hours| status::unix_now().saturating_sub(hours * 3_600)),
+            limit: history.value_of("limit").unwrap().parse()?,
+        };
+        let store = storage::TradeStore::open(&database_path)?;
+        return storage::print_history(&store, &query);
+    }
+
+    // decode 子命令：按 DEX 布局解析一个账户后退出
+    if let Some(decode) = matches.subcommand_matches("decode") {
+        let pubkey = decode.value_of("pubkey").unwrap().parse::<Pubkey>()?;
+        let rpc_url = match decode.value_of("rpc-url") {
+            Some(url) => url.to_string(),
+            None => {
+                config::Config::load_with_format(config_path, config_format)?
+                    .rpc
+                    .url
+            }
+        };
+        return decode::print_account(&RpcClient::new(rpc_url), &pubkey);
+    }
+
+    // local-sim 子命令：克隆主网账户到本地验证器，以 dry-run 模式运行机器人
+    if let Some(local) = matches.subcommand_matches("local-sim") {
+        let options = local_sim::LocalSimOptions {
+            ledger: PathBuf::from(local.value_of("ledger").unwrap()),
+            rpc_port: local.value_of("rpc-port").unwrap().parse()?,
+            extra_accounts: local
+                .values_of("clone")
+                .into_iter()
+                .flatten()
+                .map(Pubkey::from_str)
+                .collect::<Result<_, _>>()?,
+            wrap_sol: local.value_of("wrap-sol").unwrap().parse()?,
+            print_only: local.is_present("print"),
+        };
+        let config = config::Config::load_with_format(config_path, config_format)?;
+        return local_sim::run(config, &options, !matches.is_present("no-cache"), tui).await;
+    }
+
     // 启动机器人服务
-    bot::run_bot(config_path).await?;
+    bot::run_bot(
+        config_path,
+        config_format,
+        !matches.is_present("no-cache"),
+        tui,
+        matches.value_of("dump-state").map(Path::new),
+    )
+    .await?;
 
     Ok(())
 }
@@ -0,0 +1,101 @@
+use crate::transfer_fee::mint_extensions;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+
+/// SPL Token mint 中 `freeze_authority` 的偏移：mint_authority (36) + supply (8) + decimals (1) + is_initialized (1)
+const FREEZE_AUTHORITY_OFFSET: usize = 46;
+
+/// `ExtensionType::DefaultAccountState`
+const DEFAULT_ACCOUNT_STATE_EXTENSION: u16 = 6;
+/// `ExtensionType::NonTransferable`
+const NON_TRANSFERABLE_EXTENSION: u16 = 9;
+/// `ExtensionType::PermanentDelegate`
+const PERMANENT_DELEGATE_EXTENSION: u16 = 12;
+/// `ExtensionType::TransferHook`
+const TRANSFER_HOOK_EXTENSION: u16 = 14;
+/// `ExtensionType::Pausable`
+const PAUSABLE_EXTENSION: u16 = 26;
+
+/// `AccountState::Frozen`
+const ACCOUNT_STATE_FROZEN: u8 = 2;
+
+/// 可能让钱包中的代币被冻结、转走或无法卖出的 mint 设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MintRisk {
+    /// 冻结权限可以冻结钱包的代币账户
+    FreezeAuthority(Pubkey),
+    /// 永久委托人可以转走或销毁任何账户中的代币
+    PermanentDelegate(Pubkey),
+    /// 新建的代币账户默认是冻结状态
+    DefaultFrozen,
+    /// 每次转账都会调用的转账钩子程序
+    TransferHook(Pubkey),
+    /// 代币不能转账
+    NonTransferable,
+    /// 暂停权限可以暂停所有转账
+    Pausable(Pubkey),
+}
+
+impl fmt::Display for MintRisk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MintRisk::FreezeAuthority(authority) => write!(f, "freeze authority {}", authority),
+            MintRisk::PermanentDelegate(delegate) => write!(f, "permanent delegate {}", delegate),
+            MintRisk::DefaultFrozen => write!(f, "new token accounts start frozen"),
+            MintRisk::TransferHook(program) => write!(f, "transfer hook program {}", program),
+            MintRisk::NonTransferable => write!(f, "non-transferable"),
+            MintRisk::Pausable(authority) => write!(f, "pause authority {}", authority),
+        }
+    }
+}
+
+/// 读取 `offset` 处的 `COption<Pubkey>`：4 字节标记，1 表示存在，后面是 32 字节地址
+fn coption_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let tag = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?);
+    (tag == 1).then(|| Pubkey::try_from(data.get(offset + 4..offset + 36)?).ok())?
+}
+
+/// 读取 `OptionalNonZeroPubkey`：全零表示没有设置
+fn nonzero_pubkey(data: &[u8]) -> Option<Pubkey> {
+    Pubkey::try_from(data.get(..32)?)
+        .ok()
+        .filter(|pubkey| *pubkey != Pubkey::default())
+}
+
+/// SPL Token mint 账户的冻结权限，Token 2022 mint 的基础部分布局相同
+pub fn freeze_authority(mint_data: &[u8]) -> Option<Pubkey> {
+    coption_pubkey(mint_data, FREEZE_AUTHORITY_OFFSET)
+}
+
+/// 检查 mint 账户的冻结权限和 Token 2022 扩展
+///
+/// # 参数
+/// * `mint_data` - SPL Token 或 Token 2022 mint 账户的数据
+///
+/// # 返回值
+/// mint 的所有风险设置，没有时为空；已清空的权限、委托人和钩子程序不算风险
+pub fn mint_risks(mint_data: &[u8]) -> Vec<MintRisk> {
+    let mut risks: Vec<MintRisk> = freeze_authority(mint_data)
+        .map(MintRisk::FreezeAuthority)
+        .into_iter()
+        .collect();
+    for (extension_type, value) in mint_extensions(mint_data) {
+        let risk = match extension_type {
+            DEFAULT_ACCOUNT_STATE_EXTENSION => {
+                (value.first() == Some(&ACCOUNT_STATE_FROZEN)).then_some(MintRisk::DefaultFrozen)
+            }
+            NON_TRANSFERABLE_EXTENSION => Some(MintRisk::NonTransferable),
+            PERMANENT_DELEGATE_EXTENSION => nonzero_pubkey(value).map(MintRisk::PermanentDelegate),
+            // authority (32) + program_id (32)
+            TRANSFER_HOOK_EXTENSION => value
+                .get(32..)
+                .and_then(nonzero_pubkey)
+                .map(MintRisk::TransferHook),
+            // authority (32) + paused (1)
+            PAUSABLE_EXTENSION => nonzero_pubkey(value).map(MintRisk::Pausable),
+            _ => None,
+        };
+        risks.extend(risk);
+    }
+    risks
+}
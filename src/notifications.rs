@@ -0,0 +1,316 @@
+use crate::config::NotificationConfig;
+use crate::price_feed::SolPriceFeed;
+use solana_sdk::native_token::{lamports_to_sol, LAMPORTS_PER_SOL};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error};
+
+/// 告警事件类型，对应 `[notifications]` 中的开关
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    StartStop,
+    Landed,
+    RepeatedFailures,
+    LowBalance,
+    RpcOutage,
+    PoolEvicted,
+    ProfitAudit,
+    StaleData,
+}
+
+/// 需要推送的告警事件
+#[derive(Debug, Clone)]
+pub enum Alert {
+    Started {
+        wallet: Pubkey,
+        mint_count: usize,
+    },
+    Stopped {
+        reason: String,
+    },
+    Landed {
+        mint: String,
+        signature: Signature,
+        profit_lamports: i64,
+    },
+    RepeatedFailures {
+        mint: String,
+        count: u32,
+        last_error: String,
+    },
+    Cooldown {
+        mint: String,
+        failures: u32,
+        delay: Duration,
+    },
+    LowBalance {
+        wallet: Pubkey,
+        balance_lamports: u64,
+        threshold_lamports: u64,
+    },
+    RpcOutage {
+        url: String,
+        error: String,
+    },
+    RpcRecovered {
+        url: String,
+    },
+    PoolEvicted {
+        mint: String,
+        dex: &'static str,
+        pool: Pubkey,
+        reason: String,
+    },
+    /// 上链交易的报价货币余额减少
+    RouteLoss {
+        mint: String,
+        signature: Signature,
+        /// 报价货币符号
+        symbol: &'static str,
+        /// 报价收益和余额变化，以报价货币计
+        expected: Option<f64>,
+        actual: f64,
+    },
+    /// 数据过期，暂停发送
+    StaleData {
+        /// 暂停的代币，或 `all mints`
+        scope: String,
+        reason: String,
+    },
+    StaleDataRecovered {
+        scope: String,
+    },
+}
+
+impl Alert {
+    pub fn kind(&self) -> AlertKind {
+        match self {
+            Alert::Started { .. } | Alert::Stopped { .. } => AlertKind::StartStop,
+            Alert::Landed { .. } => AlertKind::Landed,
+            Alert::RepeatedFailures { .. } | Alert::Cooldown { .. } => AlertKind::RepeatedFailures,
+            Alert::LowBalance { .. } => AlertKind::LowBalance,
+            Alert::RpcOutage { .. } | Alert::RpcRecovered { .. } => AlertKind::RpcOutage,
+            Alert::PoolEvicted { .. } => AlertKind::PoolEvicted,
+            Alert::RouteLoss { .. } => AlertKind::ProfitAudit,
+            Alert::StaleData { .. } | Alert::StaleDataRecovered { .. } => AlertKind::StaleData,
+        }
+    }
+
+    /// 告警中的 SOL 金额（lamports），配置了价格来源时附带美元金额
+    pub fn lamports(&self) -> Option<i64> {
+        match self {
+            Alert::Landed {
+                profit_lamports, ..
+            } => Some(*profit_lamports),
+            Alert::LowBalance {
+                balance_lamports, ..
+            } => Some(*balance_lamports as i64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Alert {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Alert::Started { wallet, mint_count } => write!(
+                f,
+                "🟢 Arbitrage bot started (wallet {}, {} mint(s))",
+                wallet, mint_count
+            ),
+            Alert::Stopped { reason } => write!(f, "🔴 Arbitrage bot stopped: {}", reason),
+            Alert::Landed {
+                mint,
+                signature,
+                profit_lamports,
+            } => write!(
+                f,
+                "💰 Arbitrage landed for {}: {:+.9} SOL\nhttps://solscan.io/tx/{}",
+                mint,
+                *profit_lamports as f64 / LAMPORTS_PER_SOL as f64,
+                signature
+            ),
+            Alert::RepeatedFailures {
+                mint,
+                count,
+                last_error,
+            } => write!(
+                f,
+                "⚠️ {} consecutive failures for {}, last error: {}",
+                count, mint, last_error
+            ),
+            Alert::Cooldown {
+                mint,
+                failures,
+                delay,
+            } => write!(
+                f,
+                "🧊 {} consecutive failed or expired transactions for {}, backing off (next wait {:?})",
+                failures, mint, delay
+            ),
+            Alert::LowBalance {
+                wallet,
+                balance_lamports,
+                threshold_lamports,
+            } => write!(
+                f,
+                "🪫 Wallet {} balance {} SOL is below {} SOL",
+                wallet,
+                lamports_to_sol(*balance_lamports),
+                lamports_to_sol(*threshold_lamports)
+            ),
+            Alert::RpcOutage { url, error } => {
+                write!(f, "📡 RPC {} is unreachable: {}", url, error)
+            }
+            Alert::RpcRecovered { url } => write!(f, "📡 RPC {} recovered", url),
+            Alert::PoolEvicted {
+                mint,
+                dex,
+                pool,
+                reason,
+            } => write!(
+                f,
+                "🚫 Removed {} pool {} from {}: {}",
+                dex, pool, mint, reason
+            ),
+            Alert::RouteLoss {
+                mint,
+                signature,
+                symbol,
+                expected,
+                actual,
+            } => {
+                write!(f, "🚨 Landed route for {} lost {} {}", mint, -actual, symbol)?;
+                if let Some(expected) = expected {
+                    write!(f, " (quoted profit {} {})", expected, symbol)?;
+                }
+                write!(f, ", check the quoting\nhttps://solscan.io/tx/{}", signature)
+            }
+            Alert::StaleData { scope, reason } => {
+                write!(f, "⏸️ Paused sending for {}: {}", scope, reason)
+            }
+            Alert::StaleDataRecovered { scope } => {
+                write!(f, "▶️ Resumed sending for {}: data is fresh again", scope)
+            }
+        }
+    }
+}
+
+/// 告警推送器，支持 Discord webhook 和 Telegram bot API
+///
+/// 推送在后台任务中完成，失败只记录日志，不会影响机器人主流程。
+#[derive(Clone)]
+pub struct Notifier {
+    client: reqwest::Client,
+    config: Option<Arc<NotificationConfig>>,
+    price_feed: Option<Arc<SolPriceFeed>>,
+}
+
+impl Notifier {
+    /// 根据配置创建推送器，未配置 `[notifications]` 时所有告警都会被忽略
+    pub fn new(config: Option<&NotificationConfig>) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            config: config.cloned().map(Arc::new),
+            price_feed: None,
+        }
+    }
+
+    /// 使用 SOL/USD 价格在收益和余额告警中附带美元金额
+    pub fn with_price_feed(mut self, price_feed: Option<Arc<SolPriceFeed>>) -> Self {
+        self.price_feed = price_feed;
+        self
+    }
+
+    /// 告警的推送文本
+    pub fn text(&self, alert: &Alert) -> String {
+        let usd = alert.lamports().and_then(|lamports| {
+            self.price_feed
+                .as_ref()
+                .and_then(|price_feed| price_feed.usd(lamports))
+        });
+        let text = alert.to_string();
+        let Some(usd) = usd else {
+            return text;
+        };
+        // 美元金额放在第一行的 SOL 金额后面，链接等附加信息保持在后面的行
+        match text.split_once('\n') {
+            Some((first, rest)) => format!("{} (≈ ${:.2})\n{}", first, usd, rest),
+            None => format!("{} (≈ ${:.2})", text, usd),
+        }
+    }
+
+    /// 是否会推送指定类型的告警
+    pub fn is_enabled(&self, kind: AlertKind) -> bool {
+        self.config
+            .as_ref()
+            .is_some_and(|config| config.has_channel() && config.is_enabled(kind))
+    }
+
+    /// 在后台推送告警
+    pub fn notify(&self, alert: Alert) {
+        if !self.is_enabled(alert.kind()) {
+            return;
+        }
+
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            notifier.send(&alert).await;
+        });
+    }
+
+    /// 推送告警并等待完成，用于进程退出前的最后一条消息
+    pub async fn notify_and_wait(&self, alert: Alert) {
+        if self.is_enabled(alert.kind()) {
+            self.send(&alert).await;
+        }
+    }
+
+    async fn send(&self, alert: &Alert) {
+        let Some(config) = &self.config else {
+            return;
+        };
+        let text = self.text(alert);
+        debug!("Sending notification: {}", text);
+
+        if let Some(url) = &config.discord_webhook_url {
+            let body = serde_json::json!({ "content": text });
+            if let Err(e) = self.post(url, &body).await {
+                error!("Failed to send Discord notification: {}", e);
+            }
+        }
+
+        if let (Some(token), Some(chat_id)) = (&config.telegram_bot_token, &config.telegram_chat_id)
+        {
+            let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
+            let body = serde_json::json!({
+                "chat_id": chat_id,
+                "text": text,
+                "disable_web_page_preview": true,
+            });
+            if let Err(e) = self.post(&url, &body).await {
+                error!("Failed to send Telegram notification: {}", e);
+            }
+        }
+    }
+
+    async fn post(&self, url: &str, body: &serde_json::Value) -> anyhow::Result<()> {
+        // 去掉错误中的 URL，避免 Telegram token 出现在日志里
+        self.client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.without_url())?;
+        Ok(())
+    }
+}
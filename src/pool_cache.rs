@@ -0,0 +1,227 @@
+use crate::fees::PoolFee;
+use crate::pools::*;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// 缓存文件格式版本，池子结构体字段变化时需要递增，旧缓存会被丢弃
+const CACHE_VERSION: u32 = 5;
+
+/// 可以缓存的池子元数据
+///
+/// 只包含不随价格变化的账户（vault、amm_config、oracle、手续费钱包等）。
+/// DLMM 的 bin array、Whirlpool 和 Raydium CLMM 的 tick array 取决于当前价格，
+/// 每次启动都重新获取，不进入缓存。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CachedPool {
+    Raydium(RaydiumPool),
+    RaydiumCp(RaydiumCpPool),
+    Pump(PumpPool),
+    MeteoraDAmm(MeteoraDAmmPool),
+    Solfi(SolfiPool),
+    MeteoraDAmmV2(MeteoraDAmmV2Pool),
+    Vertigo(VertigoPool),
+    Moonshot(MoonshotPool),
+    Perena(PerenaPool),
+    ZeroFi(ZeroFiPool),
+}
+
+impl CachedPool {
+    /// 将缓存的池子加入 `pool_data`
+    pub fn add_to(self, pool_data: &mut MintPoolData) {
+        match self {
+            CachedPool::Raydium(pool) => pool_data.raydium_pools.push(pool),
+            CachedPool::RaydiumCp(pool) => pool_data.raydium_cp_pools.push(pool),
+            CachedPool::Pump(pool) => pool_data.pump_pools.push(pool),
+            CachedPool::MeteoraDAmm(pool) => pool_data.meteora_damm_pools.push(pool),
+            CachedPool::Solfi(pool) => pool_data.solfi_pools.push(pool),
+            CachedPool::MeteoraDAmmV2(pool) => pool_data.meteora_damm_v2_pools.push(pool),
+            CachedPool::Vertigo(pool) => pool_data.vertigo_pools.push(pool),
+            CachedPool::Moonshot(pool) => pool_data.moonshot_pools.push(pool),
+            CachedPool::Perena(pool) => pool_data.perena_pools.push(pool),
+            CachedPool::ZeroFi(pool) => pool_data.zerofi_pools.push(pool),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 池子所属的代币，换到其他代币下配置时缓存失效
+    mint: Pubkey,
+    pool: CachedPool,
+    /// 加载时读取到的手续费，动态手续费部分只反映写入缓存时的状态
+    fee: Option<PoolFee>,
+}
+
+/// 池子元数据的磁盘缓存，以池子地址为键，使用 bincode 序列化
+///
+/// 重启时命中缓存的池子不需要再请求链上账户。
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PoolCache {
+    version: u32,
+    /// mint -> 代币程序（Token 或 Token 2022）
+    token_programs: HashMap<Pubkey, Pubkey>,
+    pools: HashMap<Pubkey, CacheEntry>,
+}
+
+impl PoolCache {
+    /// 读取缓存文件，文件不存在、损坏或版本不匹配时返回空缓存
+    pub fn load<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let bytes = match std::fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::empty(),
+            Err(e) => {
+                warn!("Failed to read pool cache {}: {}", path.display(), e);
+                return Self::empty();
+            }
+        };
+
+        match bincode::deserialize::<PoolCache>(&bytes) {
+            Ok(cache) if cache.version == CACHE_VERSION => {
+                info!(
+                    "Loaded {} cached pools from {}",
+                    cache.pools.len(),
+                    path.display()
+                );
+                cache
+            }
+            Ok(cache) => {
+                warn!(
+                    "Ignoring pool cache {} with version {}, expected {}",
+                    path.display(),
+                    cache.version,
+                    CACHE_VERSION
+                );
+                Self::empty()
+            }
+            Err(e) => {
+                warn!("Ignoring unreadable pool cache {}: {}", path.display(), e);
+                Self::empty()
+            }
+        }
+    }
+
+    /// 空缓存
+    pub fn empty() -> Self {
+        Self {
+            version: CACHE_VERSION,
+            ..Default::default()
+        }
+    }
+
+    /// 写入缓存文件，先写临时文件再重命名，避免中途退出留下半个文件
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bincode::serialize(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+
+    /// 缓存的代币程序
+    pub fn token_program(&self, mint: &Pubkey) -> Option<Pubkey> {
+        self.token_programs.get(mint).copied()
+    }
+
+    /// 查找属于 `mint` 的缓存池子
+    pub fn get(&self, mint: &Pubkey, pool: &Pubkey) -> Option<&CachedPool> {
+        self.pools
+            .get(pool)
+            .filter(|entry| entry.mint == *mint)
+            .map(|entry| &entry.pool)
+    }
+
+    /// 缓存的池子手续费
+    pub fn fee(&self, mint: &Pubkey, pool: &Pubkey) -> Option<PoolFee> {
+        self.pools
+            .get(pool)
+            .filter(|entry| entry.mint == *mint)
+            .and_then(|entry| entry.fee)
+    }
+
+    /// 记录某个代币已解析的静态池子元数据
+    ///
+    /// 两侧都不是报价货币的池子不缓存，每次启动都经过加载器按 `routing.non_quote_pools` 重新检查
+    pub fn insert_mint_pools(&mut self, pool_data: &MintPoolData) {
+        let mint = pool_data.mint;
+        self.token_programs.insert(mint, pool_data.token_program);
+
+        let pools = pool_data
+            .raydium_pools
+            .iter()
+            .map(|p| (p.pool, CachedPool::Raydium(p.clone())))
+            .chain(
+                pool_data
+                    .raydium_cp_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::RaydiumCp(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .pump_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::Pump(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .meteora_damm_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::MeteoraDAmm(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .solfi_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::Solfi(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .meteora_damm_v2_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::MeteoraDAmmV2(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .vertigo_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::Vertigo(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .moonshot_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::Moonshot(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .perena_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::Perena(p.clone()))),
+            )
+            .chain(
+                pool_data
+                    .zerofi_pools
+                    .iter()
+                    .map(|p| (p.pool, CachedPool::ZeroFi(p.clone()))),
+            );
+
+        for (address, pool) in pools {
+            if pool_data.non_quote_pools.contains_key(&address) {
+                continue;
+            }
+            let fee = pool_data.pool_fees.get(&address).copied();
+            self.pools.insert(address, CacheEntry { mint, pool, fee });
+        }
+    }
+}
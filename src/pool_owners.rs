@@ -0,0 +1,146 @@
+use crate::dex::pool_program_id;
+use crate::notifications::{Alert, Notifier};
+use crate::pools::MintPoolData;
+use crate::quote::MAX_MULTIPLE_ACCOUNTS;
+use crate::status::BotStatus;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{error, warn};
+
+/// 所有者和加载时不一致的池子账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OwnerMismatch {
+    pub dex: &'static str,
+    pub pool: Pubkey,
+    /// 池子所属 DEX 的程序
+    pub expected: Pubkey,
+    /// 账户当前的所有者，账户已关闭时为 `None`
+    pub actual: Option<Pubkey>,
+}
+
+impl fmt::Display for OwnerMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.actual {
+            Some(actual) => write!(
+                f,
+                "account is now owned by {}, expected {}",
+                actual, self.expected
+            ),
+            None => write!(f, "account was closed"),
+        }
+    }
+}
+
+/// 对比池子账户的当前所有者和所属 DEX 的程序
+///
+/// # 参数
+/// * `pools` - `MintPoolData::pool_addresses` 返回的池子
+/// * `accounts` - 与 `pools` 一一对应的账户，账户不存在时为 `None`
+///
+/// # 返回值
+/// 账户已关闭或所有者变化的池子，未知 DEX 的池子不检查
+pub fn owner_mismatches(
+    pools: &[(&'static str, Pubkey)],
+    accounts: &[Option<Account>],
+) -> Vec<OwnerMismatch> {
+    pools
+        .iter()
+        .zip(accounts)
+        .filter_map(|((dex, pool), account)| {
+            let expected = pool_program_id(dex)?;
+            let actual = account.as_ref().map(|account| account.owner);
+            (actual != Some(expected)).then_some(OwnerMismatch {
+                dex,
+                pool: *pool,
+                expected,
+                actual,
+            })
+        })
+        .collect()
+}
+
+/// 读取池子账户并检查所有者
+///
+/// # 参数
+/// * `pools` - `MintPoolData::pool_addresses` 返回的池子
+pub fn fetch_owner_mismatches(
+    rpc_client: &RpcClient,
+    pools: &[(&'static str, Pubkey)],
+) -> anyhow::Result<Vec<OwnerMismatch>> {
+    let mut mismatches = Vec::new();
+    for chunk in pools.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let pubkeys: Vec<Pubkey> = chunk.iter().map(|(_, pool)| *pool).collect();
+        let accounts = rpc_client.get_multiple_accounts(&pubkeys)?;
+        mismatches.extend(owner_mismatches(chunk, &accounts));
+    }
+    Ok(mismatches)
+}
+
+/// 定期重新校验一个代币所有池子账户的所有者
+///
+/// 池子账户关闭后地址可能被其他程序重新使用，启动时的校验（或缓存中的池子信息）不再可靠。
+/// 发现所有者变化时把池子从 `pool_data` 中移除，记录错误日志并推送告警。
+///
+/// # 参数
+/// * `mint` - 代币地址，用于日志和告警
+/// * `pool_data` - 发送任务共用的池子数据
+/// * `interval` - 校验间隔
+pub async fn run(
+    mint: String,
+    pool_data: Arc<Mutex<MintPoolData>>,
+    rpc_client: Arc<RpcClient>,
+    interval: Duration,
+    notifier: Notifier,
+    status: Arc<BotStatus>,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+
+        // 读取账户期间不持有锁，不阻塞发送任务
+        let pools = pool_data.lock().await.pool_addresses();
+        let mismatches = {
+            let rpc_client = rpc_client.clone();
+            tokio::task::spawn_blocking(move || fetch_owner_mismatches(&rpc_client, &pools))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result)
+        };
+        let mismatches = match mismatches {
+            Ok(mismatches) => mismatches,
+            Err(e) => {
+                error!("Failed to check pool owners for mint {}: {}", mint, e);
+                continue;
+            }
+        };
+        if mismatches.is_empty() {
+            continue;
+        }
+
+        let mut guard = pool_data.lock().await;
+        guard.retain_pools(|pool| !mismatches.iter().any(|mismatch| mismatch.pool == *pool));
+        for mismatch in mismatches {
+            error!(
+                "Removing {} pool {} from mint {}: {}",
+                mismatch.dex, mismatch.pool, mint, mismatch
+            );
+            notifier.notify(Alert::PoolEvicted {
+                mint: mint.clone(),
+                dex: mismatch.dex,
+                pool: mismatch.pool,
+                reason: mismatch.to_string(),
+            });
+        }
+        if !guard.has_valid_route() {
+            warn!(
+                "Mint {} has no buy/sell pool pair left after removing pools",
+                mint
+            );
+        }
+        status.update_pools(&guard);
+    }
+}
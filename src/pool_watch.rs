@@ -0,0 +1,259 @@
+use crate::app_state::AppState;
+use crate::dex::{pool_loader, pool_program_id, GetAccount};
+use crate::pools::MintPoolData;
+use serde::Deserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::sync::{Mutex, MutexGuard};
+use tracing::{debug, error, info, warn};
+
+/// 运行时加入的一个池子
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct NewPool {
+    pub mint: String,
+    /// DEX 名称，与 `MintPoolData::pool_addresses` 一致，例如 `pump`、`raydium_cp`
+    pub dex: String,
+    pub address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoolFile {
+    #[serde(default)]
+    pool: Vec<NewPool>,
+}
+
+/// 解析池子目录中的一个文件
+///
+/// 文件是 TOML 格式，每个 `[[pool]]` 表是一个池子：
+///
+/// ```toml
+/// [[pool]]
+/// mint = "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz"
+/// dex = "pump"
+/// address = "..."
+/// ```
+pub fn parse_pool_file(contents: &str) -> anyhow::Result<Vec<NewPool>> {
+    let file: PoolFile = toml::from_str(contents)?;
+    Ok(file.pool)
+}
+
+/// 把一个池子加入代币的池子数据
+///
+/// 先检查池子账户的所有者是 `dex` 的程序，再调用对应 DEX 的加载器。加载在副本上进行，
+/// 失败时 `pool_data` 保持不变。
+///
+/// # 参数
+/// * `pool_data` - 代币的池子数据
+/// * `dex` - DEX 名称，见 `dex::pool_loader`
+/// * `address` - 池子地址
+/// * `get_account` - 读取账户的函数
+///
+/// # 返回值
+/// 加入了池子返回 `true`，池子已经存在返回 `false`
+///
+/// # 错误
+/// DEX 名称未知、地址非法、账户所有者不符或加载器无法解析池子时返回错误
+pub fn add_pool(
+    pool_data: &mut MintPoolData,
+    dex: &str,
+    address: &str,
+    get_account: &mut GetAccount<'_>,
+) -> anyhow::Result<bool> {
+    let (Some(load), Some(program_id)) = (pool_loader(dex), pool_program_id(dex)) else {
+        return Err(anyhow::anyhow!("`{}` is not a supported DEX", dex));
+    };
+    let pool = Pubkey::from_str(address)
+        .map_err(|e| anyhow::anyhow!("Invalid pool address {}: {}", address, e))?;
+    if pool_data
+        .pool_addresses()
+        .iter()
+        .any(|(_, existing)| *existing == pool)
+    {
+        return Ok(false);
+    }
+
+    let account = get_account(&pool)?;
+    if account.owner != program_id {
+        return Err(anyhow::anyhow!(
+            "Pool {} is owned by {}, not the {} program {}",
+            pool,
+            account.owner,
+            dex,
+            program_id
+        ));
+    }
+    let mut updated = pool_data.clone();
+    let mut get_account = |pubkey: &Pubkey| {
+        if *pubkey == pool {
+            Ok(account.clone())
+        } else {
+            get_account(pubkey)
+        }
+    };
+    load(&pool, &mut get_account, &mut updated)?;
+    *pool_data = updated;
+    Ok(true)
+}
+
+/// 通过 RPC 读取账户，把一个池子加入发送任务共用的池子数据
+///
+/// 读取账户期间不持有锁，不阻塞发送任务：先在副本上加载一次记下读取的账户，
+/// 再持有锁用这些账户加载到共用的池子数据中。
+///
+/// # 返回值
+/// 加入了池子时返回仍然持有的锁，调用方用它更新状态和 vault 跟踪；池子已经存在时返回 `None`
+///
+/// # 错误
+/// 与 `add_pool` 相同
+pub async fn add_pool_from_rpc<'a>(
+    pool_data: &'a Mutex<MintPoolData>,
+    rpc_client: Arc<RpcClient>,
+    dex: &str,
+    address: &str,
+) -> anyhow::Result<Option<MutexGuard<'a, MintPoolData>>> {
+    let snapshot = pool_data.lock().await.clone();
+    let accounts = {
+        let dex = dex.to_string();
+        let address = address.to_string();
+        tokio::task::spawn_blocking(move || {
+            let mut snapshot = snapshot;
+            let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+            let added = add_pool(&mut snapshot, &dex, &address, &mut |pubkey| {
+                let account = rpc_client.get_account(pubkey)?;
+                accounts.insert(*pubkey, account.clone());
+                Ok(account)
+            })?;
+            Ok::<_, anyhow::Error>(added.then_some(accounts))
+        })
+        .await??
+    };
+    let Some(accounts) = accounts else {
+        return Ok(None);
+    };
+
+    let mut guard = pool_data.lock().await;
+    let added = add_pool(&mut guard, dex, address, &mut |pubkey| {
+        accounts
+            .get(pubkey)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("account {} was not fetched", pubkey))
+    })?;
+    Ok(added.then_some(guard))
+}
+
+/// 找出池子目录中新增或修改过的 `.toml` 文件
+///
+/// # 参数
+/// * `dir` - 池子目录，不存在时视为空目录
+/// * `seen` - 已处理文件的修改时间，找到的文件会更新到这里
+pub fn changed_files(
+    dir: &Path,
+    seen: &mut HashMap<PathBuf, SystemTime>,
+) -> std::io::Result<Vec<PathBuf>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut changed = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|extension| extension != "toml") {
+            continue;
+        }
+        let modified = std::fs::metadata(&path)?.modified()?;
+        if seen.insert(path.clone(), modified) != Some(modified) {
+            changed.push(path);
+        }
+    }
+    changed.sort();
+    Ok(changed)
+}
+
+/// 监视池子目录，把新池子加入正在运行的代币，发送任务下一轮就会使用
+pub struct PoolWatcher {
+    /// 共享的运行状态，从中按 mint 找到发送任务共用的池子数据
+    pub state: Arc<AppState>,
+}
+
+impl PoolWatcher {
+    /// 每隔 `interval` 检查一次 `dir`，处理新增或修改过的文件
+    ///
+    /// 启动后的第一次检查会处理目录中已有的所有文件，所以重启后之前加入的池子仍然有效。
+    /// 文件中已经加载过的池子会被跳过，无法加载的池子记录错误日志，文件修改后重试。
+    pub async fn run(self, dir: PathBuf, interval: Duration) {
+        let mut seen = HashMap::new();
+        loop {
+            match changed_files(&dir, &mut seen) {
+                Ok(files) => {
+                    for path in files {
+                        self.process_file(&path).await;
+                    }
+                }
+                Err(e) => error!("Failed to read pool directory {}: {}", dir.display(), e),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    async fn process_file(&self, path: &Path) {
+        let pools = std::fs::read_to_string(path)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| parse_pool_file(&contents));
+        let pools = match pools {
+            Ok(pools) => pools,
+            Err(e) => {
+                warn!("Ignoring pool file {}: {}", path.display(), e);
+                return;
+            }
+        };
+        for pool in pools {
+            if let Err(e) = self.add(&pool).await {
+                error!(
+                    "Failed to add {} pool {} from {}: {:#}",
+                    pool.dex,
+                    pool.address,
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+
+    async fn add(&self, pool: &NewPool) -> anyhow::Result<()> {
+        let pool_data = Pubkey::from_str(&pool.mint)
+            .ok()
+            .and_then(|mint| self.state.mint(&mint))
+            .ok_or_else(|| anyhow::anyhow!("mint {} is not configured", pool.mint))?;
+
+        let Some(guard) = add_pool_from_rpc(
+            &pool_data,
+            self.state.rpc_client.clone(),
+            &pool.dex,
+            &pool.address,
+        )
+        .await?
+        else {
+            debug!("{} pool {} is already loaded", pool.dex, pool.address);
+            return Ok(());
+        };
+        info!(
+            "Added {} pool {} to mint {}",
+            pool.dex, pool.address, pool.mint
+        );
+        self.state.status.update_pools(&guard);
+        if let Some(vault_tracker) = &self.state.services.vault_tracker {
+            vault_tracker.register(&guard);
+        }
+        if let Some(copy_routes) = &self.state.services.copy_routes {
+            copy_routes.register(&guard);
+        }
+        Ok(())
+    }
+}
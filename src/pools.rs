@@ -1,19 +1,140 @@
 use crate::{
-    constants::SOL_MINT,
-    dex::raydium::{clmm_info::POOL_TICK_ARRAY_BITMAP_SEED, raydium_clmm_program_id},
+    constants::{sol_mint, usdc_mint},
+    dex::meteora::constants::{
+        damm_program_id, damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id,
+        dlmm_event_authority, dlmm_program_id, vault_program_id,
+    },
+    dex::moonshot::{moonshot_config, moonshot_dex_fee, moonshot_helio_fee, moonshot_program_id},
+    dex::perena::perena_program_id,
+    dex::pump::{pump_event_authority, pump_global_config, pump_program_id},
+    dex::raydium::{
+        clmm_info::POOL_TICK_ARRAY_BITMAP_SEED, raydium_authority, raydium_clmm_program_id,
+        raydium_cp_authority, raydium_cp_program_id, raydium_program_id,
+    },
+    dex::solfi::constants::solfi_program_id,
+    dex::vertigo::vertigo_program_id,
+    dex::whirlpool::constants::whirlpool_program_id,
+    dex::zerofi::zerofi_program_id,
+    fees::PoolFee,
+    transfer_fee::TransferFee,
 };
+use serde::{Deserialize, Serialize};
 use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
-use std::str::FromStr;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+/// 池子允许参与的交易方向
+///
+/// 买入指用 SOL 换目标代币，卖出指用目标代币换回 SOL。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolDirection {
+    /// 买卖都可以
+    #[default]
+    Both,
+    /// 只作为买入池
+    BuyOnly,
+    /// 只作为卖出池
+    SellOnly,
+}
+
+impl PoolDirection {
+    pub fn can_buy(self) -> bool {
+        self != PoolDirection::SellOnly
+    }
+
+    pub fn can_sell(self) -> bool {
+        self != PoolDirection::BuyOnly
+    }
+}
+
+/// 两侧都不是报价货币的池子（例如多跳路线中的代币/代币池子）的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NonQuotePoolPolicy {
+    /// 作为池子加载错误，按 `bot.on_pool_error` 处理
+    #[default]
+    Reject,
+    /// 记录警告后跳过，不受 `bot.on_pool_error` 影响
+    Skip,
+    /// 保留池子，目标代币之外的一侧占用报价货币 vault 的位置，不参与链下报价
+    Allow,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumPool {
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
+    /// 池子关联的 OpenBook 市场，加载失败或没有市场时为 `None`
+    pub market: Option<RaydiumMarket>,
+}
+
+/// Raydium AMM v4 swap 指令需要的 OpenBook 市场账户
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RaydiumMarket {
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    /// 池子的 coin vault 是否为 SOL vault，决定 swap 指令中两个池子 vault 的顺序
+    pub sol_is_coin: bool,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+impl RaydiumPool {
+    /// 直接调用 Raydium AMM v4 `swap_base_in` / `swap_base_out` 指令的完整账户列表
+    ///
+    /// 执行程序只需要池子和 vault，这里的列表供直接构造 Raydium 指令时使用。
+    ///
+    /// # 参数
+    /// * `user_source` - 转出代币的账户
+    /// * `user_destination` - 接收代币的账户
+    /// * `user_owner` - 两个账户的所有者（签名者）
+    ///
+    /// # 返回值
+    /// 没有市场账户时返回 `None`
+    pub fn swap_accounts(
+        &self,
+        user_source: &Pubkey,
+        user_destination: &Pubkey,
+        user_owner: &Pubkey,
+    ) -> Option<Vec<AccountMeta>> {
+        let market = self.market.as_ref()?;
+        let (pool_coin_vault, pool_pc_vault) = if market.sol_is_coin {
+            (self.sol_vault, self.token_vault)
+        } else {
+            (self.token_vault, self.sol_vault)
+        };
+        Some(vec![
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new(self.pool, false),
+            AccountMeta::new_readonly(*raydium_authority(), false),
+            AccountMeta::new(market.open_orders, false),
+            AccountMeta::new(market.target_orders, false),
+            AccountMeta::new(pool_coin_vault, false),
+            AccountMeta::new(pool_pc_vault, false),
+            AccountMeta::new_readonly(market.market_program, false),
+            AccountMeta::new(market.market, false),
+            AccountMeta::new(market.bids, false),
+            AccountMeta::new(market.asks, false),
+            AccountMeta::new(market.event_queue, false),
+            AccountMeta::new(market.coin_vault, false),
+            AccountMeta::new(market.pc_vault, false),
+            AccountMeta::new_readonly(market.vault_signer, false),
+            AccountMeta::new(*user_source, false),
+            AccountMeta::new(*user_destination, false),
+            AccountMeta::new_readonly(*user_owner, true),
+        ])
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumCpPool {
     pub pool: Pubkey,
     pub token_vault: Pubkey,
@@ -22,17 +143,19 @@ pub struct RaydiumCpPool {
     pub observation: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PumpPool {
     pub pool: Pubkey,
     pub token_vault: Pubkey,
     pub sol_vault: Pubkey,
+    /// 全局配置中的协议手续费接收地址
+    pub fee_recipient: Pubkey,
     pub fee_token_wallet: Pubkey,
     pub coin_creator_vault_ata: Pubkey,
     pub coin_creator_vault_authority: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DlmmPool {
     pub pair: Pubkey,
     pub token_vault: Pubkey,
@@ -42,7 +165,7 @@ pub struct DlmmPool {
     pub memo_program: Option<Pubkey>, // For Token 2022 support
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhirlpoolPool {
     pub pool: Pubkey,
     pub oracle: Pubkey,
@@ -52,7 +175,7 @@ pub struct WhirlpoolPool {
     pub memo_program: Option<Pubkey>, // For Token 2022 support
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumClmmPool {
     pub pool: Pubkey,
     pub amm_config: Pubkey,
@@ -64,7 +187,7 @@ pub struct RaydiumClmmPool {
     pub memo_program: Option<Pubkey>, // For Token 2022 support
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeteoraDAmmPool {
     pub pool: Pubkey,
     pub token_x_vault: Pubkey,
@@ -79,21 +202,29 @@ pub struct MeteoraDAmmPool {
     pub admin_token_fee_sol: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolfiPool {
     pub pool: Pubkey,
     pub token_x_vault: Pubkey,
     pub token_sol_vault: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeteoraDAmmV2Pool {
     pub pool: Pubkey,
     pub token_x_vault: Pubkey,
     pub token_sol_vault: Pubkey,
+    /// cp-amm 程序持有所有 vault 的 PDA
+    pub pool_authority: Pubkey,
+    /// cp-amm 程序的 Anchor 事件权限
+    pub event_authority: Pubkey,
+    /// 目标代币的代币程序
+    pub token_x_program: Pubkey,
+    /// 报价货币的代币程序
+    pub token_sol_program: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VertigoPool {
     pub pool: Pubkey,
     pub pool_owner: Pubkey,
@@ -101,7 +232,44 @@ pub struct VertigoPool {
     pub token_sol_vault: Pubkey,
 }
 
-#[derive(Debug, Clone)]
+/// Moonshot 联合曲线，SOL 以 lamports 存放在曲线账户本身
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoonshotPool {
+    /// 曲线账户
+    pub pool: Pubkey,
+    /// 曲线账户持有代币的 ATA
+    pub token_vault: Pubkey,
+}
+
+/// Perena Numeraire 稳定币池，只记录当前代币和报价货币两个 vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerenaPool {
+    pub pool: Pubkey,
+    pub token_x_vault: Pubkey,
+    pub token_sol_vault: Pubkey,
+}
+
+/// ZeroFi 交易对，每一边有一个 vault info 账户和一个代币 vault
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZeroFiPool {
+    pub pool: Pubkey,
+    pub token_x_vault_info: Pubkey,
+    pub token_x_vault: Pubkey,
+    pub token_sol_vault_info: Pubkey,
+    pub token_sol_vault: Pubkey,
+}
+
+/// 一个池子在执行程序 swap 指令中的账户
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolAccounts {
+    /// DEX 名称，与 `MintPoolData::pool_addresses` 一致
+    pub dex: &'static str,
+    pub pool: Pubkey,
+    /// 按执行程序要求的顺序排列，第一个总是 DEX 的程序
+    pub accounts: Vec<AccountMeta>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// MintPoolData 结构体用于存储与特定铸币相关的池信息和账户数据
 ///
 /// 该结构体包含了与特定铸币相关的各种去中心化交易所池信息，
@@ -115,6 +283,10 @@ pub struct MintPoolData {
     pub wallet_account: Pubkey,
     /// 钱包WSOL账户的公钥地址，用于处理SOL代币的包装和解包装
     pub wallet_wsol_account: Pubkey,
+    /// 钱包 USDC 关联代币账户的公钥地址，报价货币为 USDC 时作为链上程序的输入输出账户
+    pub wallet_usdc_account: Pubkey,
+    /// 报价货币（计价单位）的 mint，默认 SOL；池子中的 `sol_vault` 是该货币的 vault
+    pub quote_mint: Pubkey,
     /// Raydium协议的池信息列表
     pub raydium_pools: Vec<RaydiumPool>,
     /// Raydium集中流动性池信息列表
@@ -135,33 +307,51 @@ pub struct MintPoolData {
     pub meteora_damm_v2_pools: Vec<MeteoraDAmmV2Pool>,
     /// Vertigo协议的池信息列表
     pub vertigo_pools: Vec<VertigoPool>,
+    /// Moonshot 联合曲线列表
+    pub moonshot_pools: Vec<MoonshotPool>,
+    /// Perena Numeraire 稳定币池列表
+    pub perena_pools: Vec<PerenaPool>,
+    /// ZeroFi 交易对列表
+    pub zerofi_pools: Vec<ZeroFiPool>,
+    /// 设置了方向限制的池子，未列出的池子买卖都可以
+    pub pool_directions: HashMap<Pubkey, PoolDirection>,
+    /// 池子价格相对中位价的偏离，见 `quote::pool_edges`
+    pub pool_edges: HashMap<Pubkey, f64>,
+    /// 加载池子时从链上读取的手续费，未读取到的池子报价时使用默认费率
+    pub pool_fees: HashMap<Pubkey, PoolFee>,
+    /// Token 2022 mint 当前生效的转账手续费，没有手续费时为 `None`
+    pub transfer_fee: Option<TransferFee>,
+    /// 加载时遇到两侧都不是报价货币的池子的处理方式
+    pub non_quote_pool_policy: NonQuotePoolPolicy,
+    /// 按 `NonQuotePoolPolicy::Allow` 保留的池子 -> 池子另一侧的 mint，报价时跳过
+    pub non_quote_pools: HashMap<Pubkey, Pubkey>,
 }
 
 impl MintPoolData {
     /// 创建一个新的实例
     ///
     /// # 参数
-    /// * `mint` - 代币mint地址的字符串表示
-    /// * `wallet_account` - 钱包账户地址的字符串表示
+    /// * `mint` - 代币mint地址
+    /// * `wallet_account` - 钱包账户地址
     /// * `token_program` - 代币程序的公钥
-    ///
-    /// # 返回值
-    /// 返回Result包装的新实例，如果解析公钥失败则返回错误
-    pub fn new(mint: &str, wallet_account: &str, token_program: Pubkey) -> anyhow::Result<Self> {
-        // 解析SOL mint地址和钱包地址
-        let sol_mint = Pubkey::from_str(SOL_MINT)?;
-        let wallet_pk = Pubkey::from_str(wallet_account)?;
-
+    pub fn new(mint: Pubkey, wallet_account: Pubkey, token_program: Pubkey) -> Self {
+        let sol_mint = *sol_mint();
         // 计算钱包的WSOL关联代币地址
         let wallet_wsol_pk =
-            spl_associated_token_account::get_associated_token_address(&wallet_pk, &sol_mint);
+            spl_associated_token_account::get_associated_token_address(&wallet_account, &sol_mint);
+        let wallet_usdc_pk = spl_associated_token_account::get_associated_token_address(
+            &wallet_account,
+            usdc_mint(),
+        );
 
         // 构造并返回新实例，初始化所有池子列表为空
-        Ok(Self {
-            mint: Pubkey::from_str(mint)?,
+        Self {
+            mint,
             token_program,
-            wallet_account: wallet_pk,
+            wallet_account,
             wallet_wsol_account: wallet_wsol_pk,
+            wallet_usdc_account: wallet_usdc_pk,
+            quote_mint: sol_mint,
             raydium_pools: Vec::new(),
             raydium_cp_pools: Vec::new(),
             pump_pools: Vec::new(),
@@ -172,268 +362,702 @@ impl MintPoolData {
             solfi_pools: Vec::new(),
             meteora_damm_v2_pools: Vec::new(),
             vertigo_pools: Vec::new(),
-        })
+            moonshot_pools: Vec::new(),
+            perena_pools: Vec::new(),
+            zerofi_pools: Vec::new(),
+            pool_directions: HashMap::new(),
+            pool_edges: HashMap::new(),
+            pool_fees: HashMap::new(),
+            transfer_fee: None,
+            non_quote_pool_policy: NonQuotePoolPolicy::Reject,
+            non_quote_pools: HashMap::new(),
+        }
     }
 
-    pub fn add_raydium_pool(
-        &mut self,
-        pool: &str,
-        token_vault: &str,
-        sol_vault: &str,
-    ) -> anyhow::Result<()> {
+    /// 使用其他报价货币，必须在加载池子之前设置
+    ///
+    /// # 参数
+    /// * `quote_mint` - 报价货币的 mint，见 `constants::cash_mints`
+    pub fn with_quote_mint(mut self, quote_mint: Pubkey) -> Self {
+        self.quote_mint = quote_mint;
+        self
+    }
+
+    /// 设置加载两侧都不是报价货币的池子时的处理方式，见 `dex::quote_side`
+    pub fn with_non_quote_pool_policy(mut self, policy: NonQuotePoolPolicy) -> Self {
+        self.non_quote_pool_policy = policy;
+        self
+    }
+
+    /// 钱包持有报价货币的代币账户：SOL 为 WSOL 账户，USDC 为 `wallet_usdc_account`，
+    /// 其他货币为对应的关联代币账户
+    pub fn wallet_quote_account(&self) -> Pubkey {
+        if self.quote_mint == *sol_mint() {
+            self.wallet_wsol_account
+        } else if self.quote_mint == *usdc_mint() {
+            self.wallet_usdc_account
+        } else {
+            spl_associated_token_account::get_associated_token_address(
+                &self.wallet_account,
+                &self.quote_mint,
+            )
+        }
+    }
+
+    pub fn add_raydium_pool(&mut self, pool: Pubkey, token_vault: Pubkey, sol_vault: Pubkey) {
         self.raydium_pools.push(RaydiumPool {
-            pool: Pubkey::from_str(pool)?,
-            token_vault: Pubkey::from_str(token_vault)?,
-            sol_vault: Pubkey::from_str(sol_vault)?,
+            pool,
+            token_vault,
+            sol_vault,
+            market: None,
         });
-        Ok(())
+    }
+
+    /// 设置 Raydium 池子的 OpenBook 市场账户
+    pub fn set_raydium_market(&mut self, pool: &Pubkey, market: RaydiumMarket) {
+        if let Some(raydium_pool) = self.raydium_pools.iter_mut().find(|p| p.pool == *pool) {
+            raydium_pool.market = Some(market);
+        }
     }
 
     /// 向Raydium集中流动性池列表中添加一个新的池
     ///
     /// 该函数创建一个新的RaydiumCpPool实例并将其添加到内部存储中。
-    /// 所有地址参数都必须是有效的Solana公钥字符串格式。
     ///
     /// # 参数
-    /// * `pool` - 流动性池的公钥地址字符串
-    /// * `token_vault` - 代币资金池的公钥地址字符串
-    /// * `sol_vault` - SOL资金池的公钥地址字符串
-    /// * `amm_config` - AMM配置账户的公钥地址字符串
-    /// * `observation` - 价格观测账户的公钥地址字符串
-    ///
-    /// # 返回值
-    /// 返回Result<(), anyhow::Error>，成功时返回Ok(())，失败时返回包含错误信息的Err
-    ///
-    /// # 错误
-    /// 当任何公钥地址字符串格式无效时，会返回解析错误
+    /// * `pool` - 流动性池的公钥地址
+    /// * `token_vault` - 代币资金池的公钥地址
+    /// * `sol_vault` - SOL资金池的公钥地址
+    /// * `amm_config` - AMM配置账户的公钥地址
+    /// * `observation` - 价格观测账户的公钥地址
     pub fn add_raydium_cp_pool(
         &mut self,
-        pool: &str,
-        token_vault: &str,
-        sol_vault: &str,
-        amm_config: &str,
-        observation: &str,
-    ) -> anyhow::Result<()> {
+        pool: Pubkey,
+        token_vault: Pubkey,
+        sol_vault: Pubkey,
+        amm_config: Pubkey,
+        observation: Pubkey,
+    ) {
         // 创建新的Raydium集中流动性池实例并添加到列表中
         self.raydium_cp_pools.push(RaydiumCpPool {
-            pool: Pubkey::from_str(pool)?,
-            token_vault: Pubkey::from_str(token_vault)?,
-            sol_vault: Pubkey::from_str(sol_vault)?,
-            amm_config: Pubkey::from_str(amm_config)?,
-            observation: Pubkey::from_str(observation)?,
+            pool,
+            token_vault,
+            sol_vault,
+            amm_config,
+            observation,
         });
-        Ok(())
     }
 
     /// 向泵池列表中添加一个新的泵池配置
     ///
     /// # 参数
-    /// * `pool` - 泵池的公钥地址字符串
-    /// * `token_vault` - 代币保险库的公钥地址字符串
-    /// * `sol_vault` - SOL保险库的公钥地址字符串
-    /// * `fee_token_wallet` - 手续费代币钱包的公钥地址字符串
-    /// * `coin_creator_vault_ata` - 代币创建者保险库关联代币账户的公钥地址字符串
-    /// * `coin_creator_authority` - 代币创建者权限账户的公钥地址字符串
-    ///
-    /// # 返回值
-    /// 返回Result<(), anyhow::Error>，成功时返回Ok(())，失败时返回错误信息
+    /// * `pool` - 泵池的公钥地址
+    /// * `token_vault` - 代币保险库的公钥地址
+    /// * `sol_vault` - SOL保险库的公钥地址
+    /// * `fee_recipient` - 协议手续费接收地址的公钥地址
+    /// * `fee_token_wallet` - 手续费代币钱包的公钥地址
+    /// * `coin_creator_vault_ata` - 代币创建者保险库关联代币账户的公钥地址
+    /// * `coin_creator_authority` - 代币创建者权限账户的公钥地址
     pub fn add_pump_pool(
         &mut self,
-        pool: &str,
-        token_vault: &str,
-        sol_vault: &str,
-        fee_token_wallet: &str,
-        coin_creator_vault_ata: &str,
-        coin_creator_authority: &str,
-    ) -> anyhow::Result<()> {
+        pool: Pubkey,
+        token_vault: Pubkey,
+        sol_vault: Pubkey,
+        fee_recipient: Pubkey,
+        fee_token_wallet: Pubkey,
+        coin_creator_vault_ata: Pubkey,
+        coin_creator_authority: Pubkey,
+    ) {
         // 创建新的泵池结构体并添加到泵池列表中
         self.pump_pools.push(PumpPool {
-            pool: Pubkey::from_str(pool)?,
-            token_vault: Pubkey::from_str(token_vault)?,
-            sol_vault: Pubkey::from_str(sol_vault)?,
-            fee_token_wallet: Pubkey::from_str(fee_token_wallet)?,
-            coin_creator_vault_ata: Pubkey::from_str(coin_creator_vault_ata)?,
-            coin_creator_vault_authority: Pubkey::from_str(coin_creator_authority)?,
+            pool,
+            token_vault,
+            sol_vault,
+            fee_recipient,
+            fee_token_wallet,
+            coin_creator_vault_ata,
+            coin_creator_vault_authority: coin_creator_authority,
         });
-        Ok(())
     }
 
     pub fn add_dlmm_pool(
         &mut self,
-        pair: &str,
-        token_vault: &str,
-        sol_vault: &str,
-        oracle: &str,
-        bin_arrays: Vec<&str>,
-        memo_program: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let bin_array_pubkeys = bin_arrays
-            .iter()
-            .map(|&s| Pubkey::from_str(s))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let memo_program_pubkey = if let Some(memo) = memo_program {
-            Some(Pubkey::from_str(memo)?)
-        } else {
-            None
-        };
-
+        pair: Pubkey,
+        token_vault: Pubkey,
+        sol_vault: Pubkey,
+        oracle: Pubkey,
+        bin_arrays: Vec<Pubkey>,
+        memo_program: Option<Pubkey>,
+    ) {
         self.dlmm_pairs.push(DlmmPool {
-            pair: Pubkey::from_str(pair)?,
-            token_vault: Pubkey::from_str(token_vault)?,
-            sol_vault: Pubkey::from_str(sol_vault)?,
-            oracle: Pubkey::from_str(oracle)?,
-            bin_arrays: bin_array_pubkeys,
-            memo_program: memo_program_pubkey,
+            pair,
+            token_vault,
+            sol_vault,
+            oracle,
+            bin_arrays,
+            memo_program,
         });
-        Ok(())
     }
 
     pub fn add_whirlpool_pool(
         &mut self,
-        pool: &str,
-        oracle: &str,
-        x_vault: &str,
-        y_vault: &str,
-        tick_arrays: Vec<&str>,
-        memo_program: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let tick_array_pubkeys = tick_arrays
-            .iter()
-            .map(|&s| Pubkey::from_str(s))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let memo_program_pubkey = if let Some(memo) = memo_program {
-            Some(Pubkey::from_str(memo)?)
-        } else {
-            None
-        };
-
+        pool: Pubkey,
+        oracle: Pubkey,
+        x_vault: Pubkey,
+        y_vault: Pubkey,
+        tick_arrays: Vec<Pubkey>,
+        memo_program: Option<Pubkey>,
+    ) {
         self.whirlpool_pools.push(WhirlpoolPool {
-            pool: Pubkey::from_str(pool)?,
-            oracle: Pubkey::from_str(oracle)?,
-            x_vault: Pubkey::from_str(x_vault)?,
-            y_vault: Pubkey::from_str(y_vault)?,
-            tick_arrays: tick_array_pubkeys,
-            memo_program: memo_program_pubkey,
+            pool,
+            oracle,
+            x_vault,
+            y_vault,
+            tick_arrays,
+            memo_program,
         });
-        Ok(())
     }
 
     pub fn add_raydium_clmm_pool(
         &mut self,
-        pool: &str,
-        amm_config: &str,
-        observation_state: &str,
-        x_vault: &str,
-        y_vault: &str,
-        tick_arrays: Vec<&str>,
-        memo_program: Option<&str>,
-    ) -> anyhow::Result<()> {
-        let pool_pubkey = Pubkey::from_str(pool)?;
+        pool: Pubkey,
+        amm_config: Pubkey,
+        observation_state: Pubkey,
+        x_vault: Pubkey,
+        y_vault: Pubkey,
+        tick_arrays: Vec<Pubkey>,
+        memo_program: Option<Pubkey>,
+    ) {
         let bitmap_extension = Pubkey::find_program_address(
-            &[
-                POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(),
-                &pool_pubkey.as_ref(),
-            ],
-            &raydium_clmm_program_id(),
+            &[POOL_TICK_ARRAY_BITMAP_SEED.as_bytes(), pool.as_ref()],
+            raydium_clmm_program_id(),
         )
         .0;
-        let tick_array_pubkeys = tick_arrays
-            .iter()
-            .map(|&s| Pubkey::from_str(s))
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let memo_program_pubkey = if let Some(memo) = memo_program {
-            Some(Pubkey::from_str(memo)?)
-        } else {
-            None
-        };
-
         self.raydium_clmm_pools.push(RaydiumClmmPool {
-            pool: pool_pubkey,
-            amm_config: Pubkey::from_str(amm_config)?,
-            observation_state: Pubkey::from_str(observation_state)?,
-            x_vault: Pubkey::from_str(x_vault)?,
-            y_vault: Pubkey::from_str(y_vault)?,
+            pool,
+            amm_config,
+            observation_state,
+            x_vault,
+            y_vault,
             bitmap_extension,
-            tick_arrays: tick_array_pubkeys,
-            memo_program: memo_program_pubkey,
+            tick_arrays,
+            memo_program,
         });
-        Ok(())
     }
 
     pub fn add_meteora_damm_pool(
         &mut self,
-        pool: &str,
-        token_x_vault: &str,
-        token_sol_vault: &str,
-        token_x_token_vault: &str,
-        token_sol_token_vault: &str,
-        token_x_lp_mint: &str,
-        token_sol_lp_mint: &str,
-        token_x_pool_lp: &str,
-        token_sol_pool_lp: &str,
-        admin_token_fee_x: &str,
-        admin_token_fee_sol: &str,
-    ) -> anyhow::Result<()> {
+        pool: Pubkey,
+        token_x_vault: Pubkey,
+        token_sol_vault: Pubkey,
+        token_x_token_vault: Pubkey,
+        token_sol_token_vault: Pubkey,
+        token_x_lp_mint: Pubkey,
+        token_sol_lp_mint: Pubkey,
+        token_x_pool_lp: Pubkey,
+        token_sol_pool_lp: Pubkey,
+        admin_token_fee_x: Pubkey,
+        admin_token_fee_sol: Pubkey,
+    ) {
         self.meteora_damm_pools.push(MeteoraDAmmPool {
-            pool: Pubkey::from_str(pool)?,
-            token_x_vault: Pubkey::from_str(token_x_vault)?,
-            token_sol_vault: Pubkey::from_str(token_sol_vault)?,
-            token_x_token_vault: Pubkey::from_str(token_x_token_vault)?,
-            token_sol_token_vault: Pubkey::from_str(token_sol_token_vault)?,
-            token_x_lp_mint: Pubkey::from_str(token_x_lp_mint)?,
-            token_sol_lp_mint: Pubkey::from_str(token_sol_lp_mint)?,
-            token_x_pool_lp: Pubkey::from_str(token_x_pool_lp)?,
-            token_sol_pool_lp: Pubkey::from_str(token_sol_pool_lp)?,
-            admin_token_fee_x: Pubkey::from_str(admin_token_fee_x)?,
-            admin_token_fee_sol: Pubkey::from_str(admin_token_fee_sol)?,
+            pool,
+            token_x_vault,
+            token_sol_vault,
+            token_x_token_vault,
+            token_sol_token_vault,
+            token_x_lp_mint,
+            token_sol_lp_mint,
+            token_x_pool_lp,
+            token_sol_pool_lp,
+            admin_token_fee_x,
+            admin_token_fee_sol,
         });
-        Ok(())
     }
 
-    pub fn add_solfi_pool(
-        &mut self,
-        pool: &str,
-        token_x_vault: &str,
-        token_sol_vault: &str,
-    ) -> anyhow::Result<()> {
+    pub fn add_solfi_pool(&mut self, pool: Pubkey, token_x_vault: Pubkey, token_sol_vault: Pubkey) {
         self.solfi_pools.push(SolfiPool {
-            pool: Pubkey::from_str(pool)?,
-            token_x_vault: Pubkey::from_str(token_x_vault)?,
-            token_sol_vault: Pubkey::from_str(token_sol_vault)?,
+            pool,
+            token_x_vault,
+            token_sol_vault,
         });
-        Ok(())
     }
 
+    /// 加入一个 Meteora DAMM v2 池子，池子权限和事件权限取 cp-amm 程序的地址
     pub fn add_meteora_damm_v2_pool(
         &mut self,
-        pool: &str,
-        token_x_vault: &str,
-        token_sol_vault: &str,
-    ) -> anyhow::Result<()> {
+        pool: Pubkey,
+        token_x_vault: Pubkey,
+        token_sol_vault: Pubkey,
+        token_x_program: Pubkey,
+        token_sol_program: Pubkey,
+    ) {
         self.meteora_damm_v2_pools.push(MeteoraDAmmV2Pool {
-            pool: Pubkey::from_str(pool)?,
-            token_x_vault: Pubkey::from_str(token_x_vault)?,
-            token_sol_vault: Pubkey::from_str(token_sol_vault)?,
+            pool,
+            token_x_vault,
+            token_sol_vault,
+            pool_authority: *damm_v2_pool_authority(),
+            event_authority: *damm_v2_event_authority(),
+            token_x_program,
+            token_sol_program,
         });
-        Ok(())
     }
 
     pub fn add_vertigo_pool(
         &mut self,
-        pool: &str,
-        pool_owner: &str,
-        token_x_vault: &str,
-        token_sol_vault: &str,
-    ) -> anyhow::Result<()> {
+        pool: Pubkey,
+        pool_owner: Pubkey,
+        token_x_vault: Pubkey,
+        token_sol_vault: Pubkey,
+    ) {
         self.vertigo_pools.push(VertigoPool {
-            pool: Pubkey::from_str(pool)?,
-            pool_owner: Pubkey::from_str(pool_owner)?,
-            token_x_vault: Pubkey::from_str(token_x_vault)?,
-            token_sol_vault: Pubkey::from_str(token_sol_vault)?,
+            pool,
+            pool_owner,
+            token_x_vault,
+            token_sol_vault,
+        });
+    }
+
+    pub fn add_moonshot_pool(&mut self, pool: Pubkey, token_vault: Pubkey) {
+        self.moonshot_pools.push(MoonshotPool { pool, token_vault });
+    }
+
+    pub fn add_perena_pool(
+        &mut self,
+        pool: Pubkey,
+        token_x_vault: Pubkey,
+        token_sol_vault: Pubkey,
+    ) {
+        self.perena_pools.push(PerenaPool {
+            pool,
+            token_x_vault,
+            token_sol_vault,
+        });
+    }
+
+    pub fn add_zerofi_pool(
+        &mut self,
+        pool: Pubkey,
+        token_x_vault_info: Pubkey,
+        token_x_vault: Pubkey,
+        token_sol_vault_info: Pubkey,
+        token_sol_vault: Pubkey,
+    ) {
+        self.zerofi_pools.push(ZeroFiPool {
+            pool,
+            token_x_vault_info,
+            token_x_vault,
+            token_sol_vault_info,
+            token_sol_vault,
         });
-        Ok(())
+    }
+
+    /// 池子允许参与的交易方向
+    pub fn direction(&self, pool: &Pubkey) -> PoolDirection {
+        self.pool_directions.get(pool).copied().unwrap_or_default()
+    }
+
+    /// 池子的报价偏离，未报价的池子为 0
+    pub fn edge(&self, pool: &Pubkey) -> f64 {
+        self.pool_edges.get(pool).copied().unwrap_or_default()
+    }
+
+    /// 池子的总手续费（基点），没有读取到链上费率时返回 `default_bps`
+    pub fn fee_bps(&self, pool: &Pubkey, default_bps: u64) -> u64 {
+        self.pool_fees
+            .get(pool)
+            .map_or(default_bps, PoolFee::total_bps)
+    }
+
+    /// 只保留 `keep` 返回 true 的池子
+    pub fn retain_pools(&mut self, keep: impl Fn(&Pubkey) -> bool) {
+        self.raydium_pools.retain(|p| keep(&p.pool));
+        self.raydium_cp_pools.retain(|p| keep(&p.pool));
+        self.pump_pools.retain(|p| keep(&p.pool));
+        self.dlmm_pairs.retain(|p| keep(&p.pair));
+        self.whirlpool_pools.retain(|p| keep(&p.pool));
+        self.raydium_clmm_pools.retain(|p| keep(&p.pool));
+        self.meteora_damm_pools.retain(|p| keep(&p.pool));
+        self.solfi_pools.retain(|p| keep(&p.pool));
+        self.meteora_damm_v2_pools.retain(|p| keep(&p.pool));
+        self.vertigo_pools.retain(|p| keep(&p.pool));
+        self.moonshot_pools.retain(|p| keep(&p.pool));
+        self.perena_pools.retain(|p| keep(&p.pool));
+        self.zerofi_pools.retain(|p| keep(&p.pool));
+        self.non_quote_pools.retain(|pool, _| keep(pool));
+    }
+
+    /// 在方向限制下是否还能组成一条套利路线：
+    /// 至少有一个可以买入的池子和另一个可以卖出的池子
+    pub fn has_valid_route(&self) -> bool {
+        let pools = self.pool_addresses();
+        pools.iter().any(|(_, buy)| {
+            self.direction(buy).can_buy()
+                && pools
+                    .iter()
+                    .any(|(_, sell)| sell != buy && self.direction(sell).can_sell())
+        })
+    }
+
+    /// 按 DEX 名称列出所有池子地址
+    pub fn pool_addresses(&self) -> Vec<(&'static str, Pubkey)> {
+        let mut pools = Vec::new();
+        pools.extend(self.raydium_pools.iter().map(|p| ("raydium", p.pool)));
+        pools.extend(self.raydium_cp_pools.iter().map(|p| ("raydium_cp", p.pool)));
+        pools.extend(
+            self.raydium_clmm_pools
+                .iter()
+                .map(|p| ("raydium_clmm", p.pool)),
+        );
+        pools.extend(self.pump_pools.iter().map(|p| ("pump", p.pool)));
+        pools.extend(self.dlmm_pairs.iter().map(|p| ("meteora_dlmm", p.pair)));
+        pools.extend(
+            self.meteora_damm_pools
+                .iter()
+                .map(|p| ("meteora_damm", p.pool)),
+        );
+        pools.extend(
+            self.meteora_damm_v2_pools
+                .iter()
+                .map(|p| ("meteora_damm_v2", p.pool)),
+        );
+        pools.extend(self.whirlpool_pools.iter().map(|p| ("whirlpool", p.pool)));
+        pools.extend(self.solfi_pools.iter().map(|p| ("solfi", p.pool)));
+        pools.extend(self.vertigo_pools.iter().map(|p| ("vertigo", p.pool)));
+        pools.extend(self.moonshot_pools.iter().map(|p| ("moonshot", p.pool)));
+        pools.extend(self.perena_pools.iter().map(|p| ("perena", p.pool)));
+        pools.extend(self.zerofi_pools.iter().map(|p| ("zerofi", p.pool)));
+        pools
+    }
+
+    /// 每个池子在执行程序 swap 指令中的账户，按执行程序读取的 DEX 顺序排列
+    ///
+    /// 执行程序按各 DEX 固定的账户个数和顺序解析指令账户，交易构建直接把这些账户依次拼接起来。
+    pub fn pool_accounts(&self) -> Vec<PoolAccounts> {
+        let sysvar_instructions = solana_program::sysvar::instructions::ID;
+        let mut pools = Vec::new();
+        let mut push = |dex: &'static str, pool: Pubkey, accounts: Vec<AccountMeta>| {
+            pools.push(PoolAccounts {
+                dex,
+                pool,
+                accounts,
+            })
+        };
+
+        for pool in &self.raydium_pools {
+            push(
+                "raydium",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*raydium_program_id(), false),
+                    AccountMeta::new_readonly(*raydium_authority(), false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_vault, false),
+                    AccountMeta::new(pool.sol_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.raydium_cp_pools {
+            push(
+                "raydium_cp",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*raydium_cp_program_id(), false),
+                    AccountMeta::new_readonly(*raydium_cp_authority(), false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new_readonly(pool.amm_config, false),
+                    AccountMeta::new(pool.token_vault, false),
+                    AccountMeta::new(pool.sol_vault, false),
+                    AccountMeta::new(pool.observation, false),
+                ],
+            );
+        }
+
+        for pool in &self.pump_pools {
+            push(
+                "pump",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*pump_program_id(), false),
+                    AccountMeta::new_readonly(*pump_global_config(), false),
+                    AccountMeta::new_readonly(*pump_event_authority(), false),
+                    AccountMeta::new_readonly(pool.fee_recipient, false),
+                    AccountMeta::new_readonly(pool.pool, false),
+                    AccountMeta::new(pool.token_vault, false),
+                    AccountMeta::new(pool.sol_vault, false),
+                    AccountMeta::new(pool.fee_token_wallet, false),
+                    AccountMeta::new(pool.coin_creator_vault_ata, false),
+                    AccountMeta::new_readonly(pool.coin_creator_vault_authority, false),
+                ],
+            );
+        }
+
+        for pair in &self.dlmm_pairs {
+            let mut accounts = vec![
+                AccountMeta::new_readonly(*dlmm_program_id(), false),
+                AccountMeta::new(*dlmm_event_authority(), false),
+            ];
+            if let Some(memo_program) = pair.memo_program {
+                accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
+            }
+            accounts.extend([
+                AccountMeta::new(pair.pair, false),
+                AccountMeta::new(pair.token_vault, false),
+                AccountMeta::new(pair.sol_vault, false),
+                AccountMeta::new(pair.oracle, false),
+            ]);
+            accounts.extend(pair.bin_arrays.iter().map(|a| AccountMeta::new(*a, false)));
+            push("meteora_dlmm", pair.pair, accounts);
+        }
+
+        for pool in &self.whirlpool_pools {
+            let mut accounts = vec![AccountMeta::new_readonly(*whirlpool_program_id(), false)];
+            if let Some(memo_program) = pool.memo_program {
+                accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
+            }
+            accounts.extend([
+                AccountMeta::new(pool.pool, false),
+                AccountMeta::new(pool.oracle, false),
+                AccountMeta::new(pool.x_vault, false),
+                AccountMeta::new(pool.y_vault, false),
+            ]);
+            accounts.extend(pool.tick_arrays.iter().map(|a| AccountMeta::new(*a, false)));
+            push("whirlpool", pool.pool, accounts);
+        }
+
+        for pool in &self.raydium_clmm_pools {
+            let mut accounts = vec![AccountMeta::new_readonly(*raydium_clmm_program_id(), false)];
+            if let Some(memo_program) = pool.memo_program {
+                accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
+            }
+            accounts.extend([
+                AccountMeta::new(pool.pool, false),
+                AccountMeta::new_readonly(pool.amm_config, false),
+                AccountMeta::new(pool.observation_state, false),
+                AccountMeta::new(pool.bitmap_extension, false),
+                AccountMeta::new(pool.x_vault, false),
+                AccountMeta::new(pool.y_vault, false),
+            ]);
+            accounts.extend(pool.tick_arrays.iter().map(|a| AccountMeta::new(*a, false)));
+            push("raydium_clmm", pool.pool, accounts);
+        }
+
+        for pool in &self.meteora_damm_pools {
+            push(
+                "meteora_damm",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*damm_program_id(), false),
+                    AccountMeta::new_readonly(*vault_program_id(), false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                    AccountMeta::new(pool.token_x_token_vault, false),
+                    AccountMeta::new(pool.token_sol_token_vault, false),
+                    AccountMeta::new(pool.token_x_lp_mint, false),
+                    AccountMeta::new(pool.token_sol_lp_mint, false),
+                    AccountMeta::new(pool.token_x_pool_lp, false),
+                    AccountMeta::new(pool.token_sol_pool_lp, false),
+                    AccountMeta::new(pool.admin_token_fee_x, false),
+                    AccountMeta::new(pool.admin_token_fee_sol, false),
+                ],
+            );
+        }
+
+        for pool in &self.meteora_damm_v2_pools {
+            push(
+                "meteora_damm_v2",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*damm_v2_program_id(), false),
+                    AccountMeta::new_readonly(pool.event_authority, false),
+                    AccountMeta::new_readonly(pool.pool_authority, false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.solfi_pools {
+            push(
+                "solfi",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*solfi_program_id(), false),
+                    AccountMeta::new_readonly(sysvar_instructions, false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.vertigo_pools {
+            push(
+                "vertigo",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*vertigo_program_id(), false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new_readonly(pool.pool_owner, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.moonshot_pools {
+            push(
+                "moonshot",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*moonshot_program_id(), false),
+                    AccountMeta::new(*moonshot_dex_fee(), false),
+                    AccountMeta::new(*moonshot_helio_fee(), false),
+                    AccountMeta::new_readonly(*moonshot_config(), false),
+                    AccountMeta::new(pool.pool, false), // 曲线账户，同时持有 SOL
+                    AccountMeta::new(pool.token_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.zerofi_pools {
+            push(
+                "zerofi",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*zerofi_program_id(), false),
+                    AccountMeta::new_readonly(sysvar_instructions, false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_x_vault_info, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault_info, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                ],
+            );
+        }
+
+        for pool in &self.perena_pools {
+            push(
+                "perena",
+                pool.pool,
+                vec![
+                    AccountMeta::new_readonly(*perena_program_id(), false),
+                    AccountMeta::new(pool.pool, false),
+                    AccountMeta::new(pool.token_x_vault, false),
+                    AccountMeta::new(pool.token_sol_vault, false),
+                ],
+            );
+        }
+
+        pools
+    }
+
+    /// 所有池子的 (池子地址, 代币 vault, SOL vault)
+    ///
+    /// Meteora DAMM 的资金存放在多个池子共用的 Meteora vault 中，vault 余额不代表单个池子的储备，不包含在内。
+    /// Moonshot 曲线的 SOL 是曲线账户的 lamports 而不是代币账户，也不包含在内。
+    pub fn pool_vaults(&self) -> Vec<(Pubkey, Pubkey, Pubkey)> {
+        let mut vaults = Vec::new();
+        vaults.extend(
+            self.raydium_pools
+                .iter()
+                .map(|p| (p.pool, p.token_vault, p.sol_vault)),
+        );
+        vaults.extend(
+            self.raydium_cp_pools
+                .iter()
+                .map(|p| (p.pool, p.token_vault, p.sol_vault)),
+        );
+        vaults.extend(
+            self.raydium_clmm_pools
+                .iter()
+                .map(|p| (p.pool, p.x_vault, p.y_vault)),
+        );
+        vaults.extend(
+            self.pump_pools
+                .iter()
+                .map(|p| (p.pool, p.token_vault, p.sol_vault)),
+        );
+        vaults.extend(
+            self.dlmm_pairs
+                .iter()
+                .map(|p| (p.pair, p.token_vault, p.sol_vault)),
+        );
+        vaults.extend(
+            self.meteora_damm_v2_pools
+                .iter()
+                .map(|p| (p.pool, p.token_x_vault, p.token_sol_vault)),
+        );
+        vaults.extend(
+            self.whirlpool_pools
+                .iter()
+                .map(|p| (p.pool, p.x_vault, p.y_vault)),
+        );
+        vaults.extend(
+            self.solfi_pools
+                .iter()
+                .map(|p| (p.pool, p.token_x_vault, p.token_sol_vault)),
+        );
+        vaults.extend(
+            self.vertigo_pools
+                .iter()
+                .map(|p| (p.pool, p.token_x_vault, p.token_sol_vault)),
+        );
+        vaults.extend(
+            self.perena_pools
+                .iter()
+                .map(|p| (p.pool, p.token_x_vault, p.token_sol_vault)),
+        );
+        vaults.extend(
+            self.zerofi_pools
+                .iter()
+                .map(|p| (p.pool, p.token_x_vault, p.token_sol_vault)),
+        );
+        vaults
+    }
+
+    /// 启动日志中的池子汇总表，每个池子一行：DEX、池子地址、两个 vault 和 swap 指令中的其余账户数
+    ///
+    /// 没有单独 vault 的池子（Meteora DAMM、Moonshot 曲线）vault 列为 `-`。
+    pub fn summary_table(&self) -> String {
+        let vaults: HashMap<Pubkey, (Pubkey, Pubkey)> = self
+            .pool_vaults()
+            .into_iter()
+            .map(|(pool, vault_a, vault_b)| (pool, (vault_a, vault_b)))
+            .collect();
+        let mut table = format!(
+            "{:<15}  {:<44}  {:<44}  {:<44}  {:>5}",
+            "DEX", "POOL", "VAULT A", "VAULT B", "EXTRA"
+        );
+        for pool in self.pool_accounts() {
+            let pool_vaults = vaults.get(&pool.pool);
+            // 除 DEX 程序、池子和 vault 之外的账户
+            let extra = pool
+                .accounts
+                .iter()
+                .skip(1)
+                .filter(|meta| {
+                    meta.pubkey != pool.pool
+                        && !pool_vaults.is_some_and(|(vault_a, vault_b)| {
+                            meta.pubkey == *vault_a || meta.pubkey == *vault_b
+                        })
+                })
+                .count();
+            let (vault_a, vault_b) = match pool_vaults {
+                Some((vault_a, vault_b)) => (vault_a.to_string(), vault_b.to_string()),
+                None => ("-".to_string(), "-".to_string()),
+            };
+            table.push_str(&format!(
+                "\n{:<15}  {:<44}  {:<44}  {:<44}  {:>5}",
+                pool.dex,
+                pool.pool.to_string(),
+                vault_a,
+                vault_b,
+                extra
+            ));
+        }
+        table
     }
 }
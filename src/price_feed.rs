@@ -0,0 +1,171 @@
+use crate::config::{PriceFeedConfig, PriceSource};
+use crate::constants::sol_mint;
+use crate::status::{unix_now, BotStatus};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// 主网 Pyth SOL/USD 价格账户（PriceUpdateV2，shard 0）
+pub const PYTH_SOL_USD_ACCOUNT: &str = "7UVimffxr9ow1uXYxsr4LHAcV58mLzhmwaeKvJ1pjLiE";
+
+/// Jupiter 价格 API
+pub const JUPITER_PRICE_URL: &str = "https://lite-api.jup.ag/price/v3";
+
+/// `PriceUpdateV2` 账户的 Anchor discriminator
+const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// 价格发布时间早于这么多秒时不再使用
+const MAX_PRICE_AGE_SECS: u64 = 300;
+
+/// 从 Pyth 价格账户解析出的价格
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PythPrice {
+    pub price: f64,
+    /// 发布时间（Unix 秒）
+    pub publish_time: i64,
+}
+
+/// 解析 Pyth `PriceUpdateV2` 账户
+///
+/// 布局：discriminator(8) + write_authority(32) + verification_level(1 或 2) +
+/// feed_id(32) + price(i64) + conf(u64) + exponent(i32) + publish_time(i64) + ...
+///
+/// # 错误
+/// discriminator 不匹配、账户长度不够或价格不是正数时返回错误
+pub fn parse_pyth_price_update(data: &[u8]) -> anyhow::Result<PythPrice> {
+    if data.get(..8) != Some(&PRICE_UPDATE_V2_DISCRIMINATOR[..]) {
+        return Err(anyhow::anyhow!("Not a Pyth PriceUpdateV2 account"));
+    }
+    // verification_level 是 Borsh 枚举：Partial { num_signatures: u8 } 占 2 字节，Full 占 1 字节
+    let message_offset = match data.get(40) {
+        Some(0) => 42,
+        Some(1) => 41,
+        _ => return Err(anyhow::anyhow!("Invalid Pyth verification level")),
+    };
+    let message = data
+        .get(message_offset + 32..message_offset + 32 + 28)
+        .ok_or_else(|| anyhow::anyhow!("Pyth price account is too short"))?;
+
+    let price = i64::from_le_bytes(message[0..8].try_into()?);
+    let exponent = i32::from_le_bytes(message[16..20].try_into()?);
+    let publish_time = i64::from_le_bytes(message[20..28].try_into()?);
+    if price <= 0 {
+        return Err(anyhow::anyhow!("Pyth price {} is not positive", price));
+    }
+    Ok(PythPrice {
+        price: price as f64 * 10f64.powi(exponent),
+        publish_time,
+    })
+}
+
+/// 从 Jupiter 价格 API 的响应中取出 SOL 价格
+///
+/// 响应格式为 `{ "<mint>": { "usdPrice": 123.45, ... } }`
+pub fn parse_jupiter_price(body: &serde_json::Value) -> anyhow::Result<f64> {
+    body[sol_mint().to_string()]["usdPrice"]
+        .as_f64()
+        .filter(|price| price.is_finite() && *price > 0.0)
+        .ok_or_else(|| anyhow::anyhow!("Jupiter response has no SOL price"))
+}
+
+/// 最新的 SOL/USD 价格，供收益、余额告警和仪表盘换算美元金额
+///
+/// 后台任务按 `refresh_secs` 刷新，刷新失败时保留上一次的价格，价格超过 `MAX_PRICE_AGE_SECS` 不更新时不再使用。
+pub struct SolPriceFeed {
+    source: PriceSource,
+    pyth_price_account: Pubkey,
+    jupiter_url: String,
+    refresh_interval: Duration,
+    http_client: reqwest::Client,
+    /// (价格, 获取时间)
+    price: RwLock<Option<(f64, u64)>>,
+}
+
+impl SolPriceFeed {
+    pub fn new(config: &PriceFeedConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            source: config.source,
+            pyth_price_account: Pubkey::from_str(&config.pyth_price_account())?,
+            jupiter_url: config.jupiter_url(),
+            refresh_interval: config.refresh_interval(),
+            http_client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            price: RwLock::new(None),
+        })
+    }
+
+    pub fn source(&self) -> PriceSource {
+        self.source
+    }
+
+    /// 最新的 SOL/USD 价格，还没有获取到或已过期时返回 `None`
+    pub fn price(&self) -> Option<f64> {
+        let (price, fetched_at) = (*self.price.read().unwrap())?;
+        (unix_now().saturating_sub(fetched_at) <= MAX_PRICE_AGE_SECS).then_some(price)
+    }
+
+    /// 记录一次获取到的价格
+    pub fn set_price(&self, price: f64) {
+        *self.price.write().unwrap() = Some((price, unix_now()));
+    }
+
+    /// 把 lamports 换算成美元，没有可用价格时返回 `None`
+    pub fn usd(&self, lamports: i64) -> Option<f64> {
+        self.price()
+            .map(|price| lamports as f64 / LAMPORTS_PER_SOL as f64 * price)
+    }
+
+    /// 从配置的价格来源获取一次价格
+    async fn fetch(&self, rpc_client: &Arc<RpcClient>) -> anyhow::Result<f64> {
+        match self.source {
+            PriceSource::Pyth => {
+                let rpc_client = rpc_client.clone();
+                let account = self.pyth_price_account;
+                let data = tokio::task::spawn_blocking(move || {
+                    rpc_client
+                        .get_account_data(&account)
+                        .map_err(anyhow::Error::from)
+                })
+                .await??;
+                let price = parse_pyth_price_update(&data)?;
+                let age = unix_now() as i64 - price.publish_time;
+                if age > MAX_PRICE_AGE_SECS as i64 {
+                    return Err(anyhow::anyhow!("Pyth price is {} seconds old", age));
+                }
+                Ok(price.price)
+            }
+            PriceSource::Jupiter => {
+                let body: serde_json::Value = self
+                    .http_client
+                    .get(&self.jupiter_url)
+                    .query(&[("ids", sol_mint().to_string())])
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+                parse_jupiter_price(&body)
+            }
+        }
+    }
+
+    /// 定期刷新价格，并同步到仪表盘
+    pub async fn run(self: Arc<Self>, rpc_client: Arc<RpcClient>, status: Arc<BotStatus>) {
+        loop {
+            match self.fetch(&rpc_client).await {
+                Ok(price) => {
+                    debug!("SOL price: ${:.4} ({:?})", price, self.source);
+                    self.set_price(price);
+                }
+                Err(e) => warn!("Failed to fetch SOL price from {:?}: {}", self.source, e),
+            }
+            status.set_sol_usd_price(self.price());
+            tokio::time::sleep(self.refresh_interval).await;
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::config::ProxyConfig;
+use anyhow::Context;
+
+/// 按代理配置设置 HTTP 客户端：经过代理转发请求，或把连接绑定到指定的本地地址
+///
+/// 只作用于 reqwest 发出的 HTTP 请求（RPC、区块引擎），websocket 订阅、TPU 的 QUIC 连接和
+/// Geyser gRPC 仍然直接连接。
+///
+/// # 参数
+/// * `builder` - 已设置好超时等参数的客户端构建器
+/// * `proxy` - 代理配置，为 `None` 时原样返回
+///
+/// # 错误
+/// 代理地址无法解析时返回错误
+pub fn configure(
+    builder: reqwest::ClientBuilder,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<reqwest::ClientBuilder> {
+    let Some(proxy) = proxy else {
+        return Ok(builder);
+    };
+    let mut builder = builder.local_address(proxy.local_address);
+    if let Some(url) = &proxy.url {
+        builder = builder.proxy(
+            reqwest::Proxy::all(url.as_str())
+                .with_context(|| format!("Invalid proxy {}", redact(url)))?,
+        );
+    }
+    Ok(builder)
+}
+
+/// 日志中显示的代理地址，去掉 `user:password@` 认证信息
+pub fn redact(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => match rest.rsplit_once('@') {
+            Some((_, host)) => format!("{}://***@{}", scheme, host),
+            None => url.to_string(),
+        },
+        None => url.to_string(),
+    }
+}
@@ -0,0 +1,1055 @@
+use crate::concentrated_liquidity::{
+    swap_exact_in, InitializedTick, PoolPrice, TickMath, MAX_TICK, MIN_TICK,
+};
+use crate::dex::meteora::dlmm_info::{Bin, BinArray, LbPair};
+use crate::dex::meteora::vault_info::MeteoraVaultInfo;
+use crate::dex::raydium::clmm_info::{
+    compute_tick_array_start_index, PoolState, TickArrayState, TICK_ARRAY_SIZE,
+};
+use crate::dex::whirlpool::state::{
+    TickArray, Whirlpool, TICK_ARRAY_SIZE as WHIRLPOOL_TICK_ARRAY_SIZE,
+};
+use crate::fees::{dlmm_fee_rate, whirlpool_live_fee, PoolFee, METEORA_FEE_PRECISION};
+use crate::layout::{self, MintLayout, TokenAccountLayout};
+use crate::pools::{MeteoraDAmmPool, MintPoolData};
+use crate::status::unix_now;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+
+/// `getMultipleAccounts` 每次最多请求的账户数
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// Raydium AMM v4 的交易手续费（基点），加载时没有读取到池子费率时使用
+const RAYDIUM_FEE_BPS: u64 = 25;
+
+/// Raydium CP 最常用的手续费档位（基点），加载时没有读取到 amm_config 费率时使用
+const RAYDIUM_CP_FEE_BPS: u64 = 25;
+
+/// Pump AMM 的 LP 与协议手续费之和（基点），加载时没有读取到全局配置费率时使用
+const PUMP_FEE_BPS: u64 = 25;
+
+/// Meteora DAMM v1 恒定乘积池子最常用的手续费档位（基点），加载时没有读取到池子费率时使用
+const METEORA_DAMM_FEE_BPS: u64 = 25;
+
+/// Meteora DLMM 波动累积值每偏离参考 bin 一个 bin 增加的量，也是衰减系数的分母
+const DLMM_BASIS_POINT_MAX: u64 = 10_000;
+
+/// 报价需要读取的账户类型，决定如何从账户数据中解析出数量
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuoteAccount {
+    /// 代币账户的余额
+    TokenAccount,
+    /// mint 的总供应量
+    MintSupply,
+    /// Meteora vault 当前可提取的数量，见 `MeteoraVaultInfo::unlocked_amount`
+    MeteoraVault,
+}
+
+impl QuoteAccount {
+    /// 从账户数据中解析数量
+    ///
+    /// # 参数
+    /// * `data` - 账户的原始字节数据
+    /// * `now` - 当前 unix 时间（秒），计算 Meteora vault 已解锁的收益
+    pub fn amount(self, data: &[u8], now: u64) -> Option<u64> {
+        match self {
+            QuoteAccount::TokenAccount => token_amount(data),
+            QuoteAccount::MintSupply => {
+                layout::view::<MintLayout>(data, 0).map(|mint| mint.supply.get())
+            }
+            QuoteAccount::MeteoraVault => MeteoraVaultInfo::load_checked(data)
+                .ok()
+                .map(|vault| vault.unlocked_amount(now)),
+        }
+    }
+}
+
+/// 恒定乘积池子的储备和手续费
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolReserves {
+    /// 代币 vault 余额
+    pub token: u64,
+    /// SOL vault 余额（lamports）
+    pub sol: u64,
+    /// 交易手续费（基点）
+    pub fee_bps: u64,
+}
+
+impl PoolReserves {
+    /// 用 `sol_in` lamports 买入能得到的代币数量
+    pub fn buy(&self, sol_in: u64) -> u64 {
+        constant_product_out(sol_in, self.sol, self.token, self.fee_bps)
+    }
+
+    /// 卖出 `token_in` 个代币能得到的 lamports
+    pub fn sell(&self, token_in: u64) -> u64 {
+        constant_product_out(token_in, self.token, self.sol, self.fee_bps)
+    }
+}
+
+/// 先在 `buy` 买入再在 `sell` 卖出，投入 `size` lamports 能换回的 lamports
+///
+/// Token 2022 mint 有转账手续费时，代币从买入池转到钱包、再从钱包转到卖出池各扣一次。
+pub fn round_trip_out(
+    pool_data: &MintPoolData,
+    buy: &PoolReserves,
+    sell: &PoolReserves,
+    size: u64,
+) -> u64 {
+    let mut tokens = buy.buy(size);
+    if let Some(transfer_fee) = &pool_data.transfer_fee {
+        tokens = transfer_fee.after_fee(transfer_fee.after_fee(tokens));
+    }
+    sell.sell(tokens)
+}
+
+/// 恒定乘积池子的即时价格：1 个代币最小单位值多少 lamports
+///
+/// 任一储备为 0 时无法报价。
+pub fn constant_product_price(token_reserve: u64, sol_reserve: u64) -> Option<f64> {
+    (token_reserve > 0 && sol_reserve > 0).then(|| sol_reserve as f64 / token_reserve as f64)
+}
+
+/// 恒定乘积公式下输入 `amount_in` 能换出的数量，手续费从输入中扣除
+pub fn constant_product_out(
+    amount_in: u64,
+    reserve_in: u64,
+    reserve_out: u64,
+    fee_bps: u64,
+) -> u64 {
+    let amount_in = amount_in as u128 * (10_000 - fee_bps.min(10_000)) as u128 / 10_000;
+    let denominator = reserve_in as u128 + amount_in;
+    if denominator == 0 {
+        return 0;
+    }
+    (amount_in * reserve_out as u128 / denominator) as u64
+}
+
+/// 读取代币账户的余额（Token 和 Token 2022 相同）
+pub fn token_amount(data: &[u8]) -> Option<u64> {
+    layout::view::<TokenAccountLayout>(data, 0).map(|account| account.amount.get())
+}
+
+/// 每个池子中报价货币一侧的余额（报价货币最小单位），用于加载时过滤流动性太少的池子
+///
+/// 按 `pool_vaults` 取两个 vault 中 mint 为报价货币的一个，集中流动性池子的 vault
+/// 按 mint 地址排序，报价货币不一定在第二个。Moonshot 曲线的 SOL 是曲线账户的 lamports。
+/// Meteora DAMM v1 池子的资金在多个池子共用的 vault 中，不包含在内；账户缺失的池子也不包含在内。
+///
+/// # 参数
+/// * `accounts` - 已读取的 vault 和曲线账户，见 `liquidity_accounts`
+pub fn pool_liquidity(
+    pool_data: &MintPoolData,
+    accounts: &HashMap<Pubkey, Account>,
+) -> HashMap<Pubkey, u64> {
+    let quote_amount = |vault: &Pubkey| {
+        let data = &accounts.get(vault)?.data;
+        (data.get(..32)? == pool_data.quote_mint.as_ref())
+            .then(|| token_amount(data))
+            .flatten()
+    };
+    let mut liquidity: HashMap<Pubkey, u64> = pool_data
+        .pool_vaults()
+        .into_iter()
+        .filter_map(|(pool, token_vault, sol_vault)| {
+            let amount = quote_amount(&sol_vault).or_else(|| quote_amount(&token_vault))?;
+            Some((pool, amount))
+        })
+        .collect();
+    liquidity.extend(
+        pool_data
+            .moonshot_pools
+            .iter()
+            .filter_map(|curve| Some((curve.pool, accounts.get(&curve.pool)?.lamports))),
+    );
+    liquidity
+}
+
+/// `pool_liquidity` 需要读取的账户：各池子的两个 vault 和 Moonshot 曲线账户
+pub fn liquidity_accounts(pool_data: &MintPoolData) -> Vec<Pubkey> {
+    let mut pubkeys: Vec<Pubkey> = pool_data
+        .pool_vaults()
+        .into_iter()
+        .flat_map(|(_, token_vault, sol_vault)| [token_vault, sol_vault])
+        .collect();
+    pubkeys.extend(pool_data.moonshot_pools.iter().map(|curve| curve.pool));
+    pubkeys
+}
+
+/// 报价货币一侧余额低于 `min_liquidity` 的池子：(DEX 名称, 池子地址, 余额)
+///
+/// 没有读取到余额的池子不算在内。
+pub fn shallow_pools(
+    pool_data: &MintPoolData,
+    liquidity: &HashMap<Pubkey, u64>,
+    min_liquidity: u64,
+) -> Vec<(&'static str, Pubkey, u64)> {
+    pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(dex, pool)| {
+            let amount = *liquidity.get(&pool)?;
+            (amount < min_liquidity).then_some((dex, pool, amount))
+        })
+        .collect()
+}
+
+/// 储备即价格的恒定乘积池子：(池子, 代币 vault, SOL vault, 手续费)
+///
+/// 目前只处理 Raydium AMM、Raydium CP、Pump；Meteora DAMM v1 的储备要通过 vault 份额换算，单独处理。
+/// 集中流动性和 bin 类池子的 vault 余额不能反映价格，不出现在结果中。
+/// 两侧都不是报价货币的池子（`non_quote_pools`）的储备不以报价货币计价，同样不出现在结果中。
+fn constant_product_pools(pool_data: &MintPoolData) -> Vec<(Pubkey, Pubkey, Pubkey, u64)> {
+    let mut pools: Vec<(Pubkey, Pubkey, Pubkey, u64)> = Vec::new();
+    pools.extend(pool_data.raydium_pools.iter().map(|p| {
+        let fee_bps = pool_data.fee_bps(&p.pool, RAYDIUM_FEE_BPS);
+        (p.pool, p.token_vault, p.sol_vault, fee_bps)
+    }));
+    pools.extend(pool_data.raydium_cp_pools.iter().map(|p| {
+        let fee_bps = pool_data.fee_bps(&p.pool, RAYDIUM_CP_FEE_BPS);
+        (p.pool, p.token_vault, p.sol_vault, fee_bps)
+    }));
+    pools.extend(pool_data.pump_pools.iter().map(|p| {
+        let fee_bps = pool_data.fee_bps(&p.pool, PUMP_FEE_BPS);
+        (p.pool, p.token_vault, p.sol_vault, fee_bps)
+    }));
+    pools.retain(|(pool, ..)| !pool_data.non_quote_pools.contains_key(pool));
+    pools
+}
+
+/// 以报价货币计价、需要读取储备的 Meteora DAMM v1 池子
+fn meteora_damm_pools(pool_data: &MintPoolData) -> impl Iterator<Item = &MeteoraDAmmPool> {
+    pool_data
+        .meteora_damm_pools
+        .iter()
+        .filter(|pool| !pool_data.non_quote_pools.contains_key(&pool.pool))
+}
+
+/// 报价需要读取的所有账户
+///
+/// 恒定乘积池子读取两个 vault 的余额；Meteora DAMM v1 池子的资金存放在共用的 Meteora vault 中，
+/// 还要读取 vault 账户、vault 的 LP mint 和池子持有的 LP 代币账户，见 `reserves_from_balances`。
+pub fn quote_accounts(pool_data: &MintPoolData) -> Vec<(Pubkey, QuoteAccount)> {
+    let mut accounts: Vec<(Pubkey, QuoteAccount)> = constant_product_pools(pool_data)
+        .iter()
+        .flat_map(|(_, token_vault, sol_vault, _)| {
+            [
+                (*token_vault, QuoteAccount::TokenAccount),
+                (*sol_vault, QuoteAccount::TokenAccount),
+            ]
+        })
+        .collect();
+    for pool in meteora_damm_pools(pool_data) {
+        accounts.extend([
+            (pool.token_x_vault, QuoteAccount::MeteoraVault),
+            (pool.token_sol_vault, QuoteAccount::MeteoraVault),
+            (pool.token_x_lp_mint, QuoteAccount::MintSupply),
+            (pool.token_sol_lp_mint, QuoteAccount::MintSupply),
+            (pool.token_x_pool_lp, QuoteAccount::TokenAccount),
+            (pool.token_sol_pool_lp, QuoteAccount::TokenAccount),
+        ]);
+    }
+    accounts
+}
+
+/// 池子持有的 vault LP 对应的代币数量：LP 余额 × vault 可提取数量 / LP 总量
+fn vault_share(pool_lp: u64, vault_amount: u64, lp_supply: u64) -> Option<u64> {
+    if lp_supply == 0 {
+        return None;
+    }
+    Some((pool_lp as u128 * vault_amount as u128 / lp_supply as u128) as u64)
+}
+
+/// 由已知的账户数量组合出池子储备，缺少任一数量的池子不出现在结果中
+///
+/// # 参数
+/// * `balance` - `quote_accounts` 中账户按类型解析出的数量
+pub fn reserves_from_balances(
+    pool_data: &MintPoolData,
+    balance: impl Fn(&Pubkey) -> Option<u64>,
+) -> HashMap<Pubkey, PoolReserves> {
+    let mut reserves: HashMap<Pubkey, PoolReserves> = constant_product_pools(pool_data)
+        .into_iter()
+        .filter_map(|(pool, token_vault, sol_vault, fee_bps)| {
+            let reserves = PoolReserves {
+                token: balance(&token_vault)?,
+                sol: balance(&sol_vault)?,
+                fee_bps,
+            };
+            Some((pool, reserves))
+        })
+        .collect();
+    reserves.extend(meteora_damm_pools(pool_data).filter_map(|pool| {
+        let pool_reserves = PoolReserves {
+            token: vault_share(
+                balance(&pool.token_x_pool_lp)?,
+                balance(&pool.token_x_vault)?,
+                balance(&pool.token_x_lp_mint)?,
+            )?,
+            sol: vault_share(
+                balance(&pool.token_sol_pool_lp)?,
+                balance(&pool.token_sol_vault)?,
+                balance(&pool.token_sol_lp_mint)?,
+            )?,
+            fee_bps: pool_data.fee_bps(&pool.pool, METEORA_DAMM_FEE_BPS),
+        };
+        Some((pool.pool, pool_reserves))
+    }));
+    reserves
+}
+
+/// 读取 `quote_accounts` 中的账户获取池子储备
+pub fn fetch_reserves(
+    rpc_client: &RpcClient,
+    pool_data: &MintPoolData,
+) -> anyhow::Result<HashMap<Pubkey, PoolReserves>> {
+    let quote_accounts = quote_accounts(pool_data);
+    let now = unix_now();
+    let mut balances = HashMap::new();
+    for chunk in quote_accounts.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let pubkeys: Vec<Pubkey> = chunk.iter().map(|(pubkey, _)| *pubkey).collect();
+        let accounts = rpc_client.get_multiple_accounts(&pubkeys)?;
+        for ((pubkey, kind), account) in chunk.iter().zip(accounts) {
+            if let Some(amount) = account.and_then(|account| kind.amount(&account.data, now)) {
+                balances.insert(*pubkey, amount);
+            }
+        }
+    }
+
+    Ok(reserves_from_balances(pool_data, |pubkey| {
+        balances.get(pubkey).copied()
+    }))
+}
+
+/// 手续费随波动变化、报价前需要重新读取的池子：(池子, oracle)
+///
+/// 目前是 Whirlpool：启用自适应手续费的池子在 oracle 账户中记录波动状态，费率由池子的静态费率、
+/// 当前 tick 和 oracle 共同决定。没有启用的池子没有 oracle 账户，读取不到时沿用加载时的费率。
+pub fn live_fee_accounts(pool_data: &MintPoolData) -> Vec<(Pubkey, Pubkey)> {
+    pool_data
+        .whirlpool_pools
+        .iter()
+        .map(|pool| (pool.pool, pool.oracle))
+        .collect()
+}
+
+/// 由读取到的池子和 oracle 账户计算实时手续费，账户缺失或没有启用自适应手续费的池子不出现在结果中
+///
+/// # 参数
+/// * `pools` - `live_fee_accounts` 返回的 (池子, oracle)
+/// * `account` - 按地址查找已读取的账户数据
+/// * `now` - 当前 unix 时间（秒）
+pub fn live_fees<'a>(
+    pools: &[(Pubkey, Pubkey)],
+    account: impl Fn(&Pubkey) -> Option<&'a [u8]>,
+    now: u64,
+) -> HashMap<Pubkey, PoolFee> {
+    pools
+        .iter()
+        .filter_map(|(pool, oracle)| {
+            let fee = whirlpool_live_fee(account(pool)?, account(oracle)?, now)?;
+            Some((*pool, fee))
+        })
+        .collect()
+}
+
+/// 读取 `live_fee_accounts` 中的账户计算实时手续费，没有需要读取的池子时不发请求
+pub fn fetch_live_fees(
+    rpc_client: &RpcClient,
+    pool_data: &MintPoolData,
+) -> anyhow::Result<HashMap<Pubkey, PoolFee>> {
+    let pools = live_fee_accounts(pool_data);
+    let pubkeys: Vec<Pubkey> = pools
+        .iter()
+        .flat_map(|(pool, oracle)| [*pool, *oracle])
+        .collect();
+    let mut accounts: HashMap<Pubkey, Account> = HashMap::new();
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let fetched = rpc_client.get_multiple_accounts(chunk)?;
+        for (pubkey, account) in chunk.iter().zip(fetched) {
+            if let Some(account) = account {
+                accounts.insert(*pubkey, account);
+            }
+        }
+    }
+    Ok(live_fees(
+        &pools,
+        |pubkey| accounts.get(pubkey).map(|account| account.data.as_slice()),
+        unix_now(),
+    ))
+}
+
+/// 由储备计算即时价格（lamports / 代币最小单位）
+pub fn spot_prices(reserves: &HashMap<Pubkey, PoolReserves>) -> HashMap<Pubkey, f64> {
+    reserves
+        .iter()
+        .filter_map(|(pool, r)| Some((*pool, constant_product_price(r.token, r.sol)?)))
+        .collect()
+}
+
+/// 根据 vault 余额为池子报价，见 `fetch_reserves`
+///
+/// # 返回值
+/// 池子地址 -> 即时价格（lamports / 代币最小单位）
+pub fn fetch_spot_prices(
+    rpc_client: &RpcClient,
+    pool_data: &MintPoolData,
+) -> anyhow::Result<HashMap<Pubkey, f64>> {
+    Ok(spot_prices(&fetch_reserves(rpc_client, pool_data)?))
+}
+
+/// 每个池子价格相对所有已报价池子中位价的偏离程度
+///
+/// 偏离越大，该池子作为买入或卖出一方的套利空间越大；用于交易放不下所有池子时决定保留哪些。
+pub fn pool_edges(prices: &HashMap<Pubkey, f64>) -> HashMap<Pubkey, f64> {
+    let mut sorted: Vec<f64> = prices.values().copied().collect();
+    if sorted.is_empty() {
+        return HashMap::new();
+    }
+    sorted.sort_by(f64::total_cmp);
+    let median = sorted[sorted.len() / 2];
+
+    prices
+        .iter()
+        .map(|(pool, price)| (*pool, (price / median - 1.0).abs()))
+        .collect()
+}
+
+/// 买入价最低和卖出价最高的两个不同池子之间的价差，例如 0.01 表示 1%
+pub fn best_spread(pool_data: &MintPoolData, prices: &HashMap<Pubkey, f64>) -> Option<f64> {
+    let quoted: Vec<(Pubkey, f64)> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(_, pool)| prices.get(&pool).map(|price| (pool, *price)))
+        .collect();
+
+    quoted
+        .iter()
+        .filter(|(pool, _)| pool_data.direction(pool).can_buy())
+        .flat_map(|(buy, buy_price)| {
+            quoted
+                .iter()
+                .filter(move |(sell, _)| sell != buy && pool_data.direction(sell).can_sell())
+                .map(move |(_, sell_price)| sell_price / buy_price - 1.0)
+        })
+        .max_by(f64::total_cmp)
+}
+
+/// 按当前价差选出最有希望的 `top_n` 个池子
+///
+/// 已报价的池子按价格排序后，交替从最便宜的一端取可以买入的池子、从最贵的一端取可以卖出的池子；
+/// 名额有剩余时再按配置顺序补充未报价的池子。
+///
+/// # 返回值
+/// 只包含选中池子的池数据，`pool_edges` 按本次报价更新
+pub fn select_top_pools(
+    pool_data: &MintPoolData,
+    prices: &HashMap<Pubkey, f64>,
+    top_n: usize,
+) -> MintPoolData {
+    let mut quoted: Vec<(Pubkey, f64)> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(_, pool)| prices.get(&pool).map(|price| (pool, *price)))
+        .collect();
+    quoted.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut buys = quoted
+        .iter()
+        .map(|(pool, _)| *pool)
+        .filter(|pool| pool_data.direction(pool).can_buy());
+    let mut sells = quoted
+        .iter()
+        .rev()
+        .map(|(pool, _)| *pool)
+        .filter(|pool| pool_data.direction(pool).can_sell());
+
+    let mut selected: Vec<Pubkey> = Vec::new();
+    let (mut buys_done, mut sells_done) = (false, false);
+    while selected.len() < top_n && !(buys_done && sells_done) {
+        match buys.find(|pool| !selected.contains(pool)) {
+            Some(pool) => selected.push(pool),
+            None => buys_done = true,
+        }
+        if selected.len() >= top_n {
+            break;
+        }
+        match sells.find(|pool| !selected.contains(pool)) {
+            Some(pool) => selected.push(pool),
+            None => sells_done = true,
+        }
+    }
+
+    for (_, pool) in pool_data.pool_addresses() {
+        if selected.len() >= top_n {
+            break;
+        }
+        if !prices.contains_key(&pool) && !selected.contains(&pool) {
+            selected.push(pool);
+        }
+    }
+
+    let mut top = pool_data.clone();
+    top.retain_pools(|pool| selected.contains(pool));
+    top.pool_edges = pool_edges(prices);
+    top
+}
+
+/// 某个交易规模下的最优买卖池子及预期收益
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizedQuote {
+    /// 投入的 SOL（lamports）
+    pub size: u64,
+    pub buy_pool: Pubkey,
+    pub sell_pool: Pubkey,
+    /// 扣除池子手续费后的预期收益（lamports），不含交易手续费
+    pub profit: i64,
+}
+
+/// 在候选交易规模中选出预期收益最大的一个
+///
+/// 对每个规模，在已报价的池子中找出收益最大的买入/卖出池子对（遵守方向限制）。
+/// 价格偏离往往只在小规模下有利可图，因此规模越大并不一定越好。
+///
+/// # 参数
+/// * `pool_data` - 池子数据，用于方向限制
+/// * `reserves` - 池子储备
+/// * `sizes` - 候选交易规模（lamports）
+///
+/// # 返回值
+/// 收益最大的规模，没有可报价的池子对时返回 `None`；收益可能为负
+pub fn best_trade_size(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    sizes: &[u64],
+) -> Option<SizedQuote> {
+    let quoted: Vec<(Pubkey, PoolReserves)> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(_, pool)| reserves.get(&pool).map(|r| (pool, *r)))
+        .collect();
+
+    let mut best: Option<SizedQuote> = None;
+    for &size in sizes {
+        for (buy_pool, buy) in quoted
+            .iter()
+            .filter(|(pool, _)| pool_data.direction(pool).can_buy())
+        {
+            for (sell_pool, sell) in quoted
+                .iter()
+                .filter(|(pool, _)| pool != buy_pool && pool_data.direction(pool).can_sell())
+            {
+                let profit = round_trip_out(pool_data, buy, sell, size) as i64 - size as i64;
+                if best.is_none_or(|best| profit > best.profit) {
+                    best = Some(SizedQuote {
+                        size,
+                        buy_pool: *buy_pool,
+                        sell_pool: *sell_pool,
+                        profit,
+                    });
+                }
+            }
+        }
+    }
+    best
+}
+
+/// 两个恒定乘积池子组成的路线（先在 `buy` 买入，再在 `sell` 卖出）的最优投入
+///
+/// 两次兑换合起来是 `out(x) = K·x / (A + B·x)`，其中
+/// `K = γ1·γ2·b·d`、`A = a·c`、`B = γ1·(c + γ2·b)`，
+/// a、b 为买入池的 SOL、代币储备，c、d 为卖出池的代币、SOL 储备，γ 为扣除手续费后的比例。
+/// 收益 `out(x) - x` 在 `x* = (√(K·A) - A) / B` 处取得最大值，`K <= A` 时没有套利空间。
+///
+/// # 参数
+/// * `max_input` - 投入上限（lamports）
+///
+/// # 返回值
+/// 最优投入（lamports），没有套利空间时返回 `None`
+pub fn optimal_cpmm_input(buy: &PoolReserves, sell: &PoolReserves, max_input: u64) -> Option<u64> {
+    let gamma = |fee_bps: u64| (10_000 - fee_bps.min(10_000)) as f64 / 10_000.0;
+    let (g1, g2) = (gamma(buy.fee_bps), gamma(sell.fee_bps));
+    let (a, b) = (buy.sol as f64, buy.token as f64);
+    let (c, d) = (sell.token as f64, sell.sol as f64);
+
+    let k = g1 * g2 * b * d;
+    let a_term = a * c;
+    let b_term = g1 * (c + g2 * b);
+    if k <= a_term || b_term <= 0.0 {
+        return None;
+    }
+
+    let optimal = ((k * a_term).sqrt() - a_term) / b_term;
+    Some((optimal as u64).clamp(1, max_input.max(1)))
+}
+
+/// 交易规模的选择方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TradeSizing {
+    /// 从固定的候选规模（lamports）中选择
+    Fixed(Vec<u64>),
+    /// 对每组池子求解最优投入，不超过 `max_input` lamports
+    Optimal { max_input: u64 },
+}
+
+/// 按规模选择方式找出预期收益最大的路线
+///
+/// 执行程序的 Swap 指令没有投入数量字段，只有直接兑换按求出的规模构建 swap 指令；
+/// 其他情况下规模只决定这一轮是否发送。
+pub fn best_route(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    sizing: &TradeSizing,
+) -> Option<SizedQuote> {
+    match sizing {
+        TradeSizing::Fixed(sizes) => best_trade_size(pool_data, reserves, sizes),
+        TradeSizing::Optimal { max_input } => optimal_trade(pool_data, reserves, *max_input),
+    }
+}
+
+/// 对所有买入/卖出池子对求解最优投入，返回收益最大的一组
+pub fn optimal_trade(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    max_input: u64,
+) -> Option<SizedQuote> {
+    let quoted: Vec<(Pubkey, PoolReserves)> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(_, pool)| reserves.get(&pool).map(|r| (pool, *r)))
+        .collect();
+
+    let mut best: Option<SizedQuote> = None;
+    for (buy_pool, buy) in quoted
+        .iter()
+        .filter(|(pool, _)| pool_data.direction(pool).can_buy())
+    {
+        for (sell_pool, sell) in quoted
+            .iter()
+            .filter(|(pool, _)| pool != buy_pool && pool_data.direction(pool).can_sell())
+        {
+            let Some(quote) =
+                optimal_pair_quote(pool_data, (buy_pool, buy), (sell_pool, sell), max_input)
+            else {
+                continue;
+            };
+            if best.is_none_or(|best| quote.profit > best.profit) {
+                best = Some(quote);
+            }
+        }
+    }
+    best
+}
+
+/// 一组买入/卖出池子的最优投入及收益，没有套利空间时返回 `None`
+fn optimal_pair_quote(
+    pool_data: &MintPoolData,
+    (buy_pool, buy): (&Pubkey, &PoolReserves),
+    (sell_pool, sell): (&Pubkey, &PoolReserves),
+    max_input: u64,
+) -> Option<SizedQuote> {
+    // 两次转账手续费按比例并入卖出池的手续费求解，忽略单笔上限
+    let sell_with_transfer_fee = match &pool_data.transfer_fee {
+        Some(transfer_fee) => {
+            let kept = (10_000 - transfer_fee.basis_points.min(10_000) as u64).pow(2);
+            let sell_kept = 10_000 - sell.fee_bps.min(10_000);
+            PoolReserves {
+                fee_bps: 10_000 - sell_kept * kept / 100_000_000,
+                ..*sell
+            }
+        }
+        None => *sell,
+    };
+    let size = optimal_cpmm_input(buy, &sell_with_transfer_fee, max_input)?;
+    // 用整数报价复核，避免浮点误差
+    let profit = round_trip_out(pool_data, buy, sell, size) as i64 - size as i64;
+    Some(SizedQuote {
+        size,
+        buy_pool: *buy_pool,
+        sell_pool: *sell_pool,
+        profit,
+    })
+}
+
+/// 每组买入/卖出池子（遵守方向限制）按规模选择方式得到的最优报价，只保留有收益的组合
+///
+/// 与 `best_route` 不同，结果包含所有有收益的池子对，按收益从高到低排列。
+pub fn profitable_routes(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    sizing: &TradeSizing,
+) -> Vec<SizedQuote> {
+    let quoted: Vec<(Pubkey, PoolReserves)> = pool_data
+        .pool_addresses()
+        .into_iter()
+        .filter_map(|(_, pool)| reserves.get(&pool).map(|r| (pool, *r)))
+        .collect();
+
+    let mut routes = Vec::new();
+    for (buy_pool, buy) in quoted
+        .iter()
+        .filter(|(pool, _)| pool_data.direction(pool).can_buy())
+    {
+        for (sell_pool, sell) in quoted
+            .iter()
+            .filter(|(pool, _)| pool != buy_pool && pool_data.direction(pool).can_sell())
+        {
+            let quote = match sizing {
+                TradeSizing::Fixed(sizes) => sizes
+                    .iter()
+                    .map(|&size| SizedQuote {
+                        size,
+                        buy_pool: *buy_pool,
+                        sell_pool: *sell_pool,
+                        profit: round_trip_out(pool_data, buy, sell, size) as i64 - size as i64,
+                    })
+                    .max_by_key(|quote| quote.profit),
+                TradeSizing::Optimal { max_input } => {
+                    optimal_pair_quote(pool_data, (buy_pool, buy), (sell_pool, sell), *max_input)
+                }
+            };
+            routes.extend(quote.filter(|quote| quote.profit > 0));
+        }
+    }
+    routes.sort_by_key(|route| std::cmp::Reverse(route.profit));
+    routes
+}
+
+/// Meteora DLMM 兑换报价
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DlmmQuote {
+    /// 换出的数量
+    pub amount_out: u64,
+    /// 从输入中扣除的手续费
+    pub fee: u64,
+    /// 从 active bin 开始跨过的 bin 数量，决定交易需要传入多少个 bin array
+    pub bins_crossed: u32,
+}
+
+/// `amount × price >> 64`，price 为 Q64.64 定点数
+fn dlmm_mul_shr(amount: u128, price: u128, round_up: bool) -> Option<u128> {
+    let high = amount.checked_mul(price >> 64)?;
+    let low = amount.checked_mul(price & u64::MAX as u128)?;
+    let result = high.checked_add(low >> 64)?;
+    if round_up && low & u64::MAX as u128 != 0 {
+        result.checked_add(1)
+    } else {
+        Some(result)
+    }
+}
+
+/// `(amount << 64) / price`，price 为 Q64.64 定点数且不为 0
+fn dlmm_shl_div(amount: u64, price: u128, round_up: bool) -> u128 {
+    let shifted = (amount as u128) << 64;
+    if round_up {
+        shifted.div_ceil(price)
+    } else {
+        shifted / price
+    }
+}
+
+/// 在单个 bin 内兑换，与链上 `Bin::swap` 的取整方式一致
+///
+/// # 返回值
+/// `(消耗的输入（含手续费）, 换出的数量, 手续费)`，bin 中没有可换出的代币时返回 `None`
+fn dlmm_swap_bin(
+    bin: &Bin,
+    amount_in: u64,
+    fee_rate: u128,
+    swap_for_y: bool,
+) -> Option<(u64, u64, u64)> {
+    let max_amount_out = if swap_for_y {
+        bin.amount_y
+    } else {
+        bin.amount_x
+    };
+    if max_amount_out == 0 || bin.price == 0 {
+        return None;
+    }
+
+    // 换空这个 bin 需要的输入，手续费按扣费后的输入反推
+    let max_amount_in = if swap_for_y {
+        dlmm_shl_div(max_amount_out, bin.price, true)
+    } else {
+        dlmm_mul_shr(max_amount_out as u128, bin.price, true).unwrap_or(u128::MAX)
+    };
+    let max_fee = max_amount_in
+        .saturating_mul(fee_rate)
+        .div_ceil(METEORA_FEE_PRECISION - fee_rate);
+    let max_amount_in = max_amount_in.saturating_add(max_fee);
+    if amount_in as u128 >= max_amount_in {
+        return Some((max_amount_in as u64, max_amount_out, max_fee as u64));
+    }
+
+    let fee = (amount_in as u128 * fee_rate).div_ceil(METEORA_FEE_PRECISION);
+    let amount_in_after_fee = amount_in - fee as u64;
+    let amount_out = if swap_for_y {
+        dlmm_mul_shr(amount_in_after_fee as u128, bin.price, false)?
+    } else {
+        dlmm_shl_div(amount_in_after_fee, bin.price, false)
+    };
+    Some((
+        amount_in,
+        amount_out.min(max_amount_out as u128) as u64,
+        fee as u64,
+    ))
+}
+
+/// Meteora DLMM 输入 `amount_in` 能换出的数量
+///
+/// 从 `active_id` 开始逐个 bin 兑换，直到输入耗尽：每个 bin 按其价格成交，
+/// 跨过的 bin 越多，波动累积值越大，动态手续费越高（与链上 `swap` 指令相同）。
+///
+/// # 参数
+/// * `lb_pair` - 池子账户
+/// * `bin_arrays` - 已加载的 bin array，顺序不限
+/// * `amount_in` - 输入数量（含手续费）
+/// * `swap_for_y` - `true` 表示输入 X 换出 Y，`false` 表示输入 Y 换出 X
+/// * `now` - 当前 unix 时间（秒），决定波动参考值是否衰减
+///
+/// # 返回值
+/// 换出数量、手续费和跨过的 bin 数量；
+/// 已加载的 bin array 或池子的 bin 范围不足以成交全部输入时返回 `None`
+pub fn dlmm_get_amount_out(
+    lb_pair: &LbPair,
+    bin_arrays: &[BinArray],
+    amount_in: u64,
+    swap_for_y: bool,
+    now: u64,
+) -> Option<DlmmQuote> {
+    let parameters = lb_pair.parameters;
+    let mut index_reference = lb_pair.v_parameters.index_reference;
+    let mut volatility_reference = lb_pair.v_parameters.volatility_reference;
+    let elapsed = (now as i64).saturating_sub(lb_pair.v_parameters.last_update_timestamp);
+    if elapsed >= parameters.filter_period as i64 {
+        index_reference = lb_pair.active_id;
+        volatility_reference = if elapsed < parameters.decay_period as i64 {
+            (lb_pair.v_parameters.volatility_accumulator as u64
+                * parameters.reduction_factor as u64
+                / DLMM_BASIS_POINT_MAX) as u32
+        } else {
+            0
+        };
+    }
+
+    let mut quote = DlmmQuote::default();
+    let mut active_id = lb_pair.active_id;
+    let mut amount_left = amount_in;
+    while amount_left > 0 {
+        let bin = bin_arrays.iter().find_map(|array| array.bin(active_id))?;
+        let delta_id = (index_reference as i64 - active_id as i64).unsigned_abs();
+        let volatility_accumulator = (volatility_reference as u64
+            + delta_id * DLMM_BASIS_POINT_MAX)
+            .min(parameters.max_volatility_accumulator as u64);
+        let fee_rate = dlmm_fee_rate(lb_pair, volatility_accumulator as u32);
+
+        if let Some((consumed, amount_out, fee)) =
+            dlmm_swap_bin(bin, amount_left, fee_rate, swap_for_y)
+        {
+            amount_left -= consumed;
+            quote.amount_out = quote.amount_out.checked_add(amount_out)?;
+            quote.fee += fee;
+        }
+
+        if amount_left > 0 {
+            active_id = if swap_for_y {
+                active_id.checked_sub(1)?
+            } else {
+                active_id.checked_add(1)?
+            };
+            if active_id < parameters.min_bin_id || active_id > parameters.max_bin_id {
+                return None;
+            }
+            quote.bins_crossed += 1;
+        }
+    }
+    Some(quote)
+}
+
+/// 集中流动性池子的兑换报价
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClmmQuote {
+    /// 换出的数量
+    pub amount_out: u64,
+    /// 从输入中扣除的手续费
+    pub fee: u64,
+    /// 从当前 tick array 开始跨过的 tick array 数量，决定交易需要传入多少个 tick array
+    pub tick_arrays_crossed: u32,
+}
+
+/// Raydium CLMM 输入 `amount_in` 能换出的数量
+///
+/// 从当前价格开始逐个 tick 区间兑换，穿过已初始化的 tick 时更新流动性，直到输入耗尽。
+/// 池子账户中的 tick array bitmap 用来确认没有遗漏：兑换方向上遇到已初始化但没有加载的 tick array
+/// （或超出 bitmap 范围、需要扩展账户的 tick array）时停止，输入仍未耗尽则无法报价。
+///
+/// # 参数
+/// * `pool` - 池子账户
+/// * `fee_rate` - `amm_config` 的交易费率（百万分之一），见 `raydium_clmm_fee_rate`
+/// * `tick_arrays` - 已加载的 tick array，顺序不限
+/// * `amount_in` - 输入数量（含手续费）
+/// * `zero_for_one` - `true` 表示输入 token 0 换出 token 1
+///
+/// # 返回值
+/// 换出数量、手续费和跨过的 tick array 数量；已加载的 tick array 不足以成交全部输入时返回 `None`
+pub fn raydium_clmm_get_amount_out(
+    pool: &PoolState,
+    fee_rate: u32,
+    tick_arrays: &[TickArrayState],
+    amount_in: u64,
+    zero_for_one: bool,
+) -> Option<ClmmQuote> {
+    let ticks_in_array = TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    if ticks_in_array == 0 {
+        return None;
+    }
+    let loaded: HashSet<i32> = tick_arrays
+        .iter()
+        .map(|array| array.start_tick_index)
+        .collect();
+
+    // 兑换方向上第一个已初始化但没有加载的 tick array 之前的 tick 都是完整的
+    let current_start = compute_tick_array_start_index(pool.tick_current, pool.tick_spacing);
+    let mut start = current_start;
+    let boundary = loop {
+        if zero_for_one && start + ticks_in_array <= MIN_TICK {
+            break MIN_TICK;
+        }
+        if !zero_for_one && start > MAX_TICK {
+            break MAX_TICK;
+        }
+        if !loaded.contains(&start) && pool.is_tick_array_initialized(start) != Some(false) {
+            break if zero_for_one {
+                start + ticks_in_array
+            } else {
+                start
+            };
+        }
+        start += if zero_for_one {
+            -ticks_in_array
+        } else {
+            ticks_in_array
+        };
+    };
+
+    let ticks: Vec<InitializedTick> = tick_arrays
+        .iter()
+        .flat_map(|array| array.ticks.iter())
+        .filter(|tick| tick.is_initialized())
+        .map(|tick| InitializedTick {
+            tick: tick.tick,
+            liquidity_net: tick.liquidity_net,
+        })
+        .collect();
+    let price = PoolPrice {
+        sqrt_price_x64: pool.sqrt_price_x64,
+        tick_current: pool.tick_current,
+        liquidity: pool.liquidity,
+    };
+    let result = swap_exact_in(
+        TickMath::RaydiumClmm,
+        price,
+        &ticks,
+        boundary,
+        amount_in,
+        zero_for_one,
+        fee_rate,
+    )?;
+    let last_start = compute_tick_array_start_index(result.last_tick, pool.tick_spacing);
+    Some(ClmmQuote {
+        amount_out: result.amount_out,
+        fee: result.fee,
+        tick_arrays_crossed: ((last_start - current_start) / ticks_in_array).unsigned_abs(),
+    })
+}
+
+/// Whirlpool 输入 `amount_in` 能换出的数量
+///
+/// 与 [`raydium_clmm_get_amount_out`] 相同按 tick 遍历，sqrt price 和 token A 数量使用 Whirlpool 的取整方式。
+/// Whirlpool 没有 tick array bitmap，只信任从当前 tick array 开始在兑换方向上连续加载的 tick array；
+/// 启用自适应手续费的池子在 swap 途中跨过 tick 组后费率还会上升，这里按开始时的费率计算，结果略偏高。
+///
+/// # 参数
+/// * `whirlpool` - 池子账户
+/// * `fee_rate` - 总费率（百万分之一），见 `whirlpool_fee_rate`
+/// * `tick_arrays` - 已加载的 tick array，顺序不限
+/// * `amount_in` - 输入数量（含手续费）
+/// * `a_to_b` - `true` 表示输入 token A 换出 token B
+///
+/// # 返回值
+/// 换出数量、手续费和跨过的 tick array 数量；已加载的 tick array 不足以成交全部输入时返回 `None`
+pub fn whirlpool_get_amount_out(
+    whirlpool: &Whirlpool,
+    fee_rate: u32,
+    tick_arrays: &[TickArray],
+    amount_in: u64,
+    a_to_b: bool,
+) -> Option<ClmmQuote> {
+    let tick_spacing = whirlpool.tick_spacing as i32;
+    let ticks_in_array = WHIRLPOOL_TICK_ARRAY_SIZE as i32 * tick_spacing;
+    if ticks_in_array == 0 {
+        return None;
+    }
+    let loaded: HashSet<i32> = tick_arrays
+        .iter()
+        .map(|array| array.start_tick_index)
+        .collect();
+
+    // 连续加载的 tick array 覆盖的范围边界
+    let current_start = whirlpool.tick_current_index.div_euclid(ticks_in_array) * ticks_in_array;
+    let mut start = current_start;
+    let boundary = loop {
+        if !loaded.contains(&start) {
+            break if a_to_b {
+                start + ticks_in_array
+            } else {
+                start
+            };
+        }
+        if (a_to_b && start <= MIN_TICK) || (!a_to_b && start + ticks_in_array > MAX_TICK) {
+            break if a_to_b { MIN_TICK } else { MAX_TICK };
+        }
+        start += if a_to_b {
+            -ticks_in_array
+        } else {
+            ticks_in_array
+        };
+    };
+
+    let ticks: Vec<InitializedTick> = tick_arrays
+        .iter()
+        .flat_map(|array| {
+            array
+                .ticks
+                .iter()
+                .zip((array.start_tick_index..).step_by(tick_spacing as usize))
+        })
+        .filter(|(tick, _)| tick.initialized)
+        .map(|(tick, index)| InitializedTick {
+            tick: index,
+            liquidity_net: tick.liquidity_net,
+        })
+        .collect();
+    let price = PoolPrice {
+        sqrt_price_x64: whirlpool.sqrt_price,
+        tick_current: whirlpool.tick_current_index,
+        liquidity: whirlpool.liquidity,
+    };
+    let result = swap_exact_in(
+        TickMath::Whirlpool,
+        price,
+        &ticks,
+        boundary,
+        amount_in,
+        a_to_b,
+        fee_rate,
+    )?;
+    let last_start = result.last_tick.div_euclid(ticks_in_array) * ticks_in_array;
+    Some(ClmmQuote {
+        amount_out: result.amount_out,
+        fee: result.fee,
+        tick_arrays_crossed: ((last_start - current_start) / ticks_in_array).unsigned_abs(),
+    })
+}
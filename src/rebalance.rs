@@ -0,0 +1,215 @@
+use crate::config::RebalanceConfig;
+use crate::constants::CashMint;
+use crate::inline_swap::{supported_reserves, swap_instruction, SwapSide};
+use crate::pools::MintPoolData;
+use crate::quote::{self, token_amount, PoolReserves};
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::Transaction;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info};
+
+/// 卖出交易的计算单元上限，只有一条 swap 指令
+const SALE_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+
+/// 把钱包中剩余的代币卖回报价货币的一次兑换
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sale {
+    pub pool: Pubkey,
+    /// 卖出的代币数量，即钱包中的全部余额
+    pub amount_in: u64,
+    /// 按当前储备报价能换到的报价货币
+    pub expected_out: u64,
+    /// 扣除允许的滑点后至少换到的报价货币
+    pub minimum_out: u64,
+}
+
+/// 按报价选出卖出剩余代币收益最大的池子
+///
+/// 只在支持直接兑换的池子（见 `inline_swap::supports`）中选择；代币有转账手续费时按池子实际收到的数量报价。
+///
+/// # 参数
+/// * `balance` - 钱包代币账户余额
+/// * `min_value` - 报价低于该值（报价货币最小单位）时不卖出
+/// * `slippage_bps` - 允许的滑点（基点）
+///
+/// # 返回值
+/// 没有可用的池子或余额价值低于 `min_value` 时返回 `None`
+pub fn plan_sale(
+    pool_data: &MintPoolData,
+    reserves: &HashMap<Pubkey, PoolReserves>,
+    balance: u64,
+    min_value: u64,
+    slippage_bps: u64,
+) -> Option<Sale> {
+    if balance == 0 {
+        return None;
+    }
+    let received = pool_data
+        .transfer_fee
+        .map_or(balance, |fee| fee.after_fee(balance));
+    let (pool, expected_out) = supported_reserves(pool_data, reserves)
+        .into_iter()
+        .map(|(pool, reserves)| (pool, reserves.sell(received)))
+        .max_by_key(|(pool, out)| (*out, *pool))?;
+    if expected_out == 0 || expected_out < min_value {
+        return None;
+    }
+    let minimum_out =
+        (expected_out as u128 * 10_000u128.saturating_sub(slippage_bps as u128) / 10_000) as u64;
+    Some(Sale {
+        pool,
+        amount_in: balance,
+        expected_out,
+        minimum_out,
+    })
+}
+
+/// 卖出交易的指令：计算单元限制、计算单元价格和一条 swap 指令
+///
+/// # 错误
+/// 池子不在 `pool_data` 中或不支持直接兑换时返回错误
+pub fn sale_instructions(
+    pool_data: &MintPoolData,
+    wallet: &Pubkey,
+    sale: &Sale,
+    compute_unit_price: u64,
+) -> anyhow::Result<Vec<Instruction>> {
+    Ok(vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(SALE_COMPUTE_UNIT_LIMIT),
+        ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price),
+        swap_instruction(
+            pool_data,
+            wallet,
+            &sale.pool,
+            SwapSide::Sell,
+            sale.amount_in,
+            sale.minimum_out,
+        )?,
+    ])
+}
+
+/// 库存再平衡：定期把钱包中剩余的代币卖回报价货币
+///
+/// 套利失败或部分成交后钱包里可能留下代币，这部分资金不再参与套利。后台任务定期读取钱包代币余额，
+/// 价值超过 `min_value` 时按报价选择最好的池子全部卖出。
+pub struct Rebalancer {
+    rpc_client: Arc<RpcClient>,
+    wallet: Keypair,
+    pool_data: Arc<Mutex<MintPoolData>>,
+    /// 报价货币最小单位
+    min_value: u64,
+    slippage_bps: u64,
+    compute_unit_price: u64,
+    check_interval: Duration,
+}
+
+impl Rebalancer {
+    /// # 参数
+    /// * `pool_data` - 发送任务共用的池子数据
+    /// * `quote_mint` - 代币的报价货币，用于换算 `min_value`
+    /// * `compute_unit_price` - 卖出交易的计算单元价格
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        wallet: &Keypair,
+        pool_data: Arc<Mutex<MintPoolData>>,
+        config: &RebalanceConfig,
+        quote_mint: &CashMint,
+        compute_unit_price: u64,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            rpc_client,
+            wallet: Keypair::from_bytes(&wallet.to_bytes())?,
+            pool_data,
+            min_value: quote_mint.to_base_units(config.min_value()),
+            slippage_bps: config.slippage_bps(),
+            compute_unit_price,
+            check_interval: config.check_interval(),
+        })
+    }
+
+    /// 检查一次余额，需要时卖出
+    ///
+    /// # 返回值
+    /// 发送了卖出交易时返回签名
+    pub fn rebalance(&self, pool_data: &MintPoolData) -> anyhow::Result<Option<Signature>> {
+        let wallet = self.wallet.pubkey();
+        let token_account =
+            spl_associated_token_account::get_associated_token_address_with_program_id(
+                &wallet,
+                &pool_data.mint,
+                &pool_data.token_program,
+            );
+        let balance = match self.rpc_client.get_account(&token_account) {
+            Ok(account) => token_amount(&account.data).unwrap_or(0),
+            // 钱包没有代币账户时没有可卖的余额
+            Err(_) => return Ok(None),
+        };
+        if balance == 0 {
+            return Ok(None);
+        }
+
+        let reserves = quote::fetch_reserves(&self.rpc_client, pool_data)?;
+        let Some(sale) = plan_sale(
+            pool_data,
+            &reserves,
+            balance,
+            self.min_value,
+            self.slippage_bps,
+        ) else {
+            debug!(
+                "Keeping {} residual tokens of mint {}: below the rebalance threshold or no swappable pool",
+                balance, pool_data.mint
+            );
+            return Ok(None);
+        };
+
+        let instructions = sale_instructions(pool_data, &wallet, &sale, self.compute_unit_price)?;
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&wallet),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash()?,
+        );
+        let signature = self.rpc_client.send_and_confirm_transaction(&tx)?;
+        info!(
+            "Sold {} residual tokens of mint {} in pool {} for about {} (minimum {}): {}",
+            sale.amount_in,
+            pool_data.mint,
+            sale.pool,
+            sale.expected_out,
+            sale.minimum_out,
+            signature
+        );
+        Ok(Some(signature))
+    }
+
+    /// 定期检查并卖出剩余代币
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            tokio::time::sleep(self.check_interval).await;
+
+            // 读取账户和发送期间不持有锁，不阻塞发送任务
+            let pool_data = self.pool_data.lock().await.clone();
+            let mint = pool_data.mint;
+            let rebalancer = self.clone();
+            let result = tokio::task::spawn_blocking(move || rebalancer.rebalance(&pool_data))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+            if let Err(e) = result {
+                error!(
+                    "Failed to rebalance residual tokens of mint {}: {}",
+                    mint, e
+                );
+            }
+        }
+    }
+}
@@ -1,1025 +1,440 @@
-use crate::constants::sol_mint;
-use crate::dex::meteora::constants::{damm_program_id, damm_v2_program_id};
-use crate::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
-use crate::dex::meteora::{constants::dlmm_program_id, dlmm_info::DlmmInfo};
-use crate::dex::pump::{pump_fee_wallet, pump_program_id, PumpAmmInfo};
-use crate::dex::raydium::{
-    get_tick_array_pubkeys, raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id,
-    PoolState, RaydiumAmmInfo, RaydiumCpAmmInfo,
-};
-use crate::dex::solfi::constants::solfi_program_id;
-use crate::dex::solfi::info::SolfiInfo;
-use crate::dex::vertigo::{derive_vault_address, vertigo_program_id, VertigoInfo};
-use crate::dex::whirlpool::{
-    constants::whirlpool_program_id, state::Whirlpool, update_tick_array_accounts_for_onchain,
+use crate::ata::mint_token_program;
+use crate::config::{MintConfig, PoolEntry, PoolErrorPolicy};
+use crate::dex::{
+    meteora, moonshot, perena, pump, raydium, solfi, vertigo, whirlpool, zerofi, NonQuotePool,
+    PoolLoader,
 };
+use crate::pool_cache::{CachedPool, PoolCache};
 use crate::pools::*;
+use crate::quote::{liquidity_accounts, pool_liquidity, shallow_pools, MAX_MULTIPLE_ACCOUNTS};
+use crate::transfer_fee::parse_transfer_fee_config;
+use futures::stream::{self, StreamExt};
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
 use solana_client::rpc_client::RpcClient;
 use solana_program::pubkey::Pubkey;
-use spl_associated_token_account;
-use std::str::FromStr;
+use solana_sdk::account::Account;
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info};
-
-pub async fn initialize_pool_data(
-    mint: &str,
-    wallet_account: &str,
-    raydium_pools: Option<&Vec<String>>,
-    raydium_cp_pools: Option<&Vec<String>>,
-    pump_pools: Option<&Vec<String>>,
-    dlmm_pools: Option<&Vec<String>>,
-    whirlpool_pools: Option<&Vec<String>>,
-    raydium_clmm_pools: Option<&Vec<String>>,
-    meteora_damm_pools: Option<&Vec<String>>,
-    solfi_pools: Option<&Vec<String>>,
-    meteora_damm_v2_pools: Option<&Vec<String>>,
-    vertigo_pools: Option<&Vec<String>>,
-    rpc_client: Arc<RpcClient>,
-) -> anyhow::Result<MintPoolData> {
-    info!("Initializing pool data for mint: {}", mint);
-
-    // Fetch mint account to determine token program
-    let mint_pubkey = Pubkey::from_str(mint)?;
-    let mint_account = rpc_client.get_account(&mint_pubkey)?;
-
-    // 根据铸币账户所有者确定代币程序是 Token 或 Token 2022
-
-    let token_2022_program_id =
-        Pubkey::from_str("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb").unwrap();
-    let token_program = if mint_account.owner == spl_token::ID {
-        spl_token::ID
-    } else if mint_account.owner == token_2022_program_id {
-        token_2022_program_id
-    } else {
-        return Err(anyhow::anyhow!("Unknown token program for mint: {}", mint));
-    };
-
-    info!("Detected token program: {}", token_program);
-    let mut pool_data = MintPoolData::new(mint, wallet_account, token_program)?;
-    info!("Pool data initialized for mint: {}", mint);
-    // pump.fun 平台池
-    if let Some(pools) = pump_pools {
-        for pool_address in pools {
-            let pump_pool_pubkey = Pubkey::from_str(pool_address)?;
-            // 获取帐号信息
-            match rpc_client.get_account(&pump_pool_pubkey) {
-                Ok(account) => {
-                    // 如果拿到的帐号信息比对 pump.fun 池的账户，则返回错误
-                    if account.owner != pump_program_id() {
-                        error!(
-                            "Error: Pump pool account is not owned by the Pump program. Expected: {}, Actual: {}",
-                            pump_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Pump pool account is not owned by the Pump program"
-                        ));
-                    }
-
-                    /// 尝试从指定的账户数据中加载并验证 Pump AMM 信息，并将其添加到池数据中。
-                    ///
-                    /// 该函数会解析 AMM 信息，确定 SOL 和代币的 vault 地址，计算手续费账户和创建者账户的关联地址，
-                    /// 然后将这些信息注册到 `pool_data` 中。如果解析失败，则返回错误。
-                    ///
-                    /// # 参数说明：
-                    /// - `account`: 包含 AMM 信息的账户数据引用。
-                    /// - `pool_address`: 当前处理的池地址。
-                    /// - `pump_pool_pubkey`: Pump 协议中的池公钥。
-                    /// - `pool_data`: 用于存储池信息的可变引用。
-                    ///
-                    /// # 返回值：
-                    /// - 成功时返回 `Ok(())`，表示已成功添加 Pump 池。
-                    /// - 失败时返回相应的错误，如解析 AMM 信息失败等。
-                    match PumpAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            // 根据 base_mint 或 quote_mint 是否为 SOL mint 来决定 token_vault 和 sol_vault 的对应关系
-                            let (sol_vault, token_vault) = if sol_mint() == amm_info.base_mint {
-                                (
-                                    amm_info.pool_base_token_account,
-                                    amm_info.pool_quote_token_account,
-                                )
-                            } else if sol_mint() == amm_info.quote_mint {
-                                (
-                                    amm_info.pool_quote_token_account,
-                                    amm_info.pool_base_token_account,
-                                )
-                            } else {
-                                // 默认情况也使用 quote 作为 SOL vault（可能是 fallback 逻辑）
-                                (
-                                    amm_info.pool_quote_token_account,
-                                    amm_info.pool_base_token_account,
-                                )
-                            };
-
-                            // 计算手续费钱包的关联代币账户地址
-                            let fee_token_wallet =
-                                spl_associated_token_account::get_associated_token_address(
-                                    &pump_fee_wallet(),
-                                    &amm_info.quote_mint,
-                                );
-
-                            // 计算代币创建者 vault 的 ATA 地址
-                            let coin_creator_vault_ata =
-                                spl_associated_token_account::get_associated_token_address(
-                                    &amm_info.coin_creator_vault_authority,
-                                    &amm_info.quote_mint,
-                                );
-
-                            // 将解析出的池信息添加到 pool_data 中
-                            pool_data.add_pump_pool(
-                                pool_address,
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                                &fee_token_wallet.to_string(),
-                                &coin_creator_vault_ata.to_string(),
-                                &amm_info.coin_creator_vault_authority.to_string(),
-                            )?;
-
-                            // 打印调试日志，记录添加的池信息
-                            info!("Pump pool added: {}", pool_address);
-                            info!("    Base mint: {}", amm_info.base_mint.to_string());
-                            info!("    Quote mint: {}", amm_info.quote_mint.to_string());
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    Fee token wallet: {}", fee_token_wallet.to_string());
-                            info!(
-                                "    Coin creator vault ata: {}",
-                                coin_creator_vault_ata.to_string()
-                            );
-                            info!(
-                                "    Coin creator vault authority: {}",
-                                amm_info.coin_creator_vault_authority.to_string()
-                            );
-                            info!("    Initialized Pump pool: {}\n", pump_pool_pubkey);
-                        }
-                        Err(e) => {
-                            // 如果无法解析 AMM 信息，则记录错误并返回
-                            error!(
-                                "Error parsing AmmInfo from Pump pool {}: {:?}",
-                                pump_pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Pump pool account {}: {:?}",
-                        pump_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Pump pool account"));
-                }
-            }
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// 初始化池子时同时进行的 `getMultipleAccounts` 请求数上限
+const POOL_FETCH_CONCURRENCY: usize = 4;
+
+/// 用 async 客户端按 `getMultipleAccounts` 分批并发获取一组账户
+///
+/// 某一批请求失败时记录这一批的地址并继续，这些账户之后由调用方逐个读取，不影响其他批次。
+///
+/// # 返回值
+/// 成功获取的账户；不存在的账户和失败批次中的账户都不在结果中
+async fn fetch_accounts_concurrently(
+    rpc_client: &NonblockingRpcClient,
+    pubkeys: Vec<Pubkey>,
+) -> HashMap<Pubkey, Account> {
+    let results: Vec<_> = stream::iter(pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS))
+        .map(|chunk| async move { (chunk, rpc_client.get_multiple_accounts(chunk).await) })
+        .buffer_unordered(POOL_FETCH_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut accounts = HashMap::new();
+    for (chunk, result) in results {
+        match result {
+            Ok(fetched) => accounts.extend(
+                chunk
+                    .iter()
+                    .zip(fetched)
+                    .filter_map(|(pubkey, account)| Some((*pubkey, account?))),
+            ),
+            Err(e) => warn!(
+                "Failed to fetch {} accounts ({}): {}",
+                chunk.len(),
+                chunk
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                e
+            ),
         }
     }
+    accounts
+}
 
-    // 处理 Raydium 流动性池的初始化逻辑。
-    //
-    // 该代码块遍历给定的 Raydium 池地址列表，验证每个池账户是否有效，并提取必要的信息（如代币 vault 地址），
-    // 然后将其添加到 `pool_data` 中用于后续处理。
-    //
-    // # 参数说明
-    // - `raydium_pools`: 一个可选的字符串切片向量，表示要处理的 Raydium 池地址列表。
-    // - `rpc_client`: 与 Solana 链交互的 RPC 客户端实例。
-    // - `pool_data`: 包含池相关数据的结构体，用于存储解析出的池信息。
-    //
-    // # 返回值
-    // - 成功时返回 `Ok(())`。
-    // - 出现任何错误时返回 `Err(anyhow::Error)`。
-
-    if let Some(pools) = raydium_pools {
-        for pool_address in pools {
-            let raydium_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            // 获取池账户信息并验证其所有者是否为 Raydium 程序
-            match rpc_client.get_account(&raydium_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != raydium_program_id() {
-                        error!(
-                            "Error: Raydium pool account is not owned by the Raydium program. Expected: {}, Actual: {}",
-                            raydium_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Raydium pool account is not owned by the Raydium program"
-                        ));
-                    }
-
-                    // 解析账户数据为 AmmInfo 并进行有效性检查
-                    match RaydiumAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            // 确保目标 mint 在池中存在
-                            if amm_info.coin_mint != pool_data.mint
-                                && amm_info.pc_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium pool {}, skipping",
-                                    pool_data.mint, raydium_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Raydium pool: {}",
-                                    raydium_pool_pubkey
-                                ));
-                            }
-
-                            // 确保池中包含 SOL 代币
-                            if amm_info.coin_mint != sol_mint() && amm_info.pc_mint != sol_mint() {
-                                error!(
-                                    "SOL is not present in Raydium pool {}",
-                                    raydium_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Raydium pool: {}",
-                                    raydium_pool_pubkey
-                                ));
-                            }
-
-                            // 根据 SOL 是 coin 还是 pc 来确定 vault 的对应关系
-                            let (sol_vault, token_vault) = if sol_mint() == amm_info.coin_mint {
-                                (amm_info.coin_vault, amm_info.pc_vault)
-                            } else {
-                                (amm_info.pc_vault, amm_info.coin_vault)
-                            };
+/// 从池子列表中取出命中缓存的池子
+///
+/// # 参数
+/// * `pools` - 配置中的池子地址列表
+/// * `mint` - 当前代币
+/// * `pool_cache` - 池子元数据缓存，为 `None` 时不做任何过滤
+/// * `cached` - 命中缓存的池子追加到这里
+///
+/// # 返回值
+/// 未命中缓存、需要从链上加载的池子地址
+fn split_cached(
+    pools: Option<&Vec<Pubkey>>,
+    mint: &Pubkey,
+    pool_cache: Option<&PoolCache>,
+    cached: &mut Vec<CachedPool>,
+) -> Option<Vec<Pubkey>> {
+    let pools = pools?;
+    let Some(pool_cache) = pool_cache else {
+        return Some(pools.clone());
+    };
 
-                            // 将解析出的池信息加入 pool_data
-                            pool_data.add_raydium_pool(
-                                pool_address,
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                            )?;
-                            info!("Raydium pool added: {}", pool_address);
-                            info!("    Coin mint: {}", amm_info.coin_mint.to_string());
-                            info!("    PC mint: {}", amm_info.pc_mint.to_string());
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    Initialized Raydium pool: {}\n", raydium_pool_pubkey);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from Raydium pool {}: {:?}",
-                                raydium_pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium pool account {}: {:?}",
-                        raydium_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Raydium pool account"));
-                }
-            }
+    let mut uncached = Vec::new();
+    for pool_address in pools {
+        match pool_cache.get(mint, pool_address) {
+            Some(pool) => cached.push(pool.clone()),
+            None => uncached.push(*pool_address),
         }
     }
+    Some(uncached)
+}
 
-    // 处理 Raydium Concentrated Liquidity (CP) 池的逻辑。
-    //
-    // 该段代码用于验证并加载指定的 Raydium CP 池账户信息，并从中提取与当前交易对相关的 vault 地址、
-    // AMM 配置和观测密钥等信息，最终将这些信息添加到 `pool_data` 中。
-    //
-    // # 参数说明（基于上下文推测）：
-    // - `raydium_cp_pools`: 可选的字符串切片向量，表示要处理的 Raydium CP 池地址列表。
-    // - `rpc_client`: 用于与 Solana 区块链交互的 RPC 客户端实例。
-    // - `pool_data`: 存储池相关信息的数据结构，用于保存解析后的池信息。
-    //
-    // # 返回值说明：
-    // - 成功时返回 `Ok(())` 表示所有池都已成功处理。
-    // - 失败时返回错误，可能由于账户不存在、所有者不匹配、数据解析失败等原因。
-
-    if let Some(pools) = raydium_cp_pools {
-        // 遍历每一个提供的 Raydium CP 池地址
-        for pool_address in pools {
-            let raydium_cp_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            // 获取池账户信息
-            match rpc_client.get_account(&raydium_cp_pool_pubkey) {
-                Ok(account) => {
-                    // 验证账户是否由正确的程序拥有
-                    if account.owner != raydium_cp_program_id() {
-                        error!(
-                            "Error: Raydium CP pool account is not owned by the Raydium CP program. Expected: {}, Actual: {}",
-                            raydium_cp_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Raydium CP pool account is not owned by the Raydium CP program"
-                        ));
-                    }
-
-                    // 尝试解析账户中的 AMM 信息
-                    match RaydiumCpAmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            // 确保目标代币存在于该池中
-                            if amm_info.token_0_mint != pool_data.mint
-                                && amm_info.token_1_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium CP pool {}, skipping",
-                                    pool_data.mint, raydium_cp_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Raydium CP pool: {}",
-                                    raydium_cp_pool_pubkey
-                                ));
-                            }
-
-                            // 根据 SOL 是 token0 还是 token1 来决定 vault 的顺序
-                            let (sol_vault, token_vault) = if sol_mint() == amm_info.token_0_mint {
-                                (amm_info.token_0_vault, amm_info.token_1_vault)
-                            } else if sol_mint() == amm_info.token_1_mint {
-                                (amm_info.token_1_vault, amm_info.token_0_vault)
-                            } else {
-                                error!(
-                                    "SOL is not present in Raydium CP pool {}",
-                                    raydium_cp_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Raydium CP pool: {}",
-                                    raydium_cp_pool_pubkey
-                                ));
-                            };
+/// 按 `on_pool_error` 策略处理单个池子的加载结果，记录被跳过的池子
+struct SkippedPools {
+    policy: PoolErrorPolicy,
+    non_quote_pools: NonQuotePoolPolicy,
+    /// (DEX, 池子地址, 原因)
+    pools: Vec<(&'static str, Pubkey, String)>,
+}
 
-                            // 将解析出的信息添加到 pool_data 中
-                            pool_data.add_raydium_cp_pool(
-                                pool_address,
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                                &amm_info.amm_config.to_string(),
-                                &amm_info.observation_key.to_string(),
-                            )?;
-                            info!("Raydium CP pool added: {}", pool_address);
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    AMM Config: {}", amm_info.amm_config.to_string());
-                            info!(
-                                "    Observation Key: {}\n",
-                                amm_info.observation_key.to_string()
-                            );
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from Raydium CP pool {}: {:?}",
-                                raydium_cp_pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium CP pool account {}: {:?}",
-                        raydium_cp_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Raydium CP pool account"));
-                }
-            }
+impl SkippedPools {
+    fn new(policy: PoolErrorPolicy, non_quote_pools: NonQuotePoolPolicy) -> Self {
+        Self {
+            policy,
+            non_quote_pools,
+            pools: Vec::new(),
         }
     }
 
-    /// 处理 DLMM 池列表，加载每个池的信息并将其添加到 `pool_data` 中。
-    ///
-    /// 该函数会遍历传入的 DLMM 池地址列表，对每个池执行以下操作：
-    /// 1. 将地址字符串解析为 `Pubkey`；
-    /// 2. 通过 RPC 获取账户信息，并验证其所有者是否为 DLMM 程序；
-    /// 3. 解析账户数据为 `DlmmInfo` 结构体；
-    /// 4. 获取代币和 SOL 的资金池地址；
-    /// 5. 计算并获取池的 Bin Array 地址；
-    /// 6. 将池信息添加到 `pool_data` 中；
-    /// 7. 打印池的详细信息和 Bin Array 列表。
-    ///
-    /// # 参数
-    /// - `dlmm_pools`: 可选的 DLMM 池地址字符串列表；
-    /// - `rpc_client`: 用于与区块链交互的 RPC 客户端；
-    /// - `pool_data`: 用于存储池信息的数据结构；
-    ///
-    /// # 返回值
-    /// 成功时返回 `Ok(())`，失败时返回错误信息。
+    /// `fail` 策略下直接返回错误，`skip` 策略下记录后继续加载其他池子
     ///
-    /// # 错误处理
-    /// - 如果池账户的所有者不是 DLMM 程序，返回错误；
-    /// - 如果解析 `DlmmInfo` 失败，返回错误；
-    /// - 如果获取账户失败，返回错误；
-    /// - 如果计算 Bin Array 失败，返回错误；
-    if let Some(pools) = dlmm_pools {
-        for pool_address in pools {
-            let dlmm_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            // 获取 DLMM 池账户信息并验证所有者
-            match rpc_client.get_account(&dlmm_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != dlmm_program_id() {
-                        error!(
-                            "Error: DLMM pool account is not owned by the DLMM program. Expected: {}, Actual: {}",
-                            dlmm_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "DLMM pool account is not owned by the DLMM program"
-                        ));
-                    }
-
-                    // 解析 DLMM 池账户数据
-                    match DlmmInfo::load_checked(&account.data) {
-                        Ok(amm_info) => {
-                            let sol_mint = sol_mint();
-                            let (token_vault, sol_vault) =
-                                amm_info.get_token_and_sol_vaults(&pool_data.mint, &sol_mint);
-
-                            // 计算 Bin Array 地址
-                            let bin_arrays = match amm_info.calculate_bin_arrays(&dlmm_pool_pubkey)
-                            {
-                                Ok(arrays) => arrays,
-                                Err(e) => {
-                                    error!(
-                                        "Error calculating bin arrays for DLMM pool {}: {:?}",
-                                        dlmm_pool_pubkey, e
-                                    );
-                                    return Err(e);
-                                }
-                            };
-
-                            // 将 Bin Array 地址转换为字符串引用列表
-                            let bin_array_strings: Vec<String> =
-                                bin_arrays.iter().map(|pubkey| pubkey.to_string()).collect();
-                            let bin_array_str_refs: Vec<&str> =
-                                bin_array_strings.iter().map(|s| s.as_str()).collect();
-
-                            // 将池信息添加到 pool_data
-                            pool_data.add_dlmm_pool(
-                                pool_address,
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                                &amm_info.oracle.to_string(),
-                                bin_array_str_refs,
-                                None, // memo_program
-                            )?;
-
-                            // 打印池信息
-                            info!("DLMM pool added: {}", pool_address);
-                            info!("    Token X Mint: {}", amm_info.token_x_mint.to_string());
-                            info!("    Token Y Mint: {}", amm_info.token_y_mint.to_string());
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    Oracle: {}", amm_info.oracle.to_string());
-                            info!("    Active ID: {}", amm_info.active_id);
-
-                            for (i, array) in bin_array_strings.iter().enumerate() {
-                                info!("    Bin Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing AmmInfo from DLMM pool {}: {:?}",
-                                dlmm_pool_pubkey, e
-                            );
-                            return Err(e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching DLMM pool account {}: {:?}",
-                        dlmm_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching DLMM pool account"));
-                }
+    /// 两侧都不是报价货币的池子在 `routing.non_quote_pools = "skip"` 时总是跳过，不受 `on_pool_error` 影响
+    fn record(
+        &mut self,
+        dex: &'static str,
+        pool_address: &Pubkey,
+        result: anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let Err(e) = result else {
+            return Ok(());
+        };
+        let skip_non_quote = self.non_quote_pools == NonQuotePoolPolicy::Skip
+            && e.downcast_ref::<NonQuotePool>().is_some();
+        match self.policy {
+            PoolErrorPolicy::Fail if !skip_non_quote => {
+                Err(e.context(format!("Failed to load {} pool {}", dex, pool_address)))
             }
-        }
-    }
-
-    if let Some(pools) = whirlpool_pools {
-        for pool_address in pools {
-            let whirlpool_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            match rpc_client.get_account(&whirlpool_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != whirlpool_program_id() {
-                        error!(
-                            "Error: Whirlpool pool account is not owned by the Whirlpool program. Expected: {}, Actual: {}",
-                            whirlpool_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Whirlpool pool account is not owned by the Whirlpool program"
-                        ));
-                    }
-
-                    match Whirlpool::try_deserialize(&account.data) {
-                        Ok(whirlpool) => {
-                            if whirlpool.token_mint_a != pool_data.mint
-                                && whirlpool.token_mint_b != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Whirlpool pool {}, skipping",
-                                    pool_data.mint, whirlpool_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Whirlpool pool: {}",
-                                    whirlpool_pool_pubkey
-                                ));
-                            }
-
-                            let sol_mint = sol_mint();
-                            let (sol_vault, token_vault) = if sol_mint == whirlpool.token_mint_a {
-                                (whirlpool.token_vault_a, whirlpool.token_vault_b)
-                            } else if sol_mint == whirlpool.token_mint_b {
-                                (whirlpool.token_vault_b, whirlpool.token_vault_a)
-                            } else {
-                                error!(
-                                    "SOL is not present in Whirlpool pool {}",
-                                    whirlpool_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Whirlpool pool: {}",
-                                    whirlpool_pool_pubkey
-                                ));
-                            };
-
-                            let whirlpool_oracle = Pubkey::find_program_address(
-                                &[b"oracle", whirlpool_pool_pubkey.as_ref()],
-                                &whirlpool_program_id(),
-                            )
-                            .0;
-
-                            let whirlpool_tick_arrays = update_tick_array_accounts_for_onchain(
-                                &whirlpool,
-                                &whirlpool_pool_pubkey,
-                                &whirlpool_program_id(),
-                            );
-
-                            let tick_array_strings: Vec<String> = whirlpool_tick_arrays
-                                .iter()
-                                .map(|meta| meta.pubkey.to_string())
-                                .collect();
-
-                            let tick_array_str_refs: Vec<&str> =
-                                tick_array_strings.iter().map(|s| s.as_str()).collect();
-
-                            pool_data.add_whirlpool_pool(
-                                pool_address,
-                                &whirlpool_oracle.to_string(),
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                                tick_array_str_refs,
-                                None, // memo_program
-                            )?;
-
-                            info!("Whirlpool pool added: {}", pool_address);
-                            info!("    Token mint A: {}", whirlpool.token_mint_a.to_string());
-                            info!("    Token mint B: {}", whirlpool.token_mint_b.to_string());
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    Oracle: {}", whirlpool_oracle.to_string());
-
-                            for (i, array) in tick_array_strings.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Whirlpool data from pool {}: {:?}",
-                                whirlpool_pool_pubkey, e
-                            );
-                            return Err(anyhow::anyhow!("Error parsing Whirlpool data"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Whirlpool pool account {}: {:?}",
-                        whirlpool_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Whirlpool pool account"));
-                }
+            _ => {
+                warn!("Skipping {} pool {}: {:#}", dex, pool_address, e);
+                self.pools.push((dex, *pool_address, format!("{:#}", e)));
+                Ok(())
             }
         }
     }
 
-    if let Some(pools) = raydium_clmm_pools {
-        for pool_address in pools {
-            let raydium_clmm_program_id = raydium_clmm_program_id();
-
-            match rpc_client.get_account(&Pubkey::from_str(pool_address)?) {
-                Ok(account) => {
-                    if account.owner != raydium_clmm_program_id {
-                        error!(
-                            "Raydium CLMM pool {} is not owned by the Raydium CLMM program, skipping",
-                            pool_address
-                        );
-                        continue;
-                    }
-
-                    match PoolState::load_checked(&account.data) {
-                        Ok(raydium_clmm) => {
-                            if raydium_clmm.token_mint_0 != pool_data.mint
-                                && raydium_clmm.token_mint_1 != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Raydium CLMM pool {}, skipping",
-                                    pool_data.mint, pool_address
-                                );
-                                continue;
-                            }
-
-                            let sol_mint = sol_mint();
-                            let (token_vault, sol_vault) = if sol_mint == raydium_clmm.token_mint_0
-                            {
-                                (raydium_clmm.token_vault_1, raydium_clmm.token_vault_0)
-                            } else if sol_mint == raydium_clmm.token_mint_1 {
-                                (raydium_clmm.token_vault_0, raydium_clmm.token_vault_1)
-                            } else {
-                                error!("SOL is not present in Raydium CLMM pool {}", pool_address);
-                                continue;
-                            };
-
-                            let tick_array_pubkeys = get_tick_array_pubkeys(
-                                &Pubkey::from_str(pool_address)?,
-                                raydium_clmm.tick_current,
-                                raydium_clmm.tick_spacing,
-                                &[-1, 0, 1],
-                                &raydium_clmm_program_id,
-                            )?;
-
-                            let tick_array_strings: Vec<String> = tick_array_pubkeys
-                                .iter()
-                                .map(|pubkey| pubkey.to_string())
-                                .collect();
-
-                            let tick_array_str_refs: Vec<&str> =
-                                tick_array_strings.iter().map(|s| s.as_str()).collect();
-
-                            pool_data.add_raydium_clmm_pool(
-                                pool_address,
-                                &raydium_clmm.amm_config.to_string(),
-                                &raydium_clmm.observation_key.to_string(),
-                                &token_vault.to_string(),
-                                &sol_vault.to_string(),
-                                tick_array_str_refs,
-                                None, // memo_program
-                            )?;
-
-                            info!("Raydium CLMM pool added: {}", pool_address);
-                            info!(
-                                "    Token mint 0: {}",
-                                raydium_clmm.token_mint_0.to_string()
-                            );
-                            info!(
-                                "    Token mint 1: {}",
-                                raydium_clmm.token_mint_1.to_string()
-                            );
-                            info!("    Token vault: {}", token_vault.to_string());
-                            info!("    Sol vault: {}", sol_vault.to_string());
-                            info!("    AMM config: {}", raydium_clmm.amm_config.to_string());
-                            info!(
-                                "    Observation key: {}",
-                                raydium_clmm.observation_key.to_string()
-                            );
-
-                            for (i, array) in tick_array_strings.iter().enumerate() {
-                                info!("    Tick Array {}: {}", i, array);
-                            }
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Raydium CLMM data from pool {}: {:?}",
-                                pool_address, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Raydium CLMM pool account {}: {:?}",
-                        pool_address, e
-                    );
-                    continue;
-                }
-            }
+    /// 汇总输出本次跳过的池子
+    fn report(&self, mint: &Pubkey) {
+        if self.pools.is_empty() {
+            return;
+        }
+        warn!("Skipped {} pools for mint {}:", self.pools.len(), mint);
+        for (dex, pool_address, reason) in &self.pools {
+            warn!("    {} {}: {}", dex, pool_address, reason);
         }
     }
+}
 
-    if let Some(pools) = meteora_damm_pools {
-        for pool_address in pools {
-            let meteora_damm_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            match rpc_client.get_account(&meteora_damm_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != damm_program_id() {
-                        error!(
-                            "Error: Meteora DAMM pool account is not owned by the Meteora DAMM program. Expected: {}, Actual: {}",
-                            damm_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Meteora DAMM pool account is not owned by the Meteora DAMM program"
-                        ));
-                    }
-
-                    match meteora_damm_cpi::Pool::deserialize_unchecked(&account.data) {
-                        Ok(pool) => {
-                            if pool.token_a_mint != pool_data.mint
-                                && pool.token_b_mint != pool_data.mint
-                            {
-                                error!(
-                                    "Mint {} is not present in Meteora DAMM pool {}, skipping",
-                                    pool_data.mint, meteora_damm_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "Invalid Meteora DAMM pool: {}",
-                                    meteora_damm_pool_pubkey
-                                ));
-                            }
-
-                            let sol_mint = sol_mint();
-                            if pool.token_a_mint != sol_mint && pool.token_b_mint != sol_mint {
-                                error!(
-                                    "SOL is not present in Meteora DAMM pool {}",
-                                    meteora_damm_pool_pubkey
-                                );
-                                return Err(anyhow::anyhow!(
-                                    "SOL is not present in Meteora DAMM pool: {}",
-                                    meteora_damm_pool_pubkey
-                                ));
-                            }
-
-                            let (x_vault, sol_vault) = if sol_mint == pool.token_a_mint {
-                                (pool.b_vault, pool.a_vault)
-                            } else {
-                                (pool.a_vault, pool.b_vault)
-                            };
-
-                            // Fetch vault accounts
-                            let x_vault_data = rpc_client.get_account(&x_vault)?;
-                            let sol_vault_data = rpc_client.get_account(&sol_vault)?;
-
-                            let x_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
-                                &mut x_vault_data.data.as_slice(),
-                            )?;
-                            let sol_vault_obj = meteora_vault_cpi::Vault::deserialize_unchecked(
-                                &mut sol_vault_data.data.as_slice(),
-                            )?;
+/// 一个 mint 在各 DEX 中要加载的池子地址，`None` 表示没有配置该 DEX 的池子
+#[derive(Debug, Clone, Default)]
+pub struct PoolLists {
+    pub raydium: Option<Vec<Pubkey>>,
+    pub raydium_cp: Option<Vec<Pubkey>>,
+    pub pump: Option<Vec<Pubkey>>,
+    pub dlmm: Option<Vec<Pubkey>>,
+    pub whirlpool: Option<Vec<Pubkey>>,
+    pub raydium_clmm: Option<Vec<Pubkey>>,
+    pub meteora_damm: Option<Vec<Pubkey>>,
+    pub solfi: Option<Vec<Pubkey>>,
+    pub meteora_damm_v2: Option<Vec<Pubkey>>,
+    pub vertigo: Option<Vec<Pubkey>>,
+    pub moonshot: Option<Vec<Pubkey>>,
+    pub perena: Option<Vec<Pubkey>>,
+    pub zerofi: Option<Vec<Pubkey>>,
+}
 
-                            let x_token_vault = x_vault_obj.token_vault;
-                            let sol_token_vault = sol_vault_obj.token_vault;
-                            let x_lp_mint = x_vault_obj.lp_mint;
-                            let sol_lp_mint = sol_vault_obj.lp_mint;
+impl PoolLists {
+    /// 代币配置中未禁用的池子
+    ///
+    /// # 错误
+    /// 池子地址不是合法的 Pubkey 时返回错误，`Config::validate` 已经检查过
+    pub fn enabled(mint_config: &MintConfig) -> anyhow::Result<Self> {
+        let enabled = |pools: &Option<Vec<PoolEntry>>| PoolEntry::enabled_addresses(pools.as_ref());
+        Ok(Self {
+            raydium: enabled(&mint_config.raydium_pool_list)?,
+            raydium_cp: enabled(&mint_config.raydium_cp_pool_list)?,
+            pump: enabled(&mint_config.pump_pool_list)?,
+            dlmm: enabled(&mint_config.meteora_dlmm_pool_list)?,
+            whirlpool: enabled(&mint_config.whirlpool_pool_list)?,
+            raydium_clmm: enabled(&mint_config.raydium_clmm_pool_list)?,
+            meteora_damm: enabled(&mint_config.meteora_damm_pool_list)?,
+            solfi: enabled(&mint_config.solfi_pool_list)?,
+            meteora_damm_v2: enabled(&mint_config.meteora_damm_v2_pool_list)?,
+            vertigo: enabled(&mint_config.vertigo_pool_list)?,
+            moonshot: enabled(&mint_config.moonshot_pool_list)?,
+            perena: enabled(&mint_config.perena_pool_list)?,
+            zerofi: enabled(&mint_config.zerofi_pool_list)?,
+        })
+    }
+}
 
-                            let (x_pool_lp, sol_pool_lp) = if sol_mint == pool.token_a_mint {
-                                (pool.b_vault_lp, pool.a_vault_lp)
-                            } else {
-                                (pool.a_vault_lp, pool.b_vault_lp)
-                            };
+/// 加载池子时对所有 mint 相同的客户端、缓存和策略
+pub struct PoolLoadContext<'a> {
+    /// 逐个解析池子时读取附属账户（vault、配置等）
+    pub rpc_client: Arc<RpcClient>,
+    /// 并发预取 mint 和池子账户
+    pub async_rpc_client: &'a NonblockingRpcClient,
+    /// 池子元数据缓存，为 `None` 时所有池子都从链上加载
+    pub pool_cache: Option<&'a PoolCache>,
+    pub on_pool_error: PoolErrorPolicy,
+    pub non_quote_pools: NonQuotePoolPolicy,
+}
 
-                            let (x_admin_fee, sol_admin_fee) = if sol_mint == pool.token_a_mint {
-                                (pool.admin_token_b_fee, pool.admin_token_a_fee)
-                            } else {
-                                (pool.admin_token_a_fee, pool.admin_token_b_fee)
-                            };
+/// 从链上（或缓存）加载一个 mint 的所有池子
+///
+/// # 参数
+/// * `quote_mint` - 报价货币
+/// * `pools` - 各 DEX 的池子地址
+/// * `min_pool_liquidity` - 报价货币一侧的最小余额（基本单位），低于它的池子被跳过
+/// * `context` - RPC 客户端、缓存和出错策略
+///
+/// # 错误
+/// mint 账户读取失败，或 `on_pool_error = "fail"` 时任一池子加载失败
+pub async fn initialize_pool_data(
+    mint: &Pubkey,
+    wallet_account: &Pubkey,
+    quote_mint: &Pubkey,
+    pools: &PoolLists,
+    min_pool_liquidity: Option<u64>,
+    context: &PoolLoadContext<'_>,
+) -> anyhow::Result<MintPoolData> {
+    info!("Initializing pool data for mint: {}", mint);
+    let rpc_client = &context.rpc_client;
+    let pool_cache = context.pool_cache;
+
+    // 各 DEX 的加载逻辑在对应的 `dex::*::loader` 模块中，这里只负责按顺序调用并处理错误；
+    // 第三项表示池子元数据能否缓存，DLMM、Whirlpool 和 Raydium CLMM 的 bin/tick array 随价格变化，总是从链上加载
+    let loaders: [(&'static str, Option<&Vec<Pubkey>>, bool, PoolLoader); 13] = [
+        ("Pump", pools.pump.as_ref(), true, pump::loader::load_pool),
+        (
+            "Raydium",
+            pools.raydium.as_ref(),
+            true,
+            raydium::loader::load_amm_pool,
+        ),
+        (
+            "Raydium CP",
+            pools.raydium_cp.as_ref(),
+            true,
+            raydium::loader::load_cp_pool,
+        ),
+        (
+            "DLMM",
+            pools.dlmm.as_ref(),
+            false,
+            meteora::loader::load_dlmm_pool,
+        ),
+        (
+            "Whirlpool",
+            pools.whirlpool.as_ref(),
+            false,
+            whirlpool::loader::load_pool,
+        ),
+        (
+            "Raydium CLMM",
+            pools.raydium_clmm.as_ref(),
+            false,
+            raydium::loader::load_clmm_pool,
+        ),
+        (
+            "Meteora DAMM",
+            pools.meteora_damm.as_ref(),
+            true,
+            meteora::loader::load_damm_pool,
+        ),
+        (
+            "Meteora DAMM V2",
+            pools.meteora_damm_v2.as_ref(),
+            true,
+            meteora::loader::load_damm_v2_pool,
+        ),
+        (
+            "Solfi",
+            pools.solfi.as_ref(),
+            true,
+            solfi::loader::load_pool,
+        ),
+        (
+            "Vertigo",
+            pools.vertigo.as_ref(),
+            true,
+            vertigo::loader::load_pool,
+        ),
+        (
+            "Moonshot",
+            pools.moonshot.as_ref(),
+            true,
+            moonshot::loader::load_pool,
+        ),
+        (
+            "Perena",
+            pools.perena.as_ref(),
+            true,
+            perena::loader::load_pool,
+        ),
+        (
+            "ZeroFi",
+            pools.zerofi.as_ref(),
+            true,
+            zerofi::loader::load_pool,
+        ),
+    ];
+
+    // 命中缓存的静态池子直接使用，只有未命中的才请求链上账户
+    let mut cached_pools = Vec::new();
+    let loaders = loaders.map(|(dex, pools, cacheable, load)| {
+        let pools = if cacheable {
+            split_cached(pools, mint, pool_cache, &mut cached_pools)
+        } else {
+            pools.cloned()
+        };
+        (dex, pools, load)
+    });
+    let cached_token_program = pool_cache.and_then(|cache| cache.token_program(mint));
+
+    // 先并发获取 mint 和所有池子账户，下面逐个解析时直接从结果中取，
+    // 池子较多时启动时间从逐个请求的总和缩短为几轮并发请求
+    // 只有 SPL Token 的 mint 可以跳过读取；Token 2022 的转账手续费可能变化，每次启动都重新读取
+    let read_mint = cached_token_program != Some(spl_token::ID);
+    let mut pubkeys = Vec::new();
+    if read_mint {
+        pubkeys.push(*mint);
+    }
+    for (_, pools, _) in &loaders {
+        let Some(pools) = pools else {
+            continue;
+        };
+        pubkeys.extend(pools);
+    }
+    let fetch_started = Instant::now();
+    let account_count = pubkeys.len();
+    let mut accounts = fetch_accounts_concurrently(context.async_rpc_client, pubkeys).await;
+    info!(
+        "Fetched {} accounts for mint {} in {:?}",
+        account_count,
+        mint,
+        fetch_started.elapsed()
+    );
+
+    // 优先使用预取的结果，未预取的账户（如 DAMM vault）和预取失败的账户直接请求
+    let mut get_account = |pubkey: &Pubkey| -> anyhow::Result<Account> {
+        match accounts.remove(pubkey) {
+            Some(account) => Ok(account),
+            None => Ok(rpc_client.get_account(pubkey)?),
+        }
+    };
 
-                            pool_data.add_meteora_damm_pool(
-                                pool_address,
-                                &x_vault.to_string(),
-                                &sol_vault.to_string(),
-                                &x_token_vault.to_string(),
-                                &sol_token_vault.to_string(),
-                                &x_lp_mint.to_string(),
-                                &sol_lp_mint.to_string(),
-                                &x_pool_lp.to_string(),
-                                &sol_pool_lp.to_string(),
-                                &x_admin_fee.to_string(),
-                                &sol_admin_fee.to_string(),
-                            )?;
+    // Fetch mint account to determine token program
+    let mint_account = if read_mint {
+        Some(get_account(mint)?)
+    } else {
+        None
+    };
+    let token_program = match &mint_account {
+        None => spl_token::ID,
+        // 根据铸币账户所有者确定代币程序是 Token 或 Token 2022
+        Some(mint_account) => mint_token_program(mint, &mint_account.owner)?,
+    };
 
-                            info!("Meteora DAMM pool added: {}", pool_address);
-                            info!("    Token X vault: {}", x_token_vault.to_string());
-                            info!("    SOL vault: {}", sol_token_vault.to_string());
-                            info!("    Token X LP mint: {}", x_lp_mint.to_string());
-                            info!("    SOL LP mint: {}", sol_lp_mint.to_string());
-                            info!("    Token X pool LP: {}", x_pool_lp.to_string());
-                            info!("    SOL pool LP: {}", sol_pool_lp.to_string());
-                            info!("    Token X admin fee: {}", x_admin_fee.to_string());
-                            info!("    SOL admin fee: {}", sol_admin_fee.to_string());
-                            info!("");
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Meteora DAMM pool data from pool {}: {:?}",
-                                meteora_damm_pool_pubkey, e
-                            );
-                            return Err(anyhow::anyhow!("Error parsing Meteora DAMM pool data"));
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Meteora DAMM pool account {}: {:?}",
-                        meteora_damm_pool_pubkey, e
-                    );
-                    return Err(anyhow::anyhow!("Error fetching Meteora DAMM pool account"));
-                }
-            }
+    info!("Detected token program: {}", token_program);
+    let mut pool_data = MintPoolData::new(*mint, *wallet_account, token_program)
+        .with_quote_mint(*quote_mint)
+        .with_non_quote_pool_policy(context.non_quote_pools);
+    if let Some(fee_config) = mint_account
+        .as_ref()
+        .and_then(|mint_account| parse_transfer_fee_config(&mint_account.data))
+    {
+        // 新旧费率相同时不需要查询当前 epoch
+        let fee = if fee_config.older == fee_config.newer {
+            fee_config.newer
+        } else {
+            fee_config.for_epoch(rpc_client.get_epoch_info()?.epoch)
+        };
+        if fee.basis_points > 0 {
+            info!(
+                "Mint {} charges a transfer fee of {} bps (max {})",
+                mint, fee.basis_points, fee.maximum_fee
+            );
+            pool_data.transfer_fee = Some(fee);
         }
     }
-
-    if let Some(pools) = meteora_damm_v2_pools {
-        for pool_address in pools {
-            let meteora_damm_v2_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            match rpc_client.get_account(&meteora_damm_v2_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != damm_v2_program_id() {
-                        error!("Meteora DAMM V2 pool {} is not owned by the Meteora DAMM V2 program, skipping", pool_address);
-                        continue;
-                    }
-
-                    match MeteoraDAmmV2Info::load_checked(&account.data) {
-                        Ok(meteora_damm_v2_info) => {
-                            info!("Meteora DAMM V2 pool added: {}", pool_address);
-                            info!(
-                                "    Base mint: {}",
-                                meteora_damm_v2_info.base_mint.to_string()
-                            );
-                            info!(
-                                "    Quote mint: {}",
-                                meteora_damm_v2_info.quote_mint.to_string()
-                            );
-                            info!(
-                                "    Base vault: {}",
-                                meteora_damm_v2_info.base_vault.to_string()
-                            );
-                            info!(
-                                "    Quote vault: {}",
-                                meteora_damm_v2_info.quote_vault.to_string()
-                            );
-                            info!("");
-                            let token_x_vault = if sol_mint() == meteora_damm_v2_info.base_mint {
-                                meteora_damm_v2_info.quote_vault
-                            } else {
-                                meteora_damm_v2_info.base_vault
-                            };
-
-                            let token_sol_vault = if sol_mint() == meteora_damm_v2_info.base_mint {
-                                meteora_damm_v2_info.base_vault
-                            } else {
-                                meteora_damm_v2_info.quote_vault
-                            };
-                            pool_data.add_meteora_damm_v2_pool(
-                                pool_address,
-                                &token_x_vault.to_string(),
-                                &token_sol_vault.to_string(),
-                            )?;
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Meteora DAMM V2 pool data from pool {}: {:?}",
-                                meteora_damm_v2_pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Meteora DAMM V2 pool account {}: {:?}",
-                        meteora_damm_v2_pool_pubkey, e
-                    );
-                    continue;
-                }
+    info!("Pool data initialized for mint: {}", mint);
+    if !cached_pools.is_empty() {
+        info!(
+            "Loaded {} pools for mint {} from cache",
+            cached_pools.len(),
+            mint
+        );
+    }
+    for pool in cached_pools {
+        pool.add_to(&mut pool_data);
+    }
+    // 命中缓存的池子不再读取链上账户，手续费也从缓存恢复
+    if let Some(pool_cache) = pool_cache {
+        for (_, pool) in pool_data.pool_addresses() {
+            if let Some(fee) = pool_cache.fee(mint, &pool) {
+                pool_data.pool_fees.insert(pool, fee);
             }
         }
     }
 
-    if let Some(pools) = solfi_pools {
-        for pool_address in pools {
-            let solfi_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            match rpc_client.get_account(&solfi_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != solfi_program_id() {
-                        error!(
-                            "Solfi pool {} is not owned by the Solfi program, skipping",
-                            pool_address
-                        );
-                        continue;
-                    }
-
-                    match SolfiInfo::load_checked(&account.data) {
-                        Ok(solfi_info) => {
-                            info!("Solfi pool added: {}", pool_address);
-                            info!("    Base mint: {}", solfi_info.base_mint.to_string());
-                            info!("    Quote mint: {}", solfi_info.quote_mint.to_string());
-                            info!("    Base vault: {}", solfi_info.base_vault.to_string());
-                            info!("    Quote vault: {}", solfi_info.quote_vault.to_string());
-
-                            let token_x_vault = if sol_mint() == solfi_info.base_mint {
-                                solfi_info.quote_vault
-                            } else {
-                                solfi_info.base_vault
-                            };
-
-                            let token_sol_vault = if sol_mint() == solfi_info.base_mint {
-                                solfi_info.base_vault
-                            } else {
-                                solfi_info.quote_vault
-                            };
-
-                            pool_data.add_solfi_pool(
-                                pool_address,
-                                &token_x_vault.to_string(),
-                                &token_sol_vault.to_string(),
-                            )?;
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Solfi pool data from pool {}: {:?}",
-                                pool_address, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Solfi pool account {}: {:?}",
-                        solfi_pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
+    let mut skipped = SkippedPools::new(context.on_pool_error, context.non_quote_pools);
+    for (dex, pools, load) in loaders {
+        for pool in pools.iter().flatten() {
+            let result = load(pool, &mut get_account, &mut pool_data);
+            skipped.record(dex, pool, result)?;
         }
     }
 
-    if let Some(pools) = vertigo_pools {
-        for pool_address in pools {
-            let vertigo_pool_pubkey = Pubkey::from_str(pool_address)?;
-
-            match rpc_client.get_account(&vertigo_pool_pubkey) {
-                Ok(account) => {
-                    if account.owner != vertigo_program_id() {
-                        error!(
-                            "Error: Vertigo pool account is not owned by the Vertigo program. Expected: {}, Actual: {}",
-                            vertigo_program_id(), account.owner
-                        );
-                        return Err(anyhow::anyhow!(
-                            "Vertigo pool account is not owned by the Vertigo program"
-                        ));
-                    }
-
-                    match VertigoInfo::load_checked(&account.data, &vertigo_pool_pubkey) {
-                        Ok(vertigo_info) => {
-                            info!("Vertigo pool added: {}", pool_address);
-                            info!("    Mint A: {}", vertigo_info.mint_a.to_string());
-                            info!("    Mint B: {}", vertigo_info.mint_b.to_string());
-
-                            let base_mint = pool_data.mint.to_string();
-
-                            // Following the original loading pattern from user's code:
-                            let non_base_vault = if base_mint == vertigo_info.mint_a.to_string() {
-                                derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_b).0
-                            } else {
-                                derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_a).0
-                            };
-                            let base_vault = if base_mint == vertigo_info.mint_a.to_string() {
-                                derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_a).0
-                            } else {
-                                derive_vault_address(&vertigo_pool_pubkey, &vertigo_info.mint_b).0
-                            };
-
-                            // Map to transaction expected fields:
-                            // base_mint is our trading token, non-base should be SOL
-                            let token_x_vault = base_vault; // vault for our trading token
-                            let token_sol_vault = non_base_vault; // vault for SOL
-
-                            info!("    Token X Vault: {}", token_x_vault.to_string());
-                            info!("    Token SOL Vault: {}", token_sol_vault.to_string());
-                            info!("");
+    skipped.report(mint);
 
-                            pool_data.add_vertigo_pool(
-                                pool_address,
-                                &vertigo_info.pool.to_string(),
-                                &token_x_vault.to_string(),
-                                &token_sol_vault.to_string(),
-                            )?;
-                        }
-                        Err(e) => {
-                            error!(
-                                "Error parsing Vertigo pool data from pool {}: {:?}",
-                                vertigo_pool_pubkey, e
-                            );
-                            continue;
-                        }
-                    }
-                }
-                Err(e) => {
-                    error!(
-                        "Error fetching Vertigo pool account {}: {:?}",
-                        vertigo_pool_pubkey, e
-                    );
-                    continue;
-                }
-            }
-        }
+    if let Some(min_liquidity) = min_pool_liquidity {
+        skip_shallow_pools(context.async_rpc_client, &mut pool_data, min_liquidity).await;
     }
-
     Ok(pool_data)
 }
+
+/// 移除报价货币一侧余额低于 `min_liquidity` 的池子
+///
+/// 余额读取失败的池子保留，由运行时的储备检查处理。
+async fn skip_shallow_pools(
+    rpc_client: &NonblockingRpcClient,
+    pool_data: &mut MintPoolData,
+    min_liquidity: u64,
+) {
+    let accounts = fetch_accounts_concurrently(rpc_client, liquidity_accounts(pool_data)).await;
+    let liquidity = pool_liquidity(pool_data, &accounts);
+    let shallow = shallow_pools(pool_data, &liquidity, min_liquidity);
+    if shallow.is_empty() {
+        return;
+    }
+    for (dex, pool, amount) in &shallow {
+        warn!(
+            "Skipping {} pool {} of mint {}: liquidity {} is below the minimum {}",
+            dex, pool, pool_data.mint, amount, min_liquidity
+        );
+    }
+    pool_data.retain_pools(|pool| !shallow.iter().any(|(_, shallow, _)| shallow == pool));
+}
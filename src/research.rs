@@ -0,0 +1,189 @@
+use crate::pools::MintPoolData;
+use crate::quote::SizedQuote;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+/// CSV 文件的表头
+pub const CSV_HEADER: &str = "mint,buy_dex,buy_pool,sell_dex,sell_pool,first_seen_ms,last_seen_ms,duration_ms,observations,size_lamports,expected_profit_lamports,spread_bps";
+
+/// 一组买入/卖出池子从出现到消失的一段套利机会
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opportunity {
+    pub mint: String,
+    pub buy_dex: String,
+    pub buy_pool: Pubkey,
+    pub sell_dex: String,
+    pub sell_pool: Pubkey,
+    /// 第一次观察到的时间（unix 毫秒）
+    pub first_seen_ms: u64,
+    /// 最后一次观察到的时间（unix 毫秒）
+    pub last_seen_ms: u64,
+    /// 持续期间被报价到的次数
+    pub observations: u32,
+    /// 收益最高的一次报价
+    pub best: SizedQuote,
+    /// 收益最高时卖出池相对买入池的价差（基点）
+    pub spread_bps: f64,
+}
+
+impl Opportunity {
+    /// 机会可见的时长（毫秒）
+    pub fn duration_ms(&self) -> u64 {
+        self.last_seen_ms - self.first_seen_ms
+    }
+
+    /// 按 `CSV_HEADER` 的列顺序输出一行
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{:.2}",
+            self.mint,
+            self.buy_dex,
+            self.buy_pool,
+            self.sell_dex,
+            self.sell_pool,
+            self.first_seen_ms,
+            self.last_seen_ms,
+            self.duration_ms(),
+            self.observations,
+            self.best.size,
+            self.best.profit,
+            self.spread_bps
+        )
+    }
+}
+
+/// 研究模式的机会记录器
+///
+/// 每轮报价后传入当前有收益的路线：新出现的池子对开始计时，持续出现的更新最高收益，
+/// 本轮不再出现的写入 CSV 文件。多个代币的任务共用一个记录器。
+pub struct OpportunityLog {
+    writer: Mutex<BufWriter<File>>,
+    /// (买入池, 卖出池) -> 仍然可见的机会
+    open: Mutex<HashMap<(Pubkey, Pubkey), Opportunity>>,
+}
+
+impl OpportunityLog {
+    /// 以追加方式打开（或创建）CSV 文件，新文件先写入表头
+    pub fn create<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+        if writer.get_ref().metadata()?.len() == 0 {
+            writeln!(writer, "{}", CSV_HEADER)?;
+            writer.flush()?;
+        }
+        Ok(Self {
+            writer: Mutex::new(writer),
+            open: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// 当前仍然可见的机会数量
+    pub fn open_count(&self) -> usize {
+        self.open.lock().unwrap().len()
+    }
+
+    /// 记录一个代币本轮报价得到的有收益路线
+    ///
+    /// # 参数
+    /// * `pool_data` - 代币的池子数据，用于查找池子所属的 DEX
+    /// * `routes` - 本轮有收益的路线，见 `quote::profitable_routes`
+    /// * `prices` - 池子的即时价格，用于计算价差
+    /// * `now_ms` - 本轮的时间（unix 毫秒）
+    ///
+    /// # 返回值
+    /// 本轮结束并写入文件的机会数量
+    pub fn observe(
+        &self,
+        pool_data: &MintPoolData,
+        routes: &[SizedQuote],
+        prices: &HashMap<Pubkey, f64>,
+        now_ms: u64,
+    ) -> anyhow::Result<usize> {
+        let mint = pool_data.mint.to_string();
+        let dexes: HashMap<Pubkey, &str> = pool_data
+            .pool_addresses()
+            .into_iter()
+            .map(|(dex, pool)| (pool, dex))
+            .collect();
+        let spread_bps = |route: &SizedQuote| match (
+            prices.get(&route.buy_pool),
+            prices.get(&route.sell_pool),
+        ) {
+            (Some(buy), Some(sell)) if *buy > 0.0 => (sell / buy - 1.0) * 10_000.0,
+            _ => 0.0,
+        };
+
+        let mut open = self.open.lock().unwrap();
+        for route in routes {
+            let key = (route.buy_pool, route.sell_pool);
+            match open.get_mut(&key) {
+                Some(opportunity) => {
+                    opportunity.last_seen_ms = now_ms;
+                    opportunity.observations += 1;
+                    if route.profit > opportunity.best.profit {
+                        opportunity.best = *route;
+                        opportunity.spread_bps = spread_bps(route);
+                    }
+                }
+                None => {
+                    let dex = |pool: &Pubkey| dexes.get(pool).copied().unwrap_or("").to_string();
+                    open.insert(
+                        key,
+                        Opportunity {
+                            mint: mint.clone(),
+                            buy_dex: dex(&route.buy_pool),
+                            buy_pool: route.buy_pool,
+                            sell_dex: dex(&route.sell_pool),
+                            sell_pool: route.sell_pool,
+                            first_seen_ms: now_ms,
+                            last_seen_ms: now_ms,
+                            observations: 1,
+                            best: *route,
+                            spread_bps: spread_bps(route),
+                        },
+                    );
+                }
+            }
+        }
+
+        // 本代币本轮没有再出现的机会已经消失
+        let closed: Vec<(Pubkey, Pubkey)> = open
+            .iter()
+            .filter(|(key, opportunity)| {
+                opportunity.mint == mint
+                    && !routes
+                        .iter()
+                        .any(|route| (route.buy_pool, route.sell_pool) == **key)
+            })
+            .map(|(key, _)| *key)
+            .collect();
+        let closed: Vec<Opportunity> = closed.iter().filter_map(|key| open.remove(key)).collect();
+        drop(open);
+
+        self.write(&closed)?;
+        Ok(closed.len())
+    }
+
+    /// 把仍然可见的机会全部写入文件，用于退出前保存
+    pub fn flush_open(&self) -> anyhow::Result<usize> {
+        let open: Vec<Opportunity> = self.open.lock().unwrap().drain().map(|(_, o)| o).collect();
+        self.write(&open)?;
+        Ok(open.len())
+    }
+
+    fn write(&self, opportunities: &[Opportunity]) -> anyhow::Result<()> {
+        if opportunities.is_empty() {
+            return Ok(());
+        }
+        let mut writer = self.writer.lock().unwrap();
+        for opportunity in opportunities {
+            writeln!(writer, "{}", opportunity.to_csv_row())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use crate::config::RouteFeesConfig;
+use crate::quote::SizedQuote;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// 每条路线统计上链率使用的最近结果数
+pub const LANDING_WINDOW: usize = 50;
+
+/// 一条路线当前的出价和最近 `LANDING_WINDOW` 笔交易的上链情况
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RouteFeeStatus {
+    /// 下一笔交易使用的计算单元价格（micro-lamports）
+    pub compute_unit_price: u64,
+    /// 窗口内已经有结果的交易数
+    pub sent: u64,
+    pub landed: u64,
+}
+
+impl RouteFeeStatus {
+    /// 窗口内的上链率，还没有结果时为 `None`
+    pub fn landing_rate(&self) -> Option<f64> {
+        (self.sent > 0).then(|| self.landed as f64 / self.sent as f64)
+    }
+}
+
+#[derive(Debug)]
+struct RouteState {
+    /// 保留小数，价格很低时每次调整也能累积
+    price: f64,
+    outcomes: VecDeque<bool>,
+}
+
+/// 按路线调整的优先费
+///
+/// 每条路线（代币加上报价选出的买入池和卖出池）各自出价，从配置的计算单元价格开始。
+/// 没有上链时价格乘以 `1 + step * target`，上链时乘以 `1 - step * (1 - target)`，
+/// 上链率等于 `target` 时两者大致抵消：竞争激烈的路线出价逐渐升高，冷门路线逐渐降低，
+/// 价格始终在 `[min, max]` 之间。
+#[derive(Debug)]
+pub struct RouteFees {
+    configured_price: u64,
+    min_price: u64,
+    max_price: u64,
+    target: f64,
+    step: f64,
+    routes: Mutex<HashMap<String, RouteState>>,
+}
+
+impl RouteFees {
+    /// # 参数
+    /// * `configured_price` - `spam.compute_unit_price`，新路线的初始价格
+    pub fn new(config: &RouteFeesConfig, configured_price: u64) -> Self {
+        let min_price = config.min_compute_unit_price(configured_price);
+        Self {
+            configured_price,
+            min_price,
+            // `Config::validate` 保证下限不超过上限
+            max_price: config
+                .max_compute_unit_price(configured_price)
+                .max(min_price),
+            target: config.target_landing_rate(),
+            step: config.step(),
+            routes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 路线标识：有报价时为 `代币:买入池>卖出池`，否则为代币地址（代币的所有池子）
+    pub fn route_key(mint: &Pubkey, quote: Option<&SizedQuote>) -> String {
+        match quote {
+            Some(quote) => format!("{}:{}>{}", mint, quote.buy_pool, quote.sell_pool),
+            None => mint.to_string(),
+        }
+    }
+
+    /// 路线下一笔交易的计算单元价格，新路线使用配置的价格
+    pub fn price(&self, route: &str) -> u64 {
+        match self.routes.lock().unwrap().get(route) {
+            Some(state) => state.price.round() as u64,
+            None => self.configured_price.clamp(self.min_price, self.max_price),
+        }
+    }
+
+    /// 记录路线上一笔交易是否上链，并调整路线的价格
+    ///
+    /// # 返回值
+    /// 调整后的路线状态，用于更新仪表盘
+    pub fn record(&self, route: &str, landed: bool) -> RouteFeeStatus {
+        let initial = self.price(route) as f64;
+        let mut routes = self.routes.lock().unwrap();
+        let state = routes.entry(route.to_string()).or_insert(RouteState {
+            price: initial,
+            outcomes: VecDeque::new(),
+        });
+
+        let factor = if landed {
+            1.0 - self.step * (1.0 - self.target)
+        } else {
+            1.0 + self.step * self.target
+        };
+        // 价格为 0 时乘法调整不起作用，从 1 micro-lamport 开始提高
+        let price = if landed {
+            state.price
+        } else {
+            state.price.max(1.0)
+        };
+        state.price = (price * factor).clamp(self.min_price as f64, self.max_price as f64);
+
+        state.outcomes.push_back(landed);
+        if state.outcomes.len() > LANDING_WINDOW {
+            state.outcomes.pop_front();
+        }
+        RouteFeeStatus {
+            compute_unit_price: state.price.round() as u64,
+            sent: state.outcomes.len() as u64,
+            landed: state.outcomes.iter().filter(|landed| **landed).count() as u64,
+        }
+    }
+}
@@ -0,0 +1,737 @@
+use crate::config::{ProxyConfig, RateLimitConfig};
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcBlockhash};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// 启动阶段账户缓存默认的有效期（slot 数），约一分钟
+pub const DEFAULT_CACHE_SLOTS: u64 = 150;
+
+/// 一个 slot 的大致时长，没有新响应推进 slot 时按时间让缓存失效
+const SLOT_DURATION: Duration = Duration::from_millis(400);
+
+/// 令牌桶限流器
+///
+/// 桶容量为 `burst`，每秒补充 `requests_per_second` 个令牌；
+/// 令牌不足时等待到下一个令牌可用，而不是直接失败。
+#[derive(Debug)]
+pub struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    /// # 参数
+    /// * `requests_per_second` - 平均每秒允许的请求数
+    /// * `burst` - 允许的突发请求数（桶容量）
+    pub fn new(requests_per_second: u32, burst: u32) -> Self {
+        let capacity = burst.max(1) as f64;
+        Self {
+            capacity,
+            refill_per_sec: requests_per_second.max(1) as f64,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// 尝试取出一个令牌
+    ///
+    /// # 返回值
+    /// * `None` - 成功取得令牌
+    /// * `Some(wait)` - 令牌不足，需要等待 `wait` 后重试
+    pub fn try_acquire(&self) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        state.last_refill = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (1.0 - state.tokens) / self.refill_per_sec,
+            ))
+        }
+    }
+
+    /// 等待直到取得一个令牌
+    pub async fn acquire(&self) {
+        while let Some(wait) = self.try_acquire() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 带限流的 RPC 传输层，每个请求发出前先从令牌桶取令牌
+pub struct RateLimitedSender {
+    inner: Box<dyn RpcSender + Send + Sync>,
+    limiter: TokenBucket,
+}
+
+impl RateLimitedSender {
+    pub fn new(url: String, config: &RateLimitConfig) -> Self {
+        Self::with_sender(Box::new(HttpSender::new(url)), config)
+    }
+
+    /// 为已有的传输层加上限流
+    pub fn with_sender(inner: Box<dyn RpcSender + Send + Sync>, config: &RateLimitConfig) -> Self {
+        Self {
+            inner,
+            limiter: TokenBucket::new(config.requests_per_second, config.burst()),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for RateLimitedSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        self.limiter.acquire().await;
+        self.inner.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// 延迟直方图各个桶的上限（毫秒），超过最后一个上限的请求计入溢出桶
+pub const LATENCY_BUCKETS_MS: [u64; 12] =
+    [1, 5, 10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// 一个端点上一种 RPC 方法的请求统计
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MethodStats {
+    pub requests: u64,
+    pub errors: u64,
+    /// 所有请求的总耗时（微秒）
+    pub total_micros: u64,
+    /// 每个桶的请求数（不累加），最后一项是溢出桶
+    pub buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+impl MethodStats {
+    pub fn record(&mut self, latency: Duration, ok: bool) {
+        self.requests += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.total_micros += latency.as_micros() as u64;
+        let millis = latency.as_secs_f64() * 1000.0;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|&bound| millis <= bound as f64)
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        self.buckets[bucket] += 1;
+    }
+
+    /// 平均延迟（毫秒），还没有请求时为 `None`
+    pub fn mean_ms(&self) -> Option<f64> {
+        (self.requests > 0).then(|| self.total_micros as f64 / 1000.0 / self.requests as f64)
+    }
+
+    /// 分位数 `q` 所在桶的上限（毫秒）
+    ///
+    /// # 返回值
+    /// 还没有请求时为 `None`，落在溢出桶时为 `Some(None)`
+    pub fn quantile_ms(&self, q: f64) -> Option<Option<u64>> {
+        if self.requests == 0 {
+            return None;
+        }
+        let rank = ((q * self.requests as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(LATENCY_BUCKETS_MS.get(bucket).copied());
+            }
+        }
+        Some(None)
+    }
+}
+
+/// 按 (端点, 方法) 统计的 RPC 请求延迟和错误数，由 `InstrumentedSender` 记录，
+/// 通过仪表盘的 `/metrics` 导出并定期输出到日志，用于比较 RPC 服务商
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    methods: Mutex<BTreeMap<(String, String), MethodStats>>,
+}
+
+impl RpcMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录一次请求
+    ///
+    /// # 参数
+    /// * `endpoint` - 端点名称，见 `endpoint_label`
+    /// * `method` - JSON-RPC 方法名，例如 `getAccountInfo`
+    /// * `latency` - 从发出请求到收到响应的耗时，不含限流等待
+    /// * `ok` - 请求是否成功
+    pub fn record(&self, endpoint: &str, method: &str, latency: Duration, ok: bool) {
+        self.methods
+            .lock()
+            .unwrap()
+            .entry((endpoint.to_string(), method.to_string()))
+            .or_default()
+            .record(latency, ok);
+    }
+
+    /// 当前统计的副本，按端点和方法排序
+    pub fn snapshot(&self) -> BTreeMap<(String, String), MethodStats> {
+        self.methods.lock().unwrap().clone()
+    }
+
+    /// Prometheus 文本格式的延迟直方图和错误计数
+    pub fn render_prometheus(&self) -> String {
+        let methods = self.snapshot();
+        let mut out = String::new();
+        out.push_str(
+            "# HELP rpc_request_duration_seconds RPC request latency by endpoint and method\n",
+        );
+        out.push_str("# TYPE rpc_request_duration_seconds histogram\n");
+        for ((endpoint, method), stats) in &methods {
+            let labels = format!("endpoint=\"{}\",method=\"{}\"", endpoint, method);
+            let mut cumulative = 0;
+            for (bound, count) in LATENCY_BUCKETS_MS.iter().zip(&stats.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "rpc_request_duration_seconds_bucket{{{},le=\"{}\"}} {}",
+                    labels,
+                    *bound as f64 / 1000.0,
+                    cumulative
+                );
+            }
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_bucket{{{},le=\"+Inf\"}} {}",
+                labels, stats.requests
+            );
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_sum{{{}}} {}",
+                labels,
+                stats.total_micros as f64 / 1_000_000.0
+            );
+            let _ = writeln!(
+                out,
+                "rpc_request_duration_seconds_count{{{}}} {}",
+                labels, stats.requests
+            );
+        }
+        out.push_str(
+            "# HELP rpc_request_errors_total Failed RPC requests by endpoint and method\n",
+        );
+        out.push_str("# TYPE rpc_request_errors_total counter\n");
+        for ((endpoint, method), stats) in &methods {
+            let _ = writeln!(
+                out,
+                "rpc_request_errors_total{{endpoint=\"{}\",method=\"{}\"}} {}",
+                endpoint, method, stats.errors
+            );
+        }
+        out
+    }
+
+    /// 每种方法一行的摘要：请求数、错误数、平均延迟和 p50/p90/p99 所在桶的上限
+    pub fn summary(&self) -> Vec<String> {
+        let quantile = |stats: &MethodStats, q: f64| match stats.quantile_ms(q) {
+            Some(Some(bound)) => format!("<={}ms", bound),
+            Some(None) => format!(">{}ms", LATENCY_BUCKETS_MS[LATENCY_BUCKETS_MS.len() - 1]),
+            None => "-".to_string(),
+        };
+        self.snapshot()
+            .iter()
+            .map(|((endpoint, method), stats)| {
+                format!(
+                    "{} {}: {} requests, {} errors, mean {:.1}ms, p50 {}, p90 {}, p99 {}",
+                    endpoint,
+                    method,
+                    stats.requests,
+                    stats.errors,
+                    stats.mean_ms().unwrap_or_default(),
+                    quantile(stats, 0.5),
+                    quantile(stats, 0.9),
+                    quantile(stats, 0.99)
+                )
+            })
+            .collect()
+    }
+
+    /// 每隔 `interval` 把启动以来的统计摘要输出到日志
+    pub async fn log_summaries(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        // 第一次立即触发，此时还没有统计
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            for line in self.summary() {
+                info!("RPC {}", line);
+            }
+        }
+    }
+}
+
+/// 统计中使用的端点名称：只保留 URL 中的主机和端口，路径和查询参数里常带 API key，不写入日志和指标
+pub fn endpoint_label(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let host = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default();
+    // 去掉 `user:password@`
+    host.rsplit('@').next().unwrap_or(host).to_string()
+}
+
+/// 记录每个请求延迟和结果的 RPC 传输层，按方法名归入 `RpcMetrics`
+pub struct InstrumentedSender {
+    inner: Box<dyn RpcSender + Send + Sync>,
+    metrics: Arc<RpcMetrics>,
+    endpoint: String,
+}
+
+impl InstrumentedSender {
+    pub fn new(inner: Box<dyn RpcSender + Send + Sync>, metrics: Arc<RpcMetrics>) -> Self {
+        let endpoint = endpoint_label(&inner.url());
+        Self {
+            inner,
+            metrics,
+            endpoint,
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for InstrumentedSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let started = Instant::now();
+        let result = self.inner.send(request, params).await;
+        self.metrics.record(
+            &self.endpoint,
+            &request.to_string(),
+            started.elapsed(),
+            result.is_ok(),
+        );
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+#[derive(Debug)]
+struct CachedAccount {
+    value: serde_json::Value,
+    slot: Slot,
+    fetched_at: Instant,
+}
+
+/// 按 (账户地址, 请求配置) 缓存的账户数据
+///
+/// 缓存项在观察到的最新 slot 比获取时晚 `max_age_slots` 个以上后失效；
+/// 长时间没有新响应时按同样长度的时间失效。不存在的账户不缓存，下次仍会请求。
+#[derive(Debug)]
+pub struct AccountCache {
+    max_age_slots: u64,
+    latest_slot: AtomicU64,
+    accounts: Mutex<HashMap<(String, String), CachedAccount>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl AccountCache {
+    pub fn new(max_age_slots: u64) -> Self {
+        Self {
+            max_age_slots,
+            latest_slot: AtomicU64::new(0),
+            accounts: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// 命中缓存的账户读取次数
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// 需要请求 RPC 的账户读取次数
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// 记录响应中的 slot，返回该 slot
+    fn observe_slot(&self, response: &serde_json::Value) -> Option<Slot> {
+        let slot = response.get("context")?.get("slot")?.as_u64()?;
+        self.latest_slot.fetch_max(slot, Ordering::Relaxed);
+        Some(slot)
+    }
+
+    fn get(&self, pubkey: &str, config: &str) -> Option<(serde_json::Value, Slot)> {
+        let key = (pubkey.to_string(), config.to_string());
+        let mut accounts = self.accounts.lock().unwrap();
+        let fresh = accounts.get(&key).map(|cached| {
+            let latest_slot = self.latest_slot.load(Ordering::Relaxed);
+            latest_slot.saturating_sub(cached.slot) <= self.max_age_slots
+                && cached.fetched_at.elapsed() <= SLOT_DURATION * self.max_age_slots as u32
+        });
+        match fresh {
+            Some(true) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                accounts
+                    .get(&key)
+                    .map(|cached| (cached.value.clone(), cached.slot))
+            }
+            Some(false) => {
+                accounts.remove(&key);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+            None => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, pubkey: &str, config: &str, value: &serde_json::Value, slot: Slot) {
+        if value.is_null() {
+            return;
+        }
+        self.accounts.lock().unwrap().insert(
+            (pubkey.to_string(), config.to_string()),
+            CachedAccount {
+                value: value.clone(),
+                slot,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+}
+
+/// 带账户缓存的 RPC 传输层
+///
+/// 只缓存 `getAccountInfo` 和 `getMultipleAccounts`，其他请求直接转发，但会用响应中的
+/// slot 推进缓存的时钟。`getMultipleAccounts` 只请求未命中缓存的账户。
+pub struct CachingSender {
+    inner: Box<dyn RpcSender + Send + Sync>,
+    cache: Arc<AccountCache>,
+}
+
+impl CachingSender {
+    pub fn new(inner: Box<dyn RpcSender + Send + Sync>, cache: Arc<AccountCache>) -> Self {
+        Self { inner, cache }
+    }
+
+    async fn get_account_info(&self, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+        let (Some(pubkey), config) = (params[0].as_str(), params[1].to_string()) else {
+            return self.forward(RpcRequest::GetAccountInfo, params).await;
+        };
+        if let Some((value, slot)) = self.cache.get(pubkey, &config) {
+            return Ok(serde_json::json!({ "context": { "slot": slot }, "value": value }));
+        }
+
+        let pubkey = pubkey.to_string();
+        let response = self.forward(RpcRequest::GetAccountInfo, params).await?;
+        if let Some(slot) = response["context"]["slot"].as_u64() {
+            self.cache
+                .insert(&pubkey, &config, &response["value"], slot);
+        }
+        Ok(response)
+    }
+
+    async fn get_multiple_accounts(
+        &self,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let Some(pubkeys) = params[0].as_array().cloned() else {
+            return self.forward(RpcRequest::GetMultipleAccounts, params).await;
+        };
+        let config = params[1].to_string();
+
+        let mut values = Vec::with_capacity(pubkeys.len());
+        let mut missing = Vec::new();
+        let mut slot = Slot::MAX;
+        for (index, pubkey) in pubkeys.iter().enumerate() {
+            match pubkey
+                .as_str()
+                .and_then(|pubkey| self.cache.get(pubkey, &config))
+            {
+                Some((value, cached_slot)) => {
+                    slot = slot.min(cached_slot);
+                    values.push(value);
+                }
+                None => {
+                    missing.push(index);
+                    values.push(serde_json::Value::Null);
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            let missing_pubkeys: Vec<_> = missing.iter().map(|&i| pubkeys[i].clone()).collect();
+            let response = self
+                .forward(
+                    RpcRequest::GetMultipleAccounts,
+                    serde_json::json!([missing_pubkeys, params[1]]),
+                )
+                .await?;
+            let fetched_slot = response["context"]["slot"].as_u64().unwrap_or_default();
+            slot = slot.min(fetched_slot);
+            let fetched = response["value"].as_array().cloned().unwrap_or_default();
+            for (&index, value) in missing.iter().zip(fetched) {
+                if let Some(pubkey) = pubkeys[index].as_str() {
+                    self.cache.insert(pubkey, &config, &value, fetched_slot);
+                }
+                values[index] = value;
+            }
+        }
+
+        Ok(serde_json::json!({ "context": { "slot": slot }, "value": values }))
+    }
+
+    async fn forward(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        let response = self.inner.send(request, params).await?;
+        self.cache.observe_slot(&response);
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl RpcSender for CachingSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        match request {
+            RpcRequest::GetAccountInfo => self.get_account_info(params).await,
+            RpcRequest::GetMultipleAccounts => self.get_multiple_accounts(params).await,
+            _ => self.forward(request, params).await,
+        }
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// `HttpSender` 默认的请求超时
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 到 `url` 的 HTTP 传输层，配置了代理时按 `HttpSender` 的默认参数自建 reqwest 客户端
+fn http_transport(url: &str, proxy: Option<&ProxyConfig>) -> anyhow::Result<HttpSender> {
+    if proxy.is_none() {
+        return Ok(HttpSender::new(url.to_string()));
+    }
+    let builder = reqwest::Client::builder()
+        .default_headers(HttpSender::default_headers())
+        .timeout(HTTP_TIMEOUT)
+        .pool_idle_timeout(HTTP_TIMEOUT);
+    let client = crate::proxy::configure(builder, proxy)?.build()?;
+    Ok(HttpSender::new_with_client(url.to_string(), client))
+}
+
+/// 到 `url` 的 HTTP 传输层，`metrics` 不为 `None` 时外面包一层请求统计
+fn http_sender(
+    url: &str,
+    metrics: Option<&Arc<RpcMetrics>>,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<Box<dyn RpcSender + Send + Sync>> {
+    let sender = Box::new(http_transport(url, proxy)?);
+    Ok(match metrics {
+        Some(metrics) => Box::new(InstrumentedSender::new(sender, metrics.clone())),
+        None => sender,
+    })
+}
+
+/// 创建 RPC 客户端，配置了限流时每个客户端使用独立的令牌桶
+///
+/// 限流在请求统计之外，统计的延迟不包含限流等待。
+///
+/// # 参数
+/// * `url` - RPC 地址
+/// * `rate_limit` - 限流配置，为 `None` 时不限流
+/// * `metrics` - 请求统计，为 `None` 时不统计
+/// * `proxy` - 代理和出口地址，为 `None` 时直接连接
+///
+/// # 错误
+/// 代理地址无法解析或 HTTP 客户端创建失败时返回错误
+pub fn build_rpc_client(
+    url: &str,
+    rate_limit: Option<&RateLimitConfig>,
+    metrics: Option<&Arc<RpcMetrics>>,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<RpcClient> {
+    let config = RpcClientConfig::with_commitment(CommitmentConfig::default());
+    Ok(match (rate_limit, metrics) {
+        (Some(rate_limit), _) => RpcClient::new_sender(
+            RateLimitedSender::with_sender(http_sender(url, metrics, proxy)?, rate_limit),
+            config,
+        ),
+        (None, Some(metrics)) => RpcClient::new_sender(
+            InstrumentedSender::new(Box::new(http_transport(url, proxy)?), metrics.clone()),
+            config,
+        ),
+        (None, None) => RpcClient::new_sender(http_transport(url, proxy)?, config),
+    })
+}
+
+/// 创建带账户缓存的 RPC 客户端，用于启动阶段跨 mint 重复读取的账户（共享的 AMM 配置、查找表等）
+///
+/// # 参数
+/// * `url` - RPC 地址
+/// * `rate_limit` - 限流配置，为 `None` 时不限流
+/// * `cache` - 账户缓存，调用方保留引用以便查看命中情况
+/// * `metrics` - 请求统计，只记录未命中缓存、实际发出的请求
+/// * `proxy` - 代理和出口地址，为 `None` 时直接连接
+///
+/// # 错误
+/// 同 `build_rpc_client`
+pub fn build_cached_rpc_client(
+    url: &str,
+    rate_limit: Option<&RateLimitConfig>,
+    cache: Arc<AccountCache>,
+    metrics: Option<&Arc<RpcMetrics>>,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<RpcClient> {
+    Ok(RpcClient::new_sender(
+        BoxedSender(startup_sender(
+            url,
+            rate_limit,
+            Some(cache),
+            metrics,
+            proxy,
+        )?),
+        RpcClientConfig::with_commitment(CommitmentConfig::default()),
+    ))
+}
+
+/// 创建启动阶段并发预取账户用的 async RPC 客户端
+///
+/// 与阻塞客户端共享同一个账户缓存时，预取到的账户在之后逐个解析池子时直接命中缓存。
+/// 限流使用独立的令牌桶。
+///
+/// # 参数
+/// * `cache` - 账户缓存，为 `None` 时不缓存
+///
+/// 其余参数同 `build_cached_rpc_client`
+///
+/// # 错误
+/// 同 `build_rpc_client`
+pub fn build_nonblocking_rpc_client(
+    url: &str,
+    rate_limit: Option<&RateLimitConfig>,
+    cache: Option<Arc<AccountCache>>,
+    metrics: Option<&Arc<RpcMetrics>>,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<NonblockingRpcClient> {
+    Ok(NonblockingRpcClient::new_sender(
+        BoxedSender(startup_sender(url, rate_limit, cache, metrics, proxy)?),
+        RpcClientConfig::with_commitment(CommitmentConfig::default()),
+    ))
+}
+
+/// 组合好的传输层，`new_sender` 要求具体类型
+struct BoxedSender(Box<dyn RpcSender + Send + Sync>);
+
+#[async_trait]
+impl RpcSender for BoxedSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        self.0.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.0.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.0.url()
+    }
+}
+
+/// 启动阶段的传输层：请求统计在最内层，外面依次是限流和账户缓存
+fn startup_sender(
+    url: &str,
+    rate_limit: Option<&RateLimitConfig>,
+    cache: Option<Arc<AccountCache>>,
+    metrics: Option<&Arc<RpcMetrics>>,
+    proxy: Option<&ProxyConfig>,
+) -> anyhow::Result<Box<dyn RpcSender + Send + Sync>> {
+    let sender = http_sender(url, metrics, proxy)?;
+    let inner: Box<dyn RpcSender + Send + Sync> = match rate_limit {
+        Some(rate_limit) => Box::new(RateLimitedSender::with_sender(sender, rate_limit)),
+        None => sender,
+    };
+    Ok(match cache {
+        Some(cache) => Box::new(CachingSender::new(inner, cache)),
+        None => inner,
+    })
+}
+
+/// 获取最新的 blockhash 以及 RPC 节点返回它时所在的 slot
+///
+/// slot 用作发送交易时的 `min_context_slot`，保证处理交易的节点不落后于获取 blockhash 的节点。
+pub fn get_latest_blockhash_with_slot(client: &RpcClient) -> anyhow::Result<(Hash, Slot)> {
+    let response: Response<RpcBlockhash> = client.send(
+        RpcRequest::GetLatestBlockhash,
+        serde_json::json!([client.commitment()]),
+    )?;
+    let blockhash = Hash::from_str(&response.value.blockhash)?;
+    Ok((blockhash, response.context.slot))
+}
@@ -0,0 +1,87 @@
+use std::fmt;
+use std::time::Duration;
+
+/// 单个代币发送任务的轮询节奏
+///
+/// 很多代币使用相同的 `process_delay` 时，它们的 RPC 请求和发送会在同一时刻集中爆发。
+/// 每轮等待时间在 `delay` 的基础上加 `[0, jitter]` 的随机抖动，第一轮推迟 `phase_offset`，
+/// 让各个代币错开。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessSchedule {
+    delay: Duration,
+    jitter: Duration,
+    phase_offset: Duration,
+}
+
+impl ProcessSchedule {
+    pub fn new(delay: Duration, jitter: Duration, phase_offset: Duration) -> Self {
+        Self {
+            delay,
+            jitter,
+            phase_offset,
+        }
+    }
+
+    /// 按代币在列表中的位置计算节奏
+    ///
+    /// # 参数
+    /// * `delay_ms` / `jitter_ms` - 代币的 `process_delay` 和 `process_delay_jitter`
+    /// * `phase_offset_ms` - 代币配置的 `phase_offset`，配置后优先使用
+    /// * `index` / `count` - 代币在 `mint_config_list` 中的位置和代币总数
+    /// * `stagger` - 为 true 时没有配置 `phase_offset` 的代币按位置均匀分布在一个 `process_delay` 内
+    pub fn for_mint(
+        delay_ms: u64,
+        jitter_ms: u64,
+        phase_offset_ms: Option<u64>,
+        index: usize,
+        count: usize,
+        stagger: bool,
+    ) -> Self {
+        let phase_offset_ms = match phase_offset_ms {
+            Some(offset) => offset,
+            None if stagger && count > 1 => delay_ms * index as u64 / count as u64,
+            None => 0,
+        };
+        Self::new(
+            Duration::from_millis(delay_ms),
+            Duration::from_millis(jitter_ms),
+            Duration::from_millis(phase_offset_ms),
+        )
+    }
+
+    /// 没有抖动时每轮的等待时间，也是失败退避的起点
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+
+    /// 第一轮开始前的等待时间
+    pub fn phase_offset(&self) -> Duration {
+        self.phase_offset
+    }
+
+    /// 本轮结束后的等待时间，在 `[delay, delay + jitter]` 之间均匀分布
+    pub fn next_delay(&self) -> Duration {
+        let jitter_ms = self.jitter.as_millis() as u64;
+        if jitter_ms == 0 {
+            return self.delay;
+        }
+        self.delay + Duration::from_millis(rand::random::<u64>() % (jitter_ms + 1))
+    }
+}
+
+impl fmt::Display for ProcessSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "every {}ms", self.delay.as_millis())?;
+        if !self.jitter.is_zero() {
+            write!(f, " + up to {}ms jitter", self.jitter.as_millis())?;
+        }
+        if !self.phase_offset.is_zero() {
+            write!(f, ", first round after {}ms", self.phase_offset.as_millis())?;
+        }
+        Ok(())
+    }
+}
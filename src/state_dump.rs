@@ -0,0 +1,232 @@
+//! 池子状态快照，用于离线复现问题
+//!
+//! `--dump-state <dir>` 在池子加载完成后把每个代币解析出的 [`MintPoolData`] 和其中引用的所有账户的
+//! 原始数据写入目录后退出。[`StateDump::load`] 读取快照，重建机器人启动后的内存状态，
+//! 不需要 RPC 就可以重新报价、构建交易或解析账户，复现“某个池子的交易失败”一类的问题。
+
+use crate::app_state::AppState;
+use crate::pools::MintPoolData;
+use crate::quote::{quote_accounts, reserves_from_balances, PoolReserves, MAX_MULTIPLE_ACCOUNTS};
+use crate::status::unix_now;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// 快照格式版本，`MintPoolData` 或池子结构体字段变化时需要递增
+pub const STATE_DUMP_VERSION: u32 = 2;
+
+/// bincode 序列化的完整快照
+pub const STATE_FILE: &str = "state.bin";
+
+/// 便于阅读的摘要：代币、池子和读取时不存在的账户
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// 池子数据和相关账户在某个 slot 的快照
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateDump {
+    pub version: u32,
+    /// 读取账户前的 slot
+    pub slot: Slot,
+    /// 创建时间（Unix 秒），重放报价时作为当前时间
+    pub created_at: u64,
+    pub mints: Vec<MintPoolData>,
+    /// 账户地址 -> 原始账户，读取时不存在的账户不在其中
+    pub accounts: HashMap<Pubkey, Account>,
+}
+
+#[derive(Serialize)]
+struct Manifest {
+    version: u32,
+    slot: Slot,
+    created_at: u64,
+    mints: Vec<ManifestMint>,
+    accounts: usize,
+    missing_accounts: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ManifestMint {
+    mint: String,
+    /// `dex pool`
+    pools: Vec<String>,
+}
+
+/// 一个代币引用的所有账户：mint、钱包账户、各池子 swap 指令的账户和报价读取的账户，按地址排序去重
+pub fn referenced_accounts(pool_data: &MintPoolData) -> Vec<Pubkey> {
+    let mut accounts = BTreeSet::from([
+        pool_data.mint,
+        pool_data.quote_mint,
+        pool_data.wallet_account,
+        pool_data.wallet_wsol_account,
+        pool_data.wallet_usdc_account,
+    ]);
+    for pool in pool_data.pool_accounts() {
+        accounts.insert(pool.pool);
+        accounts.extend(pool.accounts.iter().map(|meta| meta.pubkey));
+    }
+    accounts.extend(
+        quote_accounts(pool_data)
+            .into_iter()
+            .map(|(pubkey, _)| pubkey),
+    );
+    accounts.into_iter().collect()
+}
+
+/// 分批读取 `pubkeys`，返回存在的账户，不存在的账户不在结果中
+///
+/// # 错误
+/// 任意一批读取失败时返回错误
+pub fn fetch_accounts(
+    rpc_client: &RpcClient,
+    pubkeys: &[Pubkey],
+) -> anyhow::Result<HashMap<Pubkey, Account>> {
+    let mut accounts = HashMap::new();
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let fetched = rpc_client.get_multiple_accounts(chunk)?;
+        for (pubkey, account) in chunk.iter().zip(fetched) {
+            if let Some(account) = account {
+                accounts.insert(*pubkey, account);
+            }
+        }
+    }
+    Ok(accounts)
+}
+
+impl StateDump {
+    /// 读取 `mints` 引用的所有账户，生成快照
+    ///
+    /// # 参数
+    /// * `rpc_client` - 读取账户的 RPC 客户端，应当读取最新状态而不是启动缓存
+    /// * `mints` - 加载完成的池子数据
+    ///
+    /// # 错误
+    /// 读取 slot 或账户失败时返回错误
+    pub fn capture(rpc_client: &RpcClient, mints: Vec<MintPoolData>) -> anyhow::Result<Self> {
+        let slot = rpc_client
+            .get_slot()
+            .context("Failed to read the current slot")?;
+        let pubkeys: BTreeSet<Pubkey> = mints.iter().flat_map(referenced_accounts).collect();
+        let pubkeys: Vec<Pubkey> = pubkeys.into_iter().collect();
+        let accounts = fetch_accounts(rpc_client, &pubkeys)
+            .context("Failed to read accounts for the state dump")?;
+
+        Ok(Self {
+            version: STATE_DUMP_VERSION,
+            slot,
+            created_at: unix_now(),
+            mints,
+            accounts,
+        })
+    }
+
+    /// 写入目录 `dir`，目录不存在时创建，已有的快照文件被覆盖
+    ///
+    /// # 错误
+    /// 创建目录、序列化或写文件失败时返回错误
+    pub fn save<P: AsRef<Path>>(&self, dir: P) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create state dump directory {}", dir.display()))?;
+        std::fs::write(dir.join(STATE_FILE), bincode::serialize(self)?)
+            .with_context(|| format!("Failed to write {}", dir.join(STATE_FILE).display()))?;
+        std::fs::write(
+            dir.join(MANIFEST_FILE),
+            serde_json::to_string_pretty(&self.manifest())?,
+        )
+        .with_context(|| format!("Failed to write {}", dir.join(MANIFEST_FILE).display()))?;
+        Ok(())
+    }
+
+    /// 读取 `save` 写入的快照
+    ///
+    /// # 错误
+    /// 文件不存在、无法解析或版本不匹配时返回错误
+    pub fn load<P: AsRef<Path>>(dir: P) -> anyhow::Result<Self> {
+        let path = dir.as_ref().join(STATE_FILE);
+        let bytes = std::fs::read(&path)
+            .with_context(|| format!("Failed to read state dump {}", path.display()))?;
+        let dump: Self = bincode::deserialize(&bytes)
+            .with_context(|| format!("Failed to parse state dump {}", path.display()))?;
+        if dump.version != STATE_DUMP_VERSION {
+            return Err(anyhow::anyhow!(
+                "State dump {} has version {}, expected {}",
+                path.display(),
+                dump.version,
+                STATE_DUMP_VERSION
+            ));
+        }
+        Ok(dump)
+    }
+
+    /// 快照中 `mint` 的池子数据
+    pub fn mint(&self, mint: &Pubkey) -> Option<&MintPoolData> {
+        self.mints.iter().find(|pool_data| pool_data.mint == *mint)
+    }
+
+    /// 快照中账户的原始数据
+    pub fn account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.accounts.get(pubkey)
+    }
+
+    /// 引用了但读取时不存在的账户，例如还没有创建的钱包 ATA 或已经关闭的池子账户
+    pub fn missing_accounts(&self) -> Vec<Pubkey> {
+        let referenced: BTreeSet<Pubkey> =
+            self.mints.iter().flat_map(referenced_accounts).collect();
+        referenced
+            .into_iter()
+            .filter(|pubkey| !self.accounts.contains_key(pubkey))
+            .collect()
+    }
+
+    /// 用快照中的账户数据重新计算池子储备，与运行时的 `quote::fetch_reserves` 一致
+    pub fn reserves(&self, pool_data: &MintPoolData) -> HashMap<Pubkey, PoolReserves> {
+        let balances: HashMap<Pubkey, u64> = quote_accounts(pool_data)
+            .into_iter()
+            .filter_map(|(pubkey, kind)| {
+                let account = self.accounts.get(&pubkey)?;
+                Some((pubkey, kind.amount(&account.data, self.created_at)?))
+            })
+            .collect();
+        reserves_from_balances(pool_data, |pubkey| balances.get(pubkey).copied())
+    }
+
+    /// 把快照中的池子数据注册到 `state`，与启动时加载完池子后的状态相同
+    pub fn restore(&self, state: &AppState) {
+        for pool_data in &self.mints {
+            state.insert_mint(pool_data.mint, Arc::new(Mutex::new(pool_data.clone())));
+        }
+    }
+
+    fn manifest(&self) -> Manifest {
+        Manifest {
+            version: self.version,
+            slot: self.slot,
+            created_at: self.created_at,
+            mints: self
+                .mints
+                .iter()
+                .map(|pool_data| ManifestMint {
+                    mint: pool_data.mint.to_string(),
+                    pools: pool_data
+                        .pool_addresses()
+                        .into_iter()
+                        .map(|(dex, pool)| format!("{} {}", dex, pool))
+                        .collect(),
+                })
+                .collect(),
+            accounts: self.accounts.len(),
+            missing_accounts: self
+                .missing_accounts()
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+        }
+    }
+}
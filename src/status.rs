@@ -0,0 +1,332 @@
+use crate::pools::MintPoolData;
+use crate::route_fees::RouteFeeStatus;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// 仪表盘保留的最近交易数量
+pub const MAX_RECENT_TRANSACTIONS: usize = 50;
+
+/// 交易结果
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TransactionOutcome {
+    /// 已发送，等待确认
+    Sent,
+    /// 已上链，附带实际收益（lamports）
+    Landed { profit_lamports: i64 },
+    /// 上链失败或超时未上链
+    NotLanded,
+    /// 发送失败
+    SendFailed { error: String },
+    /// dry-run 模式下只做了模拟
+    Simulated,
+}
+
+impl TransactionOutcome {
+    /// 结果名称，与 JSON 中的 `status` 字段一致
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionOutcome::Sent => "sent",
+            TransactionOutcome::Landed { .. } => "landed",
+            TransactionOutcome::NotLanded => "not_landed",
+            TransactionOutcome::SendFailed { .. } => "send_failed",
+            TransactionOutcome::Simulated => "simulated",
+        }
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        match self {
+            TransactionOutcome::SendFailed { error } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionRecord {
+    pub mint: String,
+    pub signature: Option<String>,
+    pub timestamp: u64,
+    pub outcome: TransactionOutcome,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolSummary {
+    pub dex: String,
+    pub pool: String,
+    /// (代币 vault, SOL vault)
+    #[serde(skip)]
+    pub vaults: Option<(Pubkey, Pubkey)>,
+    /// 代币 vault 余额，启用 vault 余额跟踪后才有
+    pub token_balance: Option<u64>,
+    /// SOL vault 余额（lamports）
+    pub sol_balance: Option<u64>,
+    /// 最近一轮报价的即时价格（lamports / 代币最小单位），每轮报价后才有
+    pub price: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MintStatus {
+    pub pools: Vec<PoolSummary>,
+    /// 池子数据最后一次刷新的时间（unix 秒）
+    pub last_refresh: u64,
+    /// 最近一轮报价中可买入池和可卖出池之间的最大价差（基点）
+    pub best_spread_bps: Option<f64>,
+    /// 已发送的交易数量，不含 dry-run 模拟和发送失败
+    pub sent_count: u64,
+    pub landed_count: u64,
+    pub realized_pnl_lamports: i64,
+    /// 最近一次交易尝试
+    pub last_send: Option<TransactionRecord>,
+}
+
+impl MintStatus {
+    /// 已发送交易的上链比例，还没有发送过交易时为 `None`
+    pub fn landed_ratio(&self) -> Option<f64> {
+        (self.sent_count > 0).then(|| self.landed_count as f64 / self.sent_count as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RpcHealth {
+    pub url: String,
+    pub healthy: bool,
+    pub last_success: Option<u64>,
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+}
+
+/// 仪表盘和状态 API 使用的快照
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatusSnapshot {
+    pub started_at: u64,
+    pub dry_run: bool,
+    pub mints: BTreeMap<String, MintStatus>,
+    pub recent_transactions: VecDeque<TransactionRecord>,
+    pub realized_pnl_lamports: i64,
+    pub landed_count: u64,
+    pub priority_fee_micro_lamports: u64,
+    /// 启用 `[route_fees]` 时每条路线的出价和上链情况，见 `RouteFees::route_key`
+    pub route_fees: BTreeMap<String, RouteFeeStatus>,
+    /// SOL/USD 价格，未启用 `[price_feed]` 或还没有获取到时为 `None`
+    pub sol_usd_price: Option<f64>,
+    pub rpc: RpcHealth,
+}
+
+/// 机器人运行状态，在各个任务之间共享
+#[derive(Debug, Default)]
+pub struct BotStatus {
+    inner: RwLock<StatusSnapshot>,
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+pub fn unix_now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+impl BotStatus {
+    pub fn new(rpc_url: String, dry_run: bool, priority_fee_micro_lamports: u64) -> Self {
+        Self {
+            inner: RwLock::new(StatusSnapshot {
+                started_at: unix_now(),
+                dry_run,
+                priority_fee_micro_lamports,
+                rpc: RpcHealth {
+                    url: rpc_url,
+                    healthy: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// 返回当前状态的副本
+    pub fn snapshot(&self) -> StatusSnapshot {
+        self.inner.read().unwrap().clone()
+    }
+
+    /// 主 RPC 的健康状态，不复制整个快照
+    pub fn rpc_health(&self) -> RpcHealth {
+        self.inner.read().unwrap().rpc.clone()
+    }
+
+    /// 记录某个代币的池子列表，并更新刷新时间
+    pub fn update_pools(&self, mint_pool_data: &MintPoolData) {
+        let vaults: HashMap<Pubkey, (Pubkey, Pubkey)> = mint_pool_data
+            .pool_vaults()
+            .into_iter()
+            .map(|(pool, token_vault, sol_vault)| (pool, (token_vault, sol_vault)))
+            .collect();
+        let pools = mint_pool_data
+            .pool_addresses()
+            .into_iter()
+            .map(|(dex, pool)| PoolSummary {
+                dex: dex.to_string(),
+                pool: pool.to_string(),
+                vaults: vaults.get(&pool).copied(),
+                token_balance: None,
+                sol_balance: None,
+                price: None,
+            })
+            .collect();
+
+        // 刷新池子列表时保留交易统计
+        let mut inner = self.inner.write().unwrap();
+        let mint = inner
+            .mints
+            .entry(mint_pool_data.mint.to_string())
+            .or_default();
+        mint.pools = pools;
+        mint.last_refresh = unix_now();
+    }
+
+    /// 用最新一轮报价更新某个代币的池子价格和最大价差
+    ///
+    /// # 参数
+    /// * `mint_pool_data` - 代币的池子数据，用于按交易方向计算价差
+    /// * `prices` - 池子地址 -> 即时价格，见 `quote::spot_prices`
+    pub fn update_prices(&self, mint_pool_data: &MintPoolData, prices: &HashMap<Pubkey, f64>) {
+        let best_spread_bps =
+            crate::quote::best_spread(mint_pool_data, prices).map(|spread| spread * 10_000.0);
+        let mut inner = self.inner.write().unwrap();
+        if let Some(mint) = inner.mints.get_mut(&mint_pool_data.mint.to_string()) {
+            for pool in &mut mint.pools {
+                pool.price = pool
+                    .pool
+                    .parse::<Pubkey>()
+                    .ok()
+                    .and_then(|address| prices.get(&address).copied());
+            }
+            mint.best_spread_bps = best_spread_bps;
+        }
+    }
+
+    /// 用最新的 vault 余额更新所有池子
+    pub fn update_vault_balances(&self, balances: &HashMap<Pubkey, u64>) {
+        let mut inner = self.inner.write().unwrap();
+        for pool in inner
+            .mints
+            .values_mut()
+            .flat_map(|mint| mint.pools.iter_mut())
+        {
+            if let Some((token_vault, sol_vault)) = pool.vaults {
+                pool.token_balance = balances.get(&token_vault).copied();
+                pool.sol_balance = balances.get(&sol_vault).copied();
+            }
+        }
+    }
+
+    /// 记录一笔交易，超过 `MAX_RECENT_TRANSACTIONS` 时丢弃最旧的记录
+    pub fn record_transaction(
+        &self,
+        mint: &str,
+        signature: Option<Signature>,
+        outcome: TransactionOutcome,
+    ) {
+        let mut inner = self.inner.write().unwrap();
+        if let TransactionOutcome::Landed { profit_lamports } = outcome {
+            inner.realized_pnl_lamports += profit_lamports;
+            inner.landed_count += 1;
+        }
+        let record = TransactionRecord {
+            mint: mint.to_string(),
+            signature: signature.map(|s| s.to_string()),
+            timestamp: unix_now(),
+            outcome,
+        };
+        let mint_status = inner.mints.entry(mint.to_string()).or_default();
+        match record.outcome {
+            TransactionOutcome::Sent => mint_status.sent_count += 1,
+            TransactionOutcome::Landed { profit_lamports } => {
+                mint_status.sent_count += 1;
+                mint_status.landed_count += 1;
+                mint_status.realized_pnl_lamports += profit_lamports;
+            }
+            _ => {}
+        }
+        mint_status.last_send = Some(record.clone());
+        inner.recent_transactions.push_front(record);
+        inner.recent_transactions.truncate(MAX_RECENT_TRANSACTIONS);
+    }
+
+    /// 更新已发送交易的最终结果
+    pub fn update_transaction_outcome(
+        &self,
+        mint: &str,
+        signature: &Signature,
+        outcome: TransactionOutcome,
+    ) {
+        let mut inner = self.inner.write().unwrap();
+        if let TransactionOutcome::Landed { profit_lamports } = outcome {
+            inner.realized_pnl_lamports += profit_lamports;
+            inner.landed_count += 1;
+        }
+        let signature = signature.to_string();
+        if let Some(mint_status) = inner.mints.get_mut(mint) {
+            if let TransactionOutcome::Landed { profit_lamports } = outcome {
+                mint_status.landed_count += 1;
+                mint_status.realized_pnl_lamports += profit_lamports;
+            }
+            if let Some(record) = mint_status
+                .last_send
+                .as_mut()
+                .filter(|record| record.signature.as_deref() == Some(signature.as_str()))
+            {
+                record.outcome = outcome.clone();
+            }
+        }
+        if let Some(record) = inner
+            .recent_transactions
+            .iter_mut()
+            .find(|record| record.signature.as_deref() == Some(signature.as_str()))
+        {
+            record.outcome = outcome;
+        }
+    }
+
+    /// 更新当前使用的计算单元价格
+    pub fn set_priority_fee(&self, micro_lamports: u64) {
+        self.inner.write().unwrap().priority_fee_micro_lamports = micro_lamports;
+    }
+
+    /// 更新一条路线的出价和上链情况
+    pub fn update_route_fee(&self, route: &str, status: RouteFeeStatus) {
+        self.inner
+            .write()
+            .unwrap()
+            .route_fees
+            .insert(route.to_string(), status);
+    }
+
+    pub fn set_sol_usd_price(&self, price: Option<f64>) {
+        self.inner.write().unwrap().sol_usd_price = price;
+    }
+
+    pub fn record_rpc_success(&self) {
+        let mut inner = self.inner.write().unwrap();
+        inner.rpc.healthy = true;
+        inner.rpc.last_success = Some(unix_now());
+        inner.rpc.consecutive_failures = 0;
+    }
+
+    pub fn record_rpc_failure(&self, error: String) {
+        let mut inner = self.inner.write().unwrap();
+        inner.rpc.healthy = false;
+        inner.rpc.consecutive_failures += 1;
+        inner.rpc.last_error = Some(error);
+    }
+}
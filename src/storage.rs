@@ -0,0 +1,239 @@
+use crate::status::{unix_now, TransactionOutcome};
+use rusqlite::{params, Connection, OptionalExtension};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::signature::Signature;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// 交易历史中的一条记录
+#[derive(Debug, Clone)]
+pub struct TradeRecord {
+    pub id: i64,
+    /// unix 秒
+    pub timestamp: u64,
+    pub mint: String,
+    /// 参与交易的 DEX，例如 `raydium,pump`
+    pub route: String,
+    pub signature: Option<String>,
+    /// dry-run 模拟得到的预期收益（lamports）
+    pub simulated_profit: Option<i64>,
+    /// 上链后的实际净收益（lamports）
+    pub realized_profit: Option<i64>,
+    pub outcome: String,
+    pub error: Option<String>,
+    /// 实际支付的交易手续费（lamports）
+    pub fee: Option<u64>,
+    /// 小费（lamports）
+    pub tip: Option<u64>,
+}
+
+/// `history` 子命令的查询条件
+#[derive(Debug, Clone, Default)]
+pub struct HistoryQuery {
+    pub mint: Option<String>,
+    pub outcome: Option<String>,
+    /// 只返回该时间（unix 秒）之后的记录
+    pub since: Option<u64>,
+    pub limit: usize,
+}
+
+/// 基于 SQLite 的交易历史存储
+///
+/// 连接由互斥锁保护，可以通过 `Arc<TradeStore>` 在多个任务间共享。
+pub struct TradeStore {
+    conn: Mutex<Connection>,
+}
+
+impl TradeStore {
+    /// 打开（或创建）数据库并初始化表结构
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                timestamp INTEGER NOT NULL,
+                mint TEXT NOT NULL,
+                route TEXT NOT NULL,
+                signature TEXT,
+                simulated_profit INTEGER,
+                realized_profit INTEGER,
+                outcome TEXT NOT NULL,
+                error TEXT,
+                fee INTEGER,
+                tip INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS trades_signature ON trades (signature);
+            CREATE INDEX IF NOT EXISTS trades_mint_timestamp ON trades (mint, timestamp);",
+        )?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// 记录一次交易尝试，返回记录 ID
+    pub fn record_attempt(
+        &self,
+        mint: &str,
+        route: &str,
+        signature: Option<&Signature>,
+        simulated_profit: Option<i64>,
+        outcome: &TransactionOutcome,
+    ) -> anyhow::Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO trades (timestamp, mint, route, signature, simulated_profit, outcome, error)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                unix_now() as i64,
+                mint,
+                route,
+                signature.map(|s| s.to_string()),
+                simulated_profit,
+                outcome.as_str(),
+                outcome.error(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// 记录已发送交易支付的小费
+    pub fn record_tip(&self, signature: &Signature, tip: u64) -> anyhow::Result<()> {
+        self.conn.lock().unwrap().execute(
+            "UPDATE trades SET tip = ?1 WHERE signature = ?2",
+            params![tip as i64, signature.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// 更新已发送交易的最终结果
+    pub fn update_outcome(
+        &self,
+        signature: &Signature,
+        outcome: &TransactionOutcome,
+        fee: Option<u64>,
+    ) -> anyhow::Result<()> {
+        let realized_profit = match outcome {
+            TransactionOutcome::Landed { profit_lamports } => Some(*profit_lamports),
+            _ => None,
+        };
+        self.conn.lock().unwrap().execute(
+            "UPDATE trades SET outcome = ?1, realized_profit = ?2, fee = ?3 WHERE signature = ?4",
+            params![
+                outcome.as_str(),
+                realized_profit,
+                fee.map(|f| f as i64),
+                signature.to_string(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 按条件查询交易历史，按时间倒序返回
+    pub fn query(&self, query: &HistoryQuery) -> anyhow::Result<Vec<TradeRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp, mint, route, signature, simulated_profit, realized_profit,
+                    outcome, error, fee, tip
+             FROM trades
+             WHERE (?1 IS NULL OR mint = ?1)
+               AND (?2 IS NULL OR outcome = ?2)
+               AND (?3 IS NULL OR timestamp >= ?3)
+             ORDER BY id DESC
+             LIMIT ?4",
+        )?;
+        let rows = stmt.query_map(
+            params![
+                query.mint,
+                query.outcome,
+                query.since.map(|s| s as i64),
+                query.limit as i64,
+            ],
+            |row| {
+                Ok(TradeRecord {
+                    id: row.get(0)?,
+                    timestamp: row.get::<_, i64>(1)? as u64,
+                    mint: row.get(2)?,
+                    route: row.get(3)?,
+                    signature: row.get(4)?,
+                    simulated_profit: row.get(5)?,
+                    realized_profit: row.get(6)?,
+                    outcome: row.get(7)?,
+                    error: row.get(8)?,
+                    fee: row.get::<_, Option<i64>>(9)?.map(|f| f as u64),
+                    tip: row.get::<_, Option<i64>>(10)?.map(|t| t as u64),
+                })
+            },
+        )?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    /// 已上链交易的累计净收益（lamports）
+    pub fn realized_pnl(&self, mint: Option<&str>) -> anyhow::Result<i64> {
+        let pnl = self
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                "SELECT SUM(realized_profit) FROM trades WHERE ?1 IS NULL OR mint = ?1",
+                params![mint],
+                |row| row.get::<_, Option<i64>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(pnl.unwrap_or_default())
+    }
+}
+
+/// 以表格形式打印交易历史，供 `history` 子命令使用
+pub fn print_history(store: &TradeStore, query: &HistoryQuery) -> anyhow::Result<()> {
+    let records = store.query(query)?;
+    if records.is_empty() {
+        println!("No trades found");
+        return Ok(());
+    }
+
+    let sol = |lamports: Option<i64>| {
+        lamports.map_or("-".to_string(), |l| {
+            format!("{:.6}", l as f64 / LAMPORTS_PER_SOL as f64)
+        })
+    };
+
+    println!(
+        "{:>6}  {:<19}  {:<44}  {:<12}  {:>10}  {:>10}  {:>8}  {:>8}  {:<24}  signature",
+        "id", "time (utc)", "mint", "outcome", "sim (SOL)", "pnl (SOL)", "fee", "tip", "route",
+    );
+    for record in &records {
+        println!(
+            "{:>6}  {:<19}  {:<44}  {:<12}  {:>10}  {:>10}  {:>8}  {:>8}  {:<24}  {}",
+            record.id,
+            format_timestamp(record.timestamp),
+            record.mint,
+            record.outcome,
+            sol(record.simulated_profit),
+            sol(record.realized_profit),
+            record.fee.map_or("-".to_string(), |f| f.to_string()),
+            record.tip.map_or("-".to_string(), |t| t.to_string()),
+            record.route,
+            record
+                .signature
+                .as_deref()
+                .or(record.error.as_deref())
+                .unwrap_or("-"),
+        );
+    }
+
+    println!(
+        "\n{} trade(s), realized PnL {} SOL",
+        records.len(),
+        sol(Some(store.realized_pnl(query.mint.as_deref())?))
+    );
+    Ok(())
+}
+
+/// 将 unix 秒格式化为 `YYYY-MM-DD HH:MM:SS`（UTC）
+fn format_timestamp(timestamp: u64) -> String {
+    chrono::DateTime::from_timestamp(timestamp as i64, 0).map_or_else(
+        || timestamp.to_string(),
+        |t| t.format("%Y-%m-%d %H:%M:%S").to_string(),
+    )
+}
@@ -0,0 +1,454 @@
+use crate::config::{Config, JitoConfig, TxVersion};
+use crate::constants::sol_mint;
+use crate::jito::{tip_for_profit, JitoSender};
+use crate::pools::MintPoolData;
+use crate::tpu::TpuSender;
+use crate::transaction::{keeps_same_pools, simulate_dry_run, SendOutcome, SendingClient};
+use async_trait::async_trait;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{debug, error, info};
+
+/// 模拟记录器保留的最近交易数
+pub const PAPER_TRADE_HISTORY: usize = 1000;
+
+/// 交给提交器发送的一笔已签名交易
+pub struct Submission<'a> {
+    /// 使用地址查找表的 v0 交易
+    pub tx: &'a VersionedTransaction,
+    /// 要求 legacy 交易的端点使用的交易，为 `None` 时跳过这些端点
+    pub legacy_tx: Option<&'a VersionedTransaction>,
+    /// 获取 blockhash 时 RPC 所在的 slot，用作 `min_context_slot`
+    pub context_slot: Slot,
+    /// 报价货币，只有 SOL 报价的收益可以直接支付 Jito 小费
+    pub quote_mint: Pubkey,
+    /// 钱包持有报价货币的账户，模拟时用来计算预期收益
+    pub wallet_quote_account: Pubkey,
+}
+
+impl<'a> Submission<'a> {
+    /// 一组池子的交易，报价货币取自池子数据，不带 legacy 交易
+    pub fn new(
+        tx: &'a VersionedTransaction,
+        mint_pool_data: &MintPoolData,
+        context_slot: Slot,
+    ) -> Self {
+        Self {
+            tx,
+            legacy_tx: None,
+            context_slot,
+            quote_mint: mint_pool_data.quote_mint,
+            wallet_quote_account: mint_pool_data.wallet_quote_account(),
+        }
+    }
+
+    pub fn with_legacy_tx(mut self, legacy_tx: Option<&'a VersionedTransaction>) -> Self {
+        self.legacy_tx = legacy_tx;
+        self
+    }
+}
+
+/// 一个提交器的发送结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubmissionResult {
+    /// 签名 -> 接受了交易的端点，按第一次发送的顺序；同一笔交易经多个端点发送时签名相同
+    pub sent: Vec<(Signature, Vec<String>)>,
+    /// 有端点报告交易已经处理过，剩下的端点和提交器不再发送
+    pub already_processed: bool,
+    /// 模拟得到的预期收益（报价货币的最小单位，SOL 为 lamports），只有模拟记录器设置
+    pub simulated_profit: Option<i64>,
+    /// 通过 Jito bundle 发送（或模拟时将要）支付的小费（lamports）
+    pub tip: Option<u64>,
+}
+
+impl SubmissionResult {
+    /// 记录一次发送成功，同一个签名的端点合并到一起
+    pub fn record(&mut self, signature: Signature, endpoint: &str) {
+        match self
+            .sent
+            .iter_mut()
+            .find(|(sent_signature, _)| *sent_signature == signature)
+        {
+            Some((_, endpoints)) => endpoints.push(endpoint.to_string()),
+            None => self.sent.push((signature, vec![endpoint.to_string()])),
+        }
+    }
+
+    /// 合并另一个提交器的结果
+    pub fn merge(&mut self, other: SubmissionResult) {
+        for (signature, endpoints) in other.sent {
+            for endpoint in endpoints {
+                self.record(signature, &endpoint);
+            }
+        }
+        self.already_processed |= other.already_processed;
+        self.simulated_profit = self.simulated_profit.or(other.simulated_profit);
+        self.tip = self.tip.or(other.tip);
+    }
+}
+
+/// 交易提交方式：RPC 发送端点、TPU 直连、Jito bundle 或 dry-run 模拟记录器
+///
+/// 提交器只负责把已经构建好的交易发出去，交易的构建和签名由调用方完成，
+/// 测试时可以换成记录交易的实现。
+#[async_trait]
+pub trait Submitter: Send + Sync {
+    /// 发送到的端点，用于单位经济记录
+    fn endpoints(&self) -> Vec<String>;
+
+    /// 是否有端点需要不使用查找表的 legacy 交易
+    fn wants_legacy(&self) -> bool {
+        false
+    }
+
+    /// 发送一笔交易
+    ///
+    /// # 返回值
+    /// 接受了交易的端点；单个端点失败只记录日志，返回的结果中不包含该端点
+    ///
+    /// # 错误
+    /// 整个提交器无法发送时返回错误，例如 Jito 模拟收益不足以支付小费
+    async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SubmissionResult>;
+}
+
+/// 按顺序扇出到多个提交器，合并它们的结果
+///
+/// 某个提交器报告交易已经处理过时不再发送给剩下的提交器。
+#[derive(Default)]
+pub struct Submitters {
+    submitters: Vec<Arc<dyn Submitter>>,
+}
+
+impl Submitters {
+    pub fn new(submitters: Vec<Arc<dyn Submitter>>) -> Self {
+        Self { submitters }
+    }
+
+    /// 按配置选择提交器
+    ///
+    /// dry-run 模式下只用模拟记录器，永不广播；启用 Jito 时只发送 bundle；
+    /// 否则先通过 TPU 直接发给 leader（延迟最低），再通过 RPC 发送端点发送。
+    ///
+    /// # 参数
+    /// * `config` - 机器人配置
+    /// * `rpc_client` - 主 RPC 客户端，dry-run 模式下用于模拟
+    /// * `sending_clients` - RPC 发送端点，只通过 TPU 发送时为空
+    /// * `tpu_sender` - TPU 发送器，`sending.mode` 为 `tpu` 或 `both` 时启用
+    /// * `jito_sender` - 启用 `[jito]` 时的区块引擎发送器
+    pub fn from_config(
+        config: &Config,
+        rpc_client: &Arc<RpcClient>,
+        sending_clients: Vec<SendingClient>,
+        tpu_sender: Option<Arc<TpuSender>>,
+        jito_sender: Option<Arc<JitoSender>>,
+    ) -> Self {
+        if config.is_dry_run() {
+            let paper = match config.jito.as_ref().filter(|jito| jito.enabled) {
+                Some(jito) => PaperSubmitter::new(rpc_client.clone()).with_tip(jito),
+                None => PaperSubmitter::new(rpc_client.clone()),
+            };
+            return Self::new(vec![Arc::new(paper)]);
+        }
+        if let Some(jito_sender) = jito_sender {
+            return Self::new(vec![jito_sender]);
+        }
+        let mut submitters: Vec<Arc<dyn Submitter>> = Vec::new();
+        if let Some(tpu_sender) = tpu_sender {
+            submitters.push(tpu_sender);
+        }
+        if !sending_clients.is_empty() {
+            let jitter = config
+                .spam
+                .as_ref()
+                .map_or(Duration::ZERO, |spam| spam.jitter());
+            submitters.push(Arc::new(RpcSubmitter::new(sending_clients, jitter)));
+        }
+        Self::new(submitters)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.submitters.is_empty()
+    }
+
+    /// 所有提交器发送到的端点，按发送顺序
+    pub fn endpoints(&self) -> Vec<String> {
+        self.submitters
+            .iter()
+            .flat_map(|submitter| submitter.endpoints())
+            .collect()
+    }
+
+    /// 是否需要构建 legacy 交易
+    pub fn wants_legacy(&self) -> bool {
+        self.submitters
+            .iter()
+            .any(|submitter| submitter.wants_legacy())
+    }
+
+    /// 依次通过每个提交器发送交易
+    ///
+    /// 每个签名只出现一次，并汇总成一条日志。
+    ///
+    /// # 错误
+    /// 所有提交器都返回错误时返回最后一个错误；只是没有端点接受交易时签名为空
+    pub async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SendOutcome> {
+        let mut result = SubmissionResult::default();
+        let mut last_error = None;
+        let mut succeeded = false;
+        for submitter in &self.submitters {
+            match submitter.send(submission).await {
+                Ok(submitted) => {
+                    succeeded = true;
+                    result.merge(submitted);
+                    if result.already_processed {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    if self.submitters.len() > 1 {
+                        error!(
+                            "Failed to send transaction through {}: {}",
+                            submitter.endpoints().join(", "),
+                            e
+                        );
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        if let (false, Some(e)) = (succeeded, last_error) {
+            return Err(e);
+        }
+
+        for (signature, endpoints) in &result.sent {
+            info!(
+                "Transaction {} sent through {}",
+                signature,
+                endpoints.join(", ")
+            );
+        }
+        Ok(SendOutcome {
+            signatures: result
+                .sent
+                .into_iter()
+                .map(|(signature, _)| signature)
+                .collect(),
+            already_processed: result.already_processed,
+            simulated_profit: result.simulated_profit,
+            tip: result.tip,
+            ..Default::default()
+        })
+    }
+}
+
+/// 通过 RPC 发送端点的 `sendTransaction` 发送
+///
+/// 依次通过每个端点发送，每个端点使用各自的预检、重试、`min_context_slot` 和交易版本设置；
+/// 设置了 `jitter` 时，相邻端点之间随机间隔一段时间，避免同一笔交易在各节点间同时竞争。
+pub struct RpcSubmitter {
+    sending_clients: Vec<SendingClient>,
+    jitter: Duration,
+}
+
+impl RpcSubmitter {
+    pub fn new(sending_clients: Vec<SendingClient>, jitter: Duration) -> Self {
+        Self {
+            sending_clients,
+            jitter,
+        }
+    }
+}
+
+#[async_trait]
+impl Submitter for RpcSubmitter {
+    fn endpoints(&self) -> Vec<String> {
+        self.sending_clients
+            .iter()
+            .map(|sending_client| sending_client.endpoint.url.clone())
+            .collect()
+    }
+
+    fn wants_legacy(&self) -> bool {
+        self.sending_clients
+            .iter()
+            .any(|sending_client| sending_client.endpoint.tx_version != TxVersion::V0)
+    }
+
+    async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SubmissionResult> {
+        let jitter_ms = self.jitter.as_millis() as u64;
+        let mut result = SubmissionResult::default();
+        for (i, sending_client) in self.sending_clients.iter().enumerate() {
+            if i > 0 && jitter_ms > 0 {
+                let delay = rand::random::<u64>() % (jitter_ms + 1);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            debug!(
+                "Sending transaction through RPC client {} ({})",
+                i, sending_client.endpoint.url
+            );
+
+            let tx = match (sending_client.endpoint.tx_version, submission.legacy_tx) {
+                (TxVersion::Legacy, Some(legacy_tx)) => legacy_tx,
+                (TxVersion::Legacy, None) => {
+                    error!("No legacy transaction for RPC client {}, skipping", i);
+                    continue;
+                }
+                (TxVersion::Auto, Some(legacy_tx))
+                    if keeps_same_pools(legacy_tx, submission.tx) =>
+                {
+                    legacy_tx
+                }
+                _ => submission.tx,
+            };
+
+            match sending_client.send(tx, submission.context_slot) {
+                Ok(signature) => result.record(signature, &sending_client.endpoint.url),
+                // 交易已经上链或已被处理，继续发送和重试都没有意义
+                Err(e) if is_already_processed(&e) => {
+                    info!(
+                        "Transaction {} was already processed, not sending it to the remaining endpoints",
+                        tx.signatures[0]
+                    );
+                    result.record(tx.signatures[0], &sending_client.endpoint.url);
+                    result.already_processed = true;
+                    break;
+                }
+                Err(e) => error!("Failed to send transaction through RPC client {}: {}", i, e),
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// 端点是否报告交易已经处理过
+fn is_already_processed(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<ClientError>()
+        .and_then(ClientError::get_transaction_error)
+        == Some(TransactionError::AlreadyProcessed)
+}
+
+/// 一笔模拟交易的记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaperTrade {
+    pub signature: Signature,
+    /// 模拟得到的预期收益，模拟失败或读取不到余额时为 `None`
+    pub simulated_profit: Option<i64>,
+    /// 通过 Jito 发送时将要支付的小费
+    pub tip: Option<u64>,
+}
+
+/// 模拟记录器的累计统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PaperTotals {
+    pub trades: u64,
+    /// 模拟收益为正的交易数
+    pub profitable: u64,
+    /// 模拟收益之和（扣除小费）
+    pub simulated_profit: i64,
+    pub tips: u64,
+}
+
+/// dry-run 模式的提交器：只模拟交易并记录预期收益，永不广播
+///
+/// 保留最近 `PAPER_TRADE_HISTORY` 笔交易和累计统计，用于评估策略而不承担风险。
+pub struct PaperSubmitter {
+    client: Arc<RpcClient>,
+    /// 设置时按 Jito 配置计算每笔交易将要支付的小费
+    jito: Option<JitoConfig>,
+    trades: Mutex<(VecDeque<PaperTrade>, PaperTotals)>,
+}
+
+impl PaperSubmitter {
+    /// # 参数
+    /// * `client` - 模拟交易使用的 RPC 客户端
+    pub fn new(client: Arc<RpcClient>) -> Self {
+        Self {
+            client,
+            jito: None,
+            trades: Mutex::new((VecDeque::new(), PaperTotals::default())),
+        }
+    }
+
+    /// 按 Jito 配置计算并记录将要支付的小费
+    pub fn with_tip(mut self, jito: &JitoConfig) -> Self {
+        self.jito = Some(jito.clone());
+        self
+    }
+
+    /// 记录一笔模拟交易，返回更新后的累计统计
+    pub fn record(&self, trade: PaperTrade) -> PaperTotals {
+        let mut trades = self.trades.lock().unwrap();
+        let (history, totals) = &mut *trades;
+        totals.trades += 1;
+        if let Some(profit) = trade.simulated_profit {
+            let tip = trade.tip.unwrap_or(0);
+            if profit > tip as i64 {
+                totals.profitable += 1;
+            }
+            totals.simulated_profit += profit - tip as i64;
+            totals.tips += tip;
+        }
+        if history.len() == PAPER_TRADE_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(trade);
+        *totals
+    }
+
+    /// 最近的模拟交易，按时间顺序
+    pub fn trades(&self) -> Vec<PaperTrade> {
+        self.trades.lock().unwrap().0.iter().cloned().collect()
+    }
+
+    pub fn totals(&self) -> PaperTotals {
+        self.trades.lock().unwrap().1
+    }
+}
+
+#[async_trait]
+impl Submitter for PaperSubmitter {
+    fn endpoints(&self) -> Vec<String> {
+        vec!["paper".to_string()]
+    }
+
+    async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SubmissionResult> {
+        let simulated_profit = simulate_dry_run(
+            &self.client,
+            submission.tx,
+            &submission.wallet_quote_account,
+        )?;
+        // 小费以 lamports 支付，只有 SOL 报价的收益可以直接分成
+        let quote_is_sol = submission.quote_mint == *sol_mint();
+        let tip = self
+            .jito
+            .as_ref()
+            .map(|jito| tip_for_profit(jito, simulated_profit.filter(|_| quote_is_sol)));
+        let totals = self.record(PaperTrade {
+            signature: submission.tx.signatures[0],
+            simulated_profit,
+            tip,
+        });
+        info!(
+            "[dry-run] Paper trade {}: simulated profit {:?}, tip {:?}; {} trades, {} profitable, total simulated profit {}",
+            submission.tx.signatures[0],
+            simulated_profit,
+            tip,
+            totals.trades,
+            totals.profitable,
+            totals.simulated_profit
+        );
+        Ok(SubmissionResult {
+            simulated_profit,
+            tip,
+            ..Default::default()
+        })
+    }
+}
@@ -0,0 +1,59 @@
+use crate::submit::{Submission, SubmissionResult, Submitter};
+use async_trait::async_trait;
+use solana_client::rpc_client::RpcClient;
+use solana_client::tpu_client::{TpuClient, TpuClientConfig};
+use solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool};
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::Arc;
+use tracing::error;
+
+type QuicTpuClient = TpuClient<QuicPool, QuicConnectionManager, QuicConfig>;
+
+/// 通过 QUIC 直接把交易发送到当前及后续 leader 的 TPU 端口，绕过 RPC 转发
+///
+/// leader 列表通过 RPC 获取，并通过 websocket 订阅 slot 变化保持更新。
+pub struct TpuSender {
+    client: QuicTpuClient,
+}
+
+impl TpuSender {
+    /// 创建 TPU 发送器
+    ///
+    /// # 参数
+    /// * `rpc_client` - 用于查询 leader 计划和节点信息的 RPC 客户端
+    /// * `websocket_url` - 订阅 slot 更新的 websocket 地址
+    /// * `fanout_slots` - 向未来多少个 slot 的 leader 发送
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        websocket_url: &str,
+        fanout_slots: u64,
+    ) -> anyhow::Result<Self> {
+        let client = TpuClient::new(rpc_client, websocket_url, TpuClientConfig { fanout_slots })?;
+        Ok(Self { client })
+    }
+
+    /// 发送已签名的交易，所有 leader 都发送失败时返回最后一个错误
+    pub fn send(&self, tx: &VersionedTransaction) -> anyhow::Result<Signature> {
+        let wire_transaction = bincode::serialize(tx)?;
+        self.client.try_send_wire_transaction(wire_transaction)?;
+        Ok(tx.signatures[0])
+    }
+}
+
+#[async_trait]
+impl Submitter for TpuSender {
+    fn endpoints(&self) -> Vec<String> {
+        vec!["tpu".to_string()]
+    }
+
+    /// 发送失败只记录日志，交易仍然可以通过其他提交器发送
+    async fn send(&self, submission: &Submission<'_>) -> anyhow::Result<SubmissionResult> {
+        let mut result = SubmissionResult::default();
+        match TpuSender::send(self, submission.tx) {
+            Ok(signature) => result.record(signature, "tpu"),
+            Err(e) => error!("Failed to send transaction through TPU: {}", e),
+        }
+        Ok(result)
+    }
+}
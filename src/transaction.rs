@@ -1,63 +1,256 @@
-use crate::config::Config;
-use crate::dex::raydium::{raydium_authority, raydium_cp_authority};
-use crate::dex::solfi::constants::solfi_program_id;
-use crate::dex::vertigo::constants::vertigo_program_id;
+use crate::config::{Config, FlashloanProvider, SendingEndpoint};
+use crate::executor::{ExecutorProgram, Executors};
+use crate::flashloan::FlashLoan;
 use crate::pools::MintPoolData;
+use crate::submit::{Submission, Submitters};
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use solana_account_decoder::UiAccountEncoding;
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcSendTransactionConfig, RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+    RpcTransactionConfig,
+};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
 use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_sdk::account::Account;
 use solana_sdk::address_lookup_table::AddressLookupTableAccount;
-use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_sdk::compute_budget::ComputeBudgetInstruction;
 use solana_sdk::hash::Hash;
 use solana_sdk::message::v0::Message;
+use solana_sdk::message::{legacy, VersionedMessage};
+use solana_sdk::packet::PACKET_DATA_SIZE;
 use solana_sdk::signature::{Keypair, Signature};
 use solana_sdk::signer::Signer;
 use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::{debug, error, info};
 
 use crate::constants::sol_mint;
-use crate::dex::meteora::constants::{
-    damm_program_id, damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id,
-    dlmm_event_authority, dlmm_program_id, vault_program_id,
-};
-use crate::dex::pump::constants::{pump_fee_wallet, pump_program_id};
-use crate::dex::raydium::constants::{
-    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id,
-};
-use crate::dex::whirlpool::constants::whirlpool_program_id;
 use solana_program::instruction::AccountMeta;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_program;
 use spl_associated_token_account::ID as associated_token_program_id;
+use spl_token::state::Account as TokenAccount;
 use spl_token::ID as token_program_id;
 use std::str::FromStr;
 
 /// 构建并发送一笔 Solana 交易，包含 Swap 操作及相关计算预算指令。
 ///
-/// 该函数会为交易添加随机扰动以避免交易哈希冲突，并通过多个 RPC 客户端尝试发送交易，
-/// 若配置中启用了重试机制，则会在发送失败时进行重试。
+/// 该函数会为交易添加随机扰动以避免交易哈希冲突，然后交给按配置选择的提交器发送，
+/// 见 `Submitters::from_config`；有端点要求 legacy 交易时另外构建一份不使用查找表的交易。
 ///
 /// # 参数说明
 /// - `wallet_kp`: 钱包的密钥对，用于签署交易。
-/// - `config`: 机器人配置信息，包括计算单元限制、是否启用 Flashloan、Spam 配置等。
+/// - `config`: 机器人配置信息，包括计算单元限制、是否启用 Flashloan 等。
+/// - `programs`: 启动时解析的执行程序和外部闪电贷。
 /// - `mint_pool_data`: Swap 操作涉及的池子数据。
-/// - `rpc_clients`: 一组 RPC 客户端，用于发送交易。
-/// - `blockhash`: 当前最新的区块哈希，用于构建交易。
+/// - `submitters`: 发送交易的提交器；dry-run 模式下为只模拟的记录器。
+/// - `blockhash`: 当前最新的区块哈希及获取时 RPC 所在的 slot，slot 用作 `min_context_slot`。
 /// - `address_lookup_table_accounts`: 地址查找表账户，用于构建版本化交易。
 ///
 /// # 返回值
-/// 返回一个 `Result<Vec<Signature>>`，其中包含所有成功发送的交易签名。若所有 RPC 客户端均发送失败，则返回错误。
+/// 返回 `SendOutcome`，其中包含所有成功发送的交易签名；dry-run 模式下签名为空，并带有模拟得到的预期收益。
 pub async fn build_and_send_transaction(
     wallet_kp: &Keypair,
     config: &Config,
+    programs: &SwapPrograms,
+    mint_pool_data: &MintPoolData,
+    submitters: &Submitters,
+    (blockhash, context_slot): (Hash, Slot),
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<SendOutcome> {
+    let tx = build_transaction(
+        wallet_kp,
+        config,
+        programs,
+        mint_pool_data,
+        blockhash,
+        address_lookup_table_accounts,
+    )?;
+
+    let legacy_tx = legacy_transaction_for(
+        wallet_kp,
+        config,
+        programs,
+        mint_pool_data,
+        submitters,
+        blockhash,
+    );
+
+    submitters
+        .send(
+            &Submission::new(&tx, mint_pool_data, context_slot).with_legacy_tx(legacy_tx.as_ref()),
+        )
+        .await
+}
+
+/// 有端点要求 legacy 交易时另外构建一份不使用查找表的交易
+///
+/// 构建失败时记录错误并返回 `None`，这些端点会被跳过。
+pub fn legacy_transaction_for(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    mint_pool_data: &MintPoolData,
+    submitters: &Submitters,
+    blockhash: Hash,
+) -> Option<VersionedTransaction> {
+    if !submitters.wants_legacy() {
+        return None;
+    }
+    match build_legacy_transaction(wallet_kp, config, programs, mint_pool_data, blockhash) {
+        Ok(legacy_tx) => Some(legacy_tx),
+        Err(e) => {
+            error!("Failed to build legacy transaction: {}", e);
+            None
+        }
+    }
+}
+
+/// 发送交易使用的 RPC 客户端及其发送参数
+#[derive(Clone)]
+pub struct SendingClient {
+    pub client: Arc<RpcClient>,
+    pub endpoint: SendingEndpoint,
+}
+
+impl SendingClient {
+    pub fn new(client: Arc<RpcClient>, endpoint: SendingEndpoint) -> Self {
+        Self { client, endpoint }
+    }
+
+    /// 该端点的 `sendTransaction` 参数
+    pub fn send_config(&self, context_slot: Slot) -> RpcSendTransactionConfig {
+        RpcSendTransactionConfig {
+            skip_preflight: self.endpoint.skip_preflight,
+            max_retries: Some(self.endpoint.max_retries as usize),
+            preflight_commitment: Some(CommitmentLevel::Confirmed),
+            min_context_slot: self.endpoint.min_context_slot.then_some(context_slot),
+            ..Default::default()
+        }
+    }
+
+    /// 通过该端点发送交易
+    pub fn send(&self, tx: &VersionedTransaction, context_slot: Slot) -> anyhow::Result<Signature> {
+        Ok(self
+            .client
+            .send_transaction_with_config(tx, self.send_config(context_slot))?)
+    }
+}
+
+/// 一次构建并发送的结果
+#[derive(Debug, Clone, Default)]
+pub struct SendOutcome {
+    /// 成功发送的交易签名，每笔交易只出现一次（经多个端点发送也是同一个签名），dry-run 模式下为空
+    pub signatures: Vec<Signature>,
+    /// 有端点报告交易已经处理过，剩下的端点没有再发送
+    pub already_processed: bool,
+    /// dry-run 模拟得到的预期收益（报价货币的最小单位，SOL 为 lamports）
+    pub simulated_profit: Option<i64>,
+    /// 合并发送时同一笔交易中的全部代币（按指令顺序），单独发送时为空
+    pub batch_mints: Vec<Pubkey>,
+    /// 通过 Jito bundle 发送时支付的小费（lamports）
+    pub tip: Option<u64>,
+}
+
+/// 交易中调用的执行程序和外部闪电贷
+///
+/// 启动时从配置解析一次，包括地址和执行程序的版本账户 PDA，之后构建每笔交易时直接使用。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SwapPrograms {
+    pub executors: Executors,
+    /// 使用 Solend 闪电贷时的借款参数，内置闪电贷或未启用时为 `None`
+    pub flash_loan: Option<FlashLoan>,
+}
+
+impl SwapPrograms {
+    /// # 错误
+    /// `[executor]` 或 `[flashloan]` 中的地址不是合法的 pubkey、布局版本不受支持，
+    /// 或 Solend 闪电贷没有配置 `max_borrow` 时返回错误
+    pub fn from_config(config: &Config) -> anyhow::Result<Self> {
+        Ok(Self {
+            executors: Executors::from_config(config.executor.as_ref())?,
+            flash_loan: match &config.flashloan {
+                Some(flashloan) => FlashLoan::from_config(flashloan)?,
+                None => None,
+            },
+        })
+    }
+}
+
+/// 构建并签名套利交易，但不发送。
+///
+/// 交易包含计算单元限制（带随机扰动以避免交易重复）、计算单元价格和 Swap 指令，
+/// 使用 Solend 闪电贷时 Swap 前后分别是借款和还款指令，
+/// 并使用地址查找表编译为 v0 版本化交易。池子太多导致交易超出大小或账户数上限时，
+/// 按报价偏离保留最有价值的一部分池子，见 `compile_within_budget`。
+///
+/// # 参数说明
+/// - `wallet_kp`: 钱包的密钥对，用于签署交易。
+/// - `config`: 机器人配置信息。
+/// - `programs`: 启动时解析的执行程序和外部闪电贷，见 `SwapPrograms::from_config`。
+/// - `mint_pool_data`: Swap 操作涉及的池子数据。
+/// - `blockhash`: 当前最新的区块哈希。
+/// - `address_lookup_table_accounts`: 地址查找表账户。
+///
+/// # 返回值
+/// 返回已签名的版本化交易。
+pub fn build_transaction(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    mint_pool_data: &MintPoolData,
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<VersionedTransaction> {
+    let (message, _) = compile_transaction_message(
+        wallet_kp,
+        config,
+        programs,
+        mint_pool_data,
+        blockhash,
+        address_lookup_table_accounts,
+    )?;
+
+    // 构造版本化交易
+    let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[wallet_kp])?;
+
+    Ok(tx)
+}
+
+/// 编译 `build_transaction` 的交易消息，但不签名
+///
+/// 第一条指令是计算单元限制，第二条是计算单元价格，`TransactionTemplate` 依赖这个顺序。
+///
+/// # 返回值
+/// 返回交易消息，以及是否因为大小限制去掉了一部分池子
+pub fn compile_transaction_message(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
     mint_pool_data: &MintPoolData,
-    rpc_clients: &[Arc<RpcClient>],
     blockhash: Hash,
     address_lookup_table_accounts: &[AddressLookupTableAccount],
-) -> anyhow::Result<Vec<Signature>> {
-    // 读取是否启用 Flashloan 和计算单元限制配置
-    let enable_flashloan = config.flashloan.as_ref().map_or(false, |k| k.enabled);
+) -> anyhow::Result<(Message, bool)> {
+    // 读取闪电贷和计算单元限制配置；内置闪电贷由执行程序处理，外部闪电贷在 Swap 前后加入借款和还款指令
+    let enable_flashloan = config
+        .flashloan
+        .as_ref()
+        .is_some_and(|k| k.enabled && k.provider == FlashloanProvider::Kamino);
+    let flash_loan = programs.flash_loan;
+    // 链上程序要求的最低收益：闪电贷手续费加上额外要求的收益，不足时交易失败（或在 no_failure_mode 下什么也不做）
+    let minimum_profit = config.flashloan.as_ref().map_or(0, |flashloan| {
+        flash_loan.map_or(0, |loan| loan.fee()) + flashloan.min_extra_profit()
+    });
+    let executors = &programs.executors;
     let compute_unit_limit = config.bot.compute_unit_limit;
     let mut instructions = vec![];
 
@@ -68,83 +261,637 @@ pub async fn build_and_send_transaction(
     instructions.push(compute_budget_ix);
 
     // 添加计算单元价格指令
-    let compute_unit_price = config.spam.as_ref().map_or(1000, |s| s.compute_unit_price);
+    let compute_unit_price = config.compute_unit_price();
     let compute_budget_price_ix =
         ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price);
     instructions.push(compute_budget_price_ix);
 
-    // 构造 Swap 操作指令
-    let swap_ix = create_swap_instruction(
-        wallet_kp,
-        mint_pool_data,
-        compute_unit_limit as u64,
-        enable_flashloan,
-    )?;
+    // 外部闪电贷：借款指令放在 Swap 之前，还款指令需要知道借款指令的位置
+    let borrow_index = instructions.len() as u8;
+    if let Some(loan) = &flash_loan {
+        instructions.push(loan.borrow_instruction(&mint_pool_data.wallet_wsol_account));
+    }
+
+    // 构造 Swap 操作指令并编译交易消息
+    let compile = |pool_data: &MintPoolData| -> anyhow::Result<Message> {
+        // 裁剪池子后池子数可能变化，每次按实际的池子数选择执行程序
+        let executor = executors.select(&pool_data.mint, pool_data.pool_addresses().len());
+        let swap_ix = create_swap_instruction(
+            wallet_kp,
+            &executor,
+            pool_data,
+            compute_unit_limit as u64,
+            enable_flashloan,
+            config.bot.no_failure_mode.unwrap_or(false),
+            minimum_profit,
+        )?;
+
+        // 合并所有指令
+        let mut all_instructions = instructions.clone();
+        debug!("Adding swap instruction");
+        all_instructions.push(swap_ix);
+        if let Some(loan) = &flash_loan {
+            all_instructions.push(loan.repay_instruction(
+                &pool_data.wallet_wsol_account,
+                &wallet_kp.pubkey(),
+                borrow_index,
+            ));
+        }
+
+        Ok(Message::try_compile(
+            &wallet_kp.pubkey(),
+            &all_instructions,
+            &select_lookup_tables(&all_instructions, address_lookup_table_accounts),
+            blockhash,
+        )?)
+    };
+    compile_within_budget(mint_pool_data, compile)
+}
+
+/// 构建并签名不使用地址查找表的 legacy 交易
+///
+/// 指令与 `build_transaction` 相同；所有账户都放在静态账户列表中，池子多时按
+/// `compile_within_budget` 的规则只保留一部分池子。
+pub fn build_legacy_transaction(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    mint_pool_data: &MintPoolData,
+    blockhash: Hash,
+) -> anyhow::Result<VersionedTransaction> {
+    // 不使用查找表编译的 v0 消息和 legacy 消息的账户列表、指令完全相同
+    let tx = build_transaction(wallet_kp, config, programs, mint_pool_data, blockhash, &[])?;
+    let VersionedMessage::V0(message) = tx.message else {
+        anyhow::bail!("build_transaction returned a non-v0 message");
+    };
+    let message = legacy::Message {
+        header: message.header,
+        account_keys: message.account_keys,
+        recent_blockhash: message.recent_blockhash,
+        instructions: message.instructions,
+    };
+    Ok(VersionedTransaction::try_new(
+        VersionedMessage::Legacy(message),
+        &[wallet_kp],
+    )?)
+}
 
-    // 合并所有指令
-    let mut all_instructions = instructions.clone();
-    debug!("Adding swap instruction");
-    all_instructions.push(swap_ix);
+/// 单笔交易允许请求的计算单元上限
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+/// 构建并签名包含多个代币的套利交易
+///
+/// 每个代币一条 Swap 指令，执行程序分别在各自的池子间寻找套利，任何一条失败都会让整笔交易失败
+/// （`no_failure_mode` 下没有机会的代币什么也不做）。计算单元限制为各代币之和，不超过
+/// `MAX_COMPUTE_UNIT_LIMIT`；计算单元价格和 blockhash 只需要一份。
+/// 不裁剪池子，交易超出大小或账户数上限时返回错误，由调用方减少代币数。
+/// Solend 闪电贷的借款和还款指令只能包住一条 Swap 指令，不支持合并。
+/// 同一个池子出现在两个代币中时返回错误，避免一笔交易里的路线重复使用同一个池子。
+///
+/// # 参数说明
+/// - `pool_datas`: 每个代币的池子数据，按指令顺序排列。
+/// - 其余参数同 `build_transaction`。
+///
+/// # 返回值
+/// 返回已签名的版本化交易。
+pub fn build_batch_transaction(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    pool_datas: &[&MintPoolData],
+    blockhash: Hash,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<VersionedTransaction> {
+    if config
+        .flashloan
+        .as_ref()
+        .is_some_and(|f| f.enabled && f.provider == FlashloanProvider::Solend)
+    {
+        anyhow::bail!("Batched transactions do not support the Solend flashloan");
+    }
+    // 运行时加入的池子可能同时属于两个代币，前一条 Swap 指令改变的储备会让后一条按过期的价格执行
+    let mut pool_mints: HashMap<Pubkey, Pubkey> = HashMap::new();
+    for pool_data in pool_datas {
+        for (_, pool) in pool_data.pool_addresses() {
+            if let Some(mint) = pool_mints.insert(pool, pool_data.mint) {
+                if mint != pool_data.mint {
+                    anyhow::bail!(
+                        "Pool {} is used by both {} and {}, which can't share a transaction",
+                        pool,
+                        mint,
+                        pool_data.mint
+                    );
+                }
+            }
+        }
+    }
+    let enable_flashloan = config
+        .flashloan
+        .as_ref()
+        .is_some_and(|k| k.enabled && k.provider == FlashloanProvider::Kamino);
+    let minimum_profit = config
+        .flashloan
+        .as_ref()
+        .map_or(0, |flashloan| flashloan.min_extra_profit());
+    let executors = &programs.executors;
+    let compute_unit_limit = config.bot.compute_unit_limit;
+    let total_compute_unit_limit = (compute_unit_limit as u64 * pool_datas.len() as u64)
+        .min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32;
+
+    // 计算单元限制同样加入随机扰动以避免交易重复
+    let mut instructions = vec![
+        ComputeBudgetInstruction::set_compute_unit_limit(
+            total_compute_unit_limit.saturating_sub(rand::random::<u32>() % 1000),
+        ),
+        ComputeBudgetInstruction::set_compute_unit_price(config.compute_unit_price()),
+    ];
+    for pool_data in pool_datas {
+        instructions.push(create_swap_instruction(
+            wallet_kp,
+            &executors.select(&pool_data.mint, pool_data.pool_addresses().len()),
+            pool_data,
+            compute_unit_limit as u64,
+            enable_flashloan,
+            config.bot.no_failure_mode.unwrap_or(false),
+            minimum_profit,
+        )?);
+    }
 
-    // 编译交易消息
     let message = Message::try_compile(
         &wallet_kp.pubkey(),
-        &all_instructions,
-        address_lookup_table_accounts,
+        &instructions,
+        &select_lookup_tables(&instructions, address_lookup_table_accounts),
         blockhash,
     )?;
+    let size = TransactionSize::of(&message)?;
+    if !size.fits() {
+        anyhow::bail!(
+            "Transaction for {} mints does not fit in {} bytes / {} accounts ({} bytes, {} accounts)",
+            pool_datas.len(),
+            PACKET_DATA_SIZE,
+            MAX_TX_ACCOUNTS,
+            size.bytes,
+            size.accounts
+        );
+    }
 
-    // 构造版本化交易
-    let tx = VersionedTransaction::try_new(
-        solana_sdk::message::VersionedMessage::V0(message),
+    Ok(VersionedTransaction::try_new(
+        VersionedMessage::V0(message),
         &[wallet_kp],
-    )?;
+    )?)
+}
 
-    // 获取最大重试次数配置，默认为 3 次
-    let max_retries = config
-        .spam
+/// 两笔交易的指令是否引用了同样多的账户，即 legacy 交易没有因为大小限制去掉池子
+pub fn keeps_same_pools(legacy_tx: &VersionedTransaction, tx: &VersionedTransaction) -> bool {
+    let instruction_accounts = |tx: &VersionedTransaction| -> usize {
+        tx.message
+            .instructions()
+            .iter()
+            .map(|ix| ix.accounts.len())
+            .sum()
+    };
+    instruction_accounts(legacy_tx) == instruction_accounts(tx)
+}
+
+/// 套利交易引用、但不在给定查找表中的账户
+///
+/// 按全部池子计算，不受 `compile_within_budget` 裁剪的影响；签名者和直接调用的程序
+/// 只能放在交易的静态账户列表中，不计入。没有被覆盖的账户每个占用 32 字节，
+/// 池子多时会让交易超出大小上限。
+pub fn uncovered_accounts(
+    wallet_kp: &Keypair,
+    config: &Config,
+    programs: &SwapPrograms,
+    mint_pool_data: &MintPoolData,
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> anyhow::Result<Vec<Pubkey>> {
+    let enable_flashloan = config
+        .flashloan
         .as_ref()
-        .and_then(|s| s.max_retries)
-        .unwrap_or(3);
+        .is_some_and(|k| k.enabled && k.provider == FlashloanProvider::Kamino);
+    let mut instructions = vec![create_swap_instruction(
+        wallet_kp,
+        &programs
+            .executors
+            .select(&mint_pool_data.mint, mint_pool_data.pool_addresses().len()),
+        mint_pool_data,
+        config.bot.compute_unit_limit as u64,
+        enable_flashloan,
+        config.bot.no_failure_mode.unwrap_or(false),
+        0,
+    )?];
+    if let Some(loan) = &programs.flash_loan {
+        instructions.push(loan.borrow_instruction(&mint_pool_data.wallet_wsol_account));
+        instructions.push(loan.repay_instruction(
+            &mint_pool_data.wallet_wsol_account,
+            &wallet_kp.pubkey(),
+            0,
+        ));
+    }
 
-    let mut signatures = Vec::new();
+    let covered: HashSet<Pubkey> = address_lookup_table_accounts
+        .iter()
+        .flat_map(|table| table.addresses.iter().copied())
+        .collect();
+    Ok(lookup_candidates(&instructions)
+        .into_iter()
+        .filter(|pubkey| !covered.contains(pubkey))
+        .collect())
+}
 
-    // 遍历所有 RPC 客户端，尝试发送交易
-    for (i, client) in rpc_clients.iter().enumerate() {
-        debug!("Sending transaction through RPC client {}", i);
+/// 指令引用的、可以通过查找表加载的账户，去重后按首次出现的顺序排列
+///
+/// 签名者和直接调用的程序只能放在交易的静态账户列表中，不包含在内。
+fn lookup_candidates(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let programs: HashSet<Pubkey> = instructions.iter().map(|ix| ix.program_id).collect();
+    let mut seen = HashSet::new();
+    instructions
+        .iter()
+        .flat_map(|ix| ix.accounts.iter())
+        .filter(|meta| !meta.is_signer && !programs.contains(&meta.pubkey))
+        .map(|meta| meta.pubkey)
+        .filter(|pubkey| seen.insert(*pubkey))
+        .collect()
+}
 
-        let signature = match send_transaction_with_retries(client, &tx, max_retries).await {
-            Ok(sig) => sig,
-            Err(e) => {
-                error!("Failed to send transaction through RPC client {}: {}", i, e);
-                continue;
-            }
+/// 为指令挑选查找表：每次选覆盖剩余账户最多的查找表，直到没有查找表能再覆盖新的账户
+///
+/// 编译消息时按传入的顺序从查找表中取账户，同一个账户出现在多个查找表中时只从第一个表加载。
+/// 每用一个查找表交易多 34 字节（表地址和两个索引列表的长度），按覆盖数排序后用尽量少的表
+/// 覆盖同样多的账户，池子多时能多放几个池子。覆盖数相同时保持传入的顺序。
+pub fn select_lookup_tables(
+    instructions: &[Instruction],
+    address_lookup_table_accounts: &[AddressLookupTableAccount],
+) -> Vec<AddressLookupTableAccount> {
+    let mut remaining: HashSet<Pubkey> = lookup_candidates(instructions).into_iter().collect();
+    let mut candidates: Vec<&AddressLookupTableAccount> =
+        address_lookup_table_accounts.iter().collect();
+    let mut selected = Vec::new();
+    while !remaining.is_empty() {
+        let coverage = |table: &AddressLookupTableAccount| {
+            table
+                .addresses
+                .iter()
+                .collect::<HashSet<_>>()
+                .into_iter()
+                .filter(|address| remaining.contains(address))
+                .count()
+        };
+        let best = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, table)| (i, coverage(table)))
+            .filter(|(_, covered)| *covered > 0)
+            // max_by_key 在相等时取最后一个，反转下标使覆盖数相同时取靠前的表
+            .max_by_key(|(i, covered)| (*covered, std::cmp::Reverse(*i)));
+        let Some((i, _)) = best else {
+            break;
         };
+        let table = candidates.remove(i);
+        for address in &table.addresses {
+            remaining.remove(address);
+        }
+        selected.push(table.clone());
+    }
+    selected
+}
 
-        info!(
-            "Transaction sent successfully through RPC client {}: {}",
-            i, signature
-        );
-        signatures.push(signature);
+/// 运行时当前允许单笔交易锁定的账户数上限（包括通过查找表加载的账户）
+pub const MAX_TX_ACCOUNTS: usize = 64;
+
+/// 签名后的交易大小和引用的账户数
+#[derive(Debug, Clone, Copy)]
+pub struct TransactionSize {
+    pub bytes: usize,
+    pub accounts: usize,
+}
+
+impl TransactionSize {
+    /// 估算消息签名后的交易大小
+    pub fn of(message: &Message) -> anyhow::Result<Self> {
+        let num_signatures = message.header.num_required_signatures as usize;
+        let message_bytes =
+            bincode::serialized_size(&VersionedMessage::V0(message.clone()))? as usize;
+        let loaded_accounts: usize = message
+            .address_table_lookups
+            .iter()
+            .map(|lookup| lookup.writable_indexes.len() + lookup.readonly_indexes.len())
+            .sum();
+        Ok(Self {
+            // 签名数量使用 short_vec 编码，签名数少于 128 时占 1 字节
+            bytes: 1 + num_signatures * 64 + message_bytes,
+            accounts: message.account_keys.len() + loaded_accounts,
+        })
+    }
+
+    pub fn fits(&self) -> bool {
+        self.bytes <= PACKET_DATA_SIZE && self.accounts <= MAX_TX_ACCOUNTS
+    }
+}
+
+/// 编译交易消息，超出大小或账户数上限时逐个去掉池子直到放得下
+///
+/// 池子按报价偏离（`MintPoolData::pool_edges`）从小到大依次去掉，未报价的池子最先去掉；
+/// 去掉后不能再组成买入/卖出池子对的池子会被跳过。
+///
+/// # 参数
+/// - `mint_pool_data`: 全部池子
+/// - `compile`: 用给定的池子编译交易消息
+///
+/// # 返回值
+/// 返回交易消息，以及是否去掉了池子
+fn compile_within_budget(
+    mint_pool_data: &MintPoolData,
+    compile: impl Fn(&MintPoolData) -> anyhow::Result<Message>,
+) -> anyhow::Result<(Message, bool)> {
+    let message = compile(mint_pool_data)?;
+    let size = TransactionSize::of(&message)?;
+    if size.fits() {
+        return Ok((message, false));
     }
 
-    Ok(signatures)
+    // 按偏离从大到小排列，偏离相同时保持配置顺序
+    let mut kept: Vec<Pubkey> = mint_pool_data
+        .pool_addresses()
+        .into_iter()
+        .map(|(_, pool)| pool)
+        .collect();
+    kept.sort_by(|a, b| mint_pool_data.edge(b).total_cmp(&mint_pool_data.edge(a)));
+    let mut selected = mint_pool_data.clone();
+    let mut size = size;
+
+    loop {
+        let without = |pool: &Pubkey| {
+            let mut trial = selected.clone();
+            trial.retain_pools(|p| p != pool);
+            trial
+        };
+        let removable = (0..kept.len()).rev().find(|&i| {
+            let trial = without(&kept[i]);
+            trial.pool_addresses().len() >= 2 && trial.has_valid_route()
+        });
+        let Some(i) = removable else {
+            anyhow::bail!(
+                "Transaction for mint {} does not fit in {} bytes / {} accounts ({} bytes, {} accounts with {} pools)",
+                mint_pool_data.mint,
+                PACKET_DATA_SIZE,
+                MAX_TX_ACCOUNTS,
+                size.bytes,
+                size.accounts,
+                kept.len()
+            );
+        };
+        let dropped = kept.remove(i);
+        selected = without(&dropped);
+
+        let message = compile(&selected)?;
+        size = TransactionSize::of(&message)?;
+        if size.fits() {
+            debug!(
+                "Kept {} of {} pools for mint {} to fit the transaction ({} bytes, {} accounts)",
+                kept.len(),
+                mint_pool_data.pool_addresses().len(),
+                mint_pool_data.mint,
+                size.bytes,
+                size.accounts
+            );
+            return Ok((message, true));
+        }
+    }
 }
 
-async fn send_transaction_with_retries(
+/// 在 dry-run 模式下模拟交易，并记录交易的 base64 编码及预期收益。
+///
+/// # 参数
+/// - `client`: 用于模拟交易的 RPC 客户端
+/// - `tx`: 已签名的版本化交易
+/// - `wallet_wsol_account`: 钱包持有报价货币的关联代币账户，默认为 WSOL 账户
+///
+/// # 返回值
+/// 模拟成功且能读取余额时返回预期收益（报价货币的最小单位，SOL 为 lamports）
+pub fn simulate_dry_run(
     client: &RpcClient,
     tx: &VersionedTransaction,
-    max_retries: u64,
-) -> anyhow::Result<Signature> {
-    Ok(client.send_transaction_with_config(
-        tx,
-        solana_client::rpc_config::RpcSendTransactionConfig {
-            skip_preflight: true,
-            max_retries: Some(max_retries as usize),
-            preflight_commitment: Some(CommitmentLevel::Confirmed),
-            ..Default::default()
+    wallet_wsol_account: &Pubkey,
+) -> anyhow::Result<Option<i64>> {
+    let encoded_tx = BASE64_STANDARD.encode(bincode::serialize(tx)?);
+    info!("[dry-run] Transaction (base64): {}", encoded_tx);
+
+    let (result, expected_profit) = simulate_balance_change(client, tx, wallet_wsol_account)?;
+
+    match &result.err {
+        Some(err) => info!("[dry-run] Simulation failed: {:?}", err),
+        None => info!("[dry-run] Simulation succeeded"),
+    }
+    info!(
+        "[dry-run] Units consumed: {}",
+        result.units_consumed.unwrap_or_default()
+    );
+    match expected_profit {
+        Some(profit) => info!("[dry-run] Expected profit: {} lamports", profit),
+        None => info!("[dry-run] Expected profit: unknown (WSOL balance unavailable)"),
+    }
+    for log in result.logs.unwrap_or_default() {
+        debug!("[dry-run]   {}", log);
+    }
+
+    Ok(expected_profit.filter(|_| result.err.is_none()))
+}
+
+/// 模拟交易并返回预期收益，用于发送前确认收益（例如按收益计算 Jito 小费）
+///
+/// # 返回值
+/// 模拟成功且能读取余额时返回预期收益（报价货币的最小单位，SOL 为 lamports），模拟失败时为 `None`
+pub fn simulate_profit(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+    wallet_quote_account: &Pubkey,
+) -> anyhow::Result<Option<i64>> {
+    let (result, profit) = simulate_balance_change(client, tx, wallet_quote_account)?;
+    if let Some(err) = &result.err {
+        debug!("Simulation failed: {:?}", err);
+    }
+    Ok(profit.filter(|_| result.err.is_none()))
+}
+
+/// 模拟交易，并比较模拟前后钱包报价货币账户的余额
+///
+/// 模拟时要求 RPC 返回该账户的最新状态。
+///
+/// # 返回值
+/// 模拟结果和余额变化，读取不到任一余额时余额变化为 `None`
+fn simulate_balance_change(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+    wallet_quote_account: &Pubkey,
+) -> anyhow::Result<(RpcSimulateTransactionResult, Option<i64>)> {
+    // 模拟前的余额
+    let pre_balance = client
+        .get_account(wallet_quote_account)
+        .ok()
+        .and_then(|account| TokenAccount::unpack(&account.data).ok())
+        .map(|token_account| token_account.amount);
+
+    let result = client
+        .simulate_transaction_with_config(
+            tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::processed()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: vec![wallet_quote_account.to_string()],
+                }),
+                ..Default::default()
+            },
+        )?
+        .value;
+
+    // 模拟后的余额
+    let post_balance = result
+        .accounts
+        .as_ref()
+        .and_then(|accounts| accounts.first().cloned().flatten())
+        .and_then(|ui_account| ui_account.decode::<Account>())
+        .and_then(|account| TokenAccount::unpack(&account.data).ok())
+        .map(|token_account| token_account.amount);
+
+    let profit = match (pre_balance, post_balance) {
+        (Some(pre), Some(post)) => Some(post as i64 - pre as i64),
+        _ => None,
+    };
+    Ok((result, profit))
+}
+
+/// 已上链交易的结果
+#[derive(Debug, Clone, Copy)]
+pub struct LandedTransaction {
+    pub slot: u64,
+    /// 扣除手续费后的净收益
+    pub profit_lamports: i64,
+    pub fee_lamports: u64,
+    /// 报价货币不是 SOL 时钱包该货币的余额变化（最小单位），不计入 `profit_lamports`
+    pub quote_profit: Option<i64>,
+}
+
+impl LandedTransaction {
+    /// 钱包报价货币账户的余额变化（最小单位），不扣除手续费，和报价收益直接比较
+    pub fn quote_delta(&self) -> i64 {
+        self.quote_profit
+            .unwrap_or(self.profit_lamports + self.fee_lamports as i64)
+    }
+}
+
+/// 上链交易的余额变化和报价收益的对比结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfitAudit {
+    /// 余额变化不低于报价收益减去容差，或没有报价收益且余额没有减少
+    Ok,
+    /// 余额没有减少，但低于报价收益减去容差
+    Shortfall { expected: i64, actual: i64 },
+    /// 余额减少：报价认为有收益的路线实际亏损，通常说明报价有误
+    Loss { expected: Option<i64>, actual: i64 },
+}
+
+/// 核对上链交易的余额变化
+///
+/// # 参数
+/// * `expected` - 发送时报价的收益（报价货币最小单位），没有报价时为 `None`，只检查是否亏损
+/// * `actual` - 钱包报价货币账户的余额变化，见 `LandedTransaction::quote_delta`
+/// * `tolerance_percent` - 余额变化允许低于报价收益的百分比
+pub fn audit_profit(expected: Option<i64>, actual: i64, tolerance_percent: f64) -> ProfitAudit {
+    if actual < 0 {
+        return ProfitAudit::Loss { expected, actual };
+    }
+    match expected {
+        Some(expected) if (actual as f64) < expected as f64 * (1.0 - tolerance_percent / 100.0) => {
+            ProfitAudit::Shortfall { expected, actual }
+        }
+        _ => ProfitAudit::Ok,
+    }
+}
+
+/// 等待已发送的交易上链，并计算该笔交易的实际收益。
+///
+/// 收益 = 钱包 WSOL 余额变化 - 交易手续费，通过交易元数据中的 pre/post token balances 计算。
+/// 报价货币不是 SOL 时另外返回该货币的余额变化。
+///
+/// # 参数
+/// - `client`: 用于查询交易状态的 RPC 客户端
+/// - `signature`: 交易签名
+/// - `wallet`: 钱包地址
+/// - `quote_mint`: 代币的报价货币
+/// - `timeout`: 最长等待时间
+///
+/// # 返回值
+/// 交易成功上链时返回 `Some(LandedTransaction)`；交易失败或超时未上链返回 `None`。
+pub async fn fetch_landed_profit(
+    client: &RpcClient,
+    signature: &Signature,
+    wallet: &Pubkey,
+    quote_mint: &Pubkey,
+    timeout: Duration,
+) -> anyhow::Result<Option<LandedTransaction>> {
+    let deadline = Instant::now() + timeout;
+
+    // 轮询交易状态，直到确认或超时
+    loop {
+        let status = client
+            .get_signature_statuses(&[*signature])?
+            .value
+            .into_iter()
+            .next()
+            .flatten();
+        match status {
+            Some(status) if status.err.is_some() => return Ok(None),
+            Some(status) if status.satisfies_commitment(CommitmentConfig::confirmed()) => break,
+            _ if Instant::now() >= deadline => return Ok(None),
+            _ => tokio::time::sleep(Duration::from_millis(500)).await,
+        }
+    }
+
+    let tx = client.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(CommitmentConfig::confirmed()),
+            max_supported_transaction_version: Some(0),
         },
-    )?)
+    )?;
+    let meta = tx
+        .transaction
+        .meta
+        .ok_or_else(|| anyhow::anyhow!("Transaction {} has no status meta", signature))?;
+
+    let wallet = wallet.to_string();
+    let token_amount =
+        |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>, mint: &Pubkey| -> i64 {
+            let mint = mint.to_string();
+            let balances = match balances {
+                OptionSerializer::Some(balances) => balances.as_slice(),
+                _ => &[],
+            };
+            balances
+                .iter()
+                .filter(|balance| {
+                    balance.mint == mint
+                        && Option::<String>::from(balance.owner.clone()).as_deref() == Some(&wallet)
+                })
+                .filter_map(|balance| balance.ui_token_amount.amount.parse::<i64>().ok())
+                .sum()
+        };
+    let delta = |mint: &Pubkey| {
+        token_amount(&meta.post_token_balances, mint) - token_amount(&meta.pre_token_balances, mint)
+    };
+
+    let sol_mint = *sol_mint();
+    Ok(Some(LandedTransaction {
+        slot: tx.slot,
+        profit_lamports: delta(&sol_mint) - meta.fee as i64,
+        fee_lamports: meta.fee,
+        quote_profit: (*quote_mint != sol_mint).then(|| delta(quote_mint)),
+    }))
 }
 
 /// Helper function to derive the vault token account PDA address for a given mint
@@ -155,31 +902,34 @@ pub fn derive_vault_token_account(program_id: &Pubkey, mint: &Pubkey) -> (Pubkey
 // See https://docs.solanamevbot.com/home/onchain-bot/onchain-program for more information
 fn create_swap_instruction(
     wallet_kp: &Keypair,
+    executor: &ExecutorProgram,
     mint_pool_data: &MintPoolData,
     compute_unit_limit: u64,
     use_flashloan: bool,
+    no_failure_mode: bool,
+    minimum_profit: u64,
 ) -> anyhow::Result<Instruction> {
     debug!("Creating swap instruction for all DEX types");
 
-    let executor_program_id =
-        Pubkey::from_str("MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz").unwrap();
-    let fee_collector = Pubkey::from_str("6AGB9kqgSp2mQXwYpdrV4QVV8urvCaDS35U1wsLssy6H").unwrap();
-
-    let pump_global_config =
-        Pubkey::from_str("ADyA8hdefvWN2dbGGWFotbzWxrAvLW83WG6QCVXvJKqw").unwrap();
-    let pump_authority = Pubkey::from_str("GS4CU59F31iL7aR2Q8zVS8DRrcRnXX1yjQ66TqNVQnaR").unwrap();
-    let sysvar_instructions =
-        Pubkey::from_str("Sysvar1nstructions1111111111111111111111111").unwrap();
+    // 执行程序自行在传入的池子间选择方向，没有逐池方向参数；
+    // 方向限制只能在链下保证至少存在一组可用的买入/卖出池子
+    if !mint_pool_data.has_valid_route() {
+        anyhow::bail!(
+            "No buy/sell pool pair for mint {} is allowed by the configured pool directions",
+            mint_pool_data.mint
+        );
+    }
 
     let wallet = wallet_kp.pubkey();
-    let sol_mint_pubkey = sol_mint();
-    let wallet_sol_account = mint_pool_data.wallet_wsol_account;
+    // 链上程序的输入输出货币，默认 SOL
+    let sol_mint_pubkey = mint_pool_data.quote_mint;
+    let wallet_sol_account = mint_pool_data.wallet_quote_account();
 
     let mut accounts = vec![
         AccountMeta::new_readonly(wallet, true), // 0. Wallet (signer)
-        AccountMeta::new_readonly(sol_mint_pubkey, false), // 1. SOL mint
-        AccountMeta::new(fee_collector, false),  // 2. Fee collector
-        AccountMeta::new(wallet_sol_account, false), // 3. Wallet SOL account
+        AccountMeta::new_readonly(sol_mint_pubkey, false), // 1. SOL (quote) mint
+        AccountMeta::new(executor.fee_collector, false), // 2. Fee collector
+        AccountMeta::new(wallet_sol_account, false), // 3. Wallet SOL (quote) account
         AccountMeta::new_readonly(token_program_id, false), // 4. Token program
         AccountMeta::new_readonly(system_program::ID, false), // 5. System program
         AccountMeta::new_readonly(associated_token_program_id, false), // 6. Associated Token program
@@ -192,10 +942,7 @@ fn create_swap_instruction(
             Pubkey::from_str("5LFpzqgsxrSfhKwbaFiAEJ2kbc9QyimjKueswsyU4T3o").unwrap(),
             false,
         ));
-        let token_pda = derive_vault_token_account(
-            &Pubkey::from_str("MEViEnscUm6tsQRoGd9h6nLQaQspKj7DB2M5FwM3Xvz").unwrap(),
-            &base_mint,
-        );
+        let token_pda = derive_vault_token_account(&executor.program_id, &base_mint);
         accounts.push(AccountMeta::new(token_pda.0, false));
     }
 
@@ -212,140 +959,23 @@ fn create_swap_instruction(
         );
     accounts.push(AccountMeta::new(wallet_x_account, false));
 
-    for pool in &mint_pool_data.raydium_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(raydium_authority(), false)); // Raydium authority
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-    }
-
-    for pool in &mint_pool_data.raydium_cp_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_cp_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(raydium_cp_authority(), false)); // Raydium CP authority
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-        accounts.push(AccountMeta::new(pool.observation, false));
-    }
-
-    for pool in &mint_pool_data.pump_pools {
-        accounts.push(AccountMeta::new_readonly(pump_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(pump_global_config, false));
-        accounts.push(AccountMeta::new_readonly(pump_authority, false));
-        accounts.push(AccountMeta::new_readonly(pump_fee_wallet(), false));
-        accounts.push(AccountMeta::new_readonly(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_vault, false));
-        accounts.push(AccountMeta::new(pool.sol_vault, false));
-        accounts.push(AccountMeta::new(pool.fee_token_wallet, false));
-        accounts.push(AccountMeta::new(pool.coin_creator_vault_ata, false));
-        accounts.push(AccountMeta::new_readonly(
-            pool.coin_creator_vault_authority,
-            false,
-        ));
-    }
-
-    for pair in &mint_pool_data.dlmm_pairs {
-        accounts.push(AccountMeta::new_readonly(dlmm_program_id(), false));
-        accounts.push(AccountMeta::new(dlmm_event_authority(), false)); // DLMM event authority
-        if let Some(memo_program) = pair.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
-        }
-        accounts.push(AccountMeta::new(pair.pair, false));
-        accounts.push(AccountMeta::new(pair.token_vault, false));
-        accounts.push(AccountMeta::new(pair.sol_vault, false));
-        accounts.push(AccountMeta::new(pair.oracle, false));
-        for bin_array in &pair.bin_arrays {
-            accounts.push(AccountMeta::new(*bin_array, false));
-        }
-    }
-
-    for pool in &mint_pool_data.whirlpool_pools {
-        accounts.push(AccountMeta::new_readonly(whirlpool_program_id(), false));
-        if let Some(memo_program) = pool.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
-        }
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.oracle, false));
-        accounts.push(AccountMeta::new(pool.x_vault, false));
-        accounts.push(AccountMeta::new(pool.y_vault, false));
-        for tick_array in &pool.tick_arrays {
-            accounts.push(AccountMeta::new(*tick_array, false));
-        }
+    // 每个池子的账户按 DEX 的固定顺序排列，见 `MintPoolData::pool_accounts`。
+    // 执行程序按位置解析账户，多个池子共用的账户（代币程序、amm_config 等）在指令中不能去掉；
+    // 编译消息时相同的账户只占一个静态账户或查找表索引，重复出现只多占 1 字节的索引
+    for pool in mint_pool_data.pool_accounts() {
+        accounts.extend(pool.accounts);
     }
 
-    for pool in &mint_pool_data.raydium_clmm_pools {
-        accounts.push(AccountMeta::new_readonly(raydium_clmm_program_id(), false));
-        if let Some(memo_program) = pool.memo_program {
-            accounts.push(AccountMeta::new_readonly(memo_program, false)); // Token 2022 memo program
-        }
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.amm_config, false));
-        accounts.push(AccountMeta::new(pool.observation_state, false));
-        accounts.push(AccountMeta::new(pool.bitmap_extension, false));
-        accounts.push(AccountMeta::new(pool.x_vault, false));
-        accounts.push(AccountMeta::new(pool.y_vault, false));
-        for tick_array in &pool.tick_arrays {
-            accounts.push(AccountMeta::new(*tick_array, false));
-        }
-    }
-
-    for pool in &mint_pool_data.meteora_damm_pools {
-        accounts.push(AccountMeta::new_readonly(damm_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(vault_program_id(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
-        accounts.push(AccountMeta::new(pool.token_x_token_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_token_vault, false));
-        accounts.push(AccountMeta::new(pool.token_x_lp_mint, false));
-        accounts.push(AccountMeta::new(pool.token_sol_lp_mint, false));
-        accounts.push(AccountMeta::new(pool.token_x_pool_lp, false));
-        accounts.push(AccountMeta::new(pool.token_sol_pool_lp, false));
-        accounts.push(AccountMeta::new(pool.admin_token_fee_x, false));
-        accounts.push(AccountMeta::new(pool.admin_token_fee_sol, false));
-    }
-
-    for pool in &mint_pool_data.meteora_damm_v2_pools {
-        accounts.push(AccountMeta::new_readonly(damm_v2_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(damm_v2_event_authority(), false));
-        accounts.push(AccountMeta::new_readonly(damm_v2_pool_authority(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
-    }
-
-    for pool in &mint_pool_data.solfi_pools {
-        accounts.push(AccountMeta::new_readonly(solfi_program_id(), false));
-        accounts.push(AccountMeta::new_readonly(sysvar_instructions, false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
-    }
-
-    for pool in &mint_pool_data.vertigo_pools {
-        accounts.push(AccountMeta::new_readonly(vertigo_program_id(), false));
-        accounts.push(AccountMeta::new(pool.pool, false));
-        accounts.push(AccountMeta::new_readonly(pool.pool_owner, false));
-        accounts.push(AccountMeta::new(pool.token_x_vault, false));
-        accounts.push(AccountMeta::new(pool.token_sol_vault, false));
-    }
-
-    let mut data = vec![26u8];
-
-    let minimum_profit: u64 = 0;
-    // When true, the bot will not fail the transaction even when it can't find a profitable arbitrage. It will just do nothing and succeed.
-    let no_failure_mode = false;
-
-    data.extend_from_slice(&minimum_profit.to_le_bytes());
-    data.extend_from_slice(&compute_unit_limit.to_le_bytes());
-    data.extend_from_slice(if no_failure_mode { &[1] } else { &[0] });
-    data.extend_from_slice(&0u16.to_le_bytes()); // Keep this 0.
-    data.extend_from_slice(if use_flashloan { &[1] } else { &[0] });
+    // When no_failure_mode is true, the bot will not fail the transaction even when it can't find a profitable arbitrage. It will just do nothing and succeed.
+    let data = executor.swap_data(
+        minimum_profit,
+        compute_unit_limit,
+        no_failure_mode,
+        use_flashloan,
+    )?;
 
     Ok(Instruction {
-        program_id: executor_program_id,
+        program_id: executor.program_id,
         accounts,
         data,
     })
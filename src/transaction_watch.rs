@@ -0,0 +1,124 @@
+use crate::constants::sol_mint;
+use crate::transaction::LandedTransaction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// 保留多少条没有人等待的推送，交易可能在发送方开始等待之前就被推送过来
+const RECENT_UPDATES: usize = 1024;
+
+/// 订阅推送的一笔钱包交易
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionUpdate {
+    pub signature: Signature,
+    pub slot: u64,
+    /// 交易执行失败
+    pub failed: bool,
+    pub fee: u64,
+    /// 钱包代币账户的余额变化（最小单位），按 mint 汇总
+    pub wallet_deltas: HashMap<Pubkey, i64>,
+}
+
+impl TransactionUpdate {
+    /// 按 `fetch_landed_profit` 的方式计算收益，执行失败的交易返回 `None`
+    ///
+    /// # 参数
+    /// * `quote_mint` - 代币的报价货币
+    pub fn landed(&self, quote_mint: &Pubkey) -> Option<LandedTransaction> {
+        if self.failed {
+            return None;
+        }
+        let delta = |mint: &Pubkey| self.wallet_deltas.get(mint).copied().unwrap_or_default();
+        let sol_mint = *sol_mint();
+        Some(LandedTransaction {
+            slot: self.slot,
+            profit_lamports: delta(&sol_mint) - self.fee as i64,
+            fee_lamports: self.fee,
+            quote_profit: (*quote_mint != sol_mint).then(|| delta(quote_mint)),
+        })
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    waiting: HashMap<Signature, oneshot::Sender<TransactionUpdate>>,
+    recent: HashMap<Signature, TransactionUpdate>,
+    recent_order: VecDeque<Signature>,
+}
+
+/// 把交易订阅推送的结果交给等待该签名的任务
+///
+/// 订阅断开时 `is_connected` 返回 `false`，调用方应改为轮询交易状态。
+#[derive(Default)]
+pub struct TransactionWatch {
+    state: Mutex<WatchState>,
+    connected: AtomicBool,
+}
+
+impl TransactionWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    pub fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// 交给等待该签名的任务，没有人等待时暂存起来
+    pub fn publish(&self, update: TransactionUpdate) {
+        let mut state = self.state.lock().unwrap();
+        let update = match state.waiting.remove(&update.signature) {
+            Some(sender) => match sender.send(update) {
+                Ok(()) => return,
+                // 等待已经超时
+                Err(update) => update,
+            },
+            None => update,
+        };
+        let signature = update.signature;
+        if state.recent.insert(signature, update).is_none() {
+            state.recent_order.push_back(signature);
+        }
+        while state.recent_order.len() > RECENT_UPDATES {
+            if let Some(oldest) = state.recent_order.pop_front() {
+                state.recent.remove(&oldest);
+            }
+        }
+    }
+
+    /// 等待签名的推送
+    ///
+    /// # 返回值
+    /// 超时前没有收到推送时返回 `None`
+    pub async fn wait(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Option<TransactionUpdate> {
+        let receiver = {
+            let mut state = self.state.lock().unwrap();
+            if let Some(update) = state.recent.remove(signature) {
+                state.recent_order.retain(|recent| recent != signature);
+                return Some(update);
+            }
+            let (sender, receiver) = oneshot::channel();
+            state.waiting.insert(*signature, sender);
+            receiver
+        };
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(update)) => Some(update),
+            _ => {
+                self.state.lock().unwrap().waiting.remove(signature);
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+
+/// Token 2022 mint 账户中基础 Mint 数据填充后的长度（与代币账户长度相同）
+const ACCOUNT_TYPE_OFFSET: usize = 165;
+
+/// `AccountType::Mint`
+const ACCOUNT_TYPE_MINT: u8 = 1;
+
+/// `ExtensionType::TransferFeeConfig`
+const TRANSFER_FEE_CONFIG_EXTENSION: u16 = 1;
+
+/// 两个 authority（各 32 字节）和 `withheld_amount` 之后是两组手续费
+const TRANSFER_FEES_OFFSET: usize = 72;
+
+/// 每组手续费：epoch (u64) + maximum_fee (u64) + transfer_fee_basis_points (u16)
+const TRANSFER_FEE_LEN: usize = 18;
+
+/// Token 2022 的转账手续费，每次转账从到账金额中扣除
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct TransferFee {
+    /// 从哪个 epoch 开始生效
+    pub epoch: u64,
+    /// 每笔转账手续费的上限
+    pub maximum_fee: u64,
+    pub basis_points: u16,
+}
+
+impl TransferFee {
+    /// 转账 `amount` 需要支付的手续费，向上取整，不超过 `maximum_fee`
+    pub fn fee(&self, amount: u64) -> u64 {
+        let fee = (amount as u128 * self.basis_points as u128).div_ceil(10_000);
+        (fee as u64).min(self.maximum_fee).min(amount)
+    }
+
+    /// 转账 `amount` 后对方实际收到的数量
+    pub fn after_fee(&self, amount: u64) -> u64 {
+        amount - self.fee(amount)
+    }
+}
+
+/// mint 的 `TransferFeeConfig` 扩展，修改手续费时新费率要到指定 epoch 才生效
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferFeeConfig {
+    pub older: TransferFee,
+    pub newer: TransferFee,
+}
+
+impl TransferFeeConfig {
+    /// 给定 epoch 生效的手续费
+    pub fn for_epoch(&self, epoch: u64) -> TransferFee {
+        if epoch >= self.newer.epoch {
+            self.newer
+        } else {
+            self.older
+        }
+    }
+}
+
+/// Token 2022 mint 账户中的扩展，按账户中的顺序返回扩展类型和数据
+///
+/// # 返回值
+/// SPL Token mint 或没有扩展时为空；数据被截断时只返回截断前的扩展
+pub fn mint_extensions(mint_data: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut extensions = Vec::new();
+    if mint_data.get(ACCOUNT_TYPE_OFFSET) != Some(&ACCOUNT_TYPE_MINT) {
+        return extensions;
+    }
+
+    // 扩展按 TLV 排列：类型 (u16) + 长度 (u16) + 数据
+    let mut offset = ACCOUNT_TYPE_OFFSET + 1;
+    while let Some(header) = mint_data.get(offset..offset + 4) {
+        let extension_type = u16::from_le_bytes([header[0], header[1]]);
+        let length = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let Some(value) = mint_data.get(offset + 4..offset + 4 + length) else {
+            break;
+        };
+        extensions.push((extension_type, value));
+        offset += 4 + length;
+    }
+    extensions
+}
+
+/// 从 Token 2022 mint 账户数据中解析 `TransferFeeConfig` 扩展
+///
+/// # 返回值
+/// 没有该扩展（包括 SPL Token mint）时返回 `None`
+pub fn parse_transfer_fee_config(mint_data: &[u8]) -> Option<TransferFeeConfig> {
+    let (_, value) = mint_extensions(mint_data)
+        .into_iter()
+        .find(|(extension_type, _)| *extension_type == TRANSFER_FEE_CONFIG_EXTENSION)?;
+    let fee = |index: usize| -> Option<TransferFee> {
+        let start = TRANSFER_FEES_OFFSET + index * TRANSFER_FEE_LEN;
+        let data = value.get(start..start + TRANSFER_FEE_LEN)?;
+        Some(TransferFee {
+            epoch: u64::from_le_bytes(data[0..8].try_into().ok()?),
+            maximum_fee: u64::from_le_bytes(data[8..16].try_into().ok()?),
+            basis_points: u16::from_le_bytes(data[16..18].try_into().ok()?),
+        })
+    };
+    Some(TransferFeeConfig {
+        older: fee(0)?,
+        newer: fee(1)?,
+    })
+}
@@ -0,0 +1,233 @@
+use crate::status::{unix_now, BotStatus, MintStatus, StatusSnapshot, TransactionOutcome};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use solana_sdk::native_token::{lamports_to_sol, LAMPORTS_PER_SOL};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 界面刷新间隔
+const REFRESH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 运行终端监控界面，直到按下 `q`、`Esc` 或 `Ctrl-C`
+///
+/// 上方是运行概况，中间每个代币一行（最大价差、上链比例、收益、最近一次发送），
+/// 下方是选中代币每个池子的价格。用上下方向键或 `j`/`k` 切换代币。
+///
+/// 终端进入 raw 模式后 `Ctrl-C` 不再产生信号，因此由界面处理并返回，调用方据此退出。
+/// 该函数会阻塞，需要在 `spawn_blocking` 中运行。
+///
+/// # 参数
+/// * `status` - 共享的机器人运行状态
+pub fn run(status: Arc<BotStatus>) -> anyhow::Result<()> {
+    let mut terminal = ratatui::init();
+    let result = (|| -> anyhow::Result<()> {
+        let mut selected = 0usize;
+        loop {
+            let snapshot = status.snapshot();
+            selected = selected.min(snapshot.mints.len().saturating_sub(1));
+            terminal.draw(|frame| draw(frame, &snapshot, selected))?;
+
+            if !event::poll(REFRESH_INTERVAL)? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(())
+                }
+                KeyCode::Down | KeyCode::Char('j') => selected += 1,
+                KeyCode::Up | KeyCode::Char('k') => selected = selected.saturating_sub(1),
+                _ => {}
+            }
+        }
+    })();
+    ratatui::restore();
+    result
+}
+
+/// 绘制一帧界面
+///
+/// # 参数
+/// * `snapshot` - 运行状态快照
+/// * `selected` - 选中代币在 `snapshot.mints` 中的序号，下方展示它的池子
+pub fn draw(frame: &mut Frame, snapshot: &StatusSnapshot, selected: usize) {
+    let [summary_area, mints_area, pools_area] = Layout::vertical([
+        Constraint::Length(4),
+        Constraint::Percentage(50),
+        Constraint::Fill(1),
+    ])
+    .areas(frame.area());
+
+    frame.render_widget(summary(snapshot), summary_area);
+
+    let now = unix_now();
+    let rows = snapshot.mints.iter().map(|(mint, status)| {
+        Row::new(vec![
+            mint.clone(),
+            status.pools.len().to_string(),
+            status
+                .best_spread_bps
+                .map_or("-".to_string(), |bps| format!("{:.1}", bps)),
+            status.sent_count.to_string(),
+            status
+                .landed_ratio()
+                .map_or("-".to_string(), |ratio| format!("{:.1}%", ratio * 100.0)),
+            format!("{:.6}", signed_sol(status.realized_pnl_lamports)),
+            last_send(status, now),
+        ])
+    });
+    let mints = Table::new(
+        rows,
+        [
+            Constraint::Length(44),
+            Constraint::Length(5),
+            Constraint::Length(11),
+            Constraint::Length(6),
+            Constraint::Length(7),
+            Constraint::Length(12),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "Mint",
+            "Pools",
+            "Spread bps",
+            "Sent",
+            "Landed",
+            "PnL SOL",
+            "Last send",
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED))
+    .block(Block::bordered().title(" Mints (↑/↓ to select, q to quit) "));
+    let mut state = TableState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(mints, mints_area, &mut state);
+
+    let Some((mint, status)) = snapshot.mints.iter().nth(selected) else {
+        frame.render_widget(Block::bordered().title(" Pools "), pools_area);
+        return;
+    };
+    let cheapest = status
+        .pools
+        .iter()
+        .filter_map(|pool| pool.price)
+        .filter(|price| *price > 0.0)
+        .reduce(f64::min);
+    let rows = status.pools.iter().map(|pool| {
+        let premium = match (pool.price, cheapest) {
+            (Some(price), Some(cheapest)) => format!("{:.1}", (price / cheapest - 1.0) * 10_000.0),
+            _ => "-".to_string(),
+        };
+        Row::new(vec![
+            pool.dex.clone(),
+            pool.pool.clone(),
+            pool.price
+                .map_or("-".to_string(), |price| format!("{:.6e}", price)),
+            premium,
+            pool.token_balance
+                .map_or("-".to_string(), |balance| balance.to_string()),
+            pool.sol_balance.map_or("-".to_string(), |balance| {
+                format!("{:.4}", lamports_to_sol(balance))
+            }),
+        ])
+    });
+    let pools = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(44),
+            Constraint::Length(13),
+            Constraint::Length(9),
+            Constraint::Length(20),
+            Constraint::Fill(1),
+        ],
+    )
+    .header(
+        Row::new(vec![
+            "DEX",
+            "Pool",
+            "Price",
+            "vs low bps",
+            "Token vault",
+            "SOL vault",
+        ])
+        .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(format!(" Pools of {} ", mint)));
+    frame.render_widget(pools, pools_area);
+}
+
+/// 运行时长、模式、收益、优先费和 RPC 健康状况
+fn summary(snapshot: &StatusSnapshot) -> Paragraph<'static> {
+    let uptime = unix_now().saturating_sub(snapshot.started_at);
+    let rpc = if snapshot.rpc.healthy {
+        Line::styled(
+            format!("RPC {} healthy", snapshot.rpc.url),
+            Style::new().fg(Color::Green),
+        )
+    } else {
+        Line::styled(
+            format!(
+                "RPC {} {} failures: {}",
+                snapshot.rpc.url,
+                snapshot.rpc.consecutive_failures,
+                snapshot.rpc.last_error.as_deref().unwrap_or("-")
+            ),
+            Style::new().fg(Color::Red),
+        )
+    };
+    Paragraph::new(vec![
+        Line::from(format!(
+            "Uptime {}h{:02}m{}  |  Realized PnL {:.6} SOL{} ({} landed)  |  Priority fee {} micro-lamports/CU",
+            uptime / 3_600,
+            uptime % 3_600 / 60,
+            if snapshot.dry_run { " (dry-run)" } else { "" },
+            signed_sol(snapshot.realized_pnl_lamports),
+            usd(snapshot, snapshot.realized_pnl_lamports),
+            snapshot.landed_count,
+            snapshot.priority_fee_micro_lamports
+        )),
+        rpc,
+    ])
+    .block(Block::bordered().title(" Arbitrage Bot "))
+}
+
+/// 最近一次交易尝试，例如 `12s ago landed +0.001000`
+fn last_send(status: &MintStatus, now: u64) -> String {
+    let Some(record) = &status.last_send else {
+        return "-".to_string();
+    };
+    let outcome = match &record.outcome {
+        TransactionOutcome::Landed { profit_lamports } => {
+            format!("landed {:+.6}", signed_sol(*profit_lamports))
+        }
+        TransactionOutcome::SendFailed { error } => format!("send failed: {}", error),
+        outcome => outcome.as_str().to_string(),
+    };
+    format!("{}s ago {}", now.saturating_sub(record.timestamp), outcome)
+}
+
+/// 有 SOL/USD 价格时的美元金额，例如 ` ($12.34)`
+fn usd(snapshot: &StatusSnapshot, lamports: i64) -> String {
+    snapshot
+        .sol_usd_price
+        .map(|price| format!(" (${:.2})", signed_sol(lamports) * price))
+        .unwrap_or_default()
+}
+
+/// 收益可能为负，不能用 `lamports_to_sol`
+fn signed_sol(lamports: i64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
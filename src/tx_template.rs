@@ -0,0 +1,220 @@
+use crate::config::Config;
+use crate::pools::MintPoolData;
+use crate::transaction::{compile_transaction_message, SwapPrograms};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::hash::Hash;
+use solana_sdk::message::v0::Message;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+
+/// 计算单元限制指令在消息中的位置，见 `compile_transaction_message`
+const COMPUTE_UNIT_LIMIT_INDEX: usize = 0;
+/// 计算单元价格指令在消息中的位置
+const COMPUTE_UNIT_PRICE_INDEX: usize = 1;
+/// `ComputeBudgetInstruction::SetComputeUnitLimit` 的指令编号
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+/// `ComputeBudgetInstruction::SetComputeUnitPrice` 的指令编号
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+/// 每个代币最多缓存的模板数，超过后清空重新编译
+const MAX_TEMPLATES: usize = 64;
+
+/// 编译好的交易消息骨架
+///
+/// 同一条路线每轮构建的交易只有 blockhash、计算单元价格和计算单元限制的随机扰动不同：
+/// 交易规模由执行程序在链上决定，Swap 指令数据中没有金额。模板保存账户列表、查找表引用和
+/// 指令数据，每轮只替换这三处再签名，省去指令构造、查找表选择和消息编译。
+#[derive(Debug, Clone)]
+pub struct TransactionTemplate {
+    message: Message,
+    compute_unit_limit: u32,
+}
+
+impl TransactionTemplate {
+    /// 用编译好的消息创建模板
+    ///
+    /// # 参数
+    /// * `message` - `compile_transaction_message` 编译的消息
+    /// * `compute_unit_limit` - 加入随机扰动前的计算单元限制
+    ///
+    /// # 错误
+    /// 前两条指令不是计算单元限制和价格指令时返回错误
+    pub fn new(message: Message, compute_unit_limit: u32) -> anyhow::Result<Self> {
+        for (index, instruction_type, name) in [
+            (
+                COMPUTE_UNIT_LIMIT_INDEX,
+                SET_COMPUTE_UNIT_LIMIT,
+                "compute unit limit",
+            ),
+            (
+                COMPUTE_UNIT_PRICE_INDEX,
+                SET_COMPUTE_UNIT_PRICE,
+                "compute unit price",
+            ),
+        ] {
+            let matches = message.instructions.get(index).is_some_and(|instruction| {
+                message
+                    .account_keys
+                    .get(instruction.program_id_index as usize)
+                    == Some(&compute_budget::id())
+                    && instruction.data.first() == Some(&instruction_type)
+            });
+            if !matches {
+                anyhow::bail!(
+                    "Instruction {} of the message is not a {} instruction",
+                    index,
+                    name
+                );
+            }
+        }
+        Ok(Self {
+            message,
+            compute_unit_limit,
+        })
+    }
+
+    /// 替换 blockhash 和计算单元价格后签名，计算单元限制和 `build_transaction` 一样加入随机扰动
+    pub fn sign(
+        &self,
+        wallet_kp: &Keypair,
+        blockhash: Hash,
+        compute_unit_price: u64,
+    ) -> anyhow::Result<VersionedTransaction> {
+        Ok(VersionedTransaction::try_new(
+            self.message(blockhash, compute_unit_price),
+            &[wallet_kp],
+        )?)
+    }
+
+    /// `sign` 签名的消息
+    pub fn message(&self, blockhash: Hash, compute_unit_price: u64) -> VersionedMessage {
+        let mut message = self.message.clone();
+        message.recent_blockhash = blockhash;
+        message.instructions[COMPUTE_UNIT_LIMIT_INDEX].data =
+            ComputeBudgetInstruction::set_compute_unit_limit(
+                self.compute_unit_limit + rand::random::<u32>() % 1000,
+            )
+            .data;
+        message.instructions[COMPUTE_UNIT_PRICE_INDEX].data =
+            ComputeBudgetInstruction::set_compute_unit_price(compute_unit_price).data;
+        VersionedMessage::V0(message)
+    }
+}
+
+/// 模板对应的路线：Swap 指令的池子账户和可用的查找表
+///
+/// 查找表扩展后地址数变化，需要重新选择查找表。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct TemplateKey {
+    accounts: Vec<Pubkey>,
+    lookup_tables: Vec<(Pubkey, usize)>,
+}
+
+impl TemplateKey {
+    fn new(
+        pool_data: &MintPoolData,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> Self {
+        Self {
+            accounts: pool_data
+                .pool_accounts()
+                .into_iter()
+                .flat_map(|pool| pool.accounts.into_iter().map(|meta| meta.pubkey))
+                .collect(),
+            lookup_tables: address_lookup_table_accounts
+                .iter()
+                .map(|table| (table.key, table.addresses.len()))
+                .collect(),
+        }
+    }
+}
+
+/// 一个代币按路线缓存的交易模板
+#[derive(Debug, Default)]
+pub struct TransactionTemplates {
+    templates: HashMap<TemplateKey, TransactionTemplate>,
+}
+
+impl TransactionTemplates {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 缓存的模板数
+    pub fn len(&self) -> usize {
+        self.templates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.templates.is_empty()
+    }
+
+    /// 构建和 `build_transaction` 相同的交易，路线第一次出现时编译并缓存模板
+    ///
+    /// 因为大小限制去掉了池子的消息取决于每轮的报价偏离，不缓存。
+    ///
+    /// # 参数
+    /// 同 `build_transaction`，`config` 中只有计算单元价格可以在两次调用之间变化
+    ///
+    /// # 错误
+    /// 编译或签名失败时返回错误
+    pub fn build(
+        &mut self,
+        wallet_kp: &Keypair,
+        config: &Config,
+        programs: &SwapPrograms,
+        mint_pool_data: &MintPoolData,
+        blockhash: Hash,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> anyhow::Result<VersionedTransaction> {
+        let message = self.build_message(
+            wallet_kp,
+            config,
+            programs,
+            mint_pool_data,
+            blockhash,
+            address_lookup_table_accounts,
+        )?;
+        Ok(VersionedTransaction::try_new(message, &[wallet_kp])?)
+    }
+
+    /// 与 `build` 相同但不签名，由调用方签名，见 `BroadcastLog::sign`
+    ///
+    /// # 错误
+    /// 编译失败时返回错误
+    pub fn build_message(
+        &mut self,
+        wallet_kp: &Keypair,
+        config: &Config,
+        programs: &SwapPrograms,
+        mint_pool_data: &MintPoolData,
+        blockhash: Hash,
+        address_lookup_table_accounts: &[AddressLookupTableAccount],
+    ) -> anyhow::Result<VersionedMessage> {
+        let key = TemplateKey::new(mint_pool_data, address_lookup_table_accounts);
+        if let Some(template) = self.templates.get(&key) {
+            return Ok(template.message(blockhash, config.compute_unit_price()));
+        }
+
+        let (message, trimmed) = compile_transaction_message(
+            wallet_kp,
+            config,
+            programs,
+            mint_pool_data,
+            blockhash,
+            address_lookup_table_accounts,
+        )?;
+        let template = TransactionTemplate::new(message, config.bot.compute_unit_limit)?;
+        let message = template.message(blockhash, config.compute_unit_price());
+        if !trimmed {
+            if self.templates.len() >= MAX_TEMPLATES {
+                self.templates.clear();
+            }
+            self.templates.insert(key, template);
+        }
+        Ok(message)
+    }
+}
@@ -0,0 +1,165 @@
+use crate::fees::PoolFee;
+use crate::pools::MintPoolData;
+use crate::quote::{self, PoolReserves, QuoteAccount, MAX_MULTIPLE_ACCOUNTS};
+use crate::status::{unix_now, BotStatus};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{debug, error};
+
+/// 所有代币池子的 vault 余额
+///
+/// 后台任务每隔一段时间用 `getMultipleAccounts` 批量读取登记过的 vault，解析 SPL Token 余额后缓存起来，
+/// 报价和仪表盘直接读取缓存，不再各自请求。读取失败时保留上一次的余额。
+///
+/// Meteora DAMM v1 池子的 vault 账户、LP mint 和池子的 LP 代币账户也一起刷新，
+/// vault 调仓或产生收益后报价使用的兑换比例随之更新，见 `quote::quote_accounts`。
+/// 启用自适应手续费的 Whirlpool 同时读取池子和 oracle 账户，缓存当前费率，见 `quote::live_fee_accounts`。
+#[derive(Debug, Default)]
+pub struct VaultTracker {
+    vaults: RwLock<BTreeMap<Pubkey, QuoteAccount>>,
+    balances: RwLock<HashMap<Pubkey, u64>>,
+    /// 池子 -> oracle，读取实时手续费
+    fee_accounts: RwLock<BTreeMap<Pubkey, Pubkey>>,
+    pool_fees: RwLock<HashMap<Pubkey, PoolFee>>,
+    /// 最近一次成功刷新的时间
+    refreshed_at: RwLock<Option<Instant>>,
+}
+
+impl VaultTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记一个代币所有池子的 vault 和报价用到的账户，下一次刷新开始跟踪
+    pub fn register(&self, pool_data: &MintPoolData) {
+        let mut vaults = self.vaults.write().unwrap();
+        for (_, token_vault, sol_vault) in pool_data.pool_vaults() {
+            vaults.insert(token_vault, QuoteAccount::TokenAccount);
+            vaults.insert(sol_vault, QuoteAccount::TokenAccount);
+        }
+        vaults.extend(quote::quote_accounts(pool_data));
+        self.fee_accounts
+            .write()
+            .unwrap()
+            .extend(quote::live_fee_accounts(pool_data));
+    }
+
+    /// 跟踪的 vault 数量
+    pub fn len(&self) -> usize {
+        self.vaults.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// vault 的最新余额，还没有读取到时返回 `None`
+    pub fn balance(&self, vault: &Pubkey) -> Option<u64> {
+        self.balances.read().unwrap().get(vault).copied()
+    }
+
+    /// 最近一次成功刷新的时间，还没有刷新过时返回 `None`
+    pub fn refreshed_at(&self) -> Option<Instant> {
+        *self.refreshed_at.read().unwrap()
+    }
+
+    /// 所有 vault 余额的副本
+    pub fn balances(&self) -> HashMap<Pubkey, u64> {
+        self.balances.read().unwrap().clone()
+    }
+
+    /// 代币各池子最新读取到的实时手续费，还没有读取到或费率不随波动变化的池子不在其中
+    pub fn pool_fees(&self, pool_data: &MintPoolData) -> HashMap<Pubkey, PoolFee> {
+        let pool_fees = self.pool_fees.read().unwrap();
+        quote::live_fee_accounts(pool_data)
+            .into_iter()
+            .filter_map(|(pool, _)| Some((pool, *pool_fees.get(&pool)?)))
+            .collect()
+    }
+
+    /// 用缓存的余额计算池子储备，见 `quote::reserves_from_balances`
+    pub fn reserves(&self, pool_data: &MintPoolData) -> HashMap<Pubkey, PoolReserves> {
+        let balances = self.balances.read().unwrap();
+        quote::reserves_from_balances(pool_data, |vault| balances.get(vault).copied())
+    }
+
+    /// 读取所有 vault 的余额
+    ///
+    /// # 返回值
+    /// 成功解析出余额的 vault 数量
+    pub fn refresh(&self, rpc_client: &RpcClient) -> anyhow::Result<usize> {
+        let vaults: Vec<(Pubkey, QuoteAccount)> = self
+            .vaults
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(vault, kind)| (*vault, *kind))
+            .collect();
+        let now = unix_now();
+        let mut balances = HashMap::with_capacity(vaults.len());
+        for chunk in vaults.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let pubkeys: Vec<Pubkey> = chunk.iter().map(|(vault, _)| *vault).collect();
+            let accounts = rpc_client.get_multiple_accounts(&pubkeys)?;
+            for ((vault, kind), account) in chunk.iter().zip(accounts) {
+                if let Some(amount) = account.and_then(|account| kind.amount(&account.data, now)) {
+                    balances.insert(*vault, amount);
+                }
+            }
+        }
+
+        let fee_pools: Vec<(Pubkey, Pubkey)> = self
+            .fee_accounts
+            .read()
+            .unwrap()
+            .iter()
+            .map(|(pool, oracle)| (*pool, *oracle))
+            .collect();
+        let pubkeys: Vec<Pubkey> = fee_pools
+            .iter()
+            .flat_map(|(pool, oracle)| [*pool, *oracle])
+            .collect();
+        let mut accounts = HashMap::new();
+        for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            for (pubkey, account) in chunk.iter().zip(rpc_client.get_multiple_accounts(chunk)?) {
+                if let Some(account) = account {
+                    accounts.insert(*pubkey, account.data);
+                }
+            }
+        }
+        let pool_fees = quote::live_fees(
+            &fee_pools,
+            |pubkey| accounts.get(pubkey).map(Vec::as_slice),
+            now,
+        );
+
+        let count = balances.len();
+        self.balances.write().unwrap().extend(balances);
+        self.pool_fees.write().unwrap().extend(pool_fees);
+        *self.refreshed_at.write().unwrap() = Some(Instant::now());
+        Ok(count)
+    }
+
+    /// 持续刷新 vault 余额，并同步到仪表盘
+    pub async fn run(
+        self: Arc<Self>,
+        rpc_client: Arc<RpcClient>,
+        interval: Duration,
+        status: Arc<BotStatus>,
+    ) {
+        loop {
+            if !self.is_empty() {
+                match self.refresh(&rpc_client) {
+                    Ok(count) => {
+                        debug!("Refreshed {} of {} vault balances", count, self.len());
+                        status.update_vault_balances(&self.balances.read().unwrap());
+                    }
+                    Err(e) => error!("Failed to refresh vault balances: {}", e),
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use crate::app_state::AppState;
+use crate::config::WatchdogConfig;
+use crate::notifications::Alert;
+use crate::vaults::VaultTracker;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+/// 看门狗的检查间隔
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// 暂停发送的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaleReason {
+    /// 代币的池子数据超过上限没有更新
+    PoolData { age: Duration },
+    /// blockhash 连续刷新失败
+    BlockhashFailures(u32),
+    /// slot 超过上限没有前进
+    SlotStalled { slot: Slot, stalled: Duration },
+}
+
+impl fmt::Display for StaleReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaleReason::PoolData { age } => {
+                write!(f, "pool data not updated for {}s", age.as_secs())
+            }
+            StaleReason::BlockhashFailures(failures) => {
+                write!(f, "blockhash refresh failed {} times in a row", failures)
+            }
+            StaleReason::SlotStalled { slot, stalled } => {
+                write!(f, "slot stuck at {} for {}s", slot, stalled.as_secs())
+            }
+        }
+    }
+}
+
+/// 过期数据看门狗
+///
+/// blockhash 连续刷新失败或 slot 停止前进时暂停所有代币，每轮报价的代币池子数据超过上限没有更新时
+/// 暂停该代币；进入和恢复时各推送一次告警。不每轮报价的代币由链上程序读取最新的池子状态，
+/// 本地没有会过期的池子数据，不检查池子数据的更新时间。
+pub struct Watchdog {
+    max_pool_age: Duration,
+    max_blockhash_failures: u32,
+    max_slot_stall: Duration,
+    /// 跟踪 vault 余额时，报价使用的池子数据随 vault 刷新而更新
+    vault_tracker: Option<Arc<VaultTracker>>,
+    /// 每轮报价的代币最近一次更新池子数据的时间
+    pool_updates: RwLock<HashMap<Pubkey, Instant>>,
+    /// 最近观察到的 slot 及其第一次出现的时间
+    slot: Mutex<Option<(Slot, Instant)>>,
+    /// 所有代币共用的暂停原因，由 `run` 更新
+    global: RwLock<Option<StaleReason>>,
+}
+
+impl Watchdog {
+    pub fn new(config: &WatchdogConfig, vault_tracker: Option<Arc<VaultTracker>>) -> Self {
+        Self {
+            max_pool_age: config.max_pool_age(),
+            max_blockhash_failures: config.max_blockhash_failures(),
+            max_slot_stall: config.max_slot_stall(),
+            vault_tracker,
+            pool_updates: RwLock::new(HashMap::new()),
+            slot: Mutex::new(None),
+            global: RwLock::new(None),
+        }
+    }
+
+    /// 开始检查代币的池子数据更新时间，登记的时间算作第一次更新
+    pub fn watch_mint(&self, mint: Pubkey, now: Instant) {
+        self.pool_updates.write().unwrap().insert(mint, now);
+    }
+
+    /// 记录代币的池子数据已更新，没有登记的代币忽略
+    pub fn record_pool_update(&self, mint: &Pubkey, now: Instant) {
+        if let Some(updated) = self.pool_updates.write().unwrap().get_mut(mint) {
+            *updated = (*updated).max(now);
+        }
+    }
+
+    /// 代币的池子数据是否过期，没有登记的代币返回 `None`
+    pub fn pool_staleness(&self, mint: &Pubkey, now: Instant) -> Option<StaleReason> {
+        let mut updated = *self.pool_updates.read().unwrap().get(mint)?;
+        if let Some(refreshed) = self
+            .vault_tracker
+            .as_ref()
+            .and_then(|tracker| tracker.refreshed_at())
+        {
+            updated = updated.max(refreshed);
+        }
+        let age = now.saturating_duration_since(updated);
+        (age > self.max_pool_age).then_some(StaleReason::PoolData { age })
+    }
+
+    /// 记录最新的 slot 和 blockhash 连续刷新失败次数，返回所有代币共用的暂停原因
+    pub fn observe(
+        &self,
+        slot: Slot,
+        blockhash_failures: u32,
+        now: Instant,
+    ) -> Option<StaleReason> {
+        let reason = if blockhash_failures >= self.max_blockhash_failures {
+            Some(StaleReason::BlockhashFailures(blockhash_failures))
+        } else {
+            let mut last = self.slot.lock().unwrap();
+            let since = match *last {
+                Some((last_slot, since)) if last_slot == slot => since,
+                _ => {
+                    *last = Some((slot, now));
+                    now
+                }
+            };
+            let stalled = now.saturating_duration_since(since);
+            (stalled > self.max_slot_stall).then_some(StaleReason::SlotStalled { slot, stalled })
+        };
+        *self.global.write().unwrap() = reason;
+        reason
+    }
+
+    /// 代币当前是否需要暂停发送
+    pub fn pause_reason(&self, mint: &Pubkey) -> Option<StaleReason> {
+        (*self.global.read().unwrap()).or_else(|| self.pool_staleness(mint, Instant::now()))
+    }
+
+    /// 每秒检查一次，进入和离开过期状态时记录日志并推送告警
+    pub async fn run(self: Arc<Self>, state: Arc<AppState>) {
+        let mut global_stale = false;
+        let mut stale_mints: HashSet<Pubkey> = HashSet::new();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let now = Instant::now();
+            let (_, slot) = state.latest_blockhash().await;
+            let failures = state.status.rpc_health().consecutive_failures;
+
+            let reason = self.observe(slot, failures, now);
+            match reason {
+                Some(reason) if !global_stale => {
+                    error!("Pausing all mints: {}", reason);
+                    state.notifier.notify(Alert::StaleData {
+                        scope: "all mints".to_string(),
+                        reason: reason.to_string(),
+                    });
+                }
+                None if global_stale => {
+                    info!("Resuming all mints: blockhash and slot are fresh again");
+                    state.notifier.notify(Alert::StaleDataRecovered {
+                        scope: "all mints".to_string(),
+                    });
+                }
+                _ => {}
+            }
+            global_stale = reason.is_some();
+
+            let mints: Vec<Pubkey> = self.pool_updates.read().unwrap().keys().copied().collect();
+            for mint in mints {
+                match self.pool_staleness(&mint, now) {
+                    Some(reason) if stale_mints.insert(mint) => {
+                        error!("Pausing mint {}: {}", mint, reason);
+                        state.notifier.notify(Alert::StaleData {
+                            scope: mint.to_string(),
+                            reason: reason.to_string(),
+                        });
+                    }
+                    None if stale_mints.remove(&mint) => {
+                        info!("Resuming mint {}: pool data updated", mint);
+                        state.notifier.notify(Alert::StaleDataRecovered {
+                            scope: mint.to_string(),
+                        });
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
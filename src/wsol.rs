@@ -0,0 +1,205 @@
+use crate::config::WsolTopUpConfig;
+use crate::constants::sol_mint;
+use crate::notifications::{Alert, Notifier};
+use crate::quote::token_amount;
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_program::native_token::{lamports_to_sol, sol_to_lamports};
+use solana_program::pubkey::Pubkey;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::signature::{Keypair, Signature};
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::get_associated_token_address;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+/// 钱包的原生 SOL 和 WSOL 余额
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalletBalance {
+    /// 原生 SOL（lamports）
+    pub native: u64,
+    /// WSOL 账户余额（lamports），账户不存在时为 0
+    pub wsol: u64,
+}
+
+impl WalletBalance {
+    pub fn total(&self) -> u64 {
+        self.native + self.wsol
+    }
+}
+
+/// 计算需要包装的 SOL 数量
+///
+/// WSOL 低于 `min_wsol` 时补充到 `target_wsol`，原生 SOL 始终保留 `gas_reserve`；
+/// 原生 SOL 不够时只包装保留量以外的部分。
+///
+/// # 返回值
+/// 需要包装的 lamports，不需要补充或没有可用的原生 SOL 时为 `None`
+pub fn top_up_amount(
+    balance: WalletBalance,
+    min_wsol: u64,
+    target_wsol: u64,
+    gas_reserve: u64,
+) -> Option<u64> {
+    if balance.wsol >= min_wsol {
+        return None;
+    }
+    let amount = target_wsol
+        .saturating_sub(balance.wsol)
+        .min(balance.native.saturating_sub(gas_reserve));
+    (amount > 0).then_some(amount)
+}
+
+/// 把 `amount` lamports 的原生 SOL 包装进钱包的 WSOL 账户，账户不存在时先创建
+pub fn wrap_sol_instructions(wallet: &Pubkey, amount: u64) -> anyhow::Result<Vec<Instruction>> {
+    let wsol_account = get_associated_token_address(wallet, sol_mint());
+    Ok(vec![
+        create_associated_token_account_idempotent(wallet, wallet, sol_mint(), &spl_token::ID),
+        system_instruction::transfer(wallet, &wsol_account, amount),
+        spl_token::instruction::sync_native(&spl_token::ID, &wsol_account)?,
+    ])
+}
+
+/// 读取钱包的原生 SOL 和 WSOL 余额
+pub fn fetch_wallet_balance(
+    rpc_client: &RpcClient,
+    wallet: &Pubkey,
+) -> anyhow::Result<WalletBalance> {
+    let wsol_account = get_associated_token_address(wallet, sol_mint());
+    let accounts = rpc_client.get_multiple_accounts(&[*wallet, wsol_account])?;
+    let native = accounts[0].as_ref().map_or(0, |account| account.lamports);
+    let wsol = accounts[1]
+        .as_ref()
+        .and_then(|account| token_amount(&account.data))
+        .unwrap_or(0);
+    Ok(WalletBalance { native, wsol })
+}
+
+/// WSOL 自动补充：定期检查钱包余额，WSOL 不足时包装原生 SOL，合计余额过低时告警
+///
+/// dry-run 模式下只记录需要包装的数量，不发送交易。
+pub struct WsolTopUp {
+    rpc_client: Arc<RpcClient>,
+    wallet: Keypair,
+    min_wsol: u64,
+    target_wsol: u64,
+    gas_reserve: u64,
+    min_total: Option<u64>,
+    check_interval: Duration,
+    dry_run: bool,
+    notifier: Notifier,
+    /// 已经发出过余额过低告警，回到阈值以上后重置
+    low_balance_alerted: AtomicBool,
+}
+
+impl WsolTopUp {
+    pub fn new(
+        rpc_client: Arc<RpcClient>,
+        wallet: &Keypair,
+        config: &WsolTopUpConfig,
+        dry_run: bool,
+        notifier: Notifier,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            rpc_client,
+            wallet: Keypair::from_bytes(&wallet.to_bytes())?,
+            min_wsol: sol_to_lamports(config.min_wsol_sol),
+            target_wsol: sol_to_lamports(config.target_wsol_sol()),
+            gas_reserve: sol_to_lamports(config.gas_reserve_sol()),
+            min_total: config.min_total_sol.map(sol_to_lamports),
+            check_interval: config.check_interval(),
+            dry_run,
+            notifier,
+            low_balance_alerted: AtomicBool::new(false),
+        })
+    }
+
+    /// 检查一次余额，需要时包装 SOL
+    ///
+    /// # 返回值
+    /// 发送了包装交易时返回签名
+    pub fn check(&self) -> anyhow::Result<Option<Signature>> {
+        let wallet = self.wallet.pubkey();
+        let balance = fetch_wallet_balance(&self.rpc_client, &wallet)?;
+        self.check_total(balance);
+
+        let Some(amount) =
+            top_up_amount(balance, self.min_wsol, self.target_wsol, self.gas_reserve)
+        else {
+            if balance.wsol < self.min_wsol {
+                warn!(
+                    "WSOL balance {} SOL is below {} SOL, but only {} SOL native is left for fees",
+                    lamports_to_sol(balance.wsol),
+                    lamports_to_sol(self.min_wsol),
+                    lamports_to_sol(balance.native)
+                );
+            }
+            return Ok(None);
+        };
+        if self.dry_run {
+            info!(
+                "Dry run: would wrap {} SOL (WSOL {} SOL, native {} SOL)",
+                lamports_to_sol(amount),
+                lamports_to_sol(balance.wsol),
+                lamports_to_sol(balance.native)
+            );
+            return Ok(None);
+        }
+
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(60_000)];
+        instructions.extend(wrap_sol_instructions(&wallet, amount)?);
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&wallet),
+            &[&self.wallet],
+            self.rpc_client.get_latest_blockhash()?,
+        );
+        let signature = self.rpc_client.send_and_confirm_transaction(&tx)?;
+        info!(
+            "Wrapped {} SOL into WSOL (WSOL was {} SOL): {}",
+            lamports_to_sol(amount),
+            lamports_to_sol(balance.wsol),
+            signature
+        );
+        Ok(Some(signature))
+    }
+
+    /// 合计余额跌破 `min_total_sol` 时告警一次，回到阈值以上后重新开始检查
+    fn check_total(&self, balance: WalletBalance) {
+        let Some(threshold_lamports) = self.min_total else {
+            return;
+        };
+        if balance.total() >= threshold_lamports {
+            self.low_balance_alerted.store(false, Ordering::Relaxed);
+            return;
+        }
+        if !self.low_balance_alerted.swap(true, Ordering::Relaxed) {
+            warn!(
+                "Wallet holds {} SOL native and {} SOL WSOL, below {} SOL in total",
+                lamports_to_sol(balance.native),
+                lamports_to_sol(balance.wsol),
+                lamports_to_sol(threshold_lamports)
+            );
+            self.notifier.notify(Alert::LowBalance {
+                wallet: self.wallet.pubkey(),
+                balance_lamports: balance.total(),
+                threshold_lamports,
+            });
+        }
+    }
+
+    /// 定期检查余额并补充 WSOL
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            if let Err(e) = self.check() {
+                error!("Failed to top up WSOL: {}", e);
+            }
+            tokio::time::sleep(self.check_interval).await;
+        }
+    }
+}
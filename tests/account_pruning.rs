@@ -0,0 +1,103 @@
+//! 模拟裁剪账户列表测试：可裁剪账户的分组、保留规则、裁剪后的池子数据、缓存过期和配置校验
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::account_pruning::{
+    prunable_accounts, select_pruned, without_accounts, PrunedRoutes,
+};
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+fn keys(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+/// 一个 DLMM 交易对（bin array 为 k[4..7]）和一个 Whirlpool 池子（tick array 为 k[11..14]）
+fn pool_data(k: &[Pubkey]) -> MintPoolData {
+    let mut data = MintPoolData::new(*usdc_mint(), Pubkey::new_unique(), spl_token::ID);
+    data.add_dlmm_pool(k[0], k[1], k[2], k[3], vec![k[4], k[5], k[6]], None);
+    data.add_whirlpool_pool(k[7], k[8], k[9], k[10], vec![k[11], k[12], k[13]], None);
+    data
+}
+
+#[test]
+fn only_trailing_arrays_of_concentrated_pools_are_prunable() {
+    let k = keys(14);
+    let mut data = pool_data(&k);
+    assert_eq!(
+        prunable_accounts(&data),
+        vec![
+            (k[0], vec![k[4], k[5], k[6]]),
+            (k[7], vec![k[11], k[12], k[13]]),
+        ]
+    );
+
+    // 只有一个 array 的池子没有可以去掉的账户
+    data.whirlpool_pools[0].tick_arrays.truncate(1);
+    assert_eq!(prunable_accounts(&data).len(), 1);
+}
+
+#[test]
+fn written_arrays_are_kept_and_untouched_pools_keep_their_first_array() {
+    let k = keys(14);
+    let groups = prunable_accounts(&pool_data(&k));
+
+    // DLMM 跨过了 k[5] 和 k[6]，Whirlpool 没有写入任何 tick array
+    let written: HashSet<Pubkey> = [k[5], k[6], k[2]].into_iter().collect();
+    let pruned = select_pruned(&groups, &written);
+    assert_eq!(pruned, [k[4], k[12], k[13]].into_iter().collect());
+
+    // 没有写入时每个池子只保留第一个
+    let pruned = select_pruned(&groups, &HashSet::new());
+    assert_eq!(pruned, [k[5], k[6], k[12], k[13]].into_iter().collect());
+}
+
+#[test]
+fn pruned_pool_data_keeps_every_other_account() {
+    let k = keys(14);
+    let data = pool_data(&k);
+    let pruned: HashSet<Pubkey> = [k[4], k[12], k[13]].into_iter().collect();
+    let slim = without_accounts(&data, &pruned);
+
+    assert_eq!(slim.dlmm_pairs[0].bin_arrays, vec![k[5], k[6]]);
+    assert_eq!(slim.whirlpool_pools[0].tick_arrays, vec![k[11]]);
+    assert_eq!(slim.pool_addresses(), data.pool_addresses());
+    assert_eq!(slim.dlmm_pairs[0].oracle, data.dlmm_pairs[0].oracle);
+}
+
+#[test]
+fn cached_routes_expire_after_the_ttl() {
+    let k = keys(14);
+    let mut routes = PrunedRoutes::new(Duration::from_secs(300));
+    let route = PrunedRoutes::route_key(&pool_data(&k));
+    let start = Instant::now();
+    assert!(routes.get(&route, start).is_none());
+
+    let pruned: HashSet<Pubkey> = [k[4]].into_iter().collect();
+    routes.insert(route.clone(), pruned.clone(), start);
+    assert_eq!(
+        routes.get(&route, start + Duration::from_secs(299)),
+        Some(&pruned)
+    );
+    assert!(routes
+        .get(&route, start + Duration::from_secs(300))
+        .is_none());
+}
+
+#[test]
+fn account_pruning_config_defaults_and_validation() {
+    let config = parse_config("[account_pruning]\nenabled = true").unwrap();
+    assert_eq!(
+        config.account_pruning.unwrap().ttl(),
+        Duration::from_secs(300)
+    );
+
+    let error = parse_config("[account_pruning]\nenabled = true\nttl_secs = 0")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("account_pruning.ttl_secs"), "{}", error);
+}
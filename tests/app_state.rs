@@ -0,0 +1,107 @@
+//! 共享运行状态测试：计算单元价格、blockhash 缓存、发送端点和代币注册
+
+mod common;
+
+use common::parse_config_with_wallet;
+use solana_client::rpc_client::RpcClient;
+use solana_onchain_arbitrage_bot::app_state::AppState;
+use solana_onchain_arbitrage_bot::config::SendingEndpoint;
+use solana_onchain_arbitrage_bot::notifications::Notifier;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::status::BotStatus;
+use solana_onchain_arbitrage_bot::submit::{RpcSubmitter, Submitters};
+use solana_onchain_arbitrage_bot::transaction::SendingClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+
+fn state() -> AppState {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(
+        &wallet,
+        "[spam]\nenabled = true\nsending_rpc_urls = [\"http://127.0.0.1:8899\"]\ncompute_unit_price = 2000",
+    )
+    .unwrap();
+    let status = Arc::new(BotStatus::new(
+        config.rpc.url.clone(),
+        false,
+        config.compute_unit_price(),
+    ));
+    AppState::new(
+        config,
+        wallet,
+        Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string())),
+        (Hash::new_unique(), 42),
+        status,
+        Notifier::new(None),
+    )
+}
+
+#[tokio::test]
+async fn state_starts_from_the_configured_price_and_blockhash() {
+    let state = state();
+    assert_eq!(state.priority_fee(), 2000);
+    assert!(state.submitters.is_empty());
+    assert!(!state.trade_sinks.is_enabled());
+
+    let blockhash = Hash::new_unique();
+    *state.blockhash.lock().await = (blockhash, 43);
+    assert_eq!(state.latest_blockhash().await, (blockhash, 43));
+}
+
+#[tokio::test]
+async fn set_blockhash_resets_the_blockhash_age() {
+    let state = state();
+    let blockhash = Hash::new_unique();
+    state.set_blockhash(blockhash, 44).await;
+    assert_eq!(state.latest_blockhash().await, (blockhash, 44));
+    assert!(state.blockhash_age() < Duration::from_secs(5));
+}
+
+#[test]
+fn send_endpoints_list_the_rpc_senders() {
+    let state = state();
+    assert!(state.send_endpoints().is_empty());
+    let state = state.with_submitters(Submitters::new(vec![Arc::new(RpcSubmitter::new(
+        vec![SendingClient::new(
+            Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string())),
+            SendingEndpoint::new("http://127.0.0.1:8899"),
+        )],
+        Duration::ZERO,
+    ))]));
+    assert_eq!(state.send_endpoints(), vec!["http://127.0.0.1:8899"]);
+}
+
+#[test]
+fn priority_fee_updates_the_dashboard_status() {
+    let state = state();
+    state.set_priority_fee(250);
+    assert_eq!(state.priority_fee(), 250);
+    assert_eq!(state.status.snapshot().priority_fee_micro_lamports, 250);
+}
+
+#[tokio::test]
+async fn registered_mints_share_pool_data() {
+    let state = state();
+    let mint = Pubkey::new_unique();
+    let pool_data = Arc::new(Mutex::new(MintPoolData::new(
+        mint,
+        state.wallet.pubkey(),
+        spl_token::ID,
+    )));
+    assert!(state.mint(&mint).is_none());
+
+    state.insert_mint(mint, pool_data.clone());
+    assert_eq!(state.mints(), vec![mint]);
+    pool_data.lock().await.add_raydium_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let shared = state.mint(&mint).unwrap();
+    assert_eq!(shared.lock().await.raydium_pools.len(), 1);
+}
@@ -0,0 +1,94 @@
+//! 启动时创建 ATA 的配置和交易测试
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::ata::{
+    create_ata_transaction, mint_token_program, TOKEN_2022_PROGRAM_ID,
+};
+use solana_onchain_arbitrage_bot::config::{Config, CreateAtaPolicy};
+use solana_program::hash::Hash;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::str::FromStr;
+
+fn config(bot: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        bot,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn create_ata_policy_parses_and_defaults_to_auto() {
+    let bot = config("").unwrap().bot;
+    assert_eq!(bot.create_ata, CreateAtaPolicy::Auto);
+    assert_eq!(bot.create_ata_attempts(), 3);
+
+    for (value, policy) in [
+        ("auto", CreateAtaPolicy::Auto),
+        ("never", CreateAtaPolicy::Never),
+        ("fail_if_missing", CreateAtaPolicy::FailIfMissing),
+    ] {
+        let bot = config(&format!("create_ata = \"{}\"", value)).unwrap().bot;
+        assert_eq!(bot.create_ata, policy);
+    }
+    assert!(config("create_ata = \"sometimes\"").is_err());
+
+    let error = config("create_ata_attempts = 0").unwrap_err().to_string();
+    assert!(error.contains("bot.create_ata_attempts"), "{}", error);
+}
+
+#[test]
+fn create_ata_transaction_uses_the_mint_token_program_and_price() {
+    let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+    let wallet = Keypair::new();
+    let mint = Pubkey::new_unique();
+    let tx = create_ata_transaction(&wallet, &mint, &token_2022, 5_000, Hash::default());
+    let message = &tx.message;
+
+    let price = ComputeBudgetInstruction::set_compute_unit_price(5_000);
+    assert_eq!(message.instructions[0].data, price.data);
+
+    let create = &message.instructions[2];
+    let accounts: Vec<Pubkey> = create
+        .accounts
+        .iter()
+        .map(|index| message.account_keys[*index as usize])
+        .collect();
+    assert_eq!(
+        accounts[1],
+        get_associated_token_address_with_program_id(&wallet.pubkey(), &mint, &token_2022)
+    );
+    assert_eq!(accounts[5], token_2022);
+    assert!(tx.verify().is_ok());
+}
+
+#[test]
+fn token_2022_mints_keep_their_token_program() {
+    let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+    let mint = Pubkey::new_unique();
+    assert_eq!(mint_token_program(&mint, &token_2022).unwrap(), token_2022);
+    assert_eq!(
+        mint_token_program(&mint, &spl_token::ID).unwrap(),
+        spl_token::ID
+    );
+    let error = mint_token_program(&mint, &Pubkey::new_unique()).unwrap_err();
+    assert!(
+        error.to_string().contains("Unknown token program"),
+        "{}",
+        error
+    );
+
+    // 回归：按 SPL Token 推导的 ATA 不是 Token 2022 mint 的账户
+    let wallet = Keypair::new();
+    let tx = create_ata_transaction(&wallet, &mint, &token_2022, 0, Hash::default());
+    let legacy_ata =
+        spl_associated_token_account::get_associated_token_address(&wallet.pubkey(), &mint);
+    assert!(!tx.message.account_keys.contains(&legacy_ata));
+    assert!(!tx.message.account_keys.contains(&spl_token::ID));
+}
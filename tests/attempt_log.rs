@@ -0,0 +1,81 @@
+//! 单位经济记录测试：路线、JSON 字段
+
+use serde_json::Value;
+use solana_onchain_arbitrage_bot::attempt_log::{AttemptRecord, RoutePool};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::SizedQuote;
+use solana_sdk::pubkey::Pubkey;
+
+/// 一个 Raydium 池子和一个 Raydium CP 池子
+fn pool_data() -> (MintPoolData, Pubkey, Pubkey) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (raydium, cp) = (Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_raydium_pool(raydium, Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_raydium_cp_pool(
+        cp,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    (pool_data, raydium, cp)
+}
+
+#[test]
+fn route_is_the_quoted_pools_in_trade_order() {
+    let (pool_data, raydium, cp) = pool_data();
+    let quote = SizedQuote {
+        size: 1_000_000_000,
+        buy_pool: cp,
+        sell_pool: raydium,
+        profit: 5_000,
+    };
+    assert_eq!(
+        AttemptRecord::route(&pool_data, Some(&quote)),
+        vec![
+            RoutePool {
+                dex: "raydium_cp",
+                pool: cp.to_string()
+            },
+            RoutePool {
+                dex: "raydium",
+                pool: raydium.to_string()
+            },
+        ]
+    );
+
+    // 没有报价时为交给执行程序的所有池子
+    let route = AttemptRecord::route(&pool_data, None);
+    assert_eq!(route.len(), 2);
+    assert_eq!(route[0].dex, "raydium");
+}
+
+#[test]
+fn record_is_a_single_json_line() {
+    let (pool_data, raydium, cp) = pool_data();
+    let record = AttemptRecord {
+        mint: pool_data.mint.to_string(),
+        route: AttemptRecord::route(&pool_data, None),
+        input_size: None,
+        expected_profit: Some(5_000),
+        compute_unit_price: 1_000,
+        compute_unit_limit: 400_000,
+        blockhash_age_ms: 1_200,
+        endpoints: vec!["https://rpc.example.com".to_string(), "tpu".to_string()],
+        dry_run: false,
+    };
+    let json = record.to_json();
+    assert!(!json.contains('\n'));
+
+    let value: Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["route"][0]["pool"], raydium.to_string());
+    assert_eq!(value["route"][1]["pool"], cp.to_string());
+    assert_eq!(value["input_size"], Value::Null);
+    assert_eq!(value["expected_profit"], 5_000);
+    assert_eq!(value["compute_unit_price"], 1_000);
+    assert_eq!(value["compute_unit_limit"], 400_000);
+    assert_eq!(value["blockhash_age_ms"], 1_200);
+    assert_eq!(value["endpoints"][1], "tpu");
+    assert_eq!(value["dry_run"], false);
+}
@@ -0,0 +1,148 @@
+//! 多代币合并交易测试
+
+mod common;
+
+use common::{parse_config_with_wallet, programs, raydium_pool_data};
+use solana_onchain_arbitrage_bot::batch::pack;
+use solana_onchain_arbitrage_bot::transaction::{
+    build_batch_transaction, uncovered_accounts, MAX_COMPUTE_UNIT_LIMIT,
+};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::time::Duration;
+
+#[test]
+fn requests_are_packed_in_order_until_the_transaction_is_full() {
+    // 任意两个请求都放得下，最多 3 个一组
+    assert_eq!(pack(5, 3, |_| true), vec![vec![0, 1, 2], vec![3, 4]]);
+    // 每组最多放两个
+    assert_eq!(
+        pack(5, 10, |group| group.len() <= 2),
+        vec![vec![0, 1], vec![2, 3], vec![4]]
+    );
+    // 放不下的请求单独成组，由单笔交易的裁剪逻辑处理
+    assert_eq!(
+        pack(3, 10, |group| group != [0, 1]),
+        vec![vec![0], vec![1, 2]]
+    );
+    assert_eq!(pack(1, 1, |_| unreachable!()), vec![vec![0]]);
+    assert!(pack(0, 4, |_| true).is_empty());
+}
+
+#[test]
+fn batched_transaction_has_one_swap_per_mint() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "").unwrap();
+    let (first, second) = (
+        raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+        raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+    );
+
+    let tx = build_batch_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &[&first, &second],
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let keys = tx.message.static_account_keys();
+    let instructions = tx.message.instructions();
+    // 计算单元限制、计算单元价格和两条 Swap 指令
+    assert_eq!(instructions.len(), 4);
+    for pool_data in [&first, &second] {
+        assert!(keys.contains(&pool_data.mint));
+    }
+    // 两个代币各 600000，扰动只会向下
+    let limit = compute_unit_limit(&instructions[0].data);
+    assert!(limit > 1_199_000 && limit <= 1_200_000, "{}", limit);
+
+    // 三个代币的计算单元限制不超过单笔交易上限，需要查找表才能放下
+    let third = raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0;
+    let mut addresses = Vec::new();
+    for pool_data in [&first, &second, &third] {
+        addresses.extend(
+            uncovered_accounts(&wallet, &config, &programs(&config), pool_data, &[]).unwrap(),
+        );
+    }
+    let lookup_table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses,
+    };
+    let tx = build_batch_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &[&first, &second, &third],
+        Hash::default(),
+        &[lookup_table],
+    )
+    .unwrap();
+    let limit = compute_unit_limit(&tx.message.instructions()[0].data);
+    assert!(limit <= MAX_COMPUTE_UNIT_LIMIT && limit > MAX_COMPUTE_UNIT_LIMIT - 1000);
+
+    // 池子太多时不裁剪，直接报错
+    let crowded = raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 12).0;
+    assert!(build_batch_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &[&first, &crowded],
+        Hash::default(),
+        &[]
+    )
+    .is_err());
+
+    // 两个代币共用一个池子时不能放进同一笔交易
+    let mut shared = raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 1).0;
+    shared.raydium_pools.push(first.raydium_pools[0].clone());
+    let error = build_batch_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &[&first, &shared],
+        Hash::default(),
+        &[],
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains(&first.raydium_pools[0].pool.to_string()),
+        "{}",
+        error
+    );
+}
+
+/// 解析 `SetComputeUnitLimit` 指令中的计算单元数
+fn compute_unit_limit(data: &[u8]) -> u32 {
+    let expected = ComputeBudgetInstruction::set_compute_unit_limit(0).data;
+    assert_eq!(data[0], expected[0]);
+    u32::from_le_bytes(data[1..5].try_into().unwrap())
+}
+
+#[test]
+fn batch_config_defaults_and_validation() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "[batch]\nenabled = true").unwrap();
+    let batch = config.batch.unwrap();
+    assert_eq!(batch.max_mints(), 4);
+    assert_eq!(batch.window(), Duration::from_millis(20));
+
+    let error = parse_config_with_wallet(&wallet, "[batch]\nenabled = true\nmax_mints = 0")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("batch.max_mints"), "{}", error);
+
+    let error = parse_config_with_wallet(
+        &wallet,
+        "[batch]\nenabled = true\n\n[flashloan]\nenabled = true\nprovider = \"solend\"\nmax_borrow = 1.0",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("batch.enabled"), "{}", error);
+}
@@ -0,0 +1,128 @@
+//! 池子黑名单测试：mint authority 解析、池子地址和代币创建者匹配、配置校验
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::blacklist::{
+    mint_authority, Blacklist, BlacklistEntries, Blocked,
+};
+use solana_onchain_arbitrage_bot::dex::pump::constants::pump_program_id;
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, PumpPool};
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+/// SPL Token mint 账户数据，只填写 mint authority
+fn mint_data(authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = vec![0u8; 82];
+    if let Some(authority) = authority {
+        data[..4].copy_from_slice(&1u32.to_le_bytes());
+        data[4..36].copy_from_slice(authority.as_ref());
+    }
+    data
+}
+
+fn pump_pool(pool: Pubkey, creator: Pubkey) -> PumpPool {
+    let (coin_creator_vault_authority, _) =
+        Pubkey::find_program_address(&[b"creator_vault", creator.as_ref()], pump_program_id());
+    PumpPool {
+        pool,
+        token_vault: Pubkey::new_unique(),
+        sol_vault: Pubkey::new_unique(),
+        fee_recipient: Pubkey::new_unique(),
+        fee_token_wallet: Pubkey::new_unique(),
+        coin_creator_vault_ata: Pubkey::new_unique(),
+        coin_creator_vault_authority,
+    }
+}
+
+fn entries(pools: &[Pubkey], mint_authorities: &[Pubkey], creators: &[Pubkey]) -> BlacklistEntries {
+    let strings = |keys: &[Pubkey]| keys.iter().map(Pubkey::to_string).collect();
+    BlacklistEntries {
+        pools: strings(pools),
+        mint_authorities: strings(mint_authorities),
+        creators: strings(creators),
+    }
+}
+
+#[test]
+fn mint_authority_reads_the_coption() {
+    let authority = Pubkey::new_unique();
+    assert_eq!(mint_authority(&mint_data(Some(authority))), Some(authority));
+    // 权限已撤销
+    assert_eq!(mint_authority(&mint_data(None)), None);
+    assert_eq!(mint_authority(&[1, 0, 0, 0, 7]), None);
+}
+
+#[test]
+fn mint_authority_entries_block_the_mint() {
+    let bad = Pubkey::new_unique();
+    let blacklist = Blacklist::new(&[&entries(&[], &[bad], &[])]);
+    assert!(blacklist.has_mint_authorities());
+    assert_eq!(
+        blacklist.check_mint(&mint_data(Some(bad))),
+        Some(Blocked::MintAuthority(bad))
+    );
+    assert_eq!(
+        blacklist.check_mint(&mint_data(Some(Pubkey::new_unique()))),
+        None
+    );
+    assert_eq!(blacklist.check_mint(&mint_data(None)), None);
+}
+
+#[test]
+fn pools_are_matched_by_address_and_creator() {
+    let wallet = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(Pubkey::new_unique(), wallet, spl_token::ID);
+    let honeypot = Pubkey::new_unique();
+    let scammer = Pubkey::new_unique();
+    let scam_pool = Pubkey::new_unique();
+    let good_pool = Pubkey::new_unique();
+    pool_data
+        .pump_pools
+        .push(pump_pool(honeypot, Pubkey::new_unique()));
+    pool_data.pump_pools.push(pump_pool(scam_pool, scammer));
+    pool_data
+        .pump_pools
+        .push(pump_pool(good_pool, Pubkey::new_unique()));
+
+    let config_entries = entries(&[honeypot], &[], &[]);
+    let remote_entries = entries(&[], &[], &[scammer]);
+    let blacklist = Blacklist::new(&[&config_entries, &remote_entries]);
+    assert_eq!(blacklist.len(), 2);
+    assert_eq!(
+        blacklist.blocked_pools(&pool_data),
+        vec![
+            ("pump", honeypot, Blocked::Pool),
+            ("pump", scam_pool, Blocked::Creator(scammer)),
+        ]
+    );
+    assert!(Blacklist::default().blocked_pools(&pool_data).is_empty());
+}
+
+#[test]
+fn invalid_entries_are_skipped() {
+    let entries = BlacklistEntries {
+        pools: vec!["not-a-pubkey".to_string(), Pubkey::new_unique().to_string()],
+        ..Default::default()
+    };
+    assert_eq!(Blacklist::new(&[&entries]).len(), 1);
+}
+
+#[test]
+fn blacklist_config_defaults_and_validation() {
+    let pool = Pubkey::new_unique();
+    let config = parse_config(&format!("[blacklist]\npools = [\"{}\"]", pool)).unwrap();
+    let blacklist = config.blacklist.unwrap();
+    assert_eq!(blacklist.entries.pools, vec![pool.to_string()]);
+    assert!(blacklist.entries.creators.is_empty());
+    assert_eq!(blacklist.refresh_interval(), Some(Duration::from_secs(300)));
+
+    let config = parse_config("[blacklist]\nrefresh_secs = 0").unwrap();
+    assert_eq!(config.blacklist.unwrap().refresh_interval(), None);
+
+    let error = parse_config("[blacklist]\ncreators = [\"bad\"]\nurl = \"\"")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("blacklist.creators[0]"), "{}", error);
+    assert!(error.contains("blacklist.url"), "{}", error);
+}
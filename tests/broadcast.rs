@@ -0,0 +1,61 @@
+//! 广播记录测试：相同的消息复用签名、重发只记录一次，以及换 blockhash 后清空
+
+use solana_onchain_arbitrage_bot::broadcast::BroadcastLog;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+
+fn message(wallet: &Keypair, lamports: u64, blockhash: Hash) -> VersionedMessage {
+    let instruction =
+        system_instruction::transfer(&wallet.pubkey(), &Pubkey::new_unique(), lamports);
+    VersionedMessage::V0(
+        v0::Message::try_compile(&wallet.pubkey(), &[instruction], &[], blockhash).unwrap(),
+    )
+}
+
+#[test]
+fn identical_messages_reuse_the_signature() {
+    let wallet = Keypair::new();
+    let blockhash = Hash::new_unique();
+    let mut broadcasts = BroadcastLog::new();
+
+    let message = message(&wallet, 1_000, blockhash);
+    let first = broadcasts.sign(&wallet, message.clone()).unwrap();
+    let again = broadcasts.sign(&wallet, message.clone()).unwrap();
+    assert_eq!(first.signatures, again.signatures);
+    assert!(again.verify_with_results().iter().all(|ok| *ok));
+
+    let mut other = message;
+    if let VersionedMessage::V0(message) = &mut other {
+        message.instructions[0].data[4] ^= 1;
+    }
+    let other = broadcasts.sign(&wallet, other).unwrap();
+    assert_ne!(other.signatures, first.signatures);
+    assert!(other.verify_with_results().iter().all(|ok| *ok));
+}
+
+#[test]
+fn resends_are_recorded_once_per_blockhash() {
+    let wallet = Keypair::new();
+    let blockhash = Hash::new_unique();
+    let mut broadcasts = BroadcastLog::new();
+    let tx = broadcasts
+        .sign(&wallet, message(&wallet, 1_000, blockhash))
+        .unwrap();
+
+    assert!(broadcasts.is_empty());
+    assert!(broadcasts.record(blockhash, tx.signatures[0]));
+    assert!(!broadcasts.record(blockhash, tx.signatures[0]));
+    assert_eq!(broadcasts.len(), 1);
+
+    // 换了 blockhash 后旧窗口的记录被清空
+    let next = Hash::new_unique();
+    let tx = broadcasts
+        .sign(&wallet, message(&wallet, 1_000, next))
+        .unwrap();
+    assert!(broadcasts.is_empty());
+    assert!(broadcasts.record(next, tx.signatures[0]));
+}
@@ -0,0 +1,203 @@
+//! 以 USDC / USDT 为报价货币的代币测试
+
+mod common;
+
+use common::{load_fixture, programs, TestConfig};
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::{cash_mint, sol_mint, usdc_mint, usdt_mint};
+use solana_onchain_arbitrage_bot::dex::solfi;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::TradeSizing;
+use solana_onchain_arbitrage_bot::transaction::build_transaction;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use spl_associated_token_account::get_associated_token_address;
+use std::str::FromStr;
+
+const BONK_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+fn parse_config(wallet: &Keypair, mint: &str, mint_options: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        wallet: Some(wallet),
+        mint: Some(mint),
+        mint_options,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn quote_mints_are_looked_up_by_symbol_or_address() {
+    assert_eq!(cash_mint("usdc").unwrap().mint, *usdc_mint());
+    assert_eq!(cash_mint(&usdt_mint().to_string()).unwrap().symbol, "USDT");
+    assert!(cash_mint("SOL").unwrap().is_sol());
+    assert_eq!(cash_mint(BONK_MINT), None);
+
+    let usdc = cash_mint("USDC").unwrap();
+    assert_eq!(usdc.to_base_units(1.5), 1_500_000);
+    assert_eq!(usdc.from_base_units(-250_000), -0.25);
+}
+
+#[test]
+fn trade_sizes_are_in_the_quote_mint() {
+    let wallet = Keypair::new();
+    let config = parse_config(
+        &wallet,
+        BONK_MINT,
+        "quote_mint = \"USDC\"\ntrade_sizes = [10.0, 2.5]",
+    )
+    .unwrap();
+    let mint_config = &config.routing.mint_config_list[0];
+    assert_eq!(mint_config.quote_mint().unwrap().mint, *usdc_mint());
+    assert_eq!(
+        mint_config.trade_sizing(),
+        Some(TradeSizing::Fixed(vec![10_000_000, 2_500_000]))
+    );
+
+    let config = TestConfig {
+        mint: Some(BONK_MINT),
+        mint_options: "max_trade_size = 2.0",
+        extra: "[research]\nenabled = true",
+        ..Default::default()
+    }
+    .parse()
+    .unwrap();
+    let mint_config = &config.routing.mint_config_list[0];
+    assert!(mint_config.quote_mint().unwrap().is_sol());
+    assert_eq!(
+        mint_config.trade_sizing(),
+        Some(TradeSizing::Optimal {
+            max_input: 2_000_000_000
+        })
+    );
+}
+
+#[test]
+fn quote_mint_validation() {
+    let wallet = Keypair::new();
+    let error =
+        |mint: &str, extra: &str| parse_config(&wallet, mint, extra).unwrap_err().to_string();
+
+    let message = error(BONK_MINT, "quote_mint = \"EUR\"");
+    assert!(message.contains("quote_mint"), "{}", message);
+    assert!(message.contains("SOL, USDC, USDT"), "{}", message);
+
+    let message = error(&usdc_mint().to_string(), "quote_mint = \"USDC\"");
+    assert!(
+        message.contains("cannot be quoted in itself"),
+        "{}",
+        message
+    );
+
+    let message = error(
+        BONK_MINT,
+        "quote_mint = \"USDT\"\n\n[flashloan]\nenabled = true\nprovider = \"solend\"\nmax_borrow = 1.0",
+    );
+    assert!(
+        message.contains("the Solend flashloan only lends SOL"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn loaders_put_the_quote_mint_vault_on_the_sol_side() {
+    // SOL/USDC 的 Solfi 池子，套利 SOL 并以 USDC 计价
+    let (pool, account) = load_fixture("solfi");
+    let mut accounts = std::collections::HashMap::from([(pool, account)]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+    let mut pool_data = MintPoolData::new(*sol_mint(), Pubkey::new_unique(), spl_token::ID)
+        .with_quote_mint(*usdc_mint());
+    solfi::loader::load_pool(&pool, &mut get_account, &mut pool_data).unwrap();
+
+    let pool = &pool_data.solfi_pools[0];
+    assert_eq!(
+        pool.token_sol_vault,
+        Pubkey::from_str("7pW88ZRMyRDUtHYPFWJ7ritHrpqPncoSbi71Cuuqfsv7").unwrap()
+    );
+    assert_eq!(
+        pool.token_x_vault,
+        Pubkey::from_str("FikXxHwCW9CzzSC5G4Muo3h2wJUabPN6RgJWrNda5rac").unwrap()
+    );
+}
+
+#[test]
+fn solfi_pools_for_another_pair_are_rejected() {
+    // SOL/USDC 的 Solfi 池子不能用于以 SOL 计价的其他代币
+    let (pool, account) = load_fixture("solfi");
+    let mut accounts = std::collections::HashMap::from([(pool, account)]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+    let mut pool_data = MintPoolData::new(
+        Pubkey::from_str(BONK_MINT).unwrap(),
+        Pubkey::new_unique(),
+        spl_token::ID,
+    );
+    let error = solfi::loader::load_pool(&pool, &mut get_account, &mut pool_data)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("trades"), "{}", error);
+    assert!(pool_data.solfi_pools.is_empty());
+}
+
+#[test]
+fn swap_instruction_trades_through_the_wallet_quote_account() {
+    let wallet = Keypair::new();
+    let config = parse_config(&wallet, BONK_MINT, "quote_mint = \"USDC\"").unwrap();
+    let pool_data = |quote_mint: Pubkey| {
+        let mut pool_data = MintPoolData::new(
+            Pubkey::from_str(BONK_MINT).unwrap(),
+            wallet.pubkey(),
+            spl_token::ID,
+        )
+        .with_quote_mint(quote_mint);
+        for _ in 0..2 {
+            pool_data.add_raydium_pool(
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+            );
+        }
+        pool_data
+    };
+
+    let usdc_pool_data = pool_data(*usdc_mint());
+    let usdc_account = get_associated_token_address(&wallet.pubkey(), usdc_mint());
+    assert_eq!(usdc_pool_data.wallet_usdc_account, usdc_account);
+    assert_eq!(usdc_pool_data.wallet_quote_account(), usdc_account);
+    let sol_pool_data = pool_data(*sol_mint());
+    assert_eq!(
+        sol_pool_data.wallet_quote_account(),
+        sol_pool_data.wallet_wsol_account
+    );
+    let usdt_pool_data = pool_data(*usdt_mint());
+    assert_eq!(
+        usdt_pool_data.wallet_quote_account(),
+        get_associated_token_address(&wallet.pubkey(), usdt_mint())
+    );
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &usdc_pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let keys = tx.message.static_account_keys();
+    let swap = tx.message.instructions().last().unwrap();
+    // 1. 报价货币的 mint，3. 钱包的报价货币账户
+    assert_eq!(keys[swap.accounts[1] as usize], *usdc_mint());
+    assert_eq!(keys[swap.accounts[3] as usize], usdc_account);
+    assert!(!keys.contains(&sol_pool_data.wallet_wsol_account));
+}
@@ -0,0 +1,166 @@
+//! 集成测试共用的配置、池子数据和账户 fixture
+//!
+//! 每个测试文件只用到其中一部分
+#![allow(dead_code)]
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use solana_onchain_arbitrage_bot::config::{Config, ConfigFormat};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::transaction::SwapPrograms;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use std::str::FromStr;
+
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// 测试配置：默认只有一个 SOL 代币、本地 RPC 和随机钱包，各字段追加到对应的 section
+#[derive(Default)]
+pub struct TestConfig<'a> {
+    /// 钱包，为 `None` 时随机生成
+    pub wallet: Option<&'a Keypair>,
+    /// `[bot]` 中追加的字段
+    pub bot: &'a str,
+    /// `[routing]` 中、代币列表之前的字段
+    pub routing: &'a str,
+    /// 替换默认的代币列表
+    pub mints: Option<&'a str>,
+    /// 默认代币，为 `None` 时使用 SOL
+    pub mint: Option<&'a str>,
+    /// 默认代币配置中追加的字段
+    pub mint_options: &'a str,
+    /// RPC 地址，为 `None` 时使用本地节点
+    pub rpc_url: Option<&'a str>,
+    /// 追加到配置末尾的 section
+    pub extra: &'a str,
+}
+
+impl TestConfig<'_> {
+    pub fn parse(&self) -> anyhow::Result<Config> {
+        let wallet = match self.wallet {
+            Some(wallet) => wallet.to_base58_string(),
+            None => Keypair::new().to_base58_string(),
+        };
+        let mints = match self.mints {
+            Some(mints) => mints.to_string(),
+            None => format!(
+                "[[routing.mint_config_list]]\nmint = \"{}\"\nprocess_delay = 1000\n{}",
+                self.mint.unwrap_or(SOL_MINT),
+                self.mint_options
+            ),
+        };
+        Config::parse(
+            &format!(
+                r#"
+[bot]
+compute_unit_limit = 600000
+{}
+
+[routing]
+{}
+{mints}
+
+[rpc]
+url = "{}"
+
+[wallet]
+private_key = "{wallet}"
+
+{}
+"#,
+                self.bot,
+                self.routing,
+                self.rpc_url.unwrap_or("http://127.0.0.1:8899"),
+                self.extra
+            ),
+            ConfigFormat::Toml,
+        )
+    }
+}
+
+/// 默认配置加上 `extra` 中的 section
+pub fn parse_config(extra: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        extra,
+        ..Default::default()
+    }
+    .parse()
+}
+
+/// 同 `parse_config`，但使用给定的钱包
+pub fn parse_config_with_wallet(wallet: &Keypair, extra: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        wallet: Some(wallet),
+        extra,
+        ..Default::default()
+    }
+    .parse()
+}
+
+/// 按配置解析的执行程序和外部闪电贷，构建交易时使用
+pub fn programs(config: &Config) -> SwapPrograms {
+    SwapPrograms::from_config(config).unwrap()
+}
+
+/// 带有 `count` 个 Raydium 池子的池数据，第 i 个池子的报价偏离为 i，返回池数据和池子地址
+pub fn raydium_pool_data(
+    mint: Pubkey,
+    wallet: Pubkey,
+    count: usize,
+) -> (MintPoolData, Vec<Pubkey>) {
+    let mut pool_data = MintPoolData::new(mint, wallet, spl_token::ID);
+    let mut pools = Vec::new();
+    for i in 0..count {
+        let pool = Pubkey::new_unique();
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+        pool_data.pool_edges.insert(pool, i as f64);
+        pools.push(pool);
+    }
+    (pool_data, pools)
+}
+
+fn read_fixture(name: &str) -> serde_json::Value {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    serde_json::from_str(&contents).unwrap()
+}
+
+/// 读取 `tests/fixtures/<name>.json` 中的账户
+pub fn load_fixture(name: &str) -> (Pubkey, Account) {
+    let json = read_fixture(name);
+    let account = &json["account"];
+    (
+        Pubkey::from_str(json["pubkey"].as_str().unwrap()).unwrap(),
+        Account {
+            lamports: account["lamports"].as_u64().unwrap_or_default(),
+            data: BASE64_STANDARD
+                .decode(account["data"][0].as_str().unwrap())
+                .unwrap(),
+            owner: Pubkey::from_str(account["owner"].as_str().unwrap()).unwrap(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+}
+
+/// 只读取 fixture 账户的数据
+pub fn fixture_data(name: &str) -> Vec<u8> {
+    load_fixture(name).1.data
+}
+
+/// 一个 SPL 代币账户，只填写 mint 和余额
+pub fn token_account(mint: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    Account {
+        data,
+        owner: spl_token::ID,
+        ..Account::default()
+    }
+}
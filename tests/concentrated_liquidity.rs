@@ -0,0 +1,276 @@
+//! 集中流动性 sqrt price 数学和按 tick 遍历的兑换模拟测试
+
+use solana_onchain_arbitrage_bot::concentrated_liquidity::{
+    compute_swap_step, delta_amount_0, delta_amount_1, next_sqrt_price_from_input,
+    sqrt_price_at_tick, swap_exact_in, whirlpool_delta_amount_0, whirlpool_sqrt_price_at_tick,
+    InitializedTick, PoolPrice, TickMath, MAX_SQRT_PRICE_X64, MAX_TICK, MIN_SQRT_PRICE_X64,
+    MIN_TICK, WHIRLPOOL_MAX_SQRT_PRICE_X64,
+};
+
+const ONE: u128 = 1 << 64;
+
+#[test]
+fn sqrt_price_at_tick_matches_the_tick_range() {
+    assert_eq!(sqrt_price_at_tick(0), Some(ONE));
+    assert_eq!(sqrt_price_at_tick(MIN_TICK), Some(MIN_SQRT_PRICE_X64));
+    assert_eq!(sqrt_price_at_tick(MAX_TICK), Some(MAX_SQRT_PRICE_X64));
+    assert_eq!(sqrt_price_at_tick(MAX_TICK + 1), None);
+    assert_eq!(sqrt_price_at_tick(MIN_TICK - 1), None);
+
+    for tick in [-50_000, -100, -1, 1, 100, 50_000] {
+        let expected = 1.0001f64.powf(tick as f64 / 2.0) * ONE as f64;
+        let actual = sqrt_price_at_tick(tick).unwrap() as f64;
+        assert!((actual / expected - 1.0).abs() < 1e-12, "{}", tick);
+        assert!(sqrt_price_at_tick(tick).unwrap() < sqrt_price_at_tick(tick + 1).unwrap());
+    }
+}
+
+#[test]
+fn whirlpool_tick_math_matches_the_on_chain_program() {
+    assert_eq!(whirlpool_sqrt_price_at_tick(0), Some(ONE));
+    assert_eq!(
+        whirlpool_sqrt_price_at_tick(MIN_TICK),
+        Some(MIN_SQRT_PRICE_X64)
+    );
+    assert_eq!(
+        whirlpool_sqrt_price_at_tick(MAX_TICK),
+        Some(WHIRLPOOL_MAX_SQRT_PRICE_X64)
+    );
+    assert_eq!(whirlpool_sqrt_price_at_tick(MAX_TICK + 1), None);
+    assert_eq!(whirlpool_sqrt_price_at_tick(MIN_TICK - 1), None);
+    // 链上 sqrt_price_from_tick_index 的结果
+    for (tick, expected) in [
+        (1, 18_447_666_387_855_959_850),
+        (-1, 18_445_821_805_675_392_311),
+        (18_200, 45_825_576_997_710_299_321),
+        (-18_200, 7_425_599_178_771_734_021),
+        (100_000, 2_737_055_259_406_582_257_880),
+    ] {
+        assert_eq!(
+            whirlpool_sqrt_price_at_tick(tick),
+            Some(expected),
+            "{}",
+            tick
+        );
+    }
+    assert_eq!(
+        TickMath::Whirlpool.sqrt_price_at_tick(-18_200),
+        whirlpool_sqrt_price_at_tick(-18_200)
+    );
+
+    // token A 数量只做一次除法，与 Raydium 分两次除的结果最多差 1
+    let lower = whirlpool_sqrt_price_at_tick(-10).unwrap();
+    let upper = whirlpool_sqrt_price_at_tick(10).unwrap();
+    let liquidity = 1_000_000_000_000;
+    assert_eq!(
+        whirlpool_delta_amount_0(lower, upper, liquidity, false),
+        Some(999_950_044)
+    );
+    assert_eq!(
+        whirlpool_delta_amount_0(upper, lower, liquidity, true),
+        Some(999_950_045)
+    );
+    let raydium = delta_amount_0(lower, upper, liquidity, false).unwrap();
+    assert!(raydium.abs_diff(999_950_044) <= 1);
+}
+
+#[test]
+fn delta_amounts_round_in_the_pools_favor() {
+    let lower = sqrt_price_at_tick(-10).unwrap();
+    let upper = sqrt_price_at_tick(10).unwrap();
+    let liquidity = 1_000_000_000_000;
+
+    let amount_0 = delta_amount_0(lower, upper, liquidity, false).unwrap();
+    let amount_1 = delta_amount_1(lower, upper, liquidity, false).unwrap();
+    // 价格在 1 附近，20 个 tick 的 sqrt price 相差约 0.1%
+    assert!((999_000_000..1_001_000_000).contains(&amount_0));
+    assert!((999_000_000..1_001_000_000).contains(&amount_1));
+    assert_eq!(
+        delta_amount_0(lower, upper, liquidity, true),
+        Some(amount_0 + 1)
+    );
+    assert_eq!(
+        delta_amount_1(lower, upper, liquidity, true),
+        Some(amount_1 + 1)
+    );
+    assert_eq!(
+        delta_amount_0(upper, lower, liquidity, false),
+        Some(amount_0)
+    );
+
+    // 超过 u64 的数量无法表示
+    assert_eq!(
+        delta_amount_1(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64, u128::MAX, false),
+        None
+    );
+
+    // 输入 token 0 价格下降，输入 token 1 价格上升
+    let down = next_sqrt_price_from_input(ONE, liquidity, 1_000_000, true).unwrap();
+    let up = next_sqrt_price_from_input(ONE, liquidity, 1_000_000, false).unwrap();
+    assert!(down < ONE && up > ONE);
+    assert_eq!(
+        next_sqrt_price_from_input(ONE, liquidity, 0, true),
+        Some(ONE)
+    );
+}
+
+#[test]
+fn swap_step_takes_the_fee_from_the_input() {
+    let liquidity = 1_000_000_000_000;
+    let target = sqrt_price_at_tick(-100).unwrap();
+
+    // 没有到达目标价格：剩余输入全部用完，手续费是输入减去实际兑换的部分
+    let step = compute_swap_step(
+        TickMath::RaydiumClmm,
+        ONE,
+        target,
+        liquidity,
+        1_000_000,
+        2_500,
+    )
+    .unwrap();
+    assert!(step.sqrt_price_next > target);
+    assert_eq!(step.amount_in + step.fee, 1_000_000);
+    assert_eq!(step.fee, 2_500);
+    // 价格约为 1，扣除 0.25% 手续费后换出约 997500 减去价格冲击
+    assert!((997_400..997_500).contains(&step.amount_out));
+
+    // 输入足够时停在目标价格，手续费按实际输入反推
+    let step = compute_swap_step(
+        TickMath::RaydiumClmm,
+        ONE,
+        target,
+        liquidity,
+        u64::MAX / 2,
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(step.sqrt_price_next, target);
+    assert_eq!(
+        step.amount_in,
+        delta_amount_0(target, ONE, liquidity, true).unwrap()
+    );
+    assert_eq!(
+        step.fee,
+        (step.amount_in as u128 * 2_500).div_ceil(997_500) as u64
+    );
+}
+
+#[test]
+fn swap_crosses_initialized_ticks_and_stops_at_the_boundary() {
+    let (wide, narrow) = (2_000_000_000_000, 1_000_000_000_000);
+    // 两个仓位：[-200, 200] 和 [-50, 50]
+    let ticks = [
+        InitializedTick {
+            tick: 50,
+            liquidity_net: -(narrow as i128),
+        },
+        InitializedTick {
+            tick: -200,
+            liquidity_net: wide as i128,
+        },
+        InitializedTick {
+            tick: -50,
+            liquidity_net: narrow as i128,
+        },
+        InitializedTick {
+            tick: 200,
+            liquidity_net: -(wide as i128),
+        },
+    ];
+    let pool = PoolPrice {
+        sqrt_price_x64: ONE,
+        tick_current: 0,
+        liquidity: wide + narrow,
+    };
+
+    let amount_in = 12_000_000_000;
+    let first = compute_swap_step(
+        TickMath::RaydiumClmm,
+        ONE,
+        sqrt_price_at_tick(-50).unwrap(),
+        wide + narrow,
+        amount_in,
+        2_500,
+    )
+    .unwrap();
+    let second = compute_swap_step(
+        TickMath::RaydiumClmm,
+        first.sqrt_price_next,
+        sqrt_price_at_tick(-200).unwrap(),
+        wide,
+        amount_in - first.amount_in - first.fee,
+        2_500,
+    )
+    .unwrap();
+    let result = swap_exact_in(
+        TickMath::RaydiumClmm,
+        pool,
+        &ticks,
+        MIN_TICK,
+        amount_in,
+        true,
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(result.amount_out, first.amount_out + second.amount_out);
+    assert_eq!(result.fee, first.fee + second.fee);
+    assert_eq!(result.ticks_crossed, 1);
+    assert_eq!(result.last_tick, -200);
+
+    // 卖出方向相同：穿过 50 后只剩宽仓位
+    let result = swap_exact_in(
+        TickMath::RaydiumClmm,
+        pool,
+        &ticks,
+        MAX_TICK,
+        amount_in,
+        false,
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(result.ticks_crossed, 1);
+    assert_eq!(result.last_tick, 200);
+
+    // 穿过所有 tick 后流动性为 0，到达边界仍有剩余输入
+    assert_eq!(
+        swap_exact_in(
+            TickMath::RaydiumClmm,
+            pool,
+            &ticks,
+            MIN_TICK,
+            u64::MAX / 2,
+            true,
+            2_500
+        ),
+        None
+    );
+    // 边界之后的 tick 没有加载
+    assert_eq!(
+        swap_exact_in(
+            TickMath::RaydiumClmm,
+            pool,
+            &ticks,
+            -60,
+            amount_in,
+            true,
+            2_500
+        ),
+        None
+    );
+    assert!(swap_exact_in(
+        TickMath::RaydiumClmm,
+        pool,
+        &ticks,
+        -60,
+        1_000_000,
+        true,
+        2_500
+    )
+    .is_some());
+    // 边界在兑换方向的反方向
+    assert_eq!(
+        swap_exact_in(TickMath::RaydiumClmm, pool, &ticks, 10, 1_000, true, 2_500),
+        None
+    );
+}
@@ -0,0 +1,63 @@
+//! 配置中 `${VAR}` 环境变量替换测试
+
+use solana_onchain_arbitrage_bot::config::{Config, ConfigFormat};
+
+const CONFIG: &str = r#"
+[bot]
+compute_unit_limit = 400000
+
+[routing]
+[[routing.mint_config_list]]
+mint = "So11111111111111111111111111111111111111112"
+process_delay = 1000
+
+[rpc]
+url = "http://127.0.0.1:8899/?key=${CONFIG_ENV_TEST_KEY}&v=$${LITERAL}" # 例如 ${CONFIG_ENV_TEST_UNSET}
+
+[wallet]
+private_key = "key"
+"#;
+
+#[test]
+fn values_are_substituted_after_parsing() {
+    // 值中的引号、反斜杠和换行不能改变配置结构
+    std::env::set_var(
+        "CONFIG_ENV_TEST_KEY",
+        "a\"b\\c\n[wallet]\nprivate_key = \"x\"",
+    );
+    let config = Config::parse(CONFIG, ConfigFormat::Toml).unwrap();
+    assert_eq!(
+        config.rpc.url,
+        "http://127.0.0.1:8899/?key=a\"b\\c\n[wallet]\nprivate_key = \"x\"&v=${LITERAL}"
+    );
+    assert_eq!(config.wallet.private_key, "key");
+
+    let json = r#"{
+        "bot": {"compute_unit_limit": 400000},
+        "routing": {"mint_config_list": [
+            {"mint": "So11111111111111111111111111111111111111112", "process_delay": 1000}
+        ]},
+        "rpc": {"url": "${CONFIG_ENV_TEST_KEY}"},
+        "wallet": {"private_key": "key"}
+    }"#;
+    let config = Config::parse(json, ConfigFormat::Json).unwrap();
+    assert!(config.rpc.url.starts_with("a\"b\\c\n"));
+}
+
+#[test]
+fn unset_variables_are_reported_together() {
+    let contents = CONFIG
+        .replace("CONFIG_ENV_TEST_KEY", "CONFIG_ENV_TEST_MISSING_A")
+        .replace("$${LITERAL}", "${CONFIG_ENV_TEST_MISSING_B}");
+    let error = Config::parse(&contents, ConfigFormat::Toml)
+        .err()
+        .unwrap()
+        .to_string();
+    assert!(
+        error.ends_with("CONFIG_ENV_TEST_MISSING_A, CONFIG_ENV_TEST_MISSING_B"),
+        "{}",
+        error
+    );
+    // 行尾注释中的变量不需要设置
+    assert!(!error.contains("CONFIG_ENV_TEST_UNSET"), "{}", error);
+}
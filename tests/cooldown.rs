@@ -0,0 +1,28 @@
+//! 连续失败退避测试
+
+use solana_onchain_arbitrage_bot::cooldown::FailureCooldown;
+use std::time::Duration;
+
+#[test]
+fn delay_doubles_after_threshold_up_to_the_cap() {
+    let cooldown = FailureCooldown::new(3, Duration::from_millis(100), Duration::from_secs(1));
+
+    assert!(!cooldown.record_failure());
+    assert!(!cooldown.record_failure());
+    assert_eq!(cooldown.delay(), Duration::from_millis(100));
+
+    // 第三次失败进入退避，只返回一次 true
+    assert!(cooldown.record_failure());
+    assert_eq!(cooldown.delay(), Duration::from_millis(200));
+    assert!(!cooldown.record_failure());
+    assert_eq!(cooldown.delay(), Duration::from_millis(400));
+
+    for _ in 0..50 {
+        cooldown.record_failure();
+    }
+    assert_eq!(cooldown.delay(), Duration::from_secs(1));
+
+    cooldown.record_success();
+    assert_eq!(cooldown.failures(), 0);
+    assert_eq!(cooldown.delay(), Duration::from_millis(100));
+}
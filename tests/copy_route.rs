@@ -0,0 +1,245 @@
+//! 跟单路线识别和报价测试
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::copy_route::{
+    include_routes, quote_routes, CopiedRoute, CopyRouteBook,
+};
+use solana_onchain_arbitrage_bot::dex::raydium::raydium_program_id;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::PoolReserves;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::{v0, MessageHeader, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use solana_transaction_status::UiTransactionStatusMeta;
+use std::collections::HashMap;
+use std::time::Duration;
+
+struct Pool {
+    pool: Pubkey,
+    token_vault: Pubkey,
+    sol_vault: Pubkey,
+}
+
+impl Pool {
+    fn new() -> Self {
+        Self {
+            pool: Pubkey::new_unique(),
+            token_vault: Pubkey::new_unique(),
+            sol_vault: Pubkey::new_unique(),
+        }
+    }
+}
+
+fn pool_data(pools: &[&Pool]) -> MintPoolData {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    for pool in pools {
+        pool_data.add_raydium_pool(pool.pool, pool.token_vault, pool.sol_vault);
+    }
+    pool_data
+}
+
+fn wsol_balance(account_index: u8, amount: u64) -> serde_json::Value {
+    serde_json::json!({
+        "accountIndex": account_index,
+        "mint": sol_mint().to_string(),
+        "uiTokenAmount": {
+            "uiAmount": null,
+            "decimals": 9,
+            "amount": amount.to_string(),
+            "uiAmountString": "0",
+        },
+    })
+}
+
+/// 另一个机器人的套利交易：外层指令调用自己的程序并传入三个池子，
+/// 内层指令先在 B 用 SOL 买入，再在 A 卖出；池子 C 只传入没有调用
+fn arbitrage_transaction(
+    a: &Pool,
+    b: &Pool,
+    c: &Pool,
+) -> (VersionedTransaction, UiTransactionStatusMeta) {
+    let signer = Pubkey::new_unique();
+    let arbitrage_program = Pubkey::new_unique();
+    // 0 付款人, 1 套利程序, 2 Raydium, 3 A, 4 B, 5 C, 6 A 的 SOL vault；7 B 的 SOL vault 由查找表加载
+    let account_keys = vec![
+        signer,
+        arbitrage_program,
+        *raydium_program_id(),
+        a.pool,
+        b.pool,
+        c.pool,
+        a.sol_vault,
+    ];
+    let message = v0::Message {
+        header: MessageHeader {
+            num_required_signatures: 1,
+            num_readonly_signed_accounts: 0,
+            num_readonly_unsigned_accounts: 2,
+        },
+        account_keys,
+        recent_blockhash: Hash::default(),
+        instructions: vec![CompiledInstruction::new_from_raw_parts(
+            1,
+            vec![],
+            vec![3, 4, 5, 6, 7],
+        )],
+        address_table_lookups: vec![],
+    };
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::new_unique()],
+        message: VersionedMessage::V0(message),
+    };
+
+    let meta = serde_json::from_value(serde_json::json!({
+        "err": null,
+        "status": { "Ok": null },
+        "fee": 5000,
+        "preBalances": [],
+        "postBalances": [],
+        "innerInstructions": [{
+            "index": 0,
+            "instructions": [
+                { "programIdIndex": 2, "accounts": [4, 7], "data": "", "stackHeight": 2 },
+                { "programIdIndex": 2, "accounts": [3, 6], "data": "", "stackHeight": 2 },
+            ],
+        }],
+        "preTokenBalances": [wsol_balance(6, 2_000_000), wsol_balance(7, 1_000_000)],
+        "postTokenBalances": [wsol_balance(6, 1_740_000), wsol_balance(7, 1_250_000)],
+        "loadedAddresses": { "writable": [b.sol_vault.to_string()], "readonly": [] },
+    }))
+    .unwrap();
+    (tx, meta)
+}
+
+#[test]
+fn routes_are_read_from_dex_calls_in_execution_order() {
+    let (a, b, c) = (Pool::new(), Pool::new(), Pool::new());
+    let pool_data = pool_data(&[&a, &b, &c]);
+    let book = CopyRouteBook::new(Duration::from_secs(30), 4);
+    book.register(&pool_data);
+
+    let (tx, mut meta) = arbitrage_transaction(&a, &b, &c);
+    let routes = book.detect(&tx, &meta, 42);
+    assert_eq!(routes.len(), 1);
+    let route = &routes[0];
+    assert_eq!(route.mint, pool_data.mint);
+    assert_eq!(route.pools, vec![b.pool, a.pool]);
+    // 投入 = 第一个池子 SOL vault 增加的 WSOL
+    assert_eq!(route.size, Some(250_000));
+    assert_eq!(route.signer, tx.message.static_account_keys()[0]);
+    assert_eq!(route.signature, tx.signatures[0]);
+    assert_eq!(route.slot, 42);
+
+    // 没有登记的池子不识别
+    let other = CopyRouteBook::new(Duration::from_secs(30), 4);
+    assert!(other.detect(&tx, &meta, 42).is_empty());
+
+    meta.err = Some(TransactionError::AccountInUse);
+    assert!(book.detect(&tx, &meta, 42).is_empty());
+}
+
+fn route(mint: Pubkey, pools: Vec<Pubkey>, size: Option<u64>) -> CopiedRoute {
+    CopiedRoute {
+        mint,
+        pools,
+        size,
+        signer: Pubkey::new_unique(),
+        signature: Signature::new_unique(),
+        slot: 1,
+    }
+}
+
+#[test]
+fn book_keeps_the_newest_routes_until_they_expire() {
+    let mint = Pubkey::new_unique();
+    let pools: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+    let book = CopyRouteBook::new(Duration::from_secs(30), 2);
+
+    book.record(route(mint, vec![pools[0], pools[1]], Some(1)));
+    book.record(route(mint, vec![pools[2], pools[3]], Some(2)));
+    // 同一组池子替换旧记录
+    book.record(route(mint, vec![pools[0], pools[1]], Some(3)));
+    let sizes: Vec<_> = book.routes(&mint).iter().map(|r| r.size).collect();
+    assert_eq!(sizes, vec![Some(3), Some(2)]);
+
+    // 超过上限时丢掉最旧的
+    book.record(route(mint, vec![pools[1], pools[2]], Some(4)));
+    let sizes: Vec<_> = book.routes(&mint).iter().map(|r| r.size).collect();
+    assert_eq!(sizes, vec![Some(4), Some(3)]);
+    assert!(book.routes(&Pubkey::new_unique()).is_empty());
+
+    let expired = CopyRouteBook::new(Duration::ZERO, 2);
+    expired.record(route(mint, vec![pools[0], pools[1]], Some(1)));
+    assert!(expired.routes(&mint).is_empty());
+}
+
+#[test]
+fn copied_routes_are_quoted_at_the_copied_size() {
+    let (a, b, c) = (Pool::new(), Pool::new(), Pool::new());
+    let pool_data = pool_data(&[&a, &b, &c]);
+    let reserves = |token: u64| PoolReserves {
+        token,
+        sol: 1_000_000_000,
+        fee_bps: 25,
+    };
+    // B 的代币更便宜
+    let reserves: HashMap<Pubkey, PoolReserves> = [
+        (a.pool, reserves(1_000_000_000)),
+        (b.pool, reserves(1_100_000_000)),
+    ]
+    .into();
+    let mint = pool_data.mint;
+
+    let routes = vec![
+        route(mint, vec![a.pool, b.pool], Some(10_000_000)),
+        route(mint, vec![b.pool, a.pool], Some(10_000_000)),
+        // 没有投入规模或者池子没有报价
+        route(mint, vec![b.pool, a.pool], None),
+        route(mint, vec![b.pool, c.pool], Some(10_000_000)),
+    ];
+    let best = quote_routes(&pool_data, &reserves, &routes).unwrap();
+    assert_eq!((best.buy_pool, best.sell_pool), (b.pool, a.pool));
+    assert_eq!(best.size, 10_000_000);
+    assert!(best.profit > 0, "{:?}", best);
+    assert_eq!(quote_routes(&pool_data, &reserves, &routes[2..]), None);
+
+    // 选出的池子之外保留跟单路线经过的池子
+    let mut selected = pool_data.clone();
+    selected.retain_pools(|pool| *pool == c.pool);
+    let kept = include_routes(&pool_data, &selected, &routes[1..2]);
+    assert_eq!(kept.pool_addresses().len(), 3);
+}
+
+#[test]
+fn copy_route_config_defaults_and_validation() {
+    let config = |extra| {
+        TestConfig {
+            rpc_url: Some("https://rpc.example.com"),
+            extra,
+            ..Default::default()
+        }
+        .parse()
+    };
+
+    let parsed = config("[copy_route]\nenabled = true").unwrap();
+    let copy_route = parsed.copy_route.unwrap();
+    assert_eq!(
+        copy_route.websocket_url(&parsed.rpc.url),
+        "wss://rpc.example.com"
+    );
+    assert_eq!(copy_route.ttl(), Duration::from_secs(30));
+    assert_eq!(copy_route.max_routes(), 4);
+
+    let error = config("[copy_route]\nenabled = true\nttl_secs = 0\nmax_routes = 0")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("copy_route.ttl_secs"), "{}", error);
+    assert!(error.contains("copy_route.max_routes"), "{}", error);
+}
@@ -0,0 +1,58 @@
+//! `decode` 子命令的布局识别测试
+
+mod common;
+
+use common::load_fixture;
+use solana_onchain_arbitrage_bot::decode::decode_account;
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_sdk::pubkey::Pubkey;
+
+#[test]
+fn fixtures_are_decoded_with_the_layout_of_their_owner() {
+    for (fixture, layout) in [
+        ("raydium_amm_v4", "raydium (AMM v4 pool)"),
+        ("raydium_cpmm", "raydium_cp (pool)"),
+        ("raydium_clmm", "raydium_clmm (pool)"),
+        ("openbook_market", "OpenBook market"),
+        ("pump_amm", "pump (pool)"),
+        ("meteora_dlmm", "meteora_dlmm (lb pair)"),
+        ("meteora_damm_v2", "meteora_damm_v2 (pool)"),
+        ("whirlpool", "whirlpool (pool)"),
+        ("solfi", "solfi (pool)"),
+        ("vertigo", "vertigo (pool)"),
+        ("moonshot", "moonshot (curve)"),
+        ("perena", "perena (pool)"),
+        ("zerofi", "zerofi (pair)"),
+    ] {
+        let (pubkey, account) = load_fixture(fixture);
+        let decoded =
+            decode_account(&pubkey, &account).unwrap_or_else(|e| panic!("{}: {}", fixture, e));
+        assert_eq!(decoded.layout(), layout, "{}", fixture);
+        assert!(!decoded.to_string().is_empty());
+    }
+}
+
+#[test]
+fn accounts_with_another_discriminator_are_rejected() {
+    let (pubkey, mut account) = load_fixture("raydium_clmm");
+    // 同一个程序的 AmmConfig 账户
+    account.data[..8].copy_from_slice(&anchor_discriminator("AmmConfig"));
+    let error = decode_account(&pubkey, &account).err().unwrap().to_string();
+    assert!(error.contains("PoolState"), "{}", error);
+
+    account.data.truncate(4);
+    assert!(decode_account(&pubkey, &account).is_err());
+
+    let (pubkey, mut account) = load_fixture("pump_amm");
+    account.owner = Pubkey::new_unique();
+    let error = decode_account(&pubkey, &account).err().unwrap().to_string();
+    assert!(error.contains("not a supported DEX program"), "{}", error);
+}
+
+#[test]
+fn anchor_discriminator_matches_the_fixture_accounts() {
+    let (_, account) = load_fixture("whirlpool");
+    assert_eq!(account.data[..8], anchor_discriminator("Whirlpool"));
+    let (_, account) = load_fixture("meteora_dlmm");
+    assert_eq!(account.data[..8], anchor_discriminator("LbPair"));
+}
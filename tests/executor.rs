@@ -0,0 +1,459 @@
+//! 自定义链上执行程序测试：程序地址、手续费账户和 Swap 指令格式的覆盖
+
+mod common;
+
+use common::{parse_config_with_wallet, programs, raydium_pool_data};
+use solana_onchain_arbitrage_bot::dex::moonshot::moonshot_program_id;
+use solana_onchain_arbitrage_bot::dex::perena::perena_program_id;
+use solana_onchain_arbitrage_bot::dex::zerofi::zerofi_program_id;
+use solana_onchain_arbitrage_bot::executor::{
+    ExecutorProgram, Executors, VersionCheck, DEFAULT_EXECUTOR_PROGRAM_ID,
+    DEFAULT_SWAP_DISCRIMINATOR, FORK_ONLY_POOL_LISTS,
+};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::transaction::build_transaction;
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader_upgradeable::{self, UpgradeableLoaderState};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// 交易中调用的程序地址和 Swap 指令数据
+fn swap_instruction(tx: &VersionedTransaction) -> (Pubkey, Vec<u8>) {
+    let keys = tx.message.static_account_keys();
+    let swap = tx.message.instructions().last().unwrap();
+    (keys[swap.program_id_index as usize], swap.data.clone())
+}
+
+#[test]
+fn default_executor_keeps_the_public_program() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "").unwrap();
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+
+    let (program_id, data) = swap_instruction(&tx);
+    assert_eq!(
+        program_id,
+        Pubkey::from_str(DEFAULT_EXECUTOR_PROGRAM_ID).unwrap()
+    );
+    assert_eq!(data[0], DEFAULT_SWAP_DISCRIMINATOR);
+    // 标识 + minimum_profit + compute_unit_limit + no_failure_mode + 保留字段 + use_flashloan
+    assert_eq!(data.len(), 1 + 8 + 8 + 1 + 2 + 1);
+    assert_eq!(u64::from_le_bytes(data[9..17].try_into().unwrap()), 600000);
+}
+
+#[test]
+fn configured_executor_is_used_for_the_swap_instruction() {
+    let wallet = Keypair::new();
+    let (program_id, fee_collector) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let config = parse_config_with_wallet(
+        &wallet,
+        &format!(
+            "[executor]\nprogram_id = \"{}\"\nfee_collector = \"{}\"\ndiscriminator = 7\nlayout_version = 1",
+            program_id, fee_collector
+        ),
+    )
+    .unwrap();
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+
+    let (swap_program_id, data) = swap_instruction(&tx);
+    assert_eq!(swap_program_id, program_id);
+    assert_eq!(data[0], 7);
+    let keys = tx.message.static_account_keys();
+    assert!(keys.contains(&fee_collector));
+    assert!(!keys.contains(&Pubkey::from_str(DEFAULT_EXECUTOR_PROGRAM_ID).unwrap()));
+}
+
+#[test]
+fn partial_executor_config_keeps_the_other_defaults() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "[executor]\ndiscriminator = 3").unwrap();
+    let executor = ExecutorProgram::from_config(config.executor.as_ref()).unwrap();
+    assert_eq!(
+        executor,
+        ExecutorProgram {
+            discriminator: 3,
+            ..ExecutorProgram::default()
+        }
+    );
+}
+
+#[test]
+fn executor_config_validation() {
+    let wallet = Keypair::new();
+    let error = parse_config_with_wallet(&wallet, "[executor]\nlayout_version = 2")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("executor.layout_version"), "{}", error);
+
+    let error = parse_config_with_wallet(&wallet, "[executor]\nprogram_id = \"not-a-pubkey\"")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("executor.program_id"), "{}", error);
+}
+
+#[test]
+fn fork_only_pools_need_a_configured_executor() {
+    let wallet = Keypair::new();
+    let mint_config = |field: &str| {
+        format!(
+            "[[routing.mint_config_list]]\nmint = \"{}\"\nprocess_delay = 1000\n{} = [\"{}\"]\n",
+            Pubkey::new_unique(),
+            field,
+            Pubkey::new_unique()
+        )
+    };
+
+    for field in FORK_ONLY_POOL_LISTS {
+        let error = parse_config_with_wallet(&wallet, &mint_config(field))
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains(&format!("routing.mint_config_list[1].{}", field)),
+            "{}",
+            error
+        );
+
+        let extra = format!(
+            "{}\n[executor]\nprogram_id = \"{}\"",
+            mint_config(field),
+            Pubkey::new_unique()
+        );
+        assert!(parse_config_with_wallet(&wallet, &extra).is_ok());
+    }
+}
+
+/// 配置了 `field` 池子列表和自部署执行程序时通过校验，Swap 指令调用该程序并带上 DEX 程序和池子
+fn assert_fork_executor_routes(
+    field: &str,
+    dex_program_id: Pubkey,
+    add_pool: impl FnOnce(&mut MintPoolData, Pubkey),
+) {
+    let wallet = Keypair::new();
+    let (program_id, mint, pool) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let config = parse_config_with_wallet(
+        &wallet,
+        &format!(
+            "[[routing.mint_config_list]]\nmint = \"{}\"\nprocess_delay = 1000\n{} = [\"{}\"]\n\n[executor]\nprogram_id = \"{}\"",
+            mint, field, pool, program_id
+        ),
+    )
+    .unwrap();
+    let (mut pool_data, _) = raydium_pool_data(mint, wallet.pubkey(), 1);
+    add_pool(&mut pool_data, pool);
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+
+    let (swap_program_id, _) = swap_instruction(&tx);
+    assert_eq!(swap_program_id, program_id);
+    let keys = tx.message.static_account_keys();
+    assert!(keys.contains(&dex_program_id), "{}", field);
+    assert!(keys.contains(&pool), "{}", field);
+}
+
+#[test]
+fn fork_executor_routes_moonshot_curves() {
+    assert_fork_executor_routes(
+        "moonshot_pool_list",
+        *moonshot_program_id(),
+        |pool_data, pool| pool_data.add_moonshot_pool(pool, Pubkey::new_unique()),
+    );
+}
+
+#[test]
+fn fork_executor_routes_perena_pools() {
+    assert_fork_executor_routes(
+        "perena_pool_list",
+        *perena_program_id(),
+        |pool_data, pool| {
+            pool_data.add_perena_pool(pool, Pubkey::new_unique(), Pubkey::new_unique())
+        },
+    );
+}
+
+#[test]
+fn fork_executor_routes_zerofi_pairs() {
+    assert_fork_executor_routes(
+        "zerofi_pool_list",
+        *zerofi_program_id(),
+        |pool_data, pool| {
+            pool_data.add_zerofi_pool(
+                pool,
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+            )
+        },
+    );
+}
+
+fn account(owner: Pubkey, data: Vec<u8>, executable: bool) -> Account {
+    Account {
+        lamports: 1,
+        data,
+        owner,
+        executable,
+        rent_epoch: 0,
+    }
+}
+
+/// 可升级程序的程序账户和 ProgramData 账户，代码为 `code`
+fn deployed_program(program_id: Pubkey, code: &[u8]) -> HashMap<Pubkey, Account> {
+    let program_data = Pubkey::new_unique();
+    let mut data = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+        slot: 1,
+        upgrade_authority_address: Some(Pubkey::new_unique()),
+    })
+    .unwrap();
+    data.extend_from_slice(code);
+    HashMap::from([
+        (
+            program_id,
+            account(
+                bpf_loader_upgradeable::id(),
+                bincode::serialize(&UpgradeableLoaderState::Program {
+                    programdata_address: program_data,
+                })
+                .unwrap(),
+                true,
+            ),
+        ),
+        (
+            program_data,
+            account(bpf_loader_upgradeable::id(), data, false),
+        ),
+    ])
+}
+
+fn probe(executor: &ExecutorProgram, accounts: &HashMap<Pubkey, Account>) -> anyhow::Result<()> {
+    executor.probe(|pubkey| Ok(accounts.get(pubkey).cloned()))
+}
+
+#[test]
+fn probe_requires_a_deployed_executable_program() {
+    let executor = ExecutorProgram::default();
+    let error = probe(&executor, &HashMap::new()).unwrap_err().to_string();
+    assert!(error.contains("does not exist"), "{}", error);
+
+    let not_executable = HashMap::from([(
+        executor.program_id,
+        account(Pubkey::new_unique(), vec![], false),
+    )]);
+    let error = probe(&executor, &not_executable).unwrap_err().to_string();
+    assert!(error.contains("is not executable"), "{}", error);
+
+    let mut accounts = deployed_program(executor.program_id, &[1, 2, 3]);
+    probe(&executor, &accounts).unwrap();
+
+    // 关闭后 ProgramData 只剩账户头
+    for account in accounts.values_mut() {
+        if !account.executable {
+            account
+                .data
+                .truncate(UpgradeableLoaderState::size_of_programdata_metadata());
+        }
+    }
+    let error = probe(&executor, &accounts).unwrap_err().to_string();
+    assert!(error.contains("has been closed"), "{}", error);
+}
+
+#[test]
+fn probe_compares_the_version_byte_with_the_layout() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(
+        &wallet,
+        "[executor]
+version_offset = 2",
+    )
+    .unwrap();
+    let executor = ExecutorProgram::from_config(config.executor.as_ref()).unwrap();
+    assert_eq!(
+        executor.version_check,
+        Some(VersionCheck {
+            account: None,
+            offset: 2
+        })
+    );
+
+    probe(
+        &executor,
+        &deployed_program(executor.program_id, &[0, 0, 1]),
+    )
+    .unwrap();
+    let error = probe(
+        &executor,
+        &deployed_program(executor.program_id, &[0, 0, 2]),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("reports layout version 2") && error.contains("layout v1"),
+        "{}",
+        error
+    );
+    let error = probe(&executor, &deployed_program(executor.program_id, &[0]))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("out of range"), "{}", error);
+
+    // 版本记录在程序派生账户中
+    let config =
+        parse_config_with_wallet(&wallet, "[executor]\nversion_seed = \"version\"").unwrap();
+    let executor = ExecutorProgram::from_config(config.executor.as_ref()).unwrap();
+    let version_account = Pubkey::find_program_address(&[b"version"], &executor.program_id).0;
+    assert_eq!(
+        executor.version_check,
+        Some(VersionCheck {
+            account: Some(version_account),
+            offset: 0
+        })
+    );
+    let mut accounts = deployed_program(executor.program_id, &[0]);
+    let error = probe(&executor, &accounts).unwrap_err().to_string();
+    assert!(error.contains("version account"), "{}", error);
+    accounts.insert(
+        version_account,
+        account(executor.program_id, vec![1], false),
+    );
+    probe(&executor, &accounts).unwrap();
+
+    let error = parse_config_with_wallet(&wallet, "[executor]\nversion_seed = \"\"")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("executor.version_seed"), "{}", error);
+}
+
+#[test]
+fn routers_are_picked_by_mint_and_pool_count() {
+    let wallet = Keypair::new();
+    let (small, large) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let sol_mint = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
+    let config = parse_config_with_wallet(
+        &wallet,
+        &format!(
+            r#"
+[executor]
+discriminator = 7
+
+[[executor.routers]]
+program_id = "{small}"
+mints = ["{sol_mint}"]
+max_pools = 2
+
+[[executor.routers]]
+program_id = "{large}"
+discriminator = 9
+min_pools = 3
+"#
+        ),
+    )
+    .unwrap();
+    let executors = Executors::from_config(config.executor.as_ref()).unwrap();
+
+    let selected = executors.select(&sol_mint, 2);
+    assert_eq!(selected.program_id, small);
+    // 没有配置的字段沿用 [executor]
+    assert_eq!(selected.discriminator, 7);
+    assert_eq!(selected.fee_collector, executors.default.fee_collector);
+
+    let other_mint = Pubkey::new_unique();
+    assert_eq!(executors.select(&other_mint, 2), executors.default);
+    assert_eq!(executors.select(&other_mint, 3).program_id, large);
+    assert_eq!(executors.select(&other_mint, 3).discriminator, 9);
+    // 匹配多个路由程序时使用第一个
+    assert_eq!(executors.select(&sol_mint, 1).program_id, small);
+    assert_eq!(executors.programs().count(), 3);
+
+    // 交易中有 2 个池子，只有第一个路由程序（限定 SOL）的池子数匹配，其他代币使用默认程序
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(swap_instruction(&tx).0, executors.default.program_id);
+
+    let config = parse_config_with_wallet(
+        &wallet,
+        &format!("[[executor.routers]]\nprogram_id = \"{small}\"\nmax_pools = 2"),
+    )
+    .unwrap();
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(Pubkey::new_unique(), wallet.pubkey(), 2).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let (program_id, data) = swap_instruction(&tx);
+    assert_eq!(program_id, small);
+    assert_eq!(data[0], DEFAULT_SWAP_DISCRIMINATOR);
+}
+
+#[test]
+fn router_config_validation() {
+    let wallet = Keypair::new();
+    let error = parse_config_with_wallet(
+        &wallet,
+        &format!(
+            "[[executor.routers]]\nprogram_id = \"not-a-pubkey\"\nlayout_version = 2\nmints = [\"{}\"]\nmin_pools = 5\nmax_pools = 4",
+            Pubkey::new_unique()
+        ),
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("executor.routers[0].program_id"),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains("executor.routers[0].layout_version"),
+        "{}",
+        error
+    );
+    assert!(
+        error.contains("executor.routers[0].mints[0]")
+            && error.contains("is not in routing.mint_config_list"),
+        "{}",
+        error
+    );
+    assert!(error.contains("executor.routers[0].min_pools"), "{}", error);
+}
@@ -0,0 +1,138 @@
+//! CSV 导出测试
+
+use solana_onchain_arbitrage_bot::config::ExportConfig;
+use solana_onchain_arbitrage_bot::export::{
+    csv_field, Exporter, RotatingCsv, OUTCOMES_HEADER, QUOTES_HEADER, SENDS_HEADER,
+};
+use solana_onchain_arbitrage_bot::quote::PoolReserves;
+use solana_onchain_arbitrage_bot::status::TransactionOutcome;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+/// 目录下以 `name` 开头的文件内容，按内容排序
+fn files(dir: &Path, name: &str) -> Vec<String> {
+    let paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.file_name()
+                .unwrap()
+                .to_string_lossy()
+                .starts_with(name)
+        })
+        .collect();
+    let mut contents: Vec<String> = paths
+        .iter()
+        .map(|path| std::fs::read_to_string(path).unwrap())
+        .collect();
+    contents.sort();
+    contents
+}
+
+#[test]
+fn fields_with_separators_are_quoted() {
+    assert_eq!(csv_field("raydium"), "raydium");
+    assert_eq!(csv_field("raydium,pump"), "\"raydium,pump\"");
+    assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+}
+
+#[test]
+fn files_rotate_by_size_and_age() {
+    let dir = temp_dir("rotating-csv");
+    let mut csv = RotatingCsv::new(&dir, "rows", "a,b", 15, Duration::from_secs(3600));
+    assert!(csv.current_path().is_none());
+    csv.write_rows(&["1,2".to_string(), "3,4".to_string()])
+        .unwrap();
+    let first = csv.current_path().unwrap().to_path_buf();
+    csv.write_rows(&["5,6".to_string()]).unwrap();
+    assert_eq!(csv.current_path().unwrap(), first);
+    // 第一个文件已经达到 15 字节
+    csv.write_rows(&["7,8".to_string(), "9,10".to_string()])
+        .unwrap();
+    assert_ne!(csv.current_path().unwrap(), first);
+    assert_eq!(
+        files(&dir, "rows"),
+        vec!["a,b\n1,2\n3,4\n5,6\n", "a,b\n7,8\n9,10\n"]
+    );
+
+    let mut csv = RotatingCsv::new(&dir, "aged", "a", 1 << 20, Duration::ZERO);
+    csv.write_rows(&["1".to_string()]).unwrap();
+    csv.write_rows(&["2".to_string()]).unwrap();
+    assert_eq!(files(&dir, "aged").len(), 2);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn exporter_writes_quotes_sends_and_outcomes() {
+    let dir = temp_dir("exporter");
+    let exporter = Exporter::new(&ExportConfig {
+        enabled: true,
+        directory: Some(dir.to_string_lossy().to_string()),
+        rotate_mb: None,
+        rotate_minutes: None,
+    });
+    let (quoted, unquoted) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let reserves = HashMap::from([(
+        quoted,
+        PoolReserves {
+            token: 1_000,
+            sol: 2_000,
+            fee_bps: 25,
+        },
+    )]);
+    exporter
+        .record_quotes(
+            "MINT",
+            &[("raydium", quoted), ("whirlpool", unquoted)],
+            &reserves,
+        )
+        .unwrap();
+    let signature = Signature::new_unique();
+    exporter
+        .record_send(
+            "MINT",
+            "raydium,pump",
+            Some(&signature),
+            None,
+            &TransactionOutcome::Sent,
+        )
+        .unwrap();
+    exporter
+        .record_outcome(
+            "MINT",
+            &signature,
+            &TransactionOutcome::Landed {
+                profit_lamports: 42,
+            },
+            Some(5_000),
+        )
+        .unwrap();
+
+    let quotes = files(&dir, "quotes");
+    let lines: Vec<&str> = quotes[0].lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[0], QUOTES_HEADER);
+    assert!(lines[1].ends_with(&format!(",MINT,raydium,{},1000,2000,25,2", quoted)));
+
+    let sends = files(&dir, "sends");
+    let lines: Vec<&str> = sends[0].lines().collect();
+    assert_eq!(lines[0], SENDS_HEADER);
+    assert!(lines[1].ends_with(&format!(",MINT,\"raydium,pump\",{},,sent,", signature)));
+
+    let outcomes = files(&dir, "outcomes");
+    let lines: Vec<&str> = outcomes[0].lines().collect();
+    assert_eq!(lines[0], OUTCOMES_HEADER);
+    assert!(lines[1].ends_with(&format!(",MINT,{},landed,42,5000", signature)));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,67 @@
+//! 优先费预算测试：净亏损逐渐用完预算时先降价再暂停
+
+use solana_onchain_arbitrage_bot::config::FeeBudgetConfig;
+use solana_onchain_arbitrage_bot::fee_budget::{FeeDecision, FeeGovernor};
+use std::time::{Duration, Instant};
+
+fn governor(max_lamports_per_hour: u64) -> FeeGovernor {
+    FeeGovernor::new(&FeeBudgetConfig {
+        enabled: true,
+        max_lamports_per_hour,
+        lower_price_at: Some(0.5),
+        min_compute_unit_price: Some(100),
+    })
+}
+
+#[test]
+fn losses_lower_the_price_then_throttle() {
+    let governor = governor(10_000);
+    let now = Instant::now();
+    assert_eq!(
+        governor.decide_at(now, 1_000),
+        FeeDecision::Send {
+            compute_unit_price: 1_000
+        }
+    );
+
+    // 用掉 75% 的预算：价格降到配置值和下限的中间
+    governor.record_at(now, 7_500, -7_500);
+    assert_eq!(
+        governor.decide_at(now, 1_000),
+        FeeDecision::Send {
+            compute_unit_price: 550
+        }
+    );
+
+    governor.record_at(now, 2_500, -2_500);
+    assert_eq!(governor.spent_at(now), 10_000);
+    assert_eq!(governor.decide_at(now, 1_000), FeeDecision::Throttle);
+
+    // 一小时后旧记录移出窗口，恢复配置的价格
+    let later = now + Duration::from_secs(3601);
+    assert_eq!(governor.net_loss_at(later), 0);
+    assert_eq!(
+        governor.decide_at(later, 1_000),
+        FeeDecision::Send {
+            compute_unit_price: 1_000
+        }
+    );
+}
+
+#[test]
+fn profits_replenish_the_budget() {
+    let governor = governor(10_000);
+    let now = Instant::now();
+    governor.record_at(now, 10_000, -10_000);
+    assert_eq!(governor.decide_at(now, 1_000), FeeDecision::Throttle);
+
+    governor.record_at(now, 5_000, 20_000);
+    assert_eq!(governor.spent_at(now), 15_000);
+    assert_eq!(governor.net_loss_at(now), 0);
+    assert_eq!(
+        governor.decide_at(now, 1_000),
+        FeeDecision::Send {
+            compute_unit_price: 1_000
+        }
+    );
+}
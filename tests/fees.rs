@@ -0,0 +1,248 @@
+//! 池子手续费读取和报价使用测试
+
+mod common;
+
+use common::fixture_data;
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::meteora::dlmm_info::DlmmInfo;
+use solana_onchain_arbitrage_bot::fees::{
+    dlmm_fee, meteora_damm_fee, meteora_damm_v2_fee, pump_fee, raydium_amm_fee, raydium_clmm_fee,
+    raydium_cp_fee, whirlpool_fee, whirlpool_live_fee, PoolFee,
+};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::{live_fee_accounts, live_fees, reserves_from_balances};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// 长度为 `len` 的账户数据，在给定偏移写入小端整数
+fn account_data(len: usize, fields: &[(usize, &[u8])]) -> Vec<u8> {
+    let mut data = vec![0; len];
+    for (offset, bytes) in fields {
+        data[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+    }
+    data
+}
+
+fn lp_fee(bps: u64) -> Option<PoolFee> {
+    Some(PoolFee {
+        lp_fee_bps: bps,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn fees_are_read_from_pool_and_config_accounts() {
+    let amm = account_data(
+        752,
+        &[(176, &25u64.to_le_bytes()), (184, &10_000u64.to_le_bytes())],
+    );
+    assert_eq!(raydium_amm_fee(&amm), lp_fee(25));
+    // 分母为 0 的池子没有有效费率
+    assert_eq!(raydium_amm_fee(&account_data(752, &[])), None);
+    assert_eq!(raydium_amm_fee(&amm[..180]), None);
+
+    let cp_config = account_data(236, &[(12, &2_500u64.to_le_bytes())]);
+    assert_eq!(raydium_cp_fee(&cp_config), lp_fee(25));
+
+    // 0.01% 档位，不足 1 基点的部分向上取整
+    let clmm_config = account_data(117, &[(47, &100u32.to_le_bytes())]);
+    assert_eq!(raydium_clmm_fee(&clmm_config), lp_fee(1));
+    let clmm_config = account_data(117, &[(47, &50u32.to_le_bytes())]);
+    assert_eq!(raydium_clmm_fee(&clmm_config), lp_fee(1));
+
+    let pump_config = account_data(
+        321,
+        &[
+            (40, &20u64.to_le_bytes()),
+            (48, &5u64.to_le_bytes()),
+            (313, &5u64.to_le_bytes()),
+        ],
+    );
+    let fee = pump_fee(&pump_config).unwrap();
+    assert_eq!(fee.lp_fee_bps, 20);
+    assert_eq!(fee.protocol_fee_bps, 10);
+    assert_eq!(fee.total_bps(), 30);
+    assert_eq!(pump_fee(&pump_config[..313]), None);
+
+    assert_eq!(Some(whirlpool_fee(3_000)), lp_fee(30));
+    assert_eq!(meteora_damm_fee(25, 10_000), lp_fee(25));
+    assert_eq!(meteora_damm_fee(25, 0), None);
+}
+
+#[test]
+fn dlmm_fee_includes_the_variable_fee() {
+    let mut lb_pair = DlmmInfo::load_checked(&fixture_data("meteora_dlmm"))
+        .unwrap()
+        .lb_pair;
+    // base_factor 10000 × bin_step 25
+    assert_eq!(dlmm_fee(&lb_pair), lp_fee(25).unwrap());
+
+    lb_pair.parameters.variable_fee_control = 40_000;
+    lb_pair.v_parameters.volatility_accumulator = 10_000;
+    // (10000 × 25)² × 40000 / 1e11 = 25000，即 0.25 基点
+    let fee = dlmm_fee(&lb_pair);
+    assert_eq!(fee.dynamic_fee_bps, 1);
+    assert_eq!(fee.total_bps(), 26);
+
+    lb_pair.parameters.base_fee_power_factor = 1;
+    assert_eq!(dlmm_fee(&lb_pair).lp_fee_bps, 250);
+}
+
+#[test]
+fn damm_v2_fee_uses_the_end_of_the_fee_schedule() {
+    let pool = |mode: u8, periods: u16, reduction: u64, dynamic: bool| {
+        account_data(
+            1112,
+            &[
+                (8, &10_000_000u64.to_le_bytes()),
+                (16, &[mode]),
+                (22, &periods.to_le_bytes()),
+                (32, &reduction.to_le_bytes()),
+                (56, &[dynamic as u8]),
+                (68, &40_000u32.to_le_bytes()),
+                (72, &1u16.to_le_bytes()),
+                (120, &100_000u128.to_le_bytes()),
+            ],
+        )
+    };
+
+    // 固定费率 100 基点
+    assert_eq!(meteora_damm_v2_fee(&pool(0, 0, 0, false)), lp_fee(100));
+    // 线性：每个周期减少 0.05%，10 个周期后剩 50 基点
+    assert_eq!(
+        meteora_damm_v2_fee(&pool(0, 10, 500_000, false)),
+        lp_fee(50)
+    );
+    // 指数：每个周期减半，两个周期后剩 25 基点
+    assert_eq!(meteora_damm_v2_fee(&pool(1, 2, 5_000, false)), lp_fee(25));
+
+    // (100000 × 1)² × 40000 / 1e11 = 4000，即 0.04 基点
+    let fee = meteora_damm_v2_fee(&pool(0, 0, 0, true)).unwrap();
+    assert_eq!(fee.dynamic_fee_bps, 1);
+    assert_eq!(fee.total_bps(), 101);
+
+    assert_eq!(meteora_damm_v2_fee(&pool(0, 0, 0, true)[..130]), None);
+}
+
+#[test]
+fn quotes_use_the_pool_fee_read_during_loading() {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (default_pool, low_fee_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for pool in [default_pool, low_fee_pool] {
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+    }
+    pool_data.pool_fees.insert(low_fee_pool, lp_fee(4).unwrap());
+
+    let reserves = reserves_from_balances(&pool_data, |_| Some(1_000_000));
+    assert_eq!(reserves[&default_pool].fee_bps, 25);
+    assert_eq!(reserves[&low_fee_pool].fee_bps, 4);
+    assert_eq!(pool_data.fee_bps(&default_pool, 30), 30);
+}
+
+/// 固定报价时间，oracle 中的时间戳相对它设置
+const NOW: u64 = 1_700_000_000;
+
+/// 启用自适应手续费的 Whirlpool 池子：fixture 的静态费率 400（4 基点），tick -18200，
+/// 手续费档位编号 1024 与 tick spacing 不同
+fn adaptive_whirlpool() -> Vec<u8> {
+    let mut data = fixture_data("whirlpool");
+    data[43..45].copy_from_slice(&1024u16.to_le_bytes());
+    data
+}
+
+/// oracle 账户：tick 组大小 64，当前 tick -18200 在第 -285 组
+fn oracle(
+    control_factor: u32,
+    last_reference_update: u64,
+    volatility_reference: u32,
+    volatility_accumulator: u32,
+    tick_group_index_reference: i32,
+) -> Vec<u8> {
+    account_data(
+        254,
+        &[
+            (0, &anchor_discriminator("Oracle")),
+            (48, &30u16.to_le_bytes()),
+            (50, &600u16.to_le_bytes()),
+            (52, &5_000u16.to_le_bytes()),
+            (54, &control_factor.to_le_bytes()),
+            (58, &350_000u32.to_le_bytes()),
+            (62, &64u16.to_le_bytes()),
+            (82, &last_reference_update.to_le_bytes()),
+            (98, &volatility_reference.to_le_bytes()),
+            (102, &tick_group_index_reference.to_le_bytes()),
+            (106, &volatility_accumulator.to_le_bytes()),
+        ],
+    )
+}
+
+#[test]
+fn whirlpool_adaptive_fee_follows_the_oracle() {
+    let pool = adaptive_whirlpool();
+
+    // 距离上次更新不足 filter_period：参考波动 10000 加上相距两个 tick 组，累积 30000
+    // (30000 × 64)² × 1500 / 1e13 = 552.96，向上取整为 553（百万分之一），即 5.53 基点
+    let fee = whirlpool_live_fee(&pool, &oracle(1_500, NOW - 10, 10_000, 0, -287), NOW).unwrap();
+    assert_eq!(fee.lp_fee_bps, 4);
+    assert_eq!(fee.dynamic_fee_bps, 6);
+    assert_eq!(fee.total_bps(), 10);
+
+    // 超过 filter_period：参考波动衰减为累积值的一半，参考 tick 组移到当前位置
+    // (20000 × 64)² × 1500 / 1e13 = 245.76，即 2.46 基点
+    let fee = whirlpool_live_fee(&pool, &oracle(1_500, NOW - 100, 0, 40_000, -287), NOW).unwrap();
+    assert_eq!(fee.dynamic_fee_bps, 3);
+
+    // 超过 decay_period：波动清零，只剩静态费率
+    let fee = whirlpool_live_fee(&pool, &oracle(1_500, NOW - 1_000, 0, 40_000, -287), NOW).unwrap();
+    assert_eq!(fee, lp_fee(4).unwrap());
+
+    // 静态费率加自适应费率不超过 10%
+    let fee = whirlpool_live_fee(&pool, &oracle(u32::MAX, NOW - 10, 10_000, 0, -287), NOW).unwrap();
+    assert_eq!(fee.dynamic_fee_bps, 996);
+    assert_eq!(fee.total_bps(), 1_000);
+
+    // 普通池子和无法解析的 oracle 沿用加载时的静态费率
+    let static_pool = fixture_data("whirlpool");
+    assert_eq!(
+        whirlpool_live_fee(&static_pool, &oracle(1_500, NOW, 0, 0, 0), NOW),
+        None
+    );
+    assert_eq!(whirlpool_live_fee(&pool, &[0; 254], NOW), None);
+}
+
+#[test]
+fn live_fees_replace_the_loaded_whirlpool_fee() {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (adaptive, fixed) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let (adaptive_oracle, fixed_oracle) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for (pool, oracle) in [(adaptive, adaptive_oracle), (fixed, fixed_oracle)] {
+        pool_data.add_whirlpool_pool(
+            pool,
+            oracle,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Vec::new(),
+            None,
+        );
+        pool_data.pool_fees.insert(pool, whirlpool_fee(400));
+    }
+
+    let accounts = HashMap::from([
+        (adaptive, adaptive_whirlpool()),
+        (adaptive_oracle, oracle(1_500, NOW - 10, 10_000, 0, -287)),
+        // 普通池子没有 oracle 账户
+        (fixed, fixture_data("whirlpool")),
+    ]);
+    let fees = live_fees(
+        &live_fee_accounts(&pool_data),
+        |pubkey| accounts.get(pubkey).map(Vec::as_slice),
+        NOW,
+    );
+    assert_eq!(fees.len(), 1);
+
+    pool_data.pool_fees.extend(fees);
+    assert_eq!(pool_data.fee_bps(&adaptive, 0), 10);
+    assert_eq!(pool_data.fee_bps(&fixed, 0), 4);
+}
@@ -0,0 +1,132 @@
+//! Solend 闪电贷指令的测试
+
+mod common;
+
+use common::{programs, TestConfig};
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::flashloan::{solend_program_id, FlashLoan};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::transaction::build_transaction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::sysvar;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn solend_config(wallet: &Keypair) -> Config {
+    TestConfig {
+        wallet: Some(wallet),
+        mint: Some(USDC_MINT),
+        extra: "[flashloan]\nenabled = true\nprovider = \"solend\"\nmax_borrow = 10.0\nfee_bps = 30\nmin_extra_profit = 5000",
+        ..Default::default()
+    }
+    .parse()
+    .expect("test config should parse")
+}
+
+#[test]
+fn fee_rounds_up() {
+    let wallet = Keypair::new();
+    let config = solend_config(&wallet);
+    let loan = FlashLoan::from_config(config.flashloan.as_ref().unwrap())
+        .unwrap()
+        .unwrap();
+    assert_eq!(loan.amount, 10 * LAMPORTS_PER_SOL);
+    assert_eq!(loan.fee(), 30_000_000);
+
+    let small = FlashLoan { amount: 1, ..loan };
+    assert_eq!(small.fee(), 1);
+}
+
+#[test]
+fn swap_is_wrapped_in_borrow_and_repay() {
+    let wallet = Keypair::new();
+    let config = solend_config(&wallet);
+    let mut pool_data = MintPoolData::new(*usdc_mint(), wallet.pubkey(), spl_token::ID);
+    for _ in 0..2 {
+        pool_data.add_raydium_pool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+    }
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let keys = tx.message.static_account_keys();
+    let instructions = tx.message.instructions();
+    let program = |i: usize| keys[instructions[i].program_id_index as usize];
+
+    assert_eq!(instructions.len(), 5);
+    assert_eq!(program(2), *solend_program_id());
+    assert_eq!(program(4), *solend_program_id());
+
+    // 还款指令记录借款指令的位置
+    assert_eq!(instructions[4].data.last(), Some(&2));
+
+    // 执行程序的最低收益 = 手续费 + 额外收益，内置闪电贷标志关闭
+    let swap_data = &instructions[3].data;
+    let minimum_profit = u64::from_le_bytes(swap_data[1..9].try_into().unwrap());
+    assert_eq!(minimum_profit, 30_000_000 + 5_000);
+    assert_eq!(swap_data.last(), Some(&0));
+}
+
+#[test]
+fn account_metas_follow_solend_instruction_order() {
+    let wallet = Keypair::new();
+    let config = solend_config(&wallet);
+    let loan = FlashLoan::from_config(config.flashloan.as_ref().unwrap())
+        .unwrap()
+        .unwrap();
+    let reserve = loan.reserve;
+    let source = Pubkey::new_unique();
+    let metas = |instruction: Instruction| {
+        instruction
+            .accounts
+            .iter()
+            .map(|meta| (meta.pubkey, meta.is_writable, meta.is_signer))
+            .collect::<Vec<_>>()
+    };
+
+    // Solend token-lending `LendingInstruction::FlashBorrowReserveLiquidity` 的账户顺序
+    assert_eq!(
+        metas(loan.borrow_instruction(&source)),
+        vec![
+            (reserve.liquidity_supply, true, false),
+            (source, true, false),
+            (reserve.reserve, true, false),
+            (reserve.lending_market, false, false),
+            (reserve.lending_market_authority(), false, false),
+            (sysvar::instructions::ID, false, false),
+            (spl_token::ID, false, false),
+        ]
+    );
+
+    // `LendingInstruction::FlashRepayReserveLiquidity`：储备账户记录借款状态，必须可写
+    assert_eq!(
+        metas(loan.repay_instruction(&source, &wallet.pubkey(), 2)),
+        vec![
+            (source, true, false),
+            (reserve.liquidity_supply, true, false),
+            (reserve.fee_receiver, true, false),
+            (source, true, false),
+            (reserve.reserve, true, false),
+            (reserve.lending_market, false, false),
+            (wallet.pubkey(), false, true),
+            (sysvar::instructions::ID, false, false),
+            (spl_token::ID, false, false),
+        ]
+    );
+}
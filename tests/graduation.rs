@@ -0,0 +1,81 @@
+//! 联合曲线毕业检测和迁移目标测试
+
+mod common;
+
+use common::load_fixture;
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::graduation::{curve_graduated, MIGRATION_VENUES};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn key(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+#[test]
+fn migration_venues_find_the_mints_in_pool_fixtures() {
+    let venue = |dex: &str| *MIGRATION_VENUES.iter().find(|v| v.dex == dex).unwrap();
+    for (dex, fixture, mint, quote_mint) in [
+        (
+            "pump",
+            "pump_amm",
+            key("ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz"),
+            *sol_mint(),
+        ),
+        ("raydium", "raydium_amm_v4", key(USDC_MINT), *sol_mint()),
+        (
+            "raydium_cp",
+            "raydium_cpmm",
+            key("69k7NXVJvykLFsXbHHEH4XKRYMWg7TQaEXFNRgutoftL"),
+            *sol_mint(),
+        ),
+        (
+            "meteora_damm_v2",
+            "meteora_damm_v2",
+            key("7VzLoiRLa5EXH8SeCN1gXR9MUvHAm8kqyEHF1vbeTBRP"),
+            *sol_mint(),
+        ),
+    ] {
+        let data = load_fixture(fixture).1.data;
+        let venue = venue(dex);
+        assert!(venue.matches(&data, &mint, &quote_mint), "{}", dex);
+        assert!(
+            !venue.matches(&data, &Pubkey::new_unique(), &quote_mint),
+            "{}",
+            dex
+        );
+        assert_eq!(
+            venue.filters(&mint, &quote_mint).len(),
+            if venue.either_order { 2 } else { 1 },
+            "{}",
+            dex
+        );
+    }
+
+    // PumpSwap 的代币总是在 base 一侧
+    let data = load_fixture("pump_amm").1.data;
+    assert!(!venue("pump").matches(
+        &data,
+        sol_mint(),
+        &key("ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz")
+    ));
+    assert!(!venue("pump").matches(&data[..60], sol_mint(), sol_mint()));
+}
+
+#[test]
+fn curves_graduate_when_sold_out_or_closed() {
+    let curve = load_fixture("moonshot").1;
+    assert!(!curve_graduated(Some(&curve)));
+    assert!(curve_graduated(None));
+
+    // curve_amount 为 0：曲线上的代币已经卖完
+    let mut sold_out = curve.clone();
+    sold_out.data[16..24].copy_from_slice(&0u64.to_le_bytes());
+    assert!(curve_graduated(Some(&sold_out)));
+
+    let mut reassigned = curve;
+    reassigned.owner = Pubkey::new_unique();
+    assert!(curve_graduated(Some(&reassigned)));
+}
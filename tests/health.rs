@@ -0,0 +1,84 @@
+//! 健康检查测试：存活和就绪检查的判定、`[health]` 配置默认值和校验
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::health::{HealthInputs, HealthReport};
+use std::time::Duration;
+
+const MAX_AGE: Duration = Duration::from_secs(60);
+
+fn healthy() -> HealthInputs {
+    HealthInputs {
+        mint_count: 2,
+        blockhash_age: Duration::from_secs(5),
+        rpc_healthy: true,
+    }
+}
+
+fn failed(report: &HealthReport) -> Vec<&str> {
+    report
+        .checks
+        .iter()
+        .filter(|check| !check.ok)
+        .map(|check| check.name)
+        .collect()
+}
+
+#[test]
+fn healthy_instance_passes_both_checks() {
+    let liveness = HealthReport::liveness(&healthy(), MAX_AGE);
+    assert!(liveness.ok);
+    assert_eq!(liveness.checks.len(), 2);
+    let readiness = HealthReport::readiness(&healthy(), MAX_AGE);
+    assert!(readiness.ok);
+    assert_eq!(readiness.checks.len(), 4);
+}
+
+#[test]
+fn loading_pools_is_alive_but_not_ready() {
+    let inputs = HealthInputs {
+        mint_count: 0,
+        ..healthy()
+    };
+    assert!(HealthReport::liveness(&inputs, MAX_AGE).ok);
+    let readiness = HealthReport::readiness(&inputs, MAX_AGE);
+    assert!(!readiness.ok);
+    assert_eq!(failed(&readiness), vec!["mints"]);
+}
+
+#[test]
+fn stale_blockhash_and_rpc_failures_fail_liveness() {
+    let inputs = HealthInputs {
+        blockhash_age: Duration::from_secs(61),
+        rpc_healthy: false,
+        ..healthy()
+    };
+    let liveness = HealthReport::liveness(&inputs, MAX_AGE);
+    assert_eq!(failed(&liveness), vec!["blockhash", "rpc"]);
+    assert_eq!(liveness.checks[0].detail, "refreshed 61s ago (max 60s)");
+    assert!(!HealthReport::readiness(&inputs, MAX_AGE).ok);
+
+    // 刚好等于上限仍然健康
+    let inputs = HealthInputs {
+        blockhash_age: MAX_AGE,
+        ..healthy()
+    };
+    assert!(HealthReport::liveness(&inputs, MAX_AGE).ok);
+}
+
+#[test]
+fn health_config_defaults_and_validation() {
+    let config = parse_config("[health]\nenabled = true").unwrap();
+    let health = config.health.unwrap();
+    assert_eq!(health.bind_address(), "0.0.0.0:8081");
+    assert_eq!(health.max_blockhash_age(), Duration::from_secs(60));
+
+    let error = parse_config(
+        "[health]\nenabled = true\nbind_address = \"localhost\"\nmax_blockhash_age_secs = 0",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("health.bind_address"), "{}", error);
+    assert!(error.contains("health.max_blockhash_age_secs"), "{}", error);
+}
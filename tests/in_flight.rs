@@ -0,0 +1,65 @@
+//! 在途交易上限测试
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::in_flight::InFlight;
+use solana_sdk::signature::Signature;
+
+fn config(bot: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        bot,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn slots_are_released_when_transactions_resolve() {
+    let in_flight = InFlight::new(2);
+    let (first, second) = (Signature::new_unique(), Signature::new_unique());
+
+    in_flight.insert(first);
+    assert!(!in_flight.is_full());
+    in_flight.insert(second);
+    assert!(in_flight.is_full());
+    assert_eq!(in_flight.count(), 2);
+
+    in_flight.remove(&first);
+    assert!(!in_flight.is_full());
+    // 重复释放不影响计数
+    in_flight.remove(&first);
+    assert_eq!(in_flight.count(), 1);
+
+    // 上限至少为 1
+    let clamped = InFlight::new(0);
+    clamped.insert(first);
+    assert!(clamped.is_full());
+}
+
+#[test]
+fn skip_if_unconfirmed_limits_to_one_transaction() {
+    assert_eq!(config("").unwrap().bot.in_flight_limit(), None);
+    assert_eq!(
+        config("max_in_flight = 3").unwrap().bot.in_flight_limit(),
+        Some(3)
+    );
+    assert_eq!(
+        config("max_in_flight = 3\nskip_if_unconfirmed = true")
+            .unwrap()
+            .bot
+            .in_flight_limit(),
+        Some(1)
+    );
+    assert_eq!(
+        config("skip_if_unconfirmed = false")
+            .unwrap()
+            .bot
+            .in_flight_limit(),
+        None
+    );
+
+    let error = config("max_in_flight = 0").unwrap_err();
+    assert!(error.to_string().contains("bot.max_in_flight"));
+}
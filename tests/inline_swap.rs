@@ -0,0 +1,296 @@
+//! 直接调用 DEX 的套利交易测试：swap 指令、余额检查和路线构造
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::dex::raydium::{raydium_cp_program_id, raydium_program_id};
+use solana_onchain_arbitrage_bot::inline_swap::{
+    balance_check_instruction, build_inline_transaction, lighthouse_program_id, supported_reserves,
+    supports, swap_instruction, InlineRoute, SwapSide,
+};
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, RaydiumMarket};
+use solana_onchain_arbitrage_bot::quote::{PoolReserves, SizedQuote};
+use solana_onchain_arbitrage_bot::transfer_fee::TransferFee;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::collections::HashMap;
+
+fn parse_config(wallet: &Keypair, mint_options: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        wallet: Some(wallet),
+        mint_options,
+        ..Default::default()
+    }
+    .parse()
+}
+
+fn market() -> RaydiumMarket {
+    RaydiumMarket {
+        open_orders: Pubkey::new_unique(),
+        target_orders: Pubkey::new_unique(),
+        sol_is_coin: false,
+        market_program: Pubkey::new_unique(),
+        market: Pubkey::new_unique(),
+        bids: Pubkey::new_unique(),
+        asks: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        coin_vault: Pubkey::new_unique(),
+        pc_vault: Pubkey::new_unique(),
+        vault_signer: Pubkey::new_unique(),
+    }
+}
+
+/// 一个带市场的 Raydium 池子、一个不带市场的 Raydium 池子和一个 Raydium CP 池子
+fn pool_data(wallet: &Keypair) -> (MintPoolData, [Pubkey; 3]) {
+    let mut pool_data = MintPoolData::new(Pubkey::new_unique(), wallet.pubkey(), spl_token::ID);
+    let pools = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    for pool in pools[..2].iter().copied() {
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+    }
+    pool_data.set_raydium_market(&pools[0], market());
+    pool_data.add_raydium_cp_pool(
+        pools[2],
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    (pool_data, pools)
+}
+
+/// 卖出池的价格比买入池高 2%
+fn reserves(buy_pool: Pubkey, sell_pool: Pubkey) -> HashMap<Pubkey, PoolReserves> {
+    HashMap::from([
+        (
+            buy_pool,
+            PoolReserves {
+                token: 1_000_000_000_000,
+                sol: 1_000_000_000_000,
+                fee_bps: 25,
+            },
+        ),
+        (
+            sell_pool,
+            PoolReserves {
+                token: 1_000_000_000_000,
+                sol: 1_020_000_000_000,
+                fee_bps: 25,
+            },
+        ),
+    ])
+}
+
+#[test]
+fn only_raydium_pools_with_a_market_and_cp_pools_are_supported() {
+    let wallet = Keypair::new();
+    let (pool_data, [with_market, without_market, cp]) = pool_data(&wallet);
+    assert!(supports(&pool_data, &with_market));
+    assert!(!supports(&pool_data, &without_market));
+    assert!(supports(&pool_data, &cp));
+    assert!(!supports(&pool_data, &Pubkey::new_unique()));
+
+    let all = reserves(with_market, without_market);
+    let supported = supported_reserves(&pool_data, &all);
+    assert_eq!(supported.len(), 1);
+    assert!(supported.contains_key(&with_market));
+}
+
+#[test]
+fn raydium_cp_swap_uses_the_pool_vaults_in_swap_direction() {
+    let wallet = Keypair::new();
+    let (pool_data, [_, _, cp]) = pool_data(&wallet);
+    let cp_pool = &pool_data.raydium_cp_pools[0];
+
+    let buy = swap_instruction(&pool_data, &wallet.pubkey(), &cp, SwapSide::Buy, 100, 90).unwrap();
+    assert_eq!(buy.program_id, *raydium_cp_program_id());
+    assert_eq!(buy.data[..8], [143, 190, 90, 218, 196, 30, 51, 222]);
+    assert_eq!(u64::from_le_bytes(buy.data[8..16].try_into().unwrap()), 100);
+    assert_eq!(u64::from_le_bytes(buy.data[16..24].try_into().unwrap()), 90);
+    assert_eq!(buy.accounts.len(), 13);
+    assert_eq!(buy.accounts[0].pubkey, wallet.pubkey());
+    assert!(buy.accounts[0].is_signer);
+    assert_eq!(buy.accounts[4].pubkey, pool_data.wallet_quote_account());
+    assert_eq!(buy.accounts[6].pubkey, cp_pool.sol_vault);
+    assert_eq!(buy.accounts[7].pubkey, cp_pool.token_vault);
+    assert_eq!(buy.accounts[10].pubkey, pool_data.quote_mint);
+    assert_eq!(buy.accounts[11].pubkey, pool_data.mint);
+
+    let sell =
+        swap_instruction(&pool_data, &wallet.pubkey(), &cp, SwapSide::Sell, 90, 100).unwrap();
+    assert_eq!(sell.accounts[5].pubkey, pool_data.wallet_quote_account());
+    assert_eq!(sell.accounts[6].pubkey, cp_pool.token_vault);
+    assert_eq!(sell.accounts[7].pubkey, cp_pool.sol_vault);
+}
+
+#[test]
+fn raydium_swap_needs_the_market_accounts() {
+    let wallet = Keypair::new();
+    let (pool_data, [with_market, without_market, _]) = pool_data(&wallet);
+
+    let ix = swap_instruction(
+        &pool_data,
+        &wallet.pubkey(),
+        &with_market,
+        SwapSide::Sell,
+        100,
+        90,
+    )
+    .unwrap();
+    assert_eq!(ix.program_id, *raydium_program_id());
+    assert_eq!(ix.data[0], 9);
+    assert_eq!(ix.data.len(), 17);
+    assert_eq!(ix.accounts.len(), 18);
+    assert_eq!(ix.accounts[16].pubkey, pool_data.wallet_quote_account());
+
+    assert!(swap_instruction(
+        &pool_data,
+        &wallet.pubkey(),
+        &without_market,
+        SwapSide::Buy,
+        100,
+        90,
+    )
+    .is_err());
+}
+
+#[test]
+fn balance_check_asserts_a_minimum_token_amount() {
+    let account = Pubkey::new_unique();
+    let ix = balance_check_instruction(&account, 1_000);
+    assert_eq!(ix.program_id, *lighthouse_program_id());
+    assert_eq!(ix.accounts.len(), 1);
+    assert_eq!(ix.accounts[0].pubkey, account);
+    assert!(!ix.accounts[0].is_writable);
+    let mut expected = vec![8, 0, 2];
+    expected.extend_from_slice(&1_000u64.to_le_bytes());
+    expected.push(4);
+    assert_eq!(ix.data, expected);
+}
+
+#[test]
+fn route_requires_the_minimum_profit() {
+    let (buy_pool, sell_pool) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let reserves = reserves(buy_pool, sell_pool);
+    let quote = SizedQuote {
+        size: 1_000_000_000,
+        buy_pool,
+        sell_pool,
+        profit: 0,
+    };
+
+    let route = InlineRoute::from_quote(&quote, &reserves, 1_000, Some(5_000_000_000)).unwrap();
+    assert_eq!(route.amount_in, 1_000_000_000);
+    assert_eq!(route.token_amount, reserves[&buy_pool].buy(1_000_000_000));
+    assert_eq!(route.minimum_out, 1_000_001_000);
+    assert_eq!(route.minimum_balance, Some(5_000_001_000));
+
+    // 2% 的价差扣除两次手续费后不到 2%
+    assert!(InlineRoute::from_quote(&quote, &reserves, 20_000_000, None).is_none());
+    let unknown = SizedQuote {
+        sell_pool: Pubkey::new_unique(),
+        ..quote
+    };
+    assert!(InlineRoute::from_quote(&unknown, &reserves, 0, None).is_none());
+}
+
+#[test]
+fn inline_transaction_has_two_swaps_and_a_balance_check() {
+    let wallet = Keypair::new();
+    let config = parse_config(&wallet, "trade_sizes = [1.0]").unwrap();
+    let (mut pool_data, [with_market, _, cp]) = pool_data(&wallet);
+    let route = InlineRoute {
+        buy_pool: cp,
+        sell_pool: with_market,
+        amount_in: 1_000,
+        token_amount: 990,
+        minimum_out: 1_000,
+        minimum_balance: Some(10_000),
+    };
+
+    let tx = build_inline_transaction(&wallet, &config, &pool_data, &route, Hash::default(), &[])
+        .unwrap();
+    let keys = tx.message.static_account_keys();
+    let programs: Vec<Pubkey> = tx
+        .message
+        .instructions()
+        .iter()
+        .map(|ix| keys[ix.program_id_index as usize])
+        .collect();
+    assert_eq!(programs.len(), 5);
+    assert_eq!(programs[2], *raydium_cp_program_id());
+    assert_eq!(programs[3], *raydium_program_id());
+    assert_eq!(programs[4], *lighthouse_program_id());
+
+    let without_check = InlineRoute {
+        minimum_balance: None,
+        ..route
+    };
+    let tx = build_inline_transaction(
+        &wallet,
+        &config,
+        &pool_data,
+        &without_check,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    assert_eq!(tx.message.instructions().len(), 4);
+
+    pool_data.transfer_fee = Some(TransferFee {
+        epoch: 0,
+        maximum_fee: u64::MAX,
+        basis_points: 100,
+    });
+    assert!(
+        build_inline_transaction(&wallet, &config, &pool_data, &route, Hash::default(), &[])
+            .is_err()
+    );
+}
+
+#[test]
+fn inline_swap_config_validation() {
+    let wallet = Keypair::new();
+    let config = parse_config(
+        &wallet,
+        "trade_sizes = [1.0]\n\n[inline_swap]\nenabled = true",
+    )
+    .unwrap();
+    let inline_swap = config.inline_swap.unwrap();
+    assert_eq!(inline_swap.min_profit(), 0);
+    assert!(inline_swap.balance_check());
+
+    let error = parse_config(&wallet, "\n[inline_swap]\nenabled = true")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("inline_swap needs trade_sizes"), "{}", error);
+
+    // 求解的规模只有直接兑换会写进 swap 指令
+    assert!(parse_config(
+        &wallet,
+        "max_trade_size = 1.0\n\n[inline_swap]\nenabled = true"
+    )
+    .is_ok());
+    let error = parse_config(&wallet, "max_trade_size = 1.0")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("max_trade_size: the executor instruction has no input amount"),
+        "{}",
+        error
+    );
+
+    let error = parse_config(
+        &wallet,
+        "max_trade_size = 1.0\n\n[inline_swap]\nenabled = true\n\n[flashloan]\nenabled = true",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("inline_swap.enabled"), "{}", error);
+}
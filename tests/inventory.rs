@@ -0,0 +1,73 @@
+//! Solend 储备解析和存取款换算测试
+
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::inventory::ReserveState;
+use solana_sdk::pubkey::Pubkey;
+
+const WAD: u128 = 1_000_000_000_000_000_000;
+
+/// 按 SPL token-lending 布局构造储备账户数据
+fn reserve_data(
+    collateral_mint: &Pubkey,
+    available: u64,
+    borrowed: u64,
+    collateral_supply: u64,
+) -> Vec<u8> {
+    let mut data = vec![0u8; 619];
+    data[0] = 1;
+    data[42..74].copy_from_slice(sol_mint().as_ref());
+    data[171..179].copy_from_slice(&available.to_le_bytes());
+    data[179..195].copy_from_slice(&(borrowed as u128 * WAD).to_le_bytes());
+    data[227..259].copy_from_slice(collateral_mint.as_ref());
+    data[259..267].copy_from_slice(&collateral_supply.to_le_bytes());
+    data
+}
+
+#[test]
+fn parses_reserve_and_converts_collateral() {
+    let address = Pubkey::new_unique();
+    let collateral_mint = Pubkey::new_unique();
+    // 总流动性 1500，cToken 供应 1000，兑换率 1.5
+    let data = reserve_data(&collateral_mint, 1_000, 500, 1_000);
+
+    let reserve = ReserveState::parse(address, &data).unwrap();
+    assert_eq!(reserve.liquidity_mint, *sol_mint());
+    assert_eq!(reserve.collateral_mint, collateral_mint);
+    assert_eq!(reserve.total_liquidity(), 1_500);
+    assert_eq!(reserve.collateral_to_liquidity(100), 150);
+    // 向上取整，赎回的流动性不少于需要的数量
+    assert_eq!(reserve.liquidity_to_collateral(151), 101);
+    assert!(reserve.collateral_to_liquidity(reserve.liquidity_to_collateral(151)) >= 151);
+}
+
+#[test]
+fn rejects_short_reserve_account() {
+    assert!(ReserveState::parse(Pubkey::new_unique(), &[0u8; 100]).is_err());
+}
+
+#[test]
+fn deposit_and_redeem_carry_amounts() {
+    let data = reserve_data(&Pubkey::new_unique(), 1_000, 0, 1_000);
+    let reserve = ReserveState::parse(Pubkey::new_unique(), &data).unwrap();
+    let (wsol, ctoken, owner) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+
+    let deposit = reserve.deposit_instruction(&wsol, &ctoken, &owner, 42);
+    assert_eq!(deposit.data[0], 4);
+    assert_eq!(
+        u64::from_le_bytes(deposit.data[1..9].try_into().unwrap()),
+        42
+    );
+    assert!(deposit
+        .accounts
+        .iter()
+        .any(|a| a.pubkey == owner && a.is_signer));
+
+    let redeem = reserve.redeem_instruction(&ctoken, &wsol, &owner, 7);
+    assert_eq!(redeem.data[0], 5);
+    assert_eq!(redeem.accounts[0].pubkey, ctoken);
+    assert_eq!(redeem.accounts[1].pubkey, wsol);
+}
@@ -0,0 +1,214 @@
+//! Jito bundle 测试：按收益分成的小费、小费交易、区块引擎响应、多区域选择和配置校验
+
+mod common;
+
+use common::parse_config_with_wallet;
+use serde_json::json;
+use solana_onchain_arbitrage_bot::config::JitoConfig;
+use solana_onchain_arbitrage_bot::jito::{
+    bundle_id, tip_for_profit, tip_ratio, tip_transaction, BlockEngineRegions, RegionStats,
+    DEFAULT_BLOCK_ENGINE_URL, TIP_ACCOUNTS,
+};
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_program;
+use std::str::FromStr;
+
+fn jito_config(tip_percent: f64, min_tip: u64, max_tip: Option<u64>) -> JitoConfig {
+    JitoConfig {
+        enabled: true,
+        block_engine_url: None,
+        block_engine_urls: None,
+        race_regions: None,
+        tip_percent: Some(tip_percent),
+        min_tip_lamports: Some(min_tip),
+        max_tip_lamports: max_tip,
+        proxy: None,
+    }
+}
+
+#[test]
+fn tip_is_a_clamped_share_of_the_profit() {
+    let config = jito_config(40.0, 10_000, Some(1_000_000));
+    assert_eq!(tip_for_profit(&config, Some(100_000)), 40_000);
+    // 收益太小时使用下限，收益很大时使用上限
+    assert_eq!(tip_for_profit(&config, Some(20_000)), 10_000);
+    assert_eq!(tip_for_profit(&config, Some(10_000_000)), 1_000_000);
+    // 收益未知或为负时使用下限
+    assert_eq!(tip_for_profit(&config, None), 10_000);
+    assert_eq!(tip_for_profit(&config, Some(-5_000)), 10_000);
+
+    let unbounded = jito_config(50.0, 1_000, None);
+    assert_eq!(tip_for_profit(&unbounded, Some(10_000_000)), 5_000_000);
+}
+
+#[test]
+fn tip_ratio_needs_a_positive_profit() {
+    assert_eq!(tip_ratio(25_000, 100_000), Some(0.25));
+    assert_eq!(tip_ratio(25_000, 0), None);
+    assert_eq!(tip_ratio(25_000, -1), None);
+}
+
+#[test]
+fn tip_transaction_pays_a_tip_account() {
+    let wallet = Keypair::new();
+    let tx = tip_transaction(&wallet, 12_345, Hash::new_unique());
+    let keys = tx.message.static_account_keys();
+    assert_eq!(keys[0], wallet.pubkey());
+
+    let instructions = tx.message.instructions();
+    assert_eq!(instructions.len(), 1);
+    let ix = &instructions[0];
+    assert_eq!(keys[ix.program_id_index as usize], system_program::ID);
+    let tip_account = keys[ix.accounts[1] as usize];
+    assert!(TIP_ACCOUNTS
+        .iter()
+        .any(|account| Pubkey::from_str(account).unwrap() == tip_account));
+    // SystemInstruction::Transfer 的编号为 2，后面是转账金额
+    assert_eq!(ix.data[..4], 2u32.to_le_bytes());
+    assert_eq!(ix.data[4..12], 12_345u64.to_le_bytes());
+    assert!(tx.verify_with_results().iter().all(|ok| *ok));
+}
+
+#[test]
+fn bundle_id_reads_the_send_bundle_response() {
+    let response = json!({ "jsonrpc": "2.0", "id": 1, "result": "2id3YC2jK9G5Wo2phDx4gJVAew8DcY5NAojnVuao8rkxwPYPe8cSwE5GzhEgJA2y8fVjDEo6iR6ykBvDxrTQrtpb" });
+    assert_eq!(
+        bundle_id(&response).unwrap(),
+        "2id3YC2jK9G5Wo2phDx4gJVAew8DcY5NAojnVuao8rkxwPYPe8cSwE5GzhEgJA2y8fVjDEo6iR6ykBvDxrTQrtpb"
+    );
+
+    let rejected = json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32602, "message": "bundle contains an expired blockhash" } });
+    let error = bundle_id(&rejected).unwrap_err().to_string();
+    assert!(error.contains("expired blockhash"), "{}", error);
+    assert!(bundle_id(&json!({ "jsonrpc": "2.0", "id": 1 })).is_err());
+}
+
+fn regions(race: usize) -> BlockEngineRegions {
+    BlockEngineRegions::new(
+        vec![
+            "https://amsterdam.mainnet.block-engine.jito.wtf".to_string(),
+            "https://frankfurt.mainnet.block-engine.jito.wtf".to_string(),
+            "https://ny.mainnet.block-engine.jito.wtf".to_string(),
+        ],
+        race,
+    )
+}
+
+#[test]
+fn region_weight_is_a_shrunk_landing_rate() {
+    // 没有结果时为先验 0.5
+    assert_eq!(RegionStats::default().weight(), 0.5);
+    let good = RegionStats {
+        sent: 16,
+        accepted: 16,
+        landed: 14,
+    };
+    let bad = RegionStats {
+        sent: 16,
+        accepted: 10,
+        landed: 2,
+    };
+    assert_eq!(good.weight(), 0.8);
+    assert_eq!(bad.weight(), 0.2);
+}
+
+#[test]
+fn regions_race_all_engines_by_default() {
+    let regions = regions(3);
+    let mut picked = regions.pick();
+    picked.sort();
+    assert_eq!(picked, vec![0, 1, 2]);
+
+    regions.record_send(0, true);
+    regions.record_send(1, false);
+    regions.record_landed(&[0]);
+    let stats = regions.stats();
+    assert_eq!(
+        stats[0],
+        RegionStats {
+            sent: 1,
+            accepted: 1,
+            landed: 1
+        }
+    );
+    assert_eq!(
+        stats[1],
+        RegionStats {
+            sent: 1,
+            accepted: 0,
+            landed: 0
+        }
+    );
+    assert!(regions.summary().contains("amsterdam"));
+}
+
+#[test]
+fn regions_that_land_more_are_picked_more() {
+    let regions = regions(1);
+    for _ in 0..50 {
+        regions.record_send(0, true);
+        regions.record_landed(&[0]);
+        regions.record_send(1, true);
+        regions.record_send(2, false);
+    }
+    let mut counts = [0; 3];
+    for _ in 0..1_000 {
+        let picked = regions.pick();
+        assert_eq!(picked.len(), 1);
+        counts[picked[0]] += 1;
+    }
+    // 落后的区域仍会被偶尔选中
+    assert!(counts[0] > 850, "{:?}", counts);
+    assert!(counts[1] + counts[2] > 0, "{:?}", counts);
+}
+
+#[test]
+fn jito_config_defaults_and_validation() {
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "[jito]\nenabled = true").unwrap();
+    let jito = config.jito.unwrap();
+    assert_eq!(jito.block_engine_url(), DEFAULT_BLOCK_ENGINE_URL);
+    assert_eq!(jito.tip_percent(), 50.0);
+    assert_eq!(jito.min_tip_lamports(), 1_000);
+    assert_eq!(jito.max_tip_lamports, None);
+    assert_eq!(jito.block_engine_urls(), vec![DEFAULT_BLOCK_ENGINE_URL]);
+    assert_eq!(jito.race_regions(), 1);
+
+    let config = parse_config_with_wallet(
+        &wallet,
+        "[jito]\nenabled = true\nblock_engine_urls = [\"https://a\", \"https://b\"]",
+    )
+    .unwrap();
+    assert_eq!(config.jito.unwrap().race_regions(), 2);
+
+    let error = parse_config_with_wallet(
+        &wallet,
+        "[jito]\nenabled = true\nblock_engine_urls = [\"https://a\", \"https://a\"]\nrace_regions = 3",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("duplicate URL https://a"), "{}", error);
+    assert!(error.contains("jito.race_regions"), "{}", error);
+
+    let error = parse_config_with_wallet(&wallet, "[jito]\nenabled = true\ntip_percent = 150")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("jito.tip_percent"), "{}", error);
+
+    let error = parse_config_with_wallet(
+        &wallet,
+        "[jito]\nenabled = true\nmin_tip_lamports = 10000\nmax_tip_lamports = 5000",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("jito.max_tip_lamports"), "{}", error);
+
+    let error =
+        parse_config_with_wallet(&wallet, "[jito]\nenabled = true\n\n[batch]\nenabled = true")
+            .unwrap_err()
+            .to_string();
+    assert!(error.contains("jito.enabled"), "{}", error);
+}
@@ -0,0 +1,113 @@
+//! 上链率模型和期望收益测试
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::landing_model::{
+    expected_value, transaction_fee, LandingBucket, LandingCounts, LandingModel,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.json", name, std::process::id()))
+}
+
+#[test]
+fn buckets_follow_fee_magnitude_and_spread_age() {
+    let bucket = LandingBucket::new(0, Duration::from_millis(50));
+    assert_eq!((bucket.fee_bucket, bucket.age_bucket), (0, 0));
+    let bucket = LandingBucket::new(1_000, Duration::from_millis(500));
+    assert_eq!((bucket.fee_bucket, bucket.age_bucket), (10, 2));
+    let bucket = LandingBucket::new(1_023, Duration::from_secs(60));
+    assert_eq!((bucket.fee_bucket, bucket.age_bucket), (10, 4));
+    assert_ne!(
+        LandingBucket::new(1_024, Duration::ZERO),
+        LandingBucket::new(1_023, Duration::ZERO)
+    );
+}
+
+#[test]
+fn landing_rates_shrink_towards_the_overall_rate() {
+    let model = LandingModel::new(0.5);
+    let fresh = LandingBucket::new(1_000, Duration::ZERO);
+    let stale = LandingBucket::new(1_000, Duration::from_secs(10));
+    assert_eq!(model.landing_probability(&fresh), 0.5);
+
+    for _ in 0..16 {
+        model.record(fresh, true);
+        model.record(stale, false);
+    }
+    assert_eq!(
+        model.counts(&fresh),
+        LandingCounts {
+            sent: 16,
+            landed: 16
+        }
+    );
+    let fresh_rate = model.landing_probability(&fresh);
+    let stale_rate = model.landing_probability(&stale);
+    assert!(fresh_rate > 0.85 && fresh_rate < 1.0, "{}", fresh_rate);
+    assert!(stale_rate < 0.15 && stale_rate > 0.0, "{}", stale_rate);
+    // 没有样本的分桶使用整体上链率
+    let unseen = model.landing_probability(&LandingBucket::new(0, Duration::ZERO));
+    assert!((unseen - 0.5).abs() < 1e-9, "{}", unseen);
+}
+
+#[test]
+fn model_is_saved_and_loaded_across_runs() {
+    let path = temp_path("landing-model");
+    let bucket = LandingBucket::new(5_000, Duration::from_millis(200));
+    let model = LandingModel::new(0.5);
+    model.record(bucket, true);
+    model.record(bucket, false);
+    model.save(&path).unwrap();
+
+    let loaded = LandingModel::load(&path, 0.5);
+    assert_eq!(loaded.counts(&bucket), LandingCounts { sent: 2, landed: 1 });
+    assert_eq!(
+        loaded.landing_probability(&bucket),
+        model.landing_probability(&bucket)
+    );
+
+    std::fs::write(&path, r#"{"version": 0, "buckets": []}"#).unwrap();
+    assert_eq!(
+        LandingModel::load(&path, 0.5).counts(&bucket),
+        LandingCounts::default()
+    );
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(
+        LandingModel::load(&path, 0.5).counts(&bucket),
+        LandingCounts::default()
+    );
+}
+
+#[test]
+fn expected_value_weighs_profit_by_landing_probability() {
+    // 5000 签名费 + 1000 micro-lamports × 600000 CU
+    assert_eq!(transaction_fee(1_000, 600_000), 5_600);
+    assert_eq!(transaction_fee(0, 600_000), 5_000);
+    assert_eq!(transaction_fee(1, 1), 5_001);
+
+    assert_eq!(expected_value(100_000, 0.5, 5_600, 0), 44_400.0);
+    assert_eq!(expected_value(10_000, 0.5, 5_600, 0), -600.0);
+    assert_eq!(expected_value(100_000, 0.5, 5_600, 50_000), -5_600.0);
+}
+
+#[test]
+fn ev_gate_config_defaults_and_validation() {
+    let parsed = parse_config("[ev_gate]\nenabled = true").unwrap();
+    let ev_gate = parsed.ev_gate.unwrap();
+    assert_eq!(ev_gate.model_path(), "landing_model.json");
+    assert_eq!(ev_gate.prior_landing_rate(), 0.5);
+
+    for prior in ["0.0", "1.5"] {
+        let error = parse_config(&format!(
+            "[ev_gate]\nenabled = true\nprior_landing_rate = {}",
+            prior
+        ))
+        .unwrap_err()
+        .to_string();
+        assert!(error.contains("ev_gate.prior_landing_rate"), "{}", error);
+    }
+}
@@ -0,0 +1,39 @@
+//! 零拷贝账户布局测试：任意偏移读取、长度检查和代币账户字段
+
+use solana_onchain_arbitrage_bot::layout::{self, PodU64, TokenAccountLayout};
+use solana_onchain_arbitrage_bot::quote::token_amount;
+use solana_sdk::pubkey::Pubkey;
+
+fn token_account(mint: &Pubkey, owner: &Pubkey, amount: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data[..32].copy_from_slice(mint.as_ref());
+    data[32..64].copy_from_slice(owner.as_ref());
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data
+}
+
+#[test]
+fn token_account_fields_are_read_in_place() {
+    let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let data = token_account(&mint, &owner, 1_234_567);
+
+    let account = layout::view::<TokenAccountLayout>(&data, 0).unwrap();
+    assert_eq!(account.mint, mint);
+    assert_eq!(account.owner, owner);
+    assert_eq!(account.amount.get(), 1_234_567);
+    assert_eq!(token_amount(&data), Some(1_234_567));
+    assert_eq!(token_amount(&data[..71]), None);
+}
+
+#[test]
+fn views_work_at_unaligned_offsets() {
+    let mut data = vec![0xffu8; 3];
+    data.extend_from_slice(&u64::MAX.to_le_bytes());
+    data.extend_from_slice(&7u64.to_le_bytes());
+
+    assert_eq!(layout::view::<PodU64>(&data, 3).unwrap().get(), u64::MAX);
+    assert_eq!(layout::view::<PodU64>(&data, 11).unwrap().get(), 7);
+    assert_eq!(layout::view::<PodU64>(&data, 12), None);
+    assert_eq!(layout::view::<PodU64>(&data, usize::MAX), None);
+    assert_eq!(PodU64::from(7).get(), 7);
+}
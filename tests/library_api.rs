@@ -0,0 +1,119 @@
+use solana_onchain_arbitrage_bot::{Config, ConfigFormat, MintPoolData, PoolDirection};
+
+#[test]
+fn example_config_loads_through_the_library() {
+    let contents = include_str!("../config.toml.example");
+    let config = Config::parse(contents, ConfigFormat::Toml).unwrap();
+    assert!(!config.routing.mint_config_list.is_empty());
+}
+
+#[test]
+fn pool_data_is_usable_without_the_binary() {
+    let mint = solana_onchain_arbitrage_bot::constants::sol_mint();
+    let wallet = solana_sdk::pubkey::Pubkey::new_unique();
+    let pool_data = MintPoolData::new(*mint, wallet, spl_token::ID);
+    assert_eq!(
+        pool_data.direction(&solana_sdk::pubkey::Pubkey::new_unique()),
+        PoolDirection::Both
+    );
+    assert!(!pool_data.has_valid_route());
+}
+
+#[test]
+fn mints_are_checked_when_the_config_loads() {
+    let contents = include_str!("../config.toml.example");
+    let config = Config::parse(contents, ConfigFormat::Toml).unwrap();
+    let mint_config = &config.routing.mint_config_list[0];
+    assert_eq!(
+        mint_config.mint_pubkey().unwrap().to_string(),
+        mint_config.mint
+    );
+
+    let invalid = contents.replacen(&mint_config.mint, "not-a-mint", 1);
+    let error = format!(
+        "{:#}",
+        Config::parse(&invalid, ConfigFormat::Toml).unwrap_err()
+    );
+    assert!(
+        error.contains("routing.mint_config_list[0].mint: invalid pubkey `not-a-mint`"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn invalid_pool_addresses_are_reported_together() {
+    let contents = r#"
+[bot]
+compute_unit_limit = 400000
+
+[routing]
+[[routing.mint_config_list]]
+mint = "So11111111111111111111111111111111111111112"
+process_delay = 1000
+trade_sizes = [1.0]
+pump_pool_list = ["bad-pump"]
+raydium_pool_list = ["Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v", { address = "bad-raydium", direction = "buy_only" }]
+
+[rpc]
+url = "http://127.0.0.1:8899"
+
+[wallet]
+private_key = "key"
+"#;
+    let error = Config::parse(contents, ConfigFormat::Toml)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.starts_with("Invalid config (2 problems)"),
+        "{}",
+        error
+    );
+    for path in [
+        "routing.mint_config_list[0].pump_pool_list[0]: invalid pubkey `bad-pump`",
+        "routing.mint_config_list[0].raydium_pool_list[1]: invalid pubkey `bad-raydium`",
+    ] {
+        assert!(error.contains(path), "{}", error);
+    }
+}
+
+#[test]
+fn malformed_mints_are_reported_with_the_other_problems() {
+    let contents = r#"
+[bot]
+compute_unit_limit = 400000
+
+[routing]
+[[routing.mint_config_list]]
+mint = "So11111111111111111111111111111111111111112"
+process_delay = 1000
+pump_pool_list = ["bad-pump"]
+
+[[routing.mint_config_list]]
+mint = "bad-mint"
+process_delay = 1000
+quote_mint = "DOGE"
+raydium_pool_list = ["Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v"]
+
+[rpc]
+url = "http://127.0.0.1:8899"
+
+[wallet]
+private_key = "key"
+"#;
+    let error = Config::parse(contents, ConfigFormat::Toml)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.starts_with("Invalid config (3 problems)"),
+        "{}",
+        error
+    );
+    for path in [
+        "routing.mint_config_list[0].pump_pool_list[0]: invalid pubkey `bad-pump`",
+        "routing.mint_config_list[1].mint: invalid pubkey `bad-mint`",
+        "routing.mint_config_list[1].quote_mint:",
+    ] {
+        assert!(error.contains(path), "{}", error);
+    }
+}
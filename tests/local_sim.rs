@@ -0,0 +1,171 @@
+//! 本地验证器模式测试：克隆账户的分类、验证器参数、需要克隆的地址和本地运行的配置
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::{Config, CreateAtaPolicy};
+use solana_onchain_arbitrage_bot::executor::{DEFAULT_EXECUTOR_PROGRAM_ID, DEFAULT_FEE_COLLECTOR};
+use solana_onchain_arbitrage_bot::flashloan::{SOLEND_PROGRAM_ID, SOLEND_SOL_RESERVE};
+use solana_onchain_arbitrage_bot::local_sim::{
+    local_config, required_accounts, ClonePlan, LocalSimOptions,
+};
+use solana_sdk::account::Account;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::native_loader;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+fn account(owner: Pubkey, executable: bool) -> Account {
+    Account {
+        lamports: 1_000_000,
+        data: vec![0; 8],
+        owner,
+        executable,
+        rent_epoch: 0,
+    }
+}
+
+fn parse_config(extra: &str) -> Config {
+    TestConfig {
+        mint_options: "lookup_table_accounts = [\"4sKLJ1Qoudh8PJyqBeuKocYdsZvxTcRShUt9aKqwhgvC\"]",
+        rpc_url: Some("https://api.mainnet-beta.solana.com"),
+        extra,
+        ..Default::default()
+    }
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn clone_plan_classifies_accounts() {
+    let wallet = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let program_data = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let missing_ata = Pubkey::new_unique();
+    let accounts = HashMap::from([
+        (wallet, account(solana_sdk::system_program::ID, false)),
+        (program, account(bpf_loader_upgradeable::id(), true)),
+        (program_data, account(bpf_loader_upgradeable::id(), false)),
+        (pool, account(Pubkey::new_unique(), false)),
+        (
+            solana_sdk::system_program::ID,
+            account(native_loader::id(), true),
+        ),
+        (spl_token::ID, account(bpf_loader_upgradeable::id(), true)),
+        (
+            solana_sdk::sysvar::clock::ID,
+            account(solana_sdk::sysvar::id(), false),
+        ),
+    ]);
+
+    let plan = ClonePlan::new(
+        [
+            wallet,
+            program,
+            program_data,
+            pool,
+            missing_ata,
+            solana_sdk::system_program::ID,
+            spl_token::ID,
+            solana_sdk::sysvar::clock::ID,
+        ],
+        &accounts,
+        &wallet,
+    );
+    assert_eq!(
+        plan.upgradeable_programs.into_iter().collect::<Vec<_>>(),
+        [program]
+    );
+    assert_eq!(plan.accounts.into_iter().collect::<Vec<_>>(), [pool]);
+    assert_eq!(plan.missing.into_iter().collect::<Vec<_>>(), [missing_ata]);
+}
+
+#[test]
+fn validator_args_clone_programs_then_accounts() {
+    let wallet = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let pool = Pubkey::new_unique();
+    let plan = ClonePlan {
+        upgradeable_programs: [program].into(),
+        accounts: [pool].into(),
+        missing: Default::default(),
+    };
+    let options = LocalSimOptions {
+        ledger: PathBuf::from("/tmp/ledger"),
+        rpc_port: 9899,
+        ..Default::default()
+    };
+
+    assert_eq!(
+        plan.validator_args("https://mainnet.example.com", &wallet, &options),
+        [
+            "--reset".to_string(),
+            "--ledger".to_string(),
+            "/tmp/ledger".to_string(),
+            "--rpc-port".to_string(),
+            "9899".to_string(),
+            "--url".to_string(),
+            "https://mainnet.example.com".to_string(),
+            "--mint".to_string(),
+            wallet.to_string(),
+            "--clone-upgradeable-program".to_string(),
+            program.to_string(),
+            "--clone".to_string(),
+            pool.to_string(),
+        ]
+    );
+    assert_eq!(options.rpc_url(), "http://127.0.0.1:9899");
+}
+
+#[test]
+fn required_accounts_include_executors_lookup_tables_and_flash_loans() {
+    let config = parse_config(
+        r#"
+[flashloan]
+enabled = true
+provider = "solend"
+max_borrow = 10.0
+"#,
+    );
+    let accounts = required_accounts(&config, &Keypair::new(), &[]).unwrap();
+    for expected in [
+        DEFAULT_EXECUTOR_PROGRAM_ID,
+        DEFAULT_FEE_COLLECTOR,
+        "4sKLJ1Qoudh8PJyqBeuKocYdsZvxTcRShUt9aKqwhgvC",
+        SOLEND_PROGRAM_ID,
+        SOLEND_SOL_RESERVE,
+    ] {
+        assert!(
+            accounts.contains(&Pubkey::from_str(expected).unwrap()),
+            "{}",
+            expected
+        );
+    }
+}
+
+#[test]
+fn local_config_points_at_the_validator_in_dry_run() {
+    let config = parse_config(
+        r#"
+[spam]
+enabled = true
+sending_rpc_urls = ["http://a.example.com"]
+compute_unit_price = 1000
+
+[jito]
+enabled = true
+"#,
+    );
+    assert!(!config.is_dry_run());
+
+    let config = local_config(config, "http://127.0.0.1:8899");
+    assert_eq!(config.rpc.url, "http://127.0.0.1:8899");
+    assert!(config.is_dry_run());
+    assert_eq!(config.bot.create_ata, CreateAtaPolicy::Never);
+    assert!(config.spam.is_none());
+    assert!(config.jito.is_none());
+}
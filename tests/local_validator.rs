@@ -0,0 +1,340 @@
+//! 基于本地验证节点的集成测试
+//!
+//! 测试需要一个克隆了主网账户的 solana-test-validator，默认被忽略。运行方式：
+//!
+//! ```text
+//! ./scripts/local-validator.sh
+//! cargo test --test local_validator -- --ignored
+//! ```
+//!
+//! 验证节点地址可以通过 `LOCAL_VALIDATOR_URL` 覆盖，默认 `http://127.0.0.1:8899`。
+
+use solana_client::nonblocking::rpc_client::RpcClient as NonblockingRpcClient;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_onchain_arbitrage_bot::ata::{ensure_token_account, TOKEN_2022_PROGRAM_ID};
+use solana_onchain_arbitrage_bot::config::{Config, CreateAtaPolicy, PoolErrorPolicy};
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, NonQuotePoolPolicy};
+use solana_onchain_arbitrage_bot::refresh::{initialize_pool_data, PoolLists, PoolLoadContext};
+use solana_onchain_arbitrage_bot::submit::Submitters;
+use solana_onchain_arbitrage_bot::transaction::{
+    build_and_send_transaction, build_transaction, SwapPrograms,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_associated_token_account::{
+    get_associated_token_address, get_associated_token_address_with_program_id,
+};
+use std::str::FromStr;
+use std::sync::Arc;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+const RAYDIUM_POOL: &str = "58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2";
+const PUMP_POOL: &str = "Gf7sXMoP8iRw4iiXmJ1nq4vxcRycbGXy5RL8a8LnTd3v";
+
+fn validator_url() -> String {
+    std::env::var("LOCAL_VALIDATOR_URL").unwrap_or_else(|_| "http://127.0.0.1:8899".to_string())
+}
+
+fn rpc_client() -> Arc<RpcClient> {
+    Arc::new(RpcClient::new_with_commitment(
+        validator_url(),
+        CommitmentConfig::confirmed(),
+    ))
+}
+
+fn async_rpc_client() -> NonblockingRpcClient {
+    NonblockingRpcClient::new_with_commitment(validator_url(), CommitmentConfig::confirmed())
+}
+
+/// 构造 dry-run 模式下的测试配置
+fn test_config(wallet: &Keypair) -> Config {
+    toml::from_str(&format!(
+        r#"
+[bot]
+compute_unit_limit = 600000
+dry_run = true
+no_failure_mode = true
+
+[routing]
+[[routing.mint_config_list]]
+mint = "{USDC_MINT}"
+raydium_pool_list = ["{RAYDIUM_POOL}"]
+pump_pool_list = ["{PUMP_POOL}"]
+process_delay = 1000
+
+[rpc]
+url = "{}"
+
+[wallet]
+private_key = "{}"
+"#,
+        validator_url(),
+        wallet.to_base58_string()
+    ))
+    .expect("test config should parse")
+}
+
+/// 创建测试钱包：空投 SOL，创建并充值 WSOL 账户，创建 USDC 账户
+fn funded_wallet(client: &RpcClient) -> Keypair {
+    let wallet = Keypair::new();
+    let airdrop = client
+        .request_airdrop(&wallet.pubkey(), 10 * LAMPORTS_PER_SOL)
+        .expect("airdrop should be accepted");
+    client
+        .poll_for_signature(&airdrop)
+        .expect("airdrop should confirm");
+
+    let usdc_mint = Pubkey::from_str(USDC_MINT).unwrap();
+    let wsol_account = get_associated_token_address(&wallet.pubkey(), sol_mint());
+    let instructions = vec![
+        create_associated_token_account_idempotent(
+            &wallet.pubkey(),
+            &wallet.pubkey(),
+            sol_mint(),
+            &spl_token::ID,
+        ),
+        system_instruction::transfer(&wallet.pubkey(), &wsol_account, LAMPORTS_PER_SOL),
+        spl_token::instruction::sync_native(&spl_token::ID, &wsol_account).unwrap(),
+        create_associated_token_account_idempotent(
+            &wallet.pubkey(),
+            &wallet.pubkey(),
+            &usdc_mint,
+            &spl_token::ID,
+        ),
+    ];
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&wallet.pubkey()),
+        &[&wallet],
+        client.get_latest_blockhash().unwrap(),
+    );
+    client
+        .send_and_confirm_transaction(&tx)
+        .expect("wallet setup transaction should land");
+
+    wallet
+}
+
+async fn load_pool_data(config: &Config, wallet: &Keypair) -> MintPoolData {
+    let mint_config = &config.routing.mint_config_list[0];
+    initialize_pool_data(
+        &mint_config.mint_pubkey().unwrap(),
+        &wallet.pubkey(),
+        &mint_config.quote_mint().unwrap().mint,
+        &PoolLists::enabled(mint_config).unwrap(),
+        None,
+        &PoolLoadContext {
+            rpc_client: rpc_client(),
+            async_rpc_client: &async_rpc_client(),
+            pool_cache: None,
+            on_pool_error: config.bot.on_pool_error,
+            non_quote_pools: config.routing.non_quote_pools,
+        },
+    )
+    .await
+    .expect("pool data should load from cloned accounts")
+}
+
+#[tokio::test]
+#[ignore = "requires scripts/local-validator.sh"]
+async fn loads_raydium_and_pump_pools_from_cloned_accounts() {
+    let client = rpc_client();
+    let wallet = funded_wallet(&client);
+    let config = test_config(&wallet);
+
+    let pool_data = load_pool_data(&config, &wallet).await;
+
+    assert_eq!(pool_data.raydium_pools.len(), 1);
+    assert_eq!(pool_data.pump_pools.len(), 1);
+
+    let raydium = &pool_data.raydium_pools[0];
+    assert_eq!(raydium.pool, Pubkey::from_str(RAYDIUM_POOL).unwrap());
+    assert_ne!(raydium.token_vault, raydium.sol_vault);
+
+    let pump = &pool_data.pump_pools[0];
+    assert_eq!(pump.pool, Pubkey::from_str(PUMP_POOL).unwrap());
+    assert_ne!(pump.token_vault, pump.sol_vault);
+}
+
+#[tokio::test]
+#[ignore = "requires scripts/local-validator.sh"]
+async fn missing_pool_fails_or_is_skipped_by_policy() {
+    let mint = Pubkey::from_str(USDC_MINT).unwrap();
+    let wallet = Keypair::new().pubkey();
+    let pools = PoolLists {
+        pump: Some(vec![
+            Pubkey::from_str(PUMP_POOL).unwrap(),
+            Pubkey::new_unique(),
+        ]),
+        ..PoolLists::default()
+    };
+    let quote_mint = *sol_mint();
+    let async_rpc_client = async_rpc_client();
+    let load = |policy| {
+        let context = PoolLoadContext {
+            rpc_client: rpc_client(),
+            async_rpc_client: &async_rpc_client,
+            pool_cache: None,
+            on_pool_error: policy,
+            non_quote_pools: NonQuotePoolPolicy::Reject,
+        };
+        let pools = &pools;
+        async move { initialize_pool_data(&mint, &wallet, &quote_mint, pools, None, &context).await }
+    };
+
+    assert!(load(PoolErrorPolicy::Fail).await.is_err());
+
+    let pool_data = load(PoolErrorPolicy::Skip)
+        .await
+        .expect("missing pool should be skipped");
+    assert_eq!(pool_data.pump_pools.len(), 1);
+    assert_eq!(
+        pool_data.pump_pools[0].pool,
+        Pubkey::from_str(PUMP_POOL).unwrap()
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires scripts/local-validator.sh"]
+async fn arbitrage_transaction_simulates_successfully() {
+    let client = rpc_client();
+    let wallet = funded_wallet(&client);
+    let config = test_config(&wallet);
+    let pool_data = load_pool_data(&config, &wallet).await;
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &SwapPrograms::from_config(&config).unwrap(),
+        &pool_data,
+        client.get_latest_blockhash().unwrap(),
+        &[],
+    )
+    .expect("transaction should build");
+
+    let result = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: true,
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+        )
+        .expect("simulation request should succeed")
+        .value;
+
+    assert!(
+        result.err.is_none(),
+        "simulation failed: {:?}\n{}",
+        result.err,
+        result.logs.unwrap_or_default().join("\n")
+    );
+}
+
+#[tokio::test]
+#[ignore = "requires scripts/local-validator.sh"]
+async fn dry_run_never_broadcasts() {
+    let client = rpc_client();
+    let wallet = funded_wallet(&client);
+    let config = test_config(&wallet);
+    let pool_data = load_pool_data(&config, &wallet).await;
+
+    let submitters = Submitters::from_config(&config, &client, Vec::new(), None, None);
+    let outcome = build_and_send_transaction(
+        &wallet,
+        &config,
+        &SwapPrograms::from_config(&config).unwrap(),
+        &pool_data,
+        &submitters,
+        (client.get_latest_blockhash().unwrap(), 0),
+        &[],
+    )
+    .await
+    .expect("dry-run should succeed");
+
+    assert!(outcome.signatures.is_empty());
+}
+
+/// 在验证节点上创建一个 Token 2022 mint
+fn create_token_2022_mint(client: &RpcClient, payer: &Keypair) -> Pubkey {
+    let mint = Keypair::new();
+    let token_2022 = Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap();
+    // 不带扩展的 mint 和 SPL Token 的 mint 一样是 82 字节
+    let space = 82;
+    let rent = client
+        .get_minimum_balance_for_rent_exemption(space)
+        .unwrap();
+    // InitializeMint2：decimals 6，mint authority 为 payer，没有 freeze authority
+    let mut data = vec![20, 6];
+    data.extend_from_slice(payer.pubkey().as_ref());
+    data.push(0);
+    let instructions = vec![
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            space as u64,
+            &token_2022,
+        ),
+        Instruction::new_with_bytes(
+            token_2022,
+            &data,
+            vec![AccountMeta::new(mint.pubkey(), false)],
+        ),
+    ];
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[payer, &mint],
+        client.get_latest_blockhash().unwrap(),
+    );
+    client
+        .send_and_confirm_transaction(&tx)
+        .expect("mint creation should land");
+    mint.pubkey()
+}
+
+#[tokio::test]
+#[ignore = "requires scripts/local-validator.sh"]
+async fn creates_token_2022_atas_with_the_mint_program() {
+    let client = rpc_client();
+    let wallet = funded_wallet(&client);
+    let mint = create_token_2022_mint(&client, &wallet);
+
+    let ata = ensure_token_account(&client, &wallet, &mint, CreateAtaPolicy::Auto, 0, 1, false)
+        .expect("Token 2022 ATA should be created");
+    assert_eq!(
+        ata,
+        get_associated_token_address_with_program_id(
+            &wallet.pubkey(),
+            &mint,
+            &Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap()
+        )
+    );
+    assert_eq!(
+        client.get_account(&ata).unwrap().owner.to_string(),
+        TOKEN_2022_PROGRAM_ID
+    );
+
+    // 已经存在时 fail_if_missing 不报错
+    ensure_token_account(
+        &client,
+        &wallet,
+        &mint,
+        CreateAtaPolicy::FailIfMissing,
+        0,
+        1,
+        false,
+    )
+    .unwrap();
+}
@@ -0,0 +1,115 @@
+//! mint 安全检查测试：冻结权限、Token 2022 风险扩展和 `allow_risky` 配置
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::mint_safety::{freeze_authority, mint_risks, MintRisk};
+use solana_onchain_arbitrage_bot::transfer_fee::mint_extensions;
+use solana_sdk::pubkey::Pubkey;
+
+/// SPL Token mint 账户数据，只填写冻结权限
+fn spl_mint(freeze: Option<Pubkey>) -> Vec<u8> {
+    let mut data = vec![0u8; 82];
+    if let Some(freeze) = freeze {
+        data[46..50].copy_from_slice(&1u32.to_le_bytes());
+        data[50..82].copy_from_slice(freeze.as_ref());
+    }
+    data
+}
+
+/// 构造带有指定扩展的 Token 2022 mint 账户数据
+fn token_2022_mint(extensions: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut data = spl_mint(None);
+    data.resize(165, 0);
+    data.push(1); // AccountType::Mint
+    for (extension_type, value) in extensions {
+        data.extend_from_slice(&extension_type.to_le_bytes());
+        data.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        data.extend_from_slice(value);
+    }
+    data
+}
+
+fn parse_config(mint_options: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        mint_options,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn freeze_authority_is_a_risk() {
+    let authority = Pubkey::new_unique();
+    assert_eq!(
+        freeze_authority(&spl_mint(Some(authority))),
+        Some(authority)
+    );
+    assert_eq!(
+        mint_risks(&spl_mint(Some(authority))),
+        vec![MintRisk::FreezeAuthority(authority)]
+    );
+    assert!(mint_risks(&spl_mint(None)).is_empty());
+}
+
+#[test]
+fn risky_token_2022_extensions_are_reported() {
+    let delegate = Pubkey::new_unique();
+    let hook_program = Pubkey::new_unique();
+    let pauser = Pubkey::new_unique();
+    let mut transfer_hook = vec![0u8; 32];
+    transfer_hook.extend_from_slice(hook_program.as_ref());
+    let mut pausable = pauser.to_bytes().to_vec();
+    pausable.push(0);
+    let data = token_2022_mint(&[
+        (6, vec![2]), // DefaultAccountState::Frozen
+        (9, vec![]),  // NonTransferable
+        (12, delegate.to_bytes().to_vec()),
+        (14, transfer_hook),
+        (26, pausable),
+    ]);
+    assert_eq!(mint_extensions(&data).len(), 5);
+    assert_eq!(
+        mint_risks(&data),
+        vec![
+            MintRisk::DefaultFrozen,
+            MintRisk::NonTransferable,
+            MintRisk::PermanentDelegate(delegate),
+            MintRisk::TransferHook(hook_program),
+            MintRisk::Pausable(pauser),
+        ]
+    );
+    assert_eq!(
+        MintRisk::PermanentDelegate(delegate).to_string(),
+        format!("permanent delegate {}", delegate)
+    );
+}
+
+#[test]
+fn cleared_extensions_are_not_risks() {
+    let data = token_2022_mint(&[
+        (1, vec![0u8; 108]), // TransferFeeConfig
+        (6, vec![1]),        // DefaultAccountState::Initialized
+        (12, vec![0u8; 32]),
+        (14, vec![0u8; 64]),
+        (26, vec![0u8; 33]),
+    ]);
+    assert!(mint_risks(&data).is_empty());
+
+    // 截断的扩展不读取
+    let mut truncated = token_2022_mint(&[(6, vec![1])]);
+    truncated.extend_from_slice(&12u16.to_le_bytes());
+    truncated.extend_from_slice(&32u16.to_le_bytes());
+    truncated.extend_from_slice(&[7u8; 16]);
+    assert_eq!(mint_extensions(&truncated).len(), 1);
+    assert!(mint_risks(&truncated).is_empty());
+}
+
+#[test]
+fn allow_risky_defaults_to_false() {
+    let config = parse_config("").unwrap();
+    assert!(!config.routing.mint_config_list[0].allow_risky());
+    let config = parse_config("allow_risky = true").unwrap();
+    assert!(config.routing.mint_config_list[0].allow_risky());
+}
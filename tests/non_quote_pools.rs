@@ -0,0 +1,121 @@
+//! 两侧都不是报价货币的池子：配置解析、加载器按策略拒绝或加载，以及报价时跳过
+
+mod common;
+
+use common::{load_fixture, TestConfig};
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::{sol_mint, usdc_mint, usdt_mint};
+use solana_onchain_arbitrage_bot::dex::{quote_side, solfi, NonQuotePool, QuoteSide};
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, NonQuotePoolPolicy};
+use solana_onchain_arbitrage_bot::quote::quote_accounts;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+fn parse_config(routing: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        routing,
+        ..Default::default()
+    }
+    .parse()
+}
+
+/// 以 USDT 计价的 SOL，加载 SOL/USDC 的 Solfi 池子
+fn load_solfi(policy: NonQuotePoolPolicy) -> (Pubkey, MintPoolData, anyhow::Result<()>) {
+    let (pool, account) = load_fixture("solfi");
+    let mut accounts = HashMap::from([(pool, account)]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+    let mut pool_data = MintPoolData::new(*sol_mint(), Pubkey::new_unique(), spl_token::ID)
+        .with_quote_mint(*usdt_mint())
+        .with_non_quote_pool_policy(policy);
+    let result = solfi::loader::load_pool(&pool, &mut get_account, &mut pool_data);
+    (pool, pool_data, result)
+}
+
+#[test]
+fn non_quote_pools_are_rejected_by_default() {
+    let config = parse_config("").unwrap();
+    assert_eq!(config.routing.non_quote_pools, NonQuotePoolPolicy::Reject);
+    let config = parse_config("non_quote_pools = \"allow\"").unwrap();
+    assert_eq!(config.routing.non_quote_pools, NonQuotePoolPolicy::Allow);
+    assert!(parse_config("non_quote_pools = \"maybe\"").is_err());
+
+    for policy in [NonQuotePoolPolicy::Reject, NonQuotePoolPolicy::Skip] {
+        let (pool, pool_data, result) = load_solfi(policy);
+        let error = result.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<NonQuotePool>(),
+            Some(&NonQuotePool {
+                dex: "Solfi",
+                pool,
+                quote_mint: *usdt_mint(),
+            })
+        );
+        assert!(pool_data.solfi_pools.is_empty());
+    }
+}
+
+#[test]
+fn allowed_non_quote_pools_use_the_other_side_as_the_quote_side() {
+    let (pool, pool_data, result) = load_solfi(NonQuotePoolPolicy::Allow);
+    result.unwrap();
+
+    assert_eq!(
+        pool_data.non_quote_pools,
+        HashMap::from([(pool, *usdc_mint())])
+    );
+    let loaded = &pool_data.solfi_pools[0];
+    assert_eq!(
+        loaded.token_sol_vault,
+        Pubkey::from_str("7pW88ZRMyRDUtHYPFWJ7ritHrpqPncoSbi71Cuuqfsv7").unwrap()
+    );
+    assert_eq!(
+        loaded.token_x_vault,
+        Pubkey::from_str("FikXxHwCW9CzzSC5G4Muo3h2wJUabPN6RgJWrNda5rac").unwrap()
+    );
+}
+
+#[test]
+fn allowed_non_quote_pools_are_left_out_of_quotes() {
+    let mint = Pubkey::new_unique();
+    let other = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(mint, Pubkey::new_unique(), spl_token::ID)
+        .with_non_quote_pool_policy(NonQuotePoolPolicy::Allow);
+
+    let [quote_pool, token_pool] = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let sol = pool_data.quote_mint;
+    assert_eq!(
+        quote_side(&mut pool_data, "Raydium", &quote_pool, &mint, &sol).unwrap(),
+        QuoteSide::B
+    );
+    assert_eq!(
+        quote_side(&mut pool_data, "Raydium", &token_pool, &mint, &other).unwrap(),
+        QuoteSide::B
+    );
+    assert_eq!(
+        quote_side(&mut pool_data, "Raydium", &token_pool, &other, &mint).unwrap(),
+        QuoteSide::A
+    );
+    assert_eq!(
+        pool_data.non_quote_pools,
+        HashMap::from([(token_pool, other)])
+    );
+
+    for pool in [quote_pool, token_pool] {
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+    }
+    let quoted = quote_accounts(&pool_data);
+    assert_eq!(quoted.len(), 2);
+    assert!(quoted.iter().all(
+        |(vault, _)| *vault == pool_data.raydium_pools[0].token_vault
+            || *vault == pool_data.raydium_pools[0].sol_vault
+    ));
+
+    // 只保留报价池子时一并清除记录
+    pool_data.retain_pools(|pool| *pool == quote_pool);
+    assert!(pool_data.non_quote_pools.is_empty());
+}
@@ -0,0 +1,579 @@
+//! 池子账户解析器测试
+//!
+//! 用 `tests/fixtures` 下按解析器偏移量构造的账户数据检查字段读取、判别符和版本校验。
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use solana_onchain_arbitrage_bot::ata::TOKEN_2022_PROGRAM_ID;
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::meteora::constants::{
+    damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id, dlmm_program_id,
+};
+use solana_onchain_arbitrage_bot::dex::meteora::dammv2_info::MeteoraDAmmV2Info;
+use solana_onchain_arbitrage_bot::dex::meteora::dlmm_info::DlmmInfo;
+use solana_onchain_arbitrage_bot::dex::moonshot::{moonshot_program_id, MoonshotCurve};
+use solana_onchain_arbitrage_bot::dex::perena::{perena_program_id, PerenaPoolInfo};
+use solana_onchain_arbitrage_bot::dex::pump::{pump_program_id, PumpAmmInfo};
+use solana_onchain_arbitrage_bot::dex::raydium::{
+    raydium_clmm_program_id, raydium_cp_program_id, raydium_program_id, OpenBookMarket, PoolState,
+    RaydiumAmmInfo, RaydiumCpAmmInfo,
+};
+use solana_onchain_arbitrage_bot::dex::solfi::constants::solfi_program_id;
+use solana_onchain_arbitrage_bot::dex::solfi::info::SolfiInfo;
+use solana_onchain_arbitrage_bot::dex::vertigo::{vertigo_program_id, VertigoInfo};
+use solana_onchain_arbitrage_bot::dex::whirlpool::constants::whirlpool_program_id;
+use solana_onchain_arbitrage_bot::dex::whirlpool::state::{TickArray, Whirlpool};
+use solana_onchain_arbitrage_bot::dex::zerofi::{zerofi_program_id, ZeroFiInfo};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+struct Fixture {
+    pubkey: Pubkey,
+    owner: Pubkey,
+    data: Vec<u8>,
+}
+
+/// 读取 `tests/fixtures/<name>.json` 中的账户数据
+fn load_fixture(name: &str) -> Fixture {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+
+    let account = &json["account"];
+    assert_eq!(
+        account["data"][1], "base64",
+        "{} must be base64 encoded",
+        name
+    );
+
+    Fixture {
+        pubkey: key(json["pubkey"].as_str().unwrap()),
+        owner: key(account["owner"].as_str().unwrap()),
+        data: BASE64_STANDARD
+            .decode(account["data"][0].as_str().unwrap())
+            .unwrap(),
+    }
+}
+
+fn key(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+#[test]
+fn pump_amm_info() {
+    let fixture = load_fixture("pump_amm");
+    assert_eq!(fixture.owner, *pump_program_id());
+
+    let info = PumpAmmInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        info.base_mint,
+        key("ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz")
+    );
+    assert_eq!(info.quote_mint, *sol_mint());
+    assert_eq!(
+        info.pool_base_token_account,
+        key("FqbqRtDr9JjvTBnebvxQn8s9UizSF7P92ec8i2icSrNi")
+    );
+    assert_eq!(
+        info.pool_quote_token_account,
+        key("3VcivVx1Cnit9c5sFbneojaDqzpdYoMe5bjJTLXsNU8J")
+    );
+
+    let coin_creator = key("FETbbxh5NWE8k9xdhkAbybbWJNYAUz9MoCaS96wMgo1b");
+    let expected_authority = Pubkey::find_program_address(
+        &[b"creator_vault", coin_creator.as_ref()],
+        pump_program_id(),
+    )
+    .0;
+    assert_eq!(info.coin_creator_vault_authority, expected_authority);
+}
+
+#[test]
+fn raydium_amm_info() {
+    let fixture = load_fixture("raydium_amm_v4");
+    assert_eq!(fixture.owner, *raydium_program_id());
+
+    let info = RaydiumAmmInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(info.coin_mint, *sol_mint());
+    assert_eq!(info.pc_mint, key(USDC_MINT));
+    assert_eq!(
+        info.coin_vault,
+        key("DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz")
+    );
+    assert_eq!(
+        info.pc_vault,
+        key("HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz")
+    );
+    assert_eq!(
+        info.open_orders,
+        key("HmiHHzq4Fym9e1D4qzLS6LDDM3tNsCTBPDWHTLZ763jY")
+    );
+    assert_eq!(
+        info.target_orders,
+        key("CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR")
+    );
+    assert_eq!(
+        info.market,
+        key("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6")
+    );
+    assert_eq!(
+        info.market_program,
+        key("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX")
+    );
+}
+
+#[test]
+fn openbook_market() {
+    let fixture = load_fixture("openbook_market");
+    assert_eq!(
+        fixture.owner,
+        key("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX")
+    );
+
+    let market = OpenBookMarket::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        market.bids,
+        key("5jWUncPNBMZJ3sTHKmMLszypVkoRK6bfEQMQUHweeQnh")
+    );
+    assert_eq!(
+        market.asks,
+        key("EaXdHx7x3mdGA38j5RSmKYSXMzAFzzUXCLNBEDXDn1d5")
+    );
+    assert_eq!(
+        market.event_queue,
+        key("8CvwxZ9Db6XbLD46NZwwmVDZZRDy7eydFcAGkXKh9axa")
+    );
+    assert_eq!(
+        market.coin_vault,
+        key("CKxTHwM9fPMRRvZmFnFoqKNd9pQR21c5Aq9bh5h9oghX")
+    );
+    assert_eq!(
+        market.pc_vault,
+        key("6A5NHCj1yF6urc9wZNe6Bcjj4LVszQNj5DwAWG97yzMu")
+    );
+    assert_eq!(
+        market
+            .vault_signer(&fixture.pubkey, &fixture.owner)
+            .unwrap(),
+        key("CTz5UMLQm2SRWHzQnU62Pi4yJqbNGjgRBHqqp6oDHfF7")
+    );
+
+    // 池子账户不是市场账户
+    let pool = load_fixture("raydium_amm_v4");
+    assert!(OpenBookMarket::load_checked(&pool.data).is_err());
+}
+
+#[test]
+fn raydium_cp_amm_info() {
+    let fixture = load_fixture("raydium_cpmm");
+    assert_eq!(fixture.owner, *raydium_cp_program_id());
+
+    let info = RaydiumCpAmmInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(info.token_0_mint, *sol_mint());
+    assert_eq!(
+        info.token_1_mint,
+        key("69k7NXVJvykLFsXbHHEH4XKRYMWg7TQaEXFNRgutoftL")
+    );
+    assert_eq!(
+        info.token_0_vault,
+        key("CDcrjHudQYdYBbmcKfbPNw3s78RGFjT63v3QBBYAozdq")
+    );
+    assert_eq!(
+        info.token_1_vault,
+        key("DLTmAQ5QV4BqJpVZan1A6QtZqFESFmFDdfpbW69xa2vo")
+    );
+    assert_eq!(
+        info.amm_config,
+        key("D4FPEruKEHrG5TenZ2mpDGEfu1iUvTiqBxvpU8HLBvC2")
+    );
+    assert_eq!(
+        info.observation_key,
+        key("2kYUZqvwKuMg3vsc8c9yTFpXqcTfu4U1DpwEqMk3XABd")
+    );
+}
+
+#[test]
+fn dlmm_info() {
+    let fixture = load_fixture("meteora_dlmm");
+    assert_eq!(fixture.owner, *dlmm_program_id());
+
+    let info = DlmmInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        info.token_x_mint,
+        key("9jfJfff6fSEk6vvMXL1db5NsMUErqLjZdn2GD1SUqyQV")
+    );
+    assert_eq!(info.token_y_mint, *sol_mint());
+    assert_eq!(
+        info.token_x_vault,
+        key("36eZPEcRbP4j5mAm2CegeY8k3to6yccG2hv28zQCyvYa")
+    );
+    assert_eq!(
+        info.token_y_vault,
+        key("9qLKqs4mXg9MwyMXDzNuTmJi16Kz2SX7NNn1C9Xygyvz")
+    );
+    assert_eq!(
+        info.oracle,
+        key("EpmPxnVb8sob7mfrmmaWGY3ttySLEcpX3NNd2L672iSf")
+    );
+    assert_eq!(info.active_id, -4527);
+    assert_eq!(info.lb_pair.bin_step, 25);
+
+    // active_id = -4527 落在第 -46 个 bin array，取前后各一个
+    let bin_arrays = info.calculate_bin_arrays(&fixture.pubkey).unwrap();
+    let expected: Vec<Pubkey> = [-47i64, -46, -45]
+        .iter()
+        .map(|index| {
+            Pubkey::find_program_address(
+                &[b"bin_array", fixture.pubkey.as_ref(), &index.to_le_bytes()],
+                dlmm_program_id(),
+            )
+            .0
+        })
+        .collect();
+    assert_eq!(bin_arrays, expected);
+}
+
+#[test]
+fn whirlpool() {
+    let fixture = load_fixture("whirlpool");
+    assert_eq!(fixture.owner, *whirlpool_program_id());
+    assert_eq!(fixture.data.len(), Whirlpool::LEN);
+
+    let whirlpool = Whirlpool::try_deserialize(&fixture.data).unwrap();
+    assert_eq!(
+        whirlpool.whirlpools_config,
+        key("2LecshUwdy9xi7meFgHtFJQNSKk4KdTrcpvaB56dP2NQ")
+    );
+    assert_eq!(whirlpool.tick_spacing, 4);
+    assert_eq!(whirlpool.fee_rate, 400);
+    assert_eq!(whirlpool.liquidity, 123_456_789_012_345);
+    assert_eq!(whirlpool.sqrt_price, 7_425_837_616_213_381_562);
+    assert_eq!(whirlpool.tick_current_index, -18200);
+    assert_eq!(whirlpool.token_mint_a, *sol_mint());
+    assert_eq!(
+        whirlpool.token_vault_a,
+        key("6YKxbnPEb3ruVoEgmqPCWYzhQb1bdbh3D8dz6PsfQY1m")
+    );
+    assert_eq!(whirlpool.token_mint_b, key(USDC_MINT));
+    assert_eq!(
+        whirlpool.token_vault_b,
+        key("5u6GArTGDUFvzf373N2HT1ocPrUJ5xHbkZ12XCd38dip")
+    );
+}
+
+#[test]
+fn whirlpool_tick_arrays() {
+    let fixture = load_fixture("whirlpool_tick_array");
+    assert_eq!(fixture.owner, *whirlpool_program_id());
+    assert_eq!(fixture.data.len(), TickArray::LEN);
+
+    let array = TickArray::try_deserialize(&fixture.data).unwrap();
+    assert_eq!(array.start_tick_index, -18304);
+    assert_eq!(array.whirlpool, load_fixture("whirlpool").pubkey);
+    let lower = array.ticks[16];
+    assert!(lower.initialized);
+    assert_eq!(lower.liquidity_net, 100_000_000_000_000);
+    assert_eq!(lower.liquidity_gross, 100_000_000_000_000);
+    assert_eq!(lower.fee_growth_outside_a, 1_000);
+    assert_eq!(lower.fee_growth_outside_b, 2_000);
+    assert_eq!(lower.reward_growths_outside, [1, 2, 3]);
+    assert_eq!(array.ticks[36].liquidity_net, -100_000_000_000_000);
+    assert_eq!(
+        array.ticks.iter().filter(|tick| tick.initialized).count(),
+        2
+    );
+
+    // 动态布局只为已初始化的 tick 分配空间
+    let fixture = load_fixture("whirlpool_dynamic_tick_array");
+    assert_eq!(fixture.owner, *whirlpool_program_id());
+    let array = TickArray::try_deserialize(&fixture.data).unwrap();
+    assert_eq!(array.start_tick_index, -17952);
+    assert_eq!(array.whirlpool, load_fixture("whirlpool").pubkey);
+    assert_eq!(array.ticks[8].liquidity_net, -5_000_000_000_000);
+    assert_eq!(array.ticks[8].liquidity_gross, 5_000_000_000_000);
+    assert_eq!(
+        array.ticks.iter().filter(|tick| tick.initialized).count(),
+        1
+    );
+
+    assert!(TickArray::try_deserialize(&fixture.data[..fixture.data.len() - 1]).is_err());
+    let mut data = fixture.data.clone();
+    data[..8].copy_from_slice(&anchor_discriminator("Whirlpool"));
+    assert!(TickArray::try_deserialize(&data).is_err());
+}
+
+#[test]
+fn raydium_clmm_pool_state() {
+    let fixture = load_fixture("raydium_clmm");
+    assert_eq!(fixture.owner, *raydium_clmm_program_id());
+
+    let pool = PoolState::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        pool.amm_config,
+        key("5dRX6N67SZy2XfgxRiWAhksWxAw2Zvo8Gx8zij4LRTQT")
+    );
+    assert_eq!(pool.token_mint_0, *sol_mint());
+    assert_eq!(pool.token_mint_1, key(USDC_MINT));
+    assert_eq!(
+        pool.token_vault_0,
+        key("CXECoL4rYSQMzt959zeRACwz2vZLAhDryx15ook9MzL9")
+    );
+    assert_eq!(
+        pool.token_vault_1,
+        key("Dc8cqKfHruRYBjGwQKfvgisHYwwMdjSCPM55axtjAWob")
+    );
+    assert_eq!(
+        pool.observation_key,
+        key("5dJTzVVGRbjDSsXeRvXgqhzFqUYxX2u4uC63CzQwXP98")
+    );
+    assert_eq!(pool.tick_spacing, 1);
+    assert_eq!(pool.liquidity, 987_654_321);
+    assert_eq!(pool.sqrt_price_x64, 7_425_837_616_213_381_562);
+    assert_eq!(pool.tick_current, -18109);
+}
+
+#[test]
+fn solfi_info() {
+    let fixture = load_fixture("solfi");
+    assert_eq!(fixture.owner, *solfi_program_id());
+
+    let info = SolfiInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(info.base_mint, *sol_mint());
+    assert_eq!(info.quote_mint, key(USDC_MINT));
+    assert_eq!(
+        info.base_vault,
+        key("FikXxHwCW9CzzSC5G4Muo3h2wJUabPN6RgJWrNda5rac")
+    );
+    assert_eq!(
+        info.quote_vault,
+        key("7pW88ZRMyRDUtHYPFWJ7ritHrpqPncoSbi71Cuuqfsv7")
+    );
+}
+
+#[test]
+fn zerofi_info() {
+    let fixture = load_fixture("zerofi");
+    assert_eq!(fixture.owner, *zerofi_program_id());
+
+    let info = ZeroFiInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        info.base_mint,
+        key("5bKZv1e7rmPVb3eeRKspVFoB8WPar61eFgDMyuJNKU4F")
+    );
+    assert_eq!(info.quote_mint, *sol_mint());
+    assert_eq!(
+        info.base_vault_info,
+        key("5tw89kG3KGnRUUjAHzcCm7yUa6kwXhvM1hpJ1KcUfnP")
+    );
+    assert_eq!(
+        info.base_vault,
+        key("71d1exWqaLGwJJG717auBpX41vFekqhnccBa7ozE6QFn")
+    );
+    assert_eq!(
+        info.quote_vault_info,
+        key("Bip58RtXmgcRyDR2C9BeNrzM3MWccdenEjPE4ci3aaev")
+    );
+    assert_eq!(
+        info.quote_vault,
+        key("BxFjgVECFptzitR1Eg8wuBuiKg5vpa5Euy1f1AFUmqvN")
+    );
+}
+
+#[test]
+fn vertigo_info() {
+    let fixture = load_fixture("vertigo");
+    assert_eq!(fixture.owner, *vertigo_program_id());
+
+    let info = VertigoInfo::load_checked(&fixture.data, &fixture.pubkey).unwrap();
+    assert_eq!(info.mint_a, *sol_mint());
+    assert_eq!(
+        info.mint_b,
+        key("7pwxk89744bDs2VZPZdY4s6MMUAC1HpNvV2kWZ4DtYTX")
+    );
+    assert_eq!(info.pool, fixture.pubkey);
+}
+
+#[test]
+fn moonshot_curve() {
+    let fixture = load_fixture("moonshot");
+    assert_eq!(fixture.owner, *moonshot_program_id());
+
+    let curve = MoonshotCurve::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        curve.mint,
+        key("6mWoqQekzv4Av9CyPFM3fkLPoLZjzVztk3s9fbV4MzZL")
+    );
+    assert_eq!(curve.total_supply, 1_000_000_000_000_000_000);
+    assert_eq!(curve.curve_amount, 768_000_000_000_000_000);
+    assert_eq!(curve.decimals, 9);
+    assert!(curve.is_sol_collateral());
+    assert_eq!(curve.marketcap_threshold, 345_000_000_000);
+    assert_eq!(curve.coef_b, 25);
+    assert_eq!(curve.migration_target, 0);
+}
+
+#[test]
+fn perena_pool_info() {
+    let fixture = load_fixture("perena");
+    assert_eq!(fixture.owner, *perena_program_id());
+
+    let info = PerenaPoolInfo::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        info.authority,
+        key("E7oyYqd1aZNzyEJckFb1iGo6zcLARrs9DmhWhy7QRDAm")
+    );
+    assert_eq!(
+        info.mints,
+        vec![
+            key(USDC_MINT),
+            key("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"),
+            key("2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo"),
+        ]
+    );
+    assert_eq!(info.vaults.len(), 3);
+    assert_eq!(
+        info.vault(&key(USDC_MINT)),
+        Some(key("5a8g5M7MSdbuDdpNJ6aTuxabGZjRZCtAaPPvERtaiz4c"))
+    );
+    assert_eq!(info.vault(sol_mint()), None);
+    assert_eq!(info.fee_rate, 100);
+
+    // 代币数超过池子的上限
+    let mut data = fixture.data.clone();
+    data[40] = 9;
+    assert!(PerenaPoolInfo::load_checked(&data).is_err());
+}
+
+#[test]
+fn meteora_damm_v2_info() {
+    let fixture = load_fixture("meteora_damm_v2");
+    assert_eq!(fixture.owner, *damm_v2_program_id());
+
+    let info = MeteoraDAmmV2Info::load_checked(&fixture.data).unwrap();
+    assert_eq!(
+        info.base_mint,
+        key("7VzLoiRLa5EXH8SeCN1gXR9MUvHAm8kqyEHF1vbeTBRP")
+    );
+    assert_eq!(info.quote_mint, *sol_mint());
+    assert_eq!(
+        info.base_vault,
+        key("54Sy8nC2Sj2ymC4jaDcdgkjxF5v2khXGNyXS1XH6PZse")
+    );
+    assert_eq!(
+        info.quote_vault,
+        key("8ZVaBHNek726MD5RWfcQuiJ3bNSK3MxJBqcsv2UFXkTv")
+    );
+    assert_eq!(info.base_token_program, spl_token::ID);
+    assert_eq!(info.quote_token_program, spl_token::ID);
+
+    // token_a_flag = 1 表示 Token 2022，未知的标志不解析
+    let mut data = fixture.data.clone();
+    data[482] = 1;
+    let info = MeteoraDAmmV2Info::load_checked(&data).unwrap();
+    assert_eq!(info.base_token_program, key(TOKEN_2022_PROGRAM_ID));
+    assert_eq!(info.quote_token_program, spl_token::ID);
+    data[483] = 2;
+    assert!(MeteoraDAmmV2Info::load_checked(&data).is_err());
+}
+
+#[test]
+fn meteora_damm_v2_authorities_are_program_pdas() {
+    let program = *damm_v2_program_id();
+    assert_eq!(
+        *damm_v2_pool_authority(),
+        Pubkey::find_program_address(&[b"pool_authority"], &program).0
+    );
+    assert_eq!(
+        *damm_v2_event_authority(),
+        Pubkey::find_program_address(&[b"__event_authority"], &program).0
+    );
+}
+
+/// 断言解析失败，并且错误说明账户版本不受支持
+fn assert_unsupported<T>(name: &str, result: anyhow::Result<T>) {
+    match result {
+        Ok(_) => panic!("{}: parsed an account of another version", name),
+        Err(e) => assert!(
+            e.to_string().contains("Unsupported account version"),
+            "{}: {}",
+            name,
+            e
+        ),
+    }
+}
+
+#[test]
+fn parsers_reject_other_account_versions() {
+    // Anchor 账户换成同一程序的其他账户类型
+    let other_discriminator = |name: &str| {
+        let mut data = load_fixture(name).data;
+        data[..8].copy_from_slice(&anchor_discriminator("Config"));
+        data
+    };
+    assert_unsupported(
+        "pump_amm",
+        PumpAmmInfo::load_checked(&other_discriminator("pump_amm")),
+    );
+    assert_unsupported(
+        "raydium_cpmm",
+        RaydiumCpAmmInfo::load_checked(&other_discriminator("raydium_cpmm")),
+    );
+    assert_unsupported(
+        "raydium_clmm",
+        PoolState::load_checked(&other_discriminator("raydium_clmm")),
+    );
+    assert_unsupported(
+        "meteora_dlmm",
+        DlmmInfo::load_checked(&other_discriminator("meteora_dlmm")),
+    );
+    assert_unsupported(
+        "meteora_damm_v2",
+        MeteoraDAmmV2Info::load_checked(&other_discriminator("meteora_damm_v2")),
+    );
+    assert_unsupported(
+        "moonshot",
+        MoonshotCurve::load_checked(&other_discriminator("moonshot")),
+    );
+    assert_unsupported(
+        "perena",
+        PerenaPoolInfo::load_checked(&other_discriminator("perena")),
+    );
+    assert_unsupported(
+        "whirlpool",
+        Whirlpool::try_deserialize(&other_discriminator("whirlpool")).map_err(Into::into),
+    );
+
+    // 没有类型标识的账户按长度核对
+    let longer = |name: &str| {
+        let mut data = load_fixture(name).data;
+        data.extend_from_slice(&[0; 8]);
+        data
+    };
+    assert_unsupported(
+        "raydium_amm_v4",
+        RaydiumAmmInfo::load_checked(&longer("raydium_amm_v4")),
+    );
+    assert_unsupported(
+        "openbook_market",
+        OpenBookMarket::load_checked(&longer("openbook_market")),
+    );
+    assert_unsupported("solfi", SolfiInfo::load_checked(&longer("solfi")));
+    assert_unsupported("zerofi", ZeroFiInfo::load_checked(&longer("zerofi")));
+    let vertigo = load_fixture("vertigo");
+    assert_unsupported(
+        "vertigo",
+        VertigoInfo::load_checked(&longer("vertigo"), &vertigo.pubkey),
+    );
+
+    // OpenBook 的账户标志不是已初始化的市场
+    let mut market = load_fixture("openbook_market").data;
+    market[5] = 1;
+    assert_unsupported("openbook_market", OpenBookMarket::load_checked(&market));
+}
@@ -0,0 +1,356 @@
+//! 每个池子在执行程序 swap 指令中的账户顺序测试
+
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::dex::meteora::constants::{
+    damm_program_id, damm_v2_event_authority, damm_v2_pool_authority, damm_v2_program_id,
+    dlmm_event_authority, dlmm_program_id, vault_program_id,
+};
+use solana_onchain_arbitrage_bot::dex::moonshot::{
+    moonshot_config, moonshot_dex_fee, moonshot_helio_fee, moonshot_program_id,
+};
+use solana_onchain_arbitrage_bot::dex::perena::perena_program_id;
+use solana_onchain_arbitrage_bot::dex::pump::{
+    pump_event_authority, pump_global_config, pump_program_id,
+};
+use solana_onchain_arbitrage_bot::dex::raydium::{
+    raydium_authority, raydium_clmm_program_id, raydium_cp_authority, raydium_cp_program_id,
+    raydium_program_id,
+};
+use solana_onchain_arbitrage_bot::dex::solfi::constants::solfi_program_id;
+use solana_onchain_arbitrage_bot::dex::vertigo::vertigo_program_id;
+use solana_onchain_arbitrage_bot::dex::whirlpool::constants::whirlpool_program_id;
+use solana_onchain_arbitrage_bot::dex::zerofi::zerofi_program_id;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
+
+fn pool_data() -> MintPoolData {
+    MintPoolData::new(*usdc_mint(), Pubkey::new_unique(), spl_token::ID)
+}
+
+/// `n` 个不同的地址
+fn keys(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+fn s(key: &Pubkey) -> String {
+    key.to_string()
+}
+
+fn w(key: Pubkey) -> AccountMeta {
+    AccountMeta::new(key, false)
+}
+
+fn r(key: Pubkey) -> AccountMeta {
+    AccountMeta::new_readonly(key, false)
+}
+
+/// 只有一个池子时，返回该池子的 DEX 名称、地址和账户
+fn single(pool_data: &MintPoolData) -> (&'static str, Pubkey, Vec<AccountMeta>) {
+    let mut pools = pool_data.pool_accounts();
+    assert_eq!(pools.len(), 1);
+    let pool = pools.remove(0);
+    (pool.dex, pool.pool, pool.accounts)
+}
+
+#[test]
+fn constant_product_pools_list_program_authority_pool_and_vaults() {
+    let k = keys(5);
+    let mut data = pool_data();
+    data.add_raydium_pool(k[0], k[1], k[2]);
+    assert_eq!(
+        single(&data),
+        (
+            "raydium",
+            k[0],
+            vec![
+                r(*raydium_program_id()),
+                r(*raydium_authority()),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+            ]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_raydium_cp_pool(k[0], k[1], k[2], k[3], k[4]);
+    assert_eq!(
+        single(&data),
+        (
+            "raydium_cp",
+            k[0],
+            vec![
+                r(*raydium_cp_program_id()),
+                r(*raydium_cp_authority()),
+                w(k[0]),
+                r(k[3]),
+                w(k[1]),
+                w(k[2]),
+                w(k[4]),
+            ]
+        )
+    );
+}
+
+#[test]
+fn pump_pools_pass_fee_and_creator_accounts_after_the_vaults() {
+    let k = keys(7);
+    let mut data = pool_data();
+    data.add_pump_pool(k[0], k[1], k[2], k[3], k[4], k[5], k[6]);
+    assert_eq!(
+        single(&data),
+        (
+            "pump",
+            k[0],
+            vec![
+                r(*pump_program_id()),
+                r(*pump_global_config()),
+                r(*pump_event_authority()),
+                r(k[3]),
+                r(k[0]),
+                w(k[1]),
+                w(k[2]),
+                w(k[4]),
+                w(k[5]),
+                r(k[6]),
+            ]
+        )
+    );
+}
+
+#[test]
+fn concentrated_pools_add_the_memo_program_and_trailing_arrays() {
+    let k = keys(8);
+    let mut data = pool_data();
+    data.add_dlmm_pool(k[0], k[1], k[2], k[3], vec![k[4], k[5]], None);
+    assert_eq!(
+        single(&data),
+        (
+            "meteora_dlmm",
+            k[0],
+            vec![
+                r(*dlmm_program_id()),
+                w(*dlmm_event_authority()),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+                w(k[3]),
+                w(k[4]),
+                w(k[5]),
+            ]
+        )
+    );
+
+    // Token 2022 的代币在程序之后多一个 memo 程序
+    let mut data = pool_data();
+    data.add_dlmm_pool(k[0], k[1], k[2], k[3], vec![k[4]], Some(k[7]));
+    let (_, _, accounts) = single(&data);
+    assert_eq!(
+        accounts[..3],
+        [r(*dlmm_program_id()), w(*dlmm_event_authority()), r(k[7])]
+    );
+    assert_eq!(accounts.len(), 8);
+
+    let mut data = pool_data();
+    data.add_whirlpool_pool(k[0], k[1], k[2], k[3], vec![k[4], k[5], k[6]], Some(k[7]));
+    assert_eq!(
+        single(&data),
+        (
+            "whirlpool",
+            k[0],
+            vec![
+                r(*whirlpool_program_id()),
+                r(k[7]),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+                w(k[3]),
+                w(k[4]),
+                w(k[5]),
+                w(k[6]),
+            ]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_raydium_clmm_pool(k[0], k[1], k[2], k[3], k[4], vec![k[5], k[6]], None);
+    let (dex, _, accounts) = single(&data);
+    assert_eq!(dex, "raydium_clmm");
+    assert_eq!(accounts[0], r(*raydium_clmm_program_id()));
+    assert_eq!(accounts[1], w(k[0]));
+    assert_eq!(accounts[2], r(k[1]));
+    assert_eq!(accounts[3], w(k[2]));
+    // bitmap extension 由池子地址推导，位于 observation 和两个 vault 之间
+    assert_eq!(accounts[5..], [w(k[3]), w(k[4]), w(k[5]), w(k[6])]);
+}
+
+#[test]
+fn meteora_pools_list_vault_program_accounts() {
+    let k = keys(11);
+    let mut data = pool_data();
+    data.add_meteora_damm_pool(
+        k[0], k[1], k[2], k[3], k[4], k[5], k[6], k[7], k[8], k[9], k[10],
+    );
+    let mut expected = vec![r(*damm_program_id()), r(*vault_program_id())];
+    expected.extend(k.iter().copied().map(w));
+    assert_eq!(single(&data), ("meteora_damm", k[0], expected));
+
+    let mut data = pool_data();
+    data.add_meteora_damm_v2_pool(k[0], k[1], k[2], k[3], k[4]);
+    let pool = &data.meteora_damm_v2_pools[0];
+    assert_eq!(pool.token_x_program, k[3]);
+    assert_eq!(pool.token_sol_program, k[4]);
+    assert_eq!(
+        single(&data),
+        (
+            "meteora_damm_v2",
+            k[0],
+            vec![
+                r(*damm_v2_program_id()),
+                r(*damm_v2_event_authority()),
+                r(*damm_v2_pool_authority()),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+            ]
+        )
+    );
+}
+
+#[test]
+fn other_pools_match_their_swap_legs() {
+    let k = keys(5);
+    let mut data = pool_data();
+    data.add_solfi_pool(k[0], k[1], k[2]);
+    assert_eq!(
+        single(&data),
+        (
+            "solfi",
+            k[0],
+            vec![
+                r(*solfi_program_id()),
+                r(sysvar::instructions::ID),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+            ]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_vertigo_pool(k[0], k[1], k[2], k[3]);
+    assert_eq!(
+        single(&data),
+        (
+            "vertigo",
+            k[0],
+            vec![r(*vertigo_program_id()), w(k[0]), r(k[1]), w(k[2]), w(k[3]),]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_moonshot_pool(k[0], k[1]);
+    assert_eq!(
+        single(&data),
+        (
+            "moonshot",
+            k[0],
+            vec![
+                r(*moonshot_program_id()),
+                w(*moonshot_dex_fee()),
+                w(*moonshot_helio_fee()),
+                r(*moonshot_config()),
+                w(k[0]),
+                w(k[1]),
+            ]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_zerofi_pool(k[0], k[1], k[2], k[3], k[4]);
+    assert_eq!(
+        single(&data),
+        (
+            "zerofi",
+            k[0],
+            vec![
+                r(*zerofi_program_id()),
+                r(sysvar::instructions::ID),
+                w(k[0]),
+                w(k[1]),
+                w(k[2]),
+                w(k[3]),
+                w(k[4]),
+            ]
+        )
+    );
+
+    let mut data = pool_data();
+    data.add_perena_pool(k[0], k[1], k[2]);
+    assert_eq!(
+        single(&data),
+        (
+            "perena",
+            k[0],
+            vec![r(*perena_program_id()), w(k[0]), w(k[1]), w(k[2])]
+        )
+    );
+}
+
+#[test]
+fn pools_are_grouped_in_the_executor_dex_order() {
+    let k = keys(7);
+    let mut data = pool_data();
+    // 按与执行程序不同的顺序加入
+    data.add_perena_pool(k[0], k[1], k[2]);
+    data.add_solfi_pool(k[3], k[1], k[2]);
+    data.add_pump_pool(k[4], k[1], k[2], k[1], k[1], k[1], k[1]);
+    data.add_raydium_cp_pool(k[5], k[1], k[2], k[1], k[1]);
+    data.add_raydium_pool(k[6], k[1], k[2]);
+
+    let pools = data.pool_accounts();
+    let order: Vec<(&str, Pubkey)> = pools.iter().map(|p| (p.dex, p.pool)).collect();
+    assert_eq!(
+        order,
+        vec![
+            ("raydium", k[6]),
+            ("raydium_cp", k[5]),
+            ("pump", k[4]),
+            ("solfi", k[3]),
+            ("perena", k[0]),
+        ]
+    );
+    // 与 `pool_addresses` 包含同样的池子
+    let mut addresses = data.pool_addresses();
+    addresses.sort();
+    let mut order = order;
+    order.sort();
+    assert_eq!(addresses, order);
+}
+
+#[test]
+fn summary_table_has_one_row_per_pool() {
+    let k = keys(11);
+    let mut data = pool_data();
+    data.add_raydium_pool(k[0], k[1], k[2]);
+    data.add_meteora_damm_pool(
+        k[10], k[1], k[2], k[3], k[4], k[5], k[6], k[7], k[8], k[9], k[0],
+    );
+    let addresses: Vec<String> = k.iter().map(s).collect();
+
+    let table = data.summary_table();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", table);
+    assert!(lines[0].starts_with("DEX"), "{}", table);
+    // Raydium 的 vault 单独成列，其余账户只有 authority
+    let raydium: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(
+        raydium,
+        vec!["raydium", &addresses[0], &addresses[1], &addresses[2], "1"]
+    );
+    // Meteora DAMM 没有单独的 vault，DEX 程序和池子之外的账户（包括 vault 程序）都算作其余账户
+    let damm: Vec<&str> = lines[2].split_whitespace().collect();
+    assert_eq!(damm, vec!["meteora_damm", &addresses[10], "-", "-", "11"]);
+}
@@ -0,0 +1,77 @@
+//! 池子元数据缓存的读写测试
+
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::pool_cache::{CachedPool, PoolCache};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::pubkey::Pubkey;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.bin", name, std::process::id()))
+}
+
+fn pool_data_with_pools(mint: &Pubkey) -> MintPoolData {
+    let wallet = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(*mint, wallet, spl_token::ID);
+    pool_data.add_raydium_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    pool_data.add_solfi_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    // tick array 随价格变化，不应进入缓存
+    pool_data.add_whirlpool_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        vec![Pubkey::new_unique()],
+        None,
+    );
+    pool_data
+}
+
+#[test]
+fn round_trip() {
+    let mint = Pubkey::new_unique();
+    let pool_data = pool_data_with_pools(&mint);
+    let raydium = pool_data.raydium_pools[0].clone();
+    let whirlpool = pool_data.whirlpool_pools[0].pool;
+
+    let mut cache = PoolCache::empty();
+    cache.insert_mint_pools(&pool_data);
+    assert_eq!(cache.len(), 2);
+
+    let path = temp_path("pool-cache-round-trip");
+    cache.save(&path).unwrap();
+    let loaded = PoolCache::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.token_program(&mint), Some(spl_token::ID));
+    match loaded.get(&mint, &raydium.pool) {
+        Some(CachedPool::Raydium(pool)) => {
+            assert_eq!(pool.token_vault, raydium.token_vault);
+            assert_eq!(pool.sol_vault, raydium.sol_vault);
+        }
+        other => panic!("unexpected cache entry: {:?}", other),
+    }
+    assert!(loaded.get(&mint, &whirlpool).is_none());
+
+    // 同一个池子配置在其他代币下时不能命中
+    assert!(loaded.get(sol_mint(), &raydium.pool).is_none());
+}
+
+#[test]
+fn unreadable_cache_is_ignored() {
+    let path = temp_path("pool-cache-corrupt");
+    std::fs::write(&path, b"not a cache").unwrap();
+    let loaded = PoolCache::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert!(loaded.is_empty());
+
+    assert!(PoolCache::load(temp_path("pool-cache-missing")).is_empty());
+}
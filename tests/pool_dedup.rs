@@ -0,0 +1,154 @@
+//! 重复池子测试：同一个代币中去重并优先保留未禁用的一项，方向冲突和不同代币之间的重复报错
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::{Config, PoolEntry};
+use solana_sdk::pubkey::Pubkey;
+
+fn parse_config(mints: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        mints: Some(mints),
+        ..Default::default()
+    }
+    .parse()
+}
+
+fn addresses(pools: &Option<Vec<PoolEntry>>) -> Vec<Pubkey> {
+    pools
+        .iter()
+        .flatten()
+        .map(|entry| entry.pubkey().unwrap())
+        .collect()
+}
+
+#[test]
+fn duplicates_within_a_mint_keep_the_first_enabled_entry() {
+    let (shared, twice, other, revived) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let config = parse_config(&format!(
+        r#"
+[[routing.mint_config_list]]
+mint = "{}"
+process_delay = 1000
+raydium_pool_list = ["{shared}", "{twice}", "{twice}", {{ address = "{revived}", disabled = true }}]
+raydium_clmm_pool_list = ["{other}", {{ address = "{shared}", disabled = true }}, "{revived}"]
+"#,
+        Pubkey::new_unique()
+    ))
+    .unwrap();
+
+    let mint_config = &config.routing.mint_config_list[0];
+    assert_eq!(addresses(&mint_config.raydium_pool_list), [shared, twice]);
+    // 禁用的一项让位给后面未禁用的一项
+    assert_eq!(
+        addresses(&mint_config.raydium_clmm_pool_list),
+        [other, revived]
+    );
+    assert!(mint_config
+        .raydium_clmm_pool_list
+        .iter()
+        .flatten()
+        .all(|entry| !entry.is_disabled()));
+}
+
+#[test]
+fn dedup_reports_each_removed_entry_with_the_kept_entry() {
+    let pool = Pubkey::new_unique();
+    let mut config: Config = toml::from_str(&format!(
+        r#"
+[bot]
+compute_unit_limit = 400000
+
+[routing]
+[[routing.mint_config_list]]
+mint = "{}"
+process_delay = 1000
+pump_pool_list = ["{pool}"]
+whirlpool_pool_list = ["{pool}"]
+
+[rpc]
+url = "http://127.0.0.1:8899"
+
+[wallet]
+private_key = "unused"
+"#,
+        Pubkey::new_unique()
+    ))
+    .unwrap();
+
+    let removed = config.dedup_pools();
+    assert_eq!(
+        removed,
+        [format!(
+            "routing.mint_config_list[0].whirlpool_pool_list[0]: duplicate pool `{}`, keeping routing.mint_config_list[0].pump_pool_list[0]",
+            pool
+        )]
+    );
+    assert!(config.dedup_pools().is_empty());
+}
+
+#[test]
+fn duplicates_across_mints_are_still_rejected() {
+    let pool = Pubkey::new_unique();
+    let error = parse_config(&format!(
+        r#"
+[[routing.mint_config_list]]
+mint = "{}"
+process_delay = 1000
+pump_pool_list = ["{pool}"]
+
+[[routing.mint_config_list]]
+mint = "{}"
+process_delay = 1000
+pump_pool_list = ["{pool}"]
+"#,
+        Pubkey::new_unique(),
+        Pubkey::new_unique()
+    ))
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains(&format!(
+            "routing.mint_config_list[1].pump_pool_list[0]: duplicate pool `{}`, already listed at routing.mint_config_list[0].pump_pool_list[0]",
+            pool
+        )),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn conflicting_directions_within_a_mint_are_rejected() {
+    let pool = Pubkey::new_unique();
+    let mints = format!(
+        r#"
+[[routing.mint_config_list]]
+mint = "{}"
+process_delay = 1000
+pump_pool_list = [{{ address = "{pool}", direction = "buy_only" }}]
+raydium_cp_pool_list = [{{ address = "{pool}", disabled = true }}]
+whirlpool_pool_list = [{{ address = "{pool}", direction = "sell_only" }}]
+"#,
+        Pubkey::new_unique()
+    );
+    let error = parse_config(&mints).unwrap_err().to_string();
+    assert!(
+        error.contains(&format!(
+            "routing.mint_config_list[0].whirlpool_pool_list[0]: pool `{}` is also listed at routing.mint_config_list[0].pump_pool_list[0] with a different direction",
+            pool
+        )),
+        "{}",
+        error
+    );
+
+    // 方向相同的重复照常去重
+    let config = parse_config(&mints.replace("sell_only", "buy_only")).unwrap();
+    let mint_config = &config.routing.mint_config_list[0];
+    assert_eq!(addresses(&mint_config.pump_pool_list), [pool]);
+    assert!(addresses(&mint_config.whirlpool_pool_list).is_empty());
+}
@@ -0,0 +1,389 @@
+//! 各 DEX 池子加载器测试
+//!
+//! 加载器通过 `get_account` 读取账户，这里用 `tests/fixtures` 下的账户数据代替 RPC，
+//! 检查解析出的 vault 是否按 SOL / 代币方向加入 `MintPoolData`。
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::pump::{PumpAmmInfo, PumpFeeRecipients};
+use solana_onchain_arbitrage_bot::dex::{
+    meteora, moonshot, perena, pump, raydium, solfi, zerofi, PoolLoader,
+};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// 读取 `tests/fixtures/<name>.json`，返回账户地址和账户
+fn load_fixture(name: &str) -> (Pubkey, Account) {
+    let path = format!(
+        "{}/tests/fixtures/{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let json: serde_json::Value = serde_json::from_str(&contents).unwrap();
+    let account = &json["account"];
+
+    let pubkey = key(json["pubkey"].as_str().unwrap());
+    let account = Account {
+        lamports: account["lamports"].as_u64().unwrap(),
+        data: BASE64_STANDARD
+            .decode(account["data"][0].as_str().unwrap())
+            .unwrap(),
+        owner: key(account["owner"].as_str().unwrap()),
+        executable: false,
+        rent_epoch: 0,
+    };
+    (pubkey, account)
+}
+
+fn key(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+/// 用 fixture 中的池子账户调用加载器
+///
+/// # 参数
+/// * `load` - 被测试的加载器
+/// * `fixture` - 池子账户的 fixture 名称
+/// * `mint` - 要套利的代币
+/// * `owner` - 替换账户所有者，用于模拟地址填错的池子
+fn load(
+    load: PoolLoader,
+    fixture: &str,
+    mint: &str,
+    owner: Option<Pubkey>,
+) -> anyhow::Result<MintPoolData> {
+    let (pool, mut account) = load_fixture(fixture);
+    if let Some(owner) = owner {
+        account.owner = owner;
+    }
+    let mut accounts = HashMap::from([(pool, account)]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+
+    let mut pool_data = MintPoolData::new(key(mint), Pubkey::new_unique(), spl_token::ID);
+    load(&pool, &mut get_account, &mut pool_data)?;
+    Ok(pool_data)
+}
+
+#[test]
+fn pump_loader_adds_token_and_sol_vaults() {
+    let pool_data = load(
+        pump::loader::load_pool,
+        "pump_amm",
+        "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz",
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(pool_data.pump_pools.len(), 1);
+    let pool = &pool_data.pump_pools[0];
+    assert_eq!(
+        pool.token_vault,
+        key("FqbqRtDr9JjvTBnebvxQn8s9UizSF7P92ec8i2icSrNi")
+    );
+    assert_ne!(pool.sol_vault, pool.token_vault);
+}
+
+#[test]
+fn raydium_cp_loader_puts_sol_vault_on_the_sol_side() {
+    let pool_data = load(
+        raydium::loader::load_cp_pool,
+        "raydium_cpmm",
+        "69k7NXVJvykLFsXbHHEH4XKRYMWg7TQaEXFNRgutoftL",
+        None,
+    )
+    .unwrap();
+
+    let pool = &pool_data.raydium_cp_pools[0];
+    assert_eq!(
+        pool.sol_vault,
+        key("CDcrjHudQYdYBbmcKfbPNw3s78RGFjT63v3QBBYAozdq")
+    );
+    assert_eq!(
+        pool.token_vault,
+        key("DLTmAQ5QV4BqJpVZan1A6QtZqFESFmFDdfpbW69xa2vo")
+    );
+
+    // 池子两边都不是当前代币
+    let error = load(
+        raydium::loader::load_cp_pool,
+        "raydium_cpmm",
+        USDC_MINT,
+        None,
+    )
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("Invalid Raydium CP pool"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn solfi_and_damm_v2_loaders_add_vaults() {
+    let pool_data = load(solfi::loader::load_pool, "solfi", USDC_MINT, None).unwrap();
+    let pool = &pool_data.solfi_pools[0];
+    assert_eq!(
+        pool.token_sol_vault,
+        key("FikXxHwCW9CzzSC5G4Muo3h2wJUabPN6RgJWrNda5rac")
+    );
+    assert_eq!(
+        pool.token_x_vault,
+        key("7pW88ZRMyRDUtHYPFWJ7ritHrpqPncoSbi71Cuuqfsv7")
+    );
+
+    let pool_data = load(
+        meteora::loader::load_damm_v2_pool,
+        "meteora_damm_v2",
+        "7VzLoiRLa5EXH8SeCN1gXR9MUvHAm8kqyEHF1vbeTBRP",
+        None,
+    )
+    .unwrap();
+    let pool = &pool_data.meteora_damm_v2_pools[0];
+    assert_eq!(
+        pool.token_x_vault,
+        key("54Sy8nC2Sj2ymC4jaDcdgkjxF5v2khXGNyXS1XH6PZse")
+    );
+    assert_eq!(
+        pool.token_sol_vault,
+        key("8ZVaBHNek726MD5RWfcQuiJ3bNSK3MxJBqcsv2UFXkTv")
+    );
+}
+
+#[test]
+fn zerofi_loader_adds_vault_infos_with_their_vaults() {
+    const MINT: &str = "5bKZv1e7rmPVb3eeRKspVFoB8WPar61eFgDMyuJNKU4F";
+    let pool_data = load(zerofi::loader::load_pool, "zerofi", MINT, None).unwrap();
+    let pool = &pool_data.zerofi_pools[0];
+    assert_eq!(
+        pool.token_x_vault_info,
+        key("5tw89kG3KGnRUUjAHzcCm7yUa6kwXhvM1hpJ1KcUfnP")
+    );
+    assert_eq!(
+        pool.token_x_vault,
+        key("71d1exWqaLGwJJG717auBpX41vFekqhnccBa7ozE6QFn")
+    );
+    assert_eq!(
+        pool.token_sol_vault_info,
+        key("Bip58RtXmgcRyDR2C9BeNrzM3MWccdenEjPE4ci3aaev")
+    );
+    assert_eq!(
+        pool.token_sol_vault,
+        key("BxFjgVECFptzitR1Eg8wuBuiKg5vpa5Euy1f1AFUmqvN")
+    );
+    assert_eq!(pool_data.pool_addresses(), vec![("zerofi", pool.pool)]);
+
+    let error = load(zerofi::loader::load_pool, "zerofi", USDC_MINT, None).unwrap_err();
+    assert!(
+        error.to_string().contains(&format!("not {}/", USDC_MINT)),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn loaders_reject_accounts_owned_by_other_programs() {
+    let loaders: [(PoolLoader, &str, &str); 6] = [
+        (
+            pump::loader::load_pool,
+            "pump_amm",
+            "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz",
+        ),
+        (solfi::loader::load_pool, "solfi", USDC_MINT),
+        (
+            moonshot::loader::load_pool,
+            "moonshot",
+            "6mWoqQekzv4Av9CyPFM3fkLPoLZjzVztk3s9fbV4MzZL",
+        ),
+        (perena::loader::load_pool, "perena", USDC_MINT),
+        (
+            zerofi::loader::load_pool,
+            "zerofi",
+            "5bKZv1e7rmPVb3eeRKspVFoB8WPar61eFgDMyuJNKU4F",
+        ),
+        (
+            meteora::loader::load_damm_v2_pool,
+            "meteora_damm_v2",
+            "7VzLoiRLa5EXH8SeCN1gXR9MUvHAm8kqyEHF1vbeTBRP",
+        ),
+    ];
+    for (loader, fixture, mint) in loaders {
+        let error = load(loader, fixture, mint, Some(Pubkey::new_unique())).unwrap_err();
+        assert!(error.to_string().contains("not owned by"), "{}", error);
+    }
+}
+
+/// Pump 全局配置账户：LP 费 20、协议费 5、创建者费 5 基点和给定的协议手续费接收地址
+fn pump_global_config_account(recipients: &[Pubkey]) -> Account {
+    let mut data = vec![0u8; 353];
+    data[..8].copy_from_slice(&anchor_discriminator("GlobalConfig"));
+    data[40..48].copy_from_slice(&20u64.to_le_bytes());
+    data[48..56].copy_from_slice(&5u64.to_le_bytes());
+    data[313..321].copy_from_slice(&5u64.to_le_bytes());
+    for (i, recipient) in recipients.iter().enumerate() {
+        data[57 + 32 * i..89 + 32 * i].copy_from_slice(recipient.as_ref());
+    }
+    Account {
+        lamports: 1,
+        data,
+        owner: *pump::pump_program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[test]
+fn pump_fee_recipients_come_from_the_global_config() {
+    let recipients = [Pubkey::new_unique(), Pubkey::new_unique()];
+    let config = pump_global_config_account(&recipients);
+    let parsed = PumpFeeRecipients::load_checked(&config.data).unwrap();
+    assert_eq!(parsed.recipients, recipients);
+    // 同一个池子总是选到同一个地址
+    let pool = Pubkey::new_unique();
+    assert_eq!(parsed.for_pool(&pool), parsed.for_pool(&pool));
+    assert!(recipients.contains(&parsed.for_pool(&pool)));
+
+    let error = PumpFeeRecipients::load_checked(&pump_global_config_account(&[]).data).unwrap_err();
+    assert!(
+        error.to_string().contains("no protocol fee recipients"),
+        "{}",
+        error
+    );
+    let (_, pool_account) = load_fixture("pump_amm");
+    let error = PumpFeeRecipients::load_checked(&pool_account.data).unwrap_err();
+    assert!(
+        error.to_string().contains("Unsupported account version"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn pump_loader_uses_the_configured_fee_recipient() {
+    let (pool, pool_account) = load_fixture("pump_amm");
+    let quote_mint = PumpAmmInfo::load_checked(&pool_account.data)
+        .unwrap()
+        .quote_mint;
+    let recipient = Pubkey::new_unique();
+    let mut accounts = HashMap::from([
+        (pool, pool_account),
+        (
+            *pump::pump_global_config(),
+            pump_global_config_account(&[recipient]),
+        ),
+    ]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+    let mut pool_data = MintPoolData::new(
+        key("ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz"),
+        Pubkey::new_unique(),
+        spl_token::ID,
+    );
+    pump::loader::load_pool(&pool, &mut get_account, &mut pool_data).unwrap();
+
+    let loaded = &pool_data.pump_pools[0];
+    assert_eq!(loaded.fee_recipient, recipient);
+    assert_eq!(
+        loaded.fee_token_wallet,
+        spl_associated_token_account::get_associated_token_address(&recipient, &quote_mint)
+    );
+    assert_eq!(pool_data.pool_fees[&pool].total_bps(), 30);
+
+    // 读不到全局配置时使用默认接收地址
+    let pool_data = load(
+        pump::loader::load_pool,
+        "pump_amm",
+        "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        pool_data.pump_pools[0].fee_recipient,
+        *pump::pump_fee_wallet()
+    );
+}
+
+#[test]
+fn moonshot_loader_uses_the_curve_ata_as_token_vault() {
+    const MINT: &str = "6mWoqQekzv4Av9CyPFM3fkLPoLZjzVztk3s9fbV4MzZL";
+    let pool_data = load(moonshot::loader::load_pool, "moonshot", MINT, None).unwrap();
+
+    let (curve, _) = load_fixture("moonshot");
+    let pool = &pool_data.moonshot_pools[0];
+    assert_eq!(pool.pool, curve);
+    assert_eq!(
+        pool.token_vault,
+        spl_associated_token_account::get_associated_token_address(&curve, &key(MINT))
+    );
+    assert_eq!(pool_data.pool_fees[&curve].total_bps(), 100);
+    assert_eq!(pool_data.pool_addresses(), vec![("moonshot", curve)]);
+    // 曲线的 SOL 不在代币账户中，不参与 vault 跟踪
+    assert!(pool_data.pool_vaults().is_empty());
+
+    let error = load(moonshot::loader::load_pool, "moonshot", USDC_MINT, None).unwrap_err();
+    assert!(
+        error.to_string().contains(&format!("not {}", USDC_MINT)),
+        "{}",
+        error
+    );
+
+    // 曲线以 SOL 计价，不能用于其他报价货币
+    let mut accounts = HashMap::from([load_fixture("moonshot")]);
+    let mut get_account = |pubkey: &Pubkey| {
+        accounts
+            .remove(pubkey)
+            .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+    };
+    let mut pool_data = MintPoolData::new(key(MINT), Pubkey::new_unique(), spl_token::ID)
+        .with_quote_mint(key(USDC_MINT));
+    let error = moonshot::loader::load_pool(&curve, &mut get_account, &mut pool_data).unwrap_err();
+    assert!(error.to_string().contains("quoted in SOL"), "{}", error);
+}
+
+#[test]
+fn perena_loader_picks_the_vaults_of_the_mint_and_quote() {
+    const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+    let (pool, _) = load_fixture("perena");
+    let load_quoted = |mint: &str, quote_mint: &str| {
+        let mut accounts = HashMap::from([load_fixture("perena")]);
+        let mut get_account = |pubkey: &Pubkey| {
+            accounts
+                .remove(pubkey)
+                .ok_or_else(|| anyhow::anyhow!("account {} not found", pubkey))
+        };
+        let mut pool_data = MintPoolData::new(key(mint), Pubkey::new_unique(), spl_token::ID)
+            .with_quote_mint(key(quote_mint));
+        perena::loader::load_pool(&pool, &mut get_account, &mut pool_data).map(|()| pool_data)
+    };
+
+    let pool_data = load_quoted(USDT_MINT, USDC_MINT).unwrap();
+    let perena_pool = &pool_data.perena_pools[0];
+    assert_eq!(
+        perena_pool.token_x_vault,
+        key("ARmkpvjvVJQbtyHZCSi9HWJfXZDXoGVPLt9seRbFuUju")
+    );
+    assert_eq!(
+        perena_pool.token_sol_vault,
+        key("5a8g5M7MSdbuDdpNJ6aTuxabGZjRZCtAaPPvERtaiz4c")
+    );
+    assert_eq!(pool_data.pool_fees[&pool].total_bps(), 1);
+    assert_eq!(pool_data.pool_addresses(), vec![("perena", pool)]);
+    assert_eq!(pool_data.pool_vaults().len(), 1);
+
+    // 报价货币不在池子中
+    let error = load_quoted(USDT_MINT, "So11111111111111111111111111111111111111112").unwrap_err();
+    assert!(error.to_string().contains("doesn't contain"), "{}", error);
+}
@@ -0,0 +1,103 @@
+//! 池子账户所有者重新校验测试
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::dex::pump::pump_program_id;
+use solana_onchain_arbitrage_bot::dex::raydium::raydium_program_id;
+use solana_onchain_arbitrage_bot::notifications::Alert;
+use solana_onchain_arbitrage_bot::pool_owners::{owner_mismatches, OwnerMismatch};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+
+fn account(owner: Pubkey) -> Option<Account> {
+    Some(Account {
+        lamports: 1,
+        data: vec![],
+        owner,
+        executable: false,
+        rent_epoch: 0,
+    })
+}
+
+fn config(bot: &str) -> Config {
+    TestConfig {
+        bot,
+        ..Default::default()
+    }
+    .parse()
+    .expect("test config should parse")
+}
+
+#[test]
+fn closed_and_reassigned_pools_are_reported() {
+    let (valid, reassigned, closed, unknown) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let other_program = Pubkey::new_unique();
+    let pools = [
+        ("raydium", valid),
+        ("pump", reassigned),
+        ("raydium", closed),
+        ("unknown_dex", unknown),
+    ];
+    let accounts = [
+        account(*raydium_program_id()),
+        account(other_program),
+        None,
+        account(other_program),
+    ];
+
+    let mismatches = owner_mismatches(&pools, &accounts);
+    assert_eq!(
+        mismatches,
+        vec![
+            OwnerMismatch {
+                dex: "pump",
+                pool: reassigned,
+                expected: *pump_program_id(),
+                actual: Some(other_program),
+            },
+            OwnerMismatch {
+                dex: "raydium",
+                pool: closed,
+                expected: *raydium_program_id(),
+                actual: None,
+            },
+        ]
+    );
+    assert_eq!(mismatches[1].to_string(), "account was closed");
+
+    let alert = Alert::PoolEvicted {
+        mint: "mint".to_string(),
+        dex: mismatches[0].dex,
+        pool: reassigned,
+        reason: mismatches[0].to_string(),
+    };
+    assert!(alert.to_string().contains(&other_program.to_string()));
+}
+
+#[test]
+fn owner_check_interval_defaults_to_a_minute() {
+    assert_eq!(
+        config("").bot.pool_owner_check_interval(),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(
+        config("pool_owner_check_secs = 5")
+            .bot
+            .pool_owner_check_interval(),
+        Some(Duration::from_secs(5))
+    );
+    assert_eq!(
+        config("pool_owner_check_secs = 0")
+            .bot
+            .pool_owner_check_interval(),
+        None
+    );
+}
@@ -0,0 +1,129 @@
+//! 运行时加入池子测试
+
+mod common;
+
+use common::load_fixture;
+use solana_onchain_arbitrage_bot::pool_watch::{add_pool, changed_files, parse_pool_file, NewPool};
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+const PUMP_MINT: &str = "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz";
+
+fn pool_data() -> MintPoolData {
+    MintPoolData::new(
+        Pubkey::from_str(PUMP_MINT).unwrap(),
+        Pubkey::new_unique(),
+        spl_token::ID,
+    )
+}
+
+#[test]
+fn pool_files_list_pools_by_mint_and_dex() {
+    let pools = parse_pool_file(
+        r#"
+[[pool]]
+mint = "ZWKqPyBCZ2N4GPkPatFmaj1zjQoNQZQTpPb6odKjzqz"
+dex = "pump"
+address = "11111111111111111111111111111111"
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        pools,
+        vec![NewPool {
+            mint: PUMP_MINT.to_string(),
+            dex: "pump".to_string(),
+            address: "11111111111111111111111111111111".to_string(),
+        }]
+    );
+    assert!(parse_pool_file("").unwrap().is_empty());
+    assert!(parse_pool_file("[[pool]]\nmint = \"x\"").is_err());
+}
+
+#[test]
+fn pools_are_added_once() {
+    let (pool, account) = load_fixture("pump_amm");
+    let mut get_account = |pubkey: &Pubkey| {
+        if *pubkey == pool {
+            Ok(account.clone())
+        } else {
+            Err(anyhow::anyhow!("account {} not found", pubkey))
+        }
+    };
+    let mut pool_data = pool_data();
+
+    assert!(add_pool(&mut pool_data, "pump", &pool.to_string(), &mut get_account).unwrap());
+    assert_eq!(pool_data.pump_pools.len(), 1);
+    assert_eq!(pool_data.pump_pools[0].pool, pool);
+    assert!(!add_pool(&mut pool_data, "pump", &pool.to_string(), &mut get_account).unwrap());
+    assert_eq!(pool_data.pump_pools.len(), 1);
+}
+
+#[test]
+fn invalid_pools_leave_the_pool_data_unchanged() {
+    let (pool, mut account) = load_fixture("pump_amm");
+    let mut pool_data = pool_data();
+
+    let error = add_pool(
+        &mut pool_data,
+        "uniswap",
+        &pool.to_string(),
+        &mut |_| unreachable!(),
+    )
+    .unwrap_err();
+    assert!(
+        error.to_string().contains("not a supported DEX"),
+        "{}",
+        error
+    );
+    assert!(add_pool(
+        &mut pool_data,
+        "pump",
+        "not-a-pubkey",
+        &mut |_| unreachable!()
+    )
+    .is_err());
+
+    // 地址填成了另一个 DEX 的池子
+    let error = add_pool(&mut pool_data, "raydium_cp", &pool.to_string(), &mut |_| {
+        Ok(account.clone())
+    })
+    .unwrap_err();
+    assert!(error.to_string().contains("is owned by"), "{}", error);
+
+    // 账户数据无法解析，加载器拒绝
+    account.data.truncate(100);
+    assert!(
+        add_pool(&mut pool_data, "pump", &pool.to_string(), &mut |_| {
+            Ok(account.clone())
+        })
+        .is_err()
+    );
+    assert!(pool_data.pool_addresses().is_empty());
+}
+
+#[test]
+fn only_new_or_modified_toml_files_are_reported() {
+    let dir = std::env::temp_dir().join(format!("pools.d-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let mut seen = HashMap::new();
+    assert!(changed_files(&dir, &mut seen).unwrap().is_empty());
+
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("a.toml"), "").unwrap();
+    std::fs::write(dir.join("notes.txt"), "").unwrap();
+    assert_eq!(
+        changed_files(&dir, &mut seen).unwrap(),
+        vec![dir.join("a.toml")]
+    );
+    assert!(changed_files(&dir, &mut seen).unwrap().is_empty());
+
+    std::fs::write(dir.join("b.toml"), "").unwrap();
+    assert_eq!(
+        changed_files(&dir, &mut seen).unwrap(),
+        vec![dir.join("b.toml")]
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,83 @@
+//! SOL/USD 价格解析和美元金额显示测试
+
+use solana_onchain_arbitrage_bot::config::PriceFeedConfig;
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::notifications::{Alert, Notifier};
+use solana_onchain_arbitrage_bot::price_feed::{
+    parse_jupiter_price, parse_pyth_price_update, SolPriceFeed,
+};
+use solana_sdk::signature::Signature;
+use std::sync::Arc;
+
+/// 构造一个 `PriceUpdateV2` 账户
+fn price_update(full_verification: bool, price: i64, exponent: i32, publish_time: i64) -> Vec<u8> {
+    let mut data = vec![34, 241, 35, 99, 157, 126, 244, 205];
+    data.extend([7; 32]); // write_authority
+    if full_verification {
+        data.push(1);
+    } else {
+        data.extend([0, 3]);
+    }
+    data.extend([9; 32]); // feed_id
+    data.extend(price.to_le_bytes());
+    data.extend(12_345u64.to_le_bytes()); // conf
+    data.extend(exponent.to_le_bytes());
+    data.extend(publish_time.to_le_bytes());
+    data.extend(publish_time.to_le_bytes()); // prev_publish_time
+    data.extend([0; 24]); // ema_price, ema_conf, posted_slot
+    data
+}
+
+#[test]
+fn parses_pyth_and_jupiter_prices() {
+    for full_verification in [true, false] {
+        let price = parse_pyth_price_update(&price_update(
+            full_verification,
+            14_512_345_678,
+            -8,
+            1_700_000_000,
+        ))
+        .unwrap();
+        assert!((price.price - 145.12345678).abs() < 1e-9);
+        assert_eq!(price.publish_time, 1_700_000_000);
+    }
+
+    let mut wrong_account = price_update(true, 1, 0, 0);
+    wrong_account[0] = 0;
+    assert!(parse_pyth_price_update(&wrong_account).is_err());
+    assert!(parse_pyth_price_update(&price_update(true, 0, -8, 0)).is_err());
+    assert!(parse_pyth_price_update(&price_update(true, 1, 0, 0)[..60]).is_err());
+
+    let body = serde_json::json!({
+        sol_mint().to_string(): { "usdPrice": 150.25, "decimals": 9 }
+    });
+    assert_eq!(parse_jupiter_price(&body).unwrap(), 150.25);
+    assert!(parse_jupiter_price(&serde_json::json!({})).is_err());
+}
+
+#[test]
+fn alerts_include_usd_amounts_once_a_price_is_known() {
+    let config: PriceFeedConfig = toml::from_str("enabled = true\nsource = \"jupiter\"").unwrap();
+    let price_feed = Arc::new(SolPriceFeed::new(&config).unwrap());
+    let notifier = Notifier::new(None).with_price_feed(Some(price_feed.clone()));
+    let alert = Alert::Landed {
+        mint: "mint".to_string(),
+        signature: Signature::default(),
+        profit_lamports: 2_000_000_000,
+    };
+
+    assert!(!notifier.text(&alert).contains('$'));
+    assert_eq!(price_feed.usd(1_000_000_000), None);
+
+    price_feed.set_price(150.0);
+    assert_eq!(price_feed.usd(-500_000_000), Some(-75.0));
+    let text = notifier.text(&alert);
+    let (first_line, link) = text.split_once('\n').unwrap();
+    assert!(first_line.ends_with("(≈ $300.00)"), "{}", text);
+    assert!(link.starts_with("https://solscan.io/tx/"));
+
+    let stopped = Alert::Stopped {
+        reason: "ctrl-c".to_string(),
+    };
+    assert_eq!(notifier.text(&stopped), stopped.to_string());
+}
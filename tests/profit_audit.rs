@@ -0,0 +1,112 @@
+//! 上链交易余额核对测试：余额变化、报价收益容差和亏损告警
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::notifications::{Alert, AlertKind};
+use solana_onchain_arbitrage_bot::transaction::{audit_profit, LandedTransaction, ProfitAudit};
+use solana_sdk::signature::Signature;
+
+fn parse_config(bot: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        bot,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn quote_delta_adds_back_the_fee() {
+    let sol = LandedTransaction {
+        slot: 1,
+        profit_lamports: 15_000,
+        fee_lamports: 5_000,
+        quote_profit: None,
+    };
+    assert_eq!(sol.quote_delta(), 20_000);
+
+    let usdc = LandedTransaction {
+        profit_lamports: -5_000,
+        quote_profit: Some(-30),
+        ..sol
+    };
+    assert_eq!(usdc.quote_delta(), -30);
+}
+
+#[test]
+fn audit_compares_the_balance_delta_with_the_quote() {
+    assert_eq!(audit_profit(Some(100_000), 60_000, 50.0), ProfitAudit::Ok);
+    assert_eq!(audit_profit(Some(100_000), 50_000, 50.0), ProfitAudit::Ok);
+    assert_eq!(
+        audit_profit(Some(100_000), 49_999, 50.0),
+        ProfitAudit::Shortfall {
+            expected: 100_000,
+            actual: 49_999
+        }
+    );
+    assert_eq!(
+        audit_profit(Some(100_000), 99_999, 0.0),
+        ProfitAudit::Shortfall {
+            expected: 100_000,
+            actual: 99_999
+        }
+    );
+    // 余额不变算作收益不足，不算亏损
+    assert_eq!(
+        audit_profit(Some(100_000), 0, 50.0),
+        ProfitAudit::Shortfall {
+            expected: 100_000,
+            actual: 0
+        }
+    );
+}
+
+#[test]
+fn audit_flags_routes_that_lost_money() {
+    assert_eq!(
+        audit_profit(Some(100_000), -1, 100.0),
+        ProfitAudit::Loss {
+            expected: Some(100_000),
+            actual: -1
+        }
+    );
+    // 没有报价收益时只核对是否亏损
+    assert_eq!(audit_profit(None, 0, 50.0), ProfitAudit::Ok);
+    assert_eq!(
+        audit_profit(None, -20_000, 50.0),
+        ProfitAudit::Loss {
+            expected: None,
+            actual: -20_000
+        }
+    );
+
+    let alert = Alert::RouteLoss {
+        mint: "mint".to_string(),
+        signature: Signature::default(),
+        symbol: "SOL",
+        expected: Some(0.0001),
+        actual: -0.00002,
+    };
+    assert_eq!(alert.kind(), AlertKind::ProfitAudit);
+    let text = alert.to_string();
+    assert!(text.contains("lost 0.00002 SOL"), "{}", text);
+    assert!(text.contains("quoted profit 0.0001 SOL"), "{}", text);
+}
+
+#[test]
+fn audit_tolerance_defaults_to_half_the_quote() {
+    let config = parse_config("").unwrap();
+    assert_eq!(config.bot.profit_audit_tolerance_percent(), 50.0);
+    let config = parse_config("profit_audit_tolerance_percent = 20").unwrap();
+    assert_eq!(config.bot.profit_audit_tolerance_percent(), 20.0);
+
+    let error = parse_config("profit_audit_tolerance_percent = 120")
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("bot.profit_audit_tolerance_percent"),
+        "{}",
+        error
+    );
+}
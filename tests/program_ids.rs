@@ -0,0 +1,108 @@
+//! 地址注册表测试：主网默认值、覆盖项优先级和覆盖文件
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::constants::{
+    program_id, program_id_names, sol_mint, ProgramId, ProgramIds, SOL_MINT,
+};
+use solana_onchain_arbitrage_bot::dex::raydium::raydium_program_id;
+use solana_onchain_arbitrage_bot::dex::whirlpool::constants::whirlpool_program_id;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[test]
+fn accessors_return_mainnet_defaults_from_one_registry() {
+    let mainnet = ProgramIds::mainnet();
+    assert_eq!(program_id_names().count(), 30);
+    assert_eq!(ProgramId::Whirlpool.name(), "whirlpool");
+    assert_eq!(ProgramId::SolMint.mainnet_address(), SOL_MINT);
+
+    assert_eq!(sol_mint(), mainnet.get(ProgramId::SolMint));
+    assert_eq!(
+        raydium_program_id().to_string(),
+        "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8"
+    );
+    // 每次访问返回同一个静态地址，不重新解析
+    assert!(std::ptr::eq(
+        whirlpool_program_id(),
+        program_id(ProgramId::Whirlpool)
+    ));
+}
+
+#[test]
+fn overrides_replace_only_named_addresses() {
+    let upgraded = Pubkey::new_unique();
+    let ids =
+        ProgramIds::with_overrides(&HashMap::from([("whirlpool".to_string(), upgraded)])).unwrap();
+    assert_eq!(*ids.get(ProgramId::Whirlpool), upgraded);
+    assert_eq!(
+        ids.get(ProgramId::Raydium),
+        ProgramIds::mainnet().get(ProgramId::Raydium)
+    );
+
+    let error = ProgramIds::with_overrides(&HashMap::from([("unknown".to_string(), upgraded)]))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("Unknown program id override `unknown`"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn override_file_sits_between_preset_and_inline_overrides() {
+    let (from_file, inline) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let path = std::env::temp_dir().join(format!("program-ids-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        format!(
+            "raydium = \"{}\"\nwhirlpool = \"{}\"\n",
+            from_file, from_file
+        ),
+    )
+    .unwrap();
+
+    let config = parse_config(&format!(
+        "[cluster]\nname = \"devnet\"\nprogram_ids_file = \"{}\"\n[cluster.program_ids]\nwhirlpool = \"{}\"",
+        path.display(),
+        inline
+    ))
+    .unwrap();
+    let overrides = config.cluster.unwrap().program_id_overrides().unwrap();
+    assert_eq!(overrides["raydium"], from_file);
+    assert_eq!(overrides["whirlpool"], inline);
+    // 文件中没有的地址保留集群预设
+    assert_eq!(
+        overrides["raydium_cp"],
+        Pubkey::from_str("CPMDWBwJDtYax9qW7AyRuVC19Cc4L4Vcy4n2BHAbHkCW").unwrap()
+    );
+
+    std::fs::write(&path, "unknown = \"11111111111111111111111111111111\"\n").unwrap();
+    let error = parse_config(&format!(
+        "[cluster]\nprogram_ids_file = \"{}\"",
+        path.display()
+    ))
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("Unknown program id override `unknown`"),
+        "{}",
+        error
+    );
+
+    std::fs::remove_file(&path).unwrap();
+    let error = parse_config(&format!(
+        "[cluster]\nprogram_ids_file = \"{}\"",
+        path.display()
+    ))
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("Failed to read program id override file"),
+        "{}",
+        error
+    );
+}
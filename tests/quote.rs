@@ -0,0 +1,721 @@
+//! 池子报价测试
+
+mod common;
+
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use base64::Engine;
+use common::{fixture_data, token_account};
+use solana_onchain_arbitrage_bot::concentrated_liquidity::{
+    compute_swap_step, sqrt_price_at_tick, TickMath,
+};
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::meteora::dlmm_info::{BinArray, DlmmInfo, LbPair};
+use solana_onchain_arbitrage_bot::dex::raydium::clmm_info::{PoolState, TickArrayState};
+use solana_onchain_arbitrage_bot::dex::whirlpool::state::{TickArray, Whirlpool};
+use solana_onchain_arbitrage_bot::fees::whirlpool_fee_rate;
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, PoolDirection};
+use solana_onchain_arbitrage_bot::quote::{
+    best_spread, best_trade_size, constant_product_out, constant_product_price,
+    dlmm_get_amount_out, liquidity_accounts, optimal_cpmm_input, optimal_trade, pool_edges,
+    pool_liquidity, raydium_clmm_get_amount_out, select_top_pools, shallow_pools,
+    whirlpool_get_amount_out, ClmmQuote, DlmmQuote, PoolReserves,
+};
+use solana_onchain_arbitrage_bot::transfer_fee::{parse_transfer_fee_config, TransferFee};
+use solana_sdk::account::Account;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+#[test]
+fn constant_product_price_needs_both_reserves() {
+    assert_eq!(constant_product_price(1_000, 2_000), Some(2.0));
+    assert_eq!(constant_product_price(0, 2_000), None);
+    assert_eq!(constant_product_price(1_000, 0), None);
+}
+
+#[test]
+fn edges_measure_distance_from_median() {
+    let (low, mid, high) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let prices = HashMap::from([(low, 0.9), (mid, 1.0), (high, 1.2)]);
+
+    let edges = pool_edges(&prices);
+    assert!((edges[&low] - 0.1).abs() < 1e-9);
+    assert_eq!(edges[&mid], 0.0);
+    assert!((edges[&high] - 0.2).abs() < 1e-9);
+    assert!(pool_edges(&HashMap::new()).is_empty());
+}
+
+#[test]
+fn top_pools_take_both_ends_of_the_price_range() {
+    let wallet = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(Pubkey::new_unique(), wallet, spl_token::ID);
+    let mut prices = HashMap::new();
+    let mut pools = Vec::new();
+    for price in [1.00, 0.95, 1.02, 1.10, 0.99] {
+        let pool = Pubkey::new_unique();
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+        prices.insert(pool, price);
+        pools.push(pool);
+    }
+
+    let top = select_top_pools(&pool_data, &prices, 2);
+    let selected: Vec<Pubkey> = top.pool_addresses().into_iter().map(|(_, p)| p).collect();
+    assert_eq!(selected.len(), 2);
+    assert!(selected.contains(&pools[1]) && selected.contains(&pools[3]));
+
+    // 最便宜的池子只能卖出时，买入一侧改用次便宜的池子
+    pool_data
+        .pool_directions
+        .insert(pools[1], PoolDirection::SellOnly);
+    let top = select_top_pools(&pool_data, &prices, 2);
+    let selected: Vec<Pubkey> = top.pool_addresses().into_iter().map(|(_, p)| p).collect();
+    assert!(selected.contains(&pools[4]) && selected.contains(&pools[3]));
+
+    let spread = best_spread(&pool_data, &prices).unwrap();
+    assert!((spread - (1.10 / 0.99 - 1.0)).abs() < 1e-9);
+}
+
+#[test]
+fn best_trade_size_accounts_for_price_impact() {
+    let wallet = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(Pubkey::new_unique(), wallet, spl_token::ID);
+    let (cheap, dear) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for pool in [cheap, dear] {
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+    }
+    // 两个 1000 SOL 深度的池子，价差 1%，各收 0.25% 手续费
+    let reserves = HashMap::from([
+        (
+            cheap,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: 1_000 * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+        (
+            dear,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: 1_010 * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+    ]);
+
+    let sizes = [
+        LAMPORTS_PER_SOL / 10,
+        LAMPORTS_PER_SOL,
+        100 * LAMPORTS_PER_SOL,
+    ];
+    let best = best_trade_size(&pool_data, &reserves, &sizes).unwrap();
+    assert_eq!(best.size, LAMPORTS_PER_SOL);
+    assert_eq!((best.buy_pool, best.sell_pool), (cheap, dear));
+    assert!(best.profit > 0);
+
+    // 规模太大时价格冲击吃掉价差
+    let large = best_trade_size(&pool_data, &reserves, &sizes[2..]).unwrap();
+    assert!(large.profit < 0);
+
+    assert_eq!(constant_product_out(1_000, 0, 0, 25), 0);
+}
+
+#[test]
+fn optimal_input_maximizes_two_pool_profit() {
+    let buy = PoolReserves {
+        token: 1_000_000_000,
+        sol: 1_000 * LAMPORTS_PER_SOL,
+        fee_bps: 25,
+    };
+    let sell = PoolReserves {
+        token: 1_000_000_000,
+        sol: 1_010 * LAMPORTS_PER_SOL,
+        fee_bps: 25,
+    };
+    let profit = |size: u64| sell.sell(buy.buy(size)) as i64 - size as i64;
+
+    let optimal = optimal_cpmm_input(&buy, &sell, u64::MAX).unwrap();
+    assert!(profit(optimal) > 0);
+    assert!(profit(optimal) >= profit(optimal * 9 / 10));
+    assert!(profit(optimal) >= profit(optimal * 11 / 10));
+
+    // 上限生效
+    assert_eq!(
+        optimal_cpmm_input(&buy, &sell, LAMPORTS_PER_SOL),
+        Some(LAMPORTS_PER_SOL)
+    );
+    // 反方向没有套利空间
+    assert_eq!(optimal_cpmm_input(&sell, &buy, u64::MAX), None);
+}
+
+#[test]
+fn optimal_input_matches_a_brute_force_search() {
+    // 储备较小，可以逐个枚举所有投入
+    for (buy_fee, sell_fee, sell_sol) in [
+        (0, 0, 1_020_000),
+        (25, 25, 1_020_000),
+        (30, 100, 1_050_000),
+        (100, 4, 1_030_000),
+    ] {
+        let buy = PoolReserves {
+            token: 2_000_000,
+            sol: 1_000_000,
+            fee_bps: buy_fee,
+        };
+        let sell = PoolReserves {
+            token: 2_000_000,
+            sol: sell_sol,
+            fee_bps: sell_fee,
+        };
+        let profit = |size: u64| sell.sell(buy.buy(size)) as i64 - size as i64;
+
+        let (best_size, best_profit) = (1..=200_000u64)
+            .map(|size| (size, profit(size)))
+            .max_by_key(|&(size, profit)| (profit, std::cmp::Reverse(size)))
+            .unwrap();
+        assert!(best_profit > 0 && best_size < 200_000);
+        let optimal = optimal_cpmm_input(&buy, &sell, u64::MAX).unwrap();
+        // 闭式解不考虑整数取整，收益与枚举结果最多差几个最小单位
+        assert!(
+            profit(optimal) >= best_profit - 2,
+            "fees {}/{}: solved {} ({}), brute force {} ({})",
+            buy_fee,
+            sell_fee,
+            optimal,
+            profit(optimal),
+            best_size,
+            best_profit
+        );
+    }
+}
+
+/// 构造带有 TransferFeeConfig 扩展的 Token 2022 mint 账户数据
+fn token_2022_mint_with_transfer_fee(older: TransferFee, newer: TransferFee) -> Vec<u8> {
+    let mut data = vec![0u8; 165];
+    data.push(1); // AccountType::Mint
+    data.extend_from_slice(&1u16.to_le_bytes()); // ExtensionType::TransferFeeConfig
+    data.extend_from_slice(&108u16.to_le_bytes());
+    data.extend_from_slice(&[0u8; 72]); // authority、withheld_amount
+    for fee in [older, newer] {
+        data.extend_from_slice(&fee.epoch.to_le_bytes());
+        data.extend_from_slice(&fee.maximum_fee.to_le_bytes());
+        data.extend_from_slice(&fee.basis_points.to_le_bytes());
+    }
+    data
+}
+
+#[test]
+fn transfer_fee_config_is_parsed_from_mint_extensions() {
+    let older = TransferFee {
+        epoch: 0,
+        maximum_fee: u64::MAX,
+        basis_points: 100,
+    };
+    let newer = TransferFee {
+        epoch: 500,
+        maximum_fee: 1_000,
+        basis_points: 50,
+    };
+    let config =
+        parse_transfer_fee_config(&token_2022_mint_with_transfer_fee(older, newer)).unwrap();
+    assert_eq!(config.for_epoch(499), older);
+    assert_eq!(config.for_epoch(500), newer);
+
+    // 手续费向上取整，并受单笔上限限制
+    assert_eq!(older.fee(10_001), 101);
+    assert_eq!(newer.fee(1_000_000), 1_000);
+    assert_eq!(newer.after_fee(1_000_000), 999_000);
+
+    // SPL Token mint 没有扩展
+    assert_eq!(parse_transfer_fee_config(&[0u8; 82]), None);
+}
+
+#[test]
+fn transfer_fee_reduces_quoted_profit() {
+    let wallet = Pubkey::new_unique();
+    let mut pool_data = MintPoolData::new(Pubkey::new_unique(), wallet, spl_token::ID);
+    let (cheap, dear) = (Pubkey::new_unique(), Pubkey::new_unique());
+    for pool in [cheap, dear] {
+        pool_data.add_raydium_pool(pool, Pubkey::new_unique(), Pubkey::new_unique());
+    }
+    // 价差 1%，两次 0.5% 的转账手续费加上池子手续费后不再赚钱
+    let reserves = HashMap::from([
+        (
+            cheap,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: 1_000 * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+        (
+            dear,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: 1_010 * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+    ]);
+    let sizes = [LAMPORTS_PER_SOL];
+    let without_fee = best_trade_size(&pool_data, &reserves, &sizes).unwrap();
+    assert!(without_fee.profit > 0);
+    assert!(optimal_trade(&pool_data, &reserves, 100 * LAMPORTS_PER_SOL).is_some());
+
+    pool_data.transfer_fee = Some(TransferFee {
+        epoch: 0,
+        maximum_fee: u64::MAX,
+        basis_points: 50,
+    });
+    let with_fee = best_trade_size(&pool_data, &reserves, &sizes).unwrap();
+    assert!(with_fee.profit < 0);
+    assert!(optimal_trade(&pool_data, &reserves, 100 * LAMPORTS_PER_SOL).is_none());
+}
+
+#[test]
+fn pool_liquidity_reads_the_quote_vault() {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (raydium, whirlpool, curve, missing) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let vaults: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+    pool_data.add_raydium_pool(raydium, vaults[0], vaults[1]);
+    // Whirlpool 的 vault 按 mint 地址排序，报价货币在 x 一侧
+    pool_data.add_whirlpool_pool(
+        whirlpool,
+        Pubkey::new_unique(),
+        vaults[2],
+        vaults[3],
+        vec![],
+        None,
+    );
+    pool_data.add_raydium_pool(missing, vaults[4], vaults[5]);
+    pool_data.add_moonshot_pool(curve, Pubkey::new_unique());
+
+    let (mint, quote_mint) = (pool_data.mint, pool_data.quote_mint);
+    let mut accounts = HashMap::from([
+        (vaults[0], token_account(&mint, 1_000_000)),
+        (vaults[1], token_account(&quote_mint, 50 * LAMPORTS_PER_SOL)),
+        (vaults[2], token_account(&quote_mint, LAMPORTS_PER_SOL)),
+        (vaults[3], token_account(&mint, 1_000_000)),
+    ]);
+    accounts.insert(
+        curve,
+        Account {
+            lamports: 3 * LAMPORTS_PER_SOL,
+            ..Account::default()
+        },
+    );
+    let needed = liquidity_accounts(&pool_data);
+    assert!(accounts.keys().all(|pubkey| needed.contains(pubkey)));
+
+    let liquidity = pool_liquidity(&pool_data, &accounts);
+    assert_eq!(liquidity[&raydium], 50 * LAMPORTS_PER_SOL);
+    assert_eq!(liquidity[&whirlpool], LAMPORTS_PER_SOL);
+    assert_eq!(liquidity[&curve], 3 * LAMPORTS_PER_SOL);
+    assert!(!liquidity.contains_key(&missing));
+
+    // 读不到余额的池子不算作流动性不足
+    let shallow = shallow_pools(&pool_data, &liquidity, 5 * LAMPORTS_PER_SOL);
+    assert_eq!(
+        shallow,
+        vec![
+            ("whirlpool", whirlpool, LAMPORTS_PER_SOL),
+            ("moonshot", curve, 3 * LAMPORTS_PER_SOL),
+        ]
+    );
+    assert!(shallow_pools(&pool_data, &liquidity, 0).is_empty());
+}
+
+/// Q64.64 表示的 1.0
+const ONE: u128 = 1 << 64;
+
+/// 手续费为 0.25% 的 DLMM 池子，active bin 是 bin array -65 的第一个 bin
+fn dlmm_pair(now: u64) -> LbPair {
+    let path = format!(
+        "{}/tests/fixtures/meteora_dlmm.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let data = BASE64_STANDARD
+        .decode(json["account"]["data"][0].as_str().unwrap())
+        .unwrap();
+    let mut lb_pair = DlmmInfo::load_checked(&data).unwrap().lb_pair;
+    lb_pair.active_id = -4550;
+    lb_pair.parameters.min_bin_id = -443_636;
+    lb_pair.parameters.max_bin_id = 443_636;
+    lb_pair.parameters.filter_period = 30;
+    lb_pair.parameters.decay_period = 600;
+    lb_pair.parameters.max_volatility_accumulator = 350_000;
+    lb_pair.parameters.variable_fee_control = 0;
+    lb_pair.v_parameters.volatility_reference = 0;
+    lb_pair.v_parameters.volatility_accumulator = 0;
+    lb_pair.v_parameters.index_reference = lb_pair.active_id;
+    lb_pair.v_parameters.last_update_timestamp = now as i64 - 10;
+    lb_pair
+}
+
+/// 按链上布局构造 bin array 账户，`bins` 为 `(bin 在数组中的位置, amount_x, amount_y, price)`
+fn bin_array(index: i64, bins: &[(usize, u64, u64, u128)]) -> BinArray {
+    let mut data = vec![0; 10_136];
+    data[..8].copy_from_slice(&anchor_discriminator("BinArray"));
+    data[8..16].copy_from_slice(&index.to_le_bytes());
+    for &(offset, amount_x, amount_y, price) in bins {
+        let start = 56 + offset * 144;
+        data[start..start + 8].copy_from_slice(&amount_x.to_le_bytes());
+        data[start + 8..start + 16].copy_from_slice(&amount_y.to_le_bytes());
+        data[start + 16..start + 32].copy_from_slice(&price.to_le_bytes());
+    }
+    BinArray::load_checked(&data).unwrap()
+}
+
+#[test]
+fn dlmm_quote_traverses_bins_across_bin_arrays() {
+    let now = 1_700_000_000;
+    let lb_pair = dlmm_pair(now);
+    // active bin -4550 只有 1000 Y，-4551 是空的，-4552 价格为 0.5
+    let arrays = [
+        bin_array(-65, &[(0, 0, 1_000, ONE), (1, 5_000, 0, ONE)]),
+        bin_array(-66, &[(68, 0, 1_000_000, ONE / 2)]),
+    ];
+    assert_eq!(arrays[0].bin(-4550).unwrap().amount_y, 1_000);
+    assert_eq!(arrays[1].bin(-4552).unwrap().price, ONE / 2);
+    assert!(arrays[0].bin(-4551).is_none());
+
+    // 只在 active bin 内成交：手续费 ceil(800 × 0.25%) = 2
+    assert_eq!(
+        dlmm_get_amount_out(&lb_pair, &arrays, 800, true, now),
+        Some(DlmmQuote {
+            amount_out: 798,
+            fee: 2,
+            bins_crossed: 0,
+        })
+    );
+
+    // 换空 active bin 需要 1000 + ceil(1000 × 0.25% / 99.75%) = 1003，
+    // 剩余 8997 扣除 23 手续费后在 -4552 以 0.5 成交
+    assert_eq!(
+        dlmm_get_amount_out(&lb_pair, &arrays, 10_000, true, now),
+        Some(DlmmQuote {
+            amount_out: 1_000 + 4_487,
+            fee: 3 + 23,
+            bins_crossed: 2,
+        })
+    );
+
+    // 输入 Y 换出 X：active bin 没有 X，在 -4549 换空 5000 X
+    assert_eq!(
+        dlmm_get_amount_out(&lb_pair, &arrays, 5_013, false, now),
+        Some(DlmmQuote {
+            amount_out: 5_000,
+            fee: 13,
+            bins_crossed: 1,
+        })
+    );
+    // 已加载的 bin array 不足以成交全部输入
+    assert_eq!(
+        dlmm_get_amount_out(&lb_pair, &arrays, 5_014, false, now),
+        None
+    );
+}
+
+#[test]
+fn dlmm_variable_fee_grows_with_bins_crossed() {
+    let now = 1_700_000_000;
+    let mut lb_pair = dlmm_pair(now);
+    let arrays = [bin_array(
+        -65,
+        &[(0, 0, 0, ONE), (1, 1_000, 0, ONE), (2, 1_000_000, 0, ONE)],
+    )];
+    let static_fee = dlmm_get_amount_out(&lb_pair, &arrays, 100_000, false, now).unwrap();
+    assert_eq!(static_fee.bins_crossed, 2);
+
+    // 偏离参考 bin 一个 bin 波动累积值加 10000：(10000 × 25)² × 40000 / 1e11 = 25000，
+    // 两个 bin 为 100000
+    lb_pair.parameters.variable_fee_control = 40_000;
+    let variable_fee = dlmm_get_amount_out(&lb_pair, &arrays, 100_000, false, now).unwrap();
+    assert_eq!(variable_fee.bins_crossed, 2);
+    assert!(variable_fee.fee > static_fee.fee);
+    assert!(variable_fee.amount_out < static_fee.amount_out);
+
+    // 超过 filter_period 后参考 bin 移到 active bin，衰减期内保留一半的波动参考值
+    lb_pair.v_parameters.index_reference = lb_pair.active_id - 10;
+    lb_pair.v_parameters.volatility_reference = 50_000;
+    lb_pair.v_parameters.volatility_accumulator = 200_000;
+    lb_pair.parameters.reduction_factor = 5_000;
+    let stale = dlmm_get_amount_out(&lb_pair, &arrays, 100_000, false, now).unwrap();
+    lb_pair.v_parameters.last_update_timestamp = now as i64 - 60;
+    let decayed = dlmm_get_amount_out(&lb_pair, &arrays, 100_000, false, now).unwrap();
+    assert!(decayed.fee < stale.fee);
+    lb_pair.v_parameters.last_update_timestamp = now as i64 - 600;
+    assert_eq!(
+        dlmm_get_amount_out(&lb_pair, &arrays, 100_000, false, now),
+        Some(variable_fee)
+    );
+}
+
+/// tick spacing 为 1、当前 tick 为 0 的 Raydium CLMM 池子，`initialized_arrays` 为 bitmap 中已初始化的 tick array
+fn clmm_pool(liquidity: u128, initialized_arrays: &[i32]) -> PoolState {
+    let mut data = vec![0; 1544];
+    data[..8].copy_from_slice(&anchor_discriminator("PoolState"));
+    data[8 + 227..8 + 229].copy_from_slice(&1u16.to_le_bytes());
+    data[8 + 229..8 + 245].copy_from_slice(&liquidity.to_le_bytes());
+    data[8 + 245..8 + 261].copy_from_slice(&ONE.to_le_bytes());
+    for start in initialized_arrays {
+        let bit = (start / 60 + 512) as usize;
+        data[8 + 896 + bit / 8] |= 1 << (bit % 8);
+    }
+    PoolState::load_checked(&data).unwrap()
+}
+
+/// 按链上布局构造 tick array 账户，`ticks` 为 `(tick, liquidity_net)`
+fn clmm_tick_array(start: i32, ticks: &[(i32, i128)]) -> TickArrayState {
+    let mut data = vec![0; 10_240];
+    data[..8].copy_from_slice(&anchor_discriminator("TickArrayState"));
+    data[40..44].copy_from_slice(&start.to_le_bytes());
+    for &(tick, liquidity_net) in ticks {
+        let offset = 44 + (tick - start) as usize * 168;
+        data[offset..offset + 4].copy_from_slice(&tick.to_le_bytes());
+        data[offset + 4..offset + 20].copy_from_slice(&liquidity_net.to_le_bytes());
+        data[offset + 20..offset + 36].copy_from_slice(&liquidity_net.unsigned_abs().to_le_bytes());
+    }
+    TickArrayState::load_checked(&data).unwrap()
+}
+
+#[test]
+fn raydium_clmm_quote_walks_loaded_tick_arrays() {
+    let (wide, narrow) = (2_000_000_000_000u128, 1_000_000_000_000u128);
+    // 仓位 [-120, 120] 和 [-30, 30]，tick 分布在 tick array -120、-60、0 和 120 中
+    let pool = clmm_pool(wide + narrow, &[-120, -60, 0, 120]);
+    assert_eq!(pool.liquidity, wide + narrow);
+    assert_eq!(pool.sqrt_price_x64, ONE);
+    assert_eq!(pool.is_tick_array_initialized(-60), Some(true));
+    assert_eq!(pool.is_tick_array_initialized(60), Some(false));
+    assert_eq!(pool.is_tick_array_initialized(60 * 512), None);
+
+    let arrays = [
+        clmm_tick_array(-60, &[(-30, narrow as i128)]),
+        clmm_tick_array(0, &[(30, -(narrow as i128))]),
+    ];
+    assert_eq!(arrays[0].ticks[30].tick, -30);
+    assert!(!arrays[0].ticks[31].is_initialized());
+
+    // 当前区间内成交，但下一个已初始化的 tick 在 tick array -60 中，交易也要传入它
+    let step = compute_swap_step(
+        TickMath::RaydiumClmm,
+        ONE,
+        sqrt_price_at_tick(-30).unwrap(),
+        wide + narrow,
+        1_000_000,
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(
+        raydium_clmm_get_amount_out(&pool, 2_500, &arrays, 1_000_000, true),
+        Some(ClmmQuote {
+            amount_out: step.amount_out,
+            fee: step.fee,
+            tick_arrays_crossed: 1,
+        })
+    );
+
+    // 穿过 -30 后只剩宽仓位，进入 tick array -60
+    let quote = raydium_clmm_get_amount_out(&pool, 2_500, &arrays, 6_000_000_000, true).unwrap();
+    assert_eq!(quote.tick_arrays_crossed, 1);
+    let first = compute_swap_step(
+        TickMath::RaydiumClmm,
+        ONE,
+        sqrt_price_at_tick(-30).unwrap(),
+        wide + narrow,
+        6_000_000_000,
+        2_500,
+    )
+    .unwrap();
+    let second = compute_swap_step(
+        TickMath::RaydiumClmm,
+        first.sqrt_price_next,
+        sqrt_price_at_tick(-60).unwrap(),
+        wide,
+        6_000_000_000 - first.amount_in - first.fee,
+        2_500,
+    )
+    .unwrap();
+    assert_eq!(quote.amount_out, first.amount_out + second.amount_out);
+
+    // tick array -120 已初始化但没有加载，兑换不能越过 tick -60
+    assert_eq!(
+        raydium_clmm_get_amount_out(&pool, 2_500, &arrays, 20_000_000_000, true),
+        None
+    );
+    // 反方向：tick array 60 未初始化可以跳过，120 没有加载
+    assert!(raydium_clmm_get_amount_out(&pool, 2_500, &arrays, 10_000_000_000, false).is_some());
+    assert_eq!(
+        raydium_clmm_get_amount_out(&pool, 2_500, &arrays, 20_000_000_000, false),
+        None
+    );
+    // 当前 tick array 没有加载时无法报价
+    assert_eq!(
+        raydium_clmm_get_amount_out(&pool, 2_500, &arrays[..1], 1_000, false),
+        None
+    );
+}
+
+/// 用连续的集中流动性公式（f64）计算 Whirlpool 的兑换结果，作为与 `swap_exact_in` 的整数运算无关的参照
+///
+/// 区间内 `a → b` 的新价格为 `√P' = L√P / (L + Δa√P)`，输出 `Δb = Δa·√P·√P'`；
+/// `b → a` 的新价格为 `√P' = √P + Δb / L`，输出 `Δa = Δb / (√P·√P')`。
+/// 到达已初始化的 tick 时按 liquidity_net 调整流动性，越过 `boundary` 时返回 `None`。
+/// 返回输出数量、手续费和最终价格所在的 tick
+fn reference_swap(
+    whirlpool: &Whirlpool,
+    ticks: &[(i32, i128)],
+    boundary: i32,
+    amount_in: u64,
+    a_to_b: bool,
+) -> Option<(f64, f64, i32)> {
+    let sqrt_price_at = |tick: i32| 1.0001f64.powf(tick as f64 / 2.0);
+    let fee_rate = whirlpool.fee_rate as f64 / 1e6;
+    let mut sqrt_price = whirlpool.sqrt_price as f64 / 2f64.powi(64);
+    let mut liquidity = whirlpool.liquidity as f64;
+    let mut remaining = amount_in as f64 * (1.0 - fee_rate);
+    let mut amount_out = 0.0;
+    let mut ticks = ticks.to_vec();
+    ticks.sort_by_key(|(tick, _)| if a_to_b { -tick } else { *tick });
+    let current = sqrt_price.ln() / 1.0001f64.ln() * 2.0;
+    let next_ticks = ticks
+        .into_iter()
+        .filter(|(tick, _)| ((*tick as f64) < current) == a_to_b)
+        .map(|(tick, net)| (tick, Some(net)))
+        .chain([(boundary, None)]);
+    for (tick, liquidity_net) in next_ticks {
+        let target = sqrt_price_at(tick);
+        let (to_target, next) = if a_to_b {
+            let next = liquidity * sqrt_price / (liquidity + remaining * sqrt_price);
+            (liquidity * (1.0 / target - 1.0 / sqrt_price), next)
+        } else {
+            (
+                liquidity * (target - sqrt_price),
+                sqrt_price + remaining / liquidity,
+            )
+        };
+        let (step_in, next) = if remaining < to_target {
+            (remaining, next)
+        } else {
+            (to_target, target)
+        };
+        amount_out += if a_to_b {
+            step_in * sqrt_price * next
+        } else {
+            step_in / (sqrt_price * next)
+        };
+        remaining -= step_in;
+        sqrt_price = next;
+        if remaining <= 0.0 {
+            let tick = (sqrt_price.ln() / 1.0001f64.ln() * 2.0).floor() as i32;
+            return Some((amount_out, amount_in as f64 * fee_rate, tick));
+        }
+        let liquidity_net = liquidity_net? as f64;
+        liquidity += if a_to_b {
+            -liquidity_net
+        } else {
+            liquidity_net
+        };
+    }
+    None
+}
+
+#[test]
+fn whirlpool_quote_matches_the_reference_formula() {
+    let whirlpool = Whirlpool::try_deserialize(&fixture_data("whirlpool")).unwrap();
+    let fee_rate = whirlpool_fee_rate(&whirlpool, None, 0);
+    assert_eq!(fee_rate, 400);
+    // fixture 的 tick_current_index 是 sqrt price 所在的 tick
+    let sqrt_price = whirlpool.sqrt_price as f64 / 2f64.powi(64);
+    assert_eq!(
+        (sqrt_price.ln() / 1.0001f64.ln() * 2.0).floor() as i32,
+        whirlpool.tick_current_index
+    );
+    // tick array -18304 中是仓位 [-18240, -18160] 的两端，动态 tick array -17952 中的 -17920
+    // 是一个宽仓位的上端，其余流动性的 tick 都在已加载的范围 [-18304, -17600) 之外
+    let fixed = TickArray::try_deserialize(&fixture_data("whirlpool_tick_array")).unwrap();
+    let dynamic =
+        TickArray::try_deserialize(&fixture_data("whirlpool_dynamic_tick_array")).unwrap();
+    let ticks: Vec<(i32, i128)> = [&fixed, &dynamic]
+        .into_iter()
+        .flat_map(|array| {
+            array
+                .ticks
+                .iter()
+                .enumerate()
+                .filter(|(_, tick)| tick.initialized)
+                .map(|(i, tick)| (array.start_tick_index + i as i32 * 4, tick.liquidity_net))
+        })
+        .collect();
+    assert_eq!(
+        ticks,
+        vec![
+            (-18240, 100_000_000_000_000),
+            (-18160, -100_000_000_000_000),
+            (-17920, -5_000_000_000_000),
+        ]
+    );
+    let arrays = [dynamic, fixed.clone()];
+
+    // 期望值来自连续公式而不是链上的兑换或 SDK 报价：fixture 是构造的账户，链上没有对应的池子。
+    // 整数运算每一步向下取整输出、向上取整手续费，与连续公式只差取整误差
+    let check = |amount_in: u64, a_to_b: bool, tick_arrays_crossed: u32| {
+        let boundary = if a_to_b { -18304 } else { -17600 };
+        let (amount_out, fee, _) =
+            reference_swap(&whirlpool, &ticks, boundary, amount_in, a_to_b).unwrap();
+        let quote =
+            whirlpool_get_amount_out(&whirlpool, fee_rate, &arrays, amount_in, a_to_b).unwrap();
+        let tolerance = amount_out * 1e-9 + 4.0;
+        assert!(
+            quote.amount_out as f64 <= amount_out + 1.0
+                && quote.amount_out as f64 >= amount_out - tolerance,
+            "{} {}: {} vs {}",
+            amount_in,
+            a_to_b,
+            quote.amount_out,
+            amount_out
+        );
+        assert!(quote.fee as f64 >= fee && quote.fee as f64 <= fee + 4.0);
+        assert_eq!(quote.tick_arrays_crossed, tick_arrays_crossed);
+    };
+    check(1_000_000_000, true, 0);
+    // 穿过 -18240 后只剩宽仓位，停在 -18304 之前
+    let (_, _, tick) = reference_swap(&whirlpool, &ticks, -18304, 700_000_000_000, true).unwrap();
+    assert!((-18304..-18240).contains(&tick));
+    check(700_000_000_000, true, 0);
+    // 反方向穿过 -18160 和 -17920，进入动态 tick array
+    let (_, _, tick) = reference_swap(&whirlpool, &ticks, -17600, 300_000_000_000, false).unwrap();
+    assert!((-17920..-17600).contains(&tick));
+    check(300_000_000_000, false, 2);
+
+    // 参照公式越过已加载范围的数量无法报价：tick array -18656 没有加载
+    for (amount_in, a_to_b) in [(1_000_000_000_000, true), (1_000_000_000_000, false)] {
+        let boundary = if a_to_b { -18304 } else { -17600 };
+        assert_eq!(
+            reference_swap(&whirlpool, &ticks, boundary, amount_in, a_to_b),
+            None
+        );
+        assert_eq!(
+            whirlpool_get_amount_out(&whirlpool, fee_rate, &arrays, amount_in, a_to_b),
+            None
+        );
+    }
+    // 没有加载动态 tick array 时不能越过 -17952
+    assert_eq!(
+        whirlpool_get_amount_out(&whirlpool, fee_rate, &[fixed], 300_000_000_000, false),
+        None
+    );
+}
@@ -0,0 +1,106 @@
+//! 库存再平衡测试：选择卖出池子、阈值、滑点和转账手续费
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::dex::raydium::raydium_cp_program_id;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::PoolReserves;
+use solana_onchain_arbitrage_bot::rebalance::{plan_sale, sale_instructions};
+use solana_onchain_arbitrage_bot::transfer_fee::TransferFee;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+fn reserves(sol: u64) -> PoolReserves {
+    PoolReserves {
+        token: 1_000_000_000_000,
+        sol,
+        fee_bps: 25,
+    }
+}
+
+/// 两个 Raydium CP 池子和一个没有 OpenBook 市场、不能直接兑换的 Raydium 池子
+fn pool_data() -> (MintPoolData, [Pubkey; 3]) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let pools = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    for pool in pools[..2].iter().copied() {
+        pool_data.add_raydium_cp_pool(
+            pool,
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+    }
+    pool_data.add_raydium_pool(pools[2], Pubkey::new_unique(), Pubkey::new_unique());
+    (pool_data, pools)
+}
+
+#[test]
+fn residual_tokens_are_sold_in_the_best_swappable_pool() {
+    let (pool_data, [cheap, rich, no_market]) = pool_data();
+    // 没有市场的池子报价最高，但不能直接兑换
+    let reserves = HashMap::from([
+        (cheap, reserves(1_000_000_000_000)),
+        (rich, reserves(1_050_000_000_000)),
+        (no_market, reserves(2_000_000_000_000)),
+    ]);
+
+    let sale = plan_sale(&pool_data, &reserves, 1_000_000, 0, 100).unwrap();
+    assert_eq!(sale.pool, rich);
+    assert_eq!(sale.amount_in, 1_000_000);
+    assert_eq!(sale.expected_out, reserves[&rich].sell(1_000_000));
+    assert_eq!(sale.minimum_out, sale.expected_out * 99 / 100);
+
+    let instructions = sale_instructions(&pool_data, &Pubkey::new_unique(), &sale, 1000).unwrap();
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(instructions[2].program_id, *raydium_cp_program_id());
+    assert_eq!(instructions[2].accounts[3].pubkey, rich);
+
+    assert_eq!(plan_sale(&pool_data, &reserves, 0, 0, 100), None);
+    assert_eq!(
+        plan_sale(&pool_data, &reserves, 1_000_000, sale.expected_out + 1, 100),
+        None
+    );
+}
+
+#[test]
+fn transfer_fee_is_deducted_before_quoting() {
+    let (mut pool_data, [pool, _, _]) = pool_data();
+    let reserves = HashMap::from([(pool, reserves(1_000_000_000_000))]);
+    pool_data.transfer_fee = Some(TransferFee {
+        epoch: 0,
+        maximum_fee: u64::MAX,
+        basis_points: 100,
+    });
+
+    let sale = plan_sale(&pool_data, &reserves, 1_000_000, 0, 0).unwrap();
+    assert_eq!(sale.amount_in, 1_000_000);
+    assert_eq!(sale.expected_out, reserves[&pool].sell(990_000));
+    assert_eq!(sale.minimum_out, sale.expected_out);
+}
+
+#[test]
+fn rebalance_config_defaults_and_validation() {
+    let config = parse_config("[rebalance]\nenabled = true")
+        .unwrap()
+        .rebalance
+        .unwrap();
+    assert_eq!(config.min_value(), 0.01);
+    assert_eq!(config.slippage_bps(), 100);
+    assert_eq!(config.check_interval().as_secs(), 60);
+
+    let error = parse_config(
+        "[rebalance]\nenabled = true\nmin_value = -1.0\nslippage_bps = 10001\ncheck_interval_secs = 0",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("rebalance.min_value"), "{}", error);
+    assert!(error.contains("rebalance.slippage_bps"), "{}", error);
+    assert!(error.contains("rebalance.check_interval_secs"), "{}", error);
+}
@@ -0,0 +1,114 @@
+//! 研究模式机会记录测试
+
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::{
+    profitable_routes, spot_prices, PoolReserves, TradeSizing,
+};
+use solana_onchain_arbitrage_bot::research::{OpportunityLog, CSV_HEADER};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("{}-{}.csv", name, std::process::id()))
+}
+
+/// 一个 Raydium 池和一个 Pump 池，Pump 池的 SOL 储备高出 `spread_percent`
+fn two_pools(spread_percent: u64) -> (MintPoolData, Pubkey, Pubkey, HashMap<Pubkey, PoolReserves>) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (cheap, dear) = (Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_raydium_pool(cheap, Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_pump_pool(
+        dear,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let reserves = HashMap::from([
+        (
+            cheap,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: 1_000 * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+        (
+            dear,
+            PoolReserves {
+                token: 1_000_000_000,
+                sol: (1_000 + 10 * spread_percent) * LAMPORTS_PER_SOL,
+                fee_bps: 25,
+            },
+        ),
+    ]);
+    (pool_data, cheap, dear, reserves)
+}
+
+#[test]
+fn profitable_routes_list_every_profitable_pair() {
+    let (pool_data, cheap, dear, reserves) = two_pools(1);
+    let sizing = TradeSizing::Optimal {
+        max_input: 10 * LAMPORTS_PER_SOL,
+    };
+    let routes = profitable_routes(&pool_data, &reserves, &sizing);
+    assert_eq!(routes.len(), 1);
+    assert_eq!((routes[0].buy_pool, routes[0].sell_pool), (cheap, dear));
+    assert!(routes[0].profit > 0);
+
+    let fixed = TradeSizing::Fixed(vec![LAMPORTS_PER_SOL / 10, LAMPORTS_PER_SOL]);
+    let routes = profitable_routes(&pool_data, &reserves, &fixed);
+    assert_eq!(routes[0].size, LAMPORTS_PER_SOL);
+
+    // 价差不够抵消两次手续费
+    let (pool_data, _, _, reserves) = two_pools(0);
+    assert!(profitable_routes(&pool_data, &reserves, &sizing).is_empty());
+}
+
+#[test]
+fn opportunities_are_written_when_they_disappear() {
+    let path = temp_path("opportunities");
+    let _ = std::fs::remove_file(&path);
+    let (pool_data, cheap, dear, reserves) = two_pools(1);
+    let prices = spot_prices(&reserves);
+    let sizing = TradeSizing::Optimal {
+        max_input: 10 * LAMPORTS_PER_SOL,
+    };
+    let routes = profitable_routes(&pool_data, &reserves, &sizing);
+
+    let log = OpportunityLog::create(&path).unwrap();
+    assert_eq!(log.observe(&pool_data, &routes, &prices, 1_000).unwrap(), 0);
+    assert_eq!(log.observe(&pool_data, &routes, &prices, 1_400).unwrap(), 0);
+    assert_eq!(log.open_count(), 1);
+    assert_eq!(log.observe(&pool_data, &[], &prices, 1_800).unwrap(), 1);
+    assert_eq!(log.open_count(), 0);
+
+    // 重新出现后在退出时写入
+    log.observe(&pool_data, &routes, &prices, 2_000).unwrap();
+    assert_eq!(log.flush_open().unwrap(), 1);
+    drop(log);
+
+    // 追加打开时不重复写表头
+    OpportunityLog::create(&path).unwrap();
+    let content = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = content.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], CSV_HEADER);
+
+    let columns: Vec<&str> = lines[1].split(',').collect();
+    assert_eq!(columns.len(), CSV_HEADER.split(',').count());
+    assert_eq!(columns[0], pool_data.mint.to_string());
+    assert_eq!(columns[1], "raydium");
+    assert_eq!(columns[2], cheap.to_string());
+    assert_eq!(columns[3], "pump");
+    assert_eq!(columns[4], dear.to_string());
+    assert_eq!(&columns[5..9], &["1000", "1400", "400", "2"]);
+    assert_eq!(columns[11], "100.00");
+    assert_eq!(lines[2].split(',').nth(7), Some("0"));
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,123 @@
+//! 按路线调整优先费测试：出价调整、上下限、统计窗口和配置校验
+
+mod common;
+
+use solana_onchain_arbitrage_bot::config::{Config, RouteFeesConfig};
+use solana_onchain_arbitrage_bot::quote::SizedQuote;
+use solana_onchain_arbitrage_bot::route_fees::{RouteFees, LANDING_WINDOW};
+use solana_onchain_arbitrage_bot::status::BotStatus;
+use solana_sdk::pubkey::Pubkey;
+
+fn parse_config(extra: &str) -> anyhow::Result<Config> {
+    common::parse_config(&format!(
+        "[spam]\nenabled = false\nsending_rpc_urls = []\ncompute_unit_price = 100000\n\n{extra}"
+    ))
+}
+
+fn route_fees_config(extra: &str) -> RouteFeesConfig {
+    parse_config(&format!("[route_fees]\nenabled = true\n{extra}"))
+        .unwrap()
+        .route_fees
+        .unwrap()
+}
+
+#[test]
+fn contested_routes_bid_more_and_quiet_routes_bid_less() {
+    let route_fees = RouteFees::new(&route_fees_config(""), 100_000);
+    let (contested, quiet) = ("contested", "quiet");
+    assert_eq!(route_fees.price(contested), 100_000);
+
+    for _ in 0..5 {
+        route_fees.record(contested, false);
+        route_fees.record(quiet, true);
+    }
+    assert!(route_fees.price(contested) > 100_000);
+    assert!(route_fees.price(quiet) < 100_000);
+
+    // 上链率等于目标时价格大致不变
+    let balanced = "balanced";
+    for _ in 0..20 {
+        route_fees.record(balanced, true);
+        route_fees.record(balanced, false);
+    }
+    let price = route_fees.price(balanced);
+    assert!((90_000..=110_000).contains(&price), "{}", price);
+
+    let status = route_fees.record(balanced, true);
+    assert_eq!(status.sent, 41);
+    assert_eq!(status.landed, 21);
+    assert_eq!(status.compute_unit_price, route_fees.price(balanced));
+}
+
+#[test]
+fn prices_stay_within_bounds_and_window_is_capped() {
+    let config =
+        route_fees_config("min_compute_unit_price = 50000\nmax_compute_unit_price = 150000");
+    let route_fees = RouteFees::new(&config, 100_000);
+
+    for _ in 0..200 {
+        route_fees.record("up", false);
+    }
+    let status = route_fees.record("up", false);
+    assert_eq!(status.compute_unit_price, 150_000);
+    assert_eq!(status.sent, LANDING_WINDOW as u64);
+    assert_eq!(status.landing_rate(), Some(0.0));
+
+    for _ in 0..200 {
+        route_fees.record("down", true);
+    }
+    assert_eq!(route_fees.price("down"), 50_000);
+}
+
+#[test]
+fn route_keys_and_dashboard_status() {
+    let mint = Pubkey::new_unique();
+    let quote = SizedQuote {
+        buy_pool: Pubkey::new_unique(),
+        sell_pool: Pubkey::new_unique(),
+        size: 1_000,
+        profit: 10,
+    };
+    assert_eq!(RouteFees::route_key(&mint, None), mint.to_string());
+    assert_eq!(
+        RouteFees::route_key(&mint, Some(&quote)),
+        format!("{}:{}>{}", mint, quote.buy_pool, quote.sell_pool)
+    );
+
+    let route_fees = RouteFees::new(&route_fees_config(""), 100_000);
+    let status = BotStatus::new("http://127.0.0.1:8899".to_string(), false, 100_000);
+    let route = RouteFees::route_key(&mint, Some(&quote));
+    status.update_route_fee(&route, route_fees.record(&route, true));
+    let snapshot = status.snapshot();
+    assert_eq!(snapshot.route_fees[&route].landed, 1);
+    assert_eq!(
+        snapshot.route_fees[&route].compute_unit_price,
+        route_fees.price(&route)
+    );
+}
+
+#[test]
+fn route_fees_config_defaults_and_validation() {
+    let config = route_fees_config("");
+    assert_eq!(config.target_landing_rate(), 0.5);
+    assert_eq!(config.step(), 0.1);
+    assert_eq!(config.min_compute_unit_price(100_000), 25_000);
+    assert_eq!(config.max_compute_unit_price(100_000), 400_000);
+
+    let error = parse_config(
+        "[route_fees]\nenabled = true\ntarget_landing_rate = 1.0\nstep = 0\nmin_compute_unit_price = 10\nmax_compute_unit_price = 5",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(
+        error.contains("route_fees.target_landing_rate"),
+        "{}",
+        error
+    );
+    assert!(error.contains("route_fees.step"), "{}", error);
+    assert!(
+        error.contains("route_fees.min_compute_unit_price"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,127 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_onchain_arbitrage_bot::rpc::{AccountCache, CachingSender};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::{Arc, Mutex};
+
+type Requests = Arc<Mutex<Vec<(RpcRequest, serde_json::Value)>>>;
+
+/// 记录收到的请求，`missing` 中的账户返回不存在，其他账户都存在
+struct MockSender {
+    requests: Requests,
+    missing: Vec<String>,
+}
+
+fn account_json() -> serde_json::Value {
+    serde_json::json!({
+        "data": ["", "base64"],
+        "executable": false,
+        "lamports": 1,
+        "owner": Pubkey::default().to_string(),
+        "rentEpoch": 0,
+        "space": 0,
+    })
+}
+
+#[async_trait]
+impl RpcSender for MockSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> ClientResult<serde_json::Value> {
+        // 客户端第一次请求前会查询节点版本
+        if request == RpcRequest::GetVersion {
+            return Ok(serde_json::json!({ "solana-core": "1.18.26" }));
+        }
+        self.requests
+            .lock()
+            .unwrap()
+            .push((request, params.clone()));
+        let account = |pubkey: &serde_json::Value| {
+            let pubkey = pubkey.as_str().unwrap();
+            if self.missing.iter().any(|missing| missing == pubkey) {
+                serde_json::Value::Null
+            } else {
+                account_json()
+            }
+        };
+        let value = match request {
+            RpcRequest::GetAccountInfo => account(&params[0]),
+            RpcRequest::GetMultipleAccounts => {
+                params[0].as_array().unwrap().iter().map(account).collect()
+            }
+            _ => serde_json::Value::Null,
+        };
+        Ok(serde_json::json!({ "context": { "slot": 100 }, "value": value }))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        "mock".to_string()
+    }
+}
+
+fn cached_client(missing: Vec<String>) -> (RpcClient, Arc<AccountCache>, Requests) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let cache = Arc::new(AccountCache::new(150));
+    let sender = MockSender {
+        requests: requests.clone(),
+        missing,
+    };
+    let client = RpcClient::new_sender(
+        CachingSender::new(Box::new(sender), cache.clone()),
+        RpcClientConfig::with_commitment(CommitmentConfig::default()),
+    );
+    (client, cache, requests)
+}
+
+#[test]
+fn repeated_account_reads_hit_the_network_once() {
+    let (client, cache, requests) = cached_client(Vec::new());
+    let pubkey = Pubkey::new_unique();
+
+    let first = client.get_account(&pubkey).unwrap();
+    let second = client.get_account(&pubkey).unwrap();
+
+    assert_eq!(first, second);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    assert_eq!((cache.hits(), cache.misses()), (1, 1));
+}
+
+#[test]
+fn multiple_accounts_only_fetch_uncached_keys() {
+    let (client, _cache, requests) = cached_client(Vec::new());
+    let cached = Pubkey::new_unique();
+    let uncached = Pubkey::new_unique();
+    client.get_account(&cached).unwrap();
+
+    let accounts = client.get_multiple_accounts(&[cached, uncached]).unwrap();
+
+    assert_eq!(accounts.len(), 2);
+    assert_eq!(accounts[0], Some(client.get_account(&cached).unwrap()));
+    assert!(accounts[1].is_some());
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].0, RpcRequest::GetMultipleAccounts);
+    assert_eq!(requests[1].1[0], serde_json::json!([uncached.to_string()]));
+}
+
+#[test]
+fn missing_accounts_are_not_cached() {
+    let pubkey = Pubkey::new_unique();
+    let (client, cache, requests) = cached_client(vec![pubkey.to_string()]);
+
+    assert!(client.get_account(&pubkey).is_err());
+    assert!(client.get_account(&pubkey).is_err());
+
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    assert_eq!(cache.hits(), 0);
+}
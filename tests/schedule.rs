@@ -0,0 +1,67 @@
+//! 代币轮询节奏测试：抖动范围、相位错开和配置校验
+
+mod common;
+
+use common::TestConfig;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::schedule::ProcessSchedule;
+use std::time::Duration;
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+fn parse_config(mint_options: &str) -> anyhow::Result<Config> {
+    TestConfig {
+        mint_options,
+        ..Default::default()
+    }
+    .parse()
+}
+
+#[test]
+fn next_delay_stays_within_jitter() {
+    let schedule = ProcessSchedule::new(millis(400), millis(50), Duration::ZERO);
+    for _ in 0..1000 {
+        let delay = schedule.next_delay();
+        assert!(delay >= millis(400) && delay <= millis(450), "{:?}", delay);
+    }
+
+    let fixed = ProcessSchedule::new(millis(400), Duration::ZERO, Duration::ZERO);
+    assert_eq!(fixed.next_delay(), millis(400));
+    assert_eq!(fixed.to_string(), "every 400ms");
+}
+
+#[test]
+fn staggered_mints_spread_across_the_delay() {
+    let offsets: Vec<_> = (0..4)
+        .map(|index| ProcessSchedule::for_mint(400, 0, None, index, 4, true).phase_offset())
+        .collect();
+    assert_eq!(offsets, [0, 100, 200, 300].map(millis));
+
+    // 配置的 phase_offset 优先，不错开时从 0 开始
+    let explicit = ProcessSchedule::for_mint(400, 20, Some(250), 1, 4, true);
+    assert_eq!(explicit.phase_offset(), millis(250));
+    assert_eq!(
+        explicit.to_string(),
+        "every 400ms + up to 20ms jitter, first round after 250ms"
+    );
+    assert_eq!(
+        ProcessSchedule::for_mint(400, 0, None, 3, 4, false).phase_offset(),
+        Duration::ZERO
+    );
+}
+
+#[test]
+fn jitter_and_phase_offset_validation() {
+    let config = parse_config("process_delay_jitter = 100\nphase_offset = 500").unwrap();
+    let mint_config = &config.routing.mint_config_list[0];
+    assert_eq!(mint_config.process_delay_jitter(), 100);
+    assert!(!config.bot.stagger_mints());
+
+    let error = parse_config("process_delay_jitter = 1001\nphase_offset = 3600001")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("process_delay_jitter"), "{}", error);
+    assert!(error.contains("phase_offset"), "{}", error);
+}
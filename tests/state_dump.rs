@@ -0,0 +1,195 @@
+//! 状态快照测试：引用的账户、写入和读取、离线报价以及恢复运行状态
+
+mod common;
+
+use common::{parse_config_with_wallet, token_account};
+use solana_client::rpc_client::RpcClient;
+use solana_onchain_arbitrage_bot::app_state::AppState;
+use solana_onchain_arbitrage_bot::dex::raydium::raydium_cp_program_id;
+use solana_onchain_arbitrage_bot::notifications::Notifier;
+use solana_onchain_arbitrage_bot::pools::{MintPoolData, PoolDirection};
+use solana_onchain_arbitrage_bot::quote::PoolReserves;
+use solana_onchain_arbitrage_bot::state_dump::{
+    referenced_accounts, StateDump, MANIFEST_FILE, STATE_DUMP_VERSION, STATE_FILE,
+};
+use solana_onchain_arbitrage_bot::status::BotStatus;
+use solana_onchain_arbitrage_bot::transfer_fee::TransferFee;
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 一个 Raydium CP 池子和一个 Whirlpool 池子，返回 Raydium CP 池子及其两个 vault
+fn pool_data() -> (MintPoolData, [Pubkey; 3]) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let [pool, token_vault, sol_vault] = [
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    ];
+    pool_data.add_raydium_cp_pool(
+        pool,
+        token_vault,
+        sol_vault,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    pool_data.add_whirlpool_pool(
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        vec![Pubkey::new_unique()],
+        None,
+    );
+    pool_data
+        .pool_directions
+        .insert(pool, PoolDirection::BuyOnly);
+    pool_data.transfer_fee = Some(TransferFee {
+        epoch: 1,
+        maximum_fee: 1_000,
+        basis_points: 50,
+    });
+    (pool_data, [pool, token_vault, sol_vault])
+}
+
+fn dump_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("state-dump-{}-{}", name, std::process::id()))
+}
+
+/// 除一个 vault 之外所有引用的账户都存在的快照
+fn dump(pool_data: MintPoolData, [_, token_vault, sol_vault]: [Pubkey; 3]) -> StateDump {
+    let mut accounts: HashMap<Pubkey, Account> = referenced_accounts(&pool_data)
+        .into_iter()
+        .map(|pubkey| (pubkey, Account::default()))
+        .collect();
+    accounts.insert(token_vault, token_account(&pool_data.mint, 1_000_000));
+    accounts.insert(sol_vault, token_account(&pool_data.quote_mint, 2_000_000));
+    accounts.remove(&pool_data.whirlpool_pools[0].x_vault);
+    StateDump {
+        version: STATE_DUMP_VERSION,
+        slot: 42,
+        created_at: 1_700_000_000,
+        mints: vec![pool_data],
+        accounts,
+    }
+}
+
+#[test]
+fn referenced_accounts_cover_swap_and_quote_accounts() {
+    let (pool_data, [pool, token_vault, sol_vault]) = pool_data();
+    let accounts = referenced_accounts(&pool_data);
+
+    for pubkey in [
+        pool_data.mint,
+        pool_data.wallet_wsol_account,
+        pool,
+        token_vault,
+        sol_vault,
+        *raydium_cp_program_id(),
+        pool_data.whirlpool_pools[0].tick_arrays[0],
+    ] {
+        assert!(accounts.contains(&pubkey), "{}", pubkey);
+    }
+    // 按地址排序去重
+    assert!(accounts.windows(2).all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn dumps_round_trip_through_a_directory() {
+    let (pool_data, pools) = pool_data();
+    let missing = pool_data.whirlpool_pools[0].x_vault;
+    let dump = dump(pool_data.clone(), pools);
+    let dir = dump_dir("round-trip");
+    dump.save(&dir).unwrap();
+    assert!(dir.join(MANIFEST_FILE).exists());
+
+    let loaded = StateDump::load(&dir).unwrap();
+    assert_eq!(loaded.slot, 42);
+    assert_eq!(loaded.accounts, dump.accounts);
+    assert_eq!(loaded.missing_accounts(), [missing]);
+
+    let restored = loaded.mint(&pool_data.mint).unwrap();
+    assert_eq!(restored.pool_addresses(), pool_data.pool_addresses());
+    assert_eq!(restored.pool_accounts(), pool_data.pool_accounts());
+    assert_eq!(restored.pool_directions, pool_data.pool_directions);
+    assert_eq!(restored.transfer_fee, pool_data.transfer_fee);
+
+    let manifest: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+    assert_eq!(manifest["slot"], 42);
+    assert_eq!(manifest["missing_accounts"][0], missing.to_string());
+    assert_eq!(
+        manifest["mints"][0]["pools"][0],
+        format!("raydium_cp {}", pools[0])
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn loading_rejects_other_versions_and_missing_dumps() {
+    let (pool_data, pools) = pool_data();
+    let mut dump = dump(pool_data, pools);
+    dump.version = STATE_DUMP_VERSION + 1;
+    let dir = dump_dir("version");
+    dump.save(&dir).unwrap();
+
+    let error = StateDump::load(&dir).unwrap_err().to_string();
+    assert!(
+        error.contains(&format!("expected {}", STATE_DUMP_VERSION)),
+        "{}",
+        error
+    );
+
+    std::fs::remove_file(dir.join(STATE_FILE)).unwrap();
+    let error = StateDump::load(&dir).unwrap_err().to_string();
+    assert!(error.contains("Failed to read state dump"), "{}", error);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn dumps_quote_offline_and_restore_the_app_state() {
+    let (pool_data, pools) = pool_data();
+    let dump = dump(pool_data, pools);
+    let pool_data = &dump.mints[0];
+
+    let reserves = dump.reserves(pool_data);
+    assert_eq!(
+        reserves,
+        HashMap::from([(
+            pools[0],
+            PoolReserves {
+                token: 1_000_000,
+                sol: 2_000_000,
+                fee_bps: reserves[&pools[0]].fee_bps,
+            }
+        )])
+    );
+
+    let wallet = Keypair::new();
+    let config = parse_config_with_wallet(&wallet, "").unwrap();
+    let state = AppState::new(
+        config,
+        wallet,
+        Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string())),
+        (Hash::new_unique(), 42),
+        Arc::new(BotStatus::new(
+            "http://127.0.0.1:8899".to_string(),
+            false,
+            0,
+        )),
+        Notifier::new(None),
+    );
+    dump.restore(&state);
+    assert_eq!(state.mints(), [pool_data.mint]);
+    let restored = state.mint(&pool_data.mint).unwrap();
+    assert_eq!(
+        restored.lock().await.pool_addresses(),
+        pool_data.pool_addresses()
+    );
+}
@@ -0,0 +1,261 @@
+//! 交易提交器测试：扇出到多个提交器、签名去重、已处理时停止、失败处理、按配置选择和模拟记录器
+
+mod common;
+
+use async_trait::async_trait;
+use common::TestConfig;
+use solana_client::rpc_client::RpcClient;
+use solana_onchain_arbitrage_bot::config::{Config, SendingEndpoint, TxVersion};
+use solana_onchain_arbitrage_bot::submit::{
+    PaperSubmitter, PaperTotals, PaperTrade, RpcSubmitter, Submission, SubmissionResult, Submitter,
+    Submitters, PAPER_TRADE_HISTORY,
+};
+use solana_onchain_arbitrage_bot::transaction::SendingClient;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 返回固定结果并记录调用次数的提交器
+struct MockSubmitter {
+    name: &'static str,
+    result: Result<SubmissionResult, &'static str>,
+    calls: AtomicUsize,
+}
+
+impl MockSubmitter {
+    fn new(name: &'static str, result: Result<SubmissionResult, &'static str>) -> Arc<Self> {
+        Arc::new(Self {
+            name,
+            result,
+            calls: AtomicUsize::new(0),
+        })
+    }
+
+    fn accepting(name: &'static str, signature: Signature) -> Arc<Self> {
+        let mut result = SubmissionResult::default();
+        result.record(signature, name);
+        Self::new(name, Ok(result))
+    }
+
+    fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl Submitter for MockSubmitter {
+    fn endpoints(&self) -> Vec<String> {
+        vec![self.name.to_string()]
+    }
+
+    async fn send(&self, _submission: &Submission<'_>) -> anyhow::Result<SubmissionResult> {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.result.clone().map_err(|e| anyhow::anyhow!(e))
+    }
+}
+
+fn transaction(signature: Signature) -> VersionedTransaction {
+    VersionedTransaction {
+        signatures: vec![signature],
+        message: VersionedMessage::Legacy(Default::default()),
+    }
+}
+
+fn submission(tx: &VersionedTransaction) -> Submission<'_> {
+    Submission {
+        tx,
+        legacy_tx: None,
+        context_slot: 0,
+        quote_mint: Pubkey::new_unique(),
+        wallet_quote_account: Pubkey::new_unique(),
+    }
+}
+
+#[tokio::test]
+async fn fan_out_merges_endpoints_under_one_signature() {
+    let signature = Signature::new_unique();
+    let tx = transaction(signature);
+    let tpu = MockSubmitter::accepting("tpu", signature);
+    let rpc = MockSubmitter::accepting("https://rpc.example.com", signature);
+    let submitters = Submitters::new(vec![tpu.clone(), rpc.clone()]);
+
+    assert_eq!(
+        submitters.endpoints(),
+        vec!["tpu", "https://rpc.example.com"]
+    );
+    let outcome = submitters.send(&submission(&tx)).await.unwrap();
+    assert_eq!(outcome.signatures, vec![signature]);
+    assert!(!outcome.already_processed);
+    assert_eq!((tpu.calls(), rpc.calls()), (1, 1));
+}
+
+#[tokio::test]
+async fn already_processed_skips_the_remaining_submitters() {
+    let signature = Signature::new_unique();
+    let tx = transaction(signature);
+    let mut processed = SubmissionResult::default();
+    processed.record(signature, "first");
+    processed.already_processed = true;
+    let first = MockSubmitter::new("first", Ok(processed));
+    let second = MockSubmitter::accepting("second", signature);
+
+    let outcome = Submitters::new(vec![first.clone(), second.clone()])
+        .send(&submission(&tx))
+        .await
+        .unwrap();
+    assert!(outcome.already_processed);
+    assert_eq!(outcome.signatures, vec![signature]);
+    assert_eq!(second.calls(), 0);
+}
+
+#[tokio::test]
+async fn one_failing_submitter_does_not_fail_the_send() {
+    let signature = Signature::new_unique();
+    let tx = transaction(signature);
+    let failing = MockSubmitter::new("jito", Err("bundle rejected"));
+    let rpc = MockSubmitter::accepting("rpc", signature);
+
+    let outcome = Submitters::new(vec![failing.clone(), rpc])
+        .send(&submission(&tx))
+        .await
+        .unwrap();
+    assert_eq!(outcome.signatures, vec![signature]);
+
+    // 所有提交器都失败时返回错误
+    let error = Submitters::new(vec![failing])
+        .send(&submission(&tx))
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "bundle rejected");
+
+    // 没有端点接受交易但也没有出错时签名为空
+    let outcome = Submitters::new(vec![MockSubmitter::new(
+        "rpc",
+        Ok(SubmissionResult::default()),
+    )])
+    .send(&submission(&tx))
+    .await
+    .unwrap();
+    assert!(outcome.signatures.is_empty());
+}
+
+fn sending_client(url: &str, tx_version: TxVersion) -> SendingClient {
+    let mut endpoint = SendingEndpoint::new(url);
+    endpoint.tx_version = tx_version;
+    SendingClient::new(Arc::new(RpcClient::new(url.to_string())), endpoint)
+}
+
+#[test]
+fn legacy_transactions_are_built_only_when_an_endpoint_wants_them() {
+    let v0_only = RpcSubmitter::new(
+        vec![sending_client("http://a.example.com", TxVersion::V0)],
+        Duration::ZERO,
+    );
+    assert!(!v0_only.wants_legacy());
+    let submitters = Submitters::new(vec![
+        Arc::new(v0_only),
+        Arc::new(RpcSubmitter::new(
+            vec![sending_client("http://b.example.com", TxVersion::Auto)],
+            Duration::ZERO,
+        )),
+    ]);
+    assert!(submitters.wants_legacy());
+}
+
+fn parse_config(bot: &str) -> Config {
+    TestConfig {
+        bot,
+        ..Default::default()
+    }
+    .parse()
+    .unwrap()
+}
+
+#[test]
+fn submitters_follow_the_config() {
+    let rpc_client = Arc::new(RpcClient::new("http://127.0.0.1:8899".to_string()));
+    let clients = || {
+        vec![
+            sending_client("http://a.example.com", TxVersion::V0),
+            sending_client("http://b.example.com", TxVersion::V0),
+        ]
+    };
+
+    let submitters = Submitters::from_config(&parse_config(""), &rpc_client, clients(), None, None);
+    assert_eq!(
+        submitters.endpoints(),
+        vec!["http://a.example.com", "http://b.example.com"]
+    );
+
+    // dry-run 模式下只模拟，不使用任何发送端点
+    let submitters = Submitters::from_config(
+        &parse_config("dry_run = true"),
+        &rpc_client,
+        clients(),
+        None,
+        None,
+    );
+    assert_eq!(submitters.endpoints(), vec!["paper"]);
+
+    assert!(
+        Submitters::from_config(&parse_config(""), &rpc_client, Vec::new(), None, None).is_empty()
+    );
+}
+
+#[test]
+fn paper_trades_accumulate_totals_and_keep_recent_history() {
+    let paper = PaperSubmitter::new(Arc::new(RpcClient::new(
+        "http://127.0.0.1:8899".to_string(),
+    )));
+    let first = Signature::new_unique();
+    let totals = paper.record(PaperTrade {
+        signature: first,
+        simulated_profit: Some(5_000),
+        tip: Some(1_000),
+    });
+    assert_eq!(
+        totals,
+        PaperTotals {
+            trades: 1,
+            profitable: 1,
+            simulated_profit: 4_000,
+            tips: 1_000,
+        }
+    );
+    // 模拟失败的交易只计数
+    paper.record(PaperTrade {
+        signature: Signature::new_unique(),
+        simulated_profit: None,
+        tip: None,
+    });
+    paper.record(PaperTrade {
+        signature: Signature::new_unique(),
+        simulated_profit: Some(-200),
+        tip: None,
+    });
+    assert_eq!(
+        paper.totals(),
+        PaperTotals {
+            trades: 3,
+            profitable: 1,
+            simulated_profit: 3_800,
+            tips: 1_000,
+        }
+    );
+
+    for _ in 0..PAPER_TRADE_HISTORY {
+        paper.record(PaperTrade {
+            signature: Signature::new_unique(),
+            simulated_profit: Some(1),
+            tip: None,
+        });
+    }
+    let trades = paper.trades();
+    assert_eq!(trades.len(), PAPER_TRADE_HISTORY);
+    assert!(trades.iter().all(|trade| trade.signature != first));
+    assert_eq!(paper.totals().trades, 3 + PAPER_TRADE_HISTORY as u64);
+}
@@ -0,0 +1,270 @@
+//! 交易大小预算测试：池子太多时按报价偏离保留池子、共用账户去重，以及查找表覆盖检查和选择
+
+mod common;
+
+use common::{programs, raydium_pool_data, TestConfig};
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::transaction::{
+    build_legacy_transaction, build_transaction, select_lookup_tables, uncovered_accounts,
+    MAX_TX_ACCOUNTS,
+};
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use std::collections::HashSet;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn test_config(wallet: &Keypair) -> Config {
+    TestConfig {
+        wallet: Some(wallet),
+        mint: Some(USDC_MINT),
+        ..Default::default()
+    }
+    .parse()
+    .expect("test config should parse")
+}
+
+#[test]
+fn small_transaction_keeps_all_pools() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let (pool_data, pools) = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3);
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let keys = tx.message.static_account_keys();
+    assert!(pools.iter().all(|pool| keys.contains(pool)));
+}
+
+#[test]
+fn oversized_transaction_keeps_highest_edge_pools() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let (pool_data, pools) = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 20);
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let keys = tx.message.static_account_keys();
+    assert!(bincode::serialized_size(&tx).unwrap() as usize <= PACKET_DATA_SIZE);
+    assert!(keys.len() <= MAX_TX_ACCOUNTS);
+
+    let kept: Vec<bool> = pools.iter().map(|pool| keys.contains(pool)).collect();
+    assert!(kept.iter().any(|k| !k), "some pools should be dropped");
+    // 保留的池子是偏离最大的那一段
+    let first_kept = kept.iter().position(|k| *k).unwrap();
+    assert!(kept[first_kept..].iter().all(|k| *k));
+}
+
+#[test]
+fn lookup_table_coverage_reports_missing_accounts() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let (pool_data, pools) = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 20);
+
+    let uncovered =
+        uncovered_accounts(&wallet, &config, &programs(&config), &pool_data, &[]).unwrap();
+    // 即使交易装不下全部池子，检查也覆盖所有池子
+    assert!(pools.iter().all(|pool| uncovered.contains(pool)));
+    // 签名者不能放进查找表
+    assert!(!uncovered.contains(&wallet.pubkey()));
+
+    let table = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: pools.clone(),
+    };
+    let remaining =
+        uncovered_accounts(&wallet, &config, &programs(&config), &pool_data, &[table]).unwrap();
+    assert_eq!(remaining.len(), uncovered.len() - pools.len());
+    assert!(pools.iter().all(|pool| !remaining.contains(pool)));
+}
+
+#[test]
+fn legacy_transaction_has_no_lookup_tables() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let (pool_data, pools) = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3);
+
+    let tx = build_legacy_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+    )
+    .unwrap();
+    assert!(matches!(tx.message, VersionedMessage::Legacy(_)));
+    assert!(tx.verify_with_results().iter().all(|ok| *ok));
+    let keys = tx.message.static_account_keys();
+    assert!(pools.iter().all(|pool| keys.contains(pool)));
+    assert!(bincode::serialized_size(&tx).unwrap() as usize <= PACKET_DATA_SIZE);
+}
+
+/// 交易的静态账户和通过查找表加载的账户
+fn message_accounts(message: &VersionedMessage) -> (Vec<Pubkey>, Vec<(Pubkey, Vec<u8>)>) {
+    let VersionedMessage::V0(message) = message else {
+        panic!("expected a v0 message");
+    };
+    let lookups = message
+        .address_table_lookups
+        .iter()
+        .map(|lookup| {
+            let mut indexes = lookup.writable_indexes.clone();
+            indexes.extend(&lookup.readonly_indexes);
+            (lookup.account_key, indexes)
+        })
+        .collect();
+    (message.account_keys.clone(), lookups)
+}
+
+#[test]
+fn shared_accounts_are_compiled_once() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let mut pool_data = MintPoolData::new(*usdc_mint(), wallet.pubkey(), spl_token::ID);
+    let amm_config = Pubkey::new_unique();
+    for _ in 0..3 {
+        pool_data.add_raydium_cp_pool(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            amm_config,
+            Pubkey::new_unique(),
+        );
+    }
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    let (keys, _) = message_accounts(&tx.message);
+    let unique: HashSet<&Pubkey> = keys.iter().collect();
+    assert_eq!(unique.len(), keys.len(), "duplicate static accounts");
+    // 执行程序按位置解析账户，共用的 amm_config 在指令中出现三次，但只占一个账户
+    let swap = tx.message.instructions().last().unwrap();
+    let amm_config_index = keys.iter().position(|key| *key == amm_config).unwrap();
+    assert_eq!(
+        swap.accounts
+            .iter()
+            .filter(|index| **index as usize == amm_config_index)
+            .count(),
+        3
+    );
+}
+
+#[test]
+fn lookup_tables_covering_more_accounts_are_used_first() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    // 14 个池子的账户数在上限以内，但没有查找表时交易超过大小上限
+    let (pool_data, pools) = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 14);
+
+    let partial = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: pools[..5].to_vec(),
+    };
+    let unused = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: vec![Pubkey::new_unique()],
+    };
+    let mut addresses =
+        uncovered_accounts(&wallet, &config, &programs(&config), &pool_data, &[]).unwrap();
+    addresses.reverse();
+    let full = AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses,
+    };
+    let tables = [partial, unused, full.clone()];
+
+    let tx = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        Hash::default(),
+        &tables,
+    )
+    .unwrap();
+    let (keys, lookups) = message_accounts(&tx.message);
+    // 完整的表覆盖了部分表的所有账户，部分表和没有用到的表都不进入交易
+    assert_eq!(lookups.len(), 1);
+    assert_eq!(lookups[0].0, full.key);
+    let loaded: Vec<Pubkey> = lookups[0]
+        .1
+        .iter()
+        .map(|index| full.addresses[*index as usize])
+        .collect();
+    let mut all: Vec<&Pubkey> = keys.iter().chain(&loaded).collect();
+    let total = all.len();
+    all.sort();
+    all.dedup();
+    assert_eq!(all.len(), total, "an account is referenced twice");
+    assert!(total <= MAX_TX_ACCOUNTS);
+    // 查找表让全部池子都放得下
+    assert!(pools.iter().all(|pool| loaded.contains(pool)));
+    assert!(bincode::serialized_size(&tx).unwrap() as usize <= PACKET_DATA_SIZE);
+}
+
+#[test]
+fn lookup_table_selection_skips_signers_and_programs() {
+    let signer = Pubkey::new_unique();
+    let program = Pubkey::new_unique();
+    let (a, b, c) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    let instruction = Instruction::new_with_bytes(
+        program,
+        &[],
+        vec![
+            AccountMeta::new(signer, true),
+            AccountMeta::new(a, false),
+            AccountMeta::new_readonly(b, false),
+            AccountMeta::new_readonly(c, false),
+            AccountMeta::new(a, false),
+        ],
+    );
+    let table = |addresses: Vec<Pubkey>| AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses,
+    };
+    // 签名者和程序不能从查找表加载，只含它们的表不选
+    let programs_only = table(vec![signer, program]);
+    let ab = table(vec![a, b]);
+    let bc = table(vec![b, c]);
+    let c_only = table(vec![c]);
+    let selected = select_lookup_tables(
+        &[instruction],
+        &[programs_only, c_only.clone(), ab.clone(), bc],
+    );
+    let keys: Vec<Pubkey> = selected.iter().map(|table| table.key).collect();
+    // ab 和 bc 各覆盖两个账户，取靠前的 ab；剩下的 c 由 c_only 和 bc 各覆盖一个，取靠前的 c_only
+    assert_eq!(keys, vec![ab.key, c_only.key]);
+}
@@ -0,0 +1,94 @@
+//! 交易订阅推送结果的测试
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::constants::{sol_mint, usdc_mint};
+use solana_onchain_arbitrage_bot::transaction_watch::{TransactionUpdate, TransactionWatch};
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn update(signature: Signature, failed: bool) -> TransactionUpdate {
+    TransactionUpdate {
+        signature,
+        slot: 42,
+        failed,
+        fee: 5_000,
+        wallet_deltas: HashMap::from([(*sol_mint(), 20_000), (*usdc_mint(), -3)]),
+    }
+}
+
+#[tokio::test]
+async fn updates_reach_the_waiting_task() {
+    let watch = Arc::new(TransactionWatch::new());
+    let signature = Signature::new_unique();
+
+    let waiting = {
+        let watch = watch.clone();
+        tokio::spawn(async move { watch.wait(&signature, Duration::from_secs(5)).await })
+    };
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    watch.publish(update(Signature::new_unique(), false));
+    watch.publish(update(signature, false));
+    assert_eq!(waiting.await.unwrap(), Some(update(signature, false)));
+}
+
+#[tokio::test]
+async fn updates_published_before_waiting_are_kept() {
+    let watch = TransactionWatch::new();
+    let signature = Signature::new_unique();
+    watch.publish(update(signature, true));
+    assert_eq!(
+        watch.wait(&signature, Duration::ZERO).await,
+        Some(update(signature, true))
+    );
+    // 只交付一次
+    assert_eq!(
+        watch.wait(&signature, Duration::from_millis(10)).await,
+        None
+    );
+
+    // 超时后到达的推送留给下一次等待
+    let late = Signature::new_unique();
+    assert_eq!(watch.wait(&late, Duration::from_millis(10)).await, None);
+    watch.publish(update(late, false));
+    assert!(watch.wait(&late, Duration::ZERO).await.is_some());
+}
+
+#[test]
+fn landed_profit_matches_the_polling_path() {
+    let signature = Signature::new_unique();
+    let landed = update(signature, false).landed(sol_mint()).unwrap();
+    assert_eq!(landed.slot, 42);
+    assert_eq!(landed.profit_lamports, 15_000);
+    assert_eq!(landed.fee_lamports, 5_000);
+    assert_eq!(landed.quote_profit, None);
+
+    let landed = update(signature, false).landed(usdc_mint()).unwrap();
+    assert_eq!(landed.quote_profit, Some(-3));
+    assert!(update(signature, true).landed(sol_mint()).is_none());
+
+    let watch = TransactionWatch::new();
+    assert!(!watch.is_connected());
+    watch.set_connected(true);
+    assert!(watch.is_connected());
+}
+
+#[test]
+fn geyser_config_validation() {
+    let error = parse_config("[geyser]\nenabled = true\nendpoint = \" \"")
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("geyser.endpoint"), "{}", error);
+    assert!(parse_config("[geyser]\nenabled = false\nendpoint = \"\"").is_ok());
+
+    let result = parse_config("[geyser]\nenabled = true\nendpoint = \"http://127.0.0.1:10000\"");
+    if cfg!(feature = "yellowstone") {
+        assert!(result.is_ok());
+    } else {
+        let error = result.unwrap_err().to_string();
+        assert!(error.contains("`yellowstone` feature"), "{}", error);
+    }
+}
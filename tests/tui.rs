@@ -0,0 +1,114 @@
+//! 终端监控界面和每个代币交易统计的测试
+
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::status::{BotStatus, TransactionOutcome};
+use solana_onchain_arbitrage_bot::tui;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+
+/// 一个 Raydium 池和一个 Pump 池
+fn pool_data() -> (MintPoolData, Pubkey, Pubkey) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let (raydium, pump) = (Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_raydium_pool(raydium, Pubkey::new_unique(), Pubkey::new_unique());
+    pool_data.add_pump_pool(
+        pump,
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    (pool_data, raydium, pump)
+}
+
+#[test]
+fn mint_status_tracks_sends_landings_and_prices() {
+    let (pool_data, raydium, pump) = pool_data();
+    let mint = pool_data.mint.to_string();
+    let status = BotStatus::new("http://localhost:8899".to_string(), false, 1_000);
+    status.update_pools(&pool_data);
+
+    let (landed, dropped) = (Signature::new_unique(), Signature::new_unique());
+    status.record_transaction(&mint, Some(landed), TransactionOutcome::Sent);
+    status.record_transaction(&mint, Some(dropped), TransactionOutcome::Sent);
+    status.record_transaction(
+        &mint,
+        None,
+        TransactionOutcome::SendFailed {
+            error: "timeout".to_string(),
+        },
+    );
+    status.update_transaction_outcome(
+        &mint,
+        &landed,
+        TransactionOutcome::Landed {
+            profit_lamports: 5_000,
+        },
+    );
+    status.update_transaction_outcome(&mint, &dropped, TransactionOutcome::NotLanded);
+    status.update_prices(&pool_data, &HashMap::from([(raydium, 1.0), (pump, 1.01)]));
+
+    // 刷新池子列表不会清空统计
+    status.update_pools(&pool_data);
+    status.update_prices(&pool_data, &HashMap::from([(raydium, 1.0), (pump, 1.01)]));
+
+    let snapshot = status.snapshot();
+    let mint_status = &snapshot.mints[&mint];
+    assert_eq!((mint_status.sent_count, mint_status.landed_count), (2, 1));
+    assert_eq!(mint_status.landed_ratio(), Some(0.5));
+    assert_eq!(mint_status.realized_pnl_lamports, 5_000);
+    assert_eq!(snapshot.realized_pnl_lamports, 5_000);
+    assert_eq!(
+        mint_status.last_send.as_ref().unwrap().outcome.as_str(),
+        "send_failed"
+    );
+    assert!((mint_status.best_spread_bps.unwrap() - 100.0).abs() < 1e-6);
+    let prices: HashMap<&str, Option<f64>> = mint_status
+        .pools
+        .iter()
+        .map(|pool| (pool.dex.as_str(), pool.price))
+        .collect();
+    assert_eq!(prices["raydium"], Some(1.0));
+    assert_eq!(prices["pump"], Some(1.01));
+}
+
+#[test]
+fn monitor_renders_mints_and_pools_of_the_selected_mint() {
+    let (pool_data, raydium, pump) = pool_data();
+    let status = BotStatus::new("http://localhost:8899".to_string(), true, 1_000);
+    status.update_pools(&pool_data);
+    status.update_prices(&pool_data, &HashMap::from([(raydium, 1.0), (pump, 1.01)]));
+    status.record_transaction(
+        &pool_data.mint.to_string(),
+        None,
+        TransactionOutcome::Simulated,
+    );
+    status.record_rpc_failure("connection refused".to_string());
+
+    let mut terminal = Terminal::new(TestBackend::new(160, 24)).unwrap();
+    let snapshot = status.snapshot();
+    terminal
+        .draw(|frame| tui::draw(frame, &snapshot, 0))
+        .unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+
+    assert!(screen.contains("(dry-run)"));
+    assert!(screen.contains("1 failures: connection refused"));
+    assert!(screen.contains(&pool_data.mint.to_string()));
+    assert!(screen.contains("100.0"));
+    assert!(screen.contains("simulated"));
+    assert!(screen.contains(&raydium.to_string()));
+    assert!(screen.contains(&pump.to_string()));
+}
@@ -0,0 +1,198 @@
+//! 交易模板测试：模板交易和完整构建的交易一致、每轮替换 blockhash 和计算单元价格、
+//! 按路线缓存，去掉池子的交易不缓存，以及模板消息交给广播记录签名
+
+mod common;
+
+use common::{programs, raydium_pool_data, TestConfig};
+use solana_onchain_arbitrage_bot::broadcast::BroadcastLog;
+use solana_onchain_arbitrage_bot::config::Config;
+use solana_onchain_arbitrage_bot::constants::usdc_mint;
+use solana_onchain_arbitrage_bot::transaction::{build_transaction, compile_transaction_message};
+use solana_onchain_arbitrage_bot::tx_template::{TransactionTemplate, TransactionTemplates};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn test_config(wallet: &Keypair) -> Config {
+    TestConfig {
+        wallet: Some(wallet),
+        mint: Some(USDC_MINT),
+        ..Default::default()
+    }
+    .parse()
+    .expect("test config should parse")
+}
+
+fn v0(tx: &VersionedTransaction) -> &solana_sdk::message::v0::Message {
+    match &tx.message {
+        VersionedMessage::V0(message) => message,
+        VersionedMessage::Legacy(_) => panic!("expected a v0 message"),
+    }
+}
+
+#[test]
+fn template_transaction_matches_a_full_build() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let pool_data = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3).0;
+    let blockhash = Hash::new_unique();
+
+    let full = build_transaction(
+        &wallet,
+        &config,
+        &programs(&config),
+        &pool_data,
+        blockhash,
+        &[],
+    )
+    .unwrap();
+    let templated = TransactionTemplates::new()
+        .build(
+            &wallet,
+            &config,
+            &programs(&config),
+            &pool_data,
+            blockhash,
+            &[],
+        )
+        .unwrap();
+    assert!(templated.verify_with_results().iter().all(|ok| *ok));
+
+    let (full, templated) = (v0(&full), v0(&templated));
+    assert_eq!(full.account_keys, templated.account_keys);
+    assert_eq!(full.recent_blockhash, templated.recent_blockhash);
+    // 计算单元限制有随机扰动，其他指令完全相同
+    assert_eq!(full.instructions[1..], templated.instructions[1..]);
+}
+
+#[test]
+fn cached_template_patches_blockhash_and_compute_unit_price() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let route = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3).0;
+    let mut templates = TransactionTemplates::new();
+
+    let first = templates
+        .build(
+            &wallet,
+            &config,
+            &programs(&config),
+            &route,
+            Hash::new_unique(),
+            &[],
+        )
+        .unwrap();
+    let blockhash = Hash::new_unique();
+    let repriced = config.with_compute_unit_price(12_345);
+    let second = templates
+        .build(
+            &wallet,
+            &repriced,
+            &programs(&repriced),
+            &route,
+            blockhash,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(templates.len(), 1);
+
+    let (first, second) = (v0(&first), v0(&second));
+    assert_eq!(second.recent_blockhash, blockhash);
+    assert_eq!(
+        second.instructions[1].data,
+        ComputeBudgetInstruction::set_compute_unit_price(12_345).data
+    );
+    assert_eq!(first.account_keys, second.account_keys);
+    assert_eq!(first.instructions[2..], second.instructions[2..]);
+
+    // 另一条路线编译新的模板
+    templates
+        .build(
+            &wallet,
+            &config,
+            &programs(&config),
+            &raydium_pool_data(*usdc_mint(), wallet.pubkey(), 2).0,
+            blockhash,
+            &[],
+        )
+        .unwrap();
+    assert_eq!(templates.len(), 2);
+}
+
+#[test]
+fn trimmed_transactions_are_not_cached() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let mut templates = TransactionTemplates::new();
+
+    let (_, trimmed) = compile_transaction_message(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(*usdc_mint(), wallet.pubkey(), 20).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    assert!(trimmed);
+    templates
+        .build(
+            &wallet,
+            &config,
+            &programs(&config),
+            &raydium_pool_data(*usdc_mint(), wallet.pubkey(), 20).0,
+            Hash::default(),
+            &[],
+        )
+        .unwrap();
+    assert!(templates.is_empty());
+}
+
+#[test]
+fn templates_need_compute_budget_instructions_first() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let (mut message, _) = compile_transaction_message(
+        &wallet,
+        &config,
+        &programs(&config),
+        &raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3).0,
+        Hash::default(),
+        &[],
+    )
+    .unwrap();
+    assert!(TransactionTemplate::new(message.clone(), 600_000).is_ok());
+
+    message.instructions.swap(0, 1);
+    let error = TransactionTemplate::new(message, 600_000)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("compute unit limit"), "{}", error);
+}
+
+#[test]
+fn template_messages_can_be_signed_by_the_broadcast_log() {
+    let wallet = Keypair::new();
+    let config = test_config(&wallet);
+    let route = raydium_pool_data(*usdc_mint(), wallet.pubkey(), 3).0;
+    let blockhash = Hash::new_unique();
+    let mut templates = TransactionTemplates::new();
+    let mut broadcasts = BroadcastLog::new();
+
+    let message = templates
+        .build_message(&wallet, &config, &programs(&config), &route, blockhash, &[])
+        .unwrap();
+    assert_eq!(templates.len(), 1);
+    let tx = broadcasts.sign(&wallet, message.clone()).unwrap();
+    assert!(tx.verify_with_results().iter().all(|ok| *ok));
+    // 同一个 blockhash 下相同的消息复用签名
+    assert_eq!(
+        broadcasts.sign(&wallet, message).unwrap().signatures,
+        tx.signatures
+    );
+}
@@ -0,0 +1,154 @@
+//! vault 余额跟踪测试
+
+use solana_onchain_arbitrage_bot::dex::discriminator::anchor_discriminator;
+use solana_onchain_arbitrage_bot::dex::meteora::vault_info::MeteoraVaultInfo;
+use solana_onchain_arbitrage_bot::pools::MintPoolData;
+use solana_onchain_arbitrage_bot::quote::{reserves_from_balances, QuoteAccount};
+use solana_onchain_arbitrage_bot::status::BotStatus;
+use solana_onchain_arbitrage_bot::vaults::VaultTracker;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+/// 一个 Raydium 池和一个 Solfi 池，返回 (池子数据, 池子, 代币 vault, SOL vault)
+fn pool_data() -> (MintPoolData, Vec<(Pubkey, Pubkey, Pubkey)>) {
+    let mut pool_data =
+        MintPoolData::new(Pubkey::new_unique(), Pubkey::new_unique(), spl_token::ID);
+    let pools: Vec<_> = (0..2)
+        .map(|_| {
+            (
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+                Pubkey::new_unique(),
+            )
+        })
+        .collect();
+    let (pool, token_vault, sol_vault) = pools[0];
+    pool_data.add_raydium_pool(pool, token_vault, sol_vault);
+    let (pool, token_vault, sol_vault) = pools[1];
+    pool_data.add_solfi_pool(pool, token_vault, sol_vault);
+    (pool_data, pools)
+}
+
+#[test]
+fn every_pool_vault_is_tracked_once() {
+    let (pool_data, pools) = pool_data();
+    assert_eq!(pool_data.pool_vaults(), pools);
+
+    let tracker = VaultTracker::new();
+    tracker.register(&pool_data);
+    tracker.register(&pool_data);
+    assert_eq!(tracker.len(), 4);
+    assert!(tracker.reserves(&pool_data).is_empty());
+}
+
+#[test]
+fn reserves_need_both_vault_balances() {
+    let (pool_data, pools) = pool_data();
+    let (raydium, token_vault, sol_vault) = pools[0];
+
+    let balances = HashMap::from([(token_vault, 1_000), (sol_vault, 2_000)]);
+    let reserves = reserves_from_balances(&pool_data, |vault| balances.get(vault).copied());
+    // Solfi 不是恒定乘积池子，不参与报价
+    assert_eq!(reserves.len(), 1);
+    assert_eq!(
+        (reserves[&raydium].token, reserves[&raydium].sol),
+        (1_000, 2_000)
+    );
+
+    let balances = HashMap::from([(token_vault, 1_000)]);
+    assert!(reserves_from_balances(&pool_data, |vault| balances.get(vault).copied()).is_empty());
+}
+
+#[test]
+fn dashboard_shows_vault_balances() {
+    let (pool_data, pools) = pool_data();
+    let (_, token_vault, sol_vault) = pools[1];
+    let status = BotStatus::new("http://localhost:8899".to_string(), true, 0);
+    status.update_pools(&pool_data);
+
+    status.update_vault_balances(&HashMap::from([(token_vault, 5), (sol_vault, 7)]));
+
+    let snapshot = status.snapshot();
+    let mint = &snapshot.mints[&pool_data.mint.to_string()];
+    let solfi = mint.pools.iter().find(|p| p.dex == "solfi").unwrap();
+    assert_eq!((solfi.token_balance, solfi.sol_balance), (Some(5), Some(7)));
+    let raydium = mint.pools.iter().find(|p| p.dex == "raydium").unwrap();
+    assert_eq!((raydium.token_balance, raydium.sol_balance), (None, None));
+}
+
+/// Meteora vault 账户数据，只填写报价用到的字段
+fn meteora_vault_data(total_amount: u64, locked_profit: u64, last_report: u64) -> Vec<u8> {
+    let mut data = vec![0u8; 1227];
+    data[..8].copy_from_slice(&anchor_discriminator("Vault"));
+    data[11..19].copy_from_slice(&total_amount.to_le_bytes());
+    data[1203..1211].copy_from_slice(&locked_profit.to_le_bytes());
+    data[1211..1219].copy_from_slice(&last_report.to_le_bytes());
+    // 每秒解锁 1%
+    data[1219..1227].copy_from_slice(&10_000_000_000u64.to_le_bytes());
+    data
+}
+
+#[test]
+fn meteora_vault_unlocks_profit_over_time() {
+    let data = meteora_vault_data(1_000_000, 100_000, 1_000);
+    let vault = MeteoraVaultInfo::load_checked(&data).unwrap();
+    assert_eq!(vault.total_amount, 1_000_000);
+    assert_eq!(vault.unlocked_amount(1_000), 900_000);
+    assert_eq!(vault.unlocked_amount(1_050), 950_000);
+    assert_eq!(vault.unlocked_amount(2_000), 1_000_000);
+    assert!(MeteoraVaultInfo::load_checked(&data[..1200]).is_err());
+
+    let amount = QuoteAccount::MeteoraVault.amount(&data, 1_000).unwrap();
+    assert_eq!(amount, 900_000);
+    let mut mint = vec![0u8; 82];
+    mint[36..44].copy_from_slice(&42u64.to_le_bytes());
+    assert_eq!(QuoteAccount::MintSupply.amount(&mint, 0), Some(42));
+}
+
+#[test]
+fn meteora_damm_reserves_follow_vault_state() {
+    let (mut pool_data, _) = pool_data();
+    let keys: Vec<Pubkey> = (0..11).map(|_| Pubkey::new_unique()).collect();
+    let [pool, x_vault, sol_vault, x_token_vault, sol_token_vault, x_lp_mint, sol_lp_mint, x_pool_lp, sol_pool_lp, x_fee, sol_fee] =
+        keys.as_slice().try_into().unwrap();
+    pool_data.add_meteora_damm_pool(
+        pool,
+        x_vault,
+        sol_vault,
+        x_token_vault,
+        sol_token_vault,
+        x_lp_mint,
+        sol_lp_mint,
+        x_pool_lp,
+        sol_pool_lp,
+        x_fee,
+        sol_fee,
+    );
+
+    // 池子持有代币 vault 一半的 LP、SOL vault 四分之一的 LP
+    let mut balances = HashMap::from([
+        (x_pool_lp, 500),
+        (x_lp_mint, 1_000),
+        (x_vault, 8_000_000),
+        (sol_pool_lp, 250),
+        (sol_lp_mint, 1_000),
+        (sol_vault, 4_000_000),
+    ]);
+    let reserves = reserves_from_balances(&pool_data, |key| balances.get(key).copied());
+    assert_eq!(
+        (reserves[&pool].token, reserves[&pool].sol),
+        (4_000_000, 1_000_000)
+    );
+
+    // vault 产生收益后兑换比例随之变化
+    balances.insert(sol_vault, 4_400_000);
+    let reserves = reserves_from_balances(&pool_data, |key| balances.get(key).copied());
+    assert_eq!(reserves[&pool].sol, 1_100_000);
+
+    let tracker = VaultTracker::new();
+    tracker.register(&pool_data);
+    assert_eq!(tracker.len(), 4 + 6);
+
+    balances.insert(sol_lp_mint, 0);
+    assert!(reserves_from_balances(&pool_data, |key| balances.get(key).copied()).is_empty());
+}
@@ -0,0 +1,123 @@
+//! 过期数据看门狗测试：池子数据更新时间、blockhash 连续失败、slot 停止前进和配置校验
+
+mod common;
+
+use common::parse_config;
+use solana_onchain_arbitrage_bot::config::WatchdogConfig;
+use solana_onchain_arbitrage_bot::vaults::VaultTracker;
+use solana_onchain_arbitrage_bot::watchdog::{StaleReason, Watchdog};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn watchdog_config() -> WatchdogConfig {
+    parse_config("[watchdog]\nenabled = true")
+        .unwrap()
+        .watchdog
+        .unwrap()
+}
+
+fn secs(secs: u64) -> Duration {
+    Duration::from_secs(secs)
+}
+
+#[test]
+fn only_watched_mints_go_stale() {
+    let watchdog = Watchdog::new(&watchdog_config(), None);
+    let start = Instant::now();
+    let mint = Pubkey::new_unique();
+    let unwatched = Pubkey::new_unique();
+    watchdog.watch_mint(mint, start);
+
+    assert_eq!(watchdog.pool_staleness(&mint, start + secs(60)), None);
+    assert_eq!(
+        watchdog.pool_staleness(&mint, start + secs(61)),
+        Some(StaleReason::PoolData { age: secs(61) })
+    );
+    assert_eq!(watchdog.pool_staleness(&unwatched, start + secs(600)), None);
+
+    // 更新后恢复，较早的更新时间不会覆盖较新的
+    watchdog.record_pool_update(&mint, start + secs(50));
+    watchdog.record_pool_update(&mint, start + secs(10));
+    assert_eq!(watchdog.pool_staleness(&mint, start + secs(100)), None);
+    watchdog.record_pool_update(&unwatched, start + secs(50));
+    assert_eq!(watchdog.pool_staleness(&unwatched, start + secs(600)), None);
+}
+
+#[test]
+fn vault_refreshes_count_as_pool_updates() {
+    let tracker = Arc::new(VaultTracker::new());
+    let watchdog = Watchdog::new(&watchdog_config(), Some(tracker.clone()));
+    let start = Instant::now() - secs(120);
+    let mint = Pubkey::new_unique();
+    watchdog.watch_mint(mint, start);
+    assert!(tracker.refreshed_at().is_none());
+    assert!(watchdog.pause_reason(&mint).is_some());
+
+    // 没有登记 vault 时刷新不需要请求 RPC
+    let rpc_client = solana_client::rpc_client::RpcClient::new("http://127.0.0.1:1".to_string());
+    assert_eq!(tracker.refresh(&rpc_client).unwrap(), 0);
+    assert!(tracker.refreshed_at().is_some());
+    assert_eq!(watchdog.pause_reason(&mint), None);
+}
+
+#[test]
+fn blockhash_failures_and_slot_stalls_pause_every_mint() {
+    let watchdog = Watchdog::new(&watchdog_config(), None);
+    let start = Instant::now();
+    let mint = Pubkey::new_unique();
+
+    assert_eq!(watchdog.observe(100, 2, start), None);
+    assert_eq!(
+        watchdog.observe(100, 3, start),
+        Some(StaleReason::BlockhashFailures(3))
+    );
+    assert_eq!(
+        watchdog.pause_reason(&mint),
+        Some(StaleReason::BlockhashFailures(3))
+    );
+
+    // 同一个 slot 持续超过 60 秒
+    assert_eq!(watchdog.observe(100, 0, start + secs(60)), None);
+    assert_eq!(
+        watchdog.observe(100, 0, start + secs(61)),
+        Some(StaleReason::SlotStalled {
+            slot: 100,
+            stalled: secs(61)
+        })
+    );
+    assert!(watchdog.pause_reason(&mint).is_some());
+
+    // slot 前进后恢复
+    assert_eq!(watchdog.observe(101, 0, start + secs(62)), None);
+    assert_eq!(watchdog.pause_reason(&mint), None);
+    assert_eq!(
+        StaleReason::SlotStalled {
+            slot: 100,
+            stalled: secs(61)
+        }
+        .to_string(),
+        "slot stuck at 100 for 61s"
+    );
+}
+
+#[test]
+fn watchdog_config_defaults_and_validation() {
+    let config = watchdog_config();
+    assert_eq!(config.max_pool_age(), secs(60));
+    assert_eq!(config.max_blockhash_failures(), 3);
+    assert_eq!(config.max_slot_stall(), secs(60));
+
+    let error = parse_config(
+        "[watchdog]\nenabled = true\nmax_pool_age_secs = 0\nmax_blockhash_failures = 0\nmax_slot_stall_secs = 10",
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(error.contains("watchdog.max_pool_age_secs"), "{}", error);
+    assert!(
+        error.contains("watchdog.max_blockhash_failures"),
+        "{}",
+        error
+    );
+    assert!(error.contains("watchdog.max_slot_stall_secs"), "{}", error);
+}
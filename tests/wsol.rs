@@ -0,0 +1,49 @@
+//! WSOL 自动补充测试
+
+use solana_onchain_arbitrage_bot::constants::sol_mint;
+use solana_onchain_arbitrage_bot::wsol::{top_up_amount, wrap_sol_instructions, WalletBalance};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use spl_associated_token_account::get_associated_token_address;
+
+#[test]
+fn tops_up_to_target_while_keeping_gas_reserve() {
+    let (min, target, reserve) = (1_000, 3_000, 500);
+    let balance = |native, wsol| WalletBalance { native, wsol };
+
+    // WSOL 充足
+    assert_eq!(
+        top_up_amount(balance(10_000, 1_000), min, target, reserve),
+        None
+    );
+    // 补充到目标值
+    assert_eq!(
+        top_up_amount(balance(10_000, 200), min, target, reserve),
+        Some(2_800)
+    );
+    // 原生 SOL 不够时只包装保留量以外的部分
+    assert_eq!(
+        top_up_amount(balance(1_500, 200), min, target, reserve),
+        Some(1_000)
+    );
+    assert_eq!(top_up_amount(balance(500, 200), min, target, reserve), None);
+    assert_eq!(balance(500, 200).total(), 700);
+}
+
+#[test]
+fn wrap_transfers_into_the_wsol_account_and_syncs() {
+    let wallet = Pubkey::new_unique();
+    let wsol_account = get_associated_token_address(&wallet, sol_mint());
+    let instructions = wrap_sol_instructions(&wallet, 42).unwrap();
+
+    assert_eq!(instructions.len(), 3);
+    assert_eq!(instructions[0].program_id, spl_associated_token_account::ID);
+    assert_eq!(instructions[1].program_id, system_program::ID);
+    assert_eq!(instructions[1].accounts[1].pubkey, wsol_account);
+    assert_eq!(
+        u64::from_le_bytes(instructions[1].data[4..12].try_into().unwrap()),
+        42
+    );
+    assert_eq!(instructions[2].program_id, spl_token::ID);
+    assert_eq!(instructions[2].accounts[0].pubkey, wsol_account);
+}